@@ -154,6 +154,7 @@ impl McpBackend for AuthTestBackend {
                     }],
                     is_error: Some(false),
                     structured_content: None,
+                    structured_content_blocks: None,
                     _meta: None,
                 })
             }
@@ -172,6 +173,7 @@ impl McpBackend for AuthTestBackend {
                     }],
                     is_error: Some(false),
                     structured_content: None,
+                    structured_content_blocks: None,
                     _meta: None,
                 })
             }
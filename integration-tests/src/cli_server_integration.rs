@@ -138,6 +138,7 @@ impl McpBackend for CliTestBackend {
                 }],
                 is_error: Some(false),
                 structured_content: None,
+                structured_content_blocks: None,
                 _meta: None,
             })
         } else {
@@ -188,6 +189,7 @@ impl McpBackend for CliTestBackend {
                         blob: None,
                         _meta: None,
                     }],
+                    _meta: None,
                 });
             }
         }
@@ -400,6 +402,7 @@ async fn test_cli_transport_integration() {
             TransportConfig::Http {
                 host: Some("127.0.0.1".to_string()),
                 port: 8080,
+                max_in_flight: None,
             },
         ),
         (
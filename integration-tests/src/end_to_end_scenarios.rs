@@ -420,6 +420,7 @@ impl McpBackend for E2ETestBackend {
             content,
             is_error: Some(false),
             structured_content: None,
+            structured_content_blocks: None,
             _meta: None,
         })
     }
@@ -485,6 +486,7 @@ impl McpBackend for E2ETestBackend {
                 blob: None,
                 _meta: None,
             }],
+            _meta: None,
         })
     }
 
@@ -189,6 +189,7 @@ impl McpBackend for MonitoringTestBackend {
                     }],
                     is_error: Some(false),
                     structured_content: None,
+                    structured_content_blocks: None,
                     _meta: None,
                 })
             }
@@ -203,6 +204,7 @@ impl McpBackend for MonitoringTestBackend {
                     }],
                     is_error: Some(false),
                     structured_content: None,
+                    structured_content_blocks: None,
                     _meta: None,
                 })
             }
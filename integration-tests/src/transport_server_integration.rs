@@ -143,6 +143,7 @@ impl McpBackend for TransportTestBackend {
                     }],
                     is_error: Some(false),
                     structured_content: None,
+                    structured_content_blocks: None,
                     _meta: None,
                 })
             }
@@ -153,6 +154,7 @@ impl McpBackend for TransportTestBackend {
                 }],
                 is_error: Some(false),
                 structured_content: None,
+                structured_content_blocks: None,
                 _meta: None,
             }),
             _ => {
@@ -197,6 +199,7 @@ impl McpBackend for TransportTestBackend {
                     blob: None,
                     _meta: None,
                 }],
+                _meta: None,
             })
         } else {
             Err(BackendError::not_supported(format!("Resource not found: {}", request.uri)).into())
@@ -271,6 +274,7 @@ async fn test_server_with_http_transport() {
         transport_config: TransportConfig::Http {
             host: Some("127.0.0.1".to_string()),
             port,
+            max_in_flight: None,
         },
         auth_config: {
             let mut auth_config = test_auth_config();
@@ -401,6 +405,7 @@ async fn test_multiple_transport_configs() {
             TransportConfig::Http {
                 host: Some("127.0.0.1".to_string()),
                 port: find_free_port().await,
+                max_in_flight: None,
             },
         ),
         (
@@ -452,6 +457,7 @@ async fn test_transport_error_handling() {
         transport_config: TransportConfig::Http {
             host: Some("127.0.0.1".to_string()),
             port: 65000, // High port number
+            max_in_flight: None,
         },
         auth_config: {
             let mut auth_config = test_auth_config();
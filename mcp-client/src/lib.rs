@@ -40,15 +40,19 @@
 
 mod client;
 mod error;
+mod reconnect;
 mod transport;
 
 #[cfg(test)]
 mod client_tests;
 #[cfg(test)]
+mod reconnect_tests;
+#[cfg(test)]
 mod transport_tests;
 
 pub use client::McpClient;
 pub use error::{ClientError, ClientResult};
+pub use reconnect::{Connector, ReconnectConfig, ReconnectEvent, ReconnectingTransport};
 pub use transport::{ClientTransport, StdioClientTransport};
 
 // Re-export protocol types for convenience
@@ -0,0 +1,389 @@
+//! Automatic reconnection for long-lived client sessions
+//!
+//! [`ReconnectingTransport`] wraps a connector that opens a fresh
+//! [`ClientTransport`]. When the connection fails with a transport error it
+//! reconnects with exponential backoff, replays the `initialize` handshake
+//! and re-subscribes to every resource the session was subscribed to.
+
+use crate::error::{ClientError, ClientResult};
+use crate::transport::{ClientTransport, JsonRpcMessage, next_request_id};
+use async_trait::async_trait;
+use pulseengine_mcp_protocol::{NumberOrString, Request, Response};
+use serde_json::json;
+use std::collections::{BTreeSet, HashMap, VecDeque};
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::{Mutex, RwLock};
+use tracing::{debug, info, warn};
+
+/// Backoff and retry settings for [`ReconnectingTransport`]
+#[derive(Debug, Clone)]
+pub struct ReconnectConfig {
+    /// Reconnect attempts before giving up on a failed connection
+    pub max_retries: u32,
+    /// Delay before the first attempt, doubled for each further attempt
+    pub base_backoff: Duration,
+    /// Upper bound on the delay between attempts
+    pub max_backoff: Duration,
+    /// Random extra delay of up to this fraction of each backoff (0.0-1.0),
+    /// so clients dropped together don't reconnect in lockstep
+    pub jitter: f64,
+    /// How long to wait for each replayed handshake or subscribe response
+    pub replay_timeout: Duration,
+}
+
+impl Default for ReconnectConfig {
+    fn default() -> Self {
+        Self {
+            max_retries: 10,
+            base_backoff: Duration::from_millis(500),
+            max_backoff: Duration::from_secs(30),
+            jitter: 0.2,
+            replay_timeout: Duration::from_secs(30),
+        }
+    }
+}
+
+impl ReconnectConfig {
+    /// Delay before the given (zero-based) attempt, without jitter
+    pub fn backoff(&self, attempt: u32) -> Duration {
+        let factor = 2u32.checked_pow(attempt).unwrap_or(u32::MAX);
+        self.base_backoff
+            .saturating_mul(factor)
+            .min(self.max_backoff)
+    }
+
+    /// Delay before the given attempt, with jitter applied
+    pub fn jittered_backoff(&self, attempt: u32) -> Duration {
+        let delay = self.backoff(attempt);
+        let jitter = self.jitter.clamp(0.0, 1.0);
+        if jitter == 0.0 {
+            return delay;
+        }
+        (delay + delay.mul_f64(jitter * rand::random::<f64>())).min(self.max_backoff)
+    }
+}
+
+/// Outcome of a successful reconnection, passed to the reconnect callback
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ReconnectEvent {
+    /// Connection attempts made, including the successful one
+    pub attempts: u32,
+    /// Whether the `initialize` handshake was replayed
+    pub reinitialized: bool,
+    /// Resource URIs re-subscribed on the new connection
+    pub resubscribed: Vec<String>,
+    /// Resource URIs the server refused to re-subscribe; these are dropped
+    pub failed_subscriptions: Vec<String>,
+}
+
+/// Opens a new connection to the server
+pub type Connector<T> =
+    Box<dyn Fn() -> Pin<Box<dyn Future<Output = ClientResult<T>> + Send>> + Send + Sync>;
+
+type ReconnectCallback = Box<dyn Fn(&ReconnectEvent) + Send + Sync>;
+
+/// Session state replayed onto a new connection
+#[derive(Default)]
+struct SessionState {
+    initialize: Option<Request>,
+    initialized_notification: Option<Request>,
+    subscriptions: BTreeSet<String>,
+    /// Subscribe requests awaiting a response, by request ID
+    pending_subscribes: HashMap<String, String>,
+}
+
+/// Client transport that transparently reconnects when the server bounces
+///
+/// Requests in flight when the connection drops are not retried, except
+/// for a `send` that itself hit the failure; callers waiting on lost
+/// responses see their usual timeout. Use
+/// [`with_reconnect_callback`](Self::with_reconnect_callback) to learn when
+/// the session has been re-established.
+pub struct ReconnectingTransport<T: ClientTransport> {
+    connector: Connector<T>,
+    config: ReconnectConfig,
+    /// Current connection and its generation, bumped on every reconnect
+    current: RwLock<(u64, Arc<T>)>,
+    reconnecting: Mutex<()>,
+    state: std::sync::Mutex<SessionState>,
+    /// Messages received while replaying the session, delivered first
+    buffered: Mutex<VecDeque<JsonRpcMessage>>,
+    on_reconnect: Option<ReconnectCallback>,
+}
+
+impl<T: ClientTransport + 'static> ReconnectingTransport<T> {
+    /// Open the first connection with `connector`
+    ///
+    /// The initial connection isn't retried; its error is returned as is.
+    pub async fn connect<F, Fut>(connector: F, config: ReconnectConfig) -> ClientResult<Self>
+    where
+        F: Fn() -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = ClientResult<T>> + Send + 'static,
+    {
+        let connector: Connector<T> = Box::new(move || Box::pin(connector()));
+        let transport = connector().await?;
+        Ok(Self {
+            connector,
+            config,
+            current: RwLock::new((0, Arc::new(transport))),
+            reconnecting: Mutex::new(()),
+            state: std::sync::Mutex::new(SessionState::default()),
+            buffered: Mutex::new(VecDeque::new()),
+            on_reconnect: None,
+        })
+    }
+
+    /// Call `callback` after each successful reconnection
+    pub fn with_reconnect_callback(
+        mut self,
+        callback: impl Fn(&ReconnectEvent) + Send + Sync + 'static,
+    ) -> Self {
+        self.on_reconnect = Some(Box::new(callback));
+        self
+    }
+
+    /// Backoff and retry settings in use
+    pub fn config(&self) -> &ReconnectConfig {
+        &self.config
+    }
+
+    /// Resource URIs that will be re-subscribed after a reconnect
+    pub fn subscriptions(&self) -> Vec<String> {
+        self.state().subscriptions.iter().cloned().collect()
+    }
+
+    fn state(&self) -> std::sync::MutexGuard<'_, SessionState> {
+        self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    async fn current(&self) -> (u64, Arc<T>) {
+        let current = self.current.read().await;
+        (current.0, current.1.clone())
+    }
+
+    /// Remember what a successfully sent message means for the session
+    fn record_sent(&self, request: &Request) {
+        let mut state = self.state();
+        match request.method.as_str() {
+            "initialize" => state.initialize = Some(request.clone()),
+            "notifications/initialized" => {
+                state.initialized_notification = Some(request.clone());
+            }
+            "resources/subscribe" => {
+                if let (Some(id), Some(uri)) = (&request.id, subscription_uri(request)) {
+                    state.pending_subscribes.insert(id_key(id), uri);
+                }
+            }
+            "resources/unsubscribe" => {
+                if let Some(uri) = subscription_uri(request) {
+                    state.subscriptions.remove(&uri);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Commit a subscription once the server has accepted it
+    fn record_received(&self, message: &JsonRpcMessage) {
+        let JsonRpcMessage::Response(response) = message else {
+            return;
+        };
+        let Some(id) = &response.id else {
+            return;
+        };
+        let mut state = self.state();
+        if let Some(uri) = state.pending_subscribes.remove(&id_key(id))
+            && response.error.is_none()
+        {
+            state.subscriptions.insert(uri);
+        }
+    }
+
+    /// Replace the connection of `failed_generation` with a new one
+    async fn reconnect(&self, failed_generation: u64) -> ClientResult<()> {
+        let _reconnecting = self.reconnecting.lock().await;
+        if self.current.read().await.0 != failed_generation {
+            // Another caller already reconnected while we waited
+            return Ok(());
+        }
+
+        let mut last_error = None;
+        for attempt in 0..self.config.max_retries {
+            tokio::time::sleep(self.config.jittered_backoff(attempt)).await;
+            let transport = match (self.connector)().await {
+                Ok(transport) => transport,
+                Err(e) => {
+                    warn!(attempt = attempt + 1, "Reconnect failed: {}", e);
+                    last_error = Some(e);
+                    continue;
+                }
+            };
+            match self.replay(&transport).await {
+                Ok(mut event) => {
+                    event.attempts = attempt + 1;
+                    *self.current.write().await = (failed_generation + 1, Arc::new(transport));
+                    info!(
+                        attempts = event.attempts,
+                        resubscribed = event.resubscribed.len(),
+                        "Reconnected to MCP server"
+                    );
+                    if let Some(callback) = &self.on_reconnect {
+                        callback(&event);
+                    }
+                    return Ok(());
+                }
+                Err(e) => {
+                    warn!(attempt = attempt + 1, "Replaying session failed: {}", e);
+                    let _ = transport.close().await;
+                    last_error = Some(e);
+                }
+            }
+        }
+
+        Err(ClientError::transport(format!(
+            "Reconnect failed after {} attempts: {}",
+            self.config.max_retries,
+            last_error.map_or_else(|| "no attempts allowed".to_string(), |e| e.to_string())
+        )))
+    }
+
+    /// Re-establish the session on a new connection
+    async fn replay(&self, transport: &T) -> ClientResult<ReconnectEvent> {
+        let (initialize, initialized, subscriptions) = {
+            let state = self.state();
+            (
+                state.initialize.clone(),
+                state.initialized_notification.clone(),
+                state.subscriptions.iter().cloned().collect::<Vec<_>>(),
+            )
+        };
+        let mut event = ReconnectEvent::default();
+        let mut stray = Vec::new();
+
+        if let Some(mut initialize) = initialize {
+            initialize.id = Some(next_request_id());
+            let response = self.exchange(transport, &initialize, &mut stray).await?;
+            if let Some(error) = response.error {
+                return Err(ClientError::from_protocol_error(error));
+            }
+            if let Some(initialized) = initialized {
+                transport.send(&initialized).await?;
+            }
+            event.reinitialized = true;
+        }
+
+        for uri in subscriptions {
+            let subscribe = Request {
+                jsonrpc: "2.0".to_string(),
+                method: "resources/subscribe".to_string(),
+                params: json!({ "uri": uri }),
+                id: Some(next_request_id()),
+            };
+            let response = self.exchange(transport, &subscribe, &mut stray).await?;
+            match response.error {
+                None => event.resubscribed.push(uri),
+                Some(error) => {
+                    warn!(uri = %uri, "Server refused re-subscription: {}", error.message);
+                    event.failed_subscriptions.push(uri);
+                }
+            }
+        }
+
+        {
+            let mut state = self.state();
+            for uri in &event.failed_subscriptions {
+                state.subscriptions.remove(uri);
+            }
+            // Subscribes in flight on the old connection never got an answer
+            state.pending_subscribes.clear();
+        }
+        self.buffered.lock().await.extend(stray);
+        Ok(event)
+    }
+
+    /// Send a replayed request and wait for its response, setting aside
+    /// anything else the server sends meanwhile
+    async fn exchange(
+        &self,
+        transport: &T,
+        request: &Request,
+        stray: &mut Vec<JsonRpcMessage>,
+    ) -> ClientResult<Response> {
+        transport.send(request).await?;
+        loop {
+            let message = tokio::time::timeout(self.config.replay_timeout, transport.recv())
+                .await
+                .map_err(|_| ClientError::Timeout(self.config.replay_timeout))??;
+            match message {
+                JsonRpcMessage::Response(response) if response.id == request.id => {
+                    return Ok(response);
+                }
+                other => stray.push(other),
+            }
+        }
+    }
+}
+
+#[async_trait]
+impl<T: ClientTransport + 'static> ClientTransport for ReconnectingTransport<T> {
+    async fn send(&self, request: &Request) -> ClientResult<()> {
+        let (generation, transport) = self.current().await;
+        match transport.send(request).await {
+            Ok(()) => {}
+            Err(ClientError::Transport(e)) => {
+                warn!("Send failed, reconnecting: {}", e);
+                self.reconnect(generation).await?;
+                let (_, transport) = self.current().await;
+                transport.send(request).await?;
+            }
+            Err(e) => return Err(e),
+        }
+        self.record_sent(request);
+        Ok(())
+    }
+
+    async fn recv(&self) -> ClientResult<JsonRpcMessage> {
+        loop {
+            if let Some(message) = self.buffered.lock().await.pop_front() {
+                self.record_received(&message);
+                return Ok(message);
+            }
+            let (generation, transport) = self.current().await;
+            match transport.recv().await {
+                Ok(message) => {
+                    self.record_received(&message);
+                    return Ok(message);
+                }
+                Err(ClientError::Transport(e)) => {
+                    warn!("Receive failed, reconnecting: {}", e);
+                    self.reconnect(generation).await?;
+                    debug!("Resuming receive on new connection");
+                }
+                Err(e) => return Err(e),
+            }
+        }
+    }
+
+    async fn close(&self) -> ClientResult<()> {
+        let (_, transport) = self.current().await;
+        transport.close().await
+    }
+}
+
+fn subscription_uri(request: &Request) -> Option<String> {
+    request
+        .params
+        .get("uri")
+        .and_then(|uri| uri.as_str())
+        .map(str::to_string)
+}
+
+fn id_key(id: &NumberOrString) -> String {
+    match id {
+        NumberOrString::Number(n) => n.to_string(),
+        NumberOrString::String(s) => s.to_string(),
+    }
+}
@@ -0,0 +1,285 @@
+//! Tests for the reconnecting client transport
+
+use super::error::{ClientError, ClientResult};
+use super::reconnect::*;
+use super::transport::{ClientTransport, JsonRpcMessage};
+use async_trait::async_trait;
+use pulseengine_mcp_protocol::{NumberOrString, Request, Response};
+use serde_json::json;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::sync::mpsc;
+
+/// Server side of the mock: every request is answered immediately, and any
+/// connection can be broken to simulate the server going away
+#[derive(Default)]
+struct MockServer {
+    /// Messages received, tagged with the connection they arrived on
+    received: Mutex<Vec<(usize, Request)>>,
+    connections: Mutex<Vec<Arc<AtomicBool>>>,
+    connects: AtomicUsize,
+    /// Connection attempts (after the first) that are refused
+    refuse_next: AtomicUsize,
+}
+
+impl MockServer {
+    fn break_connection(&self, index: usize) {
+        self.connections.lock().unwrap()[index].store(true, Ordering::SeqCst);
+    }
+
+    fn received_on(&self, index: usize) -> Vec<Request> {
+        self.received
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|(connection, _)| *connection == index)
+            .map(|(_, request)| request.clone())
+            .collect()
+    }
+}
+
+struct MockConnection {
+    index: usize,
+    server: Arc<MockServer>,
+    broken: Arc<AtomicBool>,
+    replies: mpsc::UnboundedSender<JsonRpcMessage>,
+    incoming: tokio::sync::Mutex<mpsc::UnboundedReceiver<JsonRpcMessage>>,
+}
+
+#[async_trait]
+impl ClientTransport for MockConnection {
+    async fn send(&self, request: &Request) -> ClientResult<()> {
+        if self.broken.load(Ordering::SeqCst) {
+            return Err(ClientError::transport("connection reset"));
+        }
+        self.server
+            .received
+            .lock()
+            .unwrap()
+            .push((self.index, request.clone()));
+        if request.id.is_some() {
+            let _ = self.replies.send(JsonRpcMessage::Response(Response {
+                jsonrpc: "2.0".to_string(),
+                id: request.id.clone(),
+                result: Some(json!({})),
+                error: None,
+            }));
+        }
+        Ok(())
+    }
+
+    async fn recv(&self) -> ClientResult<JsonRpcMessage> {
+        if self.broken.load(Ordering::SeqCst) {
+            return Err(ClientError::transport("connection reset"));
+        }
+        self.incoming
+            .lock()
+            .await
+            .recv()
+            .await
+            .ok_or_else(|| ClientError::transport("connection closed"))
+    }
+
+    async fn close(&self) -> ClientResult<()> {
+        Ok(())
+    }
+}
+
+fn connector(
+    server: &Arc<MockServer>,
+) -> impl Fn() -> std::future::Ready<ClientResult<MockConnection>> + Send + Sync + 'static {
+    let server = server.clone();
+    move || {
+        server.connects.fetch_add(1, Ordering::SeqCst);
+        if server
+            .refuse_next
+            .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |n| n.checked_sub(1))
+            .is_ok()
+        {
+            return std::future::ready(Err(ClientError::transport("connection refused")));
+        }
+        let broken = Arc::new(AtomicBool::new(false));
+        let mut connections = server.connections.lock().unwrap();
+        connections.push(broken.clone());
+        let (replies, incoming) = mpsc::unbounded_channel();
+        std::future::ready(Ok(MockConnection {
+            index: connections.len() - 1,
+            server: server.clone(),
+            broken,
+            replies,
+            incoming: tokio::sync::Mutex::new(incoming),
+        }))
+    }
+}
+
+fn fast_config(max_retries: u32) -> ReconnectConfig {
+    ReconnectConfig {
+        max_retries,
+        base_backoff: Duration::from_millis(1),
+        max_backoff: Duration::from_millis(5),
+        jitter: 0.0,
+        replay_timeout: Duration::from_secs(1),
+    }
+}
+
+fn request(id: i64, method: &str, params: serde_json::Value) -> Request {
+    Request {
+        jsonrpc: "2.0".to_string(),
+        method: method.to_string(),
+        params,
+        id: Some(NumberOrString::Number(id)),
+    }
+}
+
+#[test]
+fn test_backoff_doubles_up_to_max() {
+    let config = ReconnectConfig {
+        base_backoff: Duration::from_millis(100),
+        max_backoff: Duration::from_secs(1),
+        jitter: 0.0,
+        ..Default::default()
+    };
+    assert_eq!(config.backoff(0), Duration::from_millis(100));
+    assert_eq!(config.backoff(1), Duration::from_millis(200));
+    assert_eq!(config.backoff(3), Duration::from_millis(800));
+    assert_eq!(config.backoff(4), Duration::from_secs(1));
+    assert_eq!(config.backoff(64), Duration::from_secs(1));
+    assert_eq!(config.jittered_backoff(2), Duration::from_millis(400));
+
+    let config = ReconnectConfig {
+        jitter: 0.5,
+        ..config
+    };
+    for _ in 0..20 {
+        let delay = config.jittered_backoff(1);
+        assert!(delay >= Duration::from_millis(200) && delay <= Duration::from_millis(300));
+    }
+}
+
+#[tokio::test]
+async fn test_reconnect_replays_handshake_and_subscriptions() {
+    let server = Arc::new(MockServer::default());
+    let events = Arc::new(Mutex::new(Vec::new()));
+    let seen = events.clone();
+    let transport = ReconnectingTransport::connect(connector(&server), fast_config(3))
+        .await
+        .unwrap()
+        .with_reconnect_callback(move |event| seen.lock().unwrap().push(event.clone()));
+
+    transport
+        .send(&request(
+            1,
+            "initialize",
+            json!({"protocolVersion": "2025-06-18"}),
+        ))
+        .await
+        .unwrap();
+    transport.recv().await.unwrap();
+    transport
+        .send(&Request {
+            jsonrpc: "2.0".to_string(),
+            method: "notifications/initialized".to_string(),
+            params: json!({}),
+            id: None,
+        })
+        .await
+        .unwrap();
+    for (id, uri) in [(2, "file://a.txt"), (3, "file://b.txt")] {
+        transport
+            .send(&request(id, "resources/subscribe", json!({ "uri": uri })))
+            .await
+            .unwrap();
+        transport.recv().await.unwrap();
+    }
+    transport
+        .send(&request(
+            4,
+            "resources/unsubscribe",
+            json!({"uri": "file://b.txt"}),
+        ))
+        .await
+        .unwrap();
+    transport.recv().await.unwrap();
+    assert_eq!(transport.subscriptions(), vec!["file://a.txt"]);
+
+    server.break_connection(0);
+    transport
+        .send(&request(5, "tools/list", json!({})))
+        .await
+        .unwrap();
+
+    let replayed: Vec<_> = server
+        .received_on(1)
+        .into_iter()
+        .map(|request| (request.method, request.params))
+        .collect();
+    assert_eq!(
+        replayed,
+        vec![
+            (
+                "initialize".to_string(),
+                json!({"protocolVersion": "2025-06-18"})
+            ),
+            ("notifications/initialized".to_string(), json!({})),
+            (
+                "resources/subscribe".to_string(),
+                json!({"uri": "file://a.txt"})
+            ),
+            ("tools/list".to_string(), json!({})),
+        ]
+    );
+
+    // The resent request's response arrives on the new connection
+    match transport.recv().await.unwrap() {
+        JsonRpcMessage::Response(response) => {
+            assert_eq!(response.id, Some(NumberOrString::Number(5)));
+        }
+        other => panic!("expected a response, got {other:?}"),
+    }
+
+    let events = events.lock().unwrap();
+    assert_eq!(
+        *events,
+        vec![ReconnectEvent {
+            attempts: 1,
+            reinitialized: true,
+            resubscribed: vec!["file://a.txt".to_string()],
+            failed_subscriptions: vec![],
+        }]
+    );
+}
+
+#[tokio::test]
+async fn test_reconnect_backs_off_until_server_returns() {
+    let server = Arc::new(MockServer::default());
+    let transport = ReconnectingTransport::connect(connector(&server), fast_config(5))
+        .await
+        .unwrap();
+
+    server.refuse_next.store(2, Ordering::SeqCst);
+    server.break_connection(0);
+    transport
+        .send(&request(1, "ping", json!({})))
+        .await
+        .unwrap();
+
+    // First connection, two refused attempts, then success
+    assert_eq!(server.connects.load(Ordering::SeqCst), 4);
+    assert_eq!(server.received_on(1).len(), 1);
+}
+
+#[tokio::test]
+async fn test_reconnect_gives_up_after_max_retries() {
+    let server = Arc::new(MockServer::default());
+    let transport = ReconnectingTransport::connect(connector(&server), fast_config(3))
+        .await
+        .unwrap();
+
+    server.refuse_next.store(usize::MAX, Ordering::SeqCst);
+    server.break_connection(0);
+    let err = transport.recv().await.unwrap_err();
+    assert!(matches!(err, ClientError::Transport(_)));
+    assert!(err.to_string().contains("after 3 attempts"), "{err}");
+    assert_eq!(server.connects.load(Ordering::SeqCst), 4);
+}
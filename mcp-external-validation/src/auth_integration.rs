@@ -168,6 +168,7 @@ impl AuthIntegrationTester {
             strict_ip_validation: true,
             enable_role_based_rate_limiting: false,
             role_rate_limits: HashMap::new(),
+            tool_rate_limits: HashMap::new(),
         };
 
         match AuthenticationManager::new_with_validation(auth_config, auth_validation_config).await
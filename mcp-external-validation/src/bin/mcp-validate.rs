@@ -46,6 +46,10 @@ struct Cli {
     #[arg(long)]
     skip_inspector: bool,
 
+    /// Fail when an external validator is unavailable instead of skipping it
+    #[arg(long)]
+    require_external: bool,
+
     /// Run benchmark tests
     #[arg(long)]
     benchmark: bool,
@@ -125,6 +129,10 @@ async fn load_config(cli: &Cli) -> Result<ValidationConfig, Box<dyn std::error::
         config.inspector.auto_start = false;
     }
 
+    if cli.require_external {
+        config.testing.require_external_validators = true;
+    }
+
     // Filter protocol versions
     if config
         .protocols
@@ -117,6 +117,12 @@ pub struct TestingConfig {
     /// Enable Python SDK compatibility testing
     pub python_sdk_compatibility: bool,
 
+    /// Fail validation when an external validator's tools or services are
+    /// unavailable; by default such validators are warned about and reported
+    /// as skipped, so offline CI keeps running the local checks
+    #[serde(default)]
+    pub require_external_validators: bool,
+
     /// Fuzzing configuration
     pub fuzzing: FuzzingConfig,
 }
@@ -213,6 +219,7 @@ impl Default for TestingConfig {
             benchmark: false,
             test_timeout: DEFAULT_TIMEOUT_SECONDS,
             python_sdk_compatibility: true,
+            require_external_validators: false,
             fuzzing: FuzzingConfig::default(),
         }
     }
@@ -274,6 +281,11 @@ impl ValidationConfig {
                     })?;
         }
 
+        if let Ok(require) = std::env::var("MCP_VALIDATION_REQUIRE_EXTERNAL") {
+            config.testing.require_external_validators =
+                matches!(require.as_str(), "1" | "true" | "yes");
+        }
+
         if let Ok(port) = std::env::var("MCP_INSPECTOR_PORT") {
             config.inspector.port =
                 port.parse()
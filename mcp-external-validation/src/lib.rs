@@ -106,7 +106,7 @@ pub use cross_language::CrossLanguageTester;
 pub use ecosystem::EcosystemTester;
 pub use inspector::InspectorClient;
 pub use jsonrpc::JsonRpcValidator;
-pub use mcp_semantic::McpSemanticValidator;
+pub use mcp_semantic::{ErrorSemanticsResult, McpSemanticValidator};
 pub use mcp_validator::McpValidatorClient;
 pub use security::SecurityTester;
 
@@ -173,6 +173,7 @@ mod tasks_tests {
         ] {
             let execution = ToolExecution {
                 task_support: Some(support.clone()),
+                timeout_ms: None,
             };
 
             let json = serde_json::to_string(&execution).unwrap();
@@ -193,6 +194,7 @@ mod tasks_tests {
             icons: None,
             execution: Some(ToolExecution {
                 task_support: Some(TaskSupport::Required),
+                timeout_ms: None,
             }),
             _meta: None,
         };
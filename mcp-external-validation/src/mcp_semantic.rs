@@ -5,11 +5,11 @@
 //! transitions, and protocol compliance.
 
 use crate::{
-    ValidationConfig, ValidationResult,
+    ValidationConfig, ValidationError, ValidationResult,
     report::{IssueSeverity, TestScore, ValidationIssue},
 };
 use serde::{Deserialize, Serialize};
-use serde_json::Value;
+use serde_json::{Value, json};
 use std::collections::{HashMap, HashSet};
 use tracing::info;
 
@@ -75,6 +75,57 @@ pub struct McpSemanticResult {
     pub issues: Vec<ValidationIssue>,
 }
 
+/// Error code semantics validation result
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ErrorSemanticsResult {
+    /// Crafted bad requests answered with a spec-conformant error code
+    pub score: TestScore,
+    /// Issues found during validation
+    pub issues: Vec<ValidationIssue>,
+}
+
+/// A crafted bad request and the error codes the spec allows in reply
+struct ErrorSemanticsCase {
+    name: &'static str,
+    method: &'static str,
+    params: Value,
+    expected_codes: &'static [i64],
+}
+
+fn error_semantics_cases() -> Vec<ErrorSemanticsCase> {
+    vec![
+        ErrorSemanticsCase {
+            name: "unknown method",
+            method: "mcp-validator/unknown-method",
+            params: json!({}),
+            expected_codes: &[-32601],
+        },
+        ErrorSemanticsCase {
+            name: "invalid params",
+            method: "tools/call",
+            params: json!({"arguments": "not-an-object"}),
+            expected_codes: &[-32602],
+        },
+        ErrorSemanticsCase {
+            name: "unknown tool",
+            method: "tools/call",
+            params: json!({"name": "mcp-validator-unknown-tool", "arguments": {}}),
+            // Invalid params per the spec; tool-not-found is the MCP-specific code
+            expected_codes: &[-32602, -32003],
+        },
+    ]
+}
+
+/// Parse a JSON-RPC reply sent either as plain JSON or as an SSE stream
+fn parse_jsonrpc_reply(body: &str) -> Option<Value> {
+    serde_json::from_str(body).ok().or_else(|| {
+        body.lines()
+            .filter_map(|line| line.strip_prefix("data:"))
+            .filter_map(|data| serde_json::from_str::<Value>(data.trim()).ok())
+            .find(|message| message.get("id").is_some())
+    })
+}
+
 impl McpSemanticValidator {
     /// Create a new MCP semantic validator
     pub fn new(config: ValidationConfig) -> Self {
@@ -128,6 +179,131 @@ impl McpSemanticValidator {
         Ok(result)
     }
 
+    /// Verify that an HTTP server answers known bad requests with the error
+    /// codes required by JSON-RPC 2.0 and MCP
+    ///
+    /// Sends an unknown method (expects -32601), malformed `tools/call`
+    /// params (expects -32602) and a call to an unknown tool (expects -32602
+    /// or the MCP tool-not-found code) after the initialize handshake.
+    pub async fn validate_error_semantics(
+        &self,
+        server_url: &str,
+    ) -> ValidationResult<ErrorSemanticsResult> {
+        if !(server_url.starts_with("http://") || server_url.starts_with("https://")) {
+            return Err(ValidationError::InvalidServerUrl {
+                url: server_url.to_string(),
+                reason: "error semantics validation requires an HTTP(S) server".to_string(),
+            });
+        }
+        info!(
+            "Starting error code semantics validation for {}",
+            server_url
+        );
+
+        let client = reqwest::Client::builder()
+            .timeout(std::time::Duration::from_secs(
+                self.config.testing.test_timeout,
+            ))
+            .build()?;
+        let post = |message: &Value, session_id: Option<&str>| {
+            let mut request = client
+                .post(server_url)
+                .header("Accept", "application/json, text/event-stream")
+                .json(message);
+            if let Some(session_id) = session_id {
+                request = request.header("Mcp-Session-Id", session_id);
+            }
+            request.send()
+        };
+
+        // Complete the handshake so servers enforcing initialization still
+        // answer the crafted requests with their real error semantics
+        let initialize = json!({
+            "jsonrpc": "2.0",
+            "method": "initialize",
+            "params": {
+                "protocolVersion": crate::SUPPORTED_MCP_VERSIONS[0],
+                "capabilities": {},
+                "clientInfo": {"name": "mcp-external-validator", "version": env!("CARGO_PKG_VERSION")}
+            },
+            "id": 0
+        });
+        let response = post(&initialize, None).await?;
+        let session_id = response
+            .headers()
+            .get("mcp-session-id")
+            .and_then(|value| value.to_str().ok())
+            .map(str::to_string);
+        post(
+            &json!({"jsonrpc": "2.0", "method": "notifications/initialized"}),
+            session_id.as_deref(),
+        )
+        .await?;
+
+        let cases = error_semantics_cases();
+        let mut passed = 0;
+        let mut issues = Vec::new();
+
+        for (id, case) in (1..).zip(&cases) {
+            let request = json!({
+                "jsonrpc": "2.0",
+                "method": case.method,
+                "params": case.params,
+                "id": id
+            });
+            let body = post(&request, session_id.as_deref()).await?.text().await?;
+            let reply = parse_jsonrpc_reply(&body);
+            let code = reply
+                .as_ref()
+                .and_then(|reply| reply.get("error"))
+                .and_then(|error| error.get("code"))
+                .and_then(Value::as_i64);
+
+            let description = match (&reply, code) {
+                (_, Some(code)) if case.expected_codes.contains(&code) => {
+                    passed += 1;
+                    continue;
+                }
+                (_, Some(code)) => format!(
+                    "{} request returned error code {}, expected {:?}",
+                    case.name, code, case.expected_codes
+                ),
+                (Some(_), None) => format!(
+                    "{} request returned a result instead of an error",
+                    case.name
+                ),
+                (None, None) => {
+                    format!("{} request did not receive a JSON-RPC response", case.name)
+                }
+            };
+            issues.push(
+                ValidationIssue::new(
+                    IssueSeverity::Error,
+                    "error_semantics".to_string(),
+                    description,
+                    "mcp-semantic".to_string(),
+                )
+                .with_location(case.method.to_string())
+                .with_suggestion(format!(
+                    "Return a JSON-RPC error with code {:?} for {} requests",
+                    case.expected_codes, case.name
+                ))
+                .with_detail("expected_codes".to_string(), json!(case.expected_codes))
+                .with_detail("actual_code".to_string(), json!(code)),
+            );
+        }
+
+        info!(
+            "Error semantics validation completed: {}/{} cases conformant",
+            passed,
+            cases.len()
+        );
+        Ok(ErrorSemanticsResult {
+            score: TestScore::new(passed, cases.len() as u32),
+            issues,
+        })
+    }
+
     /// Reset validator state for a new validation session
     fn reset_state(&mut self) {
         self.initialized = false;
@@ -1119,4 +1295,150 @@ mod tests {
                 .any(|i| i.description.contains("called before initialization"))
         );
     }
+
+    /// Serve JSON-RPC over HTTP, answering each request with `reply(request)`
+    async fn spawn_mock_server(reply: fn(&Value) -> Value) -> String {
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        tokio::spawn(async move {
+            while let Ok((mut stream, _)) = listener.accept().await {
+                tokio::spawn(async move {
+                    let mut buffer = Vec::new();
+                    let mut chunk = [0u8; 4096];
+                    let (header_end, content_length) = loop {
+                        let read = stream.read(&mut chunk).await.unwrap();
+                        buffer.extend_from_slice(&chunk[..read]);
+                        let text = String::from_utf8_lossy(&buffer).to_string();
+                        if let Some(end) = text.find("\r\n\r\n") {
+                            let length = text[..end]
+                                .lines()
+                                .find_map(|line| {
+                                    let (name, value) = line.split_once(':')?;
+                                    name.eq_ignore_ascii_case("content-length")
+                                        .then(|| value.trim().parse::<usize>().ok())?
+                                })
+                                .unwrap_or(0);
+                            break (end + 4, length);
+                        }
+                    };
+                    while buffer.len() < header_end + content_length {
+                        let read = stream.read(&mut chunk).await.unwrap();
+                        buffer.extend_from_slice(&chunk[..read]);
+                    }
+
+                    let request: Value = serde_json::from_slice(&buffer[header_end..]).unwrap();
+                    let response = if request.get("id").is_some() {
+                        let body = reply(&request).to_string();
+                        format!(
+                            "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                            body.len(),
+                            body
+                        )
+                    } else {
+                        "HTTP/1.1 202 Accepted\r\nContent-Length: 0\r\nConnection: close\r\n\r\n"
+                            .to_string()
+                    };
+                    stream.write_all(response.as_bytes()).await.unwrap();
+                });
+            }
+        });
+        url
+    }
+
+    fn error_reply(request: &Value, code: i64) -> Value {
+        json!({
+            "jsonrpc": "2.0",
+            "error": {"code": code, "message": "error"},
+            "id": request["id"]
+        })
+    }
+
+    fn initialize_reply(request: &Value) -> Value {
+        json!({
+            "jsonrpc": "2.0",
+            "result": {
+                "protocolVersion": "2025-11-25",
+                "capabilities": {"tools": {}},
+                "serverInfo": {"name": "mock", "version": "1.0.0"}
+            },
+            "id": request["id"]
+        })
+    }
+
+    #[tokio::test]
+    async fn test_error_semantics_conformant_server() {
+        let url = spawn_mock_server(|request| match request["method"].as_str().unwrap() {
+            "initialize" => initialize_reply(request),
+            "tools/call" => error_reply(request, -32602),
+            _ => error_reply(request, -32601),
+        })
+        .await;
+
+        let validator = McpSemanticValidator::new(ValidationConfig::default());
+        let result = validator.validate_error_semantics(&url).await.unwrap();
+
+        assert!(result.issues.is_empty(), "{:?}", result.issues);
+        assert!(result.score.is_perfect());
+        assert_eq!(result.score.total, 3);
+    }
+
+    #[tokio::test]
+    async fn test_error_semantics_reports_wrong_codes() {
+        // Everything fails with a generic internal error, a common server bug
+        let url = spawn_mock_server(|request| match request["method"].as_str().unwrap() {
+            "initialize" => initialize_reply(request),
+            _ => error_reply(request, -32603),
+        })
+        .await;
+
+        let validator = McpSemanticValidator::new(ValidationConfig::default());
+        let result = validator.validate_error_semantics(&url).await.unwrap();
+
+        assert_eq!(result.score.passed, 0);
+        assert_eq!(result.issues.len(), 3);
+        assert!(result.issues.iter().all(|issue| {
+            issue.category == "error_semantics"
+                && issue.severity == IssueSeverity::Error
+                && issue.details["actual_code"] == -32603
+        }));
+        assert!(result.issues[0].description.contains("unknown method"));
+        assert!(result.issues[0].description.contains("expected [-32601]"));
+    }
+
+    #[tokio::test]
+    async fn test_error_semantics_reports_result_instead_of_error() {
+        let url = spawn_mock_server(|request| match request["method"].as_str().unwrap() {
+            "initialize" => initialize_reply(request),
+            "tools/call" => json!({
+                "jsonrpc": "2.0",
+                "result": {"content": [], "isError": true},
+                "id": request["id"]
+            }),
+            _ => error_reply(request, -32601),
+        })
+        .await;
+
+        let validator = McpSemanticValidator::new(ValidationConfig::default());
+        let result = validator.validate_error_semantics(&url).await.unwrap();
+
+        assert_eq!(result.score.passed, 1);
+        assert_eq!(result.issues.len(), 2);
+        assert!(result.issues.iter().all(|issue| {
+            issue
+                .description
+                .contains("returned a result instead of an error")
+        }));
+    }
+
+    #[tokio::test]
+    async fn test_error_semantics_requires_http_url() {
+        let validator = McpSemanticValidator::new(ValidationConfig::default());
+        let result = validator.validate_error_semantics("stdio://server").await;
+        assert!(matches!(
+            result,
+            Err(ValidationError::InvalidServerUrl { .. })
+        ));
+    }
 }
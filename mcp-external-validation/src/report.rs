@@ -5,7 +5,7 @@ use std::collections::HashMap;
 use std::time::{Duration, SystemTime};
 
 // Import McpSemanticResult from mcp_semantic module
-pub use crate::mcp_semantic::McpSemanticResult;
+pub use crate::mcp_semantic::{ErrorSemanticsResult, McpSemanticResult};
 // Import CrossLanguageResult from cross_language module
 pub use crate::cross_language::CrossLanguageResult;
 // Import EcosystemResult from ecosystem module
@@ -209,6 +209,9 @@ pub struct ExternalValidatorResults {
     /// MCP protocol semantic validation results
     pub mcp_semantic: Option<McpSemanticResult>,
 
+    /// Error code semantics results for crafted bad requests
+    pub error_semantics: Option<ErrorSemanticsResult>,
+
     /// Cross-language compatibility results
     pub cross_language: Option<CrossLanguageResult>,
 
@@ -452,10 +455,11 @@ impl ComplianceReport {
         let total = passed + failed + skipped;
 
         format!(
-            "MCP Compliance Report: {} - {}/{} tests passed, {} issues found",
+            "MCP Compliance Report: {} - {}/{} tests passed, {} skipped, {} issues found",
             self.status_string(),
             passed,
             total,
+            skipped,
             self.issues.len()
         )
     }
@@ -495,6 +499,7 @@ impl Default for ExternalValidatorResults {
             inspector: None,
             python_compat: None,
             mcp_semantic: None,
+            error_semantics: None,
             cross_language: None,
             ecosystem: None,
             security: None,
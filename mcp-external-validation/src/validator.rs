@@ -10,12 +10,23 @@ use crate::{
     jsonrpc::JsonRpcValidator,
     mcp_semantic::McpSemanticValidator,
     mcp_validator::McpValidatorClient,
-    report::{ComplianceReport, ComplianceStatus, ExternalValidatorResults, PythonCompatResult},
+    report::{
+        ComplianceReport, ComplianceStatus, ExternalValidatorResults, IndividualTestResult,
+        IssueSeverity, PythonCompatResult, TestCategoryResult, TestResult, ValidationIssue,
+    },
     security::SecurityTester,
 };
+use std::collections::HashMap;
 use std::time::{Duration, Instant};
 use tracing::{error, info, warn};
 
+/// Test category reporting whether each external validator ran
+pub const EXTERNAL_VALIDATORS_CATEGORY: &str = "external_validators";
+
+const MCP_VALIDATOR: &str = "mcp-validator";
+const MCP_INSPECTOR: &str = "mcp-inspector";
+const PYTHON_SDK: &str = "python-sdk";
+
 /// Main external validator that orchestrates all validation components
 pub struct ExternalValidator {
     config: ValidationConfig,
@@ -27,6 +38,8 @@ pub struct ExternalValidator {
     ecosystem_tester: Option<EcosystemTester>,
     security_tester: Option<SecurityTester>,
     auth_integration_tester: Option<AuthIntegrationTester>,
+    /// Why each unavailable external validator couldn't be used
+    unavailable: HashMap<&'static str, String>,
 }
 
 impl ExternalValidator {
@@ -40,6 +53,7 @@ impl ExternalValidator {
     pub async fn with_config(config: ValidationConfig) -> ValidationResult<Self> {
         // Validate configuration
         config.validate()?;
+        let mut unavailable = HashMap::new();
 
         // Initialize MCP validator client
         let mcp_validator = match McpValidatorClient::new(config.clone()) {
@@ -52,12 +66,14 @@ impl ExternalValidator {
                     }
                     Err(e) => {
                         warn!("MCP Validator service unavailable: {}", e);
+                        unavailable.insert(MCP_VALIDATOR, e.to_string());
                         None
                     }
                 }
             }
             Err(e) => {
                 warn!("Failed to initialize MCP Validator client: {}", e);
+                unavailable.insert(MCP_VALIDATOR, e.to_string());
                 None
             }
         };
@@ -133,16 +149,19 @@ impl ExternalValidator {
                     }
                     Ok(false) => {
                         warn!("MCP Inspector is not available");
+                        unavailable.insert(MCP_INSPECTOR, "npx inspector not found".to_string());
                         None
                     }
                     Err(e) => {
                         warn!("Failed to check MCP Inspector availability: {}", e);
+                        unavailable.insert(MCP_INSPECTOR, e.to_string());
                         None
                     }
                 }
             }
             Err(e) => {
                 warn!("Failed to initialize Inspector client: {}", e);
+                unavailable.insert(MCP_INSPECTOR, e.to_string());
                 None
             }
         };
@@ -157,6 +176,7 @@ impl ExternalValidator {
             ecosystem_tester,
             security_tester,
             auth_integration_tester,
+            unavailable,
         })
     }
 
@@ -203,6 +223,8 @@ impl ExternalValidator {
             }
         }
 
+        self.record_external_validators(&mut report);
+
         // Mark validation as completed
         let duration = start_time.elapsed();
         report.mark_completed(duration);
@@ -286,6 +308,24 @@ impl ExternalValidator {
             }
         }
 
+        // Error code semantics for crafted bad requests
+        if server_url.starts_with("http://") || server_url.starts_with("https://") {
+            info!("Running error code semantics validation...");
+            match self
+                .semantic_validator
+                .validate_error_semantics(server_url)
+                .await
+            {
+                Ok(error_semantics) => {
+                    info!("Error code semantics validation completed successfully");
+                    results.error_semantics = Some(error_semantics);
+                }
+                Err(e) => {
+                    warn!("Error code semantics validation failed: {}", e);
+                }
+            }
+        }
+
         // MCP Inspector
         if let Some(ref inspector) = self.inspector_client {
             info!("Running MCP Inspector tests...");
@@ -421,11 +461,13 @@ impl ExternalValidator {
                         }
                         Err(e) => {
                             warn!("Failed to setup Python environment: {}", e);
+                            self.unavailable.insert(PYTHON_SDK, e.to_string());
                         }
                     }
                 }
                 Err(e) => {
                     warn!("Python SDK tester initialization failed: {}", e);
+                    self.unavailable.insert(PYTHON_SDK, e.to_string());
                 }
             }
         } else {
@@ -435,6 +477,87 @@ impl ExternalValidator {
         Ok(results)
     }
 
+    /// Report whether each external validator ran, in [`EXTERNAL_VALIDATORS_CATEGORY`]
+    ///
+    /// An unavailable validator is reported as skipped, or as an error that
+    /// fails validation when `testing.require_external_validators` is set.
+    fn record_external_validators(&self, report: &mut ComplianceReport) {
+        let results = &report.external_results;
+        let mut outcomes = vec![
+            (MCP_VALIDATOR, results.mcp_validator.is_some()),
+            (MCP_INSPECTOR, results.inspector.is_some()),
+        ];
+        if self.config.testing.python_sdk_compatibility {
+            outcomes.push((PYTHON_SDK, results.python_compat.is_some()));
+        }
+
+        let mut category = TestCategoryResult {
+            category: EXTERNAL_VALIDATORS_CATEGORY.to_string(),
+            passed: 0,
+            failed: 0,
+            skipped: 0,
+            duration: Duration::ZERO,
+            tests: Vec::new(),
+        };
+        let mut issues = Vec::new();
+        for (name, ran) in outcomes {
+            let (result, error) = match self.unavailable.get(name) {
+                _ if ran => (TestResult::Passed, None),
+                Some(reason) if !self.config.testing.require_external_validators => {
+                    issues.push(ValidationIssue::new(
+                        IssueSeverity::Info,
+                        EXTERNAL_VALIDATORS_CATEGORY.to_string(),
+                        format!("{name} skipped: {reason}"),
+                        name.to_string(),
+                    ));
+                    (TestResult::Skipped, Some(reason.clone()))
+                }
+                Some(reason) => {
+                    issues.push(
+                        ValidationIssue::new(
+                            IssueSeverity::Error,
+                            EXTERNAL_VALIDATORS_CATEGORY.to_string(),
+                            format!("{name} unavailable: {reason}"),
+                            name.to_string(),
+                        )
+                        .with_suggestion(
+                            "Unset testing.require_external_validators to run the remaining checks offline"
+                                .to_string(),
+                        ),
+                    );
+                    (TestResult::Error, Some(reason.clone()))
+                }
+                None if name == MCP_INSPECTOR && report.server_url.starts_with("http") => (
+                    TestResult::Skipped,
+                    Some("the inspector needs a server command, not a URL".to_string()),
+                ),
+                None => (
+                    TestResult::Error,
+                    Some("validator ran but produced no results".to_string()),
+                ),
+            };
+            match result {
+                TestResult::Passed => category.passed += 1,
+                TestResult::Skipped => category.skipped += 1,
+                TestResult::Failed | TestResult::Error => category.failed += 1,
+            }
+            category.tests.push(IndividualTestResult {
+                name: name.to_string(),
+                result,
+                duration: Duration::ZERO,
+                error,
+                details: HashMap::new(),
+            });
+        }
+
+        for issue in issues {
+            report.add_issue(issue);
+        }
+        report
+            .test_results
+            .insert(EXTERNAL_VALIDATORS_CATEGORY.to_string(), category);
+    }
+
     /// Quick validation check (subset of full validation)
     pub async fn quick_validate(&self, server_url: &str) -> ValidationResult<ComplianceStatus> {
         info!("Running quick validation for {}", server_url);
@@ -694,4 +817,70 @@ mod tests {
         assert_eq!(results.successful_iterations, 95);
         assert!((results.throughput_rps - 9.5).abs() < 0.01);
     }
+
+    /// Validator with only the local validators, as if the external tools
+    /// weren't installed and the MCP Validator service was unreachable
+    fn offline_validator(require_external: bool) -> ExternalValidator {
+        let mut config = ValidationConfig::default();
+        config.testing.python_sdk_compatibility = false;
+        config.testing.require_external_validators = require_external;
+        ExternalValidator {
+            jsonrpc_validator: JsonRpcValidator::new(config.clone()).unwrap(),
+            semantic_validator: McpSemanticValidator::new(config.clone()),
+            config,
+            mcp_validator: None,
+            inspector_client: None,
+            cross_language_tester: None,
+            ecosystem_tester: None,
+            security_tester: None,
+            auth_integration_tester: None,
+            unavailable: HashMap::from([
+                (MCP_VALIDATOR, "connection refused".to_string()),
+                (MCP_INSPECTOR, "npx inspector not found".to_string()),
+            ]),
+        }
+    }
+
+    fn mcp_validator_result(report: &ComplianceReport) -> &IndividualTestResult {
+        report.test_results[EXTERNAL_VALIDATORS_CATEGORY]
+            .tests
+            .iter()
+            .find(|test| test.name == MCP_VALIDATOR)
+            .unwrap()
+    }
+
+    #[test]
+    fn test_unavailable_validator_is_skipped_by_default() {
+        let validator = offline_validator(false);
+
+        let mut report = ComplianceReport::new("stdio-server".to_string(), "2025-11-25".into());
+        validator.record_external_validators(&mut report);
+        report.mark_completed(Duration::ZERO);
+
+        let result = mcp_validator_result(&report);
+        assert_eq!(result.result, TestResult::Skipped);
+        assert!(result.error.is_some());
+        assert_eq!(report.test_statistics(), (0, 0, 2));
+        assert!(report.summary().contains("0/2 tests passed, 2 skipped"));
+        assert!(report.critical_issues().is_empty());
+        assert!(report.is_compliant());
+    }
+
+    #[test]
+    fn test_unavailable_validator_fails_when_required() {
+        let validator = offline_validator(true);
+
+        let mut report = ComplianceReport::new("stdio-server".to_string(), "2025-11-25".into());
+        validator.record_external_validators(&mut report);
+        report.mark_completed(Duration::ZERO);
+
+        assert_eq!(mcp_validator_result(&report).result, TestResult::Error);
+        assert!(
+            report
+                .critical_issues()
+                .iter()
+                .any(|issue| issue.validator == MCP_VALIDATOR)
+        );
+        assert_eq!(report.status, ComplianceStatus::NonCompliant);
+    }
 }
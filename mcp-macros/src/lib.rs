@@ -68,6 +68,30 @@ mod utils;
 ///
 /// - `description`: Optional custom description (defaults to doc comments)
 /// - `name`: Optional custom tool name (defaults to function name)
+/// - `requires("a", "b", ...)`: Parameters that must be given together,
+///   emitted as `dependentRequired`
+/// - `exclusive("x", "y", ...)`: Parameters of which at most one may be given,
+///   emitted as `oneOf`
+/// - `text_format`: Format hint for the tool's text output, e.g.
+///   `"text/markdown"`, sent as `_meta.contentType` (defaults to `text/plain`)
+/// - `deprecated`: Deprecation notice, e.g. `"Use new_tool instead"`, listed
+///   as `_meta.deprecated`; the tool keeps working, and each call sends the
+///   client a `notifications/message` warning when a tool context is available
+///
+/// Constraints are checked before the tool runs; an invalid combination is
+/// rejected with an `invalid_params` error. They may be repeated, and are also
+/// honoured on methods inside `#[mcp_tools]`:
+///
+/// ```rust,ignore
+/// #[mcp_tool(requires("start_date", "end_date"), exclusive("id", "name"))]
+/// pub async fn search(
+///     &self,
+///     start_date: Option<String>,
+///     end_date: Option<String>,
+///     id: Option<u64>,
+///     name: Option<String>,
+/// ) -> String { /* ... */ }
+/// ```
 ///
 /// # Features
 ///
@@ -203,6 +227,8 @@ pub fn mcp_server(attr: TokenStream, item: TokenStream) -> TokenStream {
 /// - `name`: Optional custom resource name (defaults to function name)
 /// - `description`: Optional custom description (defaults to doc comments)
 /// - `mime_type`: Optional MIME type (defaults to "text/plain")
+/// - `requires_permission`: Optional permission the caller's `AuthContext`
+///   must hold; the server denies reads without it
 ///
 /// # Features
 ///
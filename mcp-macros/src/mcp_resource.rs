@@ -31,6 +31,8 @@ pub struct McpResourceConfig {
     pub description: Option<String>,
     /// MIME type of the resource content
     pub mime_type: Option<String>,
+    /// Permission a caller needs to read the resource
+    pub requires_permission: Option<String>,
 }
 
 /// Parse macro attributes into McpResourceConfig
@@ -93,6 +95,20 @@ fn parse_resource_attributes(args: TokenStream) -> syn::Result<McpResourceConfig
                     ));
                 }
             }
+            "requires_permission" => {
+                if let syn::Expr::Lit(syn::ExprLit {
+                    lit: syn::Lit::Str(lit_str),
+                    ..
+                }) = value
+                {
+                    config.requires_permission = Some(lit_str.value());
+                } else {
+                    return Err(Error::new_spanned(
+                        value,
+                        "requires_permission must be a string literal",
+                    ));
+                }
+            }
             _ => {
                 return Err(Error::new_spanned(
                     value,
@@ -225,6 +241,14 @@ fn generate_resource_impl(
     // Generate unique resource info function name
     let resource_info_name =
         syn::Ident::new(&format!("__mcp_resource_info_{fn_name}"), Span::call_site());
+    let permission_name = syn::Ident::new(
+        &format!("__mcp_resource_permission_{fn_name}"),
+        Span::call_site(),
+    );
+    let permission = match &config.requires_permission {
+        Some(permission) => quote!(Some(#permission)),
+        None => quote!(None),
+    };
 
     Ok(quote! {
         // Original function (unchanged)
@@ -274,6 +298,11 @@ fn generate_resource_impl(
                 raw: None,
             }
         }
+
+        // Permission a caller needs to read the resource
+        pub fn #permission_name() -> Option<&'static str> {
+            #permission
+        }
     })
 }
 
@@ -329,5 +358,23 @@ mod tests {
         assert_eq!(config.uri_template, Some("file://{path}".to_string()));
         assert_eq!(config.name, Some("file_reader".to_string()));
         assert_eq!(config.mime_type, Some("application/json".to_string()));
+        assert_eq!(config.requires_permission, None);
+    }
+
+    #[test]
+    fn test_parse_requires_permission() {
+        let args = quote! {
+            uri_template = "db://customers/{id}",
+            requires_permission = "resource:db://customers/*"
+        };
+
+        let config = parse_resource_attributes(args).unwrap();
+        assert_eq!(
+            config.requires_permission,
+            Some("resource:db://customers/*".to_string())
+        );
+
+        let args = quote! { uri_template = "db://customers/{id}", requires_permission = 1 };
+        assert!(parse_resource_attributes(args).is_err());
     }
 }
@@ -232,6 +232,10 @@ fn generate_server_implementation(
                 }
             }
 
+            fn resource_permission(&self, uri: &str) -> Option<String> {
+                self.try_resource_permission_default(uri)
+            }
+
             async fn list_prompts(&self, _request: pulseengine_mcp_protocol::PaginatedRequestParam) -> std::result::Result<pulseengine_mcp_protocol::ListPromptsResult, Self::Error> {
                 Ok(pulseengine_mcp_protocol::ListPromptsResult { prompts: vec![], next_cursor: None })
             }
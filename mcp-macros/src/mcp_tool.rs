@@ -23,6 +23,7 @@ struct ResourceInfo {
     method_param_types: Vec<syn::Type>,
     is_async: bool,
     has_params: bool,
+    required_permission: Option<String>,
 }
 
 /// Helper to parse URI template and extract path pattern for matchit
@@ -95,11 +96,178 @@ pub struct McpToolAttribute {
     pub idempotent: Option<bool>,
     /// Custom input schema
     pub input_schema: Option<syn::Expr>,
+    /// Parameters that must be given together, e.g. `requires("start_date", "end_date")`
+    #[darling(multiple)]
+    pub requires: Vec<ParameterGroup>,
+    /// Parameters of which at most one may be given, e.g. `exclusive("id", "name")`
+    #[darling(multiple)]
+    pub exclusive: Vec<ParameterGroup>,
+    /// Format of the tool's text output, e.g. `text_format = "text/markdown"`
+    /// (text is `text/plain` when unset)
+    pub text_format: Option<String>,
+    /// Deprecation notice, e.g. `deprecated = "Use new_tool instead"`; the tool
+    /// keeps working but is marked deprecated and warns callers
+    pub deprecated: Option<String>,
+}
+
+/// Parameter names listed in a `requires(...)` or `exclusive(...)` constraint
+#[derive(Debug, Clone)]
+pub struct ParameterGroup(Vec<syn::LitStr>);
+
+impl FromMeta for ParameterGroup {
+    fn from_list(items: &[NestedMeta]) -> darling::Result<Self> {
+        let names = items
+            .iter()
+            .map(|item| match item {
+                NestedMeta::Lit(syn::Lit::Str(name)) => Ok(name.clone()),
+                other => {
+                    Err(darling::Error::custom("expected a parameter name string").with_span(other))
+                }
+            })
+            .collect::<darling::Result<Vec<_>>>()?;
+        if names.len() < 2 {
+            return Err(darling::Error::custom(
+                "a parameter constraint needs at least two parameter names",
+            ));
+        }
+        Ok(Self(names))
+    }
+}
+
+impl McpToolAttribute {
+    /// Parse the `#[mcp_tool(...)]` attribute of a method inside `#[mcp_tools]`
+    fn from_method_attrs(attrs: &[syn::Attribute]) -> syn::Result<Self> {
+        let Some(attr) = attrs.iter().find(|attr| attr.path().is_ident("mcp_tool")) else {
+            return Ok(Self::default());
+        };
+        match &attr.meta {
+            syn::Meta::Path(_) => Ok(Self::default()),
+            meta => {
+                let attr_args = NestedMeta::parse_meta_list(meta.require_list()?.tokens.clone())?;
+                Self::from_list(&attr_args)
+                    .map_err(|e| syn::Error::new_spanned(attr, e.to_string()))
+            }
+        }
+    }
+
+    fn has_parameter_constraints(&self) -> bool {
+        !self.requires.is_empty() || !self.exclusive.is_empty()
+    }
+
+    /// Reject constraints naming something other than one of the tool's parameters
+    fn check_constraint_names(&self, param_names: &[String]) -> syn::Result<()> {
+        for name in self
+            .requires
+            .iter()
+            .chain(&self.exclusive)
+            .flat_map(|g| &g.0)
+        {
+            if !param_names.contains(&name.value()) {
+                return Err(syn::Error::new_spanned(
+                    name,
+                    format!("'{}' is not a parameter of this tool", name.value()),
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    fn constraint_groups(&self) -> (TokenStream, TokenStream) {
+        let groups = |groups: &[ParameterGroup]| {
+            let groups = groups.iter().map(|group| {
+                let names = &group.0;
+                quote! { &[#(#names),*] }
+            });
+            quote! { &[#(#groups),*] }
+        };
+        (groups(&self.requires), groups(&self.exclusive))
+    }
+
+    /// Wrap an input schema expression so it carries the parameter constraints
+    fn constrain_schema(&self, schema: TokenStream) -> TokenStream {
+        if !self.has_parameter_constraints() {
+            return schema;
+        }
+        let (requires, exclusive) = self.constraint_groups();
+        quote! {
+            {
+                let mut schema = #schema;
+                pulseengine_mcp_protocol::Validator::apply_parameter_constraints(
+                    &mut schema, #requires, #exclusive,
+                );
+                schema
+            }
+        }
+    }
+
+    /// Wrap the statements producing a tool's result so its text content
+    /// carries the declared format
+    fn format_text_output(&self, tool_call: TokenStream) -> TokenStream {
+        let Some(text_format) = &self.text_format else {
+            return tool_call;
+        };
+        quote! {
+            {
+                let __result: std::result::Result<
+                    pulseengine_mcp_protocol::CallToolResult,
+                    pulseengine_mcp_protocol::Error,
+                > = { #tool_call };
+                __result.map(|result| result.with_text_format(#text_format))
+            }
+        }
+    }
+
+    /// The tool definition's `_meta`, carrying the deprecation notice if any
+    fn tool_meta(&self) -> TokenStream {
+        match &self.deprecated {
+            Some(message) => quote! {
+                Some(pulseengine_mcp_protocol::ToolMeta::with_deprecation(#message))
+            },
+            None => quote! { None },
+        }
+    }
+
+    /// Statements warning the client, through a `notifications/message` log
+    /// notification, that it called a deprecated tool
+    fn deprecation_warning(&self, tool_name: &str) -> TokenStream {
+        let Some(message) = &self.deprecated else {
+            return quote! {};
+        };
+        quote! {
+            if let Some(__tool_ctx) = pulseengine_mcp_server::try_current_context() {
+                let _ = __tool_ctx
+                    .send_log(
+                        pulseengine_mcp_protocol::LogLevel::Warning,
+                        Some("deprecation"),
+                        serde_json::json!({
+                            "message": format!("Tool '{}' is deprecated: {}", #tool_name, #message),
+                            "tool": #tool_name,
+                            "deprecated": #message,
+                        }),
+                    )
+                    .await;
+            }
+        }
+    }
+
+    /// Statements rejecting invalid parameter combinations in `args`
+    fn constraint_checks(&self) -> TokenStream {
+        if !self.has_parameter_constraints() {
+            return quote! {};
+        }
+        let (requires, exclusive) = self.constraint_groups();
+        quote! {
+            pulseengine_mcp_protocol::Validator::validate_parameter_constraints(
+                args, #requires, #exclusive,
+            )
+            .map_err(|e| pulseengine_mcp_protocol::Error::invalid_params(e.message))?;
+        }
+    }
 }
 
 /// Implementation of #[mcp_tool] macro
 pub fn mcp_tool_impl(attr: TokenStream, item: TokenStream) -> syn::Result<TokenStream> {
-    let attribute = if attr.is_empty() {
+    let attribute: McpToolAttribute = if attr.is_empty() {
         Default::default()
     } else {
         let attr_args = NestedMeta::parse_meta_list(attr)?;
@@ -123,10 +291,15 @@ pub fn mcp_tool_impl(attr: TokenStream, item: TokenStream) -> syn::Result<TokenS
     let fn_name = &function.sig.ident;
     let tool_name = attribute
         .name
+        .clone()
         .unwrap_or_else(|| function_name_to_tool_name(fn_name));
     let description = attribute
         .description
+        .clone()
         .or_else(|| extract_doc_comment(&function.attrs));
+    if let Some(param_names) = constrainable_parameter_names(&function.sig) {
+        attribute.check_constraint_names(&param_names)?;
+    }
 
     // Generate tool definition function
     let tool_def_fn_name = format_ident!("{}_tool_definition", fn_name);
@@ -139,13 +312,14 @@ pub fn mcp_tool_impl(attr: TokenStream, item: TokenStream) -> syn::Result<TokenS
     } = extract_parameters(&function.sig, &tool_name)?;
 
     // Generate input schema (ToolContext is excluded from schema)
-    let input_schema = if let Some(schema_expr) = attribute.input_schema {
+    let input_schema = if let Some(schema_expr) = &attribute.input_schema {
         quote! { #schema_expr }
     } else if param_fields.is_empty() {
         quote! { serde_json::json!({ "type": "object", "properties": {} }) }
     } else {
         generate_schema_for_type(&param_struct)
     };
+    let input_schema = attribute.constrain_schema(input_schema);
 
     // Handle async functions - inject ToolContext if needed
     let (call_expr, is_async) = if function.sig.asyncness.is_some() {
@@ -189,6 +363,7 @@ pub fn mcp_tool_impl(attr: TokenStream, item: TokenStream) -> syn::Result<TokenS
         &function.sig.output,
         is_async,
         &param_fields,
+        &attribute,
     )?;
 
     // Generate the enhanced function with tool metadata
@@ -264,6 +439,14 @@ fn generate_matchit_resource_impl(
         })
         .collect();
 
+    let router_paths: Vec<_> = resource_infos
+        .iter()
+        .map(|info| &info.path_pattern)
+        .collect();
+    let router_variants: Vec<_> = (0..resource_infos.len())
+        .map(|i| format_ident!("Resource{}", i))
+        .collect();
+
     // Generate match arms for resource dispatch
     let resource_match_arms: Vec<_> = resource_infos
         .iter()
@@ -346,7 +529,8 @@ fn generate_matchit_resource_impl(
                                         text: Some(content_str),
                                         blob: None,
                                         _meta: None,
-                                    }]
+                                    }],
+                                    _meta: None,
                                 })
                             }
                             Err(e) => Err(pulseengine_mcp_protocol::Error::internal_error(
@@ -373,7 +557,8 @@ fn generate_matchit_resource_impl(
                                         text: Some(content_str),
                                         blob: None,
                                         _meta: None,
-                                    }]
+                                    }],
+                                    _meta: None,
                                 })
                             }
                             Err(e) => Err(pulseengine_mcp_protocol::Error::internal_error(
@@ -386,6 +571,50 @@ fn generate_matchit_resource_impl(
         })
         .collect();
 
+    // Generate the permission lookup, when any resource declares one
+    let permission_impl = if resource_infos
+        .iter()
+        .any(|info| info.required_permission.is_some())
+    {
+        let permission_arms = resource_infos.iter().enumerate().map(|(i, info)| {
+            let variant_name = format_ident!("Resource{}", i);
+            match &info.required_permission {
+                Some(permission) => {
+                    quote! { ResourceHandler::#variant_name => Some(#permission.to_string()), }
+                }
+                None => quote! { ResourceHandler::#variant_name => None, },
+            }
+        });
+        quote! {
+            fn resource_permission(&self, uri: &str) -> Option<String> {
+                #resource_handler_enum
+
+                // Built on first use and shared by every later lookup
+                static ROUTER: std::sync::OnceLock<Option<matchit::Router<ResourceHandler>>> =
+                    std::sync::OnceLock::new();
+                let router = ROUTER
+                    .get_or_init(|| {
+                        let mut router = matchit::Router::new();
+                        #(
+                            router.insert(#router_paths, ResourceHandler::#router_variants).ok()?;
+                        )*
+                        Some(router)
+                    })
+                    .as_ref()?;
+                let path = match uri.find("://") {
+                    Some(pos) => format!("/{}", &uri[pos + 3..]),
+                    None if uri.starts_with('/') => uri.to_string(),
+                    None => format!("/{}", uri),
+                };
+                match router.at(&path).ok()?.value {
+                    #(#permission_arms)*
+                }
+            }
+        }
+    } else {
+        quote! {}
+    };
+
     // Generate the complete implementation
     quote! {
         impl #impl_generics pulseengine_mcp_server::McpResourcesProvider for #struct_name #ty_generics #where_clause {
@@ -395,6 +624,8 @@ fn generate_matchit_resource_impl(
                 ]
             }
 
+            #permission_impl
+
             fn read_resource_impl(
                 &self,
                 request: pulseengine_mcp_protocol::ReadResourceRequestParam,
@@ -448,6 +679,11 @@ fn generate_matchit_resource_impl(
 
 /// Extract URI template from mcp_resource attribute
 fn extract_uri_template_from_attr(attrs: &[syn::Attribute]) -> Option<String> {
+    extract_resource_attr_string(attrs, "uri_template")
+}
+
+/// Extract a string-valued argument such as `uri_template` from mcp_resource
+fn extract_resource_attr_string(attrs: &[syn::Attribute], key: &str) -> Option<String> {
     for attr in attrs {
         if attr.path().is_ident("mcp_resource")
             && let Ok(meta_list) = attr.meta.require_list()
@@ -458,7 +694,7 @@ fn extract_uri_template_from_attr(attrs: &[syn::Attribute]) -> Option<String> {
             {
                 for nested in nested_meta {
                     if let darling::ast::NestedMeta::Meta(syn::Meta::NameValue(name_value)) = nested
-                        && name_value.path.is_ident("uri_template")
+                        && name_value.path.is_ident(key)
                         && let syn::Expr::Lit(syn::ExprLit {
                             lit: syn::Lit::Str(lit_str),
                             ..
@@ -553,6 +789,10 @@ pub fn mcp_tools_impl(_attr: TokenStream, item: TokenStream) -> syn::Result<Toke
                         method_param_types,
                         is_async: method.sig.asyncness.is_some(),
                         has_params: method.sig.inputs.len() > 1,
+                        required_permission: extract_resource_attr_string(
+                            &method.attrs,
+                            "requires_permission",
+                        ),
                     };
 
                     resource_infos.push(resource_info);
@@ -580,8 +820,17 @@ pub fn mcp_tools_impl(_attr: TokenStream, item: TokenStream) -> syn::Result<Toke
                     let description =
                         doc_comment.unwrap_or_else(|| format!("Generated tool for {tool_name}"));
 
+                    let attribute = McpToolAttribute::from_method_attrs(&method.attrs)?;
+                    if let Some(param_names) = constrainable_parameter_names(&method.sig) {
+                        attribute.check_constraint_names(&param_names)?;
+                    }
+
                     // Generate JSON schema for parameters from function signature
-                    let schema = generate_input_schema_for_method(&method.sig)?;
+                    let schema =
+                        attribute.constrain_schema(generate_input_schema_for_method(&method.sig)?);
+                    let constraint_checks = attribute.constraint_checks();
+                    let tool_meta = attribute.tool_meta();
+                    let deprecation_warning = attribute.deprecation_warning(&tool_name);
 
                     // Create tool definition
                     tool_definitions.push(quote! {
@@ -594,7 +843,7 @@ pub fn mcp_tools_impl(_attr: TokenStream, item: TokenStream) -> syn::Result<Toke
                             annotations: None,
                             icons: None,
                             execution: None,
-                            _meta: None,
+                            _meta: #tool_meta,
                         }
                     });
 
@@ -603,6 +852,10 @@ pub fn mcp_tools_impl(_attr: TokenStream, item: TokenStream) -> syn::Result<Toke
                     let method_call =
                         generate_method_call_with_params(&method.sig, method_name, is_async)?;
                     let error_handling = generate_error_handling(&method.sig.output);
+                    let tool_call = attribute.format_text_output(quote! {
+                        let result = #method_call;
+                        #error_handling
+                    });
 
                     tool_dispatch_cases.push(quote! {
                         #tool_name => {
@@ -612,10 +865,11 @@ pub fn mcp_tools_impl(_attr: TokenStream, item: TokenStream) -> syn::Result<Toke
                             let args = args.as_object().ok_or_else(|| {
                                 pulseengine_mcp_protocol::Error::invalid_params("Arguments must be an object".to_string())
                             })?;
+                            #constraint_checks
+                            #deprecation_warning
 
                             // Call method and handle result based on return type
-                            let result = #method_call;
-                            #error_handling
+                            #tool_call
                         }
                     });
                 }
@@ -639,9 +893,9 @@ pub fn mcp_tools_impl(_attr: TokenStream, item: TokenStream) -> syn::Result<Toke
     let mut cleaned_impl_block = impl_block.clone();
     for item in &mut cleaned_impl_block.items {
         if let syn::ImplItem::Fn(method) = item {
-            method
-                .attrs
-                .retain(|attr| !attr.path().is_ident("mcp_resource"));
+            method.attrs.retain(|attr| {
+                !attr.path().is_ident("mcp_resource") && !attr.path().is_ident("mcp_tool")
+            });
         }
     }
 
@@ -688,6 +942,41 @@ pub fn mcp_tools_impl(_attr: TokenStream, item: TokenStream) -> syn::Result<Toke
     Ok(final_impl)
 }
 
+/// Names of the tool parameters that constraints may refer to
+///
+/// Returns `None` when the tool takes a single struct whose fields are the
+/// arguments, since those names aren't visible to the macro.
+fn constrainable_parameter_names(sig: &syn::Signature) -> Option<Vec<String>> {
+    let mut params: Vec<&syn::PatType> = sig
+        .inputs
+        .iter()
+        .filter_map(|input| match input {
+            syn::FnArg::Typed(pat_type) => Some(pat_type),
+            syn::FnArg::Receiver(_) => None,
+        })
+        .collect();
+    if params
+        .first()
+        .is_some_and(|first| is_tool_context_type(&first.ty))
+    {
+        params.remove(0);
+    }
+    if let [single] = params.as_slice()
+        && !is_primitive_or_std_type(&single.ty)
+    {
+        return None;
+    }
+    Some(
+        params
+            .iter()
+            .filter_map(|param| match &*param.pat {
+                syn::Pat::Ident(ident) => Some(ident.ident.to_string()),
+                _ => None,
+            })
+            .collect(),
+    )
+}
+
 /// Result of parameter extraction including ToolContext detection
 struct ExtractedParameters {
     /// The synthetic struct type for parameter schema
@@ -846,6 +1135,7 @@ fn generate_tool_implementation(
     return_type: &ReturnType,
     _is_async: bool,
     param_fields: &[TokenStream],
+    attribute: &McpToolAttribute,
 ) -> syn::Result<TokenStream> {
     let description_expr = match description {
         Some(desc) => quote! { Some(#desc.to_string()) },
@@ -853,10 +1143,13 @@ fn generate_tool_implementation(
     };
 
     let error_handling = generate_error_handling(return_type);
-    let tool_call = quote! {
+    let tool_call = attribute.format_text_output(quote! {
         let result = #call_expr;
         #error_handling
-    };
+    });
+    let constraint_checks = attribute.constraint_checks();
+    let tool_meta = attribute.tool_meta();
+    let deprecation_warning = attribute.deprecation_warning(tool_name);
 
     let param_extraction = if param_fields.is_empty() {
         quote! {}
@@ -876,6 +1169,7 @@ fn generate_tool_implementation(
                         })
                 )
             )?;
+            #constraint_checks
         }
     };
 
@@ -892,7 +1186,7 @@ fn generate_tool_implementation(
                 annotations: None,
                 icons: None,
                 execution: None,
-                _meta: None,
+                _meta: #tool_meta,
             }
         }
 
@@ -903,6 +1197,7 @@ fn generate_tool_implementation(
             match request.name.as_str() {
                 #tool_name => {
                     #param_extraction
+                    #deprecation_warning
 
                     #tool_call
                 }
@@ -1427,6 +1722,12 @@ fn generate_helper_methods(
             pub async fn try_read_resource_default(&self, request: pulseengine_mcp_protocol::ReadResourceRequestParam) -> std::result::Result<pulseengine_mcp_protocol::ReadResourceResult, pulseengine_mcp_protocol::Error> {
                 <Self as pulseengine_mcp_server::McpResourcesProvider>::read_resource_impl(self, request).await
             }
+
+            /// Helper method to look up a resource's required permission (used by mcp_server macro)
+            #[allow(dead_code)]
+            pub fn try_resource_permission_default(&self, uri: &str) -> Option<String> {
+                <Self as pulseengine_mcp_server::McpResourcesProvider>::resource_permission(self, uri)
+            }
         }
     }
 }
@@ -143,6 +143,7 @@ pub fn generate_error_handling(return_type: &syn::ReturnType) -> TokenStream {
                     content: vec![],
                     is_error: Some(false),
                     structured_content: None,
+                    structured_content_blocks: None,
                     _meta: None,
                 })
             }
@@ -176,6 +177,7 @@ pub fn generate_error_handling(return_type: &syn::ReturnType) -> TokenStream {
                                 content: vec![pulseengine_mcp_protocol::Content::text(text_content)],
                                 is_error: Some(false),
                                 structured_content: structured,
+                                structured_content_blocks: None,
                                 _meta: None,
                             })
                         }
@@ -205,6 +207,7 @@ pub fn generate_error_handling(return_type: &syn::ReturnType) -> TokenStream {
                         content: vec![pulseengine_mcp_protocol::Content::text(text_content)],
                         is_error: Some(false),
                         structured_content: structured,
+                        structured_content_blocks: None,
                         _meta: None,
                     })
                 }
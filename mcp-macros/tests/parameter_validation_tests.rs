@@ -192,12 +192,61 @@ mod validation_server {
     }
 }
 
+mod constraints {
+    use super::*;
+
+    #[mcp_server(name = "Constraint Server")]
+    #[derive(Default, Clone)]
+    pub struct ConstraintServer;
+
+    #[mcp_tools]
+    #[allow(dead_code)]
+    impl ConstraintServer {
+        /// Search events, optionally within a date range or by one identifier
+        #[mcp_tool(requires("start_date", "end_date"), exclusive("id", "name"))]
+        pub async fn search_events(
+            &self,
+            start_date: Option<String>,
+            end_date: Option<String>,
+            id: Option<u64>,
+            name: Option<String>,
+        ) -> String {
+            format!("{start_date:?} {end_date:?} {id:?} {name:?}")
+        }
+    }
+}
+
+mod text_formats {
+    use super::*;
+
+    #[mcp_server(name = "Text Format Server")]
+    #[derive(Default, Clone)]
+    pub struct TextFormatServer;
+
+    #[mcp_tools]
+    #[allow(dead_code)]
+    impl TextFormatServer {
+        /// Render a report as markdown
+        #[mcp_tool(text_format = "text/markdown")]
+        pub async fn report(&self, title: String) -> String {
+            format!("# {title}")
+        }
+
+        /// Echo plain text
+        pub async fn echo(&self, text: String) -> String {
+            text
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use constraints::*;
     use edge_cases::*;
     use parameter_types::*;
-    use pulseengine_mcp_server::McpBackend;
+    use pulseengine_mcp_server::{McpBackend, McpToolsProvider};
+    use text_formats::*;
     use validation_server::*;
 
     #[test]
@@ -304,4 +353,89 @@ mod tests {
             .await;
         assert!(result.contains("a,b,c,d,e,1,2,3,4,5"));
     }
+
+    async fn search_events(
+        arguments: serde_json::Value,
+    ) -> Result<pulseengine_mcp_protocol::CallToolResult, pulseengine_mcp_protocol::Error> {
+        ConstraintServer::with_defaults()
+            .call_tool_impl(pulseengine_mcp_protocol::CallToolRequestParam {
+                name: "search_events".to_string(),
+                arguments: Some(arguments),
+            })
+            .await
+    }
+
+    #[test]
+    fn test_parameter_constraints_in_schema() {
+        let tools = ConstraintServer::with_defaults().get_available_tools();
+        let schema = &tools[0].input_schema;
+
+        assert_eq!(
+            schema["dependentRequired"],
+            serde_json::json!({"start_date": ["end_date"], "end_date": ["start_date"]})
+        );
+        let branches = schema["oneOf"].as_array().unwrap();
+        assert_eq!(branches[0], serde_json::json!({"required": ["id"]}));
+        assert_eq!(branches[1], serde_json::json!({"required": ["name"]}));
+        assert_eq!(branches.len(), 3);
+    }
+
+    #[tokio::test]
+    async fn test_parameter_constraints_checked_at_dispatch() {
+        let error = search_events(serde_json::json!({"start_date": "2024-01-01"}))
+            .await
+            .unwrap_err();
+        assert_eq!(
+            error.code,
+            pulseengine_mcp_protocol::ErrorCode::InvalidParams
+        );
+        assert!(error.message.contains("'end_date' is required"));
+
+        let error = search_events(serde_json::json!({"id": 7, "name": "launch"}))
+            .await
+            .unwrap_err();
+        assert!(error.message.contains("mutually exclusive"));
+
+        let result = search_events(serde_json::json!({
+            "start_date": "2024-01-01",
+            "end_date": "2024-01-31",
+            "name": "launch",
+        }))
+        .await;
+        assert!(result.is_ok());
+        assert!(search_events(serde_json::json!({})).await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_tool_text_format_hint() {
+        use pulseengine_mcp_protocol::{CallToolRequestParam, mime_types};
+
+        let server = TextFormatServer::with_defaults();
+        let call = |name: &str, arguments: serde_json::Value| CallToolRequestParam {
+            name: name.to_string(),
+            arguments: Some(arguments),
+        };
+
+        let report = server
+            .call_tool_impl(call("report", serde_json::json!({"title": "Weekly"})))
+            .await
+            .unwrap();
+        assert_eq!(report.content[0].text_format(), Some(mime_types::MARKDOWN));
+        assert_eq!(
+            serde_json::to_value(&report.content[0]).unwrap()["_meta"]["contentType"],
+            "text/markdown"
+        );
+
+        let echo = server
+            .call_tool_impl(call("echo", serde_json::json!({"text": "hi"})))
+            .await
+            .unwrap();
+        assert_eq!(echo.content[0].text_format(), Some(mime_types::TEXT));
+        assert!(
+            serde_json::to_value(&echo.content[0])
+                .unwrap()
+                .get("_meta")
+                .is_none()
+        );
+    }
 }
@@ -0,0 +1,171 @@
+//! Tests for `#[mcp_resource(requires_permission = "...")]`
+
+use pulseengine_mcp_macros::{mcp_server, mcp_tools};
+use pulseengine_mcp_protocol::{NumberOrString, Request};
+use pulseengine_mcp_server::auth::{AuthContext, Role};
+use pulseengine_mcp_server::{
+    AuthConfig, AuthenticationManager, GenericServerHandler, McpBackend, McpServerBuilder,
+    MiddlewareStack, with_auth_context,
+};
+use std::sync::Arc;
+
+#[mcp_server(name = "Customer Data Server")]
+#[derive(Default, Clone)]
+pub struct CustomerServer;
+
+#[mcp_tools]
+impl CustomerServer {
+    /// Customer record
+    #[mcp_resource(
+        uri_template = "db://customers/{id}",
+        requires_permission = "resource:db://customers/*"
+    )]
+    pub fn customer(&self, id: String) -> Result<String, String> {
+        Ok(format!("Customer {id}"))
+    }
+
+    /// Public product catalogue
+    #[mcp_resource(uri_template = "db://products/{id}")]
+    pub fn product(&self, id: String) -> Result<String, String> {
+        Ok(format!("Product {id}"))
+    }
+}
+
+fn caller(permissions: &[&str]) -> AuthContext {
+    AuthContext {
+        user_id: Some("analyst".to_string()),
+        roles: vec![Role::Custom {
+            permissions: permissions.iter().map(|p| p.to_string()).collect(),
+        }],
+        api_key_id: None,
+        permissions: vec![],
+    }
+}
+
+async fn handler() -> GenericServerHandler<CustomerServer> {
+    let auth_manager = Arc::new(
+        AuthenticationManager::new(AuthConfig::memory())
+            .await
+            .unwrap(),
+    );
+    GenericServerHandler::new(
+        Arc::new(CustomerServer::with_defaults()),
+        auth_manager,
+        MiddlewareStack::new(),
+    )
+}
+
+fn read(uri: &str) -> Request {
+    Request {
+        jsonrpc: "2.0".to_string(),
+        id: Some(NumberOrString::Number(1)),
+        method: "resources/read".to_string(),
+        params: serde_json::json!({ "uri": uri }),
+    }
+}
+
+#[test]
+fn test_permission_is_declared_per_resource() {
+    let server = CustomerServer::with_defaults();
+    assert_eq!(
+        server.resource_permission("db://customers/42").as_deref(),
+        Some("resource:db://customers/*")
+    );
+    assert_eq!(server.resource_permission("db://products/7"), None);
+    assert_eq!(server.resource_permission("db://unknown/7"), None);
+}
+
+#[tokio::test]
+async fn test_caller_without_permission_is_denied() {
+    let handler = handler().await;
+
+    let response = with_auth_context(
+        caller(&["resource:db://products/*"]),
+        handler.handle_request(read("db://customers/42")),
+    )
+    .await
+    .unwrap();
+    let error = response.error.expect("read should be denied");
+    assert!(error.message.contains("resource:db://customers/*"));
+
+    // Unauthenticated callers are denied too
+    let response = handler
+        .handle_request(read("db://customers/42"))
+        .await
+        .unwrap();
+    assert!(response.error.is_some());
+
+    // Resources without a requirement stay readable
+    let response = handler
+        .handle_request(read("db://products/7"))
+        .await
+        .unwrap();
+    assert!(response.error.is_none());
+}
+
+#[tokio::test]
+async fn test_caller_with_permission_reads_resource() {
+    let handler = handler().await;
+
+    let response = with_auth_context(
+        caller(&["resource:db://customers/*"]),
+        handler.handle_request(read("db://customers/42")),
+    )
+    .await
+    .unwrap();
+    assert!(response.error.is_none(), "{:?}", response.error);
+    let text = response.result.unwrap()["contents"][0]["text"].clone();
+    assert_eq!(text, "\"Customer 42\"");
+}
+
+#[tokio::test]
+async fn test_transport_caller_checked_against_key_permissions() {
+    use pulseengine_mcp_server::auth::middleware::{McpAuthConfig, McpAuthMiddleware};
+    use pulseengine_mcp_transport::{ConnectionInfo, with_connection};
+
+    let auth_manager = Arc::new(
+        AuthenticationManager::new(AuthConfig::memory())
+            .await
+            .unwrap(),
+    );
+    let analyst = Role::Custom {
+        permissions: vec!["resource:db://customers/*".to_string()],
+    };
+    let key = auth_manager
+        .create_api_key("analyst".to_string(), analyst, None, None)
+        .await
+        .unwrap();
+    let handler = GenericServerHandler::new(
+        Arc::new(CustomerServer::with_defaults()),
+        auth_manager.clone(),
+        MiddlewareStack::new(),
+    )
+    .with_caller_auth(McpAuthMiddleware::new(
+        auth_manager,
+        McpAuthConfig {
+            require_auth: false,
+            ..Default::default()
+        },
+    ));
+    let over_transport = |headers: Vec<(&'static str, String)>| {
+        let connection = ConnectionInfo::new("conn-1", "http").with_headers(
+            headers
+                .iter()
+                .map(|(name, value)| (*name, value.as_str()))
+                .collect::<Vec<_>>(),
+        );
+        with_connection(
+            connection,
+            handler.handle_request(read("db://customers/42")),
+        )
+    };
+
+    let response = over_transport(vec![("x-api-key", key.key.clone())])
+        .await
+        .unwrap();
+    assert!(response.error.is_none(), "{:?}", response.error);
+
+    // Anonymous transport callers lack the permission
+    let response = over_transport(vec![]).await.unwrap();
+    assert!(response.error.is_some());
+}
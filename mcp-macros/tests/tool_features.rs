@@ -202,3 +202,104 @@ fn test_tool_naming_conventions() {
         assert!(tool_names.contains(&"camelCaseTool"));
     }
 }
+
+#[tokio::test]
+async fn test_deprecated_tool() {
+    use pulseengine_mcp_protocol::CallToolRequestParam;
+    use pulseengine_mcp_server::{
+        DefaultToolContext, McpToolsProvider, NotificationSender, RequestSender, ToolContextError,
+        with_context,
+    };
+    use std::sync::{Arc, Mutex};
+
+    #[derive(Default)]
+    struct RecordingSender {
+        sent: Mutex<Vec<(String, serde_json::Value)>>,
+    }
+
+    #[async_trait::async_trait]
+    impl NotificationSender for RecordingSender {
+        async fn send_notification(
+            &self,
+            method: &str,
+            params: serde_json::Value,
+        ) -> Result<(), ToolContextError> {
+            self.sent.lock().unwrap().push((method.to_string(), params));
+            Ok(())
+        }
+    }
+
+    #[async_trait::async_trait]
+    impl RequestSender for RecordingSender {
+        async fn send_request(
+            &self,
+            _method: &str,
+            _params: serde_json::Value,
+            _timeout: std::time::Duration,
+        ) -> Result<serde_json::Value, ToolContextError> {
+            Err(ToolContextError::NotAvailable)
+        }
+    }
+
+    #[mcp_server(name = "Deprecation Server")]
+    #[derive(Default, Clone)]
+    struct DeprecationServer;
+
+    #[mcp_tools]
+    impl DeprecationServer {
+        /// Look up a user by name
+        #[mcp_tool(deprecated = "Use find_user instead")]
+        pub async fn lookup_user(&self, name: String) -> String {
+            format!("user:{name}")
+        }
+
+        /// Find a user by name
+        pub async fn find_user(&self, name: String) -> String {
+            format!("user:{name}")
+        }
+    }
+
+    let server = DeprecationServer::default();
+    let tools = server.get_available_tools();
+    let lookup = tools.iter().find(|t| t.name == "lookup_user").unwrap();
+    let meta = serde_json::to_value(&lookup._meta).unwrap();
+    assert_eq!(meta["deprecated"], "Use find_user instead");
+    let find = tools.iter().find(|t| t.name == "find_user").unwrap();
+    assert!(find._meta.is_none());
+
+    let sender = Arc::new(RecordingSender::default());
+    let ctx = Arc::new(DefaultToolContext::new(
+        "req-1",
+        "lookup_user",
+        None,
+        None,
+        sender.clone(),
+        sender.clone(),
+    ));
+    let call = |name: &str| CallToolRequestParam {
+        name: name.to_string(),
+        arguments: Some(json!({"name": "ada"})),
+    };
+
+    // The tool still works, and the caller is warned
+    let result = with_context(ctx.clone(), server.call_tool_impl(call("lookup_user")))
+        .await
+        .unwrap();
+    assert_eq!(result.is_error, Some(false));
+    {
+        let sent = sender.sent.lock().unwrap();
+        assert_eq!(sent.len(), 1);
+        assert_eq!(sent[0].0, "notifications/message");
+        assert_eq!(sent[0].1["level"], "warning");
+        assert_eq!(sent[0].1["data"]["tool"], "lookup_user");
+        assert_eq!(sent[0].1["data"]["deprecated"], "Use find_user instead");
+    }
+
+    with_context(ctx, server.call_tool_impl(call("find_user")))
+        .await
+        .unwrap();
+    assert_eq!(sender.sent.lock().unwrap().len(), 1);
+
+    // Without a tool context the call goes through silently
+    assert!(server.call_tool_impl(call("lookup_user")).await.is_ok());
+}
@@ -0,0 +1,85 @@
+//! Canonical JSON serialization for hashing and cache keys
+//!
+//! Logically equal values must produce identical bytes wherever JSON is
+//! hashed or used as a key: request signatures, result cache keys and audit
+//! hashes of tool arguments. [`canonicalize_json`] sorts object keys,
+//! drops insignificant whitespace and normalizes numbers so that `1`, `1.0`
+//! and `-0.0`/`0` canonicalize the same way.
+
+use serde_json::{Number, Value};
+use sha2::{Digest, Sha256};
+
+/// 2^64, the first float beyond `u64::MAX`
+const U64_LIMIT: f64 = 18_446_744_073_709_551_616.0;
+
+/// Serialize `value` in canonical form
+///
+/// Object keys are sorted by their UTF-8 bytes and no whitespace is emitted.
+/// Integral floats within the 64-bit integer range are written as integers
+/// (`2.0` as `2`, `-0.0` as `0`); other floats use the shortest
+/// representation that round-trips to the same `f64`. Strings are escaped
+/// as `serde_json` escapes them.
+pub fn canonicalize_json(value: &Value) -> String {
+    let mut output = String::new();
+    write_canonical(value, &mut output);
+    output
+}
+
+/// Hex-encoded SHA-256 of [`canonicalize_json`]'s output
+pub fn canonical_hash(value: &Value) -> String {
+    Sha256::digest(canonicalize_json(value).as_bytes())
+        .iter()
+        .map(|byte| format!("{byte:02x}"))
+        .collect()
+}
+
+fn write_canonical(value: &Value, output: &mut String) {
+    match value {
+        Value::Object(map) => {
+            let mut entries: Vec<(&String, &Value)> = map.iter().collect();
+            entries.sort_by(|(a, _), (b, _)| a.cmp(b));
+            output.push('{');
+            for (index, (key, value)) in entries.into_iter().enumerate() {
+                if index > 0 {
+                    output.push(',');
+                }
+                output.push_str(&Value::String(key.clone()).to_string());
+                output.push(':');
+                write_canonical(value, output);
+            }
+            output.push('}');
+        }
+        Value::Array(items) => {
+            output.push('[');
+            for (index, item) in items.iter().enumerate() {
+                if index > 0 {
+                    output.push(',');
+                }
+                write_canonical(item, output);
+            }
+            output.push(']');
+        }
+        Value::Number(number) => output.push_str(&canonical_number(number)),
+        other => output.push_str(&other.to_string()),
+    }
+}
+
+fn canonical_number(number: &Number) -> String {
+    if number.is_i64() || number.is_u64() {
+        return number.to_string();
+    }
+    let Some(float) = number.as_f64() else {
+        return number.to_string();
+    };
+    if float.fract() == 0.0 {
+        // In-range integral floats convert exactly, so `1.0` matches `1`
+        if (0.0..U64_LIMIT).contains(&float) {
+            return (float as u64).to_string();
+        }
+        if (i64::MIN as f64..0.0).contains(&float) {
+            return (float as i64).to_string();
+        }
+    }
+    // serde_json formats floats with the shortest round-tripping digits
+    number.to_string()
+}
@@ -0,0 +1,70 @@
+//! Tests for canonical JSON serialization
+
+use crate::canonical::*;
+use serde_json::{Value, json};
+
+#[test]
+fn test_key_order_does_not_matter() {
+    let a: Value = serde_json::from_str(
+        r#"{"query": "rust", "limit": 10, "filters": {"lang": "en", "tags": ["a", "b"]}}"#,
+    )
+    .unwrap();
+    let b: Value = serde_json::from_str(
+        r#"{ "filters" : { "tags" : ["a","b"], "lang":"en" },
+            "limit":10,"query":"rust" }"#,
+    )
+    .unwrap();
+
+    assert_eq!(canonicalize_json(&a), canonicalize_json(&b));
+    assert_eq!(canonical_hash(&a), canonical_hash(&b));
+    assert_eq!(
+        canonicalize_json(&a),
+        r#"{"filters":{"lang":"en","tags":["a","b"]},"limit":10,"query":"rust"}"#
+    );
+}
+
+#[test]
+fn test_array_order_is_significant() {
+    assert_ne!(
+        canonicalize_json(&json!({"tags": ["a", "b"]})),
+        canonicalize_json(&json!({"tags": ["b", "a"]}))
+    );
+}
+
+#[test]
+fn test_numbers_are_normalized() {
+    assert_eq!(canonicalize_json(&json!(1.0)), "1");
+    assert_eq!(canonicalize_json(&json!(-0.0)), "0");
+    assert_eq!(canonicalize_json(&json!(-3.0)), "-3");
+    assert_eq!(canonicalize_json(&json!(1e17)), "100000000000000000");
+    assert_eq!(
+        canonicalize_json(&json!({"n": 2.0})),
+        canonicalize_json(&json!({"n": 2}))
+    );
+
+    // Fractional and out-of-range values keep their shortest float form
+    assert_eq!(canonicalize_json(&json!(0.1)), "0.1");
+    assert_eq!(canonicalize_json(&json!(1.5e-7)), "1.5e-7");
+    assert_eq!(canonicalize_json(&json!(1e300)), "1e300");
+    assert_eq!(canonicalize_json(&json!(u64::MAX)), u64::MAX.to_string());
+    assert_eq!(canonicalize_json(&json!(i64::MIN)), i64::MIN.to_string());
+
+    let parsed: Value = serde_json::from_str("[1.10, 1.1000, 12e-1]").unwrap();
+    assert_eq!(canonicalize_json(&parsed), "[1.1,1.1,1.2]");
+}
+
+#[test]
+fn test_strings_and_keys_are_escaped() {
+    let value = json!({"quo\"te": "line\nbreak", "ünï": null});
+    assert_eq!(
+        canonicalize_json(&value),
+        r#"{"quo\"te":"line\nbreak","ünï":null}"#
+    );
+}
+
+#[test]
+fn test_hash_distinguishes_values() {
+    let hash = canonical_hash(&json!({"a": 1}));
+    assert_eq!(hash.len(), 64);
+    assert_ne!(hash, canonical_hash(&json!({"a": 2})));
+}
@@ -0,0 +1,130 @@
+//! Content encoding for large resource payloads
+//!
+//! Large text resources (most notably `ui://` HTML resources from the MCP Apps
+//! Extension) can be pre-compressed by the server before they go over the wire.
+//! Compressed contents carry the gzip bytes base64-encoded in `blob` and are
+//! marked with `_meta.contentEncoding`, so clients that understand the hint can
+//! restore the original text with [`ResourceContents::decode`].
+//!
+//! Clients opt in per request by sending `_meta.acceptEncoding` on
+//! `resources/read`; clients that don't are always served uncompressed text.
+
+use crate::model::{Meta, ResourceContents};
+use crate::{Error, Result};
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD;
+use flate2::Compression;
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+use std::io::{Read, Write};
+
+/// Content encoding identifier for gzip-compressed payloads
+pub const GZIP: &str = "gzip";
+
+/// Check whether a request `_meta.acceptEncoding` value advertises `encoding`
+///
+/// Accepts either a single string (`"gzip"`) or an array of strings
+/// (`["gzip", "identity"]`), matching case-insensitively.
+pub fn accepts_encoding(accept_encoding: &serde_json::Value, encoding: &str) -> bool {
+    match accept_encoding {
+        serde_json::Value::String(s) => s
+            .split(',')
+            .any(|e| e.trim().eq_ignore_ascii_case(encoding)),
+        serde_json::Value::Array(values) => values
+            .iter()
+            .filter_map(|v| v.as_str())
+            .any(|e| e.trim().eq_ignore_ascii_case(encoding)),
+        _ => false,
+    }
+}
+
+impl ResourceContents {
+    /// Get the content encoding hint, if any
+    pub fn content_encoding(&self) -> Option<&str> {
+        self._meta
+            .as_ref()
+            .and_then(|m| m.content_encoding.as_deref())
+    }
+
+    /// Check if these contents carry an encoded (compressed) payload
+    pub fn is_encoded(&self) -> bool {
+        self.content_encoding().is_some()
+    }
+
+    /// Compress text contents with gzip
+    ///
+    /// The compressed bytes are base64-encoded into `blob`, `text` is cleared and
+    /// `_meta.contentEncoding` is set to `gzip`. The MIME type is preserved so the
+    /// client knows what the decoded payload is. Contents without text, or that are
+    /// already encoded, are returned unchanged.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if compression fails
+    pub fn gzip(&self, level: u32) -> Result<Self> {
+        let Some(text) = self.text.as_ref().filter(|_| !self.is_encoded()) else {
+            return Ok(self.clone());
+        };
+
+        let mut encoder = GzEncoder::new(Vec::new(), Compression::new(level.min(9)));
+        encoder
+            .write_all(text.as_bytes())
+            .and_then(|_| encoder.finish())
+            .map(|compressed| {
+                let mut meta = self._meta.clone().unwrap_or_default();
+                meta.content_encoding = Some(GZIP.to_string());
+                Self {
+                    uri: self.uri.clone(),
+                    mime_type: self.mime_type.clone(),
+                    text: None,
+                    blob: Some(STANDARD.encode(compressed)),
+                    _meta: Some(meta),
+                }
+            })
+            .map_err(|e| Error::internal_error(format!("Failed to compress resource: {e}")))
+    }
+
+    /// Decode encoded contents back into plain text
+    ///
+    /// Contents without a content encoding are returned unchanged. Other
+    /// metadata is kept; `_meta` is dropped only when nothing else is left.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the encoding is unknown or the payload is corrupt
+    pub fn decode(&self) -> Result<Self> {
+        match self.content_encoding() {
+            None => Ok(self.clone()),
+            Some(encoding) if encoding.eq_ignore_ascii_case(GZIP) => {
+                let blob = self.blob.as_deref().ok_or_else(|| {
+                    Error::invalid_params("Encoded resource contents are missing a blob")
+                })?;
+                let compressed = STANDARD.decode(blob).map_err(|e| {
+                    Error::invalid_params(format!("Invalid base64 in encoded resource: {e}"))
+                })?;
+
+                let mut text = String::new();
+                GzDecoder::new(compressed.as_slice())
+                    .read_to_string(&mut text)
+                    .map_err(|e| {
+                        Error::invalid_params(format!("Failed to decompress resource: {e}"))
+                    })?;
+
+                let meta = self._meta.clone().map(|m| Meta {
+                    content_encoding: None,
+                    ..m
+                });
+                Ok(Self {
+                    uri: self.uri.clone(),
+                    mime_type: self.mime_type.clone(),
+                    text: Some(text),
+                    blob: None,
+                    _meta: meta.filter(|m| !m.is_empty()),
+                })
+            }
+            Some(other) => Err(Error::invalid_params(format!(
+                "Unsupported content encoding: {other}"
+            ))),
+        }
+    }
+}
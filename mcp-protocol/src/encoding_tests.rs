@@ -0,0 +1,129 @@
+//! Tests for resource content encoding
+
+use crate::encoding::*;
+use crate::*;
+use serde_json::json;
+
+fn large_html() -> String {
+    let rows: String = (0..500)
+        .map(|i| format!("<tr><td>Row {i}</td><td>value</td></tr>"))
+        .collect();
+    format!("<!DOCTYPE html><html><body><table>{rows}</table></body></html>")
+}
+
+#[test]
+fn test_gzip_round_trip_preserves_other_meta() {
+    let mut meta = Meta {
+        content_type: Some("text/markdown".to_string()),
+        etag: Some("\"v2\"".to_string()),
+        ..Default::default()
+    };
+    meta.extra
+        .insert("provenance".to_string(), json!({"source": "cache"}));
+    let contents = ResourceContents {
+        _meta: Some(meta),
+        ..ResourceContents::text("file://notes.md", "# Notes\n".repeat(100))
+    };
+
+    let decoded = contents.gzip(6).unwrap().decode().unwrap();
+    assert_eq!(decoded.text, contents.text);
+    let meta = decoded._meta.unwrap();
+    assert!(meta.content_encoding.is_none());
+    assert_eq!(meta.content_type.as_deref(), Some("text/markdown"));
+    assert_eq!(meta.etag.as_deref(), Some("\"v2\""));
+    assert_eq!(meta.extra["provenance"], json!({"source": "cache"}));
+}
+
+#[test]
+fn test_gzip_round_trip_preserves_html() {
+    let html = large_html();
+    let contents = ResourceContents::html_ui("ui://dashboard", html.clone());
+
+    let compressed = contents.gzip(6).unwrap();
+    assert!(compressed.is_encoded());
+    assert_eq!(compressed.content_encoding(), Some(GZIP));
+    assert!(compressed.text.is_none());
+    assert_eq!(compressed.mime_type.as_deref(), Some(mime_types::HTML_MCP));
+    assert!(compressed.blob.as_ref().unwrap().len() < html.len());
+
+    let decoded = compressed.decode().unwrap();
+    assert!(!decoded.is_encoded());
+    assert_eq!(decoded.text.as_deref(), Some(html.as_str()));
+    assert!(decoded.blob.is_none());
+    assert!(decoded._meta.is_none());
+}
+
+#[test]
+fn test_gzip_wire_format() {
+    let compressed = ResourceContents::text("file://a.txt", "hello".repeat(100))
+        .gzip(9)
+        .unwrap();
+    let value = serde_json::to_value(&compressed).unwrap();
+
+    assert_eq!(value["_meta"]["contentEncoding"], "gzip");
+    assert!(value.get("text").is_none());
+    assert!(value["blob"].is_string());
+
+    // A client deserializing the wire payload can decode it
+    let parsed: ResourceContents = serde_json::from_value(value).unwrap();
+    assert_eq!(parsed.decode().unwrap().text.unwrap(), "hello".repeat(100));
+}
+
+#[test]
+fn test_gzip_skips_blob_and_encoded_contents() {
+    let blob = ResourceContents {
+        uri: "file://image.png".to_string(),
+        mime_type: Some("image/png".to_string()),
+        text: None,
+        blob: Some("aGVsbG8=".to_string()),
+        _meta: None,
+    };
+    let unchanged = blob.gzip(6).unwrap();
+    assert!(!unchanged.is_encoded());
+    assert_eq!(unchanged.blob, blob.blob);
+
+    let once = ResourceContents::text("file://a.txt", "abc")
+        .gzip(6)
+        .unwrap();
+    let twice = once.gzip(6).unwrap();
+    assert_eq!(once.blob, twice.blob);
+}
+
+#[test]
+fn test_decode_plain_contents_is_noop() {
+    let contents = ResourceContents::text("file://a.txt", "plain");
+    let decoded = contents.decode().unwrap();
+    assert_eq!(decoded.text.as_deref(), Some("plain"));
+}
+
+#[test]
+fn test_decode_rejects_unknown_and_corrupt_payloads() {
+    let mut contents = ResourceContents::text("file://a.txt", "plain")
+        .gzip(6)
+        .unwrap();
+    contents._meta.as_mut().unwrap().content_encoding = Some("br".to_string());
+    assert!(contents.decode().is_err());
+
+    let corrupt = ResourceContents {
+        uri: "file://a.txt".to_string(),
+        mime_type: None,
+        text: None,
+        blob: Some("bm90IGd6aXA=".to_string()),
+        _meta: Some(Meta {
+            content_encoding: Some(GZIP.to_string()),
+            ..Default::default()
+        }),
+    };
+    let err = corrupt.decode().unwrap_err();
+    assert_eq!(err.code, ErrorCode::InvalidParams);
+}
+
+#[test]
+fn test_accepts_encoding() {
+    assert!(accepts_encoding(&json!("gzip"), GZIP));
+    assert!(accepts_encoding(&json!("identity, GZIP"), GZIP));
+    assert!(accepts_encoding(&json!(["br", "gzip"]), GZIP));
+    assert!(!accepts_encoding(&json!(["br"]), GZIP));
+    assert!(!accepts_encoding(&json!(true), GZIP));
+    assert!(!accepts_encoding(&serde_json::Value::Null, GZIP));
+}
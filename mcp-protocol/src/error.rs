@@ -124,6 +124,17 @@ impl Error {
         Self::new(ErrorCode::RateLimitExceeded, message)
     }
 
+    /// Create a server busy error, for requests shed while the server is
+    /// overloaded or degraded
+    pub fn server_busy(message: impl Into<String>) -> Self {
+        Self::new(ErrorCode::ServerBusy, message)
+    }
+
+    /// Create an invalid pagination cursor error
+    pub fn invalid_cursor(message: impl Into<String>) -> Self {
+        Self::new(ErrorCode::InvalidCursor, message)
+    }
+
     /// Create a URL elicitation required error (MCP 2025-11-25)
     ///
     /// This error indicates that a request requires URL mode elicitation
@@ -160,6 +171,25 @@ pub enum ErrorCode {
     ToolNotFound = -32003,
     ValidationError = -32004,
     RateLimitExceeded = -32005,
+    /// Pagination cursor was tampered with, malformed or issued by another
+    /// server generation
+    InvalidCursor = -32006,
+    /// Server is shedding load; retry later (the JSON-RPC analogue of 503)
+    ServerBusy = -32007,
+
+    // Authentication errors, telling clients why no credential was accepted
+    /// No credentials were presented
+    AuthenticationRequired = -32008,
+    /// A header required for authentication is missing
+    MissingAuthHeader = -32009,
+    /// The authentication header can't be parsed
+    MalformedAuthHeader = -32010,
+    /// The authentication scheme isn't supported
+    UnsupportedAuthScheme = -32011,
+    /// The credential is present but empty
+    EmptyCredential = -32012,
+    /// The credential doesn't have the expected format
+    InvalidCredentialFormat = -32013,
 
     // MCP 2025-11-25 errors
     /// URL elicitation required before request can proceed
@@ -193,6 +223,14 @@ impl<'de> Deserialize<'de> for ErrorCode {
             -32003 => Ok(ErrorCode::ToolNotFound),
             -32004 => Ok(ErrorCode::ValidationError),
             -32005 => Ok(ErrorCode::RateLimitExceeded),
+            -32006 => Ok(ErrorCode::InvalidCursor),
+            -32007 => Ok(ErrorCode::ServerBusy),
+            -32008 => Ok(ErrorCode::AuthenticationRequired),
+            -32009 => Ok(ErrorCode::MissingAuthHeader),
+            -32010 => Ok(ErrorCode::MalformedAuthHeader),
+            -32011 => Ok(ErrorCode::UnsupportedAuthScheme),
+            -32012 => Ok(ErrorCode::EmptyCredential),
+            -32013 => Ok(ErrorCode::InvalidCredentialFormat),
             -32042 => Ok(ErrorCode::UrlElicitationRequired),
             _ => Err(serde::de::Error::custom(format!(
                 "Unknown error code: {code}"
@@ -215,6 +253,14 @@ impl fmt::Display for ErrorCode {
             ErrorCode::ToolNotFound => "ToolNotFound",
             ErrorCode::ValidationError => "ValidationError",
             ErrorCode::RateLimitExceeded => "RateLimitExceeded",
+            ErrorCode::InvalidCursor => "InvalidCursor",
+            ErrorCode::ServerBusy => "ServerBusy",
+            ErrorCode::AuthenticationRequired => "AuthenticationRequired",
+            ErrorCode::MissingAuthHeader => "MissingAuthHeader",
+            ErrorCode::MalformedAuthHeader => "MalformedAuthHeader",
+            ErrorCode::UnsupportedAuthScheme => "UnsupportedAuthScheme",
+            ErrorCode::EmptyCredential => "EmptyCredential",
+            ErrorCode::InvalidCredentialFormat => "InvalidCredentialFormat",
             ErrorCode::UrlElicitationRequired => "UrlElicitationRequired",
         };
         write!(f, "{name}")
@@ -276,6 +322,14 @@ impl pulseengine_logging::ErrorClassification for Error {
             ErrorCode::ToolNotFound => "tool_not_found",
             ErrorCode::ValidationError => "validation_error",
             ErrorCode::RateLimitExceeded => "rate_limit_exceeded",
+            ErrorCode::InvalidCursor => "invalid_cursor",
+            ErrorCode::ServerBusy => "server_busy",
+            ErrorCode::AuthenticationRequired => "authentication_required",
+            ErrorCode::MissingAuthHeader => "missing_auth_header",
+            ErrorCode::MalformedAuthHeader => "malformed_auth_header",
+            ErrorCode::UnsupportedAuthScheme => "unsupported_auth_scheme",
+            ErrorCode::EmptyCredential => "empty_credential",
+            ErrorCode::InvalidCredentialFormat => "invalid_credential_format",
             ErrorCode::UrlElicitationRequired => "url_elicitation_required",
         }
     }
@@ -286,6 +340,7 @@ impl pulseengine_logging::ErrorClassification for Error {
             self.code,
             ErrorCode::InternalError
                 | ErrorCode::RateLimitExceeded
+                | ErrorCode::ServerBusy
                 | ErrorCode::UrlElicitationRequired
         )
     }
@@ -295,7 +350,17 @@ impl pulseengine_logging::ErrorClassification for Error {
     }
 
     fn is_auth_error(&self) -> bool {
-        matches!(self.code, ErrorCode::Unauthorized | ErrorCode::Forbidden)
+        matches!(
+            self.code,
+            ErrorCode::Unauthorized
+                | ErrorCode::Forbidden
+                | ErrorCode::AuthenticationRequired
+                | ErrorCode::MissingAuthHeader
+                | ErrorCode::MalformedAuthHeader
+                | ErrorCode::UnsupportedAuthScheme
+                | ErrorCode::EmptyCredential
+                | ErrorCode::InvalidCredentialFormat
+        )
     }
 
     fn is_connection_error(&self) -> bool {
@@ -46,6 +46,7 @@
 //!         "temperature": "22°C",
 //!         "condition": "sunny"
 //!     })),
+//!     structured_content_blocks: None,
 //!     _meta: None,
 //! };
 //! ```
@@ -53,12 +54,21 @@
 //! This crate is currently used in production by the Loxone MCP Server
 //! for home automation with 30+ tools.
 
+pub mod canonical;
+pub mod encoding;
 pub mod error;
 pub mod errors;
 pub mod model;
+pub mod patch;
+pub mod signing;
+pub mod timestamp;
 pub mod ui;
 pub mod validation;
 
+#[cfg(test)]
+mod canonical_tests;
+#[cfg(test)]
+mod encoding_tests;
 #[cfg(test)]
 mod error_tests;
 #[cfg(test)]
@@ -66,16 +76,24 @@ mod lib_tests;
 #[cfg(test)]
 mod model_tests;
 #[cfg(test)]
+mod patch_tests;
+#[cfg(test)]
+mod signing_tests;
+#[cfg(test)]
+mod timestamp_tests;
+#[cfg(test)]
 mod ui_tests;
 #[cfg(test)]
 mod validation_tests;
 
 // Re-export core types for easy access
+pub use canonical::{canonical_hash, canonicalize_json};
 pub use error::{Error, ErrorCode, McpResult, Result};
 pub use errors::{CommonError, CommonResult};
 pub use model::*;
+pub use timestamp::TimestampFormat;
 pub use ui::*;
-pub use validation::Validator;
+pub use validation::{StructuredContentLimits, Validator};
 
 /// Protocol version constants
 pub const MCP_VERSION: &str = "2025-11-25";
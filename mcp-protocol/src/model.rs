@@ -19,6 +19,9 @@ pub mod mime_types {
     /// Plain text
     pub const TEXT: &str = "text/plain";
 
+    /// Markdown text
+    pub const MARKDOWN: &str = "text/markdown";
+
     /// Binary blob
     pub const OCTET_STREAM: &str = "application/octet-stream";
 }
@@ -39,11 +42,51 @@ pub mod uri_schemes {
 }
 
 /// Metadata for MCP protocol messages (MCP 2025-06-18)
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
 pub struct Meta {
     /// Progress token for tracking long-running operations
     #[serde(rename = "progressToken", skip_serializing_if = "Option::is_none")]
     pub progress_token: Option<String>,
+    /// Encoding applied to the payload (e.g. `gzip` for compressed resource contents)
+    #[serde(
+        rename = "contentEncoding",
+        default,
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub content_encoding: Option<String>,
+    /// Format of text content (e.g. `text/markdown`); `text/plain` when unset
+    #[serde(
+        rename = "contentType",
+        default,
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub content_type: Option<String>,
+    /// Entity tag identifying a resource version, for conditional reads
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub etag: Option<String>,
+    /// Set on conditional read results whose contents were omitted because
+    /// the client's cached version is current
+    #[serde(
+        rename = "notModified",
+        default,
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub not_modified: Option<bool>,
+    /// Additional entries, such as provenance attached by result transforms
+    #[serde(flatten)]
+    pub extra: HashMap<String, serde_json::Value>,
+}
+
+impl Meta {
+    /// Check whether no metadata is set, i.e. it serializes to `{}`
+    pub fn is_empty(&self) -> bool {
+        self.progress_token.is_none()
+            && self.content_encoding.is_none()
+            && self.content_type.is_none()
+            && self.etag.is_none()
+            && self.not_modified.is_none()
+            && self.extra.is_empty()
+    }
 }
 
 /// A flexible identifier type for JSON-RPC request IDs
@@ -247,9 +290,16 @@ pub struct ServerCapabilities {
     pub sampling: Option<SamplingCapability>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub elicitation: Option<ElicitationCapability>,
+    /// Argument autocompletion via `completion/complete`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub completions: Option<CompletionsCapability>,
     /// Tasks capability (MCP 2025-11-25 experimental)
     #[serde(skip_serializing_if = "Option::is_none")]
     pub tasks: Option<TasksCapability>,
+    /// Optional, non-standard features the server supports, e.g.
+    /// `{"streaming": true, "resource_diffs": true}`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub experimental: Option<HashMap<String, serde_json::Value>>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
@@ -272,6 +322,10 @@ pub struct PromptsCapability {
     pub list_changed: Option<bool>,
 }
 
+/// Completions capability: the server answers `completion/complete`
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct CompletionsCapability {}
+
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
 pub struct LoggingCapability {
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -377,9 +431,24 @@ impl ServerCapabilities {
     pub fn builder() -> ServerCapabilitiesBuilder {
         ServerCapabilitiesBuilder::default()
     }
+
+    /// Get an experimental feature flag advertised by the server
+    pub fn experimental_feature(&self, name: &str) -> Option<&serde_json::Value> {
+        self.experimental.as_ref()?.get(name)
+    }
+
+    /// Whether the server advertises an experimental feature as enabled
+    ///
+    /// A feature is enabled when its flag is `true` or a configuration object.
+    pub fn supports_experimental(&self, name: &str) -> bool {
+        matches!(
+            self.experimental_feature(name),
+            Some(serde_json::Value::Bool(true) | serde_json::Value::Object(_))
+        )
+    }
 }
 
-#[derive(Default)]
+#[derive(Debug, Default)]
 pub struct ServerCapabilitiesBuilder {
     capabilities: ServerCapabilities,
 }
@@ -462,6 +531,13 @@ impl ServerCapabilitiesBuilder {
         self
     }
 
+    /// Advertise argument autocompletion for prompts and resource templates
+    #[must_use]
+    pub fn enable_completions(mut self) -> Self {
+        self.capabilities.completions = Some(CompletionsCapability {});
+        self
+    }
+
     /// Enable tasks capability (MCP 2025-11-25 experimental)
     #[must_use]
     pub fn enable_tasks(mut self) -> Self {
@@ -494,6 +570,28 @@ impl ServerCapabilitiesBuilder {
         self
     }
 
+    /// Advertise an experimental feature flag
+    ///
+    /// Fails if `value` can't be represented as JSON, e.g. a map with
+    /// non-string keys.
+    pub fn experimental(
+        mut self,
+        name: impl Into<String>,
+        value: impl Serialize,
+    ) -> Result<Self, Error> {
+        let name = name.into();
+        let value = serde_json::to_value(value).map_err(|e| {
+            Error::invalid_params(format!(
+                "Experimental capability '{name}' is not JSON-serializable: {e}"
+            ))
+        })?;
+        self.capabilities
+            .experimental
+            .get_or_insert_with(HashMap::new)
+            .insert(name, value);
+        Ok(self)
+    }
+
     pub fn build(self) -> ServerCapabilities {
         self.capabilities
     }
@@ -559,6 +657,12 @@ pub struct ToolMeta {
     /// Example: `"ui://charts/bar-chart"`
     #[serde(rename = "ui/resourceUri", skip_serializing_if = "Option::is_none")]
     pub ui_resource_uri: Option<String>,
+
+    /// Deprecation notice for a tool being phased out, e.g. `"Use new_tool instead"`
+    ///
+    /// Deprecated tools keep working; clients should steer users elsewhere.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub deprecated: Option<String>,
 }
 
 impl ToolMeta {
@@ -566,6 +670,15 @@ impl ToolMeta {
     pub fn with_ui_resource(uri: impl Into<String>) -> Self {
         Self {
             ui_resource_uri: Some(uri.into()),
+            ..Default::default()
+        }
+    }
+
+    /// Create tool metadata marking the tool deprecated
+    pub fn with_deprecation(message: impl Into<String>) -> Self {
+        Self {
+            deprecated: Some(message.into()),
+            ..Default::default()
         }
     }
 }
@@ -593,6 +706,97 @@ pub struct PaginatedRequestParam {
     pub cursor: Option<String>,
 }
 
+/// Opaque, signed pagination cursor
+///
+/// Encodes an offset into a listing together with a generation id derived
+/// from the server's key material, authenticated with HMAC-SHA256 and
+/// base64url-encoded. Backends hand the token out as `next_cursor` and decode
+/// the `cursor` clients send back, instead of inventing their own format.
+/// Changing the key material (e.g. mixing in the server version) invalidates
+/// all outstanding cursors, which are then reported as stale.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Cursor {
+    /// Position in the listing where the next page starts
+    pub offset: usize,
+    /// Generation of the key material the cursor was issued under
+    pub generation: u64,
+}
+
+impl Cursor {
+    const VERSION: u8 = 1;
+    const PAYLOAD_LEN: usize = 17;
+    const TOKEN_LEN: usize = Self::PAYLOAD_LEN + 32;
+
+    /// Encode a signed cursor pointing at `offset`
+    pub fn encode(offset: usize, key_material: &[u8]) -> String {
+        use base64::Engine;
+        use hmac::Mac;
+
+        let mut token = Vec::with_capacity(Self::TOKEN_LEN);
+        token.push(Self::VERSION);
+        token.extend_from_slice(&Self::generation_of(key_material).to_be_bytes());
+        token.extend_from_slice(&(offset as u64).to_be_bytes());
+        let signature = Self::mac(key_material, &token).finalize().into_bytes();
+        token.extend_from_slice(&signature);
+        base64::engine::general_purpose::URL_SAFE_NO_PAD.encode(token)
+    }
+
+    /// Decode and verify a cursor issued with the same key material
+    ///
+    /// # Errors
+    ///
+    /// Returns an [`ErrorCode::InvalidCursor`](crate::ErrorCode::InvalidCursor)
+    /// error if the token is malformed, was issued under different key
+    /// material (stale), or fails signature verification (tampered)
+    pub fn decode(token: &str, key_material: &[u8]) -> Result<Self, Error> {
+        use base64::Engine;
+        use hmac::Mac;
+
+        let bytes = base64::engine::general_purpose::URL_SAFE_NO_PAD
+            .decode(token)
+            .ok()
+            .filter(|b| b.len() == Self::TOKEN_LEN && b[0] == Self::VERSION)
+            .ok_or_else(|| Error::invalid_cursor("Malformed pagination cursor"))?;
+        let (payload, signature) = bytes.split_at(Self::PAYLOAD_LEN);
+
+        let generation = u64::from_be_bytes(payload[1..9].try_into().expect("8 bytes"));
+        if generation != Self::generation_of(key_material) {
+            return Err(Error::invalid_cursor(
+                "Pagination cursor is stale; restart the listing without a cursor",
+            ));
+        }
+        Self::mac(key_material, payload)
+            .verify_slice(signature)
+            .map_err(|_| Error::invalid_cursor("Pagination cursor signature mismatch"))?;
+
+        let offset = u64::from_be_bytes(payload[9..17].try_into().expect("8 bytes"));
+        Ok(Self {
+            offset: usize::try_from(offset)
+                .map_err(|_| Error::invalid_cursor("Pagination cursor offset out of range"))?,
+            generation,
+        })
+    }
+
+    /// Generation id for key material, derived so it reveals nothing about the key
+    fn generation_of(key_material: &[u8]) -> u64 {
+        use hmac::Mac;
+
+        let digest = Self::mac(key_material, b"mcp-cursor-generation")
+            .finalize()
+            .into_bytes();
+        u64::from_be_bytes(digest[..8].try_into().expect("8 bytes"))
+    }
+
+    fn mac(key_material: &[u8], data: &[u8]) -> hmac::Hmac<sha2::Sha256> {
+        use hmac::Mac;
+
+        let mut mac = hmac::Hmac::<sha2::Sha256>::new_from_slice(key_material)
+            .expect("HMAC accepts keys of any length");
+        mac.update(data);
+        mac
+    }
+}
+
 /// Tool call parameters
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CallToolRequestParam {
@@ -686,6 +890,39 @@ impl Content {
         }
     }
 
+    /// Create text content with a format hint, e.g. `text/markdown`
+    pub fn text_with_format(text: impl Into<String>, content_type: impl Into<String>) -> Self {
+        Self::text(text).with_text_format(content_type)
+    }
+
+    /// Create markdown text content
+    pub fn markdown(text: impl Into<String>) -> Self {
+        Self::text_with_format(text, mime_types::MARKDOWN)
+    }
+
+    /// Set the format hint of text content; other content is returned unchanged
+    pub fn with_text_format(mut self, content_type: impl Into<String>) -> Self {
+        if let Self::Text { _meta, .. } = &mut self {
+            _meta.get_or_insert_with(Meta::default).content_type = Some(content_type.into());
+        }
+        self
+    }
+
+    /// Format of text content, `text/plain` unless hinted otherwise
+    ///
+    /// Returns `None` for content that isn't text.
+    pub fn text_format(&self) -> Option<&str> {
+        match self {
+            Self::Text { _meta, .. } => Some(
+                _meta
+                    .as_ref()
+                    .and_then(|m| m.content_type.as_deref())
+                    .unwrap_or(mime_types::TEXT),
+            ),
+            _ => None,
+        }
+    }
+
     pub fn image(data: impl Into<String>, mime_type: impl Into<String>) -> Self {
         Self::Image {
             data: data.into(),
@@ -872,16 +1109,30 @@ pub struct CallToolResult {
     pub is_error: Option<bool>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub structured_content: Option<serde_json::Value>,
+    /// Alternative named representations of the structured output, e.g. a
+    /// table and a chart spec, for clients that can pick between them
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub structured_content_blocks: Option<Vec<StructuredContentBlock>>,
     #[serde(rename = "_meta", skip_serializing_if = "Option::is_none")]
     pub _meta: Option<Meta>,
 }
 
+/// A named representation of a tool's structured output
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct StructuredContentBlock {
+    /// Name of the representation, e.g. `table` or `vega-lite`
+    pub name: String,
+    /// The structured data in this representation
+    pub content: serde_json::Value,
+}
+
 impl CallToolResult {
     pub fn success(content: Vec<Content>) -> Self {
         Self {
             content,
             is_error: Some(false),
             structured_content: None,
+            structured_content_blocks: None,
             _meta: None,
         }
     }
@@ -891,6 +1142,7 @@ impl CallToolResult {
             content,
             is_error: Some(true),
             structured_content: None,
+            structured_content_blocks: None,
             _meta: None,
         }
     }
@@ -903,6 +1155,38 @@ impl CallToolResult {
         Self::error(vec![Content::text(text)])
     }
 
+    /// Build a result up content item by content item
+    ///
+    /// # Example
+    /// ```rust
+    /// use pulseengine_mcp_protocol::CallToolResult;
+    /// use serde_json::json;
+    ///
+    /// let result = CallToolResult::builder()
+    ///     .text("Current weather: 22°C, sunny")
+    ///     .structured(json!({"temperature": 22}))
+    ///     .build();
+    /// assert_eq!(result.is_error, Some(false));
+    /// ```
+    pub fn builder() -> CallToolResultBuilder {
+        CallToolResultBuilder::default()
+    }
+
+    /// Hint the format of every text content item that doesn't declare one
+    ///
+    /// Used for tools whose text output is always e.g. markdown.
+    pub fn with_text_format(mut self, content_type: &str) -> Self {
+        for content in &mut self.content {
+            if let Content::Text { _meta, .. } = content
+                && _meta.as_ref().is_none_or(|m| m.content_type.is_none())
+            {
+                _meta.get_or_insert_with(Meta::default).content_type =
+                    Some(content_type.to_string());
+            }
+        }
+        self
+    }
+
     /// Create an input validation error result (MCP 2025-11-25)
     ///
     /// Per the MCP 2025-11-25 spec, input validation errors should be returned
@@ -934,6 +1218,7 @@ impl CallToolResult {
             content,
             is_error: Some(false),
             structured_content: Some(structured_content),
+            structured_content_blocks: None,
             _meta: None,
         }
     }
@@ -944,6 +1229,7 @@ impl CallToolResult {
             content,
             is_error: Some(true),
             structured_content: Some(structured_content),
+            structured_content_blocks: None,
             _meta: None,
         }
     }
@@ -956,6 +1242,36 @@ impl CallToolResult {
         Self::structured(vec![Content::text(text)], structured_content)
     }
 
+    /// Add a named representation of the structured output
+    ///
+    /// The first block also fills `structured_content` when it is unset, so
+    /// clients that only understand the single-field form still get output.
+    pub fn with_structured_block(
+        mut self,
+        name: impl Into<String>,
+        content: serde_json::Value,
+    ) -> Self {
+        if self.structured_content.is_none() {
+            self.structured_content = Some(content.clone());
+        }
+        self.structured_content_blocks
+            .get_or_insert_with(Vec::new)
+            .push(StructuredContentBlock {
+                name: name.into(),
+                content,
+            });
+        self
+    }
+
+    /// Get a named representation of the structured output
+    pub fn structured_block(&self, name: &str) -> Option<&serde_json::Value> {
+        self.structured_content_blocks
+            .as_ref()?
+            .iter()
+            .find(|block| block.name == name)
+            .map(|block| &block.content)
+    }
+
     /// Validate structured content against a schema
     ///
     /// # Errors
@@ -972,6 +1288,108 @@ impl CallToolResult {
         }
         Ok(())
     }
+
+    /// Validate structured content against a schema within `limits`
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the structured content exceeds the limits or doesn't
+    /// match the provided schema
+    pub fn validate_structured_content_with_limits(
+        &self,
+        output_schema: &serde_json::Value,
+        limits: &crate::validation::StructuredContentLimits,
+    ) -> crate::Result<()> {
+        use crate::validation::Validator;
+
+        if let Some(structured_content) = &self.structured_content {
+            Validator::validate_structured_content_with_limits(
+                structured_content,
+                output_schema,
+                limits,
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// Builder for [`CallToolResult`]
+///
+/// The result is marked as an error as soon as [`error`](Self::error) is
+/// called; otherwise it is a success.
+#[derive(Debug, Default)]
+pub struct CallToolResultBuilder {
+    content: Vec<Content>,
+    is_error: bool,
+    structured_content: Option<serde_json::Value>,
+    _meta: Option<Meta>,
+}
+
+impl CallToolResultBuilder {
+    /// Add text content
+    #[must_use]
+    pub fn text(mut self, text: impl Into<String>) -> Self {
+        self.content.push(Content::text(text));
+        self
+    }
+
+    /// Add a JSON value as pretty-printed text content hinted as `application/json`
+    #[must_use]
+    pub fn json(mut self, value: &serde_json::Value) -> Self {
+        let text = serde_json::to_string_pretty(value).unwrap_or_else(|_| value.to_string());
+        self.content
+            .push(Content::text_with_format(text, mime_types::JSON));
+        self
+    }
+
+    /// Add image content, base64-encoding the raw bytes
+    #[must_use]
+    pub fn image(mut self, bytes: impl AsRef<[u8]>, mime_type: impl Into<String>) -> Self {
+        use base64::Engine;
+
+        let data = base64::engine::general_purpose::STANDARD.encode(bytes);
+        self.content.push(Content::image(data, mime_type));
+        self
+    }
+
+    /// Add a UI HTML resource (MCP Apps Extension / MCP-UI)
+    #[must_use]
+    pub fn ui_html(mut self, uri: impl Into<String>, html: impl Into<String>) -> Self {
+        self.content.push(Content::ui_html(uri, html));
+        self
+    }
+
+    /// Add an error message and mark the result as an error
+    #[must_use]
+    pub fn error(mut self, message: impl Into<String>) -> Self {
+        self.content.push(Content::text(message));
+        self.is_error = true;
+        self
+    }
+
+    /// Set the structured content
+    #[must_use]
+    pub fn structured(mut self, value: serde_json::Value) -> Self {
+        self.structured_content = Some(value);
+        self
+    }
+
+    /// Set the result metadata
+    #[must_use]
+    pub fn meta(mut self, meta: Meta) -> Self {
+        self._meta = Some(meta);
+        self
+    }
+
+    pub fn build(self) -> CallToolResult {
+        CallToolResult {
+            content: self.content,
+            is_error: Some(self.is_error),
+            structured_content: self.structured_content,
+            structured_content_blocks: None,
+            _meta: self._meta,
+        }
+    }
 }
 
 /// Resource definition
@@ -1175,6 +1593,77 @@ pub struct ResourceContents {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ReadResourceResult {
     pub contents: Vec<ResourceContents>,
+    #[serde(rename = "_meta", default, skip_serializing_if = "Option::is_none")]
+    pub _meta: Option<Meta>,
+}
+
+impl ReadResourceResult {
+    /// Create a read result from resource contents
+    pub fn new(contents: Vec<ResourceContents>) -> Self {
+        Self {
+            contents,
+            _meta: None,
+        }
+    }
+
+    /// Create a result telling the client its cached version is current
+    pub fn not_modified(etag: impl Into<String>) -> Self {
+        Self {
+            contents: vec![],
+            _meta: Some(Meta {
+                etag: Some(etag.into()),
+                not_modified: Some(true),
+                ..Default::default()
+            }),
+        }
+    }
+
+    /// Tag the result with an ETag so clients can issue conditional reads
+    pub fn with_etag(mut self, etag: impl Into<String>) -> Self {
+        self._meta.get_or_insert_with(Meta::default).etag = Some(etag.into());
+        self
+    }
+
+    /// Get the ETag of this result, if the backend provided one
+    pub fn etag(&self) -> Option<&str> {
+        self._meta.as_ref().and_then(|m| m.etag.as_deref())
+    }
+
+    /// Check whether this is a not-modified response to a conditional read
+    pub fn is_not_modified(&self) -> bool {
+        self._meta
+            .as_ref()
+            .and_then(|m| m.not_modified)
+            .unwrap_or(false)
+    }
+
+    /// Check whether an `If-None-Match` value matches this result's ETag
+    ///
+    /// Accepts a single tag, a comma-separated list, an array of tags, or `*`.
+    /// Weak (`W/`) and strong tags compare equal.
+    pub fn matches_if_none_match(&self, if_none_match: &serde_json::Value) -> bool {
+        let Some(etag) = self.etag() else {
+            return false;
+        };
+        let normalize = |tag: &str| {
+            let tag = tag.trim();
+            tag.strip_prefix("W/")
+                .unwrap_or(tag)
+                .trim_matches('"')
+                .to_string()
+        };
+        let etag = normalize(etag);
+        let candidates: Vec<String> = match if_none_match {
+            serde_json::Value::String(s) => s.split(',').map(normalize).collect(),
+            serde_json::Value::Array(items) => items
+                .iter()
+                .filter_map(|v| v.as_str())
+                .map(normalize)
+                .collect(),
+            _ => vec![],
+        };
+        candidates.iter().any(|c| c == "*" || *c == etag)
+    }
 }
 
 /// Embedded resource contents for tool responses (alias for ResourceContents)
@@ -1362,6 +1851,9 @@ pub struct InitializeResult {
     pub server_info: Implementation,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub instructions: Option<String>,
+    /// Server-defined metadata, e.g. `buildInfo` identifying the running build
+    #[serde(rename = "_meta", default, skip_serializing_if = "Option::is_none")]
+    pub _meta: Option<serde_json::Value>,
 }
 
 /// Completion context for context-aware completion (MCP 2025-06-18)
@@ -1473,6 +1965,71 @@ pub struct ListResourceTemplatesResult {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SubscribeRequestParam {
     pub uri: String,
+    /// Only deliver updates for resources matching this glob
+    ///
+    /// Without a filter the subscription covers `uri` alone; with one it
+    /// covers every matching resource, and `uri` identifies the subscription
+    /// for `resources/unsubscribe`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub filter: Option<UriFilter>,
+}
+
+/// URI glob selecting which resources a subscription receives updates for
+///
+/// `*` matches any run of characters except `/`, `**` matches any run
+/// including `/`, and `?` matches one character other than `/`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct UriFilter(String);
+
+impl UriFilter {
+    pub fn new(glob: impl Into<String>) -> Self {
+        Self(glob.into())
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    /// Whether `uri` matches the glob
+    pub fn matches(&self, uri: &str) -> bool {
+        let uri: Vec<char> = uri.chars().collect();
+        let mut pattern = self.0.chars().peekable();
+        // reachable[i]: the pattern consumed so far can match uri[..i]
+        let mut reachable = vec![false; uri.len() + 1];
+        reachable[0] = true;
+
+        while let Some(token) = pattern.next() {
+            let mut next = vec![false; uri.len() + 1];
+            match token {
+                '*' => {
+                    let crosses_segments = pattern.next_if_eq(&'*').is_some();
+                    for i in 0..=uri.len() {
+                        next[i] = reachable[i]
+                            || (i > 0 && next[i - 1] && (crosses_segments || uri[i - 1] != '/'));
+                    }
+                }
+                '?' => {
+                    for i in 0..uri.len() {
+                        next[i + 1] = reachable[i] && uri[i] != '/';
+                    }
+                }
+                literal => {
+                    for i in 0..uri.len() {
+                        next[i + 1] = reachable[i] && uri[i] == literal;
+                    }
+                }
+            }
+            reachable = next;
+        }
+        reachable[uri.len()]
+    }
+}
+
+impl From<&str> for UriFilter {
+    fn from(glob: &str) -> Self {
+        Self::new(glob)
+    }
 }
 
 /// Unsubscribe request parameters
@@ -1483,10 +2040,22 @@ pub struct UnsubscribeRequestParam {
 
 /// Resource updated notification parameters
 /// Sent when a subscribed resource changes
+///
+/// May carry a JSON Patch from one resource version to the next; see
+/// [`crate::patch`].
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ResourceUpdatedNotification {
     /// URI of the resource that was updated
     pub uri: String,
+    /// RFC 6902 patch from the `base_etag` version to the `etag` version
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub patch: Option<Vec<crate::patch::PatchOperation>>,
+    /// ETag of the version the patch applies to
+    #[serde(rename = "baseEtag", default, skip_serializing_if = "Option::is_none")]
+    pub base_etag: Option<String>,
+    /// ETag of the resource after the update
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub etag: Option<String>,
 }
 
 /// Elicitation completion notification (MCP 2025-11-25)
@@ -2207,13 +2776,18 @@ impl TaskStatusNotification {
 
 /// Tool execution configuration (MCP 2025-11-25)
 ///
-/// Specifies how a tool handles task-based execution.
+/// Specifies how a tool handles task-based execution, and optionally how
+/// long a call may run.
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
 #[serde(rename_all = "camelCase")]
 pub struct ToolExecution {
     /// Whether this tool supports/requires task-based execution
     #[serde(skip_serializing_if = "Option::is_none")]
     pub task_support: Option<TaskSupport>,
+    /// Time limit in milliseconds for a call to this tool, overriding the
+    /// server-wide tool timeout
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub timeout_ms: Option<u64>,
 }
 
 /// Task support mode for tools (MCP 2025-11-25)
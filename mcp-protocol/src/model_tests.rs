@@ -260,6 +260,48 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_call_tool_result_builder() {
+        let result = CallToolResult::builder()
+            .text("Found 1 match")
+            .json(&json!({"id": 7}))
+            .image([0x89, 0x50, 0x4e, 0x47], "image/png")
+            .ui_html("ui://matches/list", "<ul><li>7</li></ul>")
+            .structured(json!({"matches": [7]}))
+            .build();
+
+        assert_eq!(result.is_error, Some(false));
+        assert_eq!(result.content.len(), 4);
+        assert_eq!(result.content[1].text_format(), Some(mime_types::JSON));
+        match &result.content[2] {
+            Content::Image {
+                data, mime_type, ..
+            } => {
+                assert_eq!(data, "iVBORw==");
+                assert_eq!(mime_type, "image/png");
+            }
+            other => panic!("Expected Image content, got {other:?}"),
+        }
+        assert!(matches!(&result.content[3], Content::Resource { .. }));
+        assert_eq!(result.structured_content, Some(json!({"matches": [7]})));
+        assert!(result._meta.is_none());
+    }
+
+    #[test]
+    fn test_call_tool_result_builder_error() {
+        let result = CallToolResult::builder()
+            .text("Looked up 3 sources")
+            .error("Upstream timed out")
+            .build();
+
+        assert_eq!(result.is_error, Some(true));
+        assert_eq!(result.content.len(), 2);
+
+        let empty = CallToolResult::builder().build();
+        assert_eq!(empty.is_error, Some(false));
+        assert!(empty.content.is_empty());
+    }
+
     #[test]
     fn test_tool_with_output_schema() {
         let tool = Tool {
@@ -1255,6 +1297,22 @@ mod tests {
         assert!(requests.tools.is_some());
     }
 
+    #[test]
+    fn test_server_capabilities_enable_completions() {
+        let capabilities = ServerCapabilities::builder().enable_completions().build();
+        let json = serde_json::to_value(&capabilities).unwrap();
+        assert_eq!(json, serde_json::json!({"completions": {}}));
+
+        let parsed: ServerCapabilities = serde_json::from_value(json).unwrap();
+        assert!(parsed.completions.is_some());
+        assert!(
+            serde_json::to_value(ServerCapabilities::default())
+                .unwrap()
+                .get("completions")
+                .is_none()
+        );
+    }
+
     #[test]
     fn test_server_capabilities_enable_tasks_basic() {
         let capabilities = ServerCapabilities::builder().enable_tasks_basic().build();
@@ -1379,10 +1437,26 @@ mod tests {
     fn test_tool_execution() {
         let execution = ToolExecution {
             task_support: Some(TaskSupport::Optional),
+            timeout_ms: None,
         };
 
         let json = serde_json::to_string(&execution).unwrap();
         assert!(json.contains("\"taskSupport\":\"optional\""));
+        assert!(!json.contains("timeoutMs"));
+    }
+
+    #[test]
+    fn test_tool_execution_timeout() {
+        let execution = ToolExecution {
+            timeout_ms: Some(1500),
+            ..Default::default()
+        };
+
+        let json = serde_json::to_value(&execution).unwrap();
+        assert_eq!(json, json!({"timeoutMs": 1500}));
+        let deserialized: ToolExecution = serde_json::from_value(json).unwrap();
+        assert_eq!(deserialized.timeout_ms, Some(1500));
+        assert!(deserialized.task_support.is_none());
     }
 
     #[test]
@@ -1415,6 +1489,7 @@ mod tests {
             icons: None,
             execution: Some(ToolExecution {
                 task_support: Some(TaskSupport::Required),
+                timeout_ms: None,
             }),
             _meta: None,
         };
@@ -1658,4 +1733,188 @@ mod tests {
         assert!(!json.contains("\"blob\""));
         assert!(!json.contains("\"_meta\""));
     }
+
+    #[test]
+    fn test_read_resource_result_etag_matching() {
+        let result = ReadResourceResult::new(vec![ResourceContents::text("file://a", "a")])
+            .with_etag("\"abc\"");
+        assert_eq!(result.etag(), Some("\"abc\""));
+
+        assert!(result.matches_if_none_match(&json!("\"abc\"")));
+        assert!(result.matches_if_none_match(&json!("abc")));
+        assert!(result.matches_if_none_match(&json!("W/\"abc\"")));
+        assert!(result.matches_if_none_match(&json!("\"old\", \"abc\"")));
+        assert!(result.matches_if_none_match(&json!(["\"old\"", "\"abc\""])));
+        assert!(result.matches_if_none_match(&json!("*")));
+        assert!(!result.matches_if_none_match(&json!("\"old\"")));
+        assert!(!result.matches_if_none_match(&json!(42)));
+
+        let untagged = ReadResourceResult::new(vec![]);
+        assert!(!untagged.matches_if_none_match(&json!("*")));
+    }
+
+    #[test]
+    fn test_read_resource_result_not_modified_serialization() {
+        let result = ReadResourceResult::not_modified("v3");
+        assert!(result.is_not_modified());
+
+        let value = serde_json::to_value(&result).unwrap();
+        assert_eq!(
+            value,
+            json!({"contents": [], "_meta": {"etag": "v3", "notModified": true}})
+        );
+
+        // Results without _meta keep the original wire format
+        let plain = serde_json::to_value(ReadResourceResult::new(vec![])).unwrap();
+        assert_eq!(plain, json!({"contents": []}));
+        let parsed: ReadResourceResult = serde_json::from_value(plain).unwrap();
+        assert!(parsed._meta.is_none());
+    }
+
+    #[test]
+    fn test_experimental_capabilities_serialization() {
+        let capabilities = ServerCapabilities::builder()
+            .experimental("streaming", true)
+            .unwrap()
+            .build();
+        let value = serde_json::to_value(&capabilities).unwrap();
+        assert_eq!(value, json!({"experimental": {"streaming": true}}));
+
+        let parsed: ServerCapabilities = serde_json::from_value(value).unwrap();
+        assert!(parsed.supports_experimental("streaming"));
+
+        // Servers without flags omit the field entirely
+        let plain = serde_json::to_value(ServerCapabilities::default()).unwrap();
+        assert_eq!(plain, json!({}));
+        assert!(
+            !serde_json::from_value::<ServerCapabilities>(plain)
+                .unwrap()
+                .supports_experimental("streaming")
+        );
+    }
+
+    #[test]
+    fn test_experimental_capability_must_be_json_serializable() {
+        // JSON object keys must be strings
+        let mut invalid = std::collections::BTreeMap::new();
+        invalid.insert(vec![1u8], true);
+
+        let error = ServerCapabilities::builder()
+            .experimental("byte_keys", invalid)
+            .unwrap_err();
+        assert!(
+            error
+                .message
+                .contains("'byte_keys' is not JSON-serializable")
+        );
+    }
+
+    #[test]
+    fn test_call_tool_result_named_structured_blocks() {
+        let table = json!({"columns": ["month", "sales"], "rows": [["jan", 10], ["feb", 12]]});
+        let chart = json!({"mark": "bar", "encoding": {"x": {"field": "month"}}});
+        let result = CallToolResult::text("sales by month")
+            .with_structured_block("table", table.clone())
+            .with_structured_block("vega-lite", chart.clone());
+
+        let value = serde_json::to_value(&result).unwrap();
+        assert_eq!(
+            value["structuredContentBlocks"],
+            json!([
+                {"name": "table", "content": table},
+                {"name": "vega-lite", "content": chart}
+            ])
+        );
+        // Single-field clients still see the first representation
+        assert_eq!(value["structuredContent"], table);
+
+        let parsed: CallToolResult = serde_json::from_value(value).unwrap();
+        assert_eq!(parsed.structured_block("table"), Some(&table));
+        assert_eq!(parsed.structured_block("vega-lite"), Some(&chart));
+        assert!(parsed.structured_block("csv").is_none());
+    }
+
+    #[test]
+    fn test_call_tool_result_single_structured_content_unchanged() {
+        let result = CallToolResult::text_with_structured("22°C", json!({"temperature": 22}));
+
+        let value = serde_json::to_value(&result).unwrap();
+        assert_eq!(value["structuredContent"], json!({"temperature": 22}));
+        assert!(value.get("structuredContentBlocks").is_none());
+
+        let legacy: CallToolResult = serde_json::from_value(json!({
+            "content": [],
+            "isError": false,
+            "structuredContent": {"temperature": 22}
+        }))
+        .unwrap();
+        assert!(legacy.structured_content_blocks.is_none());
+        assert_eq!(legacy.structured_content, Some(json!({"temperature": 22})));
+    }
+
+    #[test]
+    fn test_uri_filter_globs() {
+        let filter = UriFilter::new("file:///logs/*.txt");
+        assert!(filter.matches("file:///logs/app.txt"));
+        assert!(!filter.matches("file:///logs/archive/old.txt"));
+        assert!(!filter.matches("file:///config/app.txt"));
+
+        let filter = UriFilter::new("file:///logs/**");
+        assert!(filter.matches("file:///logs/archive/old.txt"));
+        assert!(filter.matches("file:///logs/"));
+        assert!(!filter.matches("file:///log"));
+
+        let filter = UriFilter::new("db://table/?");
+        assert!(filter.matches("db://table/a"));
+        assert!(!filter.matches("db://table/ab"));
+        assert!(!filter.matches("db://table//"));
+
+        let params: SubscribeRequestParam =
+            serde_json::from_value(json!({"uri": "file:///logs/", "filter": "file:///logs/**"}))
+                .unwrap();
+        assert_eq!(params.filter, Some(UriFilter::new("file:///logs/**")));
+        let params: SubscribeRequestParam =
+            serde_json::from_value(json!({"uri": "file:///a.txt"})).unwrap();
+        assert!(params.filter.is_none());
+        assert_eq!(
+            serde_json::to_value(&params).unwrap(),
+            json!({"uri": "file:///a.txt"})
+        );
+    }
+
+    #[test]
+    fn test_text_content_format_hint() {
+        let plain = Content::text("hello");
+        assert_eq!(plain.text_format(), Some(mime_types::TEXT));
+        assert_eq!(
+            serde_json::to_value(&plain).unwrap(),
+            json!({"type": "text", "text": "hello"})
+        );
+
+        let markdown = Content::markdown("# Title");
+        assert_eq!(markdown.text_format(), Some(mime_types::MARKDOWN));
+        assert_eq!(
+            serde_json::to_value(&markdown).unwrap(),
+            json!({"type": "text", "text": "# Title", "_meta": {"contentType": "text/markdown"}})
+        );
+        let parsed: Content = serde_json::from_value(
+            json!({"type": "text", "text": "<b>x</b>", "_meta": {"contentType": "text/html"}}),
+        )
+        .unwrap();
+        assert_eq!(parsed.text_format(), Some(mime_types::HTML));
+        assert_eq!(Content::image("aGk=", "image/png").text_format(), None);
+
+        // A result-wide format only fills in text without its own hint
+        let result = CallToolResult::success(vec![
+            Content::text("**bold**"),
+            Content::text_with_format("<i>x</i>", mime_types::HTML),
+            Content::image("aGk=", "image/png"),
+        ])
+        .with_text_format(mime_types::MARKDOWN);
+        let formats: Vec<Option<&str>> = result.content.iter().map(Content::text_format).collect();
+        assert_eq!(
+            formats,
+            [Some(mime_types::MARKDOWN), Some(mime_types::HTML), None]
+        );
+    }
 }
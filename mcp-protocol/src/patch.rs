@@ -0,0 +1,247 @@
+//! Diff-based resource updates
+//!
+//! A `notifications/resources/updated` message normally only names the
+//! changed resource, and the client re-reads it in full. For large JSON
+//! resources a server can instead attach an RFC 6902 JSON Patch describing
+//! the change, together with the ETag it applies to (`baseEtag`) and the ETag
+//! of the result (`etag`). A client whose cached copy has the base ETag
+//! applies the patch with [`ReadResourceResult::apply_update`]; any other
+//! client, or any update without a patch, falls back to a full re-read.
+
+use crate::model::{ReadResourceResult, ResourceUpdatedNotification};
+use crate::{Error, Result};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+/// A single RFC 6902 JSON Patch operation
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "op", rename_all = "lowercase")]
+pub enum PatchOperation {
+    Add { path: String, value: Value },
+    Remove { path: String },
+    Replace { path: String, value: Value },
+    Move { from: String, path: String },
+    Copy { from: String, path: String },
+    Test { path: String, value: Value },
+}
+
+/// Apply a JSON Patch to a document
+///
+/// The patch is applied atomically: if any operation fails the document is
+/// left unchanged.
+pub fn apply_patch(document: &mut Value, patch: &[PatchOperation]) -> Result<()> {
+    let mut patched = document.clone();
+    for (index, operation) in patch.iter().enumerate() {
+        apply_operation(&mut patched, operation)
+            .map_err(|e| Error::invalid_params(format!("JSON patch operation {index}: {e}")))?;
+    }
+    *document = patched;
+    Ok(())
+}
+
+/// Compute a JSON Patch transforming `old` into `new`
+///
+/// Objects are diffed key by key and arrays element by element; any other
+/// change replaces the value at its path.
+pub fn diff(old: &Value, new: &Value) -> Vec<PatchOperation> {
+    let mut patch = Vec::new();
+    diff_into(old, new, String::new(), &mut patch);
+    patch
+}
+
+fn diff_into(old: &Value, new: &Value, path: String, patch: &mut Vec<PatchOperation>) {
+    match (old, new) {
+        (Value::Object(old), Value::Object(new)) => {
+            for (key, old_value) in old {
+                let child = format!("{path}/{}", escape(key));
+                match new.get(key) {
+                    Some(new_value) => diff_into(old_value, new_value, child, patch),
+                    None => patch.push(PatchOperation::Remove { path: child }),
+                }
+            }
+            for (key, value) in new {
+                if !old.contains_key(key) {
+                    patch.push(PatchOperation::Add {
+                        path: format!("{path}/{}", escape(key)),
+                        value: value.clone(),
+                    });
+                }
+            }
+        }
+        (Value::Array(old), Value::Array(new)) => {
+            for (index, (old_value, new_value)) in old.iter().zip(new).enumerate() {
+                diff_into(old_value, new_value, format!("{path}/{index}"), patch);
+            }
+            // Remove from the end so earlier indices stay valid
+            for index in (new.len()..old.len()).rev() {
+                patch.push(PatchOperation::Remove {
+                    path: format!("{path}/{index}"),
+                });
+            }
+            for value in new.iter().skip(old.len()) {
+                patch.push(PatchOperation::Add {
+                    path: format!("{path}/-"),
+                    value: value.clone(),
+                });
+            }
+        }
+        (old, new) if old != new => patch.push(PatchOperation::Replace {
+            path,
+            value: new.clone(),
+        }),
+        _ => {}
+    }
+}
+
+fn apply_operation(
+    document: &mut Value,
+    operation: &PatchOperation,
+) -> std::result::Result<(), String> {
+    match operation {
+        PatchOperation::Add { path, value } => add(document, path, value.clone()),
+        PatchOperation::Remove { path } => remove(document, path).map(drop),
+        PatchOperation::Replace { path, value } => {
+            let target = document
+                .pointer_mut(path)
+                .ok_or_else(|| format!("path {path:?} does not exist"))?;
+            *target = value.clone();
+            Ok(())
+        }
+        PatchOperation::Move { from, path } => {
+            if path.starts_with(&format!("{from}/")) {
+                return Err(format!("cannot move {from:?} into its own child {path:?}"));
+            }
+            let value = remove(document, from)?;
+            add(document, path, value)
+        }
+        PatchOperation::Copy { from, path } => {
+            let value = document
+                .pointer(from)
+                .cloned()
+                .ok_or_else(|| format!("path {from:?} does not exist"))?;
+            add(document, path, value)
+        }
+        PatchOperation::Test { path, value } => match document.pointer(path) {
+            Some(actual) if actual == value => Ok(()),
+            Some(_) => Err(format!("test failed at {path:?}")),
+            None => Err(format!("path {path:?} does not exist")),
+        },
+    }
+}
+
+/// Split a JSON pointer into its parent pointer and unescaped last token
+fn split_pointer(path: &str) -> std::result::Result<(&str, String), String> {
+    let (parent, last) = path
+        .rsplit_once('/')
+        .ok_or_else(|| format!("invalid JSON pointer {path:?}"))?;
+    Ok((parent, last.replace("~1", "/").replace("~0", "~")))
+}
+
+fn escape(key: &str) -> String {
+    key.replace('~', "~0").replace('/', "~1")
+}
+
+fn add(document: &mut Value, path: &str, value: Value) -> std::result::Result<(), String> {
+    if path.is_empty() {
+        *document = value;
+        return Ok(());
+    }
+    let (parent, key) = split_pointer(path)?;
+    match document.pointer_mut(parent) {
+        Some(Value::Object(map)) => {
+            map.insert(key, value);
+            Ok(())
+        }
+        Some(Value::Array(items)) => {
+            let index = if key == "-" {
+                items.len()
+            } else {
+                key.parse::<usize>()
+                    .ok()
+                    .filter(|index| *index <= items.len())
+                    .ok_or_else(|| format!("invalid array index in {path:?}"))?
+            };
+            items.insert(index, value);
+            Ok(())
+        }
+        Some(_) => Err(format!("parent of {path:?} is not a container")),
+        None => Err(format!("parent of {path:?} does not exist")),
+    }
+}
+
+fn remove(document: &mut Value, path: &str) -> std::result::Result<Value, String> {
+    let (parent, key) = split_pointer(path)?;
+    let removed = match document.pointer_mut(parent) {
+        Some(Value::Object(map)) => map.remove(&key),
+        Some(Value::Array(items)) => key
+            .parse::<usize>()
+            .ok()
+            .filter(|index| *index < items.len())
+            .map(|index| items.remove(index)),
+        _ => None,
+    };
+    removed.ok_or_else(|| format!("path {path:?} does not exist"))
+}
+
+impl ResourceUpdatedNotification {
+    /// Update telling clients to re-read the resource in full
+    pub fn full(uri: impl Into<String>) -> Self {
+        Self {
+            uri: uri.into(),
+            patch: None,
+            base_etag: None,
+            etag: None,
+        }
+    }
+
+    /// Update carrying a JSON Patch from the `base_etag` version to `etag`
+    pub fn with_patch(
+        uri: impl Into<String>,
+        base_etag: impl Into<String>,
+        etag: impl Into<String>,
+        patch: Vec<PatchOperation>,
+    ) -> Self {
+        Self {
+            uri: uri.into(),
+            patch: Some(patch),
+            base_etag: Some(base_etag.into()),
+            etag: Some(etag.into()),
+        }
+    }
+}
+
+impl ReadResourceResult {
+    /// Apply a resource update to this cached read result
+    ///
+    /// Returns `Ok(true)` when the update's patch was applied to the JSON
+    /// text contents, and `Ok(false)` when the client must re-read the
+    /// resource instead: the update carries no patch, targets another
+    /// resource, or was computed against a different version than the
+    /// cached one.
+    pub fn apply_update(&mut self, update: &ResourceUpdatedNotification) -> Result<bool> {
+        let (Some(patch), Some(base_etag)) = (&update.patch, &update.base_etag) else {
+            return Ok(false);
+        };
+        if self.etag() != Some(base_etag.as_str()) {
+            return Ok(false);
+        }
+        let Some(contents) = self
+            .contents
+            .iter_mut()
+            .find(|contents| contents.uri == update.uri)
+        else {
+            return Ok(false);
+        };
+        let Some(text) = contents.text.as_deref() else {
+            return Ok(false);
+        };
+
+        let mut document: Value = serde_json::from_str(text)?;
+        apply_patch(&mut document, patch)?;
+        contents.text = Some(serde_json::to_string(&document)?);
+        if let Some(etag) = &update.etag {
+            self._meta.get_or_insert_with(Default::default).etag = Some(etag.clone());
+        }
+        Ok(true)
+    }
+}
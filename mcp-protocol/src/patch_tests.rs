@@ -0,0 +1,124 @@
+//! Tests for diff-based resource updates
+
+use crate::model::{ReadResourceResult, ResourceContents, ResourceUpdatedNotification};
+use crate::patch::*;
+use serde_json::{Value, json};
+
+const URI: &str = "file:///inventory.json";
+
+fn cached_read(document: &Value, etag: &str) -> ReadResourceResult {
+    ReadResourceResult::new(vec![ResourceContents {
+        uri: URI.to_string(),
+        mime_type: Some("application/json".to_string()),
+        text: Some(document.to_string()),
+        blob: None,
+        _meta: None,
+    }])
+    .with_etag(etag)
+}
+
+fn cached_document(result: &ReadResourceResult) -> Value {
+    serde_json::from_str(result.contents[0].text.as_deref().unwrap()).unwrap()
+}
+
+#[test]
+fn test_client_applying_patch_update_reaches_new_state() {
+    let old = json!({
+        "items": [{"sku": "a", "qty": 1}, {"sku": "b", "qty": 2}, {"sku": "c", "qty": 3}],
+        "owner": "ops",
+        "stale": true
+    });
+    let new = json!({
+        "items": [{"sku": "a", "qty": 5}, {"sku": "b", "qty": 2}],
+        "owner": "ops",
+        "tags/labels": ["x"]
+    });
+
+    // Server side: diff and emit the update over the wire
+    let update = ResourceUpdatedNotification::with_patch(URI, "v1", "v2", diff(&old, &new));
+    let wire = serde_json::to_value(&update).unwrap();
+    assert_eq!(wire["baseEtag"], "v1");
+    assert_eq!(wire["etag"], "v2");
+    assert!(wire["patch"].as_array().unwrap().len() < 5);
+
+    // Client side: apply it to the cached read
+    let received: ResourceUpdatedNotification = serde_json::from_value(wire).unwrap();
+    let mut cached = cached_read(&old, "v1");
+    assert!(cached.apply_update(&received).unwrap());
+    assert_eq!(cached_document(&cached), new);
+    assert_eq!(cached.etag(), Some("v2"));
+}
+
+#[test]
+fn test_updates_without_matching_patch_fall_back_to_full_read() {
+    let document = json!({"count": 1});
+    let mut cached = cached_read(&document, "v1");
+
+    // Plain notification: no patch, client must re-read
+    let full = ResourceUpdatedNotification::full(URI);
+    assert_eq!(serde_json::to_value(&full).unwrap(), json!({"uri": URI}));
+    assert!(!cached.apply_update(&full).unwrap());
+
+    // Patch against a version the client doesn't have
+    let patch = diff(&document, &json!({"count": 3}));
+    let stale = ResourceUpdatedNotification::with_patch(URI, "v2", "v3", patch.clone());
+    assert!(!cached.apply_update(&stale).unwrap());
+
+    // Patch for another resource
+    let other = ResourceUpdatedNotification::with_patch("file:///other.json", "v1", "v2", patch);
+    assert!(!cached.apply_update(&other).unwrap());
+
+    assert_eq!(cached_document(&cached), document);
+    assert_eq!(cached.etag(), Some("v1"));
+
+    // Older servers only send the uri
+    let legacy: ResourceUpdatedNotification = serde_json::from_value(json!({"uri": URI})).unwrap();
+    assert!(legacy.patch.is_none());
+}
+
+#[test]
+fn test_apply_patch_operations() {
+    let mut document = json!({"a": {"b": 1}, "list": [1, 2, 3], "x~y": 0});
+    let patch: Vec<PatchOperation> = serde_json::from_value(json!([
+        {"op": "test", "path": "/a/b", "value": 1},
+        {"op": "add", "path": "/list/1", "value": 9},
+        {"op": "remove", "path": "/list/0"},
+        {"op": "copy", "from": "/a", "path": "/c"},
+        {"op": "move", "from": "/a/b", "path": "/moved"},
+        {"op": "replace", "path": "/x~0y", "value": 1},
+        {"op": "add", "path": "/list/-", "value": 4}
+    ]))
+    .unwrap();
+
+    apply_patch(&mut document, &patch).unwrap();
+
+    assert_eq!(
+        document,
+        json!({"a": {}, "c": {"b": 1}, "list": [9, 2, 3, 4], "moved": 1, "x~y": 1})
+    );
+}
+
+#[test]
+fn test_failed_patch_leaves_document_unchanged() {
+    let original = json!({"count": 1});
+    let mut document = original.clone();
+    let patch = vec![
+        PatchOperation::Replace {
+            path: "/count".to_string(),
+            value: json!(2),
+        },
+        PatchOperation::Test {
+            path: "/count".to_string(),
+            value: json!(3),
+        },
+    ];
+
+    let error = apply_patch(&mut document, &patch).unwrap_err();
+    assert!(error.message.contains("JSON patch operation 1"));
+    assert_eq!(document, original);
+
+    let mut cached = cached_read(&original, "v1");
+    let update = ResourceUpdatedNotification::with_patch(URI, "v1", "v2", patch);
+    assert!(cached.apply_update(&update).is_err());
+    assert_eq!(cached.etag(), Some("v1"));
+}
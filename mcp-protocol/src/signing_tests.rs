@@ -0,0 +1,96 @@
+//! Tests for server-initiated request signing
+
+use crate::signing::*;
+use crate::*;
+use serde_json::json;
+
+fn sampling_params() -> serde_json::Value {
+    json!({
+        "messages": [{"role": "user", "content": {"type": "text", "text": "Summarize"}}],
+        "maxTokens": 100,
+        "_meta": {"progressToken": "tok-1"}
+    })
+}
+
+#[test]
+fn test_sign_adds_verifiable_header() {
+    let signer = RequestSigner::new("shared-secret");
+    let mut params = sampling_params();
+    signer.sign("sampling/createMessage", &mut params);
+
+    let header = &params["_meta"][SIGNATURE_META_KEY];
+    assert_eq!(header["algorithm"], HMAC_SHA256);
+    assert_eq!(
+        header["value"],
+        signer.signature("sampling/createMessage", &sampling_params())
+    );
+    // Existing _meta fields are preserved
+    assert_eq!(params["_meta"]["progressToken"], "tok-1");
+
+    signer.verify("sampling/createMessage", &params).unwrap();
+}
+
+#[test]
+fn test_signature_survives_wire_round_trip() {
+    let signer = RequestSigner::new(b"k".to_vec());
+    let mut params =
+        json!({"message": "Confirm?", "requestedSchema": {"type": "object", "b": 1, "a": 2}});
+    signer.sign("elicitation/create", &mut params);
+
+    let wire = serde_json::to_string(&params).unwrap();
+    let received: serde_json::Value = serde_json::from_str(&wire).unwrap();
+    signer.verify("elicitation/create", &received).unwrap();
+}
+
+#[test]
+fn test_null_params_are_signed() {
+    let signer = RequestSigner::new("secret");
+    let mut params = serde_json::Value::Null;
+    signer.sign("roots/list", &mut params);
+    signer.verify("roots/list", &params).unwrap();
+}
+
+#[test]
+fn test_tampering_is_detected() {
+    let signer = RequestSigner::new("shared-secret");
+    let mut params = sampling_params();
+    signer.sign("sampling/createMessage", &mut params);
+
+    let mut altered = params.clone();
+    altered["maxTokens"] = json!(100000);
+    let err = signer
+        .verify("sampling/createMessage", &altered)
+        .unwrap_err();
+    assert_eq!(err.code, ErrorCode::Unauthorized);
+
+    assert!(signer.verify("elicitation/create", &params).is_err());
+    assert!(
+        RequestSigner::new("other-secret")
+            .verify("sampling/createMessage", &params)
+            .is_err()
+    );
+}
+
+#[test]
+fn test_missing_or_malformed_signature_rejected() {
+    let signer = RequestSigner::new("shared-secret");
+    assert!(
+        signer
+            .verify("sampling/createMessage", &sampling_params())
+            .is_err()
+    );
+
+    let mut params = sampling_params();
+    signer.sign("sampling/createMessage", &mut params);
+    params["_meta"][SIGNATURE_META_KEY]["algorithm"] = json!("none");
+    assert!(signer.verify("sampling/createMessage", &params).is_err());
+
+    params["_meta"][SIGNATURE_META_KEY] = json!({"algorithm": HMAC_SHA256, "value": "%%%"});
+    assert!(signer.verify("sampling/createMessage", &params).is_err());
+}
+
+#[test]
+fn test_debug_redacts_secret() {
+    let debug = format!("{:?}", RequestSigner::new("top-secret"));
+    assert!(!debug.contains("top-secret"));
+}
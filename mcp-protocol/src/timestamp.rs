@@ -0,0 +1,151 @@
+//! Configurable timestamp serialization
+//!
+//! Timestamps serialize as RFC3339 strings by default, but some clients
+//! expect Unix epoch milliseconds or seconds instead. The format is a
+//! process-wide setting, chosen once at startup with
+//! [`set_timestamp_format`] (or `ServerConfig::timestamp_format` in the
+//! server crate), and applied to fields annotated with
+//! `#[serde(with = "pulseengine_mcp_protocol::timestamp::serde_format")]`.
+//!
+//! Deserialization accepts every format whatever the current setting, so
+//! data written before a format change can still be read back: strings are
+//! RFC3339, and numbers are taken as seconds or milliseconds by magnitude.
+
+use chrono::{DateTime, SecondsFormat, TimeZone, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::sync::atomic::{AtomicU8, Ordering};
+
+/// Wire format for timestamps
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TimestampFormat {
+    /// RFC3339 string, e.g. `"2025-01-01T12:00:00Z"`
+    #[default]
+    Rfc3339,
+    /// Integer milliseconds since the Unix epoch
+    EpochMillis,
+    /// Integer seconds since the Unix epoch
+    EpochSeconds,
+}
+
+static FORMAT: AtomicU8 = AtomicU8::new(0);
+
+/// Epoch values at or above this magnitude are read as milliseconds
+///
+/// `10^11` seconds lies in the year 5138, while `10^11` milliseconds is in
+/// March 1973, so only millisecond timestamps from before then are misread.
+const EPOCH_MILLIS_THRESHOLD: u64 = 100_000_000_000;
+
+/// Set the timestamp format used by [`serde_format`] for this process
+pub fn set_timestamp_format(format: TimestampFormat) {
+    FORMAT.store(format as u8, Ordering::Relaxed);
+}
+
+/// Timestamp format currently used by [`serde_format`]
+pub fn timestamp_format() -> TimestampFormat {
+    match FORMAT.load(Ordering::Relaxed) {
+        1 => TimestampFormat::EpochMillis,
+        2 => TimestampFormat::EpochSeconds,
+        _ => TimestampFormat::Rfc3339,
+    }
+}
+
+impl TimestampFormat {
+    /// Render a timestamp as a JSON value in this format
+    pub fn to_value(self, timestamp: &DateTime<Utc>) -> Value {
+        match self {
+            Self::Rfc3339 => Value::String(timestamp.to_rfc3339_opts(SecondsFormat::AutoSi, true)),
+            Self::EpochMillis => Value::from(timestamp.timestamp_millis()),
+            Self::EpochSeconds => Value::from(timestamp.timestamp()),
+        }
+    }
+
+    /// Parse a timestamp from a JSON value
+    ///
+    /// Input isn't tied to the format: strings are parsed as RFC3339, and
+    /// numbers are read as milliseconds from `10^11` in magnitude and as
+    /// seconds below that.
+    pub fn parse(self, value: &Value) -> Result<DateTime<Utc>, String> {
+        match value {
+            Value::String(s) => DateTime::parse_from_rfc3339(s)
+                .map(|dt| dt.with_timezone(&Utc))
+                .map_err(|e| format!("invalid RFC3339 timestamp {s:?}: {e}")),
+            Value::Number(n) => {
+                let n = n
+                    .as_i64()
+                    .ok_or_else(|| format!("invalid epoch timestamp {n}"))?;
+                let parsed = if n.unsigned_abs() >= EPOCH_MILLIS_THRESHOLD {
+                    Utc.timestamp_millis_opt(n).single()
+                } else {
+                    Utc.timestamp_opt(n, 0).single()
+                };
+                parsed.ok_or_else(|| format!("epoch timestamp {n} out of range"))
+            }
+            other => Err(format!("expected a timestamp, found {other}")),
+        }
+    }
+}
+
+/// Serde adapter for `DateTime<Utc>` using the process-wide format
+pub mod serde_format {
+    use super::timestamp_format;
+    use chrono::{DateTime, Utc};
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+    pub fn serialize<S: Serializer>(
+        timestamp: &DateTime<Utc>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        timestamp_format().to_value(timestamp).serialize(serializer)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<DateTime<Utc>, D::Error> {
+        let value = serde_json::Value::deserialize(deserializer)?;
+        timestamp_format()
+            .parse(&value)
+            .map_err(serde::de::Error::custom)
+    }
+
+    /// Serde adapter for `Option<DateTime<Utc>>` using the process-wide format
+    pub mod option {
+        use super::super::timestamp_format;
+        use chrono::{DateTime, Utc};
+        use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+        pub fn serialize<S: Serializer>(
+            timestamp: &Option<DateTime<Utc>>,
+            serializer: S,
+        ) -> Result<S::Ok, S::Error> {
+            timestamp
+                .as_ref()
+                .map(|ts| timestamp_format().to_value(ts))
+                .serialize(serializer)
+        }
+
+        pub fn deserialize<'de, D: Deserializer<'de>>(
+            deserializer: D,
+        ) -> Result<Option<DateTime<Utc>>, D::Error> {
+            match Option::<serde_json::Value>::deserialize(deserializer)? {
+                None | Some(serde_json::Value::Null) => Ok(None),
+                Some(value) => timestamp_format()
+                    .parse(&value)
+                    .map(Some)
+                    .map_err(serde::de::Error::custom),
+            }
+        }
+    }
+}
+
+/// Parse a timestamp value with [`TimestampFormat::parse`] using the
+/// process-wide format
+pub fn parse_timestamp(value: &Value) -> Result<DateTime<Utc>, String> {
+    timestamp_format().parse(value)
+}
+
+/// Render a timestamp as a JSON value using the process-wide format
+pub fn format_timestamp(timestamp: &DateTime<Utc>) -> Value {
+    timestamp_format().to_value(timestamp)
+}
@@ -0,0 +1,131 @@
+//! Tests for configurable timestamp serialization
+
+use crate::timestamp::*;
+use chrono::{DateTime, TimeZone, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::sync::Mutex;
+
+/// Serializes tests that change the process-wide format
+static FORMAT_LOCK: Mutex<()> = Mutex::new(());
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+struct Event {
+    #[serde(with = "crate::timestamp::serde_format")]
+    at: DateTime<Utc>,
+    #[serde(with = "crate::timestamp::serde_format::option", default)]
+    until: Option<DateTime<Utc>>,
+}
+
+fn sample_time() -> DateTime<Utc> {
+    Utc.timestamp_millis_opt(1_735_732_800_123).unwrap()
+}
+
+fn with_format<T>(format: TimestampFormat, f: impl FnOnce() -> T) -> T {
+    let _guard = FORMAT_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    set_timestamp_format(format);
+    let result = f();
+    set_timestamp_format(TimestampFormat::default());
+    result
+}
+
+#[test]
+fn test_default_format_is_rfc3339() {
+    let _guard = FORMAT_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    assert_eq!(timestamp_format(), TimestampFormat::Rfc3339);
+    assert_eq!(
+        TimestampFormat::Rfc3339.to_value(&sample_time()),
+        json!("2025-01-01T12:00:00.123Z")
+    );
+}
+
+#[test]
+fn test_serializes_in_configured_format() {
+    let event = Event {
+        at: sample_time(),
+        until: Some(sample_time()),
+    };
+
+    let rfc3339 = with_format(TimestampFormat::Rfc3339, || serde_json::to_value(&event));
+    assert_eq!(rfc3339.unwrap()["at"], "2025-01-01T12:00:00.123Z");
+
+    let millis = with_format(TimestampFormat::EpochMillis, || {
+        serde_json::to_value(&event)
+    });
+    let millis = millis.unwrap();
+    assert_eq!(millis["at"], 1_735_732_800_123_i64);
+    assert_eq!(millis["until"], 1_735_732_800_123_i64);
+
+    let seconds = with_format(TimestampFormat::EpochSeconds, || {
+        serde_json::to_value(&event)
+    });
+    assert_eq!(seconds.unwrap()["at"], 1_735_732_800_i64);
+}
+
+#[test]
+fn test_round_trips_in_each_format() {
+    let event = Event {
+        at: sample_time(),
+        until: None,
+    };
+
+    for format in [TimestampFormat::Rfc3339, TimestampFormat::EpochMillis] {
+        let restored: Event = with_format(format, || {
+            let json = serde_json::to_string(&event).unwrap();
+            serde_json::from_str(&json).unwrap()
+        });
+        assert_eq!(restored, event, "{format:?}");
+    }
+
+    // Seconds precision drops the milliseconds
+    let restored: Event = with_format(TimestampFormat::EpochSeconds, || {
+        let json = serde_json::to_string(&event).unwrap();
+        serde_json::from_str(&json).unwrap()
+    });
+    assert_eq!(restored.at, Utc.timestamp_opt(1_735_732_800, 0).unwrap());
+}
+
+#[test]
+fn test_rfc3339_strings_accepted_in_any_format() {
+    let value = json!("2025-01-01T12:00:00.123Z");
+    for format in [
+        TimestampFormat::Rfc3339,
+        TimestampFormat::EpochMillis,
+        TimestampFormat::EpochSeconds,
+    ] {
+        assert_eq!(format.parse(&value).unwrap(), sample_time());
+    }
+    assert!(TimestampFormat::Rfc3339.parse(&json!(true)).is_err());
+    assert!(TimestampFormat::Rfc3339.parse(&json!("yesterday")).is_err());
+}
+
+#[test]
+fn test_epoch_unit_detected_from_value() {
+    let seconds = json!(1_735_732_800_i64);
+    let millis = json!(1_735_732_800_123_i64);
+    for format in [
+        TimestampFormat::Rfc3339,
+        TimestampFormat::EpochMillis,
+        TimestampFormat::EpochSeconds,
+    ] {
+        assert_eq!(
+            format.parse(&seconds).unwrap(),
+            Utc.timestamp_opt(1_735_732_800, 0).unwrap(),
+            "{format:?}"
+        );
+        assert_eq!(format.parse(&millis).unwrap(), sample_time(), "{format:?}");
+    }
+
+    // Data written in one format reads back after switching to another
+    let json = with_format(TimestampFormat::EpochMillis, || {
+        serde_json::to_string(&Event {
+            at: sample_time(),
+            until: None,
+        })
+        .unwrap()
+    });
+    let restored: Event = with_format(TimestampFormat::EpochSeconds, || {
+        serde_json::from_str(&json).unwrap()
+    });
+    assert_eq!(restored.at, sample_time());
+}
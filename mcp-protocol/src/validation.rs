@@ -1,12 +1,69 @@
 //! Validation utilities for MCP protocol types
 
-use crate::{Error, Result};
+use crate::model::{CallToolResult, Tool};
+use crate::{Error, ErrorCode, Result};
 use jsonschema::{JSONSchema, ValidationError};
 use serde_json::Value;
 use std::collections::HashMap;
 use uuid::Uuid;
 use validator::Validate;
 
+/// Size bounds enforced on structured content before schema validation
+///
+/// Tool output may be untrusted, so content is measured first and rejected
+/// without running the schema validator when it's too deep or too large.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct StructuredContentLimits {
+    /// Maximum nesting depth of arrays and objects; a scalar has depth 0
+    pub max_depth: usize,
+    /// Maximum number of JSON values, counting every container and scalar
+    pub max_nodes: usize,
+}
+
+impl Default for StructuredContentLimits {
+    fn default() -> Self {
+        Self {
+            max_depth: 64,
+            max_nodes: 100_000,
+        }
+    }
+}
+
+impl StructuredContentLimits {
+    /// Check `content` against the limits, stopping at the first violation
+    ///
+    /// # Errors
+    ///
+    /// Returns a validation error naming the exceeded limit
+    pub fn check(&self, content: &Value) -> Result<()> {
+        let mut nodes = 0usize;
+        let mut stack = vec![(content, 0usize)];
+        while let Some((value, depth)) = stack.pop() {
+            nodes += 1;
+            if nodes > self.max_nodes {
+                return Err(Error::validation_error(format!(
+                    "Structured content exceeds the maximum of {} values",
+                    self.max_nodes
+                )));
+            }
+            if depth > self.max_depth {
+                return Err(Error::validation_error(format!(
+                    "Structured content exceeds the maximum nesting depth of {}",
+                    self.max_depth
+                )));
+            }
+            match value {
+                Value::Array(items) => stack.extend(items.iter().map(|item| (item, depth + 1))),
+                Value::Object(fields) => {
+                    stack.extend(fields.values().map(|field| (field, depth + 1)))
+                }
+                _ => {}
+            }
+        }
+        Ok(())
+    }
+}
+
 /// Protocol validation utilities
 pub struct Validator;
 
@@ -151,6 +208,148 @@ impl Validator {
         Ok(())
     }
 
+    /// Fill in omitted arguments from the schema's `default` keywords
+    ///
+    /// Walks the schema's `properties` and inserts each declared `default` that
+    /// the arguments don't already provide. Nested object properties are filled
+    /// recursively when the caller supplied the parent object. Explicit values,
+    /// including `null`, are never overwritten. Returns `true` if anything was added.
+    pub fn apply_schema_defaults(args: &mut Value, schema: &Value) -> bool {
+        let Some(properties) = schema.get("properties").and_then(|p| p.as_object()) else {
+            return false;
+        };
+
+        if args.is_null() {
+            let mut filled = Value::Object(serde_json::Map::new());
+            let changed = Self::apply_schema_defaults(&mut filled, schema);
+            if changed {
+                *args = filled;
+            }
+            return changed;
+        }
+        let Some(args_obj) = args.as_object_mut() else {
+            return false;
+        };
+
+        let mut changed = false;
+        for (name, prop_schema) in properties {
+            match args_obj.get_mut(name) {
+                Some(value) if value.is_object() => {
+                    changed |= Self::apply_schema_defaults(value, prop_schema);
+                }
+                Some(_) => {}
+                None => {
+                    if let Some(default) = prop_schema.get("default") {
+                        args_obj.insert(name.clone(), default.clone());
+                        changed = true;
+                    }
+                }
+            }
+        }
+        changed
+    }
+
+    /// Add parameter combination constraints to a tool's input schema
+    ///
+    /// Each `requires` group must be given together and becomes mutual
+    /// `dependentRequired` entries. Each `exclusive` group allows at most one
+    /// of its parameters and becomes a `oneOf` with one branch per parameter
+    /// plus a branch for none of them; several groups are combined under `allOf`.
+    pub fn apply_parameter_constraints(
+        schema: &mut Value,
+        requires: &[&[&str]],
+        exclusive: &[&[&str]],
+    ) {
+        let Some(schema_obj) = schema.as_object_mut() else {
+            return;
+        };
+
+        if !requires.is_empty() {
+            let dependent = schema_obj
+                .entry("dependentRequired")
+                .or_insert_with(|| Value::Object(serde_json::Map::new()));
+            if let Some(dependent) = dependent.as_object_mut() {
+                for group in requires {
+                    for name in *group {
+                        let entry = dependent
+                            .entry(name.to_string())
+                            .or_insert_with(|| Value::Array(Vec::new()));
+                        if let Some(entry) = entry.as_array_mut() {
+                            for other in group.iter().filter(|other| *other != name) {
+                                if !entry.iter().any(|existing| existing == other) {
+                                    entry.push(Value::from(*other));
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+        }
+
+        let mut one_ofs: Vec<Value> = exclusive
+            .iter()
+            .map(|group| {
+                let mut branches: Vec<Value> = group
+                    .iter()
+                    .map(|name| serde_json::json!({ "required": [name] }))
+                    .collect();
+                branches.push(serde_json::json!({ "not": { "anyOf": branches.clone() } }));
+                Value::Array(branches)
+            })
+            .collect();
+        match one_ofs.len() {
+            0 => {}
+            1 => {
+                schema_obj.insert("oneOf".to_string(), one_ofs.remove(0));
+            }
+            _ => {
+                let all_of = one_ofs
+                    .into_iter()
+                    .map(|one_of| serde_json::json!({ "oneOf": one_of }))
+                    .collect();
+                schema_obj.insert("allOf".to_string(), Value::Array(all_of));
+            }
+        }
+    }
+
+    /// Check tool arguments against parameter combination constraints
+    ///
+    /// A parameter counts as given when it is present and not `null`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a `requires` group is only partly given, or if
+    /// more than one parameter of an `exclusive` group is given
+    pub fn validate_parameter_constraints(
+        args: &serde_json::Map<String, Value>,
+        requires: &[&[&str]],
+        exclusive: &[&[&str]],
+    ) -> Result<()> {
+        let given = |name: &str| args.get(name).is_some_and(|value| !value.is_null());
+
+        for group in requires {
+            if let Some(present) = group.iter().find(|name| given(name))
+                && let Some(missing) = group.iter().find(|name| !given(name))
+            {
+                return Err(Error::validation_error(format!(
+                    "Parameter '{missing}' is required when '{present}' is provided"
+                )));
+            }
+        }
+
+        for group in exclusive {
+            let present: Vec<&str> = group.iter().copied().filter(|name| given(name)).collect();
+            if present.len() > 1 {
+                return Err(Error::validation_error(format!(
+                    "Parameters '{}' are mutually exclusive",
+                    present.join("', '")
+                )));
+            }
+        }
+
+        Ok(())
+    }
+
     /// Validate pagination parameters
     ///
     /// # Errors
@@ -173,6 +372,20 @@ impl Validator {
         Ok(())
     }
 
+    /// Validate a signed pagination cursor, returning the offset to resume at
+    ///
+    /// A missing cursor starts at offset 0.
+    ///
+    /// # Errors
+    ///
+    /// Returns an [`ErrorCode::InvalidCursor`](crate::ErrorCode::InvalidCursor)
+    /// error for malformed, tampered or stale cursors
+    pub fn validate_cursor(cursor: Option<&str>, key_material: &[u8]) -> Result<usize> {
+        cursor.map_or(Ok(0), |token| {
+            crate::Cursor::decode(token, key_material).map(|cursor| cursor.offset)
+        })
+    }
+
     /// Validate prompt name
     ///
     /// # Errors
@@ -205,10 +418,32 @@ impl Validator {
 
     /// Validate structured content against a JSON schema
     ///
+    /// Content is bounded by the default [`StructuredContentLimits`].
+    ///
     /// # Errors
     ///
     /// Returns an error if the content doesn't match the schema or if the schema is invalid
     pub fn validate_structured_content(content: &Value, output_schema: &Value) -> Result<()> {
+        Self::validate_structured_content_with_limits(
+            content,
+            output_schema,
+            &StructuredContentLimits::default(),
+        )
+    }
+
+    /// Validate structured content against a JSON schema within `limits`
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the content exceeds the limits, doesn't match the
+    /// schema, or if the schema is invalid
+    pub fn validate_structured_content_with_limits(
+        content: &Value,
+        output_schema: &Value,
+        limits: &StructuredContentLimits,
+    ) -> Result<()> {
+        limits.check(content)?;
+
         // First validate that the schema itself is valid
         Self::validate_json_schema(output_schema)?;
 
@@ -279,6 +514,71 @@ impl Validator {
         Ok(())
     }
 
+    /// Validate a tool call result against the tool's declared output schema
+    ///
+    /// Results from tools without an `output_schema`, and error results, pass
+    /// unchecked. The error data carries the tool name and the JSON pointer of
+    /// the first offending field under `path`. Structured content is bounded
+    /// by the default [`StructuredContentLimits`].
+    ///
+    /// # Errors
+    ///
+    /// Returns a validation error if the tool declares an output schema but the
+    /// result has no structured content, or the structured content doesn't
+    /// match the schema
+    pub fn validate_tool_output(tool: &Tool, result: &CallToolResult) -> Result<()> {
+        Self::validate_tool_output_with_limits(tool, result, &StructuredContentLimits::default())
+    }
+
+    /// Validate a tool call result against the tool's declared output schema
+    /// within `limits`
+    ///
+    /// # Errors
+    ///
+    /// Returns a validation error if the structured content exceeds the
+    /// limits, or as [`Self::validate_tool_output`] does
+    pub fn validate_tool_output_with_limits(
+        tool: &Tool,
+        result: &CallToolResult,
+        limits: &StructuredContentLimits,
+    ) -> Result<()> {
+        let Some(output_schema) = &tool.output_schema else {
+            return Ok(());
+        };
+        if result.is_error == Some(true) {
+            return Ok(());
+        }
+        let Some(structured_content) = &result.structured_content else {
+            return Err(Error::with_data(
+                ErrorCode::ValidationError,
+                format!(
+                    "Tool '{}' declares an output schema but returned no structured content",
+                    tool.name
+                ),
+                serde_json::json!({ "tool": tool.name, "path": "" }),
+            ));
+        };
+
+        limits.check(structured_content)?;
+        let schema = JSONSchema::compile(output_schema)
+            .map_err(|e| Error::validation_error(format!("Invalid JSON schema: {e}")))?;
+        if let Err(mut errors) = schema.validate(structured_content) {
+            let first = errors.next().expect("failed validation yields an error");
+            let path = first.instance_path.to_string();
+            let location = if path.is_empty() { "/" } else { path.as_str() };
+            return Err(Error::with_data(
+                ErrorCode::ValidationError,
+                format!(
+                    "Tool '{}' output does not match its output schema at '{location}': {first}",
+                    tool.name
+                ),
+                serde_json::json!({ "tool": tool.name, "path": path }),
+            ));
+        }
+
+        Ok(())
+    }
+
     /// Extract validation errors in a user-friendly format
     ///
     /// # Errors
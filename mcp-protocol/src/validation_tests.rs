@@ -528,4 +528,256 @@ mod tests {
         let long_prompt_name = "prompt.".to_string() + &"a".repeat(1000);
         assert!(Validator::validate_prompt_name(&long_prompt_name).is_ok());
     }
+
+    #[test]
+    fn test_apply_schema_defaults_fills_omitted_fields() {
+        let schema = json!({
+            "type": "object",
+            "properties": {
+                "name": {"type": "string", "default": "World"},
+                "count": {"type": "integer", "default": 1},
+                "loud": {"type": "boolean"},
+                "options": {
+                    "type": "object",
+                    "properties": {"format": {"type": "string", "default": "text"}}
+                }
+            }
+        });
+
+        let mut args = json!({"count": 3, "options": {}});
+        assert!(Validator::apply_schema_defaults(&mut args, &schema));
+        assert_eq!(
+            args,
+            json!({"name": "World", "count": 3, "options": {"format": "text"}})
+        );
+
+        // Explicit values (including null) are never overwritten
+        let mut args = json!({"name": null, "count": 5});
+        assert!(!Validator::apply_schema_defaults(&mut args, &schema));
+        assert_eq!(args, json!({"name": null, "count": 5}));
+    }
+
+    #[test]
+    fn test_apply_schema_defaults_null_arguments() {
+        let schema = json!({
+            "type": "object",
+            "properties": {"name": {"type": "string", "default": "World"}}
+        });
+        let mut args = serde_json::Value::Null;
+        assert!(Validator::apply_schema_defaults(&mut args, &schema));
+        assert_eq!(args, json!({"name": "World"}));
+
+        // No defaults declared leaves null arguments untouched
+        let schema = json!({"type": "object", "properties": {"name": {"type": "string"}}});
+        let mut args = serde_json::Value::Null;
+        assert!(!Validator::apply_schema_defaults(&mut args, &schema));
+        assert!(args.is_null());
+    }
+
+    fn nested(depth: usize) -> serde_json::Value {
+        (0..depth).fold(json!("leaf"), |inner, _| json!({ "child": inner }))
+    }
+
+    #[test]
+    fn test_structured_content_limits_bound_depth_and_size() {
+        let schema = json!({"type": "object", "properties": {}});
+        let limits = StructuredContentLimits {
+            max_depth: 8,
+            max_nodes: 50,
+        };
+
+        assert!(
+            Validator::validate_structured_content_with_limits(&nested(8), &schema, &limits)
+                .is_ok()
+        );
+        let error =
+            Validator::validate_structured_content_with_limits(&nested(9), &schema, &limits)
+                .unwrap_err();
+        assert!(error.message.contains("maximum nesting depth of 8"));
+
+        let wide = json!({ "items": (0..100).collect::<Vec<_>>() });
+        let error = Validator::validate_structured_content_with_limits(&wide, &schema, &limits)
+            .unwrap_err();
+        assert!(error.message.contains("maximum of 50 values"));
+    }
+
+    #[test]
+    fn test_structured_content_default_limits_reject_deep_tool_output() {
+        use crate::model::{CallToolResult, Content};
+
+        let schema = json!({"type": "object", "properties": {}});
+        let result = CallToolResult::structured(vec![Content::text("deep")], nested(500));
+        let error = result.validate_structured_content(&schema).unwrap_err();
+        assert!(error.message.contains("maximum nesting depth of 64"));
+
+        let relaxed = StructuredContentLimits {
+            max_depth: 1_000,
+            ..Default::default()
+        };
+        assert!(
+            relaxed.check(&nested(500)).is_ok(),
+            "limits are configurable"
+        );
+        assert!(
+            result
+                .validate_structured_content_with_limits(
+                    &schema,
+                    &StructuredContentLimits::default()
+                )
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn test_parameter_constraints_schema_and_dispatch() {
+        let requires: &[&[&str]] = &[&["start_date", "end_date"]];
+        let exclusive: &[&[&str]] = &[&["id", "name"]];
+
+        let mut schema = json!({"type": "object", "properties": {}});
+        Validator::apply_parameter_constraints(&mut schema, requires, exclusive);
+        assert_eq!(
+            schema["dependentRequired"],
+            json!({"start_date": ["end_date"], "end_date": ["start_date"]})
+        );
+
+        let compiled = jsonschema::JSONSchema::compile(&schema).unwrap();
+        let args = |value: serde_json::Value| value.as_object().unwrap().clone();
+        for (value, valid) in [
+            (json!({}), true),
+            (json!({"id": 1}), true),
+            (json!({"name": "a"}), true),
+            (json!({"id": 1, "name": "a"}), false),
+        ] {
+            assert_eq!(compiled.is_valid(&value), valid, "{value}");
+            assert_eq!(
+                Validator::validate_parameter_constraints(
+                    &args(value.clone()),
+                    requires,
+                    exclusive
+                )
+                .is_ok(),
+                valid,
+                "{value}"
+            );
+        }
+
+        let error = Validator::validate_parameter_constraints(
+            &args(json!({"start_date": "2024-01-01", "end_date": null})),
+            requires,
+            exclusive,
+        )
+        .unwrap_err();
+        assert_eq!(
+            error.message,
+            "Parameter 'end_date' is required when 'start_date' is provided"
+        );
+        let error = Validator::validate_parameter_constraints(
+            &args(json!({"id": 1, "name": "a"})),
+            requires,
+            exclusive,
+        )
+        .unwrap_err();
+        assert_eq!(
+            error.message,
+            "Parameters 'id', 'name' are mutually exclusive"
+        );
+
+        let mut schema = json!({"type": "object"});
+        Validator::apply_parameter_constraints(&mut schema, &[], &[&["a", "b"], &["c", "d"]]);
+        assert!(schema.get("oneOf").is_none());
+        assert_eq!(schema["allOf"].as_array().unwrap().len(), 2);
+    }
+
+    #[test]
+    fn test_signed_cursor_round_trip_and_rejection() {
+        use crate::{Cursor, ErrorCode};
+        use base64::Engine;
+
+        let key = b"server-secret:v2";
+        let token = Cursor::encode(50, key);
+        assert!(!token.contains('/') && !token.contains('='));
+
+        let cursor = Cursor::decode(&token, key).unwrap();
+        assert_eq!(cursor.offset, 50);
+        assert_eq!(Validator::validate_cursor(Some(&token), key).unwrap(), 50);
+        assert_eq!(Validator::validate_cursor(None, key).unwrap(), 0);
+
+        // Flip the offset bytes without re-signing
+        let engine = base64::engine::general_purpose::URL_SAFE_NO_PAD;
+        let mut bytes = engine.decode(&token).unwrap();
+        bytes[16] ^= 0x01;
+        let tampered = engine.encode(bytes);
+        let error = Validator::validate_cursor(Some(&tampered), key).unwrap_err();
+        assert_eq!(error.code, ErrorCode::InvalidCursor);
+        assert_eq!(error.message, "Pagination cursor signature mismatch");
+
+        // Issued by a previous server version
+        let stale = Cursor::encode(50, b"server-secret:v1");
+        let error = Validator::validate_cursor(Some(&stale), key).unwrap_err();
+        assert_eq!(error.code, ErrorCode::InvalidCursor);
+        assert!(error.message.contains("stale"));
+
+        for garbage in ["", "page-2", "not base64!"] {
+            let error = Validator::validate_cursor(Some(garbage), key).unwrap_err();
+            assert_eq!(error.code, ErrorCode::InvalidCursor);
+            assert_eq!(error.message, "Malformed pagination cursor");
+        }
+        assert_eq!(
+            serde_json::to_value(ErrorCode::InvalidCursor).unwrap(),
+            json!(-32006)
+        );
+    }
+
+    #[test]
+    fn test_validate_tool_output_against_output_schema() {
+        use crate::ErrorCode;
+        use crate::model::{CallToolResult, Content, Tool};
+
+        let mut tool = Tool {
+            name: "stats".to_string(),
+            title: None,
+            description: "Compute statistics".to_string(),
+            input_schema: json!({"type": "object", "properties": {}}),
+            output_schema: None,
+            annotations: None,
+            icons: None,
+            execution: None,
+            _meta: None,
+        };
+        let unstructured = CallToolResult::text("done");
+        assert!(Validator::validate_tool_output(&tool, &unstructured).is_ok());
+
+        tool.output_schema = Some(json!({
+            "type": "object",
+            "properties": {
+                "summary": {
+                    "type": "object",
+                    "properties": { "count": { "type": "integer" } },
+                    "required": ["count"]
+                }
+            },
+            "required": ["summary"]
+        }));
+        let valid =
+            CallToolResult::structured(vec![Content::text("ok")], json!({"summary": {"count": 3}}));
+        assert!(Validator::validate_tool_output(&tool, &valid).is_ok());
+
+        let wrong_type = CallToolResult::structured(
+            vec![Content::text("ok")],
+            json!({"summary": {"count": "three"}}),
+        );
+        let error = Validator::validate_tool_output(&tool, &wrong_type).unwrap_err();
+        assert_eq!(error.code, ErrorCode::ValidationError);
+        assert!(error.message.contains("at '/summary/count'"));
+        assert_eq!(error.data.as_ref().unwrap()["path"], "/summary/count");
+        assert_eq!(error.data.as_ref().unwrap()["tool"], "stats");
+
+        let error = Validator::validate_tool_output(&tool, &unstructured).unwrap_err();
+        assert_eq!(error.code, ErrorCode::ValidationError);
+        assert!(error.message.contains("returned no structured content"));
+
+        // Error results aren't held to the output schema
+        let failed = CallToolResult::error_text("boom");
+        assert!(Validator::validate_tool_output(&tool, &failed).is_ok());
+    }
 }
@@ -0,0 +1,81 @@
+//! Request timeouts scaled by request size
+//!
+//! A 10-byte request and a 10MB one shouldn't share a timeout: one that fits
+//! the large payload leaves small requests loosely bounded, and one that
+//! fits small requests cuts off large legitimate payloads. With
+//! [`AdaptiveTimeoutConfig`] every request gets a base allowance plus a
+//! per-KiB allowance for the serialized size of its params, capped at a
+//! maximum.
+
+use pulseengine_mcp_protocol::{Error, ErrorCode};
+use serde::{Deserialize, Serialize};
+use std::io;
+use std::time::Duration;
+
+/// Configuration for size-based request timeouts
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AdaptiveTimeoutConfig {
+    /// Timeout of a request without params, in milliseconds
+    pub base_ms: u64,
+    /// Extra time granted per KiB of serialized params, in milliseconds
+    /// (prorated for partial KiBs)
+    pub per_kib_ms: u64,
+    /// Upper bound on any request's timeout, in milliseconds
+    pub max_ms: u64,
+}
+
+impl Default for AdaptiveTimeoutConfig {
+    fn default() -> Self {
+        Self {
+            base_ms: 10_000,
+            per_kib_ms: 1,
+            max_ms: 120_000,
+        }
+    }
+}
+
+impl AdaptiveTimeoutConfig {
+    /// Timeout for a request whose params serialize to `request_bytes`
+    pub fn timeout_for(&self, request_bytes: usize) -> Duration {
+        let base = Duration::from_millis(self.base_ms);
+        let extra_nanos = u128::from(self.per_kib_ms) * 1_000_000 * request_bytes as u128 / 1024;
+        let extra = Duration::from_nanos(u64::try_from(extra_nanos).unwrap_or(u64::MAX));
+        base.saturating_add(extra)
+            .min(Duration::from_millis(self.max_ms))
+    }
+}
+
+/// Length of `value` serialized as compact JSON, without allocating it
+pub fn serialized_size(value: &serde_json::Value) -> usize {
+    struct Counter(usize);
+
+    impl io::Write for Counter {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            self.0 += buf.len();
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    let mut counter = Counter(0);
+    // Writing to the counter can't fail
+    let _ = serde_json::to_writer(&mut counter, value);
+    counter.0
+}
+
+/// Error for a request cancelled after exceeding its size-based time limit
+pub fn request_timeout_error(method: &str, limit: Duration, request_bytes: usize) -> Error {
+    let limit_ms = limit.as_millis() as u64;
+    Error::with_data(
+        ErrorCode::InternalError,
+        format!("Request '{method}' timed out after {limit_ms}ms"),
+        serde_json::json!({
+            "method": method,
+            "timeoutMs": limit_ms,
+            "requestBytes": request_bytes,
+        }),
+    )
+}
@@ -0,0 +1,57 @@
+//! Tests for size-based request timeouts
+
+use crate::adaptive_timeout::*;
+use std::time::Duration;
+
+fn config() -> AdaptiveTimeoutConfig {
+    AdaptiveTimeoutConfig {
+        base_ms: 1_000,
+        per_kib_ms: 2,
+        max_ms: 30_000,
+    }
+}
+
+#[test]
+fn test_small_request_gets_about_the_base_timeout() {
+    let config = config();
+    assert_eq!(config.timeout_for(0), Duration::from_secs(1));
+    // 10 bytes earn a prorated ~20µs on top of the base
+    let small = config.timeout_for(10);
+    assert!(small > Duration::from_secs(1));
+    assert!(small < Duration::from_millis(1_001));
+}
+
+#[test]
+fn test_large_request_gets_proportionally_more_time() {
+    let config = config();
+    // 1 MiB earns 1024 KiB * 2ms on top of the base
+    assert_eq!(
+        config.timeout_for(1024 * 1024),
+        Duration::from_millis(3_048)
+    );
+    // Twice the size earns twice the allowance
+    assert_eq!(
+        config.timeout_for(2 * 1024 * 1024),
+        Duration::from_millis(5_096)
+    );
+}
+
+#[test]
+fn test_timeout_is_capped() {
+    let config = config();
+    assert_eq!(
+        config.timeout_for(20 * 1024 * 1024),
+        Duration::from_secs(30)
+    );
+    assert_eq!(config.timeout_for(usize::MAX), Duration::from_secs(30));
+}
+
+#[test]
+fn test_serialized_size_matches_compact_json() {
+    let value = serde_json::json!({"name": "echo", "arguments": {"text": "héllo", "n": [1, 2]}});
+    assert_eq!(
+        serialized_size(&value),
+        serde_json::to_vec(&value).unwrap().len()
+    );
+    assert_eq!(serialized_size(&serde_json::Value::Null), 4);
+}
@@ -1,10 +1,14 @@
 //! Backend trait for pluggable MCP implementations
 
 use async_trait::async_trait;
+use futures::stream::{self, BoxStream, StreamExt};
 use pulseengine_mcp_protocol::*;
 use std::error::Error as StdError;
 use thiserror::Error;
 
+/// Content chunks of a streaming tool call, in the order they are produced
+pub type ToolContentStream<E> = BoxStream<'static, std::result::Result<Content, E>>;
+
 /// Error type for backend operations
 #[derive(Debug, Error)]
 pub enum BackendError {
@@ -25,6 +29,10 @@ pub enum BackendError {
 
     #[error("Custom error: {0}")]
     Custom(Box<dyn StdError + Send + Sync>),
+
+    /// Error the backend tagged with the MCP error code clients should see
+    #[error("{message}")]
+    Coded { code: ErrorCode, message: String },
 }
 
 impl BackendError {
@@ -47,6 +55,32 @@ impl BackendError {
     pub fn custom(error: impl StdError + Send + Sync + 'static) -> Self {
         Self::Custom(Box::new(error))
     }
+
+    /// Error reported to clients with an explicit MCP error code
+    pub fn with_code(code: ErrorCode, msg: impl Into<String>) -> Self {
+        Self::Coded {
+            code,
+            message: msg.into(),
+        }
+    }
+
+    /// Error caused by the client's request, reported as `invalid_params`
+    pub fn client_error(msg: impl Into<String>) -> Self {
+        Self::with_code(ErrorCode::InvalidParams, msg)
+    }
+
+    /// MCP error code this error is reported with
+    pub fn error_code(&self) -> ErrorCode {
+        match self {
+            BackendError::Configuration(_) => ErrorCode::InvalidParams,
+            BackendError::NotSupported(_) => ErrorCode::MethodNotFound,
+            BackendError::Coded { code, .. } => *code,
+            BackendError::NotInitialized
+            | BackendError::Connection(_)
+            | BackendError::Internal(_)
+            | BackendError::Custom(_) => ErrorCode::InternalError,
+        }
+    }
 }
 
 /// Convert BackendError to MCP protocol Error
@@ -61,6 +95,7 @@ impl From<BackendError> for Error {
             BackendError::NotSupported(msg) => Error::method_not_found(msg),
             BackendError::Internal(msg) => Error::internal_error(msg),
             BackendError::Custom(err) => Error::internal_error(err.to_string()),
+            BackendError::Coded { code, message } => Error::new(code, message),
         }
     }
 }
@@ -97,6 +132,16 @@ pub trait McpBackend: Send + Sync + Clone {
     /// Called regularly for monitoring and health endpoints.
     async fn health_check(&self) -> std::result::Result<(), Self::Error>;
 
+    /// Soft memory budget for a tool, in bytes
+    ///
+    /// When the server has a memory guard configured, a tool with a budget is
+    /// aborted if process memory grows past it while the tool runs. The
+    /// default declares no budget.
+    fn tool_memory_budget(&self, tool_name: &str) -> Option<u64> {
+        let _ = tool_name;
+        None
+    }
+
     // Tool Management
 
     /// List available tools with pagination
@@ -111,6 +156,54 @@ pub trait McpBackend: Send + Sync + Clone {
         request: CallToolRequestParam,
     ) -> std::result::Result<CallToolResult, Self::Error>;
 
+    /// Whether a tool produces its output incrementally
+    ///
+    /// The handler calls [`call_tool_streaming`](Self::call_tool_streaming)
+    /// instead of [`call_tool`](Self::call_tool) for tools that return `true`.
+    /// The default streams no tools.
+    fn streams_tool(&self, tool_name: &str) -> bool {
+        let _ = tool_name;
+        false
+    }
+
+    /// Execute a tool, yielding its content as it is produced
+    ///
+    /// Over streamable HTTP each chunk is forwarded to the client as it
+    /// arrives; other transports receive the buffered result. The stream is
+    /// dropped when the client disconnects, so implementations should stop
+    /// their work on drop. The default yields the content of
+    /// [`call_tool`](Self::call_tool) once the call finishes.
+    async fn call_tool_streaming(
+        &self,
+        request: CallToolRequestParam,
+    ) -> std::result::Result<ToolContentStream<Self::Error>, Self::Error> {
+        let result = self.call_tool(request).await?;
+        Ok(stream::iter(result.content.into_iter().map(Ok)).boxed())
+    }
+
+    /// Whether unknown tool names go to [`fallback_tool`](Self::fallback_tool)
+    ///
+    /// The default has no fallback, so calls to tools missing from
+    /// [`list_tools`](Self::list_tools) reach [`call_tool`](Self::call_tool)
+    /// as before.
+    fn has_fallback_tool(&self) -> bool {
+        false
+    }
+
+    /// Execute a call to a tool that no listed tool matches
+    ///
+    /// Lets dynamic or proxying backends serve tools they don't declare up
+    /// front. Only called when [`has_fallback_tool`](Self::has_fallback_tool)
+    /// returns `true`.
+    async fn fallback_tool(
+        &self,
+        name: &str,
+        arguments: Option<serde_json::Value>,
+    ) -> std::result::Result<CallToolResult, Self::Error> {
+        let _ = arguments;
+        Err(BackendError::not_supported(format!("Unknown tool: {name}")).into())
+    }
+
     // Resource Management
 
     /// List available resources with pagination
@@ -125,6 +218,16 @@ pub trait McpBackend: Send + Sync + Clone {
         request: ReadResourceRequestParam,
     ) -> std::result::Result<ReadResourceResult, Self::Error>;
 
+    /// Permission a caller needs to read a resource
+    ///
+    /// The handler checks it against the caller's `AuthContext` before
+    /// calling [`read_resource`](Self::read_resource), and denies callers
+    /// without it. The default requires no permission.
+    fn resource_permission(&self, uri: &str) -> Option<String> {
+        let _ = uri;
+        None
+    }
+
     /// List resource templates (optional)
     async fn list_resource_templates(
         &self,
@@ -154,14 +257,14 @@ pub trait McpBackend: Send + Sync + Clone {
     // Subscription Management (optional)
 
     /// Subscribe to resource updates
-    /// Default implementation accepts all subscriptions but does not track them
-    /// Override this method to implement custom subscription logic
+    /// Default implementation accepts all subscriptions; the server tracks
+    /// them per session in a [`ResourceSubscriptionManager`](crate::ResourceSubscriptionManager)
+    /// Override this method to validate subscriptions or start watching
     async fn subscribe(
         &self,
         _request: SubscribeRequestParam,
     ) -> std::result::Result<(), Self::Error> {
         // Default: accept subscription silently
-        // Notification delivery requires additional infrastructure
         Ok(())
     }
 
@@ -178,7 +281,13 @@ pub trait McpBackend: Send + Sync + Clone {
 
     // Auto-completion (optional)
 
-    /// Complete tool or resource names
+    /// Suggest values for a prompt or resource template argument
+    ///
+    /// Answers `completion/complete`; `request.argument.value` holds what the
+    /// user has typed so far. Backends implementing this should advertise
+    /// [`ServerCapabilities::completions`] in
+    /// [`get_server_info`](Self::get_server_info). The default suggests
+    /// nothing.
     async fn complete(
         &self,
         request: CompleteRequestParam,
@@ -231,6 +340,15 @@ pub trait McpBackend: Send + Sync + Clone {
         Ok(())
     }
 
+    /// Called after [`on_startup`](Self::on_startup) and before the server
+    /// accepts traffic, to prefetch caches so the first requests are fast
+    ///
+    /// With [`WarmupMode::Background`](crate::server::WarmupMode) traffic is
+    /// accepted meanwhile and only readiness waits for warm-up.
+    async fn warmup(&self) -> std::result::Result<(), Self::Error> {
+        Ok(())
+    }
+
     /// Called when the server is shutting down
     async fn on_shutdown(&self) -> std::result::Result<(), Self::Error> {
         Ok(())
@@ -286,6 +404,12 @@ pub trait SimpleBackend: Send + Sync + Clone {
         &self,
         request: CallToolRequestParam,
     ) -> std::result::Result<CallToolResult, Self::Error>;
+
+    /// Soft memory budget for a tool; see [`McpBackend::tool_memory_budget`]
+    fn tool_memory_budget(&self, tool_name: &str) -> Option<u64> {
+        let _ = tool_name;
+        None
+    }
 }
 
 /// Blanket implementation to convert SimpleBackend to McpBackend
@@ -309,6 +433,10 @@ where
         T::health_check(self).await
     }
 
+    fn tool_memory_budget(&self, tool_name: &str) -> Option<u64> {
+        T::tool_memory_budget(self, tool_name)
+    }
+
     async fn list_tools(
         &self,
         request: PaginatedRequestParam,
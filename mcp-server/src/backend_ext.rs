@@ -0,0 +1,910 @@
+//! Composable wrappers for layering cross-cutting concerns onto a backend
+//!
+//! [`BackendExt`] adds combinators that wrap any [`McpBackend`] in a new
+//! backend, so caching, logging and error mapping can be stacked by
+//! composition instead of being built into one monolithic implementation:
+//!
+//! ```rust,ignore
+//! let backend = MyBackend::initialize(config)
+//!     .await?
+//!     .with_cache(Duration::from_secs(30))
+//!     .with_logging("my-backend");
+//! ```
+//!
+//! The outermost combinator runs first on every call. Wrappers are backends
+//! themselves; their `Config` pairs the inner backend's configuration with
+//! the layer's own settings so they can also be created via `initialize`.
+
+use crate::backend::{BackendError, McpBackend, ToolContentStream};
+use async_trait::async_trait;
+use futures::StreamExt;
+use pulseengine_mcp_protocol::*;
+use serde_json::Value;
+use std::collections::{HashMap, HashSet};
+use std::error::Error as StdError;
+use std::fmt::Display;
+use std::future::Future;
+use std::marker::PhantomData;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tracing::{debug, warn};
+
+/// Combinators for wrapping a backend in additional layers
+pub trait BackendExt: McpBackend + Sized {
+    /// Cache list results and resource reads for `ttl`
+    fn with_cache(self, ttl: Duration) -> CachedBackend<Self> {
+        CachedBackend::new(self, ttl)
+    }
+
+    /// Log every backend call with its duration and outcome under `label`
+    fn with_logging(self, label: impl Into<String>) -> LoggingBackend<Self> {
+        LoggingBackend::new(self, label)
+    }
+
+    /// Convert every error returned by the backend with `f`
+    fn map_error<E, F>(self, f: F) -> MapErrorBackend<Self, E, F>
+    where
+        E: StdError + Send + Sync + Into<Error> + From<BackendError> + 'static,
+        F: Fn(Self::Error) -> E + Clone + Send + Sync + 'static,
+    {
+        MapErrorBackend::new(self, f)
+    }
+}
+
+impl<B: McpBackend> BackendExt for B {}
+
+/// Results of each kind kept by a [`CachedBackend`] unless configured
+pub const DEFAULT_CACHE_MAX_ENTRIES: usize = 1024;
+
+/// Time-limited, size-bounded cache for one kind of backend result
+struct TtlCache<T> {
+    ttl: Duration,
+    max_entries: usize,
+    entries: Mutex<HashMap<String, (Instant, T)>>,
+}
+
+impl<T: Clone> TtlCache<T> {
+    fn new(ttl: Duration, max_entries: usize) -> Self {
+        Self {
+            ttl,
+            max_entries,
+            entries: Mutex::new(HashMap::new()),
+        }
+    }
+
+    async fn get_or_load<E, Fut>(&self, key: String, load: Fut) -> std::result::Result<T, E>
+    where
+        Fut: Future<Output = std::result::Result<T, E>>,
+    {
+        self.get_or_load_if(key, load, |_| true).await
+    }
+
+    /// Like `get_or_load`, but only stores loaded values accepted by `keep`
+    async fn get_or_load_if<E, Fut>(
+        &self,
+        key: String,
+        load: Fut,
+        keep: impl FnOnce(&T) -> bool,
+    ) -> std::result::Result<T, E>
+    where
+        Fut: Future<Output = std::result::Result<T, E>>,
+    {
+        if let Some((stored_at, value)) = self.entries.lock().unwrap().get(&key)
+            && stored_at.elapsed() < self.ttl
+        {
+            return Ok(value.clone());
+        }
+
+        let value = load.await?;
+        if keep(&value) && self.max_entries > 0 {
+            let mut entries = self.entries.lock().unwrap();
+            entries.retain(|_, (stored_at, _)| stored_at.elapsed() < self.ttl);
+            // Make room by evicting the oldest entry
+            if entries.len() >= self.max_entries
+                && !entries.contains_key(&key)
+                && let Some(oldest) = entries
+                    .iter()
+                    .min_by_key(|(_, (stored_at, _))| *stored_at)
+                    .map(|(key, _)| key.clone())
+            {
+                entries.remove(&oldest);
+            }
+            entries.insert(key, (Instant::now(), value.clone()));
+        }
+        Ok(value)
+    }
+
+    fn len(&self) -> usize {
+        self.entries.lock().unwrap().len()
+    }
+
+    fn clear(&self) {
+        self.entries.lock().unwrap().clear();
+    }
+}
+
+struct Caches {
+    tools: TtlCache<ListToolsResult>,
+    resources: TtlCache<ListResourcesResult>,
+    resource_templates: TtlCache<ListResourceTemplatesResult>,
+    prompts: TtlCache<ListPromptsResult>,
+    reads: TtlCache<ReadResourceResult>,
+    tool_calls: TtlCache<CallToolResult>,
+}
+
+/// Backend wrapper caching list results and resource reads
+///
+/// Tool calls are cached only for tools opted in with
+/// [`CachedBackend::with_cached_tools`]; prompt rendering and all other
+/// operations always reach the inner backend. Clones share the same cache.
+#[derive(Clone)]
+pub struct CachedBackend<B> {
+    inner: B,
+    caches: Arc<Caches>,
+    cached_tools: Arc<HashSet<String>>,
+}
+
+impl Caches {
+    fn new(ttl: Duration, max_entries: usize) -> Self {
+        Self {
+            tools: TtlCache::new(ttl, max_entries),
+            resources: TtlCache::new(ttl, max_entries),
+            resource_templates: TtlCache::new(ttl, max_entries),
+            prompts: TtlCache::new(ttl, max_entries),
+            reads: TtlCache::new(ttl, max_entries),
+            tool_calls: TtlCache::new(ttl, max_entries),
+        }
+    }
+}
+
+impl<B: McpBackend> CachedBackend<B> {
+    pub fn new(inner: B, ttl: Duration) -> Self {
+        Self {
+            inner,
+            caches: Arc::new(Caches::new(ttl, DEFAULT_CACHE_MAX_ENTRIES)),
+            cached_tools: Arc::new(HashSet::new()),
+        }
+    }
+
+    /// Keep at most `max_entries` results of each kind
+    ///
+    /// Expired results are dropped whenever a new one is stored; when the
+    /// cache is still full, the oldest result is evicted. Resource reads and
+    /// tool calls are keyed by client-supplied URIs and arguments, so this
+    /// bounds the memory clients can make the cache use. Drops anything
+    /// cached so far.
+    pub fn with_max_entries(mut self, max_entries: usize) -> Self {
+        self.caches = Arc::new(Caches::new(self.caches.tools.ttl, max_entries));
+        self
+    }
+
+    /// Also cache successful results of these tools
+    ///
+    /// Only suitable for tools without side effects whose result depends on
+    /// their arguments alone. Results are keyed by the tool name and the
+    /// [canonical form](canonicalize_json) of the arguments, so argument
+    /// objects differing only in key order or number formatting share an
+    /// entry. Results flagged `is_error` are never cached.
+    pub fn with_cached_tools<I, S>(mut self, tools: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.cached_tools = Arc::new(tools.into_iter().map(Into::into).collect());
+        self
+    }
+
+    /// Drop all cached results, e.g. after the backend's data changed
+    pub fn invalidate(&self) {
+        self.caches.tools.clear();
+        self.caches.resources.clear();
+        self.caches.resource_templates.clear();
+        self.caches.prompts.clear();
+        self.caches.reads.clear();
+        self.caches.tool_calls.clear();
+    }
+
+    /// Number of results currently cached, including expired ones not yet
+    /// dropped
+    pub fn cached_entries(&self) -> usize {
+        let caches = &self.caches;
+        caches.tools.len()
+            + caches.resources.len()
+            + caches.resource_templates.len()
+            + caches.prompts.len()
+            + caches.reads.len()
+            + caches.tool_calls.len()
+    }
+
+    /// The wrapped backend
+    pub fn inner(&self) -> &B {
+        &self.inner
+    }
+}
+
+fn cursor_key(request: &PaginatedRequestParam) -> String {
+    request.cursor.clone().unwrap_or_default()
+}
+
+/// Missing arguments key the same entry as an empty object
+fn tool_call_key(request: &CallToolRequestParam) -> String {
+    let arguments = match &request.arguments {
+        None | Some(Value::Null) => Value::Object(Default::default()),
+        Some(arguments) => arguments.clone(),
+    };
+    format!("{}\n{}", request.name, canonicalize_json(&arguments))
+}
+
+#[async_trait]
+impl<B: McpBackend> McpBackend for CachedBackend<B> {
+    type Error = B::Error;
+    type Config = (B::Config, Duration);
+
+    async fn initialize(config: Self::Config) -> std::result::Result<Self, Self::Error> {
+        let (inner, ttl) = config;
+        Ok(Self::new(B::initialize(inner).await?, ttl))
+    }
+
+    fn get_server_info(&self) -> ServerInfo {
+        self.inner.get_server_info()
+    }
+
+    fn tool_memory_budget(&self, tool_name: &str) -> Option<u64> {
+        self.inner.tool_memory_budget(tool_name)
+    }
+
+    async fn health_check(&self) -> std::result::Result<(), Self::Error> {
+        self.inner.health_check().await
+    }
+
+    async fn list_tools(
+        &self,
+        request: PaginatedRequestParam,
+    ) -> std::result::Result<ListToolsResult, Self::Error> {
+        self.caches
+            .tools
+            .get_or_load(cursor_key(&request), self.inner.list_tools(request))
+            .await
+    }
+
+    async fn call_tool(
+        &self,
+        request: CallToolRequestParam,
+    ) -> std::result::Result<CallToolResult, Self::Error> {
+        if !self.cached_tools.contains(&request.name) {
+            return self.inner.call_tool(request).await;
+        }
+        self.caches
+            .tool_calls
+            .get_or_load_if(
+                tool_call_key(&request),
+                self.inner.call_tool(request),
+                |result| result.is_error != Some(true),
+            )
+            .await
+    }
+
+    fn streams_tool(&self, tool_name: &str) -> bool {
+        self.inner.streams_tool(tool_name)
+    }
+
+    fn resource_permission(&self, uri: &str) -> Option<String> {
+        self.inner.resource_permission(uri)
+    }
+
+    fn has_fallback_tool(&self) -> bool {
+        self.inner.has_fallback_tool()
+    }
+
+    async fn fallback_tool(
+        &self,
+        name: &str,
+        arguments: Option<Value>,
+    ) -> std::result::Result<CallToolResult, Self::Error> {
+        self.inner.fallback_tool(name, arguments).await
+    }
+
+    async fn call_tool_streaming(
+        &self,
+        request: CallToolRequestParam,
+    ) -> std::result::Result<ToolContentStream<Self::Error>, Self::Error> {
+        self.inner.call_tool_streaming(request).await
+    }
+
+    async fn list_resources(
+        &self,
+        request: PaginatedRequestParam,
+    ) -> std::result::Result<ListResourcesResult, Self::Error> {
+        self.caches
+            .resources
+            .get_or_load(cursor_key(&request), self.inner.list_resources(request))
+            .await
+    }
+
+    async fn read_resource(
+        &self,
+        request: ReadResourceRequestParam,
+    ) -> std::result::Result<ReadResourceResult, Self::Error> {
+        self.caches
+            .reads
+            .get_or_load(request.uri.clone(), self.inner.read_resource(request))
+            .await
+    }
+
+    async fn list_resource_templates(
+        &self,
+        request: PaginatedRequestParam,
+    ) -> std::result::Result<ListResourceTemplatesResult, Self::Error> {
+        self.caches
+            .resource_templates
+            .get_or_load(
+                cursor_key(&request),
+                self.inner.list_resource_templates(request),
+            )
+            .await
+    }
+
+    async fn list_prompts(
+        &self,
+        request: PaginatedRequestParam,
+    ) -> std::result::Result<ListPromptsResult, Self::Error> {
+        self.caches
+            .prompts
+            .get_or_load(cursor_key(&request), self.inner.list_prompts(request))
+            .await
+    }
+
+    async fn get_prompt(
+        &self,
+        request: GetPromptRequestParam,
+    ) -> std::result::Result<GetPromptResult, Self::Error> {
+        self.inner.get_prompt(request).await
+    }
+
+    async fn subscribe(
+        &self,
+        request: SubscribeRequestParam,
+    ) -> std::result::Result<(), Self::Error> {
+        self.inner.subscribe(request).await
+    }
+
+    async fn unsubscribe(
+        &self,
+        request: UnsubscribeRequestParam,
+    ) -> std::result::Result<(), Self::Error> {
+        self.inner.unsubscribe(request).await
+    }
+
+    async fn complete(
+        &self,
+        request: CompleteRequestParam,
+    ) -> std::result::Result<CompleteResult, Self::Error> {
+        self.inner.complete(request).await
+    }
+
+    async fn elicit(
+        &self,
+        request: ElicitationRequestParam,
+    ) -> std::result::Result<ElicitationResult, Self::Error> {
+        self.inner.elicit(request).await
+    }
+
+    async fn set_level(
+        &self,
+        request: SetLevelRequestParam,
+    ) -> std::result::Result<(), Self::Error> {
+        self.inner.set_level(request).await
+    }
+
+    async fn on_startup(&self) -> std::result::Result<(), Self::Error> {
+        self.inner.on_startup().await
+    }
+
+    async fn warmup(&self) -> std::result::Result<(), Self::Error> {
+        self.inner.warmup().await
+    }
+
+    async fn on_shutdown(&self) -> std::result::Result<(), Self::Error> {
+        self.inner.on_shutdown().await
+    }
+
+    async fn on_client_connect(
+        &self,
+        client_info: &Implementation,
+    ) -> std::result::Result<(), Self::Error> {
+        self.inner.on_client_connect(client_info).await
+    }
+
+    async fn on_client_disconnect(
+        &self,
+        client_info: &Implementation,
+    ) -> std::result::Result<(), Self::Error> {
+        self.inner.on_client_disconnect(client_info).await
+    }
+
+    async fn handle_custom_method(
+        &self,
+        method: &str,
+        params: serde_json::Value,
+    ) -> std::result::Result<serde_json::Value, Self::Error> {
+        self.inner.handle_custom_method(method, params).await
+    }
+}
+
+/// Backend wrapper logging every call with its duration and outcome
+#[derive(Clone)]
+pub struct LoggingBackend<B> {
+    inner: B,
+    label: Arc<str>,
+}
+
+impl<B: McpBackend> LoggingBackend<B> {
+    pub fn new(inner: B, label: impl Into<String>) -> Self {
+        Self {
+            inner,
+            label: Arc::from(label.into()),
+        }
+    }
+
+    /// The wrapped backend
+    pub fn inner(&self) -> &B {
+        &self.inner
+    }
+
+    async fn logged<T, E: Display>(
+        &self,
+        operation: &'static str,
+        call: impl Future<Output = std::result::Result<T, E>>,
+    ) -> std::result::Result<T, E> {
+        let start = Instant::now();
+        let result = call.await;
+        let duration_ms = start.elapsed().as_millis();
+        match &result {
+            Ok(_) => debug!(
+                backend = %self.label,
+                operation,
+                duration_ms,
+                "Backend call succeeded"
+            ),
+            Err(e) => warn!(
+                backend = %self.label,
+                operation,
+                duration_ms,
+                error = %e,
+                "Backend call failed"
+            ),
+        }
+        result
+    }
+}
+
+#[async_trait]
+impl<B: McpBackend> McpBackend for LoggingBackend<B> {
+    type Error = B::Error;
+    type Config = (B::Config, String);
+
+    async fn initialize(config: Self::Config) -> std::result::Result<Self, Self::Error> {
+        let (inner, label) = config;
+        Ok(Self::new(B::initialize(inner).await?, label))
+    }
+
+    fn get_server_info(&self) -> ServerInfo {
+        self.inner.get_server_info()
+    }
+
+    fn tool_memory_budget(&self, tool_name: &str) -> Option<u64> {
+        self.inner.tool_memory_budget(tool_name)
+    }
+
+    async fn health_check(&self) -> std::result::Result<(), Self::Error> {
+        self.logged("health_check", self.inner.health_check()).await
+    }
+
+    async fn list_tools(
+        &self,
+        request: PaginatedRequestParam,
+    ) -> std::result::Result<ListToolsResult, Self::Error> {
+        self.logged("list_tools", self.inner.list_tools(request))
+            .await
+    }
+
+    async fn call_tool(
+        &self,
+        request: CallToolRequestParam,
+    ) -> std::result::Result<CallToolResult, Self::Error> {
+        self.logged("call_tool", self.inner.call_tool(request))
+            .await
+    }
+
+    fn streams_tool(&self, tool_name: &str) -> bool {
+        self.inner.streams_tool(tool_name)
+    }
+
+    fn resource_permission(&self, uri: &str) -> Option<String> {
+        self.inner.resource_permission(uri)
+    }
+
+    fn has_fallback_tool(&self) -> bool {
+        self.inner.has_fallback_tool()
+    }
+
+    async fn fallback_tool(
+        &self,
+        name: &str,
+        arguments: Option<Value>,
+    ) -> std::result::Result<CallToolResult, Self::Error> {
+        self.logged("fallback_tool", self.inner.fallback_tool(name, arguments))
+            .await
+    }
+
+    async fn call_tool_streaming(
+        &self,
+        request: CallToolRequestParam,
+    ) -> std::result::Result<ToolContentStream<Self::Error>, Self::Error> {
+        self.logged(
+            "call_tool_streaming",
+            self.inner.call_tool_streaming(request),
+        )
+        .await
+    }
+
+    async fn list_resources(
+        &self,
+        request: PaginatedRequestParam,
+    ) -> std::result::Result<ListResourcesResult, Self::Error> {
+        self.logged("list_resources", self.inner.list_resources(request))
+            .await
+    }
+
+    async fn read_resource(
+        &self,
+        request: ReadResourceRequestParam,
+    ) -> std::result::Result<ReadResourceResult, Self::Error> {
+        self.logged("read_resource", self.inner.read_resource(request))
+            .await
+    }
+
+    async fn list_resource_templates(
+        &self,
+        request: PaginatedRequestParam,
+    ) -> std::result::Result<ListResourceTemplatesResult, Self::Error> {
+        self.logged(
+            "list_resource_templates",
+            self.inner.list_resource_templates(request),
+        )
+        .await
+    }
+
+    async fn list_prompts(
+        &self,
+        request: PaginatedRequestParam,
+    ) -> std::result::Result<ListPromptsResult, Self::Error> {
+        self.logged("list_prompts", self.inner.list_prompts(request))
+            .await
+    }
+
+    async fn get_prompt(
+        &self,
+        request: GetPromptRequestParam,
+    ) -> std::result::Result<GetPromptResult, Self::Error> {
+        self.logged("get_prompt", self.inner.get_prompt(request))
+            .await
+    }
+
+    async fn subscribe(
+        &self,
+        request: SubscribeRequestParam,
+    ) -> std::result::Result<(), Self::Error> {
+        self.logged("subscribe", self.inner.subscribe(request))
+            .await
+    }
+
+    async fn unsubscribe(
+        &self,
+        request: UnsubscribeRequestParam,
+    ) -> std::result::Result<(), Self::Error> {
+        self.logged("unsubscribe", self.inner.unsubscribe(request))
+            .await
+    }
+
+    async fn complete(
+        &self,
+        request: CompleteRequestParam,
+    ) -> std::result::Result<CompleteResult, Self::Error> {
+        self.logged("complete", self.inner.complete(request)).await
+    }
+
+    async fn elicit(
+        &self,
+        request: ElicitationRequestParam,
+    ) -> std::result::Result<ElicitationResult, Self::Error> {
+        self.logged("elicit", self.inner.elicit(request)).await
+    }
+
+    async fn set_level(
+        &self,
+        request: SetLevelRequestParam,
+    ) -> std::result::Result<(), Self::Error> {
+        self.logged("set_level", self.inner.set_level(request))
+            .await
+    }
+
+    async fn on_startup(&self) -> std::result::Result<(), Self::Error> {
+        self.logged("on_startup", self.inner.on_startup()).await
+    }
+
+    async fn warmup(&self) -> std::result::Result<(), Self::Error> {
+        self.logged("warmup", self.inner.warmup()).await
+    }
+
+    async fn on_shutdown(&self) -> std::result::Result<(), Self::Error> {
+        self.logged("on_shutdown", self.inner.on_shutdown()).await
+    }
+
+    async fn on_client_connect(
+        &self,
+        client_info: &Implementation,
+    ) -> std::result::Result<(), Self::Error> {
+        self.logged(
+            "on_client_connect",
+            self.inner.on_client_connect(client_info),
+        )
+        .await
+    }
+
+    async fn on_client_disconnect(
+        &self,
+        client_info: &Implementation,
+    ) -> std::result::Result<(), Self::Error> {
+        self.logged(
+            "on_client_disconnect",
+            self.inner.on_client_disconnect(client_info),
+        )
+        .await
+    }
+
+    async fn handle_custom_method(
+        &self,
+        method: &str,
+        params: serde_json::Value,
+    ) -> std::result::Result<serde_json::Value, Self::Error> {
+        self.logged(
+            "handle_custom_method",
+            self.inner.handle_custom_method(method, params),
+        )
+        .await
+    }
+}
+
+/// Backend wrapper converting every error with a mapping function
+pub struct MapErrorBackend<B, E, F> {
+    inner: B,
+    map: F,
+    _error: PhantomData<fn() -> E>,
+}
+
+impl<B: Clone, E, F: Clone> Clone for MapErrorBackend<B, E, F> {
+    fn clone(&self) -> Self {
+        Self {
+            inner: self.inner.clone(),
+            map: self.map.clone(),
+            _error: PhantomData,
+        }
+    }
+}
+
+impl<B, E, F> MapErrorBackend<B, E, F>
+where
+    B: McpBackend,
+    F: Fn(B::Error) -> E,
+{
+    pub fn new(inner: B, map: F) -> Self {
+        Self {
+            inner,
+            map,
+            _error: PhantomData,
+        }
+    }
+
+    /// The wrapped backend
+    pub fn inner(&self) -> &B {
+        &self.inner
+    }
+}
+
+#[async_trait]
+impl<B, E, F> McpBackend for MapErrorBackend<B, E, F>
+where
+    B: McpBackend,
+    E: StdError + Send + Sync + Into<Error> + From<BackendError> + 'static,
+    F: Fn(B::Error) -> E + Clone + Send + Sync + 'static,
+{
+    type Error = E;
+    type Config = (B::Config, F);
+
+    async fn initialize(config: Self::Config) -> std::result::Result<Self, Self::Error> {
+        let (inner, map) = config;
+        match B::initialize(inner).await {
+            Ok(inner) => Ok(Self::new(inner, map)),
+            Err(e) => Err(map(e)),
+        }
+    }
+
+    fn get_server_info(&self) -> ServerInfo {
+        self.inner.get_server_info()
+    }
+
+    fn tool_memory_budget(&self, tool_name: &str) -> Option<u64> {
+        self.inner.tool_memory_budget(tool_name)
+    }
+
+    async fn health_check(&self) -> std::result::Result<(), Self::Error> {
+        self.inner.health_check().await.map_err(&self.map)
+    }
+
+    async fn list_tools(
+        &self,
+        request: PaginatedRequestParam,
+    ) -> std::result::Result<ListToolsResult, Self::Error> {
+        self.inner.list_tools(request).await.map_err(&self.map)
+    }
+
+    async fn call_tool(
+        &self,
+        request: CallToolRequestParam,
+    ) -> std::result::Result<CallToolResult, Self::Error> {
+        self.inner.call_tool(request).await.map_err(&self.map)
+    }
+
+    fn streams_tool(&self, tool_name: &str) -> bool {
+        self.inner.streams_tool(tool_name)
+    }
+
+    fn resource_permission(&self, uri: &str) -> Option<String> {
+        self.inner.resource_permission(uri)
+    }
+
+    fn has_fallback_tool(&self) -> bool {
+        self.inner.has_fallback_tool()
+    }
+
+    async fn fallback_tool(
+        &self,
+        name: &str,
+        arguments: Option<Value>,
+    ) -> std::result::Result<CallToolResult, Self::Error> {
+        self.inner
+            .fallback_tool(name, arguments)
+            .await
+            .map_err(&self.map)
+    }
+
+    async fn call_tool_streaming(
+        &self,
+        request: CallToolRequestParam,
+    ) -> std::result::Result<ToolContentStream<Self::Error>, Self::Error> {
+        let map = self.map.clone();
+        let chunks = self
+            .inner
+            .call_tool_streaming(request)
+            .await
+            .map_err(&self.map)?;
+        Ok(chunks.map(move |chunk| chunk.map_err(&map)).boxed())
+    }
+
+    async fn list_resources(
+        &self,
+        request: PaginatedRequestParam,
+    ) -> std::result::Result<ListResourcesResult, Self::Error> {
+        self.inner.list_resources(request).await.map_err(&self.map)
+    }
+
+    async fn read_resource(
+        &self,
+        request: ReadResourceRequestParam,
+    ) -> std::result::Result<ReadResourceResult, Self::Error> {
+        self.inner.read_resource(request).await.map_err(&self.map)
+    }
+
+    async fn list_resource_templates(
+        &self,
+        request: PaginatedRequestParam,
+    ) -> std::result::Result<ListResourceTemplatesResult, Self::Error> {
+        self.inner
+            .list_resource_templates(request)
+            .await
+            .map_err(&self.map)
+    }
+
+    async fn list_prompts(
+        &self,
+        request: PaginatedRequestParam,
+    ) -> std::result::Result<ListPromptsResult, Self::Error> {
+        self.inner.list_prompts(request).await.map_err(&self.map)
+    }
+
+    async fn get_prompt(
+        &self,
+        request: GetPromptRequestParam,
+    ) -> std::result::Result<GetPromptResult, Self::Error> {
+        self.inner.get_prompt(request).await.map_err(&self.map)
+    }
+
+    async fn subscribe(
+        &self,
+        request: SubscribeRequestParam,
+    ) -> std::result::Result<(), Self::Error> {
+        self.inner.subscribe(request).await.map_err(&self.map)
+    }
+
+    async fn unsubscribe(
+        &self,
+        request: UnsubscribeRequestParam,
+    ) -> std::result::Result<(), Self::Error> {
+        self.inner.unsubscribe(request).await.map_err(&self.map)
+    }
+
+    async fn complete(
+        &self,
+        request: CompleteRequestParam,
+    ) -> std::result::Result<CompleteResult, Self::Error> {
+        self.inner.complete(request).await.map_err(&self.map)
+    }
+
+    async fn elicit(
+        &self,
+        request: ElicitationRequestParam,
+    ) -> std::result::Result<ElicitationResult, Self::Error> {
+        self.inner.elicit(request).await.map_err(&self.map)
+    }
+
+    async fn set_level(
+        &self,
+        request: SetLevelRequestParam,
+    ) -> std::result::Result<(), Self::Error> {
+        self.inner.set_level(request).await.map_err(&self.map)
+    }
+
+    async fn on_startup(&self) -> std::result::Result<(), Self::Error> {
+        self.inner.on_startup().await.map_err(&self.map)
+    }
+
+    async fn warmup(&self) -> std::result::Result<(), Self::Error> {
+        self.inner.warmup().await.map_err(&self.map)
+    }
+
+    async fn on_shutdown(&self) -> std::result::Result<(), Self::Error> {
+        self.inner.on_shutdown().await.map_err(&self.map)
+    }
+
+    async fn on_client_connect(
+        &self,
+        client_info: &Implementation,
+    ) -> std::result::Result<(), Self::Error> {
+        self.inner
+            .on_client_connect(client_info)
+            .await
+            .map_err(&self.map)
+    }
+
+    async fn on_client_disconnect(
+        &self,
+        client_info: &Implementation,
+    ) -> std::result::Result<(), Self::Error> {
+        self.inner
+            .on_client_disconnect(client_info)
+            .await
+            .map_err(&self.map)
+    }
+
+    async fn handle_custom_method(
+        &self,
+        method: &str,
+        params: serde_json::Value,
+    ) -> std::result::Result<serde_json::Value, Self::Error> {
+        self.inner
+            .handle_custom_method(method, params)
+            .await
+            .map_err(&self.map)
+    }
+}
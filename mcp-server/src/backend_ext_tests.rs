@@ -0,0 +1,221 @@
+//! Tests for composable backend wrappers
+
+use crate::backend::{BackendError, McpBackend, SimpleBackend};
+use crate::backend_ext::BackendExt;
+use async_trait::async_trait;
+use pulseengine_mcp_protocol::*;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::Duration;
+
+/// Backend counting `list_tools` and `echo` calls and failing every other
+/// tool call
+#[derive(Clone, Default)]
+struct CountingBackend {
+    list_calls: Arc<AtomicUsize>,
+    echo_calls: Arc<AtomicUsize>,
+}
+
+#[async_trait]
+impl SimpleBackend for CountingBackend {
+    type Error = BackendError;
+    type Config = ();
+
+    async fn initialize(_config: Self::Config) -> std::result::Result<Self, Self::Error> {
+        Ok(Self::default())
+    }
+
+    fn get_server_info(&self) -> ServerInfo {
+        ServerInfo {
+            protocol_version: ProtocolVersion::default(),
+            capabilities: ServerCapabilities::default(),
+            server_info: Implementation::new("Counting Backend", "1.0.0"),
+            instructions: None,
+        }
+    }
+
+    async fn health_check(&self) -> std::result::Result<(), Self::Error> {
+        Ok(())
+    }
+
+    async fn list_tools(
+        &self,
+        _request: PaginatedRequestParam,
+    ) -> std::result::Result<ListToolsResult, Self::Error> {
+        let calls = self.list_calls.fetch_add(1, Ordering::SeqCst) + 1;
+        Ok(ListToolsResult {
+            tools: vec![],
+            next_cursor: Some(calls.to_string()),
+        })
+    }
+
+    async fn call_tool(
+        &self,
+        request: CallToolRequestParam,
+    ) -> std::result::Result<CallToolResult, Self::Error> {
+        if request.name != "echo" {
+            return Err(BackendError::internal("boom"));
+        }
+        let calls = self.echo_calls.fetch_add(1, Ordering::SeqCst) + 1;
+        Ok(CallToolResult {
+            content: vec![Content::text(calls.to_string())],
+            is_error: Some(false),
+            structured_content: request.arguments,
+            structured_content_blocks: None,
+            _meta: None,
+        })
+    }
+}
+
+fn page() -> PaginatedRequestParam {
+    PaginatedRequestParam { cursor: None }
+}
+
+fn call() -> CallToolRequestParam {
+    CallToolRequestParam {
+        name: "anything".to_string(),
+        arguments: None,
+    }
+}
+
+fn wrap(layer: &'static str) -> impl Fn(BackendError) -> BackendError + Clone {
+    move |error| match error {
+        BackendError::Internal(msg) => BackendError::internal(format!("{layer}({msg})")),
+        other => other,
+    }
+}
+
+#[tokio::test]
+async fn test_error_mappers_run_inner_to_outer() {
+    let backend = CountingBackend::default()
+        .map_error(wrap("first"))
+        .map_error(wrap("second"));
+
+    let error = backend.call_tool(call()).await.unwrap_err();
+    assert!(matches!(error, BackendError::Internal(msg) if msg == "second(first(boom))"));
+}
+
+#[tokio::test]
+async fn test_cache_and_logging_layers_compose() {
+    let inner = CountingBackend::default();
+    let backend = inner
+        .clone()
+        .with_logging("counting")
+        .with_cache(Duration::from_secs(60));
+
+    let first = backend.list_tools(page()).await.unwrap();
+    let second = backend.list_tools(page()).await.unwrap();
+    assert_eq!(first.next_cursor.as_deref(), Some("1"));
+    assert_eq!(second.next_cursor.as_deref(), Some("1"));
+    assert_eq!(inner.list_calls.load(Ordering::SeqCst), 1);
+
+    // Uncached operations still pass through every layer
+    let error = backend.call_tool(call()).await.unwrap_err();
+    assert!(matches!(error, BackendError::Internal(msg) if msg == "boom"));
+
+    backend.invalidate();
+    let third = backend.list_tools(page()).await.unwrap();
+    assert_eq!(third.next_cursor.as_deref(), Some("2"));
+}
+
+#[tokio::test]
+async fn test_cache_inside_error_mapper() {
+    let inner = CountingBackend::default();
+    let backend = inner
+        .clone()
+        .with_cache(Duration::ZERO)
+        .map_error(wrap("mapped"));
+
+    backend.list_tools(page()).await.unwrap();
+    backend.list_tools(page()).await.unwrap();
+    assert_eq!(inner.list_calls.load(Ordering::SeqCst), 2);
+
+    let error = backend.call_tool(call()).await.unwrap_err();
+    assert!(matches!(error, BackendError::Internal(msg) if msg == "mapped(boom)"));
+}
+
+#[tokio::test]
+async fn test_wrapped_backend_initializes_from_paired_config() {
+    let backend = <crate::backend_ext::CachedBackend<CountingBackend> as McpBackend>::initialize((
+        (),
+        Duration::from_secs(1),
+    ))
+    .await
+    .unwrap();
+    assert_eq!(
+        backend.get_server_info().server_info.name,
+        "Counting Backend"
+    );
+}
+
+fn echo(arguments: &str) -> CallToolRequestParam {
+    CallToolRequestParam {
+        name: "echo".to_string(),
+        arguments: Some(serde_json::from_str(arguments).unwrap()),
+    }
+}
+
+#[tokio::test]
+async fn test_cached_tools_key_on_canonical_arguments() {
+    let inner = CountingBackend::default();
+    let backend = inner
+        .clone()
+        .with_cache(Duration::from_secs(60))
+        .with_cached_tools(["echo"]);
+
+    backend
+        .call_tool(echo(r#"{"query": "rust", "limit": 10}"#))
+        .await
+        .unwrap();
+    // Same arguments in a different key order and number format hit the cache
+    backend
+        .call_tool(echo(r#"{"limit":10.0,"query":"rust"}"#))
+        .await
+        .unwrap();
+    assert_eq!(inner.echo_calls.load(Ordering::SeqCst), 1);
+
+    backend
+        .call_tool(echo(r#"{"query": "rust", "limit": 20}"#))
+        .await
+        .unwrap();
+    assert_eq!(inner.echo_calls.load(Ordering::SeqCst), 2);
+
+    // Tools not opted in are never cached
+    backend.call_tool(call()).await.unwrap_err();
+    backend.call_tool(call()).await.unwrap_err();
+
+    backend.invalidate();
+    backend
+        .call_tool(echo(r#"{"query": "rust", "limit": 10}"#))
+        .await
+        .unwrap();
+    assert_eq!(inner.echo_calls.load(Ordering::SeqCst), 3);
+}
+
+#[tokio::test]
+async fn test_cache_drops_expired_entries_and_evicts_when_full() {
+    let inner = CountingBackend::default();
+    let backend = inner
+        .clone()
+        .with_cache(Duration::from_millis(50))
+        .with_cached_tools(["echo"])
+        .with_max_entries(2);
+
+    for n in 1..=3 {
+        backend
+            .call_tool(echo(&format!(r#"{{"n": {n}}}"#)))
+            .await
+            .unwrap();
+    }
+    // The oldest result made room for the third
+    assert_eq!(backend.cached_entries(), 2);
+    backend.call_tool(echo(r#"{"n": 3}"#)).await.unwrap();
+    assert_eq!(inner.echo_calls.load(Ordering::SeqCst), 3);
+    backend.call_tool(echo(r#"{"n": 1}"#)).await.unwrap();
+    assert_eq!(inner.echo_calls.load(Ordering::SeqCst), 4);
+
+    // Storing a new result drops the expired ones
+    tokio::time::sleep(Duration::from_millis(80)).await;
+    backend.call_tool(echo(r#"{"n": 4}"#)).await.unwrap();
+    assert_eq!(backend.cached_entries(), 1);
+}
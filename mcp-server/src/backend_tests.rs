@@ -78,6 +78,30 @@ fn test_backend_error_to_protocol_error() {
     assert_eq!(protocol_err.code, ErrorCode::InternalError);
 }
 
+#[test]
+fn test_backend_error_explicit_code() {
+    let client_err = BackendError::client_error("'path' must be absolute");
+    assert_eq!(client_err.error_code(), ErrorCode::InvalidParams);
+    assert_eq!(client_err.to_string(), "'path' must be absolute");
+    let protocol_err: Error = client_err.into();
+    assert_eq!(protocol_err.code, ErrorCode::InvalidParams);
+    assert_eq!(protocol_err.message, "'path' must be absolute");
+
+    let not_found = BackendError::with_code(ErrorCode::ResourceNotFound, "no such file");
+    let protocol_err: Error = not_found.into();
+    assert_eq!(protocol_err.code, ErrorCode::ResourceNotFound);
+
+    // Untagged errors keep their default codes
+    assert_eq!(
+        BackendError::internal("boom").error_code(),
+        ErrorCode::InternalError
+    );
+    assert_eq!(
+        BackendError::not_supported("x").error_code(),
+        ErrorCode::MethodNotFound
+    );
+}
+
 // Mock backend for testing
 #[derive(Clone)]
 struct MockBackend {
@@ -181,6 +205,7 @@ impl McpBackend for MockBackend {
                 }],
                 is_error: Some(false),
                 structured_content: None,
+                structured_content_blocks: None,
                 _meta: None,
             })
         } else {
@@ -338,6 +363,7 @@ async fn test_mock_backend_optional_methods() {
     let subscribe_result = backend
         .subscribe(SubscribeRequestParam {
             uri: "test://resource".to_string(),
+            filter: None,
         })
         .await;
     // Default implementation now accepts subscriptions (Phase 1 of subscription support)
@@ -444,6 +470,7 @@ impl SimpleBackend for MockSimpleBackend {
             content: vec![],
             is_error: Some(false),
             structured_content: None,
+            structured_content_blocks: None,
             _meta: None,
         })
     }
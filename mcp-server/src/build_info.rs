@@ -0,0 +1,87 @@
+//! Build metadata for deployment verification
+//!
+//! When correlating an incident with a deployment, operators need to know
+//! exactly which build is running. [`BuildInfo`] carries the git commit,
+//! build timestamp and compiler version. A server given one reports it
+//! under `_meta.buildInfo` in the `initialize` result and at the `/version`
+//! endpoint of the health router.
+//!
+//! [`build_info!`](crate::build_info) reads the metadata from environment
+//! variables set at compile time, usually by the application's `build.rs`:
+//!
+//! ```rust,ignore
+//! // build.rs
+//! fn main() {
+//!     let commit = std::process::Command::new("git")
+//!         .args(["rev-parse", "HEAD"])
+//!         .output()
+//!         .ok()
+//!         .and_then(|out| String::from_utf8(out.stdout).ok());
+//!     if let Some(commit) = commit {
+//!         println!("cargo:rustc-env=MCP_BUILD_GIT_COMMIT={}", commit.trim());
+//!     }
+//!     println!("cargo:rustc-env=MCP_BUILD_TIMESTAMP={}", chrono::Utc::now().to_rfc3339());
+//! }
+//!
+//! // main.rs
+//! let config = ServerConfig {
+//!     build_info: Some(pulseengine_mcp_server::build_info!()),
+//!     ..Default::default()
+//! };
+//! ```
+
+use serde::{Deserialize, Serialize};
+
+/// Which build of the server is running; every field is optional
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BuildInfo {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub git_commit: Option<String>,
+    /// When the binary was built, typically RFC 3339
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub build_timestamp: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rustc_version: Option<String>,
+}
+
+impl BuildInfo {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_git_commit(mut self, commit: impl Into<String>) -> Self {
+        self.git_commit = Some(commit.into());
+        self
+    }
+
+    pub fn with_build_timestamp(mut self, timestamp: impl Into<String>) -> Self {
+        self.build_timestamp = Some(timestamp.into());
+        self
+    }
+
+    pub fn with_rustc_version(mut self, version: impl Into<String>) -> Self {
+        self.rustc_version = Some(version.into());
+        self
+    }
+
+    /// Whether no field is set, in which case nothing is reported
+    pub fn is_empty(&self) -> bool {
+        self.git_commit.is_none() && self.build_timestamp.is_none() && self.rustc_version.is_none()
+    }
+}
+
+/// Build metadata of the calling crate, read at compile time from
+/// `MCP_BUILD_GIT_COMMIT`, `MCP_BUILD_TIMESTAMP` and `MCP_BUILD_RUSTC_VERSION`
+///
+/// Variables that weren't set leave their field empty.
+#[macro_export]
+macro_rules! build_info {
+    () => {
+        $crate::BuildInfo {
+            git_commit: option_env!("MCP_BUILD_GIT_COMMIT").map(str::to_string),
+            build_timestamp: option_env!("MCP_BUILD_TIMESTAMP").map(str::to_string),
+            rustc_version: option_env!("MCP_BUILD_RUSTC_VERSION").map(str::to_string),
+        }
+    };
+}
@@ -0,0 +1,44 @@
+//! Tests for build metadata
+
+use crate::build_info::*;
+
+#[test]
+fn test_build_info_omits_unset_fields() {
+    let info = BuildInfo::new().with_git_commit("0123abcd");
+    assert!(!info.is_empty());
+    assert_eq!(
+        serde_json::to_value(&info).unwrap(),
+        serde_json::json!({"gitCommit": "0123abcd"})
+    );
+
+    assert!(BuildInfo::new().is_empty());
+    assert_eq!(
+        serde_json::to_value(BuildInfo::new()).unwrap(),
+        serde_json::json!({})
+    );
+}
+
+#[test]
+fn test_build_info_round_trip() {
+    let info = BuildInfo::new()
+        .with_git_commit("0123abcd")
+        .with_build_timestamp("2026-01-01T00:00:00Z")
+        .with_rustc_version("1.89.0");
+    let json = serde_json::to_value(&info).unwrap();
+    assert_eq!(json["buildTimestamp"], "2026-01-01T00:00:00Z");
+    assert_eq!(json["rustcVersion"], "1.89.0");
+    assert_eq!(serde_json::from_value::<BuildInfo>(json).unwrap(), info);
+}
+
+#[test]
+fn test_build_info_macro_reads_compile_time_env() {
+    let info = crate::build_info!();
+    assert_eq!(
+        info.git_commit.as_deref(),
+        option_env!("MCP_BUILD_GIT_COMMIT")
+    );
+    assert_eq!(
+        info.build_timestamp.as_deref(),
+        option_env!("MCP_BUILD_TIMESTAMP")
+    );
+}
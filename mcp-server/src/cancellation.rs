@@ -0,0 +1,98 @@
+//! In-flight request tracking for `notifications/cancelled`
+//!
+//! Every request with a JSON-RPC id is registered with a
+//! [`CancellationToken`] for as long as it is being handled. Ids are only
+//! unique per client, so entries are keyed by the caller (its session, or
+//! its connection where there is none) and id. When the client cancels a
+//! request, its token fires and the handler drops the request's future,
+//! aborting the backend call at its next await point.
+
+use pulseengine_mcp_protocol::NumberOrString;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use tokio_util::sync::CancellationToken;
+
+/// Method of the client notification that cancels an in-flight request
+pub const CANCELLED_NOTIFICATION_METHOD: &str = "notifications/cancelled";
+
+type RequestKey = (String, NumberOrString);
+
+/// Cancellation tokens of requests currently being handled
+#[derive(Clone, Default)]
+pub struct InFlightRequests {
+    tokens: Arc<Mutex<HashMap<RequestKey, (u64, CancellationToken)>>>,
+    next_registration: Arc<AtomicU64>,
+}
+
+impl InFlightRequests {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Track a request until the returned guard is dropped
+    pub fn register(&self, caller_key: &str, id: NumberOrString) -> InFlightRequest {
+        let key = (caller_key.to_string(), id);
+        let token = CancellationToken::new();
+        let registration = self.next_registration.fetch_add(1, Ordering::Relaxed);
+        self.tokens
+            .lock()
+            .unwrap()
+            .insert(key.clone(), (registration, token.clone()));
+        InFlightRequest {
+            requests: self.clone(),
+            key,
+            registration,
+            token,
+        }
+    }
+
+    /// Fire the token of an in-flight request; `false` if it isn't running
+    pub fn cancel(&self, caller_key: &str, id: &NumberOrString) -> bool {
+        let key = (caller_key.to_string(), id.clone());
+        match self.tokens.lock().unwrap().get(&key) {
+            Some((_, token)) => {
+                token.cancel();
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Number of requests currently tracked
+    pub fn len(&self) -> usize {
+        self.tokens.lock().unwrap().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+/// Registration of one in-flight request, removed when dropped
+pub struct InFlightRequest {
+    requests: InFlightRequests,
+    key: RequestKey,
+    registration: u64,
+    token: CancellationToken,
+}
+
+impl InFlightRequest {
+    /// Completes when the client cancels this request
+    pub async fn cancelled(&self) {
+        self.token.cancelled().await;
+    }
+}
+
+impl Drop for InFlightRequest {
+    fn drop(&mut self) {
+        let mut tokens = self.requests.tokens.lock().unwrap();
+        // A reused id may have replaced this entry; only remove our own token
+        if tokens
+            .get(&self.key)
+            .is_some_and(|(registration, _)| *registration == self.registration)
+        {
+            tokens.remove(&self.key);
+        }
+    }
+}
@@ -0,0 +1,123 @@
+//! Hiding features from clients that can't use them
+//!
+//! A server built for rich hosts may expose UI resources or advertise
+//! sampling and elicitation to every client, including ones that can't
+//! consume them. When enabled per capability, the `initialize` result and
+//! subsequent `*/list` responses are filtered against the capabilities the
+//! client declared, so each client only sees what it can use.
+
+use pulseengine_mcp_protocol::{
+    ListResourceTemplatesResult, ListResourcesResult, ListToolsResult, ServerCapabilities,
+    uri_schemes,
+};
+use serde::{Deserialize, Serialize};
+
+/// Extension keys under which clients declare MCP Apps (UI) support
+const UI_EXTENSION_KEYS: [&str; 2] = ["io.modelcontextprotocol/apps", "io.modelcontextprotocol/ui"];
+
+/// Which features to hide from clients lacking the matching capability
+///
+/// Every filter is off by default.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CapabilityFilterConfig {
+    /// Hide `ui://` resources and templates, and tools' UI resource links,
+    /// from clients that don't declare the MCP Apps extension
+    pub ui: bool,
+    /// Don't advertise `sampling` to clients without the sampling capability
+    pub sampling: bool,
+    /// Don't advertise `elicitation` to clients without the elicitation
+    /// capability
+    pub elicitation: bool,
+}
+
+impl CapabilityFilterConfig {
+    /// Filter every capability-gated feature
+    pub fn all() -> Self {
+        Self {
+            ui: true,
+            sampling: true,
+            elicitation: true,
+        }
+    }
+
+    /// Whether a client's declared capabilities include MCP Apps support
+    ///
+    /// The extension may be declared under `extensions` or `experimental`.
+    pub fn client_supports_ui(client_capabilities: &serde_json::Value) -> bool {
+        ["extensions", "experimental"].iter().any(|section| {
+            client_capabilities.get(section).is_some_and(|declared| {
+                UI_EXTENSION_KEYS
+                    .iter()
+                    .any(|key| declared.get(key).is_some())
+            })
+        })
+    }
+
+    fn hides_ui(&self, client_capabilities: &serde_json::Value) -> bool {
+        self.ui && !Self::client_supports_ui(client_capabilities)
+    }
+
+    /// Remove advertised capabilities the client can't use
+    pub fn filter_capabilities(
+        &self,
+        capabilities: &mut ServerCapabilities,
+        client_capabilities: &serde_json::Value,
+    ) {
+        if self.sampling && client_capabilities.get("sampling").is_none() {
+            capabilities.sampling = None;
+        }
+        if self.elicitation && client_capabilities.get("elicitation").is_none() {
+            capabilities.elicitation = None;
+        }
+        if self.hides_ui(client_capabilities)
+            && let Some(experimental) = &mut capabilities.experimental
+        {
+            for key in UI_EXTENSION_KEYS {
+                experimental.remove(key);
+            }
+        }
+    }
+
+    /// Drop tools' UI resource links for clients that can't render them
+    pub fn filter_tools(
+        &self,
+        result: &mut ListToolsResult,
+        client_capabilities: &serde_json::Value,
+    ) {
+        if !self.hides_ui(client_capabilities) {
+            return;
+        }
+        for tool in &mut result.tools {
+            if let Some(meta) = &mut tool._meta {
+                meta.ui_resource_uri = None;
+            }
+        }
+    }
+
+    /// Drop UI resources for clients that can't render them
+    pub fn filter_resources(
+        &self,
+        result: &mut ListResourcesResult,
+        client_capabilities: &serde_json::Value,
+    ) {
+        if self.hides_ui(client_capabilities) {
+            result
+                .resources
+                .retain(|resource| !resource.is_ui_resource());
+        }
+    }
+
+    /// Drop UI resource templates for clients that can't render them
+    pub fn filter_resource_templates(
+        &self,
+        result: &mut ListResourceTemplatesResult,
+        client_capabilities: &serde_json::Value,
+    ) {
+        if self.hides_ui(client_capabilities) {
+            result
+                .resource_templates
+                .retain(|template| !template.uri_template.starts_with(uri_schemes::UI));
+        }
+    }
+}
@@ -3,12 +3,21 @@
 //! This module provides utilities for CLI-based MCP servers, including:
 //! - Server info creation from Cargo.toml metadata
 //! - Logging configuration
+//! - Configuration validation with field-level errors
 //! - Environment variable utilities
 
 use pulseengine_mcp_protocol::{Implementation, ProtocolVersion, ServerCapabilities, ServerInfo};
 use serde::{Deserialize, Serialize};
 use std::env;
+use std::fmt::Write as _;
+use std::str::FromStr;
 use thiserror::Error;
+use tracing::field::{Field, Visit};
+use tracing::{Event, Subscriber};
+use tracing_subscriber::Layer;
+use tracing_subscriber::fmt::format::Writer;
+use tracing_subscriber::fmt::{FmtContext, FormatEvent, FormatFields, MakeWriter};
+use tracing_subscriber::registry::LookupSpan;
 
 /// CLI-related errors
 #[derive(Debug, Error)]
@@ -51,6 +60,9 @@ impl CliError {
 }
 
 /// Default logging configuration
+///
+/// Defaults to JSON lines on stderr: log aggregators can ingest it directly,
+/// and stdout stays reserved for JSON-RPC messages on the stdio transport.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct DefaultLoggingConfig {
     pub level: String,
@@ -59,16 +71,39 @@ pub struct DefaultLoggingConfig {
     pub structured: bool,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+/// Log line format
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub enum LogFormat {
+    /// One JSON object per event, for log aggregators
     #[serde(rename = "json")]
     Json,
+    /// `key=value` pairs, one line per event
+    #[serde(rename = "logfmt")]
+    Logfmt,
+    /// Multi-line human-readable output for development
     #[serde(rename = "pretty")]
     Pretty,
+    /// Single-line human-readable output
     #[serde(rename = "compact")]
     Compact,
 }
 
+impl FromStr for LogFormat {
+    type Err = CliError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "json" => Ok(Self::Json),
+            "logfmt" => Ok(Self::Logfmt),
+            "pretty" => Ok(Self::Pretty),
+            "compact" => Ok(Self::Compact),
+            other => Err(CliError::configuration(format!(
+                "Unknown log format '{other}' (expected json, logfmt, pretty or compact)"
+            ))),
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum LogOutput {
     #[serde(rename = "stdout")]
@@ -79,49 +114,175 @@ pub enum LogOutput {
     File(String),
 }
 
+impl FromStr for LogOutput {
+    type Err = CliError;
+
+    /// `stdout`, `stderr`, or any other value as a file path
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim() {
+            "" => Err(CliError::configuration("Empty log output")),
+            "stdout" => Ok(Self::Stdout),
+            "stderr" => Ok(Self::Stderr),
+            path => Ok(Self::File(path.to_string())),
+        }
+    }
+}
+
 impl Default for DefaultLoggingConfig {
     fn default() -> Self {
         Self {
             level: "info".to_string(),
-            format: LogFormat::Pretty,
-            output: LogOutput::Stdout,
+            format: LogFormat::Json,
+            output: LogOutput::Stderr,
             structured: true,
         }
     }
 }
 
 impl DefaultLoggingConfig {
+    /// Defaults overridden by `MCP_LOG_LEVEL`, `MCP_LOG_FORMAT` and
+    /// `MCP_LOG_OUTPUT`
+    ///
+    /// `RUST_LOG` still takes precedence over the level at initialization.
+    pub fn from_env() -> Result<Self, CliError> {
+        let mut config = Self::default();
+        if let Ok(level) = env::var("MCP_LOG_LEVEL") {
+            config.level = level;
+        }
+        if let Ok(format) = env::var("MCP_LOG_FORMAT") {
+            config.format = format.parse()?;
+        }
+        if let Ok(output) = env::var("MCP_LOG_OUTPUT") {
+            config.output = output.parse()?;
+        }
+        Ok(config)
+    }
+
     pub fn initialize(&self) -> Result<(), CliError> {
-        use tracing_subscriber::{EnvFilter, fmt, prelude::*};
+        use std::io::IsTerminal;
+        use tracing_subscriber::fmt::writer::BoxMakeWriter;
+        use tracing_subscriber::{EnvFilter, prelude::*};
 
         let level = env::var("RUST_LOG").unwrap_or_else(|_| self.level.clone());
         let filter = EnvFilter::try_from_default_env()
             .or_else(|_| EnvFilter::try_new(&level))
             .map_err(|e| CliError::logging(format!("Invalid log level: {e}")))?;
 
-        match self.format {
-            LogFormat::Json => {
-                tracing_subscriber::registry()
-                    .with(filter)
-                    .with(fmt::layer().json())
-                    .init();
+        let (writer, is_terminal) = match &self.output {
+            LogOutput::Stdout => (
+                BoxMakeWriter::new(std::io::stdout),
+                std::io::stdout().is_terminal(),
+            ),
+            LogOutput::Stderr => (
+                BoxMakeWriter::new(std::io::stderr),
+                std::io::stderr().is_terminal(),
+            ),
+            LogOutput::File(path) => {
+                let file = std::fs::OpenOptions::new()
+                    .create(true)
+                    .append(true)
+                    .open(path)?;
+                (BoxMakeWriter::new(std::sync::Mutex::new(file)), false)
             }
-            LogFormat::Pretty => {
-                tracing_subscriber::registry()
-                    .with(filter)
-                    .with(fmt::layer().pretty())
-                    .init();
-            }
-            LogFormat::Compact => {
-                tracing_subscriber::registry()
-                    .with(filter)
-                    .with(fmt::layer().compact())
-                    .init();
-            }
-        }
+        };
+
+        tracing_subscriber::registry()
+            .with(filter)
+            .with(self.layer(writer, is_terminal))
+            .init();
 
         Ok(())
     }
+
+    /// Formatting layer for the configured format; colors are only used for
+    /// the human-readable formats
+    fn layer<S, W>(&self, writer: W, ansi: bool) -> Box<dyn Layer<S> + Send + Sync>
+    where
+        S: Subscriber + for<'a> LookupSpan<'a>,
+        W: for<'w> MakeWriter<'w> + Send + Sync + 'static,
+    {
+        let human = matches!(self.format, LogFormat::Pretty | LogFormat::Compact);
+        let layer = tracing_subscriber::fmt::layer()
+            .with_writer(writer)
+            .with_ansi(ansi && human);
+        match self.format {
+            LogFormat::Json => layer.json().boxed(),
+            LogFormat::Logfmt => layer.event_format(Logfmt).boxed(),
+            LogFormat::Pretty => layer.pretty().boxed(),
+            LogFormat::Compact => layer.compact().boxed(),
+        }
+    }
+}
+
+/// Event formatter writing `ts=.. level=.. target=.. msg=..` followed by the
+/// event's fields
+struct Logfmt;
+
+impl<S, N> FormatEvent<S, N> for Logfmt
+where
+    S: Subscriber + for<'a> LookupSpan<'a>,
+    N: for<'a> FormatFields<'a> + 'static,
+{
+    fn format_event(
+        &self,
+        _ctx: &FmtContext<'_, S, N>,
+        mut writer: Writer<'_>,
+        event: &Event<'_>,
+    ) -> std::fmt::Result {
+        let metadata = event.metadata();
+        write!(
+            writer,
+            "ts={} level={} target={}",
+            chrono::Utc::now().to_rfc3339_opts(chrono::SecondsFormat::Millis, true),
+            metadata.level().as_str().to_ascii_lowercase(),
+            logfmt_value(metadata.target())
+        )?;
+
+        let mut fields = LogfmtFields::default();
+        event.record(&mut fields);
+        writer.write_str(&fields.0)?;
+        writeln!(writer)
+    }
+}
+
+/// ` key=value` pairs of an event's fields, with `message` renamed to `msg`
+#[derive(Default)]
+struct LogfmtFields(String);
+
+impl LogfmtFields {
+    fn push(&mut self, field: &Field, value: &str) {
+        let key = match field.name() {
+            "message" => "msg",
+            name => name,
+        };
+        let _ = write!(self.0, " {key}={}", logfmt_value(value));
+    }
+}
+
+impl Visit for LogfmtFields {
+    fn record_str(&mut self, field: &Field, value: &str) {
+        self.push(field, value);
+    }
+
+    fn record_debug(&mut self, field: &Field, value: &dyn std::fmt::Debug) {
+        self.push(field, &format!("{value:?}"));
+    }
+}
+
+/// Quote values that are empty or contain spaces, `=` or quotes
+fn logfmt_value(value: &str) -> String {
+    let needs_quotes = value.is_empty()
+        || value
+            .chars()
+            .any(|c| c.is_whitespace() || c == '=' || c == '"' || c == '\\');
+    if !needs_quotes {
+        return value.to_string();
+    }
+    let escaped = value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n");
+    format!("\"{escaped}\"")
 }
 
 /// Create default server info from Cargo.toml metadata
@@ -148,6 +309,105 @@ pub fn create_server_info(name: Option<String>, version: Option<String>) -> Serv
     }
 }
 
+/// Collects field-level configuration errors before server startup
+///
+/// Each check records a `field: problem` message instead of failing fast, so
+/// [`ConfigValidator::finish`] reports every misconfiguration at once as a
+/// single [`CliError::Configuration`]. Use [`ConfigValidator::custom`] for
+/// checks specific to an application's configuration.
+///
+/// # Example
+/// ```rust,ignore
+/// ConfigValidator::new()
+///     .port("port", config.port)
+///     .required("database_url", config.database_url.as_ref())
+///     .both_or_neither(("tls_cert", config.tls_cert.is_some()), ("tls_key", config.tls_key.is_some()))
+///     .finish()?;
+/// ```
+#[derive(Debug, Default)]
+pub struct ConfigValidator {
+    errors: Vec<String>,
+}
+
+impl ConfigValidator {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Require a usable TCP port (1-65535)
+    pub fn port(self, field: &str, port: u16) -> Self {
+        self.in_range(field, port, 1..=u16::MAX)
+    }
+
+    /// Require `value` to lie within `range`
+    pub fn in_range<T>(mut self, field: &str, value: T, range: std::ops::RangeInclusive<T>) -> Self
+    where
+        T: PartialOrd + std::fmt::Display,
+    {
+        if !range.contains(&value) {
+            self.errors.push(format!(
+                "{field}: {value} is out of range (expected {} to {})",
+                range.start(),
+                range.end()
+            ));
+        }
+        self
+    }
+
+    /// Require an optional field to be set
+    pub fn required<T>(mut self, field: &str, value: Option<T>) -> Self {
+        if value.is_none() {
+            self.errors.push(format!("{field}: is required"));
+        }
+        self
+    }
+
+    /// Reject setting both of two fields; each is `(name, is_set)`
+    pub fn mutually_exclusive(mut self, a: (&str, bool), b: (&str, bool)) -> Self {
+        if a.1 && b.1 {
+            self.errors
+                .push(format!("{}: cannot be combined with {}", a.0, b.0));
+        }
+        self
+    }
+
+    /// Require two fields to be set together or not at all; each is `(name, is_set)`
+    pub fn both_or_neither(mut self, a: (&str, bool), b: (&str, bool)) -> Self {
+        match (a.1, b.1) {
+            (true, false) => self
+                .errors
+                .push(format!("{}: is required when {} is set", b.0, a.0)),
+            (false, true) => self
+                .errors
+                .push(format!("{}: is required when {} is set", a.0, b.0)),
+            _ => {}
+        }
+        self
+    }
+
+    /// Run an application-specific check, recording its error against `field`
+    pub fn custom(mut self, field: &str, check: impl FnOnce() -> Result<(), String>) -> Self {
+        if let Err(problem) = check() {
+            self.errors.push(format!("{field}: {problem}"));
+        }
+        self
+    }
+
+    /// Messages recorded so far
+    pub fn errors(&self) -> &[String] {
+        &self.errors
+    }
+
+    /// Report every recorded problem as one configuration error
+    pub fn finish(self) -> Result<(), CliError> {
+        if self.errors.is_empty() {
+            Ok(())
+        } else {
+            Err(CliError::configuration(self.errors.join("; ")))
+        }
+    }
+}
+
 /// Environment variable utilities
 pub mod env_utils {
     use std::env;
@@ -209,8 +469,121 @@ mod tests {
         let config = DefaultLoggingConfig::default();
         assert_eq!(config.level, "info");
         assert!(config.structured);
-        assert!(matches!(config.format, LogFormat::Pretty));
-        assert!(matches!(config.output, LogOutput::Stdout));
+        assert!(matches!(config.format, LogFormat::Json));
+        assert!(matches!(config.output, LogOutput::Stderr));
+    }
+
+    #[test]
+    fn test_log_format_and_output_from_str() {
+        assert_eq!("json".parse::<LogFormat>().unwrap(), LogFormat::Json);
+        assert_eq!("LOGFMT".parse::<LogFormat>().unwrap(), LogFormat::Logfmt);
+        assert_eq!("pretty".parse::<LogFormat>().unwrap(), LogFormat::Pretty);
+        assert!(matches!(
+            "xml".parse::<LogFormat>(),
+            Err(CliError::Configuration(message)) if message.contains("xml")
+        ));
+
+        assert!(matches!("stderr".parse(), Ok(LogOutput::Stderr)));
+        assert!(matches!(
+            "/var/log/mcp.log".parse(),
+            Ok(LogOutput::File(path)) if path == "/var/log/mcp.log"
+        ));
+    }
+
+    #[derive(Clone, Default)]
+    struct CapturedLogs(std::sync::Arc<std::sync::Mutex<Vec<u8>>>);
+
+    impl std::io::Write for CapturedLogs {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            self.0.lock().unwrap().extend_from_slice(buf);
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    /// Output of one event logged through a layer in `format`
+    fn log_event(format: LogFormat) -> String {
+        use tracing_subscriber::prelude::*;
+
+        let logs = CapturedLogs::default();
+        let config = DefaultLoggingConfig {
+            format,
+            ..Default::default()
+        };
+        let writer = {
+            let logs = logs.clone();
+            move || logs.clone()
+        };
+        let subscriber = tracing_subscriber::registry().with(config.layer(writer, false));
+        tracing::subscriber::with_default(subscriber, || {
+            tracing::info!(target: "app", tool = "echo", attempts = 2, "tool call done");
+        });
+        String::from_utf8(logs.0.lock().unwrap().clone()).unwrap()
+    }
+
+    #[test]
+    fn test_json_format_emits_one_object_per_event() {
+        let output = log_event(LogFormat::Json);
+        let lines: Vec<_> = output.lines().collect();
+        assert_eq!(lines.len(), 1);
+
+        let event: serde_json::Value = serde_json::from_str(lines[0]).unwrap();
+        assert_eq!(event["level"], "INFO");
+        assert_eq!(event["target"], "app");
+        assert_eq!(event["fields"]["message"], "tool call done");
+        assert_eq!(event["fields"]["tool"], "echo");
+        assert_eq!(event["fields"]["attempts"], 2);
+        assert!(event["timestamp"].is_string());
+    }
+
+    #[test]
+    fn test_logfmt_format_emits_key_value_pairs() {
+        let output = log_event(LogFormat::Logfmt);
+        let lines: Vec<_> = output.lines().collect();
+        assert_eq!(lines.len(), 1);
+
+        // Split on spaces outside quotes, then on the first '='
+        let mut pairs = std::collections::HashMap::new();
+        let mut token = String::new();
+        let mut quoted = false;
+        for c in lines[0].chars().chain([' ']) {
+            match c {
+                '"' => quoted = !quoted,
+                ' ' if !quoted => {
+                    let (key, value) = token.split_once('=').unwrap();
+                    pairs.insert(key.to_string(), value.to_string());
+                    token.clear();
+                }
+                c => token.push(c),
+            }
+        }
+        assert_eq!(pairs["level"], "info");
+        assert_eq!(pairs["target"], "app");
+        assert_eq!(pairs["msg"], "tool call done");
+        assert_eq!(pairs["tool"], "echo");
+        assert_eq!(pairs["attempts"], "2");
+        assert!(chrono::DateTime::parse_from_rfc3339(&pairs["ts"]).is_ok());
+    }
+
+    #[test]
+    fn test_pretty_format_is_human_readable() {
+        let output = log_event(LogFormat::Pretty);
+        assert!(output.lines().count() > 1);
+        assert!(output.contains("INFO"));
+        assert!(output.contains("tool call done"));
+        assert!(output.contains("echo"));
+        assert!(!output.contains('\u{1b}'));
+    }
+
+    #[test]
+    fn test_logfmt_value_quoting() {
+        assert_eq!(logfmt_value("plain"), "plain");
+        assert_eq!(logfmt_value(""), "\"\"");
+        assert_eq!(logfmt_value("a b"), "\"a b\"");
+        assert_eq!(logfmt_value("say \"hi\""), "\"say \\\"hi\\\"\"");
     }
 
     #[test]
@@ -223,6 +596,9 @@ mod tests {
 
         let compact_format = serde_json::to_string(&LogFormat::Compact).unwrap();
         assert!(compact_format.contains("compact"));
+
+        let logfmt_format = serde_json::to_string(&LogFormat::Logfmt).unwrap();
+        assert!(logfmt_format.contains("logfmt"));
     }
 
     #[test]
@@ -264,6 +640,53 @@ mod tests {
         assert_eq!(result, "default");
     }
 
+    #[test]
+    fn test_config_validator_reports_field_level_errors() {
+        assert!(
+            ConfigValidator::new()
+                .port("port", 8080)
+                .required("host", Some("localhost"))
+                .both_or_neither(("tls_cert", true), ("tls_key", true))
+                .finish()
+                .is_ok()
+        );
+
+        let error = ConfigValidator::new().port("port", 0).finish().unwrap_err();
+        assert!(matches!(error, CliError::Configuration(_)));
+        assert_eq!(
+            error.to_string(),
+            "Configuration error: port: 0 is out of range (expected 1 to 65535)"
+        );
+
+        let error = ConfigValidator::new()
+            .both_or_neither(("tls_cert", true), ("tls_key", false))
+            .finish()
+            .unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "Configuration error: tls_key: is required when tls_cert is set"
+        );
+
+        let validator = ConfigValidator::new()
+            .required::<&str>("database_url", None)
+            .mutually_exclusive(("stdio", true), ("http_port", true))
+            .in_range("workers", 0, 1..=64)
+            .custom("log_level", || Err("unknown level 'loud'".to_string()));
+        assert_eq!(
+            validator.errors(),
+            [
+                "database_url: is required",
+                "stdio: cannot be combined with http_port",
+                "workers: 0 is out of range (expected 1 to 64)",
+                "log_level: unknown level 'loud'",
+            ]
+        );
+        assert!(matches!(
+            validator.finish(),
+            Err(CliError::Configuration(message)) if message.contains("; stdio: cannot")
+        ));
+    }
+
     #[test]
     fn test_env_utils_get_required_env_missing() {
         let result: Result<String, _> = env_utils::get_required_env("NON_EXISTENT_VAR_12345");
@@ -0,0 +1,121 @@
+//! Allow and deny lists for client implementations
+//!
+//! Operators can refuse clients with known protocol bugs by matching the
+//! `clientInfo` sent in `initialize` against name and semver range rules.
+//! Deny rules win over allow rules, and an empty allowlist admits every
+//! client that isn't denied.
+
+use pulseengine_mcp_protocol::{Error, Implementation};
+use semver::{Version, VersionReq};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// Matches client implementations by name and, optionally, version range
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ClientRule {
+    /// Client implementation name, compared case-insensitively
+    pub name: String,
+    /// Semver range of matching versions; every version matches when `None`
+    #[serde(default)]
+    pub versions: Option<VersionReq>,
+}
+
+impl ClientRule {
+    /// Match every version of a client
+    pub fn any_version(name: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            versions: None,
+        }
+    }
+
+    /// Match the versions of a client within a semver range, e.g. `<1.4.2`
+    pub fn versions(name: impl Into<String>, range: &str) -> Result<Self, semver::Error> {
+        Ok(Self {
+            name: name.into(),
+            versions: Some(VersionReq::parse(range)?),
+        })
+    }
+
+    /// Whether a client matches this rule
+    ///
+    /// A rule with a version range never matches a client whose version
+    /// isn't valid semver.
+    pub fn matches(&self, client: &Implementation) -> bool {
+        if !self.name.eq_ignore_ascii_case(&client.name) {
+            return false;
+        }
+        match &self.versions {
+            None => true,
+            Some(range) => Version::parse(client.version.trim_start_matches('v'))
+                .is_ok_and(|version| range.matches(&version)),
+        }
+    }
+}
+
+impl fmt::Display for ClientRule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.versions {
+            Some(range) => write!(f, "{} {}", self.name, range),
+            None => write!(f, "{}", self.name),
+        }
+    }
+}
+
+/// Policy deciding which client implementations may initialize
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ClientPolicy {
+    /// Clients admitted; every client is admitted when empty
+    #[serde(default)]
+    pub allow: Vec<ClientRule>,
+    /// Clients refused, even if they are also allowed
+    #[serde(default)]
+    pub deny: Vec<ClientRule>,
+}
+
+impl ClientPolicy {
+    /// Admit only clients matching `rule` (in addition to earlier allow rules)
+    pub fn allow(mut self, rule: ClientRule) -> Self {
+        self.allow.push(rule);
+        self
+    }
+
+    /// Refuse clients matching `rule`
+    pub fn deny(mut self, rule: ClientRule) -> Self {
+        self.deny.push(rule);
+        self
+    }
+
+    /// Check a client, returning an invalid request error for refused clients
+    ///
+    /// The error names the rule that refused the client and, when there is
+    /// an allowlist, the clients the server supports.
+    pub fn check(&self, client: &Implementation) -> Result<(), Error> {
+        let client_label = format!("Client '{}' version {}", client.name, client.version);
+        let supported = || {
+            if self.allow.is_empty() {
+                String::new()
+            } else {
+                let allowed: Vec<String> = self.allow.iter().map(ToString::to_string).collect();
+                format!("; supported clients: {}", allowed.join(", "))
+            }
+        };
+        if let Some(rule) = self.deny.iter().find(|rule| rule.matches(client)) {
+            let blocked = match &rule.versions {
+                Some(range) => format!("{} versions {range} are blocked", rule.name),
+                None => format!("all versions of {} are blocked", rule.name),
+            };
+            return Err(Error::invalid_request(format!(
+                "{client_label} is not supported by this server ({blocked}){}",
+                supported()
+            )));
+        }
+        if !self.allow.is_empty() && !self.allow.iter().any(|rule| rule.matches(client)) {
+            return Err(Error::invalid_request(format!(
+                "{client_label} is not supported by this server{}",
+                supported()
+            )));
+        }
+        Ok(())
+    }
+}
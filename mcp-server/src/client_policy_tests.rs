@@ -0,0 +1,89 @@
+//! Tests for client implementation allow/deny lists
+
+use crate::client_policy::*;
+use pulseengine_mcp_protocol::{ErrorCode, Implementation};
+
+fn client(name: &str, version: &str) -> Implementation {
+    Implementation::new(name, version)
+}
+
+#[test]
+fn test_default_policy_allows_every_client() {
+    let policy = ClientPolicy::default();
+    assert!(policy.check(&client("anything", "0.0.1")).is_ok());
+    assert!(policy.check(&client("unversioned", "dev")).is_ok());
+}
+
+#[test]
+fn test_deny_rule_matches_semver_range() {
+    let policy =
+        ClientPolicy::default().deny(ClientRule::versions("buggy-client", "<1.4.2").unwrap());
+
+    let error = policy.check(&client("Buggy-Client", "1.4.1")).unwrap_err();
+    assert_eq!(error.code, ErrorCode::InvalidRequest);
+    assert!(
+        error
+            .message
+            .contains("Client 'Buggy-Client' version 1.4.1")
+    );
+    assert!(
+        error
+            .message
+            .contains("(buggy-client versions <1.4.2 are blocked)")
+    );
+    assert!(!error.message.contains("upgrade"));
+
+    // Clients newer than the server supports aren't told to upgrade either
+    let capped = ClientPolicy::default()
+        .allow(ClientRule::versions("inspector", ">=0.10").unwrap())
+        .deny(ClientRule::versions("inspector", ">=2.0").unwrap());
+    let error = capped.check(&client("inspector", "2.1.0")).unwrap_err();
+    assert_eq!(
+        error.message,
+        "Client 'inspector' version 2.1.0 is not supported by this server \
+         (inspector versions >=2.0 are blocked); supported clients: inspector >=0.10"
+    );
+
+    assert!(policy.check(&client("buggy-client", "v1.4.2")).is_ok());
+    assert!(policy.check(&client("other-client", "1.0.0")).is_ok());
+    // Versions that aren't semver can't fall inside a denied range
+    assert!(policy.check(&client("buggy-client", "nightly")).is_ok());
+}
+
+#[test]
+fn test_allowlist_admits_only_listed_clients() {
+    let policy = ClientPolicy::default()
+        .allow(ClientRule::versions("inspector", ">=0.10").unwrap())
+        .allow(ClientRule::any_version("claude-desktop"))
+        .deny(ClientRule::versions("claude-desktop", "=0.7.0").unwrap());
+
+    assert!(policy.check(&client("inspector", "0.11.0")).is_ok());
+    assert!(policy.check(&client("claude-desktop", "0.8.0")).is_ok());
+
+    let error = policy.check(&client("inspector", "0.9.0")).unwrap_err();
+    assert!(
+        error
+            .message
+            .contains("supported clients: inspector >=0.10, claude-desktop")
+    );
+    assert!(policy.check(&client("unknown", "1.0.0")).is_err());
+    // Deny rules win over allow rules
+    let error = policy
+        .check(&client("claude-desktop", "0.7.0"))
+        .unwrap_err();
+    assert!(
+        error
+            .message
+            .contains("claude-desktop versions =0.7.0 are blocked")
+    );
+}
+
+#[test]
+fn test_policy_deserializes_from_config() {
+    let policy: ClientPolicy = serde_json::from_value(serde_json::json!({
+        "deny": [{"name": "buggy-client", "versions": "<2.0.0"}]
+    }))
+    .unwrap();
+    assert!(policy.allow.is_empty());
+    assert!(policy.check(&client("buggy-client", "1.9.9")).is_err());
+}
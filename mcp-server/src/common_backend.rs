@@ -188,6 +188,12 @@ pub trait McpResourcesProvider {
     ) -> impl std::future::Future<
         Output = std::result::Result<ReadResourceResult, pulseengine_mcp_protocol::Error>,
     > + Send;
+
+    /// Permission needed to read `uri`; see [`crate::McpBackend::resource_permission`]
+    fn resource_permission(&self, uri: &str) -> Option<String> {
+        let _ = uri;
+        None
+    }
 }
 
 /// Helper trait for servers with prompts
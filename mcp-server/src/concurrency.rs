@@ -0,0 +1,162 @@
+//! Fair request concurrency limiting across connections
+//!
+//! A plain global semaphore lets one busy connection take every free slot as
+//! soon as it opens, starving quieter clients. This limiter queues waiting
+//! requests per connection and hands out freed slots round-robin across the
+//! connections that are waiting, so every connection makes progress no matter
+//! how many requests another one has queued.
+
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, VecDeque};
+use std::sync::{Arc, Mutex};
+use tokio::sync::oneshot;
+
+/// Configuration for request concurrency limiting
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConcurrencyConfig {
+    /// Maximum number of requests executing at once across all connections
+    pub max_concurrent_requests: usize,
+    /// Maximum number of requests a single connection may execute at once
+    pub max_per_connection: Option<usize>,
+}
+
+impl Default for ConcurrencyConfig {
+    fn default() -> Self {
+        Self {
+            max_concurrent_requests: 64,
+            max_per_connection: None,
+        }
+    }
+}
+
+#[derive(Default)]
+struct State {
+    in_flight: usize,
+    /// Executing requests per connection
+    active: HashMap<String, usize>,
+    /// Waiting requests per connection, in arrival order
+    queues: HashMap<String, VecDeque<oneshot::Sender<ConcurrencyPermit>>>,
+    /// Connections with waiting requests, in round-robin order
+    rotation: VecDeque<String>,
+}
+
+struct Inner {
+    config: ConcurrencyConfig,
+    state: Mutex<State>,
+}
+
+/// Concurrency limiter that shares slots fairly between connections
+#[derive(Clone)]
+pub struct FairConcurrencyLimiter {
+    inner: Arc<Inner>,
+}
+
+/// A granted execution slot; released when dropped
+pub struct ConcurrencyPermit {
+    /// `None` once the slot has been reclaimed without dropping the permit
+    inner: Option<Arc<Inner>>,
+    connection: String,
+}
+
+impl Drop for ConcurrencyPermit {
+    fn drop(&mut self) {
+        if let Some(inner) = self.inner.take() {
+            let mut state = inner.state.lock().unwrap();
+            Inner::release_slot(&mut state, &self.connection);
+            inner.dispatch(&mut state);
+        }
+    }
+}
+
+impl FairConcurrencyLimiter {
+    pub fn new(config: ConcurrencyConfig) -> Self {
+        Self {
+            inner: Arc::new(Inner {
+                config,
+                state: Mutex::new(State::default()),
+            }),
+        }
+    }
+
+    /// Wait for an execution slot on behalf of a connection
+    pub async fn acquire(&self, connection: &str) -> ConcurrencyPermit {
+        let (tx, rx) = oneshot::channel();
+        {
+            let mut state = self.inner.state.lock().unwrap();
+            let queue = state.queues.entry(connection.to_string()).or_default();
+            queue.push_back(tx);
+            if queue.len() == 1 {
+                state.rotation.push_back(connection.to_string());
+            }
+            self.inner.dispatch(&mut state);
+        }
+        // The sender is only dropped after handing over a permit
+        rx.await.expect("limiter dropped a waiting request")
+    }
+
+    /// Number of requests currently executing
+    pub fn in_flight(&self) -> usize {
+        self.inner.state.lock().unwrap().in_flight
+    }
+
+    /// Number of requests waiting for a slot
+    pub fn queued(&self) -> usize {
+        let state = self.inner.state.lock().unwrap();
+        state.queues.values().map(VecDeque::len).sum()
+    }
+}
+
+impl Inner {
+    fn connection_has_room(&self, state: &State, connection: &str) -> bool {
+        self.config
+            .max_per_connection
+            .is_none_or(|max| state.active.get(connection).copied().unwrap_or(0) < max)
+    }
+
+    /// Hand free slots to waiting connections in round-robin order
+    fn dispatch(self: &Arc<Self>, state: &mut State) {
+        while state.in_flight < self.config.max_concurrent_requests {
+            let Some(position) = state
+                .rotation
+                .iter()
+                .position(|connection| self.connection_has_room(state, connection))
+            else {
+                return;
+            };
+            let connection = state.rotation.remove(position).expect("position in range");
+            let queue = state
+                .queues
+                .get_mut(&connection)
+                .expect("queued connection");
+            let waiter = queue.pop_front().expect("queued connection has waiters");
+            if queue.is_empty() {
+                state.queues.remove(&connection);
+            } else {
+                state.rotation.push_back(connection.clone());
+            }
+
+            state.in_flight += 1;
+            *state.active.entry(connection.clone()).or_default() += 1;
+            let permit = ConcurrencyPermit {
+                inner: Some(self.clone()),
+                connection,
+            };
+            if let Err(mut permit) = waiter.send(permit) {
+                // The waiting request was cancelled; reclaim its slot here
+                // since the permit's drop would re-lock the state
+                permit.inner = None;
+                Self::release_slot(state, &permit.connection);
+            }
+        }
+    }
+
+    fn release_slot(state: &mut State, connection: &str) {
+        state.in_flight -= 1;
+        if let Some(active) = state.active.get_mut(connection) {
+            *active -= 1;
+            if *active == 0 {
+                state.active.remove(connection);
+            }
+        }
+    }
+}
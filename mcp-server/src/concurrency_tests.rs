@@ -0,0 +1,100 @@
+//! Tests for fair request concurrency limiting
+
+use crate::concurrency::*;
+use std::time::Duration;
+use tokio::sync::mpsc;
+
+async fn wait_for_queued(limiter: &FairConcurrencyLimiter, expected: usize) {
+    while limiter.queued() < expected {
+        tokio::task::yield_now().await;
+    }
+}
+
+#[tokio::test]
+async fn test_flooding_connection_does_not_starve_quiet_one() {
+    let limiter = FairConcurrencyLimiter::new(ConcurrencyConfig {
+        max_concurrent_requests: 1,
+        max_per_connection: None,
+    });
+    let (tx, mut rx) = mpsc::unbounded_channel();
+
+    let held = limiter.acquire("busy").await;
+    for _ in 0..10 {
+        let (limiter, tx) = (limiter.clone(), tx.clone());
+        tokio::spawn(async move {
+            let _permit = limiter.acquire("busy").await;
+            tx.send("busy").unwrap();
+        });
+    }
+    wait_for_queued(&limiter, 10).await;
+
+    let quiet = {
+        let (limiter, tx) = (limiter.clone(), tx.clone());
+        tokio::spawn(async move {
+            let _permit = limiter.acquire("quiet").await;
+            tx.send("quiet").unwrap();
+        })
+    };
+    wait_for_queued(&limiter, 11).await;
+    drop(held);
+
+    let mut order = Vec::new();
+    for _ in 0..11 {
+        order.push(rx.recv().await.unwrap());
+    }
+    quiet.await.unwrap();
+
+    let position = order.iter().position(|c| *c == "quiet").unwrap();
+    assert!(
+        position <= 1,
+        "quiet connection served at {position}: {order:?}"
+    );
+    assert_eq!(limiter.in_flight(), 0);
+    assert_eq!(limiter.queued(), 0);
+}
+
+#[tokio::test]
+async fn test_per_connection_limit_leaves_room_for_others() {
+    let limiter = FairConcurrencyLimiter::new(ConcurrencyConfig {
+        max_concurrent_requests: 4,
+        max_per_connection: Some(2),
+    });
+
+    let _busy1 = limiter.acquire("busy").await;
+    let _busy2 = limiter.acquire("busy").await;
+    let blocked = {
+        let limiter = limiter.clone();
+        tokio::spawn(async move { limiter.acquire("busy").await })
+    };
+    wait_for_queued(&limiter, 1).await;
+    assert_eq!(limiter.in_flight(), 2);
+
+    let quiet = tokio::time::timeout(Duration::from_secs(1), limiter.acquire("quiet"))
+        .await
+        .expect("quiet connection should get a free slot");
+    assert_eq!(limiter.in_flight(), 3);
+
+    drop(_busy1);
+    let _busy3 = blocked.await.unwrap();
+    assert_eq!(limiter.in_flight(), 3);
+    drop(quiet);
+}
+
+#[tokio::test]
+async fn test_cancelled_waiter_releases_its_slot() {
+    let limiter = FairConcurrencyLimiter::new(ConcurrencyConfig {
+        max_concurrent_requests: 1,
+        max_per_connection: None,
+    });
+
+    let held = limiter.acquire("a").await;
+    let timed_out = tokio::time::timeout(Duration::from_millis(10), limiter.acquire("b")).await;
+    assert!(timed_out.is_err());
+    drop(held);
+
+    assert_eq!(limiter.in_flight(), 0);
+    assert_eq!(limiter.queued(), 0);
+    let _permit = tokio::time::timeout(Duration::from_secs(1), limiter.acquire("b"))
+        .await
+        .expect("slot should be free after the cancelled waiter");
+}
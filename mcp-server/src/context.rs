@@ -1,9 +1,144 @@
 //! Request context for MCP operations
 
+use crate::tool_context::{NotificationSender, ToolContextError};
+use pulseengine_auth::AuthContext;
+use pulseengine_logging::LogSanitizer;
+use pulseengine_logging::sanitization::get_sanitizer;
 use pulseengine_mcp_protocol::Implementation;
-use std::collections::HashMap;
+use serde_json::Value;
+use std::collections::{BTreeMap, HashMap};
+use std::sync::{Arc, Mutex};
 use uuid::Uuid;
 
+tokio::task_local! {
+    /// Context of the request currently being dispatched to the backend
+    static REQUEST_CONTEXT: RequestContext;
+
+    /// Authentication of the caller whose requests are being handled
+    static AUTH_CONTEXT: AuthContext;
+}
+
+/// Get the context of the request currently being handled
+///
+/// Returns `None` if called outside of request dispatch
+pub fn try_current_request_context() -> Option<RequestContext> {
+    REQUEST_CONTEXT.try_with(|ctx| ctx.clone()).ok()
+}
+
+/// Execute an async block with a request context
+pub async fn with_request_context<F, T>(context: RequestContext, f: F) -> T
+where
+    F: std::future::Future<Output = T>,
+{
+    REQUEST_CONTEXT.scope(context, f).await
+}
+
+/// Get the authentication context of the current caller
+///
+/// Returns `None` outside a [`with_auth_context`] scope
+pub fn try_current_auth_context() -> Option<AuthContext> {
+    AUTH_CONTEXT.try_with(|auth| auth.clone()).ok()
+}
+
+/// Handle requests in an async block on behalf of an authenticated caller
+///
+/// Transports or embedders that authenticate the caller wrap request
+/// handling in this so the handler can enforce per-resource permissions.
+pub async fn with_auth_context<F, T>(auth: AuthContext, f: F) -> T
+where
+    F: std::future::Future<Output = T>,
+{
+    AUTH_CONTEXT.scope(auth, f).await
+}
+
+/// Sends `notifications/progress` for the request being handled
+///
+/// Obtained from [`RequestContext::progress`]. Reporting is a no-op when the
+/// client didn't put a `progressToken` in the request's `_meta`, or when no
+/// transport is available to deliver notifications.
+///
+/// # Example
+/// ```rust,ignore
+/// let progress = try_current_request_context()
+///     .map(|ctx| ctx.progress().clone())
+///     .unwrap_or_default();
+/// for (done, item) in batch.iter().enumerate() {
+///     process(item).await;
+///     progress
+///         .report(done as f64 + 1.0, Some(batch.len() as f64), Some(format!("Processed {item}")))
+///         .await?;
+/// }
+/// ```
+#[derive(Clone, Default)]
+pub struct ProgressReporter {
+    target: Option<(Value, Arc<dyn NotificationSender>)>,
+}
+
+impl std::fmt::Debug for ProgressReporter {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ProgressReporter")
+            .field("progress_token", &self.progress_token())
+            .finish()
+    }
+}
+
+impl ProgressReporter {
+    /// Report progress against `progress_token` through `sender`
+    pub fn new(progress_token: Value, sender: Arc<dyn NotificationSender>) -> Self {
+        Self {
+            target: Some((progress_token, sender)),
+        }
+    }
+
+    /// A reporter that discards every report
+    pub fn disabled() -> Self {
+        Self::default()
+    }
+
+    /// Whether reports reach the client
+    pub fn is_enabled(&self) -> bool {
+        self.target.is_some()
+    }
+
+    /// The client's progress token, a string or integer
+    pub fn progress_token(&self) -> Option<&Value> {
+        self.target.as_ref().map(|(token, _)| token)
+    }
+
+    /// Send a progress notification
+    ///
+    /// `progress` should increase with every call; `total` is omitted when
+    /// unknown.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the notification couldn't be delivered
+    pub async fn report(
+        &self,
+        progress: f64,
+        total: Option<f64>,
+        message: Option<String>,
+    ) -> Result<(), ToolContextError> {
+        let Some((token, sender)) = &self.target else {
+            return Ok(());
+        };
+
+        let mut params = serde_json::json!({
+            "progressToken": token,
+            "progress": progress,
+        });
+        if let Some(total) = total {
+            params["total"] = total.into();
+        }
+        if let Some(message) = message {
+            params["message"] = message.into();
+        }
+        sender
+            .send_notification("notifications/progress", params)
+            .await
+    }
+}
+
 /// Request context containing metadata and client information
 #[derive(Debug, Clone)]
 pub struct RequestContext {
@@ -13,10 +148,18 @@ pub struct RequestContext {
     pub metadata: HashMap<String, String>,
     /// Client information
     pub client_info: Option<Implementation>,
+    /// Protocol version negotiated by the session's `initialize` handshake
+    pub protocol_version: Option<String>,
     /// Authentication information
     pub authenticated_user: Option<String>,
     /// Authorization roles
     pub roles: Vec<String>,
+    /// Caller's authentication context, when the request was authenticated
+    pub auth_context: Option<AuthContext>,
+    /// Custom attributes recorded on the request's tracing span (shared across clones)
+    span_attributes: Arc<Mutex<BTreeMap<String, String>>>,
+    /// Progress notifications for this request
+    progress: ProgressReporter,
 }
 
 impl RequestContext {
@@ -26,8 +169,12 @@ impl RequestContext {
             request_id: Uuid::new_v4(),
             metadata: HashMap::new(),
             client_info: None,
+            protocol_version: None,
             authenticated_user: None,
             roles: vec![],
+            auth_context: None,
+            span_attributes: Arc::default(),
+            progress: ProgressReporter::default(),
         }
     }
 
@@ -37,8 +184,12 @@ impl RequestContext {
             request_id,
             metadata: HashMap::new(),
             client_info: None,
+            protocol_version: None,
             authenticated_user: None,
             roles: vec![],
+            auth_context: None,
+            span_attributes: Arc::default(),
+            progress: ProgressReporter::default(),
         }
     }
 
@@ -48,6 +199,12 @@ impl RequestContext {
         self
     }
 
+    /// Set the negotiated protocol version
+    pub fn with_protocol_version(mut self, protocol_version: impl Into<String>) -> Self {
+        self.protocol_version = Some(protocol_version.into());
+        self
+    }
+
     /// Set authenticated user
     pub fn with_user(mut self, user: impl Into<String>) -> Self {
         self.authenticated_user = Some(user.into());
@@ -60,12 +217,41 @@ impl RequestContext {
         self
     }
 
+    /// Set the caller's authentication context
+    ///
+    /// Also fills in the authenticated user (the user id, or the API key id
+    /// when there is none) and the caller's role names.
+    pub fn with_auth(mut self, auth: AuthContext) -> Self {
+        if let Some(user) = auth.user_id.as_ref().or(auth.api_key_id.as_ref()) {
+            self.authenticated_user = Some(user.clone());
+        }
+        for role in &auth.roles {
+            let role = role.to_string();
+            if !self.roles.contains(&role) {
+                self.roles.push(role);
+            }
+        }
+        self.auth_context = Some(auth);
+        self
+    }
+
     /// Add metadata
     pub fn with_metadata(mut self, key: impl Into<String>, value: impl Into<String>) -> Self {
         self.metadata.insert(key.into(), value.into());
         self
     }
 
+    /// Report progress through `reporter`
+    pub fn with_progress_reporter(mut self, reporter: ProgressReporter) -> Self {
+        self.progress = reporter;
+        self
+    }
+
+    /// Progress reporter for this request (a no-op unless the client asked for progress)
+    pub fn progress(&self) -> &ProgressReporter {
+        &self.progress
+    }
+
     /// Get metadata value
     pub fn get_metadata(&self, key: &str) -> Option<&String> {
         self.metadata.get(key)
@@ -76,6 +262,30 @@ impl RequestContext {
         self.roles.contains(&role.to_string())
     }
 
+    /// Record a custom attribute on the request's tracing span
+    ///
+    /// Values of sensitive-looking keys (tokens, passwords, ...) are redacted,
+    /// and other values pass through the global log sanitizer.
+    pub fn record_span_attribute(&self, key: impl Into<String>, value: impl AsRef<str>) {
+        let key = key.into();
+        let value = if LogSanitizer::is_sensitive_field(&key) {
+            "[REDACTED]".to_string()
+        } else {
+            get_sanitizer().sanitize(value.as_ref())
+        };
+        if let Ok(mut attributes) = self.span_attributes.lock() {
+            attributes.insert(key, value);
+        }
+    }
+
+    /// Get the custom span attributes recorded so far
+    pub fn span_attributes(&self) -> BTreeMap<String, String> {
+        self.span_attributes
+            .lock()
+            .map(|attributes| attributes.clone())
+            .unwrap_or_default()
+    }
+
     /// Check if user is authenticated
     pub fn is_authenticated(&self) -> bool {
         self.authenticated_user.is_some()
@@ -163,6 +163,34 @@ fn test_request_context_is_authenticated() {
     assert!(authenticated_context.is_authenticated());
 }
 
+#[test]
+fn test_request_context_with_auth_sets_user_and_roles() {
+    use pulseengine_auth::{AuthContext, Role};
+
+    let auth = AuthContext {
+        user_id: Some("alice".to_string()),
+        roles: vec![Role::Operator, Role::Monitor],
+        api_key_id: Some("key-1".to_string()),
+        permissions: vec![],
+    };
+    let context = RequestContext::new().with_role("operator").with_auth(auth);
+    assert_eq!(context.authenticated_user.as_deref(), Some("alice"));
+    assert!(context.is_authenticated());
+    assert_eq!(context.roles, vec!["operator", "monitor"]);
+    assert!(context.auth_context.is_some());
+
+    // API keys without a user are identified by their key id
+    let auth = AuthContext {
+        user_id: None,
+        roles: vec![Role::Admin],
+        api_key_id: Some("key-2".to_string()),
+        permissions: vec![],
+    };
+    let context = RequestContext::new().with_auth(auth);
+    assert_eq!(context.authenticated_user.as_deref(), Some("key-2"));
+    assert!(context.has_role("admin"));
+}
+
 #[test]
 fn test_request_context_has_role_empty() {
     let context = RequestContext::new();
@@ -292,3 +320,34 @@ fn test_request_context_metadata_empty_values() {
     assert_eq!(context.get_metadata("empty_key"), Some(&"".to_string()));
     assert_eq!(context.get_metadata(""), Some(&"empty_value".to_string()));
 }
+
+#[test]
+fn test_span_attributes_shared_and_redacted() {
+    let context = RequestContext::new();
+    let clone = context.clone();
+
+    clone.record_span_attribute("tenant", "acme");
+    clone.record_span_attribute("client_secret", "hunter2");
+
+    let attributes = context.span_attributes();
+    assert_eq!(attributes["tenant"], "acme");
+    assert_eq!(attributes["client_secret"], "[REDACTED]");
+}
+
+#[tokio::test]
+async fn test_current_request_context_scope() {
+    use crate::context::{try_current_request_context, with_request_context};
+
+    assert!(try_current_request_context().is_none());
+
+    let context = RequestContext::new();
+    let id = context.request_id;
+    with_request_context(context.clone(), async {
+        let current = try_current_request_context().unwrap();
+        assert_eq!(current.request_id, id);
+        current.record_span_attribute("step", "inner");
+    })
+    .await;
+
+    assert_eq!(context.span_attributes()["step"], "inner");
+}
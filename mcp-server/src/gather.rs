@@ -0,0 +1,93 @@
+//! Deadline-bounded aggregation for backends that fan out to several sources
+//!
+//! A backend that assembles one response from several async sources is only
+//! as fast as its slowest source, and hangs if any of them stalls.
+//! [`gather_with_deadline`] polls every source concurrently, keeps whatever
+//! finished by the deadline and drops the rest, so the backend can answer
+//! with a partial result and say which sources are missing.
+
+use futures::stream::{FuturesUnordered, StreamExt};
+use std::future::Future;
+use std::time::Duration;
+use tokio::time::Instant;
+
+/// Results collected by [`gather_with_deadline`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Gathered<T> {
+    /// One entry per source in input order; `None` for sources that stalled
+    pub results: Vec<Option<T>>,
+    /// The deadline the sources were held to
+    pub deadline: Duration,
+}
+
+impl<T> Gathered<T> {
+    /// Whether every source finished before the deadline
+    pub fn is_complete(&self) -> bool {
+        self.results.iter().all(Option::is_some)
+    }
+
+    /// Indices of the sources that missed the deadline
+    pub fn stalled(&self) -> Vec<usize> {
+        self.results
+            .iter()
+            .enumerate()
+            .filter_map(|(index, result)| result.is_none().then_some(index))
+            .collect()
+    }
+
+    /// Results of the sources that finished, in input order
+    pub fn completed(&self) -> impl Iterator<Item = &T> {
+        self.results.iter().flatten()
+    }
+
+    /// Consume into the completed results, in input order
+    pub fn into_completed(self) -> Vec<T> {
+        self.results.into_iter().flatten().collect()
+    }
+
+    /// Note to attach to a partial result, or `None` when nothing stalled
+    pub fn stall_note(&self) -> Option<String> {
+        let stalled = self.stalled();
+        if stalled.is_empty() {
+            return None;
+        }
+        let sources = stalled
+            .iter()
+            .map(usize::to_string)
+            .collect::<Vec<_>>()
+            .join(", ");
+        Some(format!(
+            "Partial result: {} of {} sources did not respond within {:?} (stalled: {sources})",
+            stalled.len(),
+            self.results.len(),
+            self.deadline
+        ))
+    }
+}
+
+/// Run `futures` concurrently and collect the ones that finish within
+/// `deadline`
+///
+/// Sources still pending at the deadline are dropped, which cancels them,
+/// and show up as `None` in [`Gathered::results`]. Returns as soon as every
+/// source has finished, so the deadline only costs time when something
+/// stalls.
+pub async fn gather_with_deadline<I, F>(futures: I, deadline: Duration) -> Gathered<F::Output>
+where
+    I: IntoIterator<Item = F>,
+    F: Future,
+{
+    let expires = Instant::now() + deadline;
+    let mut pending: FuturesUnordered<_> = futures
+        .into_iter()
+        .enumerate()
+        .map(|(index, future)| async move { (index, future.await) })
+        .collect();
+    let mut results: Vec<Option<F::Output>> = (0..pending.len()).map(|_| None).collect();
+
+    while let Ok(Some((index, output))) = tokio::time::timeout_at(expires, pending.next()).await {
+        results[index] = Some(output);
+    }
+
+    Gathered { results, deadline }
+}
@@ -0,0 +1,84 @@
+//! Tests for deadline-bounded aggregation
+
+use crate::gather::*;
+use std::time::Duration;
+
+async fn source(value: u32, delay: Duration) -> u32 {
+    tokio::time::sleep(delay).await;
+    value
+}
+
+#[tokio::test]
+async fn test_all_sources_finish_in_time() {
+    let gathered = gather_with_deadline(
+        [
+            source(1, Duration::from_millis(5)),
+            source(2, Duration::ZERO),
+            source(3, Duration::from_millis(1)),
+        ],
+        Duration::from_secs(5),
+    )
+    .await;
+
+    assert!(gathered.is_complete());
+    assert!(gathered.stalled().is_empty());
+    assert_eq!(gathered.stall_note(), None);
+    // Results keep input order regardless of completion order
+    assert_eq!(gathered.into_completed(), vec![1, 2, 3]);
+}
+
+#[tokio::test]
+async fn test_stalled_source_is_reported_and_others_kept() {
+    let started = std::time::Instant::now();
+    let gathered = gather_with_deadline(
+        [
+            source(1, Duration::ZERO),
+            source(2, Duration::from_secs(30)),
+            source(3, Duration::from_millis(5)),
+        ],
+        Duration::from_millis(100),
+    )
+    .await;
+
+    assert!(started.elapsed() < Duration::from_secs(5));
+    assert!(!gathered.is_complete());
+    assert_eq!(gathered.results, vec![Some(1), None, Some(3)]);
+    assert_eq!(gathered.stalled(), vec![1]);
+    assert_eq!(
+        gathered.completed().copied().collect::<Vec<_>>(),
+        vec![1, 3]
+    );
+
+    let note = gathered.stall_note().unwrap();
+    assert!(note.contains("1 of 3 sources"), "{note}");
+    assert!(note.contains("(stalled: 1)"), "{note}");
+}
+
+#[tokio::test]
+async fn test_stalled_sources_are_cancelled() {
+    struct DropFlag(std::sync::Arc<std::sync::atomic::AtomicBool>);
+    impl Drop for DropFlag {
+        fn drop(&mut self) {
+            self.0.store(true, std::sync::atomic::Ordering::SeqCst);
+        }
+    }
+
+    let dropped = std::sync::Arc::new(std::sync::atomic::AtomicBool::new(false));
+    let flag = DropFlag(dropped.clone());
+    let stalled = async move {
+        let _flag = flag;
+        std::future::pending::<u32>().await
+    };
+
+    let gathered = gather_with_deadline([stalled], Duration::from_millis(20)).await;
+    assert_eq!(gathered.results, vec![None]);
+    assert!(dropped.load(std::sync::atomic::Ordering::SeqCst));
+}
+
+#[tokio::test]
+async fn test_no_sources() {
+    let gathered =
+        gather_with_deadline(Vec::<std::future::Ready<u32>>::new(), Duration::ZERO).await;
+    assert!(gathered.is_complete());
+    assert!(gathered.results.is_empty());
+}
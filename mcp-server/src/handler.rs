@@ -1,18 +1,52 @@
 //! Generic request handler for MCP protocol
 
-use crate::tool_context::{NoOpToolContext, ToolContext, create_tool_context, with_context};
-use crate::{backend::McpBackend, context::RequestContext, middleware::MiddlewareStack};
-use pulseengine_auth::AuthenticationManager;
-use pulseengine_logging::{get_metrics, spans};
+use crate::adaptive_timeout::{AdaptiveTimeoutConfig, request_timeout_error, serialized_size};
+use crate::build_info::BuildInfo;
+use crate::cancellation::{CANCELLED_NOTIFICATION_METHOD, InFlightRequests};
+use crate::capability_filter::CapabilityFilterConfig;
+use crate::client_policy::ClientPolicy;
+use crate::concurrency::{ConcurrencyConfig, FairConcurrencyLimiter};
+use crate::context::{ProgressReporter, RequestContext, with_request_context};
+use crate::load_shedding::LoadShedder;
+use crate::memory_guard::MemoryGuard;
+use crate::notification_retry::{
+    NotificationDelivery, NotificationDeliveryStats, NotificationRetryConfig,
+};
+use crate::observability::ToolUsageAnalytics;
+use crate::protocol_session::{
+    ConcurrentInitialize, DEFAULT_SESSION_KEY, InitializedStrictness, ProtocolSession,
+    ProtocolSessionStats, ProtocolSessions,
+};
+use crate::resource_access::ResourceAccessPolicy;
+use crate::resource_compression::ResourceCompressionConfig;
+use crate::resource_subscriptions::ResourceSubscriptionManager;
+use crate::result_transform::{ResultTransform, ResultTransformPipeline};
+use crate::tool_context::{
+    NoOpToolContext, ToolContext, TransportBridge, create_signed_tool_context, with_context,
+};
+use crate::verbosity::ListVerbosity;
+use crate::{backend::McpBackend, middleware::MiddlewareStack};
+use pulseengine_auth::middleware::{AuthMiddlewareError, McpAuthMiddleware};
+use pulseengine_auth::permissions::PermissionChecker;
+use pulseengine_auth::transport::auth_extractors::TransportType;
+use pulseengine_auth::{AuthContext, AuthenticationManager};
+use pulseengine_logging::sanitization::{LogSanitizer, SanitizationConfig, get_sanitizer};
+use pulseengine_logging::{SamplingConfig, TraceSampler, get_metrics, spans};
+use pulseengine_mcp_protocol::signing::RequestSigner;
 use pulseengine_mcp_protocol::*;
-use pulseengine_mcp_transport::{Transport, try_current_session_id};
-
-use std::collections::HashSet;
+use pulseengine_mcp_transport::{
+    ConnectionClosed, StreamingNotification, Transport, try_current_connection,
+    try_current_session_id, try_notification_sender,
+};
+
+use futures::{FutureExt, StreamExt};
+use std::collections::{HashMap, HashSet};
+use std::panic::AssertUnwindSafe;
 use std::sync::Arc;
-use std::time::Instant;
+use std::time::{Duration, Instant};
 use thiserror::Error;
 use tokio::sync::RwLock;
-use tracing::{debug, error, info, instrument};
+use tracing::{debug, error, info, instrument, warn};
 
 /// Error type for handler operations
 #[derive(Debug, Error)]
@@ -28,6 +62,9 @@ pub enum HandlerError {
 
     #[error("Protocol error: {0}")]
     Protocol(#[from] Error),
+
+    #[error("Request {0} was cancelled by the client")]
+    Cancelled(NumberOrString),
 }
 
 // Implement ErrorClassification for HandlerError
@@ -38,6 +75,7 @@ impl pulseengine_logging::ErrorClassification for HandlerError {
             HandlerError::Authorization(_) => "authorization",
             HandlerError::Backend(_) => "backend",
             HandlerError::Protocol(_) => "protocol",
+            HandlerError::Cancelled(_) => "cancelled",
         }
     }
 
@@ -70,15 +108,89 @@ pub struct GenericServerHandler<B: McpBackend> {
     backend: Arc<B>,
     #[allow(dead_code)]
     auth_manager: Arc<AuthenticationManager>,
+    /// Optional authentication of transport callers from their connection
+    caller_auth: Option<Arc<McpAuthMiddleware>>,
     middleware: MiddlewareStack,
-    /// Global subscription registry tracking subscribed resource URIs
-    /// Note: This is a simplified global implementation. For per-client
-    /// subscriptions, use a `HashMap<ClientId, HashSet<String>>` instead.
-    subscriptions: Arc<RwLock<HashSet<String>>>,
+    /// Resource subscriptions per session, used to fan out update notifications
+    subscriptions: ResourceSubscriptionManager,
+    /// Advertise `resources.subscribe` because a manager was supplied
+    advertise_subscriptions: bool,
+    /// Protocol version and client capabilities negotiated per session
+    sessions: ProtocolSessions,
     /// Optional transport reference for bidirectional communication (shared across clones)
     /// When set, enables tools to send notifications and make requests to the client.
     /// Uses Arc<RwLock<...>> so that all clones of the handler share the same transport.
     transport: Arc<RwLock<Option<Arc<dyn Transport>>>>,
+    /// Optional pre-compression of large `resources/read` payloads
+    resource_compression: Option<ResourceCompressionConfig>,
+    /// Fill omitted tool arguments from input schema `default` values before dispatch
+    resolve_argument_defaults: bool,
+    /// Optional HMAC signing of server-initiated requests
+    request_signer: Option<Arc<RequestSigner>>,
+    /// Reject methods other than `initialize`/`ping` before the handshake
+    require_initialization: bool,
+    /// Handling of requests sent before `notifications/initialized`
+    initialized_strictness: InitializedStrictness,
+    /// Optional limit on concurrently executing requests, shared fairly
+    /// across connections
+    concurrency: Option<FairConcurrencyLimiter>,
+    /// Requests of one JSON-RPC batch dispatched at once
+    batch_concurrency: usize,
+    /// Reject batches in which two requests share an id
+    unique_batch_ids: bool,
+    /// Optional sanitization of outgoing error `data`
+    error_data_sanitizer: Option<Arc<LogSanitizer>>,
+    /// Optional enforcement of backend-hinted tool memory budgets
+    memory_guard: Option<MemoryGuard>,
+    /// Post-processing applied to every tool result
+    result_transforms: ResultTransformPipeline,
+    /// Optional allow/deny lists checked against `clientInfo` on initialize
+    client_policy: Option<Arc<ClientPolicy>>,
+    /// Optional hiding of features the client's capabilities can't use
+    capability_filter: Option<CapabilityFilterConfig>,
+    /// Optional per-method sampling of request spans (every request traced when `None`)
+    trace_sampler: Option<Arc<TraceSampler>>,
+    /// Turn panics while handling a request into `internal_error` responses
+    catch_panics: bool,
+    /// Optional aggregate tool usage analytics
+    tool_analytics: Option<Arc<ToolUsageAnalytics>>,
+    /// Check tool results against their declared output schema before responding
+    validate_tool_output: bool,
+    /// Size bounds on structured content checked by output validation
+    structured_content_limits: StructuredContentLimits,
+    /// Requests being handled, cancellable via `notifications/cancelled`
+    in_flight: InFlightRequests,
+    /// Optional retry of failed resource update notifications
+    notification_delivery: Option<NotificationDelivery>,
+    /// Optional upper bound on tool call duration
+    max_tool_timeout: Option<Duration>,
+    /// Optional timeout for every request, scaled by its size
+    adaptive_timeout: Option<AdaptiveTimeoutConfig>,
+    /// Optional rejection of low-priority requests while the backend is degraded
+    load_shedder: Option<LoadShedder>,
+    /// Permission and scheme checks applied before resource reads
+    resource_access: ResourceAccessPolicy,
+    /// Optional build metadata reported in the `initialize` result
+    build_info: Option<BuildInfo>,
+}
+
+/// Error for a tool call cancelled after exceeding its time limit
+fn tool_timeout_error(tool: &str, limit: Duration) -> Error {
+    let limit_ms = limit.as_millis() as u64;
+    Error::with_data(
+        ErrorCode::InternalError,
+        format!("Tool '{tool}' timed out after {limit_ms}ms"),
+        serde_json::json!({ "tool": tool, "timeoutMs": limit_ms }),
+    )
+}
+
+/// Text of a caught panic payload
+fn panic_message(panic: &(dyn std::any::Any + Send)) -> &str {
+    panic
+        .downcast_ref::<&str>()
+        .copied()
+        .or_else(|| panic.downcast_ref::<String>().map(String::as_str))
+        .unwrap_or("non-string panic payload")
 }
 
 /// Helper to create a JSON-RPC response with a result
@@ -103,6 +215,148 @@ fn make_empty_response(id: Option<NumberOrString>) -> Response {
     }
 }
 
+/// Key identifying the current session for negotiation state
+fn current_session_key() -> String {
+    try_current_session_id().unwrap_or_else(|| DEFAULT_SESSION_KEY.to_string())
+}
+
+/// Key identifying the caller for state that must not be shared between
+/// clients, such as cancellable requests and fair concurrency slots
+///
+/// The session where the transport scoped one, otherwise the connection, so
+/// clients of transports without sessions (e.g. WebSocket) stay apart.
+fn current_caller_key() -> String {
+    try_current_session_id()
+        .or_else(|| {
+            try_current_connection()
+                .map(|connection| connection.connection_id)
+                .filter(|id| !id.is_empty())
+        })
+        .unwrap_or_else(|| DEFAULT_SESSION_KEY.to_string())
+}
+
+/// Notification carrying one content chunk of a streaming tool call
+///
+/// Params are `{ "requestId", "index", "content" }`, where `requestId` is the
+/// id of the `tools/call` request the chunk belongs to.
+pub const TOOL_RESULT_CHUNK_METHOD: &str = "notifications/tools/resultChunk";
+
+/// Notification a client sends once it has received the `initialize` result
+pub const INITIALIZED_NOTIFICATION_METHOD: &str = "notifications/initialized";
+
+/// Pages of `tools/list` followed when looking up a tool definition
+const MAX_TOOL_LIST_PAGES: usize = 100;
+
+/// Requests of one JSON-RPC batch dispatched at once unless configured
+pub use pulseengine_mcp_transport::batch::DEFAULT_BATCH_CONCURRENCY;
+
+/// Outcome of a tool call before output validation and result transforms
+struct ToolOutput {
+    /// The result, or for a streaming tool every chunk buffered into one
+    result: CallToolResult,
+    /// Whether the chunks were already sent to the client, transformed
+    streamed: bool,
+}
+
+/// Run a streaming tool call to completion
+///
+/// Within a streamable HTTP request every chunk is run through `transforms`
+/// and forwarded as a [`TOOL_RESULT_CHUNK_METHOD`] SSE event as soon as it is
+/// produced. Either way the chunks are also buffered into one result, with
+/// adjacent text concatenated, which clients that can't stream receive as an
+/// ordinary result and which output validation checks; a tool that declares
+/// an output schema streams its structured content as JSON text. The stream
+/// is dropped, which aborts the tool, as soon as the client disconnects.
+async fn run_streaming_tool<B: McpBackend>(
+    backend: &B,
+    params: CallToolRequestParam,
+    request_id: Option<NumberOrString>,
+    transforms: &ResultTransformPipeline,
+    structured: bool,
+) -> std::result::Result<ToolOutput, Error> {
+    let disconnected = || Error::internal_error("Client disconnected during streaming tool call");
+    let tool_name = params.name.clone();
+    let mut chunks = backend
+        .call_tool_streaming(params)
+        .await
+        .map_err(Into::into)?;
+    let sender = try_notification_sender();
+    let mut content: Vec<Content> = Vec::new();
+    let mut index = 0u64;
+
+    loop {
+        let chunk = match &sender {
+            Some(sender) => tokio::select! {
+                chunk = chunks.next() => chunk,
+                () = sender.closed() => {
+                    debug!("Client disconnected, aborting streaming tool call");
+                    return Err(disconnected());
+                }
+            },
+            None => chunks.next().await,
+        };
+        let Some(chunk) = chunk else { break };
+        let chunk = chunk.map_err(Into::into)?;
+
+        let chunk = match &sender {
+            Some(sender) => {
+                let mut transformed = CallToolResult::success(vec![chunk]);
+                transforms.apply(&tool_name, &mut transformed);
+                for chunk in &transformed.content {
+                    let notification = StreamingNotification {
+                        id: None,
+                        method: TOOL_RESULT_CHUNK_METHOD.to_string(),
+                        params: serde_json::json!({
+                            "requestId": request_id,
+                            "index": index,
+                            "content": chunk,
+                        }),
+                    };
+                    sender.send(notification).map_err(|_| disconnected())?;
+                    index += 1;
+                }
+                transformed.content
+            }
+            None => vec![chunk],
+        };
+        for chunk in chunk {
+            match (content.last_mut(), chunk) {
+                (Some(Content::Text { text, .. }), Content::Text { text: more, .. }) => {
+                    text.push_str(&more);
+                }
+                (_, chunk) => content.push(chunk),
+            }
+        }
+    }
+
+    let mut result = CallToolResult::success(content);
+    if structured && let [Content::Text { text, .. }] = result.content.as_slice() {
+        result.structured_content = serde_json::from_str(text).ok();
+    }
+    Ok(ToolOutput {
+        result,
+        streamed: sender.is_some(),
+    })
+}
+
+/// Record standardized method attributes on a request span
+fn record_span_attributes(span: &tracing::Span, request: &Request) {
+    let bytes = serde_json::to_vec(&request.params).map_or(0, |b| b.len());
+    span.record("mcp.request.bytes", bytes as u64);
+
+    let (attribute, param) = match request.method.as_str() {
+        "tools/call" => ("mcp.tool.name", "name"),
+        "prompts/get" => ("mcp.prompt.name", "name"),
+        "resources/read" | "resources/subscribe" | "resources/unsubscribe" => {
+            ("mcp.resource.uri", "uri")
+        }
+        _ => return,
+    };
+    if let Some(value) = request.params.get(param).and_then(|v| v.as_str()) {
+        span.record(attribute, get_sanitizer().sanitize(value));
+    }
+}
+
 /// Parse optional paginated params, defaulting to no cursor
 #[inline]
 fn parse_paginated_params(
@@ -126,11 +380,465 @@ impl<B: McpBackend> GenericServerHandler<B> {
             backend,
             auth_manager,
             middleware,
-            subscriptions: Arc::new(RwLock::new(HashSet::new())),
+            subscriptions: ResourceSubscriptionManager::new(),
+            advertise_subscriptions: false,
+            sessions: ProtocolSessions::new(),
             transport: Arc::new(RwLock::new(None)),
+            resource_compression: None,
+            resolve_argument_defaults: false,
+            request_signer: None,
+            require_initialization: false,
+            initialized_strictness: InitializedStrictness::Off,
+            concurrency: None,
+            batch_concurrency: DEFAULT_BATCH_CONCURRENCY,
+            unique_batch_ids: true,
+            error_data_sanitizer: None,
+            memory_guard: None,
+            result_transforms: ResultTransformPipeline::new(),
+            client_policy: None,
+            capability_filter: None,
+            trace_sampler: None,
+            catch_panics: true,
+            tool_analytics: None,
+            validate_tool_output: false,
+            structured_content_limits: StructuredContentLimits::default(),
+            in_flight: InFlightRequests::new(),
+            notification_delivery: None,
+            max_tool_timeout: None,
+            adaptive_timeout: None,
+            load_shedder: None,
+            resource_access: ResourceAccessPolicy::default(),
+            build_info: None,
+            caller_auth: None,
         }
     }
 
+    /// Enable compression of large resource payloads for clients that accept it
+    pub fn with_resource_compression(mut self, config: ResourceCompressionConfig) -> Self {
+        self.resource_compression = Some(config);
+        self
+    }
+
+    /// Resolve declared schema defaults for omitted tool arguments before dispatch
+    ///
+    /// When enabled, `tools/call` looks up the tool's `input_schema` and inserts
+    /// every property `default` the client didn't provide, so tools receive
+    /// fully-populated arguments.
+    pub fn with_argument_defaults(mut self, enabled: bool) -> Self {
+        self.resolve_argument_defaults = enabled;
+        self
+    }
+
+    /// Sign server-initiated requests (sampling, elicitation) with a shared secret
+    pub fn with_request_signing(mut self, signer: RequestSigner) -> Self {
+        self.request_signer = Some(Arc::new(signer));
+        self
+    }
+
+    /// Recover from panics raised while handling a request
+    ///
+    /// Enabled by default: a panicking backend call is logged with the
+    /// request's correlation id and answered with an internal error, and the
+    /// connection stays up. Disable to let panics propagate.
+    pub fn with_panic_recovery(mut self, enabled: bool) -> Self {
+        self.catch_panics = enabled;
+        self
+    }
+
+    /// Count `tools/call` requests per tool and day in `analytics`
+    ///
+    /// Only calls to tools the backend lists are counted. Callers are
+    /// identified by their API key, or their session or connection when
+    /// unauthenticated; only a hash of the identity is kept.
+    pub fn with_tool_analytics(mut self, analytics: Arc<ToolUsageAnalytics>) -> Self {
+        self.tool_analytics = Some(analytics);
+        self
+    }
+
+    /// Retry resource update notifications that fail transiently
+    ///
+    /// Sends to a closed connection are dropped rather than retried;
+    /// notifications failing every attempt are dead-lettered. Both are
+    /// counted in [`Self::notification_delivery_stats`].
+    pub fn with_notification_retry(mut self, config: NotificationRetryConfig) -> Self {
+        self.notification_delivery = Some(NotificationDelivery::new(config));
+        self
+    }
+
+    /// Cap how long a tool call may run
+    ///
+    /// Clients can ask for a tighter limit with `_meta.timeoutMs` on
+    /// `tools/call`; longer requests are clamped to this maximum, which also
+    /// applies when the client names no limit. A tool whose definition sets
+    /// `execution.timeoutMs` uses that limit instead, at the cost of a tool
+    /// lookup per call. A call that runs out of time is cancelled and
+    /// answered with a timeout error.
+    pub fn with_max_tool_timeout(mut self, timeout: Duration) -> Self {
+        self.max_tool_timeout = Some(timeout);
+        self
+    }
+
+    /// Time out every request after an allowance scaled by its size
+    ///
+    /// Each request gets the base allowance plus a per-KiB allowance for its
+    /// serialized params, capped at the configured maximum, so large payloads
+    /// have time to be processed while small requests stay tightly bounded.
+    /// A request that runs out of time is cancelled and answered with a
+    /// timeout error. Tool calls remain subject to their own timeout as well.
+    pub fn with_adaptive_timeout(mut self, config: AdaptiveTimeoutConfig) -> Self {
+        self.adaptive_timeout = Some(config);
+        self
+    }
+
+    /// Restrict resource reads, e.g. to an allowlist of URI schemes
+    ///
+    /// Permissions declared by the backend's
+    /// [`resource_permission`](McpBackend::resource_permission) are enforced
+    /// whether or not a policy is set.
+    pub fn with_resource_access(mut self, policy: ResourceAccessPolicy) -> Self {
+        self.resource_access = policy;
+        self
+    }
+
+    /// Report build metadata under `_meta.buildInfo` in the `initialize` result
+    ///
+    /// An empty [`BuildInfo`] is treated as no metadata.
+    pub fn with_build_info(mut self, info: BuildInfo) -> Self {
+        self.build_info = (!info.is_empty()).then_some(info);
+        self
+    }
+
+    /// Authenticate callers from the credentials and peer address of the
+    /// transport connection their request arrived on
+    ///
+    /// The resulting [`AuthContext`] is what per-tool rate limits and
+    /// resource permissions are checked against, along with the middleware's
+    /// [permission checker](McpAuthMiddleware::with_permission_checker) for
+    /// the tool, prompt or resource a request names. Requests already carrying
+    /// an auth context, such as those of an in-process client, keep theirs.
+    pub fn with_caller_auth(mut self, middleware: McpAuthMiddleware) -> Self {
+        self.caller_auth = Some(Arc::new(middleware));
+        self
+    }
+
+    /// Permission checker of `caller_auth` and the authenticated caller, for
+    /// checking the tool, prompt or resource a request names
+    fn permission_check(&self) -> Option<(&PermissionChecker, AuthContext)> {
+        let checker = self.caller_auth.as_ref()?.permission_checker()?;
+        let caller = crate::context::try_current_request_context()?.auth_context?;
+        Some((checker, caller))
+    }
+
+    /// Auth context of the caller of `method`: the one in scope, else the
+    /// one `caller_auth` derives from the current transport connection
+    async fn caller_auth_context(
+        &self,
+        method: &str,
+        request_id: Option<&NumberOrString>,
+    ) -> std::result::Result<Option<AuthContext>, HandlerError> {
+        if let Some(auth) = crate::context::try_current_auth_context() {
+            return Ok(Some(auth));
+        }
+        let Some(middleware) = &self.caller_auth else {
+            return Ok(None);
+        };
+
+        let connection = try_current_connection().unwrap_or_default();
+        let config = middleware.config();
+        let mut headers = HashMap::new();
+        let names = [
+            Some(config.auth_header_name.as_str()),
+            Some("X-API-Key"),
+            config.client_ip_header.as_deref(),
+        ];
+        for name in names.into_iter().flatten() {
+            if let Some(value) = connection.header(name) {
+                headers.insert(name.to_string(), value.to_string());
+            }
+        }
+        let transport = match connection.transport {
+            "websocket" => TransportType::WebSocket,
+            "stdio" | "unix" => TransportType::Stdio,
+            _ => TransportType::Http,
+        };
+
+        match middleware
+            .authenticate_connection(
+                &connection.connection_id,
+                &transport,
+                connection.peer_addr.map(|addr| addr.ip()),
+                method,
+                request_id.map(ToString::to_string),
+                Some(&headers),
+            )
+            .await
+        {
+            Ok(context) => Ok(context.auth.auth_context),
+            Err(AuthMiddlewareError::AccessDenied(reason)) => {
+                Err(HandlerError::Authorization(reason))
+            }
+            // Keep the reason the credentials were rejected for the client
+            Err(AuthMiddlewareError::Extraction(e)) => Err(HandlerError::Protocol(e.into())),
+            Err(e) => Err(HandlerError::Authentication(e.to_string())),
+        }
+    }
+
+    /// Time limit for a tool call: the client's `_meta.timeoutMs`, clamped
+    /// to the tool's own `execution.timeoutMs` or else the server maximum
+    fn tool_timeout(&self, params: &serde_json::Value, tool: Option<&Tool>) -> Option<Duration> {
+        let requested = params
+            .get("_meta")
+            .and_then(|meta| meta.get("timeoutMs"))
+            .and_then(serde_json::Value::as_u64)
+            .map(Duration::from_millis);
+        let limit = tool
+            .and_then(|tool| tool.execution.as_ref())
+            .and_then(|execution| execution.timeout_ms)
+            .map(Duration::from_millis)
+            .or(self.max_tool_timeout);
+        match (requested, limit) {
+            (Some(requested), Some(max)) => Some(requested.min(max)),
+            (requested, max) => requested.or(max),
+        }
+    }
+
+    /// Delivery counters, when notification retry is enabled
+    pub fn notification_delivery_stats(&self) -> Option<NotificationDeliveryStats> {
+        self.notification_delivery
+            .as_ref()
+            .map(NotificationDelivery::stats)
+    }
+
+    /// Track subscriptions in `manager`, shared with the backend
+    ///
+    /// The backend keeps a clone and calls
+    /// [`ResourceSubscriptionManager::notify_resource_changed`] when a
+    /// resource changes. `initialize` then advertises `resources.subscribe`
+    /// whenever the backend declares the resources capability.
+    pub fn with_resource_subscriptions(mut self, manager: ResourceSubscriptionManager) -> Self {
+        self.subscriptions = manager;
+        self.advertise_subscriptions = true;
+        self
+    }
+
+    /// Resource subscriptions tracked by this handler
+    pub fn resource_subscriptions(&self) -> &ResourceSubscriptionManager {
+        &self.subscriptions
+    }
+
+    /// Limit how many resources a single session may subscribe to
+    ///
+    /// `resources/subscribe` for a new URI beyond the limit is rejected
+    /// before it reaches the backend.
+    pub fn with_max_subscriptions_per_session(mut self, max: usize) -> Self {
+        self.sessions = self.sessions.with_max_subscriptions(max);
+        self
+    }
+
+    /// Choose how an `initialize` arriving while another for the same
+    /// session is in progress is handled (rejected by default)
+    pub fn with_concurrent_initialize(mut self, mode: ConcurrentInitialize) -> Self {
+        self.sessions = self.sessions.with_concurrent_initialize(mode);
+        self
+    }
+
+    /// Validate tool results against the tool's `output_schema` (strict mode)
+    ///
+    /// When enabled, a successful `tools/call` result whose structured content
+    /// is missing or doesn't match the declared schema is replaced with a
+    /// validation error naming the offending field. Intended for development
+    /// and testing.
+    pub fn with_output_validation(mut self, enabled: bool) -> Self {
+        self.validate_tool_output = enabled;
+        self
+    }
+
+    /// Bound the depth and size of structured content checked by output
+    /// validation (the [`StructuredContentLimits`] defaults otherwise)
+    pub fn with_structured_content_limits(mut self, limits: StructuredContentLimits) -> Self {
+        self.structured_content_limits = limits;
+        self
+    }
+
+    /// Enforce the initialization state machine
+    ///
+    /// When enabled, only `initialize` and `ping` are accepted until the
+    /// session has been initialized; every other method is rejected with an
+    /// invalid request error.
+    pub fn with_initialization_required(mut self, required: bool) -> Self {
+        self.require_initialization = required;
+        self
+    }
+
+    /// Track the client's `notifications/initialized` and choose how requests
+    /// sent before it are handled (accepted silently by default)
+    ///
+    /// Per MCP, a client sends `notifications/initialized` once it has
+    /// received the `initialize` result and should only send other requests
+    /// after that; `ping` and notifications are always accepted.
+    pub fn with_initialized_strictness(mut self, strictness: InitializedStrictness) -> Self {
+        self.initialized_strictness = strictness;
+        self
+    }
+
+    /// Reject lower-priority requests while the shedder's health signal
+    /// reports the backend degraded or unhealthy
+    ///
+    /// Shed requests fail fast with a busy error instead of waiting for an
+    /// execution slot; `ping` is never shed.
+    pub fn with_load_shedding(mut self, shedder: LoadShedder) -> Self {
+        self.load_shedder = Some(shedder);
+        self
+    }
+
+    /// Limit concurrently executing requests, sharing slots fairly so one
+    /// busy connection can't starve the others
+    pub fn with_concurrency_limit(mut self, config: ConcurrencyConfig) -> Self {
+        self.concurrency = Some(FairConcurrencyLimiter::new(config));
+        self
+    }
+
+    /// Dispatch at most `limit` requests of a JSON-RPC batch at once
+    ///
+    /// Applies to [`Self::handle_message`]; transports bound their batches
+    /// themselves, see [`pulseengine_mcp_transport::BatchConfig`]. Each
+    /// request still waits for a slot of [`Self::with_concurrency_limit`]
+    /// when that is configured.
+    pub fn with_batch_concurrency(mut self, limit: usize) -> Self {
+        self.batch_concurrency = limit.max(1);
+        self
+    }
+
+    /// Reject JSON-RPC batches in which two requests share an id
+    ///
+    /// Enabled by default: duplicate ids make it impossible for the client to
+    /// tell which response answers which request, so such a batch is answered
+    /// with a single invalid-request error without processing any entry.
+    /// Notifications, which carry no id, are never duplicates.
+    ///
+    /// Applies to [`Self::handle_message`]; transports check batches
+    /// themselves, see [`pulseengine_mcp_transport::BatchConfig`].
+    pub fn with_unique_batch_ids(mut self, enforce: bool) -> Self {
+        self.unique_batch_ids = enforce;
+        self
+    }
+
+    /// Sanitize the `data` of error responses before they reach the client
+    ///
+    /// Only takes effect when `config.enabled` is set (release builds by
+    /// default); see [`LogSanitizer::sanitize_error_data`].
+    pub fn with_error_data_sanitization(mut self, config: SanitizationConfig) -> Self {
+        self.error_data_sanitizer = Some(Arc::new(LogSanitizer::with_config(config)));
+        self
+    }
+
+    /// Abort tool calls that grow memory past their
+    /// [`McpBackend::tool_memory_budget`] hint
+    pub fn with_memory_guard(mut self, guard: MemoryGuard) -> Self {
+        self.memory_guard = Some(guard);
+        self
+    }
+
+    /// Register a transform run over every tool result after `call_tool`
+    pub fn with_result_transform(mut self, transform: impl ResultTransform + 'static) -> Self {
+        self.result_transforms.push(Arc::new(transform));
+        self
+    }
+
+    /// Replace the tool result transform pipeline
+    pub fn with_result_transforms(mut self, pipeline: ResultTransformPipeline) -> Self {
+        self.result_transforms = pipeline;
+        self
+    }
+
+    /// Refuse `initialize` from client implementations the policy rejects
+    pub fn with_client_policy(mut self, policy: ClientPolicy) -> Self {
+        self.client_policy = Some(Arc::new(policy));
+        self
+    }
+
+    /// Hide features from clients that don't declare the matching capability
+    pub fn with_capability_filter(mut self, filter: CapabilityFilterConfig) -> Self {
+        self.capability_filter = Some(filter);
+        self
+    }
+
+    /// Only create request spans for a sample of requests, per method
+    ///
+    /// Failed requests that weren't sampled are still traced when
+    /// `always_sample_errors` is set.
+    pub fn with_trace_sampling(mut self, config: SamplingConfig) -> Self {
+        self.trace_sampler = Some(Arc::new(TraceSampler::new(config)));
+        self
+    }
+
+    /// Capabilities the current session's client declared on initialize
+    async fn client_capabilities(&self) -> serde_json::Value {
+        self.sessions
+            .get(&current_session_key())
+            .await
+            .map(|session| session.client_capabilities)
+            .unwrap_or_default()
+    }
+
+    /// Reject a request that arrives before the session is initialized, or
+    /// before the client has confirmed it with `notifications/initialized`
+    async fn check_initialized(&self, method: &str) -> std::result::Result<(), Error> {
+        let check_handshake = self.initialized_strictness != InitializedStrictness::Off
+            && !method.starts_with("notifications/");
+        if !(self.require_initialization || check_handshake)
+            || matches!(method, "initialize" | "ping")
+        {
+            return Ok(());
+        }
+        let session = self.sessions.get(&current_session_key()).await;
+        if self.require_initialization && session.is_none() {
+            return Err(Error::invalid_request(format!(
+                "Server not initialized: send `initialize` before `{method}`"
+            )));
+        }
+        if check_handshake && !session.is_some_and(|session| session.client_initialized) {
+            if self.initialized_strictness == InitializedStrictness::Reject {
+                return Err(Error::invalid_request(format!(
+                    "Initialization not complete: send `notifications/initialized` before `{method}`"
+                )));
+            }
+            warn!(method = %method, "Request received before notifications/initialized");
+        }
+        Ok(())
+    }
+
+    /// Look up a tool definition by name, following pagination cursors
+    ///
+    /// Gives up, as if the tool wasn't listed, when the backend repeats a
+    /// cursor or pages past [`MAX_TOOL_LIST_PAGES`].
+    async fn find_tool(&self, name: &str) -> std::result::Result<Option<Tool>, Error> {
+        let mut cursor = None;
+        let mut seen = HashSet::new();
+        for _ in 0..MAX_TOOL_LIST_PAGES {
+            let page = self
+                .backend
+                .list_tools(PaginatedRequestParam { cursor })
+                .await
+                .map_err(|e| e.into())?;
+            if let Some(tool) = page.tools.into_iter().find(|t| t.name == name) {
+                return Ok(Some(tool));
+            }
+            match page.next_cursor {
+                Some(next) if !next.is_empty() => {
+                    if !seen.insert(next.clone()) {
+                        warn!(tool = %name, cursor = %next, "Tool listing repeated a cursor");
+                        return Ok(None);
+                    }
+                    cursor = Some(next);
+                }
+                _ => return Ok(None),
+            }
+        }
+        warn!(tool = %name, "Tool listing exceeded {MAX_TOOL_LIST_PAGES} pages");
+        Ok(None)
+    }
+
     /// Set the transport for bidirectional communication
     ///
     /// When set, tools can send notifications and make requests to the client
@@ -139,11 +847,51 @@ impl<B: McpBackend> GenericServerHandler<B> {
     pub fn set_transport(&self, transport: Arc<dyn Transport>) {
         // Use try_write to avoid blocking; if we can't acquire the lock,
         // another thread is updating it which is fine.
+        self.subscriptions
+            .attach(transport.clone(), self.notification_delivery.clone());
         if let Ok(mut guard) = self.transport.try_write() {
             *guard = Some(transport);
         }
     }
 
+    /// Release the state kept for a connection the transport reports closed:
+    /// its cached authentication, negotiated protocol session and
+    /// subscriptions
+    pub async fn connection_closed(&self, closed: &ConnectionClosed) {
+        if let Some(middleware) = &self.caller_auth {
+            middleware.end_connection(&closed.connection_id).await;
+        }
+        // Transports without sessions (stdio) serve a single client
+        let session = closed.session_id.as_deref().unwrap_or(DEFAULT_SESSION_KEY);
+        self.sessions.remove(session).await;
+        if let Some(session) = &closed.session_id {
+            let uris = self.subscriptions.remove_session(session).await;
+            debug!(session = %session, subscriptions = uris.len(), "Session closed");
+        }
+    }
+
+    /// Progress reporter for a request, enabled when it carries a `progressToken`
+    async fn progress_reporter(&self, request: &Request) -> ProgressReporter {
+        let Some(token) = request
+            .params
+            .get("_meta")
+            .and_then(|meta| meta.get("progressToken"))
+            .filter(|token| token.is_string() || token.is_number())
+        else {
+            return ProgressReporter::disabled();
+        };
+        match self.transport.read().await.as_ref() {
+            Some(transport) => ProgressReporter::new(
+                token.clone(),
+                Arc::new(TransportBridge::new(
+                    transport.clone(),
+                    try_current_session_id(),
+                )),
+            ),
+            None => ProgressReporter::disabled(),
+        }
+    }
+
     /// Create a ToolContext for the current request
     ///
     /// If a transport is set and supports bidirectional communication, returns
@@ -171,12 +919,13 @@ impl<B: McpBackend> GenericServerHandler<B> {
             if supports_bidir {
                 eprintln!("[DEBUG] Creating DefaultToolContext for {tool_name}");
                 // Use the factory function from tool_context module
-                create_tool_context(
+                create_signed_tool_context(
                     transport.clone(),
                     request_id,
                     tool_name,
                     progress_token,
                     session_id,
+                    self.request_signer.clone(),
                 )
             } else {
                 eprintln!("[DEBUG] Transport doesn't support bidirectional for {tool_name}");
@@ -195,17 +944,102 @@ impl<B: McpBackend> GenericServerHandler<B> {
     /// This is useful for Phase 3 notification delivery - backends can query
     /// which resources have active subscriptions before sending notifications.
     pub async fn get_subscribed_uris(&self) -> Vec<String> {
-        let subs = self.subscriptions.read().await;
-        subs.iter().cloned().collect()
+        self.subscriptions.subscribed_uris().await
     }
 
     /// Check if a specific resource URI has active subscriptions
     ///
-    /// Useful for optimizing notification delivery - only send notifications
-    /// for resources that have active subscribers.
+    /// A filtered subscription covers every resource matching its filter
+    /// rather than its own URI. Useful for optimizing notification delivery -
+    /// only send notifications for resources that have active subscribers.
     pub async fn is_subscribed(&self, uri: &str) -> bool {
-        let subs = self.subscriptions.read().await;
-        subs.contains(uri)
+        self.subscriptions.is_subscribed(uri).await
+    }
+
+    /// Notify clients that a subscribed resource changed
+    ///
+    /// Backends that can compute the change attach a JSON Patch with
+    /// [`ResourceUpdatedNotification::with_patch`]; clients that can't apply
+    /// it, and every update built with [`ResourceUpdatedNotification::full`],
+    /// fall back to re-reading the resource. Only the sessions subscribed to
+    /// the resource are notified. Returns `false` without sending anything
+    /// when the resource has no subscribers.
+    pub async fn notify_resource_updated(
+        &self,
+        update: ResourceUpdatedNotification,
+    ) -> std::result::Result<bool, Error> {
+        Ok(self.subscriptions.notify(update).await? > 0)
+    }
+
+    /// Get the negotiated protocol state for the current session
+    ///
+    /// Returns `None` until the session has sent `initialize`.
+    pub async fn negotiated_session(&self) -> Option<ProtocolSession> {
+        self.sessions.get(&current_session_key()).await
+    }
+
+    /// Get statistics for the current session, including its subscription count
+    pub async fn session_stats(&self) -> Option<ProtocolSessionStats> {
+        self.sessions.stats(&current_session_key()).await
+    }
+
+    /// Handle a raw JSON-RPC message, which may be a batch
+    ///
+    /// A top-level array is a batch: its entries are dispatched concurrently,
+    /// up to the batch concurrency limit, and their responses returned as an
+    /// array in the order of the requests. Notifications get no entry, so
+    /// `None` is returned for a single notification or a batch of only
+    /// notifications. An empty array and entries that aren't valid requests
+    /// are answered with an invalid-request error, as JSON-RPC 2.0 requires,
+    /// and so is a batch whose requests share an id, unless
+    /// [`Self::with_unique_batch_ids`] is disabled.
+    pub async fn handle_message(&self, message: serde_json::Value) -> Option<serde_json::Value> {
+        let responses = match message {
+            serde_json::Value::Array(entries) if entries.is_empty() => {
+                let error = Error::invalid_request("Batch must contain at least one request");
+                return Some(response_value(error_response(None, error)));
+            }
+            serde_json::Value::Array(entries) => {
+                if self.unique_batch_ids
+                    && let Some(id) = duplicate_batch_id(&entries)
+                {
+                    let error = Error::invalid_request(format!(
+                        "Batch contains more than one request with id {id}"
+                    ));
+                    return Some(response_value(error_response(None, error)));
+                }
+                debug!(size = entries.len(), "Handling batch");
+                futures::stream::iter(entries)
+                    .map(|entry| self.handle_batch_entry(entry))
+                    .buffered(self.batch_concurrency)
+                    .filter_map(std::future::ready)
+                    .collect::<Vec<_>>()
+                    .await
+            }
+            single => return self.handle_batch_entry(single).await.map(response_value),
+        };
+        (!responses.is_empty())
+            .then(|| serde_json::Value::Array(responses.into_iter().map(response_value).collect()))
+    }
+
+    /// Handle one message, returning a response unless it is a notification
+    async fn handle_batch_entry(&self, entry: serde_json::Value) -> Option<Response> {
+        let request: Request = match serde_json::from_value(entry) {
+            Ok(request) => request,
+            Err(e) => {
+                let error = Error::invalid_request(format!("Invalid request: {e}"));
+                return Some(error_response(None, error));
+            }
+        };
+        let id = request.id.clone();
+        let result = self.handle_request(request).await;
+        let id = id?;
+        match result {
+            Ok(response) => Some(response),
+            // A request the client cancelled goes unanswered
+            Err(HandlerError::Cancelled(_)) => None,
+            Err(error) => Some(error_response(Some(id), error.into())),
+        }
     }
 
     /// Handle an MCP request
@@ -221,8 +1055,20 @@ impl<B: McpBackend> GenericServerHandler<B> {
         // Store request ID before moving request
         let request_id = request.id.clone();
 
-        // Create request context
-        let context = RequestContext::new();
+        // Create request context, carrying what the session negotiated
+        let mut context =
+            RequestContext::new().with_progress_reporter(self.progress_reporter(&request).await);
+        if let Some(session) = self.sessions.get(&current_session_key()).await {
+            context = context
+                .with_protocol_version(session.protocol_version)
+                .with_client_info(session.client_info);
+        }
+        if let Some(auth) = self
+            .caller_auth_context(&method, request_id.as_ref())
+            .await?
+        {
+            context = context.with_auth(auth);
+        }
 
         // Get metrics collector
         let metrics = get_metrics();
@@ -233,32 +1079,135 @@ impl<B: McpBackend> GenericServerHandler<B> {
         // Apply middleware
         let request = self.middleware.process_request(request, &context).await?;
 
+        // Track the request so the client can cancel it while it runs
+        let in_flight = request_id
+            .clone()
+            .map(|id| self.in_flight.register(&current_caller_key(), id));
+
+        // Shed low-priority work while the backend is degraded, before it
+        // queues for an execution slot
+        let shed = self
+            .load_shedder
+            .as_ref()
+            .and_then(|shedder| shedder.check(&method).err());
+
+        // Wait for an execution slot, queued fairly per connection. A
+        // cancellation must not queue behind the requests it cancels.
+        let _permit = match (&self.concurrency, &shed) {
+            (Some(limiter), None) if method != CANCELLED_NOTIFICATION_METHOD => {
+                Some(limiter.acquire(&current_caller_key()).await)
+            }
+            _ => None,
+        };
+
         // Route to appropriate handler with tracing
+        let request_id_str = request_id
+            .as_ref()
+            .map(|id| id.to_string())
+            .unwrap_or_else(|| "none".to_string());
+        let request_timeout = self.adaptive_timeout.as_ref().map(|config| {
+            let request_bytes = serialized_size(&request.params);
+            (config.timeout_for(request_bytes), request_bytes)
+        });
+        let sampled = self
+            .trace_sampler
+            .as_ref()
+            .is_none_or(|sampler| sampler.should_sample(&method));
         let result = {
-            let request_id_str = request_id
-                .as_ref()
-                .map(|id| id.to_string())
-                .unwrap_or_else(|| "none".to_string());
-            let span = spans::mcp_request_span(&method, &request_id_str);
+            let span = if sampled {
+                spans::mcp_request_span(&method, &request_id_str)
+            } else {
+                tracing::Span::none()
+            };
             let _guard = span.enter();
+            record_span_attributes(&span, &request);
 
-            match request.method.as_str() {
-                "initialize" => self.handle_initialize(request).await,
-                "tools/list" => self.handle_list_tools(request).await,
-                "tools/call" => self.handle_call_tool(request).await,
-                "resources/list" => self.handle_list_resources(request).await,
-                "resources/read" => self.handle_read_resource(request).await,
-                "resources/templates/list" => self.handle_list_resource_templates(request).await,
-                "prompts/list" => self.handle_list_prompts(request).await,
-                "prompts/get" => self.handle_get_prompt(request).await,
-                "resources/subscribe" => self.handle_subscribe(request).await,
-                "resources/unsubscribe" => self.handle_unsubscribe(request).await,
-                "completion/complete" => self.handle_complete(request).await,
-                "elicitation/create" => self.handle_elicit(request).await,
-                "logging/setLevel" => self.handle_set_level(request).await,
-                "ping" => self.handle_ping(request).await,
-                _ => self.handle_custom_method(request).await,
+            let dispatch = async {
+                if let Some(error) = shed {
+                    return Err(error);
+                }
+                self.check_initialized(&request.method).await?;
+                match request.method.as_str() {
+                    "initialize" => self.handle_initialize(request).await,
+                    "tools/list" => self.handle_list_tools(request).await,
+                    "tools/call" => self.handle_call_tool(request).await,
+                    "resources/list" => self.handle_list_resources(request).await,
+                    "resources/read" => self.handle_read_resource(request).await,
+                    "resources/templates/list" => {
+                        self.handle_list_resource_templates(request).await
+                    }
+                    "prompts/list" => self.handle_list_prompts(request).await,
+                    "prompts/get" => self.handle_get_prompt(request).await,
+                    "resources/subscribe" => self.handle_subscribe(request).await,
+                    "resources/unsubscribe" => self.handle_unsubscribe(request).await,
+                    "completion/complete" => self.handle_complete(request).await,
+                    "elicitation/create" => self.handle_elicit(request).await,
+                    "logging/setLevel" => self.handle_set_level(request).await,
+                    "ping" => self.handle_ping(request).await,
+                    CANCELLED_NOTIFICATION_METHOD => self.handle_cancelled(request).await,
+                    INITIALIZED_NOTIFICATION_METHOD => self.handle_initialized(request).await,
+                    _ => self.handle_custom_method(request).await,
+                }
+            };
+            // Boxed so the timeout wrapper below doesn't inline the whole
+            // dispatch future into the caller's stack frame
+            let handling = Box::pin(with_request_context(context.clone(), async {
+                if !self.catch_panics {
+                    return dispatch.await;
+                }
+                AssertUnwindSafe(dispatch)
+                    .catch_unwind()
+                    .await
+                    .unwrap_or_else(|panic| {
+                        error!(
+                            method = %method,
+                            request_id = ?request_id,
+                            correlation_id = %context.request_id,
+                            panic = %panic_message(panic.as_ref()),
+                            "Request handler panicked"
+                        );
+                        Err(Error::internal_error(format!(
+                            "Internal error while handling {method} (correlation id {})",
+                            context.request_id
+                        )))
+                    })
+            }));
+            let handling = async {
+                match request_timeout {
+                    Some((limit, request_bytes)) => {
+                        tokio::time::timeout(limit, handling).await.unwrap_or_else(|_| {
+                            warn!(method = %method, request_id = ?request_id, timeout_ms = %limit.as_millis(), request_bytes, "Request timed out");
+                            Err(request_timeout_error(&method, limit, request_bytes))
+                        })
+                    }
+                    None => handling.await,
+                }
+            };
+            let result = match &in_flight {
+                Some(in_flight) => tokio::select! {
+                    result = handling => result,
+                    () = in_flight.cancelled() => {
+                        // Dropping `handling` aborts the backend call
+                        metrics
+                            .record_request_end(&method, start_time.elapsed(), false)
+                            .await;
+                        info!(method = %method, request_id = ?request_id, "Request cancelled by client");
+                        return Err(HandlerError::Cancelled(
+                            request_id.clone().expect("tracked requests have an id"),
+                        ));
+                    }
+                },
+                None => handling.await,
+            };
+
+            let attributes = context.span_attributes();
+            if !attributes.is_empty() {
+                span.record(
+                    "mcp.attributes",
+                    serde_json::to_string(&attributes).unwrap_or_default(),
+                );
             }
+            result
         };
 
         // Calculate request duration
@@ -281,7 +1230,7 @@ impl<B: McpBackend> GenericServerHandler<B> {
 
                 Ok(response)
             }
-            Err(error) => {
+            Err(mut error) => {
                 // Record failed request
                 metrics.record_request_end(&method, duration, false).await;
 
@@ -290,6 +1239,13 @@ impl<B: McpBackend> GenericServerHandler<B> {
                     .record_error(&method, &context.request_id.to_string(), &error, duration)
                     .await;
 
+                // Errors are traced even when the request wasn't sampled
+                let _error_span = self
+                    .trace_sampler
+                    .as_ref()
+                    .filter(|sampler| !sampled && sampler.should_sample_error())
+                    .map(|_| spans::mcp_request_span(&method, &request_id_str).entered());
+
                 error!(
                     method = %method,
                     duration_ms = %duration.as_millis(),
@@ -298,6 +1254,12 @@ impl<B: McpBackend> GenericServerHandler<B> {
                     "Request failed"
                 );
 
+                if let Some(sanitizer) = &self.error_data_sanitizer
+                    && let Some(data) = &error.data
+                {
+                    error.data = Some(sanitizer.sanitize_error_data(data));
+                }
+
                 Ok(Response {
                     jsonrpc: "2.0".to_string(),
                     id: request_id,
@@ -311,6 +1273,21 @@ impl<B: McpBackend> GenericServerHandler<B> {
     #[instrument(skip(self, request), fields(mcp.method = "initialize"))]
     async fn handle_initialize(&self, request: Request) -> std::result::Result<Response, Error> {
         let params: InitializeRequestParam = serde_json::from_value(request.params)?;
+        let _initializing = self
+            .sessions
+            .begin_initialize(&current_session_key())
+            .await?;
+
+        if let Some(policy) = &self.client_policy
+            && let Err(error) = policy.check(&params.client_info)
+        {
+            info!(
+                client = %params.client_info.name,
+                client_version = %params.client_info.version,
+                "Refusing client rejected by client policy"
+            );
+            return Err(error);
+        }
 
         // Negotiate protocol version: use the client's version if we support it,
         // otherwise fall back to the server's latest supported version
@@ -329,12 +1306,54 @@ impl<B: McpBackend> GenericServerHandler<B> {
             "Protocol version negotiated"
         );
 
-        let server_info = self.backend.get_server_info();
+        // Record the negotiation; a repeated initialize renegotiates in place
+        let renegotiation = self
+            .sessions
+            .initialize(
+                &current_session_key(),
+                negotiated_version.clone(),
+                params.capabilities,
+                params.client_info,
+            )
+            .await?;
+        if let Some(previous_version) = &renegotiation.previous_version {
+            info!(
+                previous_version = %previous_version,
+                negotiated_version = %negotiated_version,
+                "Session re-initialized"
+            );
+        }
+        if !renegotiation.invalidated_subscriptions.is_empty() {
+            for uri in &renegotiation.invalidated_subscriptions {
+                self.subscriptions
+                    .unsubscribe(&current_session_key(), uri)
+                    .await;
+            }
+            debug!(
+                count = renegotiation.invalidated_subscriptions.len(),
+                "Invalidated subscriptions from previous protocol version"
+            );
+        }
+
+        let mut server_info = self.backend.get_server_info();
+        if self.advertise_subscriptions
+            && let Some(resources) = server_info.capabilities.resources.as_mut()
+        {
+            resources.subscribe = Some(true);
+        }
+        if let Some(filter) = &self.capability_filter {
+            let client_capabilities = self.client_capabilities().await;
+            filter.filter_capabilities(&mut server_info.capabilities, &client_capabilities);
+        }
         let result = InitializeResult {
             protocol_version: negotiated_version,
             capabilities: server_info.capabilities,
             server_info: server_info.server_info.clone(),
             instructions: server_info.instructions,
+            _meta: self
+                .build_info
+                .as_ref()
+                .map(|info| serde_json::json!({ "buildInfo": info })),
         };
 
         Ok(Response {
@@ -347,19 +1366,90 @@ impl<B: McpBackend> GenericServerHandler<B> {
 
     #[instrument(skip(self, request), fields(mcp.method = "tools/list"))]
     async fn handle_list_tools(&self, request: Request) -> std::result::Result<Response, Error> {
+        let verbosity = ListVerbosity::from_params(&request.params);
         let params = parse_paginated_params(request.params)?;
-        let result = self
+        let mut result = self
             .backend
             .list_tools(params)
             .await
             .map_err(|e| e.into())?;
-        Ok(make_response(request.id, serde_json::to_value(result)?))
+        if let Some(filter) = &self.capability_filter {
+            filter.filter_tools(&mut result, &self.client_capabilities().await);
+        }
+        let mut result = serde_json::to_value(result)?;
+        verbosity.apply_to_tools(&mut result);
+        Ok(make_response(request.id, result))
     }
 
     #[instrument(skip(self, request), fields(mcp.method = "tools/call"))]
     async fn handle_call_tool(&self, request: Request) -> std::result::Result<Response, Error> {
-        let params: CallToolRequestParam = serde_json::from_value(request.params.clone())?;
+        let mut params: CallToolRequestParam = serde_json::from_value(request.params.clone())?;
         let tool_name = params.name.clone();
+
+        if let Some((checker, caller)) = self.permission_check() {
+            checker
+                .check_tool(&caller, &tool_name)
+                .await
+                .map_err(|e| Error::forbidden(e.to_string()))?;
+        }
+
+        // Rate limits of the authenticated caller, charged only for
+        // calls the caller is allowed to make
+        if let Some(auth) =
+            crate::context::try_current_request_context().and_then(|context| context.auth_context)
+            && self
+                .auth_manager
+                .check_tool_rate_limit(&auth, &tool_name)
+                .await
+                .map_err(|e| Error::internal_error(e.to_string()))?
+        {
+            warn!(tool = %tool_name, "Tool call rate limited");
+            return Err(Error::rate_limit_exceeded(format!(
+                "Rate limit exceeded for tool '{tool_name}'"
+            )));
+        }
+
+        // Listing tools can be expensive, so the definition is only looked up
+        // for features that need it: argument defaults, per-tool timeouts,
+        // analytics, output validation, streamed structured content and
+        // routing unlisted tools to the fallback
+        let streaming = self.backend.streams_tool(&tool_name);
+        let has_fallback = self.backend.has_fallback_tool();
+        let tool = if self.resolve_argument_defaults
+            || self.max_tool_timeout.is_some()
+            || self.tool_analytics.is_some()
+            || self.validate_tool_output
+            || streaming
+            || has_fallback
+        {
+            self.find_tool(&tool_name).await.inspect_err(|e| {
+                warn!(tool = %tool_name, error = %e, "Tool lookup failed");
+            })?
+        } else {
+            None
+        };
+        // Tools missing from the listing go to the backend's fallback, if any
+        let use_fallback = tool.is_none() && has_fallback;
+        // Only tools that exist are counted, so made-up names can't grow
+        // the analytics
+        if let Some(analytics) = &self.tool_analytics
+            && tool.is_some()
+        {
+            let caller = crate::context::try_current_request_context()
+                .and_then(|context| context.auth_context)
+                .and_then(|auth| auth.api_key_id)
+                .unwrap_or_else(current_caller_key);
+            analytics.record_call(&tool_name, &caller);
+        }
+        if self.resolve_argument_defaults
+            && let Some(tool) = &tool
+        {
+            let mut arguments = params.arguments.take().unwrap_or_default();
+            if Validator::apply_schema_defaults(&mut arguments, &tool.input_schema) {
+                debug!(tool = %tool_name, "Resolved schema defaults for omitted arguments");
+            }
+            params.arguments = (!arguments.is_null()).then_some(arguments);
+        }
         let start_time = Instant::now();
 
         // Extract request ID for context
@@ -400,6 +1490,7 @@ impl<B: McpBackend> GenericServerHandler<B> {
             "[DEBUG] handle_call_tool: session_id from task-local = {:?}",
             session_id
         );
+        let _in_flight = self.sessions.begin_request(&current_session_key()).await;
         let context = self
             .make_tool_context(request_id, tool_name.clone(), progress_token, session_id)
             .await;
@@ -411,11 +1502,81 @@ impl<B: McpBackend> GenericServerHandler<B> {
             // Execute the backend call within the context scope
             // This makes the context available via try_current_context() in tools
             let backend = self.backend.clone();
-            let tool_result =
-                with_context(context, async move { backend.call_tool(params).await }).await;
+            let memory_budget = self
+                .memory_guard
+                .clone()
+                .zip(self.backend.tool_memory_budget(&tool_name));
+            let guarded_tool = tool_name.clone();
+            let call_id = request.id.clone();
+            let timeout = self.tool_timeout(&request.params, tool.as_ref());
+            let transforms = &self.result_transforms;
+            let structured = tool.as_ref().is_some_and(|t| t.output_schema.is_some());
+            // Boxed so the tool call's state doesn't inflate every request future
+            let tool_result = Box::pin(with_context(context, async move {
+                let call = async {
+                    let result = if use_fallback {
+                        backend
+                            .fallback_tool(&params.name, params.arguments)
+                            .await
+                            .map_err(Into::<Error>::into)
+                    } else if streaming {
+                        return run_streaming_tool(
+                            backend.as_ref(),
+                            params,
+                            call_id,
+                            transforms,
+                            structured,
+                        )
+                        .await;
+                    } else {
+                        backend.call_tool(params).await.map_err(Into::<Error>::into)
+                    };
+                    result.map(|result| ToolOutput {
+                        result,
+                        streamed: false,
+                    })
+                };
+                let guarded = async {
+                    match memory_budget {
+                        Some((guard, budget)) => guard
+                            .run(&guarded_tool, budget, call)
+                            .await
+                            .unwrap_or_else(|exceeded| Err(exceeded.into())),
+                        None => call.await,
+                    }
+                };
+                // Dropping the call on expiry cancels the tool at its next await point
+                match timeout {
+                    Some(limit) => tokio::time::timeout(limit, guarded)
+                        .await
+                        .unwrap_or_else(|_| Err(tool_timeout_error(&guarded_tool, limit))),
+                    None => guarded.await,
+                }
+            }))
+            .await;
+            let tool_result = match (tool_result, &tool) {
+                (Ok(output), Some(tool)) if self.validate_tool_output => {
+                    Validator::validate_tool_output_with_limits(
+                        tool,
+                        &output.result,
+                        &self.structured_content_limits,
+                    )
+                    .map(|()| output)
+                }
+                (other, _) => other,
+            };
 
             match tool_result {
-                Ok(result) => {
+                Ok(ToolOutput {
+                    mut result,
+                    streamed,
+                }) => {
+                    if streamed {
+                        // The client already has the transformed chunks
+                        result.content.clear();
+                    } else {
+                        self.result_transforms.apply(&tool_name, &mut result);
+                    }
                     let duration = start_time.elapsed();
                     metrics.record_request_end(&tool_name, duration, true).await;
                     info!(
@@ -436,7 +1597,7 @@ impl<B: McpBackend> GenericServerHandler<B> {
                         error = %err,
                         "Tool call failed"
                     );
-                    return Err(err.into());
+                    return Err(err);
                 }
             }
         };
@@ -453,22 +1614,70 @@ impl<B: McpBackend> GenericServerHandler<B> {
         &self,
         request: Request,
     ) -> std::result::Result<Response, Error> {
+        let verbosity = ListVerbosity::from_params(&request.params);
         let params = parse_paginated_params(request.params)?;
-        let result = self
+        let mut result = self
             .backend
             .list_resources(params)
             .await
             .map_err(|e| e.into())?;
-        Ok(make_response(request.id, serde_json::to_value(result)?))
+        if let Some(filter) = &self.capability_filter {
+            filter.filter_resources(&mut result, &self.client_capabilities().await);
+        }
+        let mut result = serde_json::to_value(result)?;
+        verbosity.apply_to_resources(&mut result);
+        Ok(make_response(request.id, result))
     }
 
     async fn handle_read_resource(&self, request: Request) -> std::result::Result<Response, Error> {
-        let params: ReadResourceRequestParam = serde_json::from_value(request.params)?;
-        let result = self
+        let params: ReadResourceRequestParam = serde_json::from_value(request.params.clone())?;
+
+        let caller =
+            crate::context::try_current_request_context().and_then(|context| context.auth_context);
+        let permission = self.backend.resource_permission(&params.uri);
+        if let Err(e) =
+            self.resource_access
+                .authorize(&params.uri, permission.as_deref(), caller.as_ref())
+        {
+            warn!(uri = %params.uri, "Resource read denied: {}", e.message);
+            return Err(e);
+        }
+        if let Some((checker, caller)) = self.permission_check() {
+            checker
+                .check_resource(&caller, &params.uri)
+                .await
+                .map_err(|e| Error::forbidden(e.to_string()))?;
+        }
+
+        let mut result = self
             .backend
             .read_resource(params)
             .await
             .map_err(|e| e.into())?;
+
+        // Conditional read: skip the contents if the client's cached version is current
+        if let Some(if_none_match) = request
+            .params
+            .get("_meta")
+            .and_then(|m| m.get("ifNoneMatch"))
+            && result.matches_if_none_match(if_none_match)
+            && let Some(etag) = result.etag()
+        {
+            debug!(etag = %etag, "Resource not modified");
+            let not_modified = ReadResourceResult::not_modified(etag);
+            return Ok(make_response(
+                request.id,
+                serde_json::to_value(not_modified)?,
+            ));
+        }
+
+        // Pre-compress large payloads, but only for clients that can decode them
+        if let Some(compression) = &self.resource_compression
+            && compression.client_accepts(&request.params)
+        {
+            result = compression.compress(result)?;
+        }
+
         Ok(make_response(request.id, serde_json::to_value(result)?))
     }
 
@@ -477,11 +1686,14 @@ impl<B: McpBackend> GenericServerHandler<B> {
         request: Request,
     ) -> std::result::Result<Response, Error> {
         let params = parse_paginated_params(request.params)?;
-        let result = self
+        let mut result = self
             .backend
             .list_resource_templates(params)
             .await
             .map_err(|e| e.into())?;
+        if let Some(filter) = &self.capability_filter {
+            filter.filter_resource_templates(&mut result, &self.client_capabilities().await);
+        }
         Ok(make_response(request.id, serde_json::to_value(result)?))
     }
 
@@ -497,6 +1709,12 @@ impl<B: McpBackend> GenericServerHandler<B> {
 
     async fn handle_get_prompt(&self, request: Request) -> std::result::Result<Response, Error> {
         let params: GetPromptRequestParam = serde_json::from_value(request.params)?;
+        if let Some((checker, caller)) = self.permission_check() {
+            checker
+                .check_prompt(&caller, &params.name)
+                .await
+                .map_err(|e| Error::forbidden(e.to_string()))?;
+        }
         let result = self
             .backend
             .get_prompt(params)
@@ -508,14 +1726,33 @@ impl<B: McpBackend> GenericServerHandler<B> {
     async fn handle_subscribe(&self, request: Request) -> std::result::Result<Response, Error> {
         let params: SubscribeRequestParam = serde_json::from_value(request.params)?;
         let uri = params.uri.clone();
+        let filter = params.filter.clone();
+        if let Some((checker, caller)) = self.permission_check() {
+            checker
+                .check_subscribe(&caller, &uri)
+                .await
+                .map_err(|e| Error::forbidden(e.to_string()))?;
+        }
 
-        // Forward to backend first (allows custom validation/logic)
-        self.backend.subscribe(params).await.map_err(|e| e.into())?;
+        // Reserve the session's slot before the backend sees the request, so
+        // a subscription over the cap has no side effects
+        let session_key = current_session_key();
+        let added = self.sessions.add_subscription(&session_key, &uri).await?;
 
-        // Track subscription globally
-        let mut subs = self.subscriptions.write().await;
-        if subs.insert(uri.clone()) {
-            debug!("Subscribed to resource: {}", uri);
+        // Forward to backend (allows custom validation/logic)
+        if let Err(e) = self.backend.subscribe(params).await {
+            if added {
+                self.sessions.remove_subscription(&session_key, &uri).await;
+            }
+            return Err(e.into());
+        }
+
+        if self
+            .subscriptions
+            .subscribe(&session_key, &uri, filter.clone())
+            .await
+        {
+            debug!(filter = ?filter.as_ref().map(UriFilter::as_str), "Subscribed to resource: {}", uri);
         }
 
         Ok(make_empty_response(request.id))
@@ -532,8 +1769,9 @@ impl<B: McpBackend> GenericServerHandler<B> {
             .map_err(|e| e.into())?;
 
         // Remove from subscription tracking
-        let mut subs = self.subscriptions.write().await;
-        if subs.remove(&uri) {
+        let session_key = current_session_key();
+        self.sessions.remove_subscription(&session_key, &uri).await;
+        if self.subscriptions.unsubscribe(&session_key, &uri).await {
             debug!("Unsubscribed from resource: {}", uri);
         }
 
@@ -562,6 +1800,45 @@ impl<B: McpBackend> GenericServerHandler<B> {
         Ok(make_empty_response(request.id))
     }
 
+    /// Abort the in-flight request named by a `notifications/cancelled`
+    async fn handle_cancelled(&self, request: Request) -> std::result::Result<Response, Error> {
+        let request_id: NumberOrString = request
+            .params
+            .get("requestId")
+            .cloned()
+            .ok_or_else(|| Error::invalid_params("Missing requestId"))
+            .and_then(|id| {
+                serde_json::from_value(id).map_err(|e| Error::invalid_params(e.to_string()))
+            })?;
+        let reason = request.params.get("reason").and_then(|r| r.as_str());
+
+        if self.in_flight.cancel(&current_caller_key(), &request_id) {
+            info!(request_id = %request_id, reason = ?reason, "Cancelling in-flight request");
+        } else {
+            // The request may already have completed; cancellation is best effort
+            debug!(request_id = %request_id, "Ignoring cancellation of unknown request");
+        }
+        Ok(make_empty_response(request.id))
+    }
+
+    /// Requests currently being handled
+    pub fn in_flight_requests(&self) -> &InFlightRequests {
+        &self.in_flight
+    }
+
+    /// Record that the client completed the handshake, then pass the
+    /// notification on to the backend like any other custom method
+    async fn handle_initialized(&self, request: Request) -> std::result::Result<Response, Error> {
+        if !self
+            .sessions
+            .mark_client_initialized(&current_session_key())
+            .await
+        {
+            debug!("Received notifications/initialized before initialize");
+        }
+        self.handle_custom_method(request).await
+    }
+
     async fn handle_custom_method(&self, request: Request) -> std::result::Result<Response, Error> {
         let result = self
             .backend
@@ -572,6 +1849,29 @@ impl<B: McpBackend> GenericServerHandler<B> {
     }
 }
 
+fn error_response(id: Option<NumberOrString>, error: Error) -> Response {
+    Response {
+        jsonrpc: "2.0".to_string(),
+        id,
+        result: None,
+        error: Some(error),
+    }
+}
+
+fn response_value(response: Response) -> serde_json::Value {
+    serde_json::to_value(response).expect("responses serialize to JSON")
+}
+
+/// The first non-null `id` shared by two entries of a batch
+fn duplicate_batch_id(entries: &[serde_json::Value]) -> Option<&serde_json::Value> {
+    let mut seen = HashSet::new();
+    entries
+        .iter()
+        .filter_map(|entry| entry.get("id"))
+        .filter(|id| !id.is_null())
+        .find(|id| !seen.insert(id.to_string()))
+}
+
 // Convert HandlerError to protocol Error
 impl From<HandlerError> for Error {
     fn from(err: HandlerError) -> Self {
@@ -580,6 +1880,9 @@ impl From<HandlerError> for Error {
             HandlerError::Authorization(msg) => Error::forbidden(msg),
             HandlerError::Backend(msg) => Error::internal_error(msg),
             HandlerError::Protocol(e) => e,
+            HandlerError::Cancelled(id) => {
+                Error::invalid_request(format!("Request {id} was cancelled"))
+            }
         }
     }
 }
@@ -631,7 +1934,9 @@ mod tests {
                         logging: Some(LoggingCapability { level: None }),
                         sampling: None,
                         elicitation: Some(ElicitationCapability::default()),
+                        completions: None,
                         tasks: None,
+                        experimental: None,
                     },
                     server_info: Implementation::new("test-server", "1.0.0"),
                     instructions: None,
@@ -734,6 +2039,7 @@ mod tests {
                     }],
                     is_error: Some(false),
                     structured_content: None,
+                    structured_content_blocks: None,
                     _meta: None,
                 })
             } else {
@@ -772,6 +2078,7 @@ mod tests {
                         blob: None,
                         _meta: None,
                     }],
+                    _meta: None,
                 })
             } else {
                 Err(MockBackendError::TestError(
@@ -1,8 +1,11 @@
 //! Tests for generic request handler functionality
 
-use crate::backend::{BackendError, McpBackend};
+use crate::BuildInfo;
+use crate::adaptive_timeout::AdaptiveTimeoutConfig;
+use crate::backend::{BackendError, McpBackend, ToolContentStream};
 use crate::handler::{GenericServerHandler, HandlerError};
 use crate::middleware::MiddlewareStack;
+use crate::protocol_session::ConcurrentInitialize;
 use async_trait::async_trait;
 use pulseengine_auth::{AuthConfig, AuthenticationManager, config::StorageConfig};
 use pulseengine_mcp_protocol::error::ErrorCode;
@@ -16,6 +19,8 @@ use std::sync::Arc;
 struct MockHandlerBackend {
     should_fail: bool,
     server_name: String,
+    /// Blocks `get_server_info`, keeping an `initialize` in progress
+    server_info_delay: Option<std::time::Duration>,
 }
 
 #[derive(Debug)]
@@ -52,10 +57,14 @@ impl McpBackend for MockHandlerBackend {
         Ok(Self {
             should_fail,
             server_name,
+            server_info_delay: None,
         })
     }
 
     fn get_server_info(&self) -> ServerInfo {
+        if let Some(delay) = self.server_info_delay {
+            std::thread::sleep(delay);
+        }
         ServerInfo {
             protocol_version: ProtocolVersion::default(),
             capabilities: ServerCapabilities {
@@ -74,7 +83,9 @@ impl McpBackend for MockHandlerBackend {
                 }),
                 sampling: None,
                 elicitation: Some(ElicitationCapability::default()),
+                completions: None,
                 tasks: None,
+                experimental: None,
             },
             server_info: Implementation::new(self.server_name.clone(), "1.0.0"),
             instructions: Some("Mock handler backend for testing".to_string()),
@@ -159,6 +170,7 @@ impl McpBackend for MockHandlerBackend {
                     }],
                     is_error: Some(false),
                     structured_content: None,
+                    structured_content_blocks: None,
                     _meta: None,
                 })
             }
@@ -169,6 +181,7 @@ impl McpBackend for MockHandlerBackend {
                 }],
                 is_error: Some(true),
                 structured_content: None,
+                structured_content_blocks: None,
                 _meta: None,
             }),
             _ => {
@@ -210,6 +223,7 @@ impl McpBackend for MockHandlerBackend {
                     blob: None,
                     _meta: None,
                 }],
+                _meta: None,
             })
         } else {
             Err(BackendError::not_supported(format!("Resource not found: {}", request.uri)).into())
@@ -264,11 +278,16 @@ impl McpBackend for MockHandlerBackend {
 }
 
 async fn create_test_handler() -> GenericServerHandler<MockHandlerBackend> {
-    let backend = Arc::new(
-        MockHandlerBackend::initialize((false, "Test Handler Backend".to_string()))
-            .await
-            .unwrap(),
-    );
+    let backend = MockHandlerBackend::initialize((false, "Test Handler Backend".to_string()))
+        .await
+        .unwrap();
+    create_test_handler_with(backend).await
+}
+
+async fn create_test_handler_with(
+    backend: MockHandlerBackend,
+) -> GenericServerHandler<MockHandlerBackend> {
+    let backend = Arc::new(backend);
     let auth_config = AuthConfig {
         storage: StorageConfig::Memory,
         enabled: false,
@@ -356,6 +375,107 @@ async fn test_handler_initialize() {
     assert!(result.capabilities.tools.is_some());
 }
 
+#[tokio::test]
+async fn test_initialize_reports_build_info_only_when_configured() {
+    let initialize = || Request {
+        jsonrpc: "2.0".to_string(),
+        id: Some(NumberOrString::Number(1)),
+        method: "initialize".to_string(),
+        params: serde_json::json!({
+            "protocolVersion": "2024-11-05",
+            "capabilities": {},
+            "clientInfo": {"name": "Test Client", "version": "1.0.0"}
+        }),
+    };
+
+    let handler = create_test_handler().await;
+    let result = handler
+        .handle_request(initialize())
+        .await
+        .unwrap()
+        .result
+        .unwrap();
+    assert!(result.get("_meta").is_none());
+
+    let handler = create_test_handler()
+        .await
+        .with_build_info(BuildInfo::new());
+    let result = handler
+        .handle_request(initialize())
+        .await
+        .unwrap()
+        .result
+        .unwrap();
+    assert!(result.get("_meta").is_none());
+
+    let handler = create_test_handler().await.with_build_info(
+        BuildInfo::new()
+            .with_git_commit("0123abcd")
+            .with_rustc_version("1.89.0"),
+    );
+    let result = handler
+        .handle_request(initialize())
+        .await
+        .unwrap()
+        .result
+        .unwrap();
+    assert_eq!(
+        result["_meta"]["buildInfo"],
+        serde_json::json!({"gitCommit": "0123abcd", "rustcVersion": "1.89.0"})
+    );
+}
+
+#[tokio::test]
+async fn test_list_verbosity_minimal_omits_heavy_fields() {
+    let handler = create_test_handler().await;
+    let list = |method: &str, params: serde_json::Value| Request {
+        jsonrpc: "2.0".to_string(),
+        id: Some(NumberOrString::Number(1)),
+        method: method.to_string(),
+        params,
+    };
+    let minimal = serde_json::json!({"_meta": {"verbosity": "minimal"}});
+
+    let full = handler
+        .handle_request(list("tools/list", serde_json::json!({})))
+        .await
+        .unwrap()
+        .result
+        .unwrap();
+    let tool = &full["tools"][0];
+    assert_eq!(tool["description"], "A test tool");
+    assert!(tool.get("inputSchema").is_some());
+
+    let lean = handler
+        .handle_request(list("tools/list", minimal.clone()))
+        .await
+        .unwrap()
+        .result
+        .unwrap();
+    let tool = &lean["tools"][0];
+    assert_eq!(tool["name"], "test_tool");
+    assert!(tool.get("description").is_none());
+    assert!(tool.get("inputSchema").is_none());
+
+    let full = handler
+        .handle_request(list("resources/list", serde_json::Value::Null))
+        .await
+        .unwrap()
+        .result
+        .unwrap();
+    assert_eq!(full["resources"][0]["description"], "First test resource");
+
+    let lean = handler
+        .handle_request(list("resources/list", minimal))
+        .await
+        .unwrap()
+        .result
+        .unwrap();
+    let resource = &lean["resources"][0];
+    assert_eq!(resource["uri"], "test://resource1");
+    assert!(resource.get("description").is_none());
+}
+
 #[tokio::test]
 async fn test_handler_list_tools() {
     let handler = create_test_handler().await;
@@ -731,3 +851,3049 @@ fn test_handler_error_debug() {
     assert!(debug_str.contains("Backend"));
     assert!(debug_str.contains("test"));
 }
+
+// Backend that records the arguments each tool call receives
+#[derive(Clone, Default)]
+struct RecordingBackend {
+    tools: Vec<Tool>,
+    calls: Arc<std::sync::Mutex<Vec<CallToolRequestParam>>>,
+    resource_etag: Option<String>,
+    capabilities: Option<ServerCapabilities>,
+    resources: Vec<Resource>,
+    stream_dropped: Arc<std::sync::atomic::AtomicBool>,
+    /// `nap` calls currently sleeping, and the most seen at once
+    napping: Arc<std::sync::atomic::AtomicUsize>,
+    peak_napping: Arc<std::sync::atomic::AtomicUsize>,
+    /// Serve unknown tool names from `fallback_tool`
+    fallback: bool,
+    /// Cursor returned with every page of tools, never advancing
+    tools_cursor: Option<String>,
+    /// Number of `list_tools` calls served
+    tool_listings: Arc<std::sync::atomic::AtomicUsize>,
+}
+
+/// Sets its flag when the streaming tool call holding it is dropped
+struct DropFlag(Arc<std::sync::atomic::AtomicBool>);
+
+impl Drop for DropFlag {
+    fn drop(&mut self) {
+        self.0.store(true, std::sync::atomic::Ordering::SeqCst);
+    }
+}
+
+impl RecordingBackend {
+    fn with_tool(name: &str, input_schema: serde_json::Value) -> Self {
+        Self {
+            tools: vec![Tool {
+                name: name.to_string(),
+                description: format!("{name} tool"),
+                input_schema,
+                output_schema: None,
+                title: None,
+                annotations: None,
+                icons: None,
+                execution: None,
+                _meta: None,
+            }],
+            ..Default::default()
+        }
+    }
+
+    fn peak_concurrent_naps(&self) -> usize {
+        self.peak_napping.load(std::sync::atomic::Ordering::SeqCst)
+    }
+
+    fn last_arguments(&self) -> Option<serde_json::Value> {
+        self.calls
+            .lock()
+            .unwrap()
+            .last()
+            .and_then(|c| c.arguments.clone())
+    }
+}
+
+#[async_trait]
+impl McpBackend for RecordingBackend {
+    type Error = MockHandlerError;
+    type Config = ();
+
+    async fn initialize(_: Self::Config) -> std::result::Result<Self, Self::Error> {
+        Ok(Self::default())
+    }
+
+    fn get_server_info(&self) -> ServerInfo {
+        ServerInfo {
+            protocol_version: ProtocolVersion::default(),
+            capabilities: self
+                .capabilities
+                .clone()
+                .unwrap_or_else(|| ServerCapabilities::builder().enable_tools().build()),
+            server_info: Implementation::new("recording-backend", "1.0.0"),
+            instructions: None,
+        }
+    }
+
+    async fn health_check(&self) -> std::result::Result<(), Self::Error> {
+        Ok(())
+    }
+
+    async fn list_tools(
+        &self,
+        _request: PaginatedRequestParam,
+    ) -> std::result::Result<ListToolsResult, Self::Error> {
+        self.tool_listings
+            .fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+        Ok(ListToolsResult {
+            tools: self.tools.clone(),
+            next_cursor: self.tools_cursor.clone(),
+        })
+    }
+
+    async fn call_tool(
+        &self,
+        request: CallToolRequestParam,
+    ) -> std::result::Result<CallToolResult, Self::Error> {
+        self.calls.lock().unwrap().push(request.clone());
+        if request.name == "panic" {
+            panic!("tool panicked");
+        }
+        if request.name == "batch"
+            && let Some(context) = crate::context::try_current_request_context()
+        {
+            for item in 1..=3 {
+                context
+                    .progress()
+                    .report(f64::from(item), Some(3.0), Some(format!("item {item}")))
+                    .await
+                    .unwrap();
+            }
+        }
+        if request.name == "version"
+            && let Some(context) = crate::context::try_current_request_context()
+        {
+            return Ok(CallToolResult::text(
+                context
+                    .protocol_version
+                    .unwrap_or_else(|| "none".to_string()),
+            ));
+        }
+        if request.name == "slow" {
+            let _dropped = DropFlag(self.stream_dropped.clone());
+            tokio::time::sleep(std::time::Duration::from_secs(30)).await;
+        }
+        if request.name == "nap" {
+            use std::sync::atomic::Ordering;
+            let napping = self.napping.fetch_add(1, Ordering::SeqCst) + 1;
+            self.peak_napping.fetch_max(napping, Ordering::SeqCst);
+            tokio::time::sleep(std::time::Duration::from_millis(20)).await;
+            self.napping.fetch_sub(1, Ordering::SeqCst);
+        }
+        if let Some(context) = crate::context::try_current_request_context() {
+            context.record_span_attribute("tenant", "acme");
+            context.record_span_attribute("api_token", "s3cret");
+        }
+        Ok(CallToolResult::text(format!("called {}", request.name)))
+    }
+
+    fn streams_tool(&self, tool_name: &str) -> bool {
+        tool_name.starts_with("tail")
+    }
+
+    fn has_fallback_tool(&self) -> bool {
+        self.fallback
+    }
+
+    async fn fallback_tool(
+        &self,
+        name: &str,
+        arguments: Option<serde_json::Value>,
+    ) -> std::result::Result<CallToolResult, Self::Error> {
+        Ok(CallToolResult::text(format!(
+            "proxied {name} with {}",
+            arguments.unwrap_or_default()
+        )))
+    }
+
+    // `tail` yields two log lines and an image; `tail_follow` yields one
+    // line and then waits forever for more; `tail_json` yields a JSON object
+    // in two pieces
+    async fn call_tool_streaming(
+        &self,
+        request: CallToolRequestParam,
+    ) -> std::result::Result<ToolContentStream<Self::Error>, Self::Error> {
+        use futures::stream::{self, StreamExt};
+
+        let flag = DropFlag(self.stream_dropped.clone());
+        let chunks = match request.name.as_str() {
+            "tail_follow" => stream::iter(vec![Ok(Content::text("line 1\n"))])
+                .chain(stream::pending())
+                .boxed(),
+            "tail_json" => stream::iter(vec![
+                Ok(Content::text("{\"count\": ")),
+                Ok(Content::text("2}")),
+            ])
+            .boxed(),
+            _ => stream::iter(vec![
+                Ok(Content::text("line 1\n")),
+                Ok(Content::text("line 2\n")),
+                Ok(Content::image("aGk=", "image/png")),
+            ])
+            .boxed(),
+        };
+        Ok(chunks
+            .map(move |chunk| {
+                let _held_until_dropped = &flag;
+                chunk
+            })
+            .boxed())
+    }
+
+    async fn list_resources(
+        &self,
+        _request: PaginatedRequestParam,
+    ) -> std::result::Result<ListResourcesResult, Self::Error> {
+        Ok(ListResourcesResult {
+            resources: self.resources.clone(),
+            next_cursor: None,
+        })
+    }
+
+    async fn read_resource(
+        &self,
+        request: ReadResourceRequestParam,
+    ) -> std::result::Result<ReadResourceResult, Self::Error> {
+        let result =
+            ReadResourceResult::new(vec![ResourceContents::text(request.uri, "static content")]);
+        Ok(match &self.resource_etag {
+            Some(etag) => result.with_etag(etag.clone()),
+            None => result,
+        })
+    }
+
+    async fn list_prompts(
+        &self,
+        _request: PaginatedRequestParam,
+    ) -> std::result::Result<ListPromptsResult, Self::Error> {
+        Ok(ListPromptsResult {
+            prompts: vec![],
+            next_cursor: None,
+        })
+    }
+
+    async fn get_prompt(
+        &self,
+        request: GetPromptRequestParam,
+    ) -> std::result::Result<GetPromptResult, Self::Error> {
+        Err(BackendError::not_supported(format!("Prompt not found: {}", request.name)).into())
+    }
+}
+
+fn recording_handler(backend: &RecordingBackend) -> GenericServerHandler<RecordingBackend> {
+    GenericServerHandler::new(
+        Arc::new(backend.clone()),
+        Arc::new(AuthenticationManager::new_disabled()),
+        MiddlewareStack::new(),
+    )
+}
+
+fn call_tool_request(name: &str, arguments: Option<serde_json::Value>) -> Request {
+    let mut params = serde_json::json!({ "name": name });
+    if let Some(arguments) = arguments {
+        params["arguments"] = arguments;
+    }
+    Request {
+        jsonrpc: "2.0".to_string(),
+        id: Some(NumberOrString::Number(1)),
+        method: "tools/call".to_string(),
+        params,
+    }
+}
+
+fn greeting_schema() -> serde_json::Value {
+    serde_json::json!({
+        "type": "object",
+        "properties": {
+            "name": {"type": "string", "default": "World"},
+            "punctuation": {"type": "string", "default": "!"}
+        }
+    })
+}
+
+#[tokio::test]
+async fn test_argument_defaults_populated_before_dispatch() {
+    let backend = RecordingBackend::with_tool("say_hello", greeting_schema());
+    let handler = recording_handler(&backend).with_argument_defaults(true);
+
+    let response = handler
+        .handle_request(call_tool_request(
+            "say_hello",
+            Some(serde_json::json!({"punctuation": "?"})),
+        ))
+        .await
+        .unwrap();
+    assert!(response.error.is_none());
+    assert_eq!(
+        backend.last_arguments(),
+        Some(serde_json::json!({"name": "World", "punctuation": "?"}))
+    );
+
+    // Omitting arguments entirely still yields fully-populated arguments
+    handler
+        .handle_request(call_tool_request("say_hello", None))
+        .await
+        .unwrap();
+    assert_eq!(
+        backend.last_arguments(),
+        Some(serde_json::json!({"name": "World", "punctuation": "!"}))
+    );
+}
+
+#[tokio::test]
+async fn test_argument_defaults_disabled_by_default() {
+    let backend = RecordingBackend::with_tool("say_hello", greeting_schema());
+    let handler = recording_handler(&backend);
+
+    handler
+        .handle_request(call_tool_request("say_hello", None))
+        .await
+        .unwrap();
+    assert_eq!(backend.last_arguments(), None);
+}
+
+fn initialize_request(protocol_version: &str, capabilities: serde_json::Value) -> Request {
+    Request {
+        jsonrpc: "2.0".to_string(),
+        id: Some(NumberOrString::Number(1)),
+        method: "initialize".to_string(),
+        params: serde_json::json!({
+            "protocolVersion": protocol_version,
+            "capabilities": capabilities,
+            "clientInfo": {"name": "upgrading-client", "version": "1.0.0"}
+        }),
+    }
+}
+
+#[tokio::test]
+async fn test_reinitialize_upgrades_protocol_version() {
+    let backend = RecordingBackend::default();
+    let handler = recording_handler(&backend);
+    assert!(handler.negotiated_session().await.is_none());
+
+    let response = handler
+        .handle_request(initialize_request("2024-11-05", serde_json::json!({})))
+        .await
+        .unwrap();
+    assert_eq!(response.result.unwrap()["protocolVersion"], "2024-11-05");
+
+    let session = handler.negotiated_session().await.unwrap();
+    assert_eq!(session.protocol_version, "2024-11-05");
+    assert_eq!(session.generation, 1);
+    assert!(session.client_capabilities.get("elicitation").is_none());
+
+    let response = handler
+        .handle_request(initialize_request(
+            "2025-11-25",
+            serde_json::json!({"elicitation": {"form": {}}, "sampling": {}}),
+        ))
+        .await
+        .unwrap();
+    assert!(response.error.is_none());
+    assert_eq!(response.result.unwrap()["protocolVersion"], "2025-11-25");
+
+    let session = handler.negotiated_session().await.unwrap();
+    assert_eq!(session.protocol_version, "2025-11-25");
+    assert_eq!(session.generation, 2);
+    assert!(session.client_capabilities.get("elicitation").is_some());
+    assert!(session.client_capabilities.get("sampling").is_some());
+}
+
+async fn slow_initialize_handler(
+    mode: ConcurrentInitialize,
+) -> GenericServerHandler<MockHandlerBackend> {
+    let backend = MockHandlerBackend {
+        should_fail: false,
+        server_name: "Slow Backend".to_string(),
+        server_info_delay: Some(std::time::Duration::from_millis(200)),
+    };
+    create_test_handler_with(backend)
+        .await
+        .with_concurrent_initialize(mode)
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_concurrent_initialize_exactly_one_succeeds() {
+    let handler = slow_initialize_handler(ConcurrentInitialize::Reject).await;
+    let first = tokio::spawn({
+        let handler = handler.clone();
+        async move {
+            handler
+                .handle_request(initialize_request("2025-06-18", serde_json::json!({})))
+                .await
+        }
+    });
+    let second = tokio::spawn({
+        let handler = handler.clone();
+        async move {
+            handler
+                .handle_request(initialize_request("2025-06-18", serde_json::json!({})))
+                .await
+        }
+    });
+    let responses = [
+        first.await.unwrap().unwrap(),
+        second.await.unwrap().unwrap(),
+    ];
+
+    let succeeded = responses.iter().filter(|r| r.error.is_none()).count();
+    assert_eq!(succeeded, 1);
+    let error = responses.iter().find_map(|r| r.error.as_ref()).unwrap();
+    assert_eq!(error.code, ErrorCode::InvalidRequest);
+    assert!(error.message.contains("still being processed"));
+    assert_eq!(handler.negotiated_session().await.unwrap().generation, 1);
+
+    // Once the first has finished, re-initializing works as before
+    let response = handler
+        .handle_request(initialize_request("2025-06-18", serde_json::json!({})))
+        .await
+        .unwrap();
+    assert!(response.error.is_none());
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_concurrent_initialize_queued_when_configured() {
+    let handler = slow_initialize_handler(ConcurrentInitialize::Queue).await;
+    let requests = (0..2).map(|_| {
+        let handler = handler.clone();
+        tokio::spawn(async move {
+            handler
+                .handle_request(initialize_request("2025-06-18", serde_json::json!({})))
+                .await
+        })
+    });
+    for request in requests.collect::<Vec<_>>() {
+        assert!(request.await.unwrap().unwrap().error.is_none());
+    }
+    assert_eq!(handler.negotiated_session().await.unwrap().generation, 2);
+}
+
+#[tokio::test]
+async fn test_reinitialize_invalidates_subscriptions_on_version_change() {
+    let backend = RecordingBackend::default();
+    let handler = recording_handler(&backend);
+    let subscribe = Request {
+        jsonrpc: "2.0".to_string(),
+        id: Some(NumberOrString::Number(2)),
+        method: "resources/subscribe".to_string(),
+        params: serde_json::json!({"uri": "file://watched.txt"}),
+    };
+
+    handler
+        .handle_request(initialize_request("2025-06-18", serde_json::json!({})))
+        .await
+        .unwrap();
+    handler.handle_request(subscribe.clone()).await.unwrap();
+    assert!(handler.is_subscribed("file://watched.txt").await);
+
+    // Same version: subscriptions survive
+    handler
+        .handle_request(initialize_request("2025-06-18", serde_json::json!({})))
+        .await
+        .unwrap();
+    assert!(handler.is_subscribed("file://watched.txt").await);
+
+    // Upgrade: subscriptions from the old version are dropped
+    handler
+        .handle_request(initialize_request("2025-11-25", serde_json::json!({})))
+        .await
+        .unwrap();
+    assert!(!handler.is_subscribed("file://watched.txt").await);
+    assert!(
+        handler
+            .negotiated_session()
+            .await
+            .unwrap()
+            .subscriptions
+            .is_empty()
+    );
+}
+
+fn subscribe_request(uri: &str) -> Request {
+    Request {
+        jsonrpc: "2.0".to_string(),
+        id: Some(NumberOrString::Number(2)),
+        method: "resources/subscribe".to_string(),
+        params: serde_json::json!({ "uri": uri }),
+    }
+}
+
+#[tokio::test]
+async fn test_closed_connection_drops_its_subscriptions() {
+    use pulseengine_mcp_transport::{ConnectionClosed, with_session};
+
+    let backend = RecordingBackend::default();
+    let handler = recording_handler(&backend);
+    for session in ["a", "b"] {
+        with_session(
+            session.to_string(),
+            handler.handle_request(subscribe_request(&format!("file://{session}.txt"))),
+        )
+        .await
+        .unwrap();
+    }
+
+    handler
+        .connection_closed(&ConnectionClosed {
+            connection_id: "a".to_string(),
+            session_id: Some("a".to_string()),
+        })
+        .await;
+    assert!(!handler.is_subscribed("file://a.txt").await);
+    assert!(handler.is_subscribed("file://b.txt").await);
+}
+
+#[tokio::test]
+async fn test_closed_connection_releases_auth_and_protocol_session() {
+    use pulseengine_auth::Role;
+    use pulseengine_auth::middleware::{McpAuthConfig, McpAuthMiddleware};
+    use pulseengine_mcp_transport::{
+        ConnectionClosed, ConnectionInfo, with_connection, with_session,
+    };
+
+    let auth_manager = Arc::new(
+        AuthenticationManager::new(AuthConfig::memory())
+            .await
+            .unwrap(),
+    );
+    let key = auth_manager
+        .create_api_key("client".to_string(), Role::Operator, None, None)
+        .await
+        .unwrap();
+    let handler = GenericServerHandler::new(
+        Arc::new(RecordingBackend::default()),
+        auth_manager.clone(),
+        MiddlewareStack::new(),
+    )
+    .with_caller_auth(McpAuthMiddleware::new(
+        auth_manager,
+        McpAuthConfig {
+            connection_auth_ttl: Some(std::time::Duration::from_secs(60)),
+            ..Default::default()
+        },
+    ));
+    let on_connection = |authorization: Option<String>, request: Request| {
+        let connection = ConnectionInfo::new("ws-1", "websocket").with_headers(
+            authorization
+                .as_deref()
+                .map(|value| ("authorization", value)),
+        );
+        with_connection(
+            connection,
+            with_session("ws-1".to_string(), handler.handle_request(request)),
+        )
+    };
+
+    // Authenticated once, later messages on the connection need no credentials
+    let bearer = Some(format!("Bearer {}", key.key));
+    let response = on_connection(bearer, list_tools_request()).await.unwrap();
+    assert!(response.error.is_none());
+    let initialize = initialize_request("2025-06-18", serde_json::json!({}));
+    assert!(on_connection(None, initialize).await.is_ok());
+    assert!(on_connection(None, list_tools_request()).await.is_ok());
+    let session = || with_session("ws-1".to_string(), handler.negotiated_session());
+    assert!(session().await.is_some());
+
+    handler
+        .connection_closed(&ConnectionClosed {
+            connection_id: "ws-1".to_string(),
+            session_id: Some("ws-1".to_string()),
+        })
+        .await;
+    assert!(session().await.is_none());
+    assert!(on_connection(None, list_tools_request()).await.is_err());
+}
+
+#[tokio::test]
+async fn test_subscribe_rejected_beyond_session_limit() {
+    let backend = RecordingBackend::default();
+    let handler = recording_handler(&backend).with_max_subscriptions_per_session(3);
+    handler
+        .handle_request(initialize_request("2025-06-18", serde_json::json!({})))
+        .await
+        .unwrap();
+
+    for i in 0..3 {
+        let response = handler
+            .handle_request(subscribe_request(&format!("file://{i}.txt")))
+            .await
+            .unwrap();
+        assert!(response.error.is_none());
+    }
+    let stats = handler.session_stats().await.unwrap();
+    assert_eq!(stats.subscriptions, 3);
+    assert_eq!(stats.max_subscriptions, Some(3));
+
+    let response = handler
+        .handle_request(subscribe_request("file://3.txt"))
+        .await
+        .unwrap();
+    let error = response
+        .error
+        .expect("fourth subscription should be rejected");
+    assert!(error.message.contains("maximum of 3"), "{}", error.message);
+    assert!(!handler.is_subscribed("file://3.txt").await);
+    assert_eq!(handler.session_stats().await.unwrap().subscriptions, 3);
+
+    // Re-subscribing to a held URI is still accepted
+    let response = handler
+        .handle_request(subscribe_request("file://0.txt"))
+        .await
+        .unwrap();
+    assert!(response.error.is_none());
+}
+
+#[tokio::test]
+async fn test_subscribe_limit_applies_before_initialize() {
+    let backend = RecordingBackend::default();
+    let handler = recording_handler(&backend).with_max_subscriptions_per_session(2);
+
+    for i in 0..2 {
+        let response = handler
+            .handle_request(subscribe_request(&format!("file://{i}.txt")))
+            .await
+            .unwrap();
+        assert!(response.error.is_none());
+    }
+    let response = handler
+        .handle_request(subscribe_request("file://2.txt"))
+        .await
+        .unwrap();
+    let error = response
+        .error
+        .expect("subscription past the cap should be rejected");
+    assert!(error.message.contains("maximum of 2"), "{}", error.message);
+    assert!(!handler.is_subscribed("file://2.txt").await);
+}
+
+fn read_resource_request(meta: Option<serde_json::Value>) -> Request {
+    let mut params = serde_json::json!({"uri": "file://static.txt"});
+    if let Some(meta) = meta {
+        params["_meta"] = meta;
+    }
+    Request {
+        jsonrpc: "2.0".to_string(),
+        id: Some(NumberOrString::Number(3)),
+        method: "resources/read".to_string(),
+        params,
+    }
+}
+
+#[tokio::test]
+async fn test_conditional_read_with_matching_etag_not_modified() {
+    let backend = RecordingBackend {
+        resource_etag: Some("\"v1\"".to_string()),
+        ..Default::default()
+    };
+    let handler = recording_handler(&backend);
+
+    // Unconditional read returns contents tagged with the ETag
+    let response = handler
+        .handle_request(read_resource_request(None))
+        .await
+        .unwrap();
+    let result: ReadResourceResult = serde_json::from_value(response.result.unwrap()).unwrap();
+    assert_eq!(result.etag(), Some("\"v1\""));
+    assert_eq!(result.contents.len(), 1);
+    assert!(!result.is_not_modified());
+
+    let response = handler
+        .handle_request(read_resource_request(Some(
+            serde_json::json!({"ifNoneMatch": "\"v1\""}),
+        )))
+        .await
+        .unwrap();
+    let wire = response.result.unwrap();
+    assert_eq!(wire["contents"], serde_json::json!([]));
+    assert_eq!(wire["_meta"]["notModified"], true);
+    assert_eq!(wire["_meta"]["etag"], "\"v1\"");
+}
+
+#[tokio::test]
+async fn test_conditional_read_with_stale_etag_returns_content() {
+    let backend = RecordingBackend {
+        resource_etag: Some("\"v2\"".to_string()),
+        ..Default::default()
+    };
+    let handler = recording_handler(&backend);
+
+    let response = handler
+        .handle_request(read_resource_request(Some(
+            serde_json::json!({"ifNoneMatch": "\"v1\""}),
+        )))
+        .await
+        .unwrap();
+    let result: ReadResourceResult = serde_json::from_value(response.result.unwrap()).unwrap();
+    assert!(!result.is_not_modified());
+    assert_eq!(result.etag(), Some("\"v2\""));
+    assert_eq!(result.contents[0].text.as_deref(), Some("static content"));
+}
+
+#[tokio::test]
+async fn test_conditional_read_without_backend_etag_returns_content() {
+    let backend = RecordingBackend::default();
+    let handler = recording_handler(&backend);
+
+    let response = handler
+        .handle_request(read_resource_request(Some(
+            serde_json::json!({"ifNoneMatch": "*"}),
+        )))
+        .await
+        .unwrap();
+    let wire = response.result.unwrap();
+    assert_eq!(wire["contents"][0]["text"], "static content");
+    assert!(wire.get("_meta").is_none());
+}
+
+// Layer capturing the fields recorded on `mcp_request` spans
+#[derive(Clone, Default)]
+struct SpanFieldCapture(Arc<std::sync::Mutex<std::collections::HashMap<String, String>>>);
+
+impl tracing::field::Visit for SpanFieldCapture {
+    fn record_debug(&mut self, field: &tracing::field::Field, value: &dyn std::fmt::Debug) {
+        self.0
+            .lock()
+            .unwrap()
+            .insert(field.name().to_string(), format!("{value:?}"));
+    }
+
+    fn record_str(&mut self, field: &tracing::field::Field, value: &str) {
+        self.0
+            .lock()
+            .unwrap()
+            .insert(field.name().to_string(), value.to_string());
+    }
+}
+
+impl<S> tracing_subscriber::Layer<S> for SpanFieldCapture
+where
+    S: tracing::Subscriber + for<'a> tracing_subscriber::registry::LookupSpan<'a>,
+{
+    fn on_new_span(
+        &self,
+        attrs: &tracing::span::Attributes<'_>,
+        _id: &tracing::span::Id,
+        _ctx: tracing_subscriber::layer::Context<'_, S>,
+    ) {
+        if attrs.metadata().name() == "mcp_request" {
+            attrs.record(&mut self.clone());
+        }
+    }
+
+    fn on_record(
+        &self,
+        id: &tracing::span::Id,
+        values: &tracing::span::Record<'_>,
+        ctx: tracing_subscriber::layer::Context<'_, S>,
+    ) {
+        if ctx
+            .span(id)
+            .is_some_and(|span| span.name() == "mcp_request")
+        {
+            values.record(&mut self.clone());
+        }
+    }
+}
+
+#[tokio::test]
+async fn test_tool_call_span_carries_method_attributes() {
+    use tracing_subscriber::layer::SubscriberExt;
+
+    let capture = SpanFieldCapture::default();
+    let subscriber = tracing_subscriber::registry().with(capture.clone());
+    let _default = tracing::subscriber::set_default(subscriber);
+
+    let backend = RecordingBackend::with_tool("say_hello", greeting_schema());
+    let handler = recording_handler(&backend);
+    let request = call_tool_request("say_hello", Some(serde_json::json!({"name": "Ada"})));
+    let expected_bytes = serde_json::to_vec(&request.params).unwrap().len();
+
+    let response = handler.handle_request(request).await.unwrap();
+    assert!(response.error.is_none());
+
+    let fields = capture.0.lock().unwrap().clone();
+    assert_eq!(fields["mcp.method"], "tools/call");
+    assert_eq!(fields["mcp.tool.name"], "say_hello");
+    assert_eq!(fields["mcp.request.bytes"], expected_bytes.to_string());
+    assert!(!fields.contains_key("mcp.resource.uri"));
+
+    // Backend-supplied attributes are attached, with sensitive values redacted
+    let attributes: serde_json::Value = serde_json::from_str(&fields["mcp.attributes"]).unwrap();
+    assert_eq!(attributes["tenant"], "acme");
+    assert_eq!(attributes["api_token"], "[REDACTED]");
+}
+
+#[tokio::test]
+async fn test_resource_read_span_carries_uri() {
+    use tracing_subscriber::layer::SubscriberExt;
+
+    let capture = SpanFieldCapture::default();
+    let subscriber = tracing_subscriber::registry().with(capture.clone());
+    let _default = tracing::subscriber::set_default(subscriber);
+
+    let backend = RecordingBackend::default();
+    let handler = recording_handler(&backend);
+    handler
+        .handle_request(read_resource_request(None))
+        .await
+        .unwrap();
+
+    let fields = capture.0.lock().unwrap().clone();
+    assert_eq!(fields["mcp.method"], "resources/read");
+    assert_eq!(fields["mcp.resource.uri"], "file://static.txt");
+    assert!(!fields.contains_key("mcp.tool.name"));
+    assert!(!fields.contains_key("mcp.attributes"));
+}
+
+fn list_tools_request() -> Request {
+    Request {
+        jsonrpc: "2.0".to_string(),
+        id: Some(NumberOrString::Number(2)),
+        method: "tools/list".to_string(),
+        params: serde_json::json!({}),
+    }
+}
+
+#[tokio::test]
+async fn test_methods_rejected_before_initialize_when_required() {
+    let backend = RecordingBackend::default();
+    let handler = recording_handler(&backend).with_initialization_required(true);
+
+    let response = handler.handle_request(list_tools_request()).await.unwrap();
+    let error = response.error.expect("tools/list must be rejected");
+    assert_eq!(error.code, ErrorCode::InvalidRequest);
+    assert!(error.message.contains("not initialized"));
+
+    handler
+        .handle_request(initialize_request("2025-11-25", serde_json::json!({})))
+        .await
+        .unwrap();
+    let response = handler.handle_request(list_tools_request()).await.unwrap();
+    assert!(response.error.is_none());
+}
+
+fn initialized_notification() -> Request {
+    Request {
+        jsonrpc: "2.0".to_string(),
+        id: None,
+        method: crate::INITIALIZED_NOTIFICATION_METHOD.to_string(),
+        params: serde_json::Value::Null,
+    }
+}
+
+#[tokio::test]
+async fn test_tool_call_rejected_before_initialized_notification_when_strict() {
+    let backend = RecordingBackend::with_tool("echo", serde_json::json!({"type": "object"}));
+    let handler = recording_handler(&backend)
+        .with_initialized_strictness(crate::InitializedStrictness::Reject);
+
+    handler
+        .handle_request(initialize_request("2025-11-25", serde_json::json!({})))
+        .await
+        .unwrap();
+    let response = handler
+        .handle_request(call_tool_request("echo", None))
+        .await
+        .unwrap();
+    let error = response
+        .error
+        .expect("tools/call must wait for initialized");
+    assert_eq!(error.code, ErrorCode::InvalidRequest);
+    assert!(error.message.contains("notifications/initialized"));
+    assert!(backend.calls.lock().unwrap().is_empty());
+    assert!(
+        !handler
+            .negotiated_session()
+            .await
+            .unwrap()
+            .client_initialized
+    );
+}
+
+#[tokio::test]
+async fn test_tool_call_accepted_after_initialized_notification_when_strict() {
+    let backend = RecordingBackend::with_tool("echo", serde_json::json!({"type": "object"}));
+    let handler = recording_handler(&backend)
+        .with_initialized_strictness(crate::InitializedStrictness::Reject);
+
+    handler
+        .handle_request(initialize_request("2025-11-25", serde_json::json!({})))
+        .await
+        .unwrap();
+    handler
+        .handle_request(initialized_notification())
+        .await
+        .unwrap();
+    assert!(
+        handler
+            .negotiated_session()
+            .await
+            .unwrap()
+            .client_initialized
+    );
+
+    let response = handler
+        .handle_request(call_tool_request("echo", None))
+        .await
+        .unwrap();
+    assert!(response.error.is_none(), "{:?}", response.error);
+    assert_eq!(backend.calls.lock().unwrap().len(), 1);
+}
+
+#[tokio::test]
+async fn test_requests_before_initialized_notification_only_warned() {
+    let backend = RecordingBackend::default();
+    let handler =
+        recording_handler(&backend).with_initialized_strictness(crate::InitializedStrictness::Warn);
+
+    let response = handler.handle_request(list_tools_request()).await.unwrap();
+    assert!(response.error.is_none());
+}
+
+#[tokio::test]
+async fn test_ping_allowed_before_initialize_when_required() {
+    let backend = RecordingBackend::default();
+    let handler = recording_handler(&backend).with_initialization_required(true);
+
+    let request = Request {
+        jsonrpc: "2.0".to_string(),
+        id: Some(NumberOrString::Number(1)),
+        method: "ping".to_string(),
+        params: serde_json::Value::Null,
+    };
+    let response = handler.handle_request(request).await.unwrap();
+    assert!(response.error.is_none());
+    assert!(handler.negotiated_session().await.is_none());
+}
+
+#[tokio::test]
+async fn test_methods_allowed_before_initialize_by_default() {
+    let backend = RecordingBackend::default();
+    let handler = recording_handler(&backend);
+
+    let response = handler.handle_request(list_tools_request()).await.unwrap();
+    assert!(response.error.is_none());
+}
+
+#[tokio::test]
+async fn test_concurrency_limit_serves_every_connection() {
+    let backend = RecordingBackend::default();
+    let handler =
+        recording_handler(&backend).with_concurrency_limit(crate::concurrency::ConcurrencyConfig {
+            max_concurrent_requests: 1,
+            max_per_connection: None,
+        });
+
+    let requests = ["busy", "busy", "busy", "quiet"].map(|session| {
+        let handler = handler.clone();
+        pulseengine_mcp_transport::with_session(session.to_string(), async move {
+            handler.handle_request(list_tools_request()).await.unwrap()
+        })
+    });
+    for response in futures::future::join_all(requests).await {
+        assert!(response.error.is_none());
+    }
+}
+
+#[tokio::test]
+async fn test_tool_analytics_counts_only_listed_tools_per_connection() {
+    use pulseengine_mcp_transport::{ConnectionInfo, with_connection};
+
+    let backend = RecordingBackend::with_tool("known", serde_json::json!({ "type": "object" }));
+    let analytics = Arc::new(crate::observability::ToolUsageAnalytics::default());
+    let handler = recording_handler(&backend).with_tool_analytics(analytics.clone());
+
+    for (connection, tool) in [
+        ("conn-a", "known"),
+        ("conn-b", "known"),
+        ("conn-a", "made-up"),
+    ] {
+        with_connection(
+            ConnectionInfo::new(connection, "websocket"),
+            handler.handle_request(call_tool_request(tool, None)),
+        )
+        .await
+        .unwrap();
+    }
+
+    let records = analytics.records();
+    assert_eq!(records.len(), 1);
+    assert_eq!(records[0].tool, "known");
+    assert_eq!(records[0].calls, 2);
+    assert_eq!(records[0].unique_callers, 2);
+}
+
+#[tokio::test]
+async fn test_concurrency_limit_separates_connections_without_sessions() {
+    use pulseengine_mcp_transport::{ConnectionInfo, with_connection};
+
+    let backend = RecordingBackend::default();
+    let handler =
+        recording_handler(&backend).with_concurrency_limit(crate::concurrency::ConcurrencyConfig {
+            max_concurrent_requests: 2,
+            max_per_connection: Some(1),
+        });
+
+    let busy = tokio::spawn(with_connection(ConnectionInfo::new("busy", "websocket"), {
+        let handler = handler.clone();
+        async move {
+            handler
+                .handle_request(call_tool_request("slow", None))
+                .await
+        }
+    }));
+    while backend.calls.lock().unwrap().is_empty() {
+        tokio::task::yield_now().await;
+    }
+
+    // Another connection has its own per-connection allowance
+    let response = tokio::time::timeout(
+        std::time::Duration::from_secs(5),
+        with_connection(
+            ConnectionInfo::new("quiet", "websocket"),
+            handler.handle_request(list_tools_request()),
+        ),
+    )
+    .await
+    .expect("quiet connection isn't queued behind the busy one")
+    .unwrap();
+    assert!(response.error.is_none());
+    busy.abort();
+}
+
+/// Backend whose tool errors carry structured data with internals
+#[derive(Clone)]
+struct LeakyBackend;
+
+#[derive(Debug)]
+struct LeakyError;
+
+impl fmt::Display for LeakyError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "upstream unavailable")
+    }
+}
+
+impl StdError for LeakyError {}
+
+impl From<BackendError> for LeakyError {
+    fn from(_: BackendError) -> Self {
+        LeakyError
+    }
+}
+
+impl From<LeakyError> for Error {
+    fn from(err: LeakyError) -> Self {
+        Error::with_data(
+            ErrorCode::InternalError,
+            err.to_string(),
+            serde_json::json!({"retry_after": 30, "db_password": "hunter2"}),
+        )
+    }
+}
+
+#[async_trait]
+impl crate::backend::SimpleBackend for LeakyBackend {
+    type Error = LeakyError;
+    type Config = ();
+
+    async fn initialize(_: Self::Config) -> std::result::Result<Self, Self::Error> {
+        Ok(Self)
+    }
+
+    fn get_server_info(&self) -> ServerInfo {
+        ServerInfo {
+            protocol_version: ProtocolVersion::default(),
+            capabilities: ServerCapabilities::default(),
+            server_info: Implementation::new("leaky", "1.0.0"),
+            instructions: None,
+        }
+    }
+
+    async fn health_check(&self) -> std::result::Result<(), Self::Error> {
+        Ok(())
+    }
+
+    async fn list_tools(
+        &self,
+        _: PaginatedRequestParam,
+    ) -> std::result::Result<ListToolsResult, Self::Error> {
+        Ok(ListToolsResult {
+            tools: vec![],
+            next_cursor: None,
+        })
+    }
+
+    async fn call_tool(
+        &self,
+        _: CallToolRequestParam,
+    ) -> std::result::Result<CallToolResult, Self::Error> {
+        Err(LeakyError)
+    }
+}
+
+fn leaky_handler() -> GenericServerHandler<LeakyBackend> {
+    GenericServerHandler::new(
+        Arc::new(LeakyBackend),
+        Arc::new(AuthenticationManager::new_disabled()),
+        MiddlewareStack::new(),
+    )
+}
+
+#[tokio::test]
+async fn test_error_data_sanitized_in_production_mode() {
+    let handler =
+        leaky_handler().with_error_data_sanitization(pulseengine_logging::SanitizationConfig {
+            enabled: true,
+            ..Default::default()
+        });
+
+    let response = handler
+        .handle_request(call_tool_request("anything", None))
+        .await
+        .unwrap();
+    let data = response.error.unwrap().data.unwrap();
+    assert_eq!(data["retry_after"], 30);
+    assert!(data.get("db_password").is_none());
+}
+
+#[tokio::test]
+async fn test_error_data_untouched_when_sanitization_disabled() {
+    let handler =
+        leaky_handler().with_error_data_sanitization(pulseengine_logging::SanitizationConfig {
+            enabled: false,
+            ..Default::default()
+        });
+
+    let response = handler
+        .handle_request(call_tool_request("anything", None))
+        .await
+        .unwrap();
+    let data = response.error.unwrap().data.unwrap();
+    assert_eq!(data["db_password"], "hunter2");
+}
+
+/// Backend whose `hog` tool reports growing memory through a shared counter
+#[derive(Clone)]
+struct MemoryHogBackend {
+    usage: Arc<std::sync::atomic::AtomicU64>,
+}
+
+#[async_trait]
+impl crate::backend::SimpleBackend for MemoryHogBackend {
+    type Error = BackendError;
+    type Config = ();
+
+    async fn initialize(_: Self::Config) -> std::result::Result<Self, Self::Error> {
+        Ok(Self {
+            usage: Arc::default(),
+        })
+    }
+
+    fn get_server_info(&self) -> ServerInfo {
+        ServerInfo {
+            protocol_version: ProtocolVersion::default(),
+            capabilities: ServerCapabilities::default(),
+            server_info: Implementation::new("hog", "1.0.0"),
+            instructions: None,
+        }
+    }
+
+    async fn health_check(&self) -> std::result::Result<(), Self::Error> {
+        Ok(())
+    }
+
+    async fn list_tools(
+        &self,
+        _: PaginatedRequestParam,
+    ) -> std::result::Result<ListToolsResult, Self::Error> {
+        Ok(ListToolsResult {
+            tools: vec![],
+            next_cursor: None,
+        })
+    }
+
+    async fn call_tool(
+        &self,
+        _: CallToolRequestParam,
+    ) -> std::result::Result<CallToolResult, Self::Error> {
+        self.usage
+            .fetch_add(64 * 1024 * 1024, std::sync::atomic::Ordering::SeqCst);
+        tokio::time::sleep(std::time::Duration::from_secs(30)).await;
+        Ok(CallToolResult::text("done"))
+    }
+
+    fn tool_memory_budget(&self, tool_name: &str) -> Option<u64> {
+        (tool_name == "hog").then_some(1024 * 1024)
+    }
+}
+
+#[tokio::test]
+async fn test_tool_over_memory_budget_is_aborted() {
+    let backend = MemoryHogBackend::initialize(()).await.unwrap();
+    let usage = backend.usage.clone();
+    let guard = crate::memory_guard::MemoryGuard::new(crate::memory_guard::MemoryGuardConfig {
+        check_interval: std::time::Duration::from_millis(5),
+    })
+    .with_sampler(move || Some(usage.load(std::sync::atomic::Ordering::SeqCst)));
+    let handler = GenericServerHandler::new(
+        Arc::new(backend),
+        Arc::new(AuthenticationManager::new_disabled()),
+        MiddlewareStack::new(),
+    )
+    .with_memory_guard(guard);
+
+    let response = handler
+        .handle_request(call_tool_request("hog", None))
+        .await
+        .unwrap();
+    let error = response.error.unwrap();
+    assert_eq!(error.code, ErrorCode::InternalError);
+    assert!(error.message.contains("Tool 'hog' aborted"));
+    assert!(error.message.contains("budget of 1048576 bytes"));
+}
+
+/// Records which tool produced every result in its `_meta`
+struct Provenance;
+
+impl crate::result_transform::ResultTransform for Provenance {
+    fn name(&self) -> &str {
+        "provenance"
+    }
+
+    fn transform(&self, tool_name: &str, result: &mut CallToolResult) {
+        result
+            .structured_content
+            .get_or_insert_with(|| serde_json::json!({}));
+        result._meta.get_or_insert_with(Meta::default).extra.insert(
+            "provenance".to_string(),
+            serde_json::json!({"server": "recording-backend", "tool": tool_name}),
+        );
+    }
+}
+
+#[tokio::test]
+async fn test_result_transform_appends_provenance_to_every_result() {
+    let backend = RecordingBackend::default();
+    let handler = recording_handler(&backend).with_result_transform(Provenance);
+
+    for tool in ["alpha", "beta"] {
+        let response = handler
+            .handle_request(call_tool_request(tool, None))
+            .await
+            .unwrap();
+        let result = response.result.unwrap();
+        assert_eq!(result["content"][0]["text"], format!("called {tool}"));
+        assert_eq!(result["structuredContent"], serde_json::json!({}));
+        assert_eq!(
+            result["_meta"]["provenance"],
+            serde_json::json!({"server": "recording-backend", "tool": tool})
+        );
+    }
+}
+
+fn initialize_request_from(name: &str, version: &str) -> Request {
+    let mut request = initialize_request("2025-06-18", serde_json::json!({}));
+    request.params["clientInfo"] = serde_json::json!({"name": name, "version": version});
+    request
+}
+
+#[tokio::test]
+async fn test_client_policy_rejects_denylisted_client_version() {
+    let backend = RecordingBackend::default();
+    let handler = recording_handler(&backend).with_client_policy(
+        crate::client_policy::ClientPolicy::default()
+            .deny(crate::client_policy::ClientRule::versions("buggy-client", "<1.4.2").unwrap()),
+    );
+
+    let response = handler
+        .handle_request(initialize_request_from("buggy-client", "1.3.0"))
+        .await
+        .unwrap();
+    let error = response.error.unwrap();
+    assert_eq!(error.code, ErrorCode::InvalidRequest);
+    assert!(error.message.contains("not supported by this server"));
+    assert!(handler.negotiated_session().await.is_none());
+
+    let response = handler
+        .handle_request(initialize_request_from("buggy-client", "1.4.2"))
+        .await
+        .unwrap();
+    assert!(response.error.is_none());
+    assert!(handler.negotiated_session().await.is_some());
+}
+
+/// Transport capturing the notifications sent through it
+#[derive(Default)]
+struct NotificationRecorder {
+    sent: std::sync::Mutex<Vec<(String, serde_json::Value)>>,
+}
+
+#[async_trait]
+impl pulseengine_mcp_transport::Transport for NotificationRecorder {
+    async fn start(
+        &mut self,
+        _handler: pulseengine_mcp_transport::RequestHandler,
+    ) -> std::result::Result<(), pulseengine_mcp_transport::TransportError> {
+        Ok(())
+    }
+
+    async fn stop(&mut self) -> std::result::Result<(), pulseengine_mcp_transport::TransportError> {
+        Ok(())
+    }
+
+    async fn health_check(
+        &self,
+    ) -> std::result::Result<(), pulseengine_mcp_transport::TransportError> {
+        Ok(())
+    }
+
+    async fn send_notification(
+        &self,
+        _session_id: Option<&str>,
+        method: &str,
+        params: serde_json::Value,
+    ) -> std::result::Result<(), pulseengine_mcp_transport::TransportError> {
+        self.sent.lock().unwrap().push((method.to_string(), params));
+        Ok(())
+    }
+}
+
+#[tokio::test]
+async fn test_resource_update_patch_reaches_subscribed_client() {
+    use pulseengine_mcp_protocol::patch::diff;
+
+    let uri = "file:///config.json";
+    let old = serde_json::json!({"retries": 3, "hosts": ["a", "b"]});
+    let new = serde_json::json!({"retries": 5, "hosts": ["a", "b", "c"]});
+
+    let backend = RecordingBackend::default();
+    let handler = recording_handler(&backend);
+    let transport = Arc::new(NotificationRecorder::default());
+    handler.set_transport(transport.clone());
+
+    let update = ResourceUpdatedNotification::with_patch(uri, "v1", "v2", diff(&old, &new));
+    assert!(
+        !handler
+            .notify_resource_updated(update.clone())
+            .await
+            .unwrap()
+    );
+    assert!(transport.sent.lock().unwrap().is_empty());
+
+    let subscribe = Request {
+        jsonrpc: "2.0".to_string(),
+        id: Some(NumberOrString::Number(1)),
+        method: "resources/subscribe".to_string(),
+        params: serde_json::json!({"uri": uri}),
+    };
+    assert!(
+        handler
+            .handle_request(subscribe)
+            .await
+            .unwrap()
+            .error
+            .is_none()
+    );
+    assert!(handler.notify_resource_updated(update).await.unwrap());
+
+    let (method, params) = transport.sent.lock().unwrap().pop().unwrap();
+    assert_eq!(method, "notifications/resources/updated");
+
+    // A client holding the v1 read applies the patch instead of re-reading
+    let received: ResourceUpdatedNotification = serde_json::from_value(params).unwrap();
+    let mut cached = ReadResourceResult::new(vec![ResourceContents {
+        uri: uri.to_string(),
+        mime_type: Some("application/json".to_string()),
+        text: Some(old.to_string()),
+        blob: None,
+        _meta: None,
+    }])
+    .with_etag("v1");
+    assert!(cached.apply_update(&received).unwrap());
+    let patched: serde_json::Value =
+        serde_json::from_str(cached.contents[0].text.as_deref().unwrap()).unwrap();
+    assert_eq!(patched, new);
+    assert_eq!(cached.etag(), Some("v2"));
+}
+
+#[tokio::test]
+async fn test_global_rate_limit_applies_with_auth_disabled() {
+    use crate::rate_limit::{GlobalRateLimitConfig, GlobalRateLimiter};
+
+    let auth = Arc::new(AuthenticationManager::new_disabled());
+    let limiter = GlobalRateLimiter::new(GlobalRateLimitConfig {
+        requests_per_second: 0.001,
+        burst: 5,
+    });
+    let handler = GenericServerHandler::new(
+        Arc::new(RecordingBackend::default()),
+        auth,
+        MiddlewareStack::new().with_rate_limit(limiter),
+    );
+
+    let ping = || Request {
+        jsonrpc: "2.0".to_string(),
+        id: Some(NumberOrString::Number(1)),
+        method: "ping".to_string(),
+        params: serde_json::json!({}),
+    };
+
+    for _ in 0..5 {
+        let response = handler.handle_request(ping()).await.unwrap();
+        assert!(response.error.is_none());
+    }
+
+    let rejected = handler.handle_request(ping()).await.unwrap_err();
+    let error: Error = rejected.into();
+    assert_eq!(error.code, ErrorCode::RateLimitExceeded);
+    assert_eq!(error.message, "Server rate limit exceeded");
+}
+
+#[tokio::test]
+async fn test_backend_experimental_capabilities_in_initialize() {
+    let backend = RecordingBackend {
+        capabilities: Some(
+            ServerCapabilities::builder()
+                .enable_tools()
+                .experimental("streaming", true)
+                .unwrap()
+                .experimental(
+                    "resource_diffs",
+                    serde_json::json!({"format": "json-patch"}),
+                )
+                .unwrap()
+                .build(),
+        ),
+        ..Default::default()
+    };
+    let handler = recording_handler(&backend);
+
+    let response = handler
+        .handle_request(initialize_request("2025-06-18", serde_json::json!({})))
+        .await
+        .unwrap();
+    let result = response.result.unwrap();
+    assert_eq!(
+        result["capabilities"]["experimental"],
+        serde_json::json!({"streaming": true, "resource_diffs": {"format": "json-patch"}})
+    );
+
+    // Clients negotiate optional behaviors from the advertised flags
+    let initialized: InitializeResult = serde_json::from_value(result).unwrap();
+    assert!(initialized.capabilities.supports_experimental("streaming"));
+    assert!(
+        initialized
+            .capabilities
+            .supports_experimental("resource_diffs")
+    );
+    assert!(!initialized.capabilities.supports_experimental("batching"));
+}
+
+fn ui_backend() -> RecordingBackend {
+    let mut backend = RecordingBackend::with_tool("chart", serde_json::json!({"type": "object"}));
+    backend.tools[0]._meta = Some(ToolMeta::with_ui_resource("ui://charts/bar"));
+    backend.resources = vec![
+        Resource::ui_resource("ui://charts/bar", "bar_chart", "Bar chart viewer"),
+        Resource {
+            uri: "file:///notes.txt".to_string(),
+            name: "notes".to_string(),
+            title: None,
+            description: None,
+            mime_type: Some("text/plain".to_string()),
+            annotations: None,
+            icons: None,
+            raw: None,
+            _meta: None,
+        },
+    ];
+    backend.capabilities = Some(
+        ServerCapabilities::builder()
+            .enable_tools()
+            .enable_resources()
+            .enable_sampling()
+            .experimental("io.modelcontextprotocol/apps", serde_json::json!({}))
+            .unwrap()
+            .build(),
+    );
+    backend
+}
+
+async fn listed_resource_uris(handler: &GenericServerHandler<RecordingBackend>) -> Vec<String> {
+    let response = handler
+        .handle_request(Request {
+            jsonrpc: "2.0".to_string(),
+            id: Some(NumberOrString::Number(2)),
+            method: "resources/list".to_string(),
+            params: serde_json::json!({}),
+        })
+        .await
+        .unwrap();
+    let result: ListResourcesResult = serde_json::from_value(response.result.unwrap()).unwrap();
+    result.resources.into_iter().map(|r| r.uri).collect()
+}
+
+async fn listed_tool(handler: &GenericServerHandler<RecordingBackend>) -> Tool {
+    let response = handler
+        .handle_request(Request {
+            jsonrpc: "2.0".to_string(),
+            id: Some(NumberOrString::Number(3)),
+            method: "tools/list".to_string(),
+            params: serde_json::json!({}),
+        })
+        .await
+        .unwrap();
+    let result: ListToolsResult = serde_json::from_value(response.result.unwrap()).unwrap();
+    result.tools.into_iter().next().unwrap()
+}
+
+#[tokio::test]
+async fn test_capability_filter_hides_ui_from_non_ui_client() {
+    let backend = ui_backend();
+    let filter = crate::capability_filter::CapabilityFilterConfig {
+        ui: true,
+        ..Default::default()
+    };
+
+    // A client without the MCP Apps extension
+    let handler = recording_handler(&backend).with_capability_filter(filter.clone());
+    let response = handler
+        .handle_request(initialize_request("2025-06-18", serde_json::json!({})))
+        .await
+        .unwrap();
+    let capabilities = &response.result.unwrap()["capabilities"];
+    assert!(
+        capabilities["experimental"]
+            .get("io.modelcontextprotocol/apps")
+            .is_none()
+    );
+    // Sampling isn't filtered unless opted in
+    assert!(capabilities.get("sampling").is_some());
+    assert_eq!(listed_resource_uris(&handler).await, ["file:///notes.txt"]);
+    assert!(
+        listed_tool(&handler)
+            .await
+            ._meta
+            .is_none_or(|meta| meta.ui_resource_uri.is_none())
+    );
+
+    // A client declaring the MCP Apps extension
+    let handler = recording_handler(&backend).with_capability_filter(filter);
+    let response = handler
+        .handle_request(initialize_request(
+            "2025-06-18",
+            serde_json::json!({"extensions": {"io.modelcontextprotocol/apps": {}}}),
+        ))
+        .await
+        .unwrap();
+    let capabilities = &response.result.unwrap()["capabilities"];
+    assert!(
+        capabilities["experimental"]
+            .get("io.modelcontextprotocol/apps")
+            .is_some()
+    );
+    assert_eq!(
+        listed_resource_uris(&handler).await,
+        ["ui://charts/bar", "file:///notes.txt"]
+    );
+    assert_eq!(
+        listed_tool(&handler)
+            .await
+            ._meta
+            .unwrap()
+            .ui_resource_uri
+            .as_deref(),
+        Some("ui://charts/bar")
+    );
+}
+
+#[tokio::test]
+async fn test_capability_filter_is_opt_in() {
+    let backend = ui_backend();
+
+    let handler = recording_handler(&backend);
+    handler
+        .handle_request(initialize_request("2025-06-18", serde_json::json!({})))
+        .await
+        .unwrap();
+    assert_eq!(listed_resource_uris(&handler).await.len(), 2);
+
+    let handler = recording_handler(&backend)
+        .with_capability_filter(crate::capability_filter::CapabilityFilterConfig::all());
+    let response = handler
+        .handle_request(initialize_request("2025-06-18", serde_json::json!({})))
+        .await
+        .unwrap();
+    assert!(
+        response.result.unwrap()["capabilities"]
+            .get("sampling")
+            .is_none()
+    );
+}
+
+/// Subscriber layer recording the method of every `mcp_request` span
+#[derive(Clone, Default)]
+struct RequestSpanRecorder {
+    methods: Arc<std::sync::Mutex<Vec<String>>>,
+}
+
+impl RequestSpanRecorder {
+    fn count(&self, method: &str) -> usize {
+        self.methods
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|m| *m == method)
+            .count()
+    }
+}
+
+impl<S: tracing::Subscriber> tracing_subscriber::Layer<S> for RequestSpanRecorder {
+    fn on_new_span(
+        &self,
+        attrs: &tracing::span::Attributes<'_>,
+        _id: &tracing::span::Id,
+        _ctx: tracing_subscriber::layer::Context<'_, S>,
+    ) {
+        struct MethodVisitor(Option<String>);
+        impl tracing::field::Visit for MethodVisitor {
+            fn record_str(&mut self, field: &tracing::field::Field, value: &str) {
+                if field.name() == "mcp.method" {
+                    self.0 = Some(value.to_string());
+                }
+            }
+            fn record_debug(
+                &mut self,
+                _field: &tracing::field::Field,
+                _value: &dyn std::fmt::Debug,
+            ) {
+            }
+        }
+
+        if attrs.metadata().name() == "mcp_request" {
+            let mut visitor = MethodVisitor(None);
+            attrs.record(&mut visitor);
+            if let Some(method) = visitor.0 {
+                self.methods.lock().unwrap().push(method);
+            }
+        }
+    }
+}
+
+fn request(method: &str, params: serde_json::Value) -> Request {
+    Request {
+        jsonrpc: "2.0".to_string(),
+        id: Some(NumberOrString::Number(1)),
+        method: method.to_string(),
+        params,
+    }
+}
+
+#[tokio::test]
+async fn test_trace_sampling_per_method_always_traces_errors() {
+    use tracing_subscriber::layer::SubscriberExt;
+
+    let recorder = RequestSpanRecorder::default();
+    let _subscriber =
+        tracing::subscriber::set_default(tracing_subscriber::registry().with(recorder.clone()));
+
+    let backend = RecordingBackend::with_tool("echo", serde_json::json!({"type": "object"}));
+    let handler = recording_handler(&backend).with_trace_sampling(
+        pulseengine_logging::SamplingConfig {
+            default_rate: 0.0,
+            ..Default::default()
+        }
+        .with_method_rate("ping", 0.01)
+        .with_method_rate("tools/call", 1.0),
+    );
+
+    for _ in 0..200 {
+        handler
+            .handle_request(request("ping", serde_json::json!({})))
+            .await
+            .unwrap();
+    }
+    for _ in 0..5 {
+        handler
+            .handle_request(call_tool_request("echo", None))
+            .await
+            .unwrap();
+    }
+    handler
+        .handle_request(request("tools/list", serde_json::json!({})))
+        .await
+        .unwrap();
+    assert_eq!(recorder.count("ping"), 2);
+    assert_eq!(recorder.count("tools/call"), 5);
+    assert_eq!(recorder.count("tools/list"), 0);
+
+    // prompts/get fails in this backend and is traced despite a rate of 0
+    for _ in 0..3 {
+        let response = handler
+            .handle_request(request(
+                "prompts/get",
+                serde_json::json!({"name": "missing"}),
+            ))
+            .await
+            .unwrap();
+        assert!(response.error.is_some());
+    }
+    assert_eq!(recorder.count("prompts/get"), 3);
+}
+
+#[tokio::test]
+async fn test_filtered_subscription_only_receives_matching_updates() {
+    let backend = RecordingBackend::default();
+    let handler = recording_handler(&backend);
+    let transport = Arc::new(NotificationRecorder::default());
+    handler.set_transport(transport.clone());
+
+    let subscribe = Request {
+        jsonrpc: "2.0".to_string(),
+        id: Some(NumberOrString::Number(1)),
+        method: "resources/subscribe".to_string(),
+        params: serde_json::json!({"uri": "file:///logs/", "filter": "file:///logs/*.log"}),
+    };
+    let response = handler.handle_request(subscribe).await.unwrap();
+    assert!(response.error.is_none());
+
+    for uri in [
+        "file:///logs/app.log",
+        "file:///logs/app.txt",
+        "file:///config.json",
+        "file:///logs/",
+        "file:///logs/worker.log",
+    ] {
+        handler
+            .notify_resource_updated(ResourceUpdatedNotification::full(uri))
+            .await
+            .unwrap();
+    }
+
+    let delivered: Vec<String> = transport
+        .sent
+        .lock()
+        .unwrap()
+        .iter()
+        .map(|(_, params)| params["uri"].as_str().unwrap().to_string())
+        .collect();
+    assert_eq!(
+        delivered,
+        ["file:///logs/app.log", "file:///logs/worker.log"]
+    );
+
+    let unsubscribe = Request {
+        jsonrpc: "2.0".to_string(),
+        id: Some(NumberOrString::Number(2)),
+        method: "resources/unsubscribe".to_string(),
+        params: serde_json::json!({"uri": "file:///logs/"}),
+    };
+    handler.handle_request(unsubscribe).await.unwrap();
+    assert!(!handler.is_subscribed("file:///logs/app.log").await);
+}
+
+#[tokio::test]
+async fn test_backend_panic_becomes_internal_error() {
+    let backend = RecordingBackend::default();
+    let handler = recording_handler(&backend);
+
+    let response = handler
+        .handle_request(call_tool_request("panic", None))
+        .await
+        .unwrap();
+    let error = response.error.unwrap();
+    assert_eq!(error.code, ErrorCode::InternalError);
+    assert!(error.message.contains("tools/call"));
+    assert!(!error.message.contains("tool panicked"));
+
+    // The handler keeps serving requests after the panic
+    let response = handler
+        .handle_request(call_tool_request("echo", None))
+        .await
+        .unwrap();
+    assert!(response.error.is_none());
+    assert_eq!(backend.calls.lock().unwrap().len(), 2);
+
+    let handler = recording_handler(&backend).with_panic_recovery(false);
+    let unwound = tokio::spawn(async move {
+        handler
+            .handle_request(call_tool_request("panic", None))
+            .await
+    })
+    .await;
+    assert!(unwound.unwrap_err().is_panic());
+}
+
+#[tokio::test]
+async fn test_streaming_tool_buffered_without_streaming_transport() {
+    let backend = RecordingBackend::default();
+    let handler = recording_handler(&backend);
+
+    let response = handler
+        .handle_request(call_tool_request("tail", None))
+        .await
+        .unwrap();
+    let result: CallToolResult = serde_json::from_value(response.result.unwrap()).unwrap();
+    assert_eq!(
+        serde_json::to_value(&result.content).unwrap(),
+        serde_json::json!([
+            { "type": "text", "text": "line 1\nline 2\n" },
+            { "type": "image", "data": "aGk=", "mimeType": "image/png" },
+        ])
+    );
+    // Streaming tools don't go through `call_tool`
+    assert!(backend.calls.lock().unwrap().is_empty());
+}
+
+#[tokio::test]
+async fn test_streaming_tool_forwards_chunks_as_events() {
+    use crate::handler::TOOL_RESULT_CHUNK_METHOD;
+
+    let backend = RecordingBackend::default();
+    let handler = recording_handler(&backend);
+    let (sender, mut events) = tokio::sync::mpsc::unbounded_channel();
+
+    let response = pulseengine_mcp_transport::with_streaming_context(
+        "stream-session".to_string(),
+        sender,
+        handler.handle_request(call_tool_request("tail", None)),
+    )
+    .await
+    .unwrap();
+
+    let mut chunks = Vec::new();
+    while let Ok(event) = events.try_recv() {
+        assert_eq!(event.method, TOOL_RESULT_CHUNK_METHOD);
+        assert_eq!(event.params["requestId"], 1);
+        assert_eq!(event.params["index"], chunks.len());
+        chunks.push(event.params["content"].clone());
+    }
+    assert_eq!(chunks.len(), 3);
+    assert_eq!(chunks[1]["text"], "line 2\n");
+    assert_eq!(chunks[2]["type"], "image");
+
+    let result: CallToolResult = serde_json::from_value(response.result.unwrap()).unwrap();
+    assert!(result.content.is_empty());
+}
+
+/// Redacts the word "line" from text content
+struct RedactLines;
+
+impl crate::result_transform::ResultTransform for RedactLines {
+    fn name(&self) -> &str {
+        "redact-lines"
+    }
+
+    fn transform(&self, _tool_name: &str, result: &mut CallToolResult) {
+        for content in &mut result.content {
+            if let Content::Text { text, .. } = content {
+                *text = text.replace("line", "[redacted]");
+            }
+        }
+    }
+}
+
+#[tokio::test]
+async fn test_streamed_chunks_run_through_result_transforms() {
+    let backend = RecordingBackend::default();
+    let handler = recording_handler(&backend).with_result_transform(RedactLines);
+    let (sender, mut events) = tokio::sync::mpsc::unbounded_channel();
+
+    pulseengine_mcp_transport::with_streaming_context(
+        "stream-session".to_string(),
+        sender,
+        handler.handle_request(call_tool_request("tail", None)),
+    )
+    .await
+    .unwrap();
+
+    let first = events.try_recv().unwrap();
+    assert_eq!(first.params["content"]["text"], "[redacted] 1\n");
+
+    let buffered = handler
+        .handle_request(call_tool_request("tail", None))
+        .await
+        .unwrap();
+    assert_eq!(
+        buffered.result.unwrap()["content"][0]["text"],
+        "[redacted] 1\n[redacted] 2\n"
+    );
+}
+
+#[tokio::test]
+async fn test_streaming_tool_output_validated_against_aggregate() {
+    let mut backend =
+        RecordingBackend::with_tool("tail_json", serde_json::json!({"type": "object"}));
+    backend.tools[0].output_schema = Some(serde_json::json!({
+        "type": "object",
+        "properties": { "count": { "type": "integer" } },
+        "required": ["count"]
+    }));
+    let handler = recording_handler(&backend).with_output_validation(true);
+
+    let buffered = handler
+        .handle_request(call_tool_request("tail_json", None))
+        .await
+        .unwrap();
+    let result = buffered.result.unwrap();
+    assert_eq!(result["structuredContent"], serde_json::json!({"count": 2}));
+
+    let (sender, mut events) = tokio::sync::mpsc::unbounded_channel();
+    let streamed = pulseengine_mcp_transport::with_streaming_context(
+        "stream-session".to_string(),
+        sender,
+        handler.handle_request(call_tool_request("tail_json", None)),
+    )
+    .await
+    .unwrap();
+    assert!(streamed.error.is_none());
+    assert_eq!(events.try_recv().unwrap().params["index"], 0);
+    assert_eq!(events.try_recv().unwrap().params["index"], 1);
+
+    backend.tools[0].output_schema = Some(serde_json::json!({
+        "type": "object",
+        "properties": { "count": { "type": "string" } },
+        "required": ["count"]
+    }));
+    let handler = recording_handler(&backend).with_output_validation(true);
+    let invalid = handler
+        .handle_request(call_tool_request("tail_json", None))
+        .await
+        .unwrap();
+    assert_eq!(invalid.error.unwrap().code, ErrorCode::ValidationError);
+}
+
+#[tokio::test]
+async fn test_output_validation_applies_configured_content_limits() {
+    let mut backend =
+        RecordingBackend::with_tool("tail_json", serde_json::json!({"type": "object"}));
+    backend.tools[0].output_schema = Some(serde_json::json!({
+        "type": "object",
+        "properties": { "count": { "type": "integer" } }
+    }));
+    let handler = recording_handler(&backend)
+        .with_output_validation(true)
+        .with_structured_content_limits(StructuredContentLimits {
+            max_depth: 8,
+            max_nodes: 1,
+        });
+
+    let error = handler
+        .handle_request(call_tool_request("tail_json", None))
+        .await
+        .unwrap()
+        .error
+        .unwrap();
+    assert_eq!(error.code, ErrorCode::ValidationError);
+    assert!(error.message.contains("maximum of 1 values"));
+}
+
+#[tokio::test]
+async fn test_client_disconnect_aborts_streaming_tool() {
+    let backend = RecordingBackend::default();
+    let handler = recording_handler(&backend);
+    let (sender, mut events) = tokio::sync::mpsc::unbounded_channel();
+
+    let call = tokio::spawn(pulseengine_mcp_transport::with_streaming_context(
+        "stream-session".to_string(),
+        sender,
+        async move {
+            handler
+                .handle_request(call_tool_request("tail_follow", None))
+                .await
+        },
+    ));
+
+    let first = events.recv().await.unwrap();
+    assert_eq!(first.params["content"]["text"], "line 1\n");
+    drop(events);
+
+    let response = tokio::time::timeout(std::time::Duration::from_secs(5), call)
+        .await
+        .expect("streaming call should stop after disconnect")
+        .unwrap()
+        .unwrap();
+    assert!(response.error.unwrap().message.contains("disconnected"));
+    assert!(
+        backend
+            .stream_dropped
+            .load(std::sync::atomic::Ordering::SeqCst)
+    );
+}
+
+#[tokio::test]
+async fn test_output_validation_rejects_results_violating_output_schema() {
+    let mut backend = RecordingBackend::with_tool("report", serde_json::json!({"type": "object"}));
+    backend.tools[0].output_schema = Some(serde_json::json!({
+        "type": "object",
+        "properties": { "count": { "type": "integer" } },
+        "required": ["count"]
+    }));
+
+    // Lenient by default: the text-only result goes out unchecked
+    let response = recording_handler(&backend)
+        .handle_request(call_tool_request("report", None))
+        .await
+        .unwrap();
+    assert!(response.error.is_none());
+
+    let strict = recording_handler(&backend).with_output_validation(true);
+    let response = strict
+        .handle_request(call_tool_request("report", None))
+        .await
+        .unwrap();
+    let error = response.error.unwrap();
+    assert_eq!(error.code, ErrorCode::ValidationError);
+    assert!(error.message.contains("returned no structured content"));
+
+    // Tools without an output schema are unaffected
+    let response = strict
+        .handle_request(call_tool_request("undeclared", None))
+        .await
+        .unwrap();
+    assert!(response.error.is_none());
+}
+
+#[tokio::test]
+async fn test_cancelled_notification_aborts_in_flight_tool_call() {
+    let backend = RecordingBackend::default();
+    let handler = recording_handler(&backend);
+
+    let call = tokio::spawn({
+        let handler = handler.clone();
+        async move {
+            handler
+                .handle_request(call_tool_request("slow", None))
+                .await
+        }
+    });
+    while backend.calls.lock().unwrap().is_empty() {
+        tokio::task::yield_now().await;
+    }
+    assert_eq!(handler.in_flight_requests().len(), 1);
+
+    let cancel = Request {
+        jsonrpc: "2.0".to_string(),
+        id: None,
+        method: "notifications/cancelled".to_string(),
+        params: serde_json::json!({ "requestId": 1, "reason": "user aborted" }),
+    };
+    handler.handle_request(cancel).await.unwrap();
+
+    // No response is produced, and the tool future is dropped well before it finishes
+    let outcome = tokio::time::timeout(std::time::Duration::from_secs(5), call)
+        .await
+        .expect("cancelled call returns promptly")
+        .unwrap();
+    assert!(matches!(
+        outcome,
+        Err(HandlerError::Cancelled(NumberOrString::Number(1)))
+    ));
+    assert!(
+        backend
+            .stream_dropped
+            .load(std::sync::atomic::Ordering::SeqCst)
+    );
+    assert!(handler.in_flight_requests().is_empty());
+
+    // Cancelling a request that already finished is ignored
+    let late = Request {
+        jsonrpc: "2.0".to_string(),
+        id: None,
+        method: "notifications/cancelled".to_string(),
+        params: serde_json::json!({ "requestId": 1 }),
+    };
+    assert!(handler.handle_request(late).await.is_ok());
+}
+
+#[tokio::test]
+async fn test_cancelled_notification_only_reaches_its_own_connection() {
+    use pulseengine_mcp_transport::{ConnectionInfo, with_connection};
+
+    let backend = RecordingBackend::default();
+    let handler = recording_handler(&backend);
+    let on = |id: &str| ConnectionInfo::new(id, "websocket");
+
+    // Both clients use request id 1
+    let spawn_call = |id: &str| {
+        let handler = handler.clone();
+        tokio::spawn(with_connection(on(id), async move {
+            handler
+                .handle_request(call_tool_request("slow", None))
+                .await
+        }))
+    };
+    let call_a = spawn_call("conn-a");
+    let call_b = spawn_call("conn-b");
+    while backend.calls.lock().unwrap().len() < 2 {
+        tokio::task::yield_now().await;
+    }
+    assert_eq!(handler.in_flight_requests().len(), 2);
+
+    let cancel = || Request {
+        jsonrpc: "2.0".to_string(),
+        id: None,
+        method: "notifications/cancelled".to_string(),
+        params: serde_json::json!({ "requestId": 1 }),
+    };
+    with_connection(on("conn-b"), handler.handle_request(cancel()))
+        .await
+        .unwrap();
+
+    let outcome = tokio::time::timeout(std::time::Duration::from_secs(5), call_b)
+        .await
+        .expect("cancelled call returns promptly")
+        .unwrap();
+    assert!(matches!(outcome, Err(HandlerError::Cancelled(_))));
+
+    // The other connection's request with the same id keeps running
+    assert_eq!(handler.in_flight_requests().len(), 1);
+    assert!(!call_a.is_finished());
+
+    with_connection(on("conn-a"), handler.handle_request(cancel()))
+        .await
+        .unwrap();
+    let outcome = call_a.await.unwrap();
+    assert!(matches!(outcome, Err(HandlerError::Cancelled(_))));
+}
+
+#[tokio::test]
+async fn test_cancellation_bypasses_concurrency_limit() {
+    let backend = RecordingBackend::default();
+    let handler =
+        recording_handler(&backend).with_concurrency_limit(crate::concurrency::ConcurrencyConfig {
+            max_concurrent_requests: 1,
+            max_per_connection: None,
+        });
+
+    let call = tokio::spawn({
+        let handler = handler.clone();
+        async move {
+            handler
+                .handle_message(serde_json::json!({
+                    "jsonrpc": "2.0",
+                    "id": 1,
+                    "method": "tools/call",
+                    "params": { "name": "slow", "arguments": {} },
+                }))
+                .await
+        }
+    });
+    while backend.calls.lock().unwrap().is_empty() {
+        tokio::task::yield_now().await;
+    }
+
+    // The slow call holds the only slot; the cancellation doesn't wait for it
+    let cancel = Request {
+        jsonrpc: "2.0".to_string(),
+        id: None,
+        method: "notifications/cancelled".to_string(),
+        params: serde_json::json!({ "requestId": 1 }),
+    };
+    tokio::time::timeout(
+        std::time::Duration::from_secs(5),
+        handler.handle_request(cancel),
+    )
+    .await
+    .expect("cancellation isn't queued")
+    .unwrap();
+
+    // The cancelled request goes unanswered
+    let outcome = tokio::time::timeout(std::time::Duration::from_secs(5), call)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(outcome, None);
+}
+
+#[tokio::test]
+async fn test_tool_reports_progress_when_client_supplies_token() {
+    let backend = RecordingBackend::default();
+    let handler = recording_handler(&backend);
+    let transport = Arc::new(NotificationRecorder::default());
+    handler.set_transport(transport.clone());
+
+    let mut request = call_tool_request("batch", None);
+    request.params["_meta"] = serde_json::json!({ "progressToken": 7 });
+    let response = handler.handle_request(request).await.unwrap();
+    assert!(response.error.is_none());
+
+    let sent = transport.sent.lock().unwrap().clone();
+    assert_eq!(sent.len(), 3);
+    assert!(
+        sent.iter()
+            .all(|(method, _)| method == "notifications/progress")
+    );
+    assert_eq!(
+        sent[2].1,
+        serde_json::json!({
+            "progressToken": 7,
+            "progress": 3.0,
+            "total": 3.0,
+            "message": "item 3",
+        })
+    );
+
+    // Without a progress token the reporter stays silent
+    let response = handler
+        .handle_request(call_tool_request("batch", None))
+        .await
+        .unwrap();
+    assert!(response.error.is_none());
+    assert_eq!(transport.sent.lock().unwrap().len(), 3);
+}
+
+#[tokio::test]
+async fn test_tool_reads_negotiated_protocol_version() {
+    async fn version_seen_by_tool(handler: &GenericServerHandler<RecordingBackend>) -> String {
+        let response = handler
+            .handle_request(call_tool_request("version", None))
+            .await
+            .unwrap();
+        let result: CallToolResult = serde_json::from_value(response.result.unwrap()).unwrap();
+        match &result.content[0] {
+            Content::Text { text, .. } => text.clone(),
+            other => panic!("unexpected content {other:?}"),
+        }
+    }
+
+    let backend = RecordingBackend::default();
+    let handler = recording_handler(&backend);
+    assert_eq!(version_seen_by_tool(&handler).await, "none");
+
+    // A supported requested version is negotiated as-is
+    handler
+        .handle_request(initialize_request("2024-11-05", serde_json::json!({})))
+        .await
+        .unwrap();
+    assert_eq!(version_seen_by_tool(&handler).await, "2024-11-05");
+
+    // An unsupported one falls back to the server's latest version
+    handler
+        .handle_request(initialize_request("1999-01-01", serde_json::json!({})))
+        .await
+        .unwrap();
+    assert_eq!(
+        version_seen_by_tool(&handler).await,
+        pulseengine_mcp_protocol::MCP_VERSION
+    );
+}
+
+fn call_tool_with_timeout(name: &str, timeout_ms: u64) -> Request {
+    let mut request = call_tool_request(name, None);
+    request.params["_meta"] = serde_json::json!({ "timeoutMs": timeout_ms });
+    request
+}
+
+#[tokio::test]
+async fn test_client_timeout_cancels_slow_tool() {
+    let backend = RecordingBackend::default();
+    let handler = recording_handler(&backend);
+
+    let response = tokio::time::timeout(
+        std::time::Duration::from_secs(5),
+        handler.handle_request(call_tool_with_timeout("slow", 50)),
+    )
+    .await
+    .expect("timed out call returns promptly")
+    .unwrap();
+    let error = response.error.unwrap();
+    assert!(error.message.contains("timed out after 50ms"));
+    assert_eq!(error.data.unwrap()["timeoutMs"], 50);
+    assert!(
+        backend
+            .stream_dropped
+            .load(std::sync::atomic::Ordering::SeqCst)
+    );
+
+    // A generous budget lets the tool complete
+    let response = handler
+        .handle_request(call_tool_with_timeout("nap", 5_000))
+        .await
+        .unwrap();
+    assert!(response.error.is_none());
+}
+
+#[tokio::test]
+async fn test_client_timeout_is_clamped_to_server_maximum() {
+    let backend = RecordingBackend::default();
+    let handler =
+        recording_handler(&backend).with_max_tool_timeout(std::time::Duration::from_millis(50));
+
+    let response = handler
+        .handle_request(call_tool_with_timeout("slow", 600_000))
+        .await
+        .unwrap();
+    assert_eq!(response.error.unwrap().data.unwrap()["timeoutMs"], 50);
+
+    // The maximum also applies when the client names no limit
+    let response = handler
+        .handle_request(call_tool_request("slow", None))
+        .await
+        .unwrap();
+    assert!(response.error.unwrap().message.contains("timed out"));
+}
+
+#[tokio::test]
+async fn test_adaptive_timeout_scales_with_request_size() {
+    let backend = RecordingBackend::default();
+    let handler = recording_handler(&backend).with_adaptive_timeout(AdaptiveTimeoutConfig {
+        base_ms: 5,
+        per_kib_ms: 100,
+        max_ms: 2_000,
+    });
+
+    // A small request is held to about the base timeout
+    let response = handler
+        .handle_request(call_tool_request("nap", None))
+        .await
+        .unwrap();
+    let error = response.error.unwrap();
+    assert!(
+        error
+            .message
+            .starts_with("Request 'tools/call' timed out after")
+    );
+    let data = error.data.unwrap();
+    assert!(data["timeoutMs"].as_u64().unwrap() < 10);
+    assert_eq!(data["method"], "tools/call");
+
+    // A 10 KiB payload earns a second more, enough for the same tool
+    let payload = "x".repeat(10 * 1024);
+    let response = handler
+        .handle_request(call_tool_request(
+            "nap",
+            Some(serde_json::json!({ "payload": payload })),
+        ))
+        .await
+        .unwrap();
+    assert!(response.error.is_none());
+
+    // However large the request, the cap holds
+    let payload = "x".repeat(1024 * 1024);
+    let response = tokio::time::timeout(
+        std::time::Duration::from_secs(10),
+        handler.handle_request(call_tool_request(
+            "slow",
+            Some(serde_json::json!({ "payload": payload })),
+        )),
+    )
+    .await
+    .expect("capped timeout fires")
+    .unwrap();
+    assert_eq!(response.error.unwrap().data.unwrap()["timeoutMs"], 2_000);
+}
+
+fn tool_with_timeout(name: &str, timeout_ms: u64) -> Tool {
+    Tool {
+        name: name.to_string(),
+        description: format!("{name} tool"),
+        input_schema: serde_json::json!({"type": "object"}),
+        output_schema: None,
+        title: None,
+        annotations: None,
+        icons: None,
+        execution: Some(ToolExecution {
+            timeout_ms: Some(timeout_ms),
+            ..Default::default()
+        }),
+        _meta: None,
+    }
+}
+
+#[tokio::test]
+async fn test_tool_timeout_overrides_server_maximum() {
+    let backend = RecordingBackend {
+        tools: vec![
+            tool_with_timeout("slow", 50),
+            tool_with_timeout("nap", 5_000),
+        ],
+        ..Default::default()
+    };
+    let handler =
+        recording_handler(&backend).with_max_tool_timeout(std::time::Duration::from_millis(5));
+
+    // A tighter per-tool limit cancels the call
+    let response = tokio::time::timeout(
+        std::time::Duration::from_secs(5),
+        handler.handle_request(call_tool_request("slow", None)),
+    )
+    .await
+    .expect("timed out call returns promptly")
+    .unwrap();
+    let error = response.error.unwrap();
+    assert_eq!(error.code, ErrorCode::InternalError);
+    assert!(error.message.contains("Tool 'slow' timed out after 50ms"));
+    assert!(
+        backend
+            .stream_dropped
+            .load(std::sync::atomic::Ordering::SeqCst)
+    );
+
+    // A looser one lets a tool outlast the server-wide limit
+    let response = handler
+        .handle_request(call_tool_request("nap", None))
+        .await
+        .unwrap();
+    assert!(response.error.is_none());
+
+    // Clients can still ask for less time than the tool allows
+    let response = handler
+        .handle_request(call_tool_with_timeout("nap", 1))
+        .await
+        .unwrap();
+    assert_eq!(response.error.unwrap().data.unwrap()["timeoutMs"], 1);
+}
+
+#[tokio::test]
+async fn test_complete_defaults_to_no_suggestions() {
+    let handler = create_test_handler().await;
+    let request = Request {
+        jsonrpc: "2.0".to_string(),
+        id: Some(NumberOrString::Number(1)),
+        method: "completion/complete".to_string(),
+        params: serde_json::json!({
+            "ref": {"type": "ref/prompt", "name": "greeting"},
+            "argument": {"name": "name", "value": "Wo"},
+            "context": {"argumentNames": ["language"], "values": {"language": "en"}}
+        }),
+    };
+
+    let response = handler.handle_request(request).await.unwrap();
+    assert!(response.error.is_none());
+    assert_eq!(
+        response.result.unwrap(),
+        serde_json::json!({"completion": {"values": []}})
+    );
+}
+
+#[tokio::test]
+async fn test_degraded_health_sheds_low_priority_requests() {
+    let backend = RecordingBackend::default();
+    let signal = crate::HealthSignal::new();
+    let handler = recording_handler(&backend).with_load_shedding(crate::LoadShedder::new(
+        crate::LoadSheddingConfig::default(),
+        signal.clone(),
+    ));
+    let complete = Request {
+        jsonrpc: "2.0".to_string(),
+        id: Some(NumberOrString::Number(7)),
+        method: "completion/complete".to_string(),
+        params: serde_json::json!({
+            "ref": {"type": "ref/prompt", "name": "greeting"},
+            "argument": {"name": "name", "value": "Wo"}
+        }),
+    };
+    let ping = Request {
+        jsonrpc: "2.0".to_string(),
+        id: Some(NumberOrString::Number(8)),
+        method: "ping".to_string(),
+        params: serde_json::json!({}),
+    };
+
+    signal.set(crate::BackendHealth::Degraded);
+    let response = handler.handle_request(complete.clone()).await.unwrap();
+    let error = response.error.expect("low-priority request should be shed");
+    assert_eq!(error.code, ErrorCode::ServerBusy);
+    assert_eq!(error.data.unwrap()["health"], "degraded");
+
+    let response = handler.handle_request(ping.clone()).await.unwrap();
+    assert!(response.error.is_none());
+
+    // Once healthy again the request reaches the backend
+    signal.set(crate::BackendHealth::Healthy);
+    let response = handler.handle_request(complete).await.unwrap();
+    assert_ne!(
+        response.error.map(|error| error.code),
+        Some(ErrorCode::ServerBusy)
+    );
+}
+
+#[tokio::test]
+async fn test_batch_mixes_requests_and_notifications() {
+    let handler = create_test_handler().await;
+    let batch = serde_json::json!([
+        {"jsonrpc": "2.0", "id": 1, "method": "ping"},
+        {"jsonrpc": "2.0", "method": "notifications/initialized"},
+        {"jsonrpc": "2.0", "id": "list", "method": "tools/list", "params": {}},
+        {"jsonrpc": "2.0", "id": 3, "method": "unknown/method"},
+        42
+    ]);
+
+    let responses = handler.handle_message(batch).await.unwrap();
+    let responses = responses.as_array().unwrap();
+    assert_eq!(responses.len(), 4, "the notification gets no entry");
+
+    assert_eq!(responses[0]["id"], 1);
+    assert!(responses[0]["result"].is_object());
+    assert_eq!(responses[1]["id"], "list");
+    assert!(responses[1]["result"]["tools"].is_array());
+    assert_eq!(responses[2]["id"], 3);
+    assert!(responses[2]["error"].is_object());
+    // An entry that isn't a request is answered without an id
+    assert!(responses[3].get("id").is_none());
+    assert_eq!(
+        responses[3]["error"]["code"],
+        serde_json::to_value(ErrorCode::InvalidRequest).unwrap()
+    );
+}
+
+#[tokio::test]
+async fn test_batch_of_notifications_has_no_response() {
+    let handler = create_test_handler().await.with_batch_concurrency(1);
+    let batch = serde_json::json!([
+        {"jsonrpc": "2.0", "method": "notifications/initialized"},
+        {"jsonrpc": "2.0", "method": "notifications/cancelled", "params": {"requestId": 9}}
+    ]);
+    assert!(handler.handle_message(batch).await.is_none());
+
+    let notification = serde_json::json!({"jsonrpc": "2.0", "method": "notifications/initialized"});
+    assert!(handler.handle_message(notification).await.is_none());
+}
+
+#[tokio::test]
+async fn test_empty_batch_is_invalid_request() {
+    let handler = create_test_handler().await;
+    let response = handler.handle_message(serde_json::json!([])).await.unwrap();
+    assert!(
+        response.is_object(),
+        "an empty batch gets a single response"
+    );
+    assert_eq!(
+        response["error"]["code"],
+        serde_json::to_value(ErrorCode::InvalidRequest).unwrap()
+    );
+}
+
+#[tokio::test]
+async fn test_batch_with_duplicate_ids_is_rejected() {
+    let backend = RecordingBackend::default();
+    let handler = recording_handler(&backend);
+    let batch = serde_json::json!([
+        {"jsonrpc": "2.0", "id": 7, "method": "tools/call", "params": {"name": "nap", "arguments": {}}},
+        {"jsonrpc": "2.0", "method": "notifications/initialized"},
+        {"jsonrpc": "2.0", "id": 7, "method": "tools/call", "params": {"name": "nap", "arguments": {}}}
+    ]);
+
+    let response = handler.handle_message(batch.clone()).await.unwrap();
+    assert!(response.is_object(), "the batch gets a single error");
+    assert!(response.get("id").is_none());
+    assert_eq!(
+        response["error"]["code"],
+        serde_json::to_value(ErrorCode::InvalidRequest).unwrap()
+    );
+    assert!(
+        response["error"]["message"]
+            .as_str()
+            .unwrap()
+            .contains("id 7")
+    );
+    assert!(
+        backend.calls.lock().unwrap().is_empty(),
+        "nothing was processed"
+    );
+
+    // Enforcement can be turned off
+    let handler = recording_handler(&backend).with_unique_batch_ids(false);
+    let responses = handler.handle_message(batch).await.unwrap();
+    assert_eq!(responses.as_array().unwrap().len(), 2);
+}
+
+#[tokio::test]
+async fn test_batch_with_unique_ids_is_processed() {
+    let handler = create_test_handler().await;
+    let batch = serde_json::json!([
+        {"jsonrpc": "2.0", "id": 1, "method": "ping"},
+        {"jsonrpc": "2.0", "id": "1", "method": "ping"},
+        {"jsonrpc": "2.0", "method": "notifications/initialized"},
+        {"jsonrpc": "2.0", "method": "notifications/initialized"}
+    ]);
+
+    let responses = handler.handle_message(batch).await.unwrap();
+    let ids: Vec<_> = responses
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|response| response["id"].clone())
+        .collect();
+    assert_eq!(ids, [serde_json::json!(1), serde_json::json!("1")]);
+}
+
+#[tokio::test]
+async fn test_single_message_is_not_wrapped_in_array() {
+    let handler = create_test_handler().await;
+    let response = handler
+        .handle_message(serde_json::json!({"jsonrpc": "2.0", "id": 5, "method": "ping"}))
+        .await
+        .unwrap();
+    assert_eq!(response["id"], 5);
+    assert!(response["result"].is_object());
+}
+
+#[tokio::test]
+async fn test_batch_requests_run_concurrently_up_to_limit() {
+    let backend = RecordingBackend::default();
+    let handler = recording_handler(&backend).with_batch_concurrency(2);
+    let batch = serde_json::Value::Array(
+        (0..4)
+            .map(|id| {
+                serde_json::json!({
+                    "jsonrpc": "2.0",
+                    "id": id,
+                    "method": "tools/call",
+                    "params": {"name": "nap", "arguments": {}}
+                })
+            })
+            .collect(),
+    );
+
+    let responses = handler.handle_message(batch).await.unwrap();
+    let ids: Vec<_> = responses
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|response| response["id"].clone())
+        .collect();
+    assert_eq!(ids, [0, 1, 2, 3]);
+    assert_eq!(backend.peak_concurrent_naps(), 2);
+}
+
+#[tokio::test]
+async fn test_resource_changes_reach_subscribed_sessions_only() {
+    use pulseengine_mcp_transport::with_session;
+
+    let subscriptions = crate::ResourceSubscriptionManager::new();
+    let backend = RecordingBackend {
+        capabilities: Some(ServerCapabilities {
+            resources: Some(ResourcesCapability {
+                subscribe: None,
+                list_changed: None,
+            }),
+            ..Default::default()
+        }),
+        ..Default::default()
+    };
+    let handler = recording_handler(&backend).with_resource_subscriptions(subscriptions.clone());
+    let transport = Arc::new(NotificationRecorder::default());
+    handler.set_transport(transport.clone());
+
+    // The supplied manager makes `initialize` advertise subscriptions
+    let response = handler
+        .handle_request(initialize_request("2025-06-18", serde_json::json!({})))
+        .await
+        .unwrap();
+    assert_eq!(
+        response.result.unwrap()["capabilities"]["resources"]["subscribe"],
+        true
+    );
+
+    for session in ["watcher", "bystander"] {
+        let uri = if session == "watcher" {
+            "file:///config.json"
+        } else {
+            "file:///other.json"
+        };
+        let response = with_session(
+            session.to_string(),
+            handler.handle_request(subscribe_request(uri)),
+        )
+        .await
+        .unwrap();
+        assert!(response.error.is_none());
+    }
+    assert_eq!(
+        subscriptions.subscribers("file:///config.json").await,
+        ["watcher"]
+    );
+
+    // The backend notifies through its clone of the manager
+    let notified = subscriptions
+        .notify_resource_changed("file:///config.json")
+        .await
+        .unwrap();
+    assert_eq!(notified, 1);
+    let sent = transport.sent.lock().unwrap();
+    assert_eq!(sent.len(), 1);
+    assert_eq!(sent[0].1["uri"], "file:///config.json");
+}
+
+/// Backend rejecting every tool call as a client error
+#[derive(Clone)]
+struct StrictArgsBackend;
+
+#[async_trait]
+impl crate::backend::SimpleBackend for StrictArgsBackend {
+    type Error = BackendError;
+    type Config = ();
+
+    async fn initialize(_: Self::Config) -> std::result::Result<Self, Self::Error> {
+        Ok(Self)
+    }
+
+    fn get_server_info(&self) -> ServerInfo {
+        ServerInfo {
+            protocol_version: ProtocolVersion::default(),
+            capabilities: ServerCapabilities::default(),
+            server_info: Implementation::new("strict", "1.0.0"),
+            instructions: None,
+        }
+    }
+
+    async fn health_check(&self) -> std::result::Result<(), Self::Error> {
+        Ok(())
+    }
+
+    async fn list_tools(
+        &self,
+        _: PaginatedRequestParam,
+    ) -> std::result::Result<ListToolsResult, Self::Error> {
+        Ok(ListToolsResult {
+            tools: vec![],
+            next_cursor: None,
+        })
+    }
+
+    async fn call_tool(
+        &self,
+        _: CallToolRequestParam,
+    ) -> std::result::Result<CallToolResult, Self::Error> {
+        Err(BackendError::client_error("'path' must be absolute"))
+    }
+}
+
+#[tokio::test]
+async fn test_backend_client_error_maps_to_invalid_params() {
+    let handler = GenericServerHandler::new(
+        Arc::new(StrictArgsBackend),
+        Arc::new(AuthenticationManager::new_disabled()),
+        MiddlewareStack::new(),
+    );
+
+    let response = handler
+        .handle_request(call_tool_request("read", None))
+        .await
+        .unwrap();
+    let error = response.error.unwrap();
+    assert_eq!(error.code, ErrorCode::InvalidParams);
+    assert_eq!(error.message, "'path' must be absolute");
+}
+
+#[tokio::test]
+async fn test_tool_rate_limits_apply_per_tool() {
+    use pulseengine_auth::{AuthContext, Role, ToolRateLimitConfig, ValidationConfig};
+
+    let mut validation_config = ValidationConfig::default();
+    validation_config.tool_rate_limits.insert(
+        "export".to_string(),
+        ToolRateLimitConfig {
+            max_requests_per_window: 1,
+            window_duration_seconds: 3600,
+        },
+    );
+    let auth_manager =
+        AuthenticationManager::new_with_validation(AuthConfig::memory(), validation_config)
+            .await
+            .unwrap();
+    let handler = GenericServerHandler::new(
+        Arc::new(RecordingBackend::default()),
+        Arc::new(auth_manager),
+        MiddlewareStack::new(),
+    );
+    let caller = AuthContext {
+        user_id: Some("user-1".to_string()),
+        roles: vec![Role::Operator],
+        api_key_id: Some("key-1".to_string()),
+        permissions: vec![],
+    };
+    let call = |name: &str| {
+        let request = Request {
+            jsonrpc: "2.0".to_string(),
+            id: Some(NumberOrString::Number(1)),
+            method: "tools/call".to_string(),
+            params: serde_json::json!({ "name": name, "arguments": {} }),
+        };
+        crate::context::with_auth_context(caller.clone(), handler.handle_request(request))
+    };
+
+    assert!(call("export").await.unwrap().error.is_none());
+    let error = call("export").await.unwrap().error.unwrap();
+    assert_eq!(error.code, ErrorCode::RateLimitExceeded);
+    assert_eq!(error.message, "Rate limit exceeded for tool 'export'");
+
+    // Other tools have no limit of their own
+    for _ in 0..3 {
+        assert!(call("search").await.unwrap().error.is_none());
+    }
+}
+
+#[tokio::test]
+async fn test_tool_rate_limits_apply_to_transport_callers() {
+    use pulseengine_auth::middleware::{McpAuthConfig, McpAuthMiddleware};
+    use pulseengine_auth::{Role, ToolRateLimitConfig, ValidationConfig};
+    use pulseengine_mcp_transport::{ConnectionInfo, with_connection};
+
+    let mut validation_config = ValidationConfig::default();
+    validation_config.tool_rate_limits.insert(
+        "export".to_string(),
+        ToolRateLimitConfig {
+            max_requests_per_window: 1,
+            window_duration_seconds: 3600,
+        },
+    );
+    let auth_manager = Arc::new(
+        AuthenticationManager::new_with_validation(AuthConfig::memory(), validation_config)
+            .await
+            .unwrap(),
+    );
+    let key = auth_manager
+        .create_api_key("client".to_string(), Role::Operator, None, None)
+        .await
+        .unwrap();
+    let handler = GenericServerHandler::new(
+        Arc::new(RecordingBackend::default()),
+        auth_manager.clone(),
+        MiddlewareStack::new(),
+    )
+    .with_caller_auth(McpAuthMiddleware::new(
+        auth_manager,
+        McpAuthConfig::default(),
+    ));
+    let call = |authorization: Option<String>| {
+        let request = Request {
+            jsonrpc: "2.0".to_string(),
+            id: Some(NumberOrString::Number(1)),
+            method: "tools/call".to_string(),
+            params: serde_json::json!({ "name": "export", "arguments": {} }),
+        };
+        let connection = ConnectionInfo::new("conn-1", "http").with_headers(
+            authorization
+                .as_deref()
+                .map(|value| ("authorization", value)),
+        );
+        with_connection(connection, handler.handle_request(request))
+    };
+    let bearer = Some(format!("Bearer {}", key.key));
+
+    assert!(call(bearer.clone()).await.unwrap().error.is_none());
+    let error = call(bearer).await.unwrap().error.unwrap();
+    assert_eq!(error.code, ErrorCode::RateLimitExceeded);
+
+    // Callers without credentials are turned away before reaching the tool
+    let error: Error = call(None).await.unwrap_err().into();
+    assert_eq!(error.code, ErrorCode::Unauthorized);
+}
+
+#[tokio::test]
+async fn test_permission_checker_guards_tools_and_resources() {
+    use pulseengine_auth::middleware::{McpAuthConfig, McpAuthMiddleware};
+    use pulseengine_auth::{PermissionChecker, PermissionConfig, Role};
+    use pulseengine_mcp_transport::{ConnectionInfo, with_connection};
+
+    let auth_manager = Arc::new(
+        AuthenticationManager::new(AuthConfig::memory())
+            .await
+            .unwrap(),
+    );
+    let key = auth_manager
+        .create_api_key("client".to_string(), Role::Operator, None, None)
+        .await
+        .unwrap();
+    let mut config =
+        PermissionConfig::permissive().deny_role_resource(Role::Operator, "file://secret/*");
+    config.tools.admin_only_tools.insert("wipe".to_string());
+    let handler = GenericServerHandler::new(
+        Arc::new(RecordingBackend::default()),
+        auth_manager.clone(),
+        MiddlewareStack::new(),
+    )
+    .with_caller_auth(
+        McpAuthMiddleware::new(auth_manager, McpAuthConfig::default())
+            .with_permission_checker(PermissionChecker::new(config)),
+    );
+    let bearer = format!("Bearer {}", key.key);
+    let send = |method: &str, params: serde_json::Value| {
+        let request = Request {
+            jsonrpc: "2.0".to_string(),
+            id: Some(NumberOrString::Number(1)),
+            method: method.to_string(),
+            params,
+        };
+        let connection = ConnectionInfo::new("conn-1", "http")
+            .with_headers([("authorization", bearer.as_str())]);
+        with_connection(connection, handler.handle_request(request))
+    };
+
+    let response = send(
+        "tools/call",
+        serde_json::json!({ "name": "wipe", "arguments": {} }),
+    )
+    .await
+    .unwrap();
+    assert_eq!(response.error.unwrap().code, ErrorCode::Forbidden);
+    let response = send(
+        "resources/read",
+        serde_json::json!({ "uri": "file://secret/x" }),
+    )
+    .await
+    .unwrap();
+    assert_eq!(response.error.unwrap().code, ErrorCode::Forbidden);
+
+    // Anything the configuration does not restrict still goes through
+    let response = send(
+        "tools/call",
+        serde_json::json!({ "name": "echo", "arguments": {} }),
+    )
+    .await
+    .unwrap();
+    assert!(response.error.is_none());
+    let response = send(
+        "resources/read",
+        serde_json::json!({ "uri": "file://public/x" }),
+    )
+    .await
+    .unwrap();
+    assert!(response.error.is_none());
+}
+
+#[tokio::test]
+async fn test_denied_tool_calls_do_not_take_rate_limit_tokens() {
+    use pulseengine_auth::middleware::{McpAuthConfig, McpAuthMiddleware};
+    use pulseengine_auth::{
+        PermissionChecker, PermissionConfig, Role, ToolRateLimitConfig, ValidationConfig,
+    };
+    use pulseengine_mcp_transport::{ConnectionInfo, with_connection};
+
+    let mut validation_config = ValidationConfig::default();
+    validation_config.tool_rate_limits.insert(
+        "wipe".to_string(),
+        ToolRateLimitConfig {
+            max_requests_per_window: 1,
+            window_duration_seconds: 3600,
+        },
+    );
+    let auth_manager = Arc::new(
+        AuthenticationManager::new_with_validation(AuthConfig::memory(), validation_config)
+            .await
+            .unwrap(),
+    );
+    let key = auth_manager
+        .create_api_key("client".to_string(), Role::Operator, None, None)
+        .await
+        .unwrap();
+    let mut config = PermissionConfig::permissive();
+    config.tools.admin_only_tools.insert("wipe".to_string());
+    let handler = GenericServerHandler::new(
+        Arc::new(RecordingBackend::default()),
+        auth_manager.clone(),
+        MiddlewareStack::new(),
+    )
+    .with_caller_auth(
+        McpAuthMiddleware::new(auth_manager, McpAuthConfig::default())
+            .with_permission_checker(PermissionChecker::new(config)),
+    );
+    let bearer = format!("Bearer {}", key.key);
+
+    // Every denied call is reported as such, not as exceeding the limit
+    for _ in 0..3 {
+        let request = Request {
+            jsonrpc: "2.0".to_string(),
+            id: Some(NumberOrString::Number(1)),
+            method: "tools/call".to_string(),
+            params: serde_json::json!({ "name": "wipe", "arguments": {} }),
+        };
+        let connection = ConnectionInfo::new("conn-1", "http")
+            .with_headers([("authorization", bearer.as_str())]);
+        let response = with_connection(connection, handler.handle_request(request))
+            .await
+            .unwrap();
+        assert_eq!(response.error.unwrap().code, ErrorCode::Forbidden);
+    }
+}
+
+#[tokio::test]
+async fn test_tool_definition_looked_up_only_when_needed() {
+    use std::sync::atomic::Ordering;
+
+    let backend = RecordingBackend::with_tool("local", serde_json::json!({"type": "object"}));
+    let listings = || backend.tool_listings.load(Ordering::SeqCst);
+
+    let plain = recording_handler(&backend);
+    let response = plain
+        .handle_request(call_tool_request("local", None))
+        .await
+        .unwrap();
+    assert!(response.error.is_none());
+    assert_eq!(listings(), 0);
+
+    // Per-tool timeouts need the definition
+    let timed =
+        recording_handler(&backend).with_max_tool_timeout(std::time::Duration::from_secs(5));
+    let response = timed
+        .handle_request(call_tool_request("local", None))
+        .await
+        .unwrap();
+    assert!(response.error.is_none());
+    assert_eq!(listings(), 1);
+}
+
+#[tokio::test]
+async fn test_tool_lookup_stops_on_repeated_cursor() {
+    let backend = RecordingBackend {
+        tools_cursor: Some("again".to_string()),
+        ..RecordingBackend::with_tool("local", serde_json::json!({"type": "object"}))
+    };
+    let handler = recording_handler(&backend);
+
+    // The tool isn't on the first page, and the backend keeps handing back
+    // the same cursor; the lookup gives up and the call still goes through
+    let response = tokio::time::timeout(
+        std::time::Duration::from_secs(5),
+        handler.handle_request(call_tool_request("unlisted", None)),
+    )
+    .await
+    .expect("tool lookup looped on the repeated cursor")
+    .unwrap();
+    assert!(response.error.is_none());
+    assert_eq!(backend.calls.lock().unwrap().len(), 1);
+}
+
+#[tokio::test]
+async fn test_unknown_tool_served_by_fallback() {
+    let backend = RecordingBackend {
+        fallback: true,
+        ..RecordingBackend::with_tool("local", serde_json::json!({"type": "object"}))
+    };
+    let handler = recording_handler(&backend);
+
+    let response = handler
+        .handle_request(call_tool_request(
+            "upstream_search",
+            Some(serde_json::json!({"query": "rust"})),
+        ))
+        .await
+        .unwrap();
+    assert!(response.error.is_none());
+    let result: CallToolResult = serde_json::from_value(response.result.unwrap()).unwrap();
+    match &result.content[0] {
+        Content::Text { text, .. } => {
+            assert_eq!(text, r#"proxied upstream_search with {"query":"rust"}"#)
+        }
+        _ => panic!("Expected text content"),
+    }
+    assert!(backend.calls.lock().unwrap().is_empty());
+
+    // Listed tools still go to `call_tool`
+    handler
+        .handle_request(call_tool_request("local", None))
+        .await
+        .unwrap();
+    assert_eq!(backend.calls.lock().unwrap().len(), 1);
+}
@@ -2,6 +2,8 @@
 
 use crate::McpServer;
 use crate::backend::McpBackend;
+use crate::build_info::BuildInfo;
+use crate::server::WarmupStatus;
 use axum::{
     Router,
     extract::State,
@@ -47,6 +49,15 @@ pub struct ReadyResponse {
     pub message: Option<String>,
 }
 
+/// Version response, with build metadata fields when configured
+#[derive(Debug, Serialize, Deserialize)]
+pub struct VersionResponse {
+    pub name: String,
+    pub version: String,
+    #[serde(flatten)]
+    pub build: BuildInfo,
+}
+
 /// Health check state
 pub struct HealthState<B: McpBackend> {
     pub server: Arc<McpServer<B>>,
@@ -120,6 +131,22 @@ pub async fn ready_handler<B: McpBackend + 'static>(
     // Check if server is running and ready to accept requests
     let is_running = state.server.is_running().await;
 
+    // Don't take traffic until the backend has warmed up
+    let warmup_pending = match state.server.warmup_status().await {
+        WarmupStatus::Complete => None,
+        WarmupStatus::Pending => Some("Backend warm-up in progress".to_string()),
+        WarmupStatus::Failed(e) => Some(format!("Backend warm-up failed: {e}")),
+    };
+    if is_running && let Some(message) = warmup_pending {
+        return (
+            StatusCode::SERVICE_UNAVAILABLE,
+            Json(ReadyResponse {
+                ready: false,
+                message: Some(message),
+            }),
+        );
+    }
+
     if is_running {
         // Additional readiness checks
         match state.server.health_check().await {
@@ -164,6 +191,18 @@ pub async fn ready_handler<B: McpBackend + 'static>(
     }
 }
 
+/// Handler for /version endpoint (deployment verification)
+pub async fn version_handler<B: McpBackend + 'static>(
+    State(state): State<Arc<HealthState<B>>>,
+) -> impl IntoResponse {
+    let implementation = &state.server.get_server_info().server_info;
+    Json(VersionResponse {
+        name: implementation.name.clone(),
+        version: implementation.version.clone(),
+        build: state.server.build_info().cloned().unwrap_or_default(),
+    })
+}
+
 /// Create health check router
 pub fn create_health_router<B: McpBackend + 'static>(server: Arc<McpServer<B>>) -> Router {
     let state = Arc::new(HealthState { server });
@@ -171,6 +210,7 @@ pub fn create_health_router<B: McpBackend + 'static>(server: Arc<McpServer<B>>)
     Router::new()
         .route("/health", get(health_handler::<B>))
         .route("/ready", get(ready_handler::<B>))
+        .route("/version", get(version_handler::<B>))
         .with_state(state)
 }
 
@@ -197,4 +237,28 @@ mod tests {
         assert!(json.contains("\"status\":\"healthy\""));
         assert!(json.contains("\"backend\""));
     }
+
+    #[test]
+    fn test_version_response_serialization() {
+        let response = VersionResponse {
+            name: "server".to_string(),
+            version: "1.0.0".to_string(),
+            build: BuildInfo::new().with_git_commit("abc123"),
+        };
+        let json = serde_json::to_value(&response).unwrap();
+        assert_eq!(
+            json,
+            serde_json::json!({"name": "server", "version": "1.0.0", "gitCommit": "abc123"})
+        );
+
+        let response = VersionResponse {
+            build: BuildInfo::default(),
+            ..response
+        };
+        let json = serde_json::to_value(&response).unwrap();
+        assert_eq!(
+            json,
+            serde_json::json!({"name": "server", "version": "1.0.0"})
+        );
+    }
 }
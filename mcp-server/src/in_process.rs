@@ -0,0 +1,207 @@
+//! In-process client for testing backends without a transport
+//!
+//! [`InProcessClient`] hands requests straight to a [`GenericServerHandler`]
+//! built the same way [`McpServer`] builds it, so they pass through the
+//! middleware stack, initialization checks, validation and resource
+//! authorization exactly as requests arriving over a socket would. Backend
+//! authors can then assert protocol-level behavior in ordinary async tests:
+//!
+//! ```rust,ignore
+//! let client = InProcessClient::builder(MyBackend::default()).build().await?;
+//! client.initialize().await?;
+//! let tools = client.list_tools().await?;
+//! let result = client.call_tool("echo", json!({"text": "hi"})).await?;
+//! ```
+
+use crate::backend::McpBackend;
+use crate::handler::GenericServerHandler;
+use crate::server::{McpServer, ServerConfig, ServerError};
+use pulseengine_auth::{AuthConfig, AuthContext};
+use pulseengine_mcp_protocol::*;
+use serde::de::DeserializeOwned;
+use serde_json::{Value, json};
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicI64, Ordering};
+
+/// Session id used when the builder isn't given one
+pub const IN_PROCESS_SESSION_ID: &str = "in-process";
+
+/// Builder for an [`InProcessClient`]
+pub struct InProcessClientBuilder<B: McpBackend> {
+    backend: B,
+    config: ServerConfig,
+    session_id: String,
+    auth: Option<AuthContext>,
+}
+
+impl<B: McpBackend + 'static> InProcessClientBuilder<B> {
+    /// Server configuration for the handler
+    ///
+    /// Defaults to `ServerConfig::default()` with in-memory auth storage, so
+    /// tests don't touch the key file in the home directory.
+    pub fn with_config(mut self, config: ServerConfig) -> Self {
+        self.config = config;
+        self
+    }
+
+    /// Session the client's requests belong to
+    pub fn with_session_id(mut self, session_id: impl Into<String>) -> Self {
+        self.session_id = session_id.into();
+        self
+    }
+
+    /// Send every request on behalf of this authenticated caller
+    pub fn with_auth_context(mut self, auth: AuthContext) -> Self {
+        self.auth = Some(auth);
+        self
+    }
+
+    /// Build the handler and client; no `initialize` request is sent
+    pub async fn build(self) -> std::result::Result<InProcessClient<B>, ServerError> {
+        let server = McpServer::new(self.backend, self.config).await?;
+        Ok(InProcessClient {
+            handler: server.handler().clone(),
+            session_id: self.session_id,
+            auth: self.auth,
+            next_id: AtomicI64::new(1),
+        })
+    }
+}
+
+/// Client that calls a server handler directly, without any socket
+pub struct InProcessClient<B: McpBackend> {
+    handler: GenericServerHandler<B>,
+    session_id: String,
+    auth: Option<AuthContext>,
+    next_id: AtomicI64,
+}
+
+impl<B: McpBackend + 'static> InProcessClient<B> {
+    /// Start building a client for `backend`
+    pub fn builder(backend: B) -> InProcessClientBuilder<B> {
+        InProcessClientBuilder {
+            backend,
+            config: ServerConfig {
+                auth_config: AuthConfig::memory(),
+                ..Default::default()
+            },
+            session_id: IN_PROCESS_SESSION_ID.to_string(),
+            auth: None,
+        }
+    }
+
+    /// The handler requests are sent to
+    pub fn handler(&self) -> &GenericServerHandler<B> {
+        &self.handler
+    }
+
+    /// Send a request and return its raw result, or the error the server
+    /// answered with
+    pub async fn request(&self, method: &str, params: Value) -> std::result::Result<Value, Error> {
+        let id = NumberOrString::Number(self.next_id.fetch_add(1, Ordering::Relaxed));
+        let response = self.send(method, params, Some(id)).await?;
+        match response.error {
+            Some(error) => Err(error),
+            None => Ok(response.result.unwrap_or(Value::Null)),
+        }
+    }
+
+    /// Send a notification; only errors raised before dispatch are returned
+    pub async fn notify(&self, method: &str, params: Value) -> std::result::Result<(), Error> {
+        self.send(method, params, None).await.map(|_| ())
+    }
+
+    /// Run the initialize handshake, including `notifications/initialized`
+    pub async fn initialize(&self) -> std::result::Result<InitializeResult, Error> {
+        let params = InitializeRequestParam {
+            protocol_version: MCP_VERSION.to_string(),
+            capabilities: json!({}),
+            client_info: Implementation::new("in-process-client", env!("CARGO_PKG_VERSION")),
+        };
+        let result = self
+            .typed("initialize", serde_json::to_value(params)?)
+            .await?;
+        self.notify("notifications/initialized", json!({})).await?;
+        Ok(result)
+    }
+
+    pub async fn ping(&self) -> std::result::Result<(), Error> {
+        self.request("ping", json!({})).await.map(|_| ())
+    }
+
+    pub async fn list_tools(&self) -> std::result::Result<ListToolsResult, Error> {
+        self.typed("tools/list", json!({})).await
+    }
+
+    pub async fn call_tool(
+        &self,
+        name: &str,
+        arguments: Value,
+    ) -> std::result::Result<CallToolResult, Error> {
+        let params = CallToolRequestParam {
+            name: name.to_string(),
+            arguments: (!arguments.is_null()).then_some(arguments),
+        };
+        self.typed("tools/call", serde_json::to_value(params)?)
+            .await
+    }
+
+    pub async fn list_resources(&self) -> std::result::Result<ListResourcesResult, Error> {
+        self.typed("resources/list", json!({})).await
+    }
+
+    pub async fn read_resource(&self, uri: &str) -> std::result::Result<ReadResourceResult, Error> {
+        self.typed("resources/read", json!({ "uri": uri })).await
+    }
+
+    pub async fn list_prompts(&self) -> std::result::Result<ListPromptsResult, Error> {
+        self.typed("prompts/list", json!({})).await
+    }
+
+    pub async fn get_prompt(
+        &self,
+        name: &str,
+        arguments: Option<HashMap<String, String>>,
+    ) -> std::result::Result<GetPromptResult, Error> {
+        let params = GetPromptRequestParam {
+            name: name.to_string(),
+            arguments,
+        };
+        self.typed("prompts/get", serde_json::to_value(params)?)
+            .await
+    }
+
+    async fn typed<T: DeserializeOwned>(
+        &self,
+        method: &str,
+        params: Value,
+    ) -> std::result::Result<T, Error> {
+        let result = self.request(method, params).await?;
+        serde_json::from_value(result).map_err(|e| {
+            Error::internal_error(format!("Unexpected {method} result from server: {e}"))
+        })
+    }
+
+    async fn send(
+        &self,
+        method: &str,
+        params: Value,
+        id: Option<NumberOrString>,
+    ) -> std::result::Result<Response, Error> {
+        let request = Request {
+            jsonrpc: "2.0".to_string(),
+            method: method.to_string(),
+            params,
+            id,
+        };
+        let handle = pulseengine_mcp_transport::with_session(
+            self.session_id.clone(),
+            self.handler.handle_request(request),
+        );
+        let response = match self.auth.clone() {
+            Some(auth) => crate::context::with_auth_context(auth, handle).await,
+            None => handle.await,
+        };
+        response.map_err(Into::into)
+    }
+}
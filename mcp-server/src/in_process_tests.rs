@@ -0,0 +1,269 @@
+//! Tests for the in-process client harness
+
+use crate::backend::{BackendError, McpBackend};
+use crate::in_process::*;
+use crate::server::ServerConfig;
+use async_trait::async_trait;
+use pulseengine_auth::{AuthConfig, AuthContext};
+use pulseengine_mcp_protocol::error::ErrorCode;
+use pulseengine_mcp_protocol::*;
+use serde_json::json;
+
+#[derive(Clone, Default)]
+struct NotesBackend;
+
+#[async_trait]
+impl McpBackend for NotesBackend {
+    type Error = BackendError;
+    type Config = ();
+
+    async fn initialize(_: Self::Config) -> std::result::Result<Self, Self::Error> {
+        Ok(Self)
+    }
+
+    fn get_server_info(&self) -> ServerInfo {
+        ServerInfo {
+            protocol_version: ProtocolVersion::default(),
+            capabilities: ServerCapabilities::builder()
+                .enable_tools()
+                .enable_resources()
+                .build(),
+            server_info: Implementation::new("notes-backend", "1.0.0"),
+            instructions: None,
+        }
+    }
+
+    async fn health_check(&self) -> std::result::Result<(), Self::Error> {
+        Ok(())
+    }
+
+    async fn list_tools(
+        &self,
+        _request: PaginatedRequestParam,
+    ) -> std::result::Result<ListToolsResult, Self::Error> {
+        Ok(ListToolsResult {
+            tools: vec![Tool {
+                name: "echo".to_string(),
+                description: "Echo the text back".to_string(),
+                input_schema: json!({
+                    "type": "object",
+                    "properties": {"text": {"type": "string"}},
+                    "required": ["text"]
+                }),
+                output_schema: None,
+                title: None,
+                annotations: None,
+                icons: None,
+                execution: None,
+                _meta: None,
+            }],
+            next_cursor: None,
+        })
+    }
+
+    async fn call_tool(
+        &self,
+        request: CallToolRequestParam,
+    ) -> std::result::Result<CallToolResult, Self::Error> {
+        let arguments = request.arguments.unwrap_or_default();
+        Ok(CallToolResult::text(
+            arguments["text"].as_str().unwrap_or_default(),
+        ))
+    }
+
+    async fn list_resources(
+        &self,
+        _request: PaginatedRequestParam,
+    ) -> std::result::Result<ListResourcesResult, Self::Error> {
+        Ok(ListResourcesResult {
+            resources: vec![],
+            next_cursor: None,
+        })
+    }
+
+    async fn read_resource(
+        &self,
+        request: ReadResourceRequestParam,
+    ) -> std::result::Result<ReadResourceResult, Self::Error> {
+        Ok(ReadResourceResult {
+            contents: vec![ResourceContents::text(&request.uri, "note body")],
+            _meta: None,
+        })
+    }
+
+    fn resource_permission(&self, uri: &str) -> Option<String> {
+        uri.starts_with("notes://private/")
+            .then(|| "resource:notes://private/*".to_string())
+    }
+
+    async fn list_prompts(
+        &self,
+        _request: PaginatedRequestParam,
+    ) -> std::result::Result<ListPromptsResult, Self::Error> {
+        Ok(ListPromptsResult {
+            prompts: vec![],
+            next_cursor: None,
+        })
+    }
+
+    async fn get_prompt(
+        &self,
+        request: GetPromptRequestParam,
+    ) -> std::result::Result<GetPromptResult, Self::Error> {
+        Err(BackendError::not_supported(format!(
+            "Prompt not found: {}",
+            request.name
+        )))
+    }
+}
+
+async fn client() -> InProcessClient<NotesBackend> {
+    InProcessClient::builder(NotesBackend)
+        .build()
+        .await
+        .unwrap()
+}
+
+#[tokio::test]
+async fn test_initialize_and_list_tools() {
+    let client = client().await;
+
+    let init = client.initialize().await.unwrap();
+    assert_eq!(init.server_info.name, "notes-backend");
+    assert!(init.capabilities.tools.is_some());
+
+    let tools = client.list_tools().await.unwrap();
+    assert_eq!(tools.tools.len(), 1);
+    assert_eq!(tools.tools[0].name, "echo");
+    client.ping().await.unwrap();
+}
+
+#[tokio::test]
+async fn test_call_tool_returns_typed_result() {
+    let client = client().await;
+    let result = client
+        .call_tool("echo", json!({"text": "hello"}))
+        .await
+        .unwrap();
+    match &result.content[0] {
+        Content::Text { text, .. } => assert_eq!(text, "hello"),
+        other => panic!("Expected text content, got {other:?}"),
+    }
+}
+
+#[tokio::test]
+async fn test_errors_come_back_as_protocol_errors() {
+    let client = client().await;
+
+    let error = client
+        .request("no/such/method", json!({}))
+        .await
+        .unwrap_err();
+    assert_eq!(error.code, ErrorCode::MethodNotFound);
+
+    let error = client.get_prompt("missing", None).await.unwrap_err();
+    assert!(error.message.contains("missing"), "{}", error.message);
+}
+
+#[tokio::test]
+async fn test_config_applies_to_pipeline() {
+    let client = InProcessClient::builder(NotesBackend)
+        .with_config(ServerConfig {
+            auth_config: AuthConfig::memory(),
+            require_initialization: true,
+            ..Default::default()
+        })
+        .build()
+        .await
+        .unwrap();
+
+    let error = client.list_tools().await.unwrap_err();
+    assert_eq!(error.code, ErrorCode::InvalidRequest);
+
+    client.initialize().await.unwrap();
+    assert!(client.list_tools().await.is_ok());
+}
+
+#[tokio::test]
+async fn test_config_sets_initialized_strictness() {
+    let client = InProcessClient::builder(NotesBackend)
+        .with_config(ServerConfig {
+            auth_config: AuthConfig::memory(),
+            initialized_strictness: crate::InitializedStrictness::Reject,
+            ..Default::default()
+        })
+        .build()
+        .await
+        .unwrap();
+
+    // `initialize` without the follow-up `notifications/initialized`
+    let params = json!({
+        "protocolVersion": MCP_VERSION,
+        "capabilities": {},
+        "clientInfo": {"name": "test", "version": "1.0"}
+    });
+    client.request("initialize", params).await.unwrap();
+    let error = client.list_tools().await.unwrap_err();
+    assert_eq!(error.code, ErrorCode::InvalidRequest);
+
+    client
+        .notify("notifications/initialized", json!({}))
+        .await
+        .unwrap();
+    assert!(client.list_tools().await.is_ok());
+}
+
+#[tokio::test]
+async fn test_resource_permissions_follow_auth_context() {
+    let anonymous = client().await;
+    let error = anonymous
+        .read_resource("notes://private/1")
+        .await
+        .unwrap_err();
+    assert_eq!(error.code, ErrorCode::Forbidden);
+    assert!(anonymous.read_resource("notes://public/1").await.is_ok());
+
+    let authorized = InProcessClient::builder(NotesBackend)
+        .with_auth_context(AuthContext {
+            user_id: Some("alice".to_string()),
+            roles: vec![],
+            api_key_id: None,
+            permissions: vec!["resource:notes://private/*".to_string()],
+        })
+        .build()
+        .await
+        .unwrap();
+    let result = authorized.read_resource("notes://private/1").await.unwrap();
+    assert_eq!(result.contents[0].uri, "notes://private/1");
+}
+
+#[tokio::test]
+async fn test_requests_run_in_the_client_session() {
+    let client = InProcessClient::builder(NotesBackend)
+        .with_config(ServerConfig {
+            auth_config: AuthConfig::memory(),
+            require_initialization: true,
+            ..Default::default()
+        })
+        .with_session_id("first")
+        .build()
+        .await
+        .unwrap();
+    client.initialize().await.unwrap();
+    assert!(client.list_tools().await.is_ok());
+
+    // Another session on the same handler still has to initialize
+    let request = Request {
+        jsonrpc: "2.0".to_string(),
+        method: "tools/list".to_string(),
+        params: json!({}),
+        id: Some(NumberOrString::Number(1)),
+    };
+    let response = pulseengine_mcp_transport::with_session(
+        "second".to_string(),
+        client.handler().handle_request(request),
+    )
+    .await
+    .unwrap();
+    assert_eq!(response.error.unwrap().code, ErrorCode::InvalidRequest);
+}
@@ -122,16 +122,38 @@
 //!
 
 pub mod builder_trait;
+pub mod cancellation;
+pub mod capability_filter;
 pub mod cli_helpers;
+pub mod client_policy;
 pub mod common_backend;
+pub mod concurrency;
 pub mod observability;
+pub mod protocol_session;
+pub mod rate_limit;
+pub mod replay_protection;
+pub mod resource_access;
+pub mod resource_compression;
+pub mod resource_subscriptions;
+pub mod result_transform;
 pub mod tool_context;
+pub mod tool_pagination;
 
+pub mod adaptive_timeout;
 pub mod backend;
+pub mod backend_ext;
+pub mod build_info;
 pub mod context;
+pub mod gather;
 pub mod handler;
+pub mod in_process;
+pub mod load_shedding;
+pub mod memory_guard;
 pub mod middleware;
+pub mod namespace;
+pub mod notification_retry;
 pub mod server;
+pub mod verbosity;
 
 // Endpoint modules
 pub mod alerting_endpoint;
@@ -141,41 +163,123 @@ pub mod metrics_endpoint;
 
 // Test modules
 #[cfg(test)]
+mod adaptive_timeout_tests;
+#[cfg(test)]
+mod backend_ext_tests;
+#[cfg(test)]
 mod backend_tests;
 #[cfg(test)]
+mod build_info_tests;
+#[cfg(test)]
+mod client_policy_tests;
+#[cfg(test)]
+mod concurrency_tests;
+#[cfg(test)]
 mod context_tests;
 #[cfg(test)]
+mod gather_tests;
+#[cfg(test)]
 mod handler_tests;
 #[cfg(test)]
+mod in_process_tests;
+#[cfg(test)]
 mod lib_tests;
 #[cfg(test)]
+mod load_shedding_tests;
+#[cfg(test)]
+mod memory_guard_tests;
+#[cfg(test)]
 mod middleware_tests;
 #[cfg(test)]
+mod namespace_tests;
+#[cfg(test)]
+mod notification_retry_tests;
+#[cfg(test)]
+mod protocol_session_tests;
+#[cfg(test)]
+mod rate_limit_tests;
+#[cfg(test)]
+mod replay_protection_tests;
+#[cfg(test)]
+mod resource_access_tests;
+#[cfg(test)]
+mod resource_compression_tests;
+#[cfg(test)]
+mod resource_subscriptions_tests;
+#[cfg(test)]
+mod result_transform_tests;
+#[cfg(test)]
 mod server_tests;
 #[cfg(test)]
 mod tool_context_tests;
+#[cfg(test)]
+mod tool_pagination_tests;
+#[cfg(test)]
+mod verbosity_tests;
 
 // Re-export core types
-pub use backend::{BackendError, McpBackend};
+pub use adaptive_timeout::AdaptiveTimeoutConfig;
+pub use backend::{BackendError, McpBackend, ToolContentStream};
+pub use backend_ext::{
+    BackendExt, CachedBackend, DEFAULT_CACHE_MAX_ENTRIES, LoggingBackend, MapErrorBackend,
+};
+pub use build_info::BuildInfo;
 pub use builder_trait::{McpServerBuilder, McpService};
+pub use cancellation::{CANCELLED_NOTIFICATION_METHOD, InFlightRequest, InFlightRequests};
+pub use capability_filter::CapabilityFilterConfig;
+pub use client_policy::{ClientPolicy, ClientRule};
 pub use common_backend::{
     CommonBackendImpl, CommonMcpError, HasServerInfo, McpPromptsProvider, McpResourcesProvider,
     McpToolsProvider,
 };
-pub use context::RequestContext;
-pub use handler::{GenericServerHandler, HandlerError};
+pub use concurrency::{ConcurrencyConfig, ConcurrencyPermit, FairConcurrencyLimiter};
+pub use context::{
+    ProgressReporter, RequestContext, try_current_auth_context, try_current_request_context,
+    with_auth_context, with_request_context,
+};
+pub use gather::{Gathered, gather_with_deadline};
+pub use handler::{
+    DEFAULT_BATCH_CONCURRENCY, GenericServerHandler, HandlerError, INITIALIZED_NOTIFICATION_METHOD,
+    TOOL_RESULT_CHUNK_METHOD,
+};
+pub use in_process::{IN_PROCESS_SESSION_ID, InProcessClient, InProcessClientBuilder};
+pub use load_shedding::{
+    BackendHealth, HealthSignal, LoadShedder, LoadSheddingConfig, RequestPriority,
+};
+pub use memory_guard::{MemoryBudgetExceeded, MemoryGuard, MemoryGuardConfig, process_rss_bytes};
 pub use middleware::{Middleware, MiddlewareStack};
-pub use server::{McpServer, ServerConfig, ServerError};
+pub use namespace::ProviderRegistry;
+pub use notification_retry::{
+    NotificationDelivery, NotificationDeliveryStats, NotificationRetryConfig,
+};
+pub use protocol_session::{
+    ConcurrentInitialize, InitializeGuard, InitializedStrictness, ProtocolSession,
+    ProtocolSessionStats, ProtocolSessions,
+};
+pub use rate_limit::{GlobalRateLimitConfig, GlobalRateLimiter};
+pub use replay_protection::{ReplayGuard, ReplayProtectionConfig};
+pub use resource_access::ResourceAccessPolicy;
+pub use resource_compression::ResourceCompressionConfig;
+pub use resource_subscriptions::{RESOURCE_UPDATED_METHOD, ResourceSubscriptionManager};
+pub use result_transform::{ResultTransform, ResultTransformPipeline};
+pub use server::{McpServer, ServerConfig, ServerError, WarmupMode, WarmupStatus};
 pub use tool_context::{
     CreateMessageRequest, CreateMessageResult, DefaultToolContext, ElicitationAction,
     ElicitationRequest, ElicitationResult, IncludeContext, LogNotificationParams, ModelHint,
     ModelPreferences, NoOpToolContext, NotificationSender, ProgressNotificationParams,
     RequestSender, SamplingContent, SamplingMessage, SamplingRole, ToolContext, ToolContextError,
-    TransportBridge, create_tool_context, current_context, try_current_context, with_context,
+    TransportBridge, create_signed_tool_context, create_tool_context, current_context,
+    try_current_context, with_context,
 };
+pub use tool_pagination::{
+    CURSOR_ARGUMENT, NEXT_CURSOR_META_KEY, ToolResultPaginator, cursor_argument, next_cursor,
+};
+pub use verbosity::ListVerbosity;
 
 // Re-export CLI helpers
-pub use cli_helpers::{CliError, DefaultLoggingConfig, LogFormat, LogOutput, create_server_info};
+pub use cli_helpers::{
+    CliError, ConfigValidator, DefaultLoggingConfig, LogFormat, LogOutput, create_server_info,
+};
 
 // Re-export from dependencies for convenience
 pub use pulseengine_auth::{self as auth, AuthConfig, AuthenticationManager};
@@ -184,7 +288,10 @@ pub use pulseengine_mcp_security::{self as security, SecurityConfig, SecurityMid
 pub use pulseengine_mcp_transport::{self as transport, Transport, TransportConfig};
 
 // Re-export observability (merged from mcp-monitoring)
-pub use observability::{MetricsCollector, MonitoringConfig, ServerMetrics, SystemMetrics};
+pub use observability::{
+    AnalyticsConfig, MetricsCollector, MonitoringConfig, ServerMetrics, StatsdConfig,
+    StatsdExporter, SystemMetrics, ToolUsageAnalytics,
+};
 /// Alias for backward compatibility
 pub mod monitoring {
     pub use super::observability::*;
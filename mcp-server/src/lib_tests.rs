@@ -150,6 +150,7 @@ impl McpBackend for IntegrationTestBackend {
                 }],
                 is_error: Some(false),
                 structured_content: None,
+                structured_content_blocks: None,
                 _meta: None,
             })
         } else {
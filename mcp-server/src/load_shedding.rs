@@ -0,0 +1,184 @@
+//! Health-aware load shedding
+//!
+//! While a dependency is slow, queueing every request behind it only deepens
+//! the outage. A [`HealthSignal`] carries the backend's current health, set
+//! by whatever monitors it. When the signal reports degraded or unhealthy,
+//! [`LoadShedder`] rejects lower-priority requests up front with a busy
+//! error, while `ping` and other critical traffic keeps flowing.
+
+use pulseengine_mcp_protocol::{Error, ErrorCode};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU8, Ordering};
+use tracing::warn;
+
+/// Backend health as reported through a [`HealthSignal`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum BackendHealth {
+    Healthy,
+    /// Working, but slow or partially failing
+    Degraded,
+    Unhealthy,
+}
+
+impl fmt::Display for BackendHealth {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Healthy => "healthy",
+            Self::Degraded => "degraded",
+            Self::Unhealthy => "unhealthy",
+        })
+    }
+}
+
+/// How important a request is to keep serving under load
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RequestPriority {
+    Low,
+    Normal,
+    High,
+    /// Never shed
+    Critical,
+}
+
+impl fmt::Display for RequestPriority {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Low => "low",
+            Self::Normal => "normal",
+            Self::High => "high",
+            Self::Critical => "critical",
+        })
+    }
+}
+
+/// Current backend health, shared between the handler and whatever updates it
+#[derive(Debug, Clone, Default)]
+pub struct HealthSignal {
+    health: Arc<AtomicU8>,
+}
+
+impl HealthSignal {
+    /// Create a signal that starts out healthy
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn set(&self, health: BackendHealth) {
+        self.health.store(health as u8, Ordering::Relaxed);
+    }
+
+    pub fn get(&self) -> BackendHealth {
+        match self.health.load(Ordering::Relaxed) {
+            0 => BackendHealth::Healthy,
+            1 => BackendHealth::Degraded,
+            _ => BackendHealth::Unhealthy,
+        }
+    }
+}
+
+/// Which requests to shed at each level of degraded health
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LoadSheddingConfig {
+    /// Requests below this priority are rejected while degraded
+    pub degraded_min_priority: RequestPriority,
+    /// Requests below this priority are rejected while unhealthy
+    pub unhealthy_min_priority: RequestPriority,
+    /// Priority of methods missing from `method_priorities`
+    pub default_priority: RequestPriority,
+    /// Priority by JSON-RPC method name
+    pub method_priorities: HashMap<String, RequestPriority>,
+    /// Retry delay suggested to clients whose request was shed
+    pub retry_after_ms: u64,
+}
+
+impl Default for LoadSheddingConfig {
+    fn default() -> Self {
+        let method_priorities = [
+            ("ping", RequestPriority::Critical),
+            ("notifications/cancelled", RequestPriority::Critical),
+            ("initialize", RequestPriority::High),
+            ("notifications/initialized", RequestPriority::High),
+            ("resources/unsubscribe", RequestPriority::High),
+            ("logging/setLevel", RequestPriority::High),
+            ("resources/subscribe", RequestPriority::Low),
+            ("completion/complete", RequestPriority::Low),
+        ]
+        .into_iter()
+        .map(|(method, priority)| (method.to_string(), priority))
+        .collect();
+
+        Self {
+            degraded_min_priority: RequestPriority::Normal,
+            unhealthy_min_priority: RequestPriority::High,
+            default_priority: RequestPriority::Normal,
+            method_priorities,
+            retry_after_ms: 1000,
+        }
+    }
+}
+
+/// Rejects low-priority requests while the [`HealthSignal`] reports trouble
+#[derive(Debug, Clone)]
+pub struct LoadShedder {
+    config: Arc<LoadSheddingConfig>,
+    signal: HealthSignal,
+}
+
+impl LoadShedder {
+    pub fn new(config: LoadSheddingConfig, signal: HealthSignal) -> Self {
+        Self {
+            config: Arc::new(config),
+            signal,
+        }
+    }
+
+    pub fn config(&self) -> &LoadSheddingConfig {
+        &self.config
+    }
+
+    /// The health signal this shedder consults
+    pub fn signal(&self) -> &HealthSignal {
+        &self.signal
+    }
+
+    /// Priority assigned to a method
+    pub fn priority(&self, method: &str) -> RequestPriority {
+        self.config
+            .method_priorities
+            .get(method)
+            .copied()
+            .unwrap_or(self.config.default_priority)
+    }
+
+    /// Check whether a request for `method` may proceed at the current health
+    ///
+    /// Shed requests get an [`ErrorCode::ServerBusy`] error carrying `retryAfterMs`, the current health and the priority.
+    pub fn check(&self, method: &str) -> Result<(), Error> {
+        let health = self.signal.get();
+        let min_priority = match health {
+            BackendHealth::Healthy => return Ok(()),
+            BackendHealth::Degraded => self.config.degraded_min_priority,
+            BackendHealth::Unhealthy => self.config.unhealthy_min_priority,
+        };
+        let priority = self.priority(method);
+        if priority == RequestPriority::Critical || priority >= min_priority {
+            return Ok(());
+        }
+
+        warn!(method, %health, %priority, "Shedding request");
+        Err(Error::with_data(
+            ErrorCode::ServerBusy,
+            format!("Server is {health}; {method} requests are temporarily rejected"),
+            serde_json::json!({
+                "retryAfterMs": self.config.retry_after_ms,
+                "health": health,
+                "priority": priority,
+            }),
+        ))
+    }
+}
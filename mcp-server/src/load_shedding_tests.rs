@@ -0,0 +1,73 @@
+//! Tests for health-aware load shedding
+
+use crate::load_shedding::*;
+use pulseengine_mcp_protocol::ErrorCode;
+
+fn shedder() -> (LoadShedder, HealthSignal) {
+    let signal = HealthSignal::new();
+    (
+        LoadShedder::new(LoadSheddingConfig::default(), signal.clone()),
+        signal,
+    )
+}
+
+#[test]
+fn test_nothing_shed_while_healthy() {
+    let (shedder, signal) = shedder();
+    assert_eq!(signal.get(), BackendHealth::Healthy);
+    for method in ["completion/complete", "tools/call", "ping"] {
+        assert!(shedder.check(method).is_ok());
+    }
+}
+
+#[test]
+fn test_degraded_sheds_low_priority_only() {
+    let (shedder, signal) = shedder();
+    signal.set(BackendHealth::Degraded);
+
+    let error = shedder.check("completion/complete").unwrap_err();
+    assert_eq!(error.code, ErrorCode::ServerBusy);
+    let data = error.data.unwrap();
+    assert_eq!(data["retryAfterMs"], 1000);
+    assert_eq!(data["health"], "degraded");
+    assert_eq!(data["priority"], "low");
+
+    assert!(shedder.check("tools/call").is_ok());
+    assert!(shedder.check("initialize").is_ok());
+    assert!(shedder.check("ping").is_ok());
+}
+
+#[test]
+fn test_unhealthy_sheds_up_to_configured_threshold() {
+    let (shedder, signal) = shedder();
+    signal.set(BackendHealth::Unhealthy);
+
+    assert!(shedder.check("tools/call").is_err());
+    assert!(shedder.check("some/custom").is_err());
+    assert!(shedder.check("initialize").is_ok());
+    assert!(shedder.check("ping").is_ok());
+
+    signal.set(BackendHealth::Healthy);
+    assert!(shedder.check("tools/call").is_ok());
+}
+
+#[test]
+fn test_thresholds_and_priorities_configurable() {
+    let signal = HealthSignal::new();
+    let mut config = LoadSheddingConfig {
+        degraded_min_priority: RequestPriority::Critical,
+        ..Default::default()
+    };
+    config
+        .method_priorities
+        .insert("tools/call".to_string(), RequestPriority::Critical);
+    let shedder = LoadShedder::new(config, signal.clone());
+    signal.set(BackendHealth::Degraded);
+
+    // Critical requests always pass, everything else is shed
+    assert_eq!(shedder.priority("tools/call"), RequestPriority::Critical);
+    assert!(shedder.check("tools/call").is_ok());
+    assert!(shedder.check("ping").is_ok());
+    assert!(shedder.check("initialize").is_err());
+    assert!(shedder.check("resources/read").is_err());
+}
@@ -0,0 +1,166 @@
+//! Best-effort memory budgets for tool execution
+//!
+//! Backends can hint a soft memory budget per tool through
+//! [`McpBackend::tool_memory_budget`](crate::McpBackend::tool_memory_budget).
+//! While such a tool runs, the guard periodically samples the process
+//! resident set size and aborts the call once RSS has grown past the budget
+//! since the tool started.
+//!
+//! This is advisory: RSS is process-wide, so concurrent requests count against
+//! whichever tool is being watched, and a tool is only aborted at its next
+//! `.await` point. It protects against tools that are clearly over budget, not
+//! against precise overuse.
+
+use pulseengine_mcp_protocol::{Error, ErrorCode};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::future::Future;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::time::MissedTickBehavior;
+use tracing::warn;
+
+/// Shortest interval at which RSS is sampled; smaller intervals are raised to it
+pub const MIN_CHECK_INTERVAL: Duration = Duration::from_millis(1);
+
+/// Configuration for tool memory budget enforcement
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MemoryGuardConfig {
+    /// How often RSS is sampled while a budgeted tool runs (at least
+    /// [`MIN_CHECK_INTERVAL`])
+    pub check_interval: Duration,
+}
+
+impl Default for MemoryGuardConfig {
+    fn default() -> Self {
+        Self {
+            check_interval: Duration::from_millis(100),
+        }
+    }
+}
+
+/// Source of memory usage samples in bytes, `None` when unavailable
+pub type MemorySampler = Arc<dyn Fn() -> Option<u64> + Send + Sync>;
+
+/// Resident set size of the current process in bytes
+///
+/// Read from `/proc/self/status`; returns `None` on platforms without procfs.
+pub fn process_rss_bytes() -> Option<u64> {
+    let status = std::fs::read_to_string("/proc/self/status").ok()?;
+    let line = status.lines().find(|line| line.starts_with("VmRSS:"))?;
+    let kib: u64 = line.split_whitespace().nth(1)?.parse().ok()?;
+    Some(kib * 1024)
+}
+
+/// A tool grew memory usage past its hinted budget
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MemoryBudgetExceeded {
+    pub tool: String,
+    pub budget_bytes: u64,
+    pub used_bytes: u64,
+}
+
+impl fmt::Display for MemoryBudgetExceeded {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Tool '{}' aborted: memory grew by {} bytes, exceeding its budget of {} bytes",
+            self.tool, self.used_bytes, self.budget_bytes
+        )
+    }
+}
+
+impl std::error::Error for MemoryBudgetExceeded {}
+
+impl From<MemoryBudgetExceeded> for Error {
+    fn from(err: MemoryBudgetExceeded) -> Self {
+        Error::with_data(
+            ErrorCode::InternalError,
+            err.to_string(),
+            serde_json::json!({
+                "tool": err.tool,
+                "budgetBytes": err.budget_bytes,
+                "usedBytes": err.used_bytes,
+            }),
+        )
+    }
+}
+
+/// Watches memory growth of running tools against their budgets
+#[derive(Clone)]
+pub struct MemoryGuard {
+    config: MemoryGuardConfig,
+    sampler: MemorySampler,
+}
+
+impl MemoryGuard {
+    /// Create a guard that samples the process RSS
+    pub fn new(config: MemoryGuardConfig) -> Self {
+        Self {
+            config,
+            sampler: Arc::new(process_rss_bytes),
+        }
+    }
+
+    /// Replace the memory source, e.g. with allocator statistics
+    pub fn with_sampler(
+        mut self,
+        sampler: impl Fn() -> Option<u64> + Send + Sync + 'static,
+    ) -> Self {
+        self.sampler = Arc::new(sampler);
+        self
+    }
+
+    /// Run a tool future, aborting it if memory grows past `budget_bytes`
+    ///
+    /// Runs the future unguarded when no memory sample is available.
+    pub async fn run<F: Future>(
+        &self,
+        tool: &str,
+        budget_bytes: u64,
+        future: F,
+    ) -> Result<F::Output, MemoryBudgetExceeded> {
+        let Some(baseline) = self.sample().await else {
+            return Ok(future.await);
+        };
+        let check_interval = self.config.check_interval.max(MIN_CHECK_INTERVAL);
+        let start = tokio::time::Instant::now() + check_interval;
+        let mut ticker = tokio::time::interval_at(start, check_interval);
+        ticker.set_missed_tick_behavior(MissedTickBehavior::Delay);
+        tokio::pin!(future);
+
+        loop {
+            tokio::select! {
+                biased;
+                output = &mut future => return Ok(output),
+                _ = ticker.tick() => {
+                    let Some(current) = self.sample().await else { continue };
+                    let used_bytes = current.saturating_sub(baseline);
+                    if used_bytes > budget_bytes {
+                        warn!(
+                            tool = %tool,
+                            budget_bytes,
+                            used_bytes,
+                            "Aborting tool call over its memory budget"
+                        );
+                        return Err(MemoryBudgetExceeded {
+                            tool: tool.to_string(),
+                            budget_bytes,
+                            used_bytes,
+                        });
+                    }
+                }
+            }
+        }
+    }
+
+    /// Take a memory sample off the async runtime, since samplers such as
+    /// [`process_rss_bytes`] do blocking reads
+    async fn sample(&self) -> Option<u64> {
+        let sampler = self.sampler.clone();
+        tokio::task::spawn_blocking(move || sampler())
+            .await
+            .ok()
+            .flatten()
+    }
+}
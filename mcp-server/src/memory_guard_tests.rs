@@ -0,0 +1,117 @@
+//! Tests for best-effort tool memory budgets
+
+use crate::memory_guard::*;
+use pulseengine_mcp_protocol::{Error, ErrorCode};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Duration;
+
+const MIB: u64 = 1024 * 1024;
+
+fn fast_config() -> MemoryGuardConfig {
+    MemoryGuardConfig {
+        check_interval: Duration::from_millis(5),
+    }
+}
+
+fn guard_with_counter(usage: &Arc<AtomicU64>) -> MemoryGuard {
+    let usage = usage.clone();
+    MemoryGuard::new(fast_config()).with_sampler(move || Some(usage.load(Ordering::SeqCst)))
+}
+
+#[tokio::test]
+async fn test_tool_over_budget_is_aborted() {
+    let usage = Arc::new(AtomicU64::new(100 * MIB));
+    let guard = guard_with_counter(&usage);
+
+    let tool_usage = usage.clone();
+    let result = guard
+        .run("profile", 10 * MIB, async move {
+            tool_usage.fetch_add(50 * MIB, Ordering::SeqCst);
+            tokio::time::sleep(Duration::from_secs(30)).await;
+            "finished"
+        })
+        .await;
+
+    let exceeded = result.unwrap_err();
+    assert_eq!(exceeded.tool, "profile");
+    assert_eq!(exceeded.budget_bytes, 10 * MIB);
+    assert_eq!(exceeded.used_bytes, 50 * MIB);
+
+    let error: Error = exceeded.into();
+    assert_eq!(error.code, ErrorCode::InternalError);
+    assert!(error.message.contains("Tool 'profile' aborted"));
+    assert!(error.message.contains("exceeding its budget"));
+    assert_eq!(error.data.unwrap()["budgetBytes"], 10 * MIB);
+}
+
+#[tokio::test]
+async fn test_tool_within_budget_completes() {
+    let usage = Arc::new(AtomicU64::new(100 * MIB));
+    let guard = guard_with_counter(&usage);
+
+    let tool_usage = usage.clone();
+    let result = guard
+        .run("small", 10 * MIB, async move {
+            tool_usage.fetch_add(MIB, Ordering::SeqCst);
+            tokio::time::sleep(Duration::from_millis(30)).await;
+            "finished"
+        })
+        .await;
+
+    assert_eq!(result.unwrap(), "finished");
+}
+
+#[tokio::test]
+async fn test_unavailable_sampler_runs_unguarded() {
+    let guard = MemoryGuard::new(fast_config()).with_sampler(|| None);
+
+    let result = guard
+        .run("any", 0, async {
+            tokio::time::sleep(Duration::from_millis(20)).await;
+            42
+        })
+        .await;
+
+    assert_eq!(result.unwrap(), 42);
+}
+
+#[tokio::test]
+async fn test_zero_check_interval_is_clamped() {
+    let usage = Arc::new(AtomicU64::new(100 * MIB));
+    let guard = MemoryGuard::new(MemoryGuardConfig {
+        check_interval: Duration::ZERO,
+    })
+    .with_sampler({
+        let usage = usage.clone();
+        move || Some(usage.load(Ordering::SeqCst))
+    });
+
+    let tool_usage = usage.clone();
+    let result = guard
+        .run("profile", 10 * MIB, async move {
+            tool_usage.fetch_add(50 * MIB, Ordering::SeqCst);
+            tokio::time::sleep(Duration::from_secs(30)).await;
+        })
+        .await;
+
+    assert_eq!(result.unwrap_err().used_bytes, 50 * MIB);
+}
+
+#[cfg(target_os = "linux")]
+#[tokio::test]
+async fn test_real_allocation_exceeds_budget() {
+    assert!(process_rss_bytes().is_some());
+    let guard = MemoryGuard::new(fast_config());
+
+    let result = guard
+        .run("allocate", 4 * MIB, async {
+            // Touch every page so the allocation is resident
+            let buffer = std::hint::black_box(vec![1u8; (128 * MIB) as usize]);
+            tokio::time::sleep(Duration::from_secs(30)).await;
+            buffer.len()
+        })
+        .await;
+
+    assert!(result.unwrap_err().used_bytes > 4 * MIB);
+}
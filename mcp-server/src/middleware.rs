@@ -1,7 +1,22 @@
 //! Middleware stack for request/response processing
+//!
+//! The stack wraps request handling in layers, onion style: requests pass
+//! through the layers outermost first and responses come back through them
+//! in reverse. From the outside in, the layers are:
+//!
+//! 1. the global rate limit, so floods are rejected before any other work
+//! 2. replay protection, so replayed requests are never processed
+//! 3. security validation
+//! 4. custom [`Middleware`] added with [`MiddlewareStack::with_middleware`],
+//!    ordered by [`Middleware::priority`] and then by registration
+//! 5. monitoring, closest to the handler so it measures the handler alone
+//!
+//! Authentication happens before any of these, at the transport layer.
 
 use crate::context::RequestContext;
 use crate::observability::MetricsCollector;
+use crate::rate_limit::GlobalRateLimiter;
+use crate::replay_protection::ReplayGuard;
 use pulseengine_auth::AuthenticationManager;
 use pulseengine_mcp_protocol::*;
 use pulseengine_mcp_security::SecurityMiddleware;
@@ -11,28 +26,46 @@ use std::sync::Arc;
 use tracing::debug;
 
 /// Trait for middleware components
+///
+/// Both hooks pass their input through unchanged by default, so a
+/// middleware only implements the side it cares about.
 #[async_trait]
 pub trait Middleware: Send + Sync {
-    /// Process incoming request
-    async fn process_request(
+    /// Inspect or rewrite an incoming request; an error rejects it
+    async fn on_request(
         &self,
         request: Request,
         context: &RequestContext,
-    ) -> std::result::Result<Request, Error>;
+    ) -> std::result::Result<Request, Error> {
+        let _ = context;
+        Ok(request)
+    }
 
-    /// Process outgoing response
-    async fn process_response(
+    /// Inspect or rewrite an outgoing response
+    async fn on_response(
         &self,
         response: Response,
         context: &RequestContext,
-    ) -> std::result::Result<Response, Error>;
+    ) -> std::result::Result<Response, Error> {
+        let _ = context;
+        Ok(response)
+    }
+
+    /// Position in the stack; lower values run earlier on requests and later
+    /// on responses. Middleware with equal priority keep registration order.
+    fn priority(&self) -> i32 {
+        0
+    }
 }
 
 /// Stack of middleware components
 #[derive(Clone)]
 pub struct MiddlewareStack {
+    rate_limit: Option<GlobalRateLimiter>,
+    replay_protection: Option<ReplayGuard>,
     security: Option<SecurityMiddleware>,
     auth: Option<Arc<AuthenticationManager>>,
+    custom: Vec<Arc<dyn Middleware>>,
     monitoring: Option<Arc<MetricsCollector>>,
 }
 
@@ -40,12 +73,28 @@ impl MiddlewareStack {
     /// Create a new middleware stack
     pub fn new() -> Self {
         Self {
+            rate_limit: None,
+            replay_protection: None,
             security: None,
             auth: None,
+            custom: Vec::new(),
             monitoring: None,
         }
     }
 
+    /// Add a server-wide request rate limit, enforced whether or not auth is
+    /// enabled
+    pub fn with_rate_limit(mut self, rate_limit: GlobalRateLimiter) -> Self {
+        self.rate_limit = Some(rate_limit);
+        self
+    }
+
+    /// Reject requests with reused nonces or stale timestamps
+    pub fn with_replay_protection(mut self, guard: ReplayGuard) -> Self {
+        self.replay_protection = Some(guard);
+        self
+    }
+
     /// Add security middleware
     pub fn with_security(mut self, security: SecurityMiddleware) -> Self {
         self.security = Some(security);
@@ -64,6 +113,16 @@ impl MiddlewareStack {
         self
     }
 
+    /// Add a custom middleware, placed by its [`priority`](Middleware::priority)
+    pub fn with_middleware(mut self, middleware: Arc<dyn Middleware>) -> Self {
+        let priority = middleware.priority();
+        let position = self
+            .custom
+            .partition_point(|existing| existing.priority() <= priority);
+        self.custom.insert(position, middleware);
+        self
+    }
+
     /// Process request through middleware stack
     pub async fn process_request(
         &self,
@@ -72,7 +131,14 @@ impl MiddlewareStack {
     ) -> std::result::Result<Request, crate::handler::HandlerError> {
         debug!("Processing request through middleware stack");
 
-        // Security middleware (first)
+        if let Some(rate_limit) = &self.rate_limit {
+            request = rate_limit.on_request(request, context).await?;
+        }
+
+        if let Some(replay_protection) = &self.replay_protection {
+            request = replay_protection.on_request(request, context).await?;
+        }
+
         if let Some(security) = &self.security {
             let sec_context = pulseengine_mcp_security::middleware::RequestContext {
                 request_id: context.request_id,
@@ -83,7 +149,10 @@ impl MiddlewareStack {
         // Authentication is handled at the transport layer via pulseengine_auth.
         // The AuthenticationManager is stored for downstream access (e.g., key validation).
 
-        // Monitoring middleware (last)
+        for middleware in &self.custom {
+            request = middleware.on_request(request, context).await?;
+        }
+
         if let Some(monitoring) = &self.monitoring {
             let mon_context = crate::observability::collector::RequestContext {
                 request_id: context.request_id,
@@ -102,7 +171,6 @@ impl MiddlewareStack {
     ) -> std::result::Result<Response, crate::handler::HandlerError> {
         debug!("Processing response through middleware stack");
 
-        // Monitoring middleware (first on response)
         if let Some(monitoring) = &self.monitoring {
             let mon_context = crate::observability::collector::RequestContext {
                 request_id: context.request_id,
@@ -110,7 +178,10 @@ impl MiddlewareStack {
             response = monitoring.process_response(response, &mon_context)?;
         }
 
-        // Security middleware (last on response)
+        for middleware in self.custom.iter().rev() {
+            response = middleware.on_response(response, context).await?;
+        }
+
         if let Some(security) = &self.security {
             let sec_context = pulseengine_mcp_security::middleware::RequestContext {
                 request_id: context.request_id,
@@ -118,6 +189,14 @@ impl MiddlewareStack {
             response = security.process_response(response, &sec_context)?;
         }
 
+        if let Some(replay_protection) = &self.replay_protection {
+            response = replay_protection.on_response(response, context).await?;
+        }
+
+        if let Some(rate_limit) = &self.rate_limit {
+            response = rate_limit.on_response(response, context).await?;
+        }
+
         Ok(response)
     }
 }
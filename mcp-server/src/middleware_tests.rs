@@ -303,7 +303,7 @@ struct MockMiddleware {
 
 #[async_trait]
 impl Middleware for MockMiddleware {
-    async fn process_request(
+    async fn on_request(
         &self,
         request: Request,
         _context: &RequestContext,
@@ -315,7 +315,7 @@ impl Middleware for MockMiddleware {
         }
     }
 
-    async fn process_response(
+    async fn on_response(
         &self,
         response: Response,
         _context: &RequestContext,
@@ -343,7 +343,7 @@ async fn test_custom_middleware_implementation() {
         params: serde_json::Value::Null,
     };
 
-    let result = mock_middleware.process_request(request, &context).await;
+    let result = mock_middleware.on_request(request, &context).await;
     assert!(result.is_ok());
 
     let response = Response {
@@ -355,7 +355,7 @@ async fn test_custom_middleware_implementation() {
         error: None,
     };
 
-    let result = mock_middleware.process_response(response, &context).await;
+    let result = mock_middleware.on_response(response, &context).await;
     assert!(result.is_ok());
 }
 
@@ -374,7 +374,7 @@ async fn test_custom_middleware_failure() {
         params: serde_json::Value::Null,
     };
 
-    let result = mock_middleware.process_request(request, &context).await;
+    let result = mock_middleware.on_request(request, &context).await;
     assert!(result.is_err());
 
     let response = Response {
@@ -386,7 +386,7 @@ async fn test_custom_middleware_failure() {
         error: None,
     };
 
-    let result = mock_middleware.process_response(response, &context).await;
+    let result = mock_middleware.on_response(response, &context).await;
     assert!(result.is_err());
 }
 
@@ -428,3 +428,175 @@ fn test_middleware_stack_clone() {
         assert!(result2.is_ok());
     });
 }
+
+/// Records the order in which its hooks run
+struct TracingMiddleware {
+    name: &'static str,
+    priority: i32,
+    calls: Arc<std::sync::Mutex<Vec<String>>>,
+}
+
+#[async_trait]
+impl Middleware for TracingMiddleware {
+    async fn on_request(
+        &self,
+        request: Request,
+        _context: &RequestContext,
+    ) -> std::result::Result<Request, Error> {
+        self.calls
+            .lock()
+            .unwrap()
+            .push(format!("request:{}", self.name));
+        Ok(request)
+    }
+
+    async fn on_response(
+        &self,
+        response: Response,
+        _context: &RequestContext,
+    ) -> std::result::Result<Response, Error> {
+        self.calls
+            .lock()
+            .unwrap()
+            .push(format!("response:{}", self.name));
+        Ok(response)
+    }
+
+    fn priority(&self) -> i32 {
+        self.priority
+    }
+}
+
+fn tracing_stack(
+    layers: &[(&'static str, i32)],
+) -> (MiddlewareStack, Arc<std::sync::Mutex<Vec<String>>>) {
+    let calls = Arc::new(std::sync::Mutex::new(Vec::new()));
+    let stack = layers
+        .iter()
+        .fold(MiddlewareStack::new(), |stack, &(name, priority)| {
+            stack.with_middleware(Arc::new(TracingMiddleware {
+                name,
+                priority,
+                calls: calls.clone(),
+            }))
+        });
+    (stack, calls)
+}
+
+async fn round_trip(stack: &MiddlewareStack) {
+    let context = RequestContext::new();
+    let request = Request {
+        jsonrpc: "2.0".to_string(),
+        id: Some(NumberOrString::Number(1)),
+        method: "ping".to_string(),
+        params: serde_json::Value::Null,
+    };
+    let request = stack.process_request(request, &context).await.unwrap();
+    let response = Response {
+        jsonrpc: "2.0".to_string(),
+        id: request.id,
+        result: Some(serde_json::json!({})),
+        error: None,
+    };
+    stack.process_response(response, &context).await.unwrap();
+}
+
+#[tokio::test]
+async fn test_middleware_onion_order() {
+    let (stack, calls) = tracing_stack(&[("outer", 0), ("middle", 0), ("inner", 0)]);
+    round_trip(&stack).await;
+
+    assert_eq!(
+        *calls.lock().unwrap(),
+        [
+            "request:outer",
+            "request:middle",
+            "request:inner",
+            "response:inner",
+            "response:middle",
+            "response:outer",
+        ]
+    );
+}
+
+#[tokio::test]
+async fn test_middleware_priority_overrides_registration_order() {
+    let (stack, calls) =
+        tracing_stack(&[("late", 10), ("first", -5), ("default", 0), ("early", -5)]);
+    round_trip(&stack).await;
+
+    assert_eq!(
+        *calls.lock().unwrap(),
+        [
+            "request:first",
+            "request:early",
+            "request:default",
+            "request:late",
+            "response:late",
+            "response:default",
+            "response:early",
+            "response:first",
+        ]
+    );
+}
+
+#[tokio::test]
+async fn test_middleware_rejection_stops_inner_layers() {
+    let calls = Arc::new(std::sync::Mutex::new(Vec::new()));
+    let stack = MiddlewareStack::new()
+        .with_middleware(Arc::new(MockMiddleware { should_fail: true }))
+        .with_middleware(Arc::new(TracingMiddleware {
+            name: "inner",
+            priority: 0,
+            calls: calls.clone(),
+        }));
+    let request = Request {
+        jsonrpc: "2.0".to_string(),
+        id: Some(NumberOrString::Number(1)),
+        method: "ping".to_string(),
+        params: serde_json::Value::Null,
+    };
+
+    let result = stack.process_request(request, &RequestContext::new()).await;
+    assert!(result.is_err());
+    assert!(calls.lock().unwrap().is_empty());
+}
+
+/// Only hooks requests; responses pass through the default `on_response`
+struct RequestTagger;
+
+#[async_trait]
+impl Middleware for RequestTagger {
+    async fn on_request(
+        &self,
+        mut request: Request,
+        _context: &RequestContext,
+    ) -> std::result::Result<Request, Error> {
+        request.params = serde_json::json!({"tagged": true});
+        Ok(request)
+    }
+}
+
+#[tokio::test]
+async fn test_middleware_default_hooks_pass_through() {
+    let stack = MiddlewareStack::new().with_middleware(Arc::new(RequestTagger));
+    let context = RequestContext::new();
+    let request = Request {
+        jsonrpc: "2.0".to_string(),
+        id: Some(NumberOrString::Number(1)),
+        method: "ping".to_string(),
+        params: serde_json::Value::Null,
+    };
+    let request = stack.process_request(request, &context).await.unwrap();
+    assert_eq!(request.params["tagged"], true);
+
+    let response = Response {
+        jsonrpc: "2.0".to_string(),
+        id: request.id,
+        result: Some(serde_json::json!({"ok": true})),
+        error: None,
+    };
+    let response = stack.process_response(response, &context).await.unwrap();
+    assert_eq!(response.result.unwrap()["ok"], true);
+    assert_eq!(RequestTagger.priority(), 0);
+}
@@ -0,0 +1,350 @@
+//! Namespaced composition of tool and resource providers
+//!
+//! Several [`McpToolsProvider`]s embedded in one server easily end up with
+//! clashing tool names. [`ProviderRegistry`] mounts each provider under a
+//! prefix, so `add` from a provider registered as `math` is listed as
+//! `math_add`, and strips the prefix again when a call is dispatched:
+//!
+//! ```rust,ignore
+//! let registry = ProviderRegistry::new()
+//!     .register("math", MathTools)?
+//!     .register_tools("text", TextTools)?;
+//! ```
+//!
+//! The registry implements the provider traits itself, so it can be used
+//! anywhere a single provider is expected. Resources keep their URIs, which
+//! already identify them, and only have their names prefixed; reads are
+//! routed to the provider listing a matching URI or URI template.
+
+use crate::backend::BackendError;
+use crate::common_backend::{McpResourcesProvider, McpToolsProvider};
+use pulseengine_mcp_protocol::*;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+
+type ProviderFuture<'a, T> =
+    Pin<Box<dyn Future<Output = std::result::Result<T, Error>> + Send + 'a>>;
+
+/// Object-safe view of an [`McpToolsProvider`]
+trait DynToolsProvider: Send + Sync {
+    fn tools(&self) -> Vec<Tool>;
+    fn call(&self, request: CallToolRequestParam) -> ProviderFuture<'_, CallToolResult>;
+}
+
+impl<P: McpToolsProvider + Send + Sync> DynToolsProvider for P {
+    fn tools(&self) -> Vec<Tool> {
+        self.get_available_tools()
+    }
+
+    fn call(&self, request: CallToolRequestParam) -> ProviderFuture<'_, CallToolResult> {
+        Box::pin(self.call_tool_impl(request))
+    }
+}
+
+/// Object-safe view of an [`McpResourcesProvider`]
+trait DynResourcesProvider: Send + Sync {
+    fn resources(&self) -> Vec<Resource>;
+    fn read(&self, request: ReadResourceRequestParam) -> ProviderFuture<'_, ReadResourceResult>;
+}
+
+impl<P: McpResourcesProvider + Send + Sync> DynResourcesProvider for P {
+    fn resources(&self) -> Vec<Resource> {
+        self.get_available_resources()
+    }
+
+    fn read(&self, request: ReadResourceRequestParam) -> ProviderFuture<'_, ReadResourceResult> {
+        Box::pin(self.read_resource_impl(request))
+    }
+}
+
+struct Mounted<P: ?Sized> {
+    prefix: String,
+    provider: Arc<P>,
+}
+
+impl<P: ?Sized> Clone for Mounted<P> {
+    fn clone(&self) -> Self {
+        Self {
+            prefix: self.prefix.clone(),
+            provider: self.provider.clone(),
+        }
+    }
+}
+
+/// Tool and resource providers mounted under namespace prefixes
+#[derive(Clone)]
+pub struct ProviderRegistry {
+    separator: String,
+    tools: Vec<Mounted<dyn DynToolsProvider>>,
+    resources: Vec<Mounted<dyn DynResourcesProvider>>,
+}
+
+impl Default for ProviderRegistry {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ProviderRegistry {
+    /// Create an empty registry joining prefixes and names with `_`
+    pub fn new() -> Self {
+        Self {
+            separator: "_".to_string(),
+            tools: Vec::new(),
+            resources: Vec::new(),
+        }
+    }
+
+    /// Use `separator` between prefix and name instead of `_`
+    ///
+    /// Must be set before registering providers.
+    pub fn with_separator(mut self, separator: impl Into<String>) -> Self {
+        self.separator = separator.into();
+        self
+    }
+
+    /// Register a provider's tools and resources under `prefix`
+    pub fn register<P>(
+        self,
+        prefix: impl Into<String>,
+        provider: P,
+    ) -> std::result::Result<Self, BackendError>
+    where
+        P: McpToolsProvider + McpResourcesProvider + Send + Sync + 'static,
+    {
+        let prefix = prefix.into();
+        let provider = Arc::new(provider);
+        self.mount_tools(prefix.clone(), provider.clone())?
+            .mount_resources(prefix, provider)
+    }
+
+    /// Register a provider's tools under `prefix`
+    ///
+    /// An empty prefix mounts the tools under their own names.
+    pub fn register_tools<P>(
+        self,
+        prefix: impl Into<String>,
+        provider: P,
+    ) -> std::result::Result<Self, BackendError>
+    where
+        P: McpToolsProvider + Send + Sync + 'static,
+    {
+        self.mount_tools(prefix.into(), Arc::new(provider))
+    }
+
+    /// Register a provider's resources under `prefix`
+    ///
+    /// An empty prefix leaves the resource names unchanged.
+    pub fn register_resources<P>(
+        self,
+        prefix: impl Into<String>,
+        provider: P,
+    ) -> std::result::Result<Self, BackendError>
+    where
+        P: McpResourcesProvider + Send + Sync + 'static,
+    {
+        self.mount_resources(prefix.into(), Arc::new(provider))
+    }
+
+    fn mount_tools(
+        mut self,
+        prefix: String,
+        provider: Arc<dyn DynToolsProvider>,
+    ) -> std::result::Result<Self, BackendError> {
+        let existing: Vec<String> = self.list_tools().into_iter().map(|t| t.name).collect();
+        for tool in provider.tools() {
+            let name = self.qualify(&prefix, &tool.name);
+            if existing.contains(&name) {
+                return Err(BackendError::configuration(format!(
+                    "Tool '{name}' is already registered"
+                )));
+            }
+        }
+        self.tools.push(Mounted { prefix, provider });
+        Ok(self)
+    }
+
+    fn mount_resources(
+        mut self,
+        prefix: String,
+        provider: Arc<dyn DynResourcesProvider>,
+    ) -> std::result::Result<Self, BackendError> {
+        let existing: Vec<String> = self
+            .resources
+            .iter()
+            .flat_map(|mounted| mounted.provider.resources())
+            .map(|resource| resource.uri)
+            .collect();
+        if let Some(resource) = provider
+            .resources()
+            .into_iter()
+            .find(|resource| existing.contains(&resource.uri))
+        {
+            return Err(BackendError::configuration(format!(
+                "Resource '{}' is already registered",
+                resource.uri
+            )));
+        }
+        self.resources.push(Mounted { prefix, provider });
+        Ok(self)
+    }
+
+    fn qualify(&self, prefix: &str, name: &str) -> String {
+        if prefix.is_empty() {
+            name.to_string()
+        } else {
+            format!("{prefix}{}{name}", self.separator)
+        }
+    }
+
+    fn strip<'a>(&self, prefix: &str, name: &'a str) -> Option<&'a str> {
+        if prefix.is_empty() {
+            return Some(name);
+        }
+        name.strip_prefix(prefix)?
+            .strip_prefix(self.separator.as_str())
+    }
+
+    /// All registered tools under their namespaced names
+    pub fn list_tools(&self) -> Vec<Tool> {
+        self.tools
+            .iter()
+            .flat_map(|mounted| {
+                mounted.provider.tools().into_iter().map(|tool| Tool {
+                    name: self.qualify(&mounted.prefix, &tool.name),
+                    ..tool
+                })
+            })
+            .collect()
+    }
+
+    /// All registered resources, with namespaced names
+    pub fn list_resources(&self) -> Vec<Resource> {
+        self.resources
+            .iter()
+            .flat_map(|mounted| {
+                mounted
+                    .provider
+                    .resources()
+                    .into_iter()
+                    .map(|resource| Resource {
+                        name: self.qualify(&mounted.prefix, &resource.name),
+                        ..resource
+                    })
+            })
+            .collect()
+    }
+
+    /// Dispatch a call by namespaced tool name to the provider that owns it
+    pub async fn call_tool(
+        &self,
+        request: CallToolRequestParam,
+    ) -> std::result::Result<CallToolResult, Error> {
+        // Prefixes may overlap (`a` and `a_b`), so the stripped name must also
+        // be one of the provider's tools
+        let target = self.tools.iter().find_map(|mounted| {
+            let name = self.strip(&mounted.prefix, &request.name)?;
+            mounted
+                .provider
+                .tools()
+                .iter()
+                .any(|tool| tool.name == name)
+                .then(|| (mounted, name.to_string()))
+        });
+
+        match target {
+            Some((mounted, name)) => {
+                mounted
+                    .provider
+                    .call(CallToolRequestParam {
+                        name,
+                        arguments: request.arguments,
+                    })
+                    .await
+            }
+            None => Err(Error::invalid_params(format!(
+                "Unknown tool: {}",
+                request.name
+            ))),
+        }
+    }
+
+    /// Route a read to the provider listing a matching URI or URI template
+    pub async fn read_resource(
+        &self,
+        request: ReadResourceRequestParam,
+    ) -> std::result::Result<ReadResourceResult, Error> {
+        let target = self.resources.iter().find(|mounted| {
+            mounted
+                .provider
+                .resources()
+                .iter()
+                .any(|resource| uri_matches_template(&resource.uri, &request.uri))
+        });
+
+        match target {
+            Some(mounted) => mounted.provider.read(request).await,
+            None => Err(Error::invalid_params(format!(
+                "Unknown resource: {}",
+                request.uri
+            ))),
+        }
+    }
+}
+
+impl McpToolsProvider for ProviderRegistry {
+    fn get_available_tools(&self) -> Vec<Tool> {
+        self.list_tools()
+    }
+
+    fn call_tool_impl(
+        &self,
+        request: CallToolRequestParam,
+    ) -> impl Future<Output = std::result::Result<CallToolResult, Error>> + Send {
+        self.call_tool(request)
+    }
+}
+
+impl McpResourcesProvider for ProviderRegistry {
+    fn get_available_resources(&self) -> Vec<Resource> {
+        self.list_resources()
+    }
+
+    fn read_resource_impl(
+        &self,
+        request: ReadResourceRequestParam,
+    ) -> impl Future<Output = std::result::Result<ReadResourceResult, Error>> + Send {
+        self.read_resource(request)
+    }
+}
+
+/// Whether `uri` is `template` with every `{param}` replaced by a non-empty value
+fn uri_matches_template(template: &str, uri: &str) -> bool {
+    let mut literals = Vec::new();
+    let mut rest = template;
+    while let Some(start) = rest.find('{') {
+        let Some(end) = rest[start..].find('}') else {
+            break;
+        };
+        literals.push(&rest[..start]);
+        rest = &rest[start + end + 1..];
+    }
+    literals.push(rest);
+
+    let (first, tail) = literals.split_first().expect("at least one literal");
+    let Some(mut remaining) = uri.strip_prefix(first) else {
+        return false;
+    };
+    let Some((last, middle)) = tail.split_last() else {
+        return remaining.is_empty();
+    };
+    for literal in middle {
+        // Each placeholder takes at least one character
+        match remaining.get(1..).and_then(|after| after.find(literal)) {
+            Some(index) => remaining = &remaining[index + 1 + literal.len()..],
+            None => return false,
+        }
+    }
+    remaining.len() > last.len() && remaining.ends_with(last)
+}
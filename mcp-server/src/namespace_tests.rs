@@ -0,0 +1,183 @@
+//! Tests for namespaced provider composition
+
+use crate::common_backend::{McpResourcesProvider, McpToolsProvider};
+use crate::namespace::ProviderRegistry;
+use pulseengine_mcp_protocol::*;
+use serde_json::json;
+
+fn tool(name: &str) -> Tool {
+    Tool {
+        name: name.to_string(),
+        title: None,
+        description: format!("{name} tool"),
+        input_schema: json!({"type": "object", "properties": {"value": {"type": "string"}}}),
+        output_schema: None,
+        annotations: None,
+        icons: None,
+        execution: None,
+        _meta: None,
+    }
+}
+
+fn text_of(result: &CallToolResult) -> &str {
+    match &result.content[0] {
+        Content::Text { text, .. } => text,
+        _ => panic!("expected text content"),
+    }
+}
+
+/// Provider whose tools echo its label and the called tool name
+struct LabelledTools {
+    label: &'static str,
+    tools: &'static [&'static str],
+    resource_uri: &'static str,
+}
+
+impl McpToolsProvider for LabelledTools {
+    fn get_available_tools(&self) -> Vec<Tool> {
+        self.tools.iter().map(|name| tool(name)).collect()
+    }
+
+    fn call_tool_impl(
+        &self,
+        request: CallToolRequestParam,
+    ) -> impl std::future::Future<Output = std::result::Result<CallToolResult, Error>> + Send {
+        let known = self.tools.contains(&request.name.as_str());
+        let label = self.label;
+        async move {
+            if !known {
+                return Err(Error::invalid_params(format!(
+                    "Unknown tool: {}",
+                    request.name
+                )));
+            }
+            Ok(CallToolResult::text(format!("{label}:{}", request.name)))
+        }
+    }
+}
+
+impl McpResourcesProvider for LabelledTools {
+    fn get_available_resources(&self) -> Vec<Resource> {
+        vec![Resource::ui_resource(self.resource_uri, "status", "Status")]
+    }
+
+    fn read_resource_impl(
+        &self,
+        request: ReadResourceRequestParam,
+    ) -> impl std::future::Future<Output = std::result::Result<ReadResourceResult, Error>> + Send
+    {
+        let label = self.label;
+        async move {
+            Ok(ReadResourceResult::new(vec![ResourceContents::text(
+                request.uri,
+                label,
+            )]))
+        }
+    }
+}
+
+const MATH: LabelledTools = LabelledTools {
+    label: "math",
+    tools: &["add", "describe"],
+    resource_uri: "math://status/{topic}",
+};
+
+const TEXT: LabelledTools = LabelledTools {
+    label: "text",
+    tools: &["describe", "upper"],
+    resource_uri: "text://status",
+};
+
+#[tokio::test]
+async fn test_two_providers_are_listed_and_dispatched_by_prefix() {
+    let registry = ProviderRegistry::new()
+        .register("math", MATH)
+        .unwrap()
+        .register("text", TEXT)
+        .unwrap();
+
+    let tools = registry.get_available_tools();
+    let names: Vec<&str> = tools.iter().map(|tool| tool.name.as_str()).collect();
+    assert_eq!(
+        names,
+        ["math_add", "math_describe", "text_describe", "text_upper"]
+    );
+    assert_eq!(tools[0].input_schema, tool("add").input_schema);
+
+    for (name, expected) in [
+        ("math_describe", "math:describe"),
+        ("text_describe", "text:describe"),
+        ("text_upper", "text:upper"),
+    ] {
+        let result = registry
+            .call_tool_impl(CallToolRequestParam {
+                name: name.to_string(),
+                arguments: Some(json!({"value": "x"})),
+            })
+            .await
+            .unwrap();
+        assert_eq!(text_of(&result), expected);
+    }
+
+    for name in ["describe", "math_upper", "mathdescribe"] {
+        let error = registry
+            .call_tool_impl(CallToolRequestParam {
+                name: name.to_string(),
+                arguments: None,
+            })
+            .await
+            .unwrap_err();
+        assert_eq!(error.message, format!("Unknown tool: {name}"));
+    }
+}
+
+#[tokio::test]
+async fn test_resources_keep_uris_and_route_by_template() {
+    let registry = ProviderRegistry::new()
+        .with_separator(".")
+        .register("math", MATH)
+        .unwrap()
+        .register("text", TEXT)
+        .unwrap();
+
+    let resources = registry.get_available_resources();
+    assert_eq!(resources[0].name, "math.status");
+    assert_eq!(resources[0].uri, "math://status/{topic}");
+    assert_eq!(resources[1].name, "text.status");
+
+    let read = |uri: &str| {
+        registry.read_resource_impl(ReadResourceRequestParam {
+            uri: uri.to_string(),
+        })
+    };
+    let result = read("math://status/primes").await.unwrap();
+    assert_eq!(result.contents[0].text.as_deref(), Some("math"));
+    let result = read("text://status").await.unwrap();
+    assert_eq!(result.contents[0].text.as_deref(), Some("text"));
+    assert!(read("math://status/").await.is_err());
+    assert!(read("other://status").await.is_err());
+}
+
+#[test]
+fn test_colliding_registrations_are_rejected() {
+    let registry = ProviderRegistry::new().register("math", MATH).unwrap();
+
+    let error = registry.clone().register_tools("math", TEXT).err().unwrap();
+    assert!(error.to_string().contains("math_describe"));
+
+    let error = registry
+        .clone()
+        .register_resources("other", LabelledTools { tools: &[], ..MATH })
+        .err()
+        .unwrap();
+    assert!(error.to_string().contains("math://status/{topic}"));
+
+    // Unprefixed tools keep their names
+    let registry = registry.register_tools("", TEXT).unwrap();
+    assert!(
+        registry
+            .get_available_tools()
+            .iter()
+            .any(|tool| tool.name == "upper")
+    );
+}
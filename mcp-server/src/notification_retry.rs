@@ -0,0 +1,145 @@
+//! Retry with backoff for server-to-client notification delivery
+//!
+//! A resource update lost to a transient write error leaves the client with
+//! stale state, so [`NotificationDelivery`] retries failed sends with
+//! exponential backoff. Sends to a closed connection or an unknown session
+//! are never retried: the notification is dropped and counted. Notifications
+//! still failing after `max_attempts` are dead-lettered, i.e. given up on and
+//! counted, so operators can alert on lost updates.
+
+use pulseengine_mcp_transport::{Transport, TransportError};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Duration;
+use tracing::{error, warn};
+
+/// Notification retry configuration
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct NotificationRetryConfig {
+    /// Send attempts per notification, including the first
+    pub max_attempts: u32,
+    /// Delay before the first retry; doubled for each further retry
+    pub initial_backoff_ms: u64,
+    /// Upper bound on the delay between retries
+    pub max_backoff_ms: u64,
+}
+
+impl Default for NotificationRetryConfig {
+    fn default() -> Self {
+        Self {
+            max_attempts: 3,
+            initial_backoff_ms: 100,
+            max_backoff_ms: 5_000,
+        }
+    }
+}
+
+/// Notification delivery counters
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct NotificationDeliveryStats {
+    /// Notifications delivered, possibly after retries
+    pub delivered: u64,
+    /// Retry attempts made
+    pub retries: u64,
+    /// Notifications given up on after exhausting every attempt
+    pub dead_lettered: u64,
+    /// Notifications dropped because the connection was closed
+    pub dropped: u64,
+}
+
+#[derive(Default)]
+struct Counters {
+    delivered: AtomicU64,
+    retries: AtomicU64,
+    dead_lettered: AtomicU64,
+    dropped: AtomicU64,
+}
+
+/// Sends notifications through a transport, retrying transient failures
+#[derive(Clone)]
+pub struct NotificationDelivery {
+    config: NotificationRetryConfig,
+    counters: Arc<Counters>,
+}
+
+impl NotificationDelivery {
+    pub fn new(config: NotificationRetryConfig) -> Self {
+        Self {
+            config,
+            counters: Arc::default(),
+        }
+    }
+
+    pub fn config(&self) -> &NotificationRetryConfig {
+        &self.config
+    }
+
+    /// Counters accumulated across every send
+    pub fn stats(&self) -> NotificationDeliveryStats {
+        NotificationDeliveryStats {
+            delivered: self.counters.delivered.load(Ordering::Relaxed),
+            retries: self.counters.retries.load(Ordering::Relaxed),
+            dead_lettered: self.counters.dead_lettered.load(Ordering::Relaxed),
+            dropped: self.counters.dropped.load(Ordering::Relaxed),
+        }
+    }
+
+    /// Send a notification, retrying transient failures with backoff
+    ///
+    /// # Errors
+    ///
+    /// Returns the last transport error if the connection is closed, the
+    /// transport can't send notifications, or every attempt failed
+    pub async fn send(
+        &self,
+        transport: &dyn Transport,
+        session_id: Option<&str>,
+        method: &str,
+        params: Value,
+    ) -> Result<(), TransportError> {
+        let max_attempts = self.config.max_attempts.max(1);
+        let mut backoff = Duration::from_millis(self.config.initial_backoff_ms);
+        let mut attempt = 1;
+        loop {
+            let error = match transport
+                .send_notification(session_id, method, params.clone())
+                .await
+            {
+                Ok(()) => {
+                    self.counters.delivered.fetch_add(1, Ordering::Relaxed);
+                    return Ok(());
+                }
+                Err(error) => error,
+            };
+
+            match error {
+                TransportError::ChannelClosed | TransportError::SessionNotFound(_) => {
+                    self.counters.dropped.fetch_add(1, Ordering::Relaxed);
+                    warn!(method = %method, error = %error, "Dropped notification for closed connection");
+                    return Err(error);
+                }
+                TransportError::NotSupported(_) | TransportError::Config(_) => return Err(error),
+                _ if attempt >= max_attempts => {
+                    self.counters.dead_lettered.fetch_add(1, Ordering::Relaxed);
+                    error!(
+                        method = %method,
+                        attempts = attempt,
+                        error = %error,
+                        "Notification dead-lettered after exhausting retries"
+                    );
+                    return Err(error);
+                }
+                _ => {
+                    warn!(method = %method, attempt, error = %error, "Retrying notification delivery");
+                    self.counters.retries.fetch_add(1, Ordering::Relaxed);
+                    tokio::time::sleep(backoff).await;
+                    backoff = (backoff * 2).min(Duration::from_millis(self.config.max_backoff_ms));
+                    attempt += 1;
+                }
+            }
+        }
+    }
+}
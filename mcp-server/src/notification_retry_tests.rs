@@ -0,0 +1,137 @@
+//! Tests for notification delivery retry
+
+use crate::notification_retry::*;
+use async_trait::async_trait;
+use pulseengine_mcp_transport::{RequestHandler, Transport, TransportError};
+use serde_json::json;
+use std::sync::Mutex;
+
+/// Transport whose notification sends fail with the queued errors first
+#[derive(Default)]
+struct FlakyTransport {
+    failures: Mutex<Vec<TransportError>>,
+    attempts: Mutex<u32>,
+}
+
+impl FlakyTransport {
+    fn failing_with(failures: Vec<TransportError>) -> Self {
+        Self {
+            failures: Mutex::new(failures),
+            ..Default::default()
+        }
+    }
+
+    fn attempts(&self) -> u32 {
+        *self.attempts.lock().unwrap()
+    }
+}
+
+#[async_trait]
+impl Transport for FlakyTransport {
+    async fn start(&mut self, _handler: RequestHandler) -> Result<(), TransportError> {
+        Ok(())
+    }
+
+    async fn stop(&mut self) -> Result<(), TransportError> {
+        Ok(())
+    }
+
+    async fn health_check(&self) -> Result<(), TransportError> {
+        Ok(())
+    }
+
+    async fn send_notification(
+        &self,
+        _session_id: Option<&str>,
+        _method: &str,
+        _params: serde_json::Value,
+    ) -> Result<(), TransportError> {
+        *self.attempts.lock().unwrap() += 1;
+        let mut failures = self.failures.lock().unwrap();
+        if failures.is_empty() {
+            Ok(())
+        } else {
+            Err(failures.remove(0))
+        }
+    }
+}
+
+fn delivery(max_attempts: u32) -> NotificationDelivery {
+    NotificationDelivery::new(NotificationRetryConfig {
+        max_attempts,
+        initial_backoff_ms: 1,
+        max_backoff_ms: 4,
+    })
+}
+
+fn write_error() -> TransportError {
+    TransportError::Connection("broken pipe".to_string())
+}
+
+#[tokio::test]
+async fn test_flaky_write_succeeds_on_retry() {
+    let delivery = delivery(3);
+    let transport = FlakyTransport::failing_with(vec![write_error(), write_error()]);
+
+    let result = delivery
+        .send(
+            &transport,
+            None,
+            "notifications/resources/updated",
+            json!({}),
+        )
+        .await;
+    assert!(result.is_ok());
+    assert_eq!(transport.attempts(), 3);
+    assert_eq!(
+        delivery.stats(),
+        NotificationDeliveryStats {
+            delivered: 1,
+            retries: 2,
+            dead_lettered: 0,
+            dropped: 0,
+        }
+    );
+}
+
+#[tokio::test]
+async fn test_exhausted_attempts_are_dead_lettered() {
+    let delivery = delivery(3);
+    let transport = FlakyTransport::failing_with((0..5).map(|_| write_error()).collect());
+
+    let error = delivery
+        .send(
+            &transport,
+            None,
+            "notifications/resources/updated",
+            json!({}),
+        )
+        .await
+        .unwrap_err();
+    assert!(matches!(error, TransportError::Connection(_)));
+    assert_eq!(transport.attempts(), 3);
+    let stats = delivery.stats();
+    assert_eq!(stats.dead_lettered, 1);
+    assert_eq!(stats.retries, 2);
+    assert_eq!(stats.delivered, 0);
+}
+
+#[tokio::test]
+async fn test_closed_connection_is_dropped_without_retry() {
+    let delivery = delivery(5);
+    let transport = FlakyTransport::failing_with(vec![TransportError::ChannelClosed]);
+
+    let error = delivery
+        .send(
+            &transport,
+            Some("gone"),
+            "notifications/resources/updated",
+            json!({}),
+        )
+        .await
+        .unwrap_err();
+    assert!(matches!(error, TransportError::ChannelClosed));
+    assert_eq!(transport.attempts(), 1);
+    assert_eq!(delivery.stats().dropped, 1);
+    assert_eq!(delivery.stats().retries, 0);
+}
@@ -0,0 +1,360 @@
+//! Privacy-preserving tool usage analytics
+//!
+//! [`ToolUsageAnalytics`] counts calls per tool per day and estimates how
+//! many distinct callers used each tool. Callers are only ever fed into a
+//! [`HyperLogLog`] sketch, so raw identities are never stored and memory per
+//! tool and day stays fixed however many callers there are. Aggregates can be
+//! exported as JSON or CSV.
+
+use chrono::{NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::hash::{DefaultHasher, Hash, Hasher};
+use std::sync::Mutex;
+
+/// Cardinality estimator with a fixed memory footprint
+///
+/// Uses `2^precision` one-byte registers; the standard error of the estimate
+/// is about `1.04 / sqrt(2^precision)`, so 1.6% at the default precision of 12.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HyperLogLog {
+    precision: u8,
+    registers: Vec<u8>,
+}
+
+impl HyperLogLog {
+    /// Create an empty sketch; `precision` is clamped to 4..=16
+    pub fn new(precision: u8) -> Self {
+        let precision = precision.clamp(4, 16);
+        Self {
+            precision,
+            registers: vec![0; 1 << precision],
+        }
+    }
+
+    /// Add an item to the sketch
+    pub fn insert<T: Hash + ?Sized>(&mut self, item: &T) {
+        let mut hasher = DefaultHasher::new();
+        item.hash(&mut hasher);
+        let hash = hasher.finish();
+
+        let index = (hash >> (64 - self.precision)) as usize;
+        let remaining = hash << self.precision;
+        let rank = (remaining.leading_zeros() as u8).min(64 - self.precision) + 1;
+        self.registers[index] = self.registers[index].max(rank);
+    }
+
+    /// Fold another sketch of the same precision into this one
+    pub fn merge(&mut self, other: &Self) {
+        if other.precision != self.precision {
+            return;
+        }
+        for (register, theirs) in self.registers.iter_mut().zip(&other.registers) {
+            *register = (*register).max(*theirs);
+        }
+    }
+
+    /// Estimated number of distinct items inserted
+    pub fn estimate(&self) -> u64 {
+        let m = self.registers.len() as f64;
+        let alpha = match self.registers.len() {
+            16 => 0.673,
+            32 => 0.697,
+            64 => 0.709,
+            _ => 0.7213 / (1.0 + 1.079 / m),
+        };
+        let sum: f64 = self
+            .registers
+            .iter()
+            .map(|&rank| 2f64.powi(-i32::from(rank)))
+            .sum();
+        let raw = alpha * m * m / sum;
+
+        let zeros = self.registers.iter().filter(|&&rank| rank == 0).count();
+        let estimate = if raw <= 2.5 * m && zeros > 0 {
+            // Linear counting is more accurate for small cardinalities
+            m * (m / zeros as f64).ln()
+        } else {
+            raw
+        };
+        estimate.round() as u64
+    }
+}
+
+/// Tool usage analytics configuration
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AnalyticsConfig {
+    /// HyperLogLog precision for unique caller estimates (4..=16)
+    pub precision: u8,
+    /// Days of history kept, counted back from the most recent recorded day
+    pub retention_days: u32,
+    /// Distinct tools tracked per day; calls to further tools aren't recorded
+    pub max_tools_per_day: usize,
+}
+
+impl Default for AnalyticsConfig {
+    fn default() -> Self {
+        Self {
+            precision: 12,
+            retention_days: 90,
+            max_tools_per_day: 1024,
+        }
+    }
+}
+
+/// Aggregated usage of one tool on one day
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ToolUsageRecord {
+    pub date: NaiveDate,
+    pub tool: String,
+    pub calls: u64,
+    /// Approximate number of distinct callers
+    pub unique_callers: u64,
+}
+
+struct DailyToolUsage {
+    calls: u64,
+    callers: HyperLogLog,
+}
+
+/// Accumulates per-tool, per-day call counts and unique caller estimates
+pub struct ToolUsageAnalytics {
+    config: AnalyticsConfig,
+    days: Mutex<BTreeMap<NaiveDate, HashMap<String, DailyToolUsage>>>,
+}
+
+impl std::fmt::Debug for ToolUsageAnalytics {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ToolUsageAnalytics")
+            .field("config", &self.config)
+            .finish_non_exhaustive()
+    }
+}
+
+impl Default for ToolUsageAnalytics {
+    fn default() -> Self {
+        Self::new(AnalyticsConfig::default())
+    }
+}
+
+impl ToolUsageAnalytics {
+    pub fn new(config: AnalyticsConfig) -> Self {
+        Self {
+            config,
+            days: Mutex::new(BTreeMap::new()),
+        }
+    }
+
+    pub fn config(&self) -> &AnalyticsConfig {
+        &self.config
+    }
+
+    /// Record a call to `tool` by `caller` today (UTC)
+    pub fn record_call(&self, tool: &str, caller: &str) {
+        self.record_call_on(Utc::now().date_naive(), tool, caller);
+    }
+
+    /// Record a call to `tool` by `caller` on `date`
+    ///
+    /// Only a hash of `caller` reaches the unique caller sketch. Once
+    /// `max_tools_per_day` tools were seen on `date`, calls to other tools
+    /// are dropped, so memory stays bounded however many names are called.
+    pub fn record_call_on(&self, date: NaiveDate, tool: &str, caller: &str) {
+        let mut days = self.days.lock().unwrap();
+        let tools = days.entry(date).or_default();
+        if !tools.contains_key(tool) && tools.len() >= self.config.max_tools_per_day {
+            return;
+        }
+        let usage = tools
+            .entry(tool.to_string())
+            .or_insert_with(|| DailyToolUsage {
+                calls: 0,
+                callers: HyperLogLog::new(self.config.precision),
+            });
+        usage.calls += 1;
+        usage.callers.insert(caller);
+
+        if let Some(&newest) = days.keys().next_back()
+            && let Some(cutoff) =
+                newest.checked_sub_days(chrono::Days::new(u64::from(self.config.retention_days)))
+        {
+            days.retain(|day, _| *day > cutoff);
+        }
+    }
+
+    /// Usage per tool per day, ordered by date then tool name
+    pub fn records(&self) -> Vec<ToolUsageRecord> {
+        let days = self.days.lock().unwrap();
+        let mut records = Vec::new();
+        for (date, tools) in days.iter() {
+            let mut day: Vec<ToolUsageRecord> = tools
+                .iter()
+                .map(|(tool, usage)| ToolUsageRecord {
+                    date: *date,
+                    tool: tool.clone(),
+                    calls: usage.calls,
+                    unique_callers: usage.callers.estimate(),
+                })
+                .collect();
+            day.sort_by(|a, b| a.tool.cmp(&b.tool));
+            records.extend(day);
+        }
+        records
+    }
+
+    /// Approximate distinct callers of `tool` across all retained days
+    pub fn unique_callers(&self, tool: &str) -> u64 {
+        let days = self.days.lock().unwrap();
+        let mut callers = HyperLogLog::new(self.config.precision);
+        for usage in days.values().filter_map(|tools| tools.get(tool)) {
+            callers.merge(&usage.callers);
+        }
+        callers.estimate()
+    }
+
+    /// Export [`Self::records`] as a JSON array
+    pub fn to_json(&self) -> serde_json::Value {
+        serde_json::to_value(self.records()).unwrap_or_default()
+    }
+
+    /// Export [`Self::records`] as CSV with a `date,tool,calls,unique_callers` header
+    pub fn to_csv(&self) -> String {
+        let mut csv = String::from("date,tool,calls,unique_callers\n");
+        for record in self.records() {
+            csv.push_str(&format!(
+                "{},{},{},{}\n",
+                record.date,
+                csv_field(&record.tool),
+                record.calls,
+                record.unique_callers
+            ));
+        }
+        csv
+    }
+}
+
+/// Quote a CSV field when it contains a delimiter, quote or line break
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn day(n: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2026, 3, n).unwrap()
+    }
+
+    #[test]
+    fn test_hyperloglog_estimates_within_error_bounds() {
+        let mut sketch = HyperLogLog::new(12);
+        for i in 0..10_000 {
+            sketch.insert(&format!("caller-{i}"));
+            sketch.insert(&format!("caller-{}", i % 100));
+        }
+        let estimate = sketch.estimate() as f64;
+        assert!(
+            (estimate - 10_000.0).abs() / 10_000.0 < 0.05,
+            "estimate {estimate} too far from 10000"
+        );
+
+        let mut small = HyperLogLog::new(12);
+        for caller in ["alice", "bob", "carol", "alice", "bob"] {
+            small.insert(caller);
+        }
+        assert_eq!(small.estimate(), 3);
+    }
+
+    #[test]
+    fn test_usage_is_aggregated_per_tool_and_day() {
+        let analytics = ToolUsageAnalytics::default();
+        for caller in ["alice", "bob", "alice", "carol"] {
+            analytics.record_call_on(day(1), "search", caller);
+        }
+        analytics.record_call_on(day(1), "fetch", "alice");
+        for caller in ["dave", "alice"] {
+            analytics.record_call_on(day(2), "search", caller);
+        }
+
+        let records = analytics.records();
+        let summary: Vec<(NaiveDate, &str, u64, u64)> = records
+            .iter()
+            .map(|r| (r.date, r.tool.as_str(), r.calls, r.unique_callers))
+            .collect();
+        assert_eq!(
+            summary,
+            [
+                (day(1), "fetch", 1, 1),
+                (day(1), "search", 4, 3),
+                (day(2), "search", 2, 2),
+            ]
+        );
+        assert_eq!(analytics.unique_callers("search"), 4);
+        assert_eq!(analytics.unique_callers("unused"), 0);
+
+        let json = analytics.to_json();
+        assert_eq!(json[1]["tool"], "search");
+        assert_eq!(json[1]["date"], "2026-03-01");
+        assert_eq!(json[1]["unique_callers"], 3);
+        assert!(!json.to_string().contains("alice"));
+
+        let csv = analytics.to_csv();
+        assert_eq!(
+            csv,
+            "date,tool,calls,unique_callers\n\
+             2026-03-01,fetch,1,1\n\
+             2026-03-01,search,4,3\n\
+             2026-03-02,search,2,2\n"
+        );
+    }
+
+    #[test]
+    fn test_old_days_are_pruned_and_csv_is_escaped() {
+        let analytics = ToolUsageAnalytics::new(AnalyticsConfig {
+            retention_days: 2,
+            ..Default::default()
+        });
+        analytics.record_call_on(day(1), "old", "alice");
+        analytics.record_call_on(day(4), "a,\"b\"", "alice");
+
+        let records = analytics.records();
+        assert_eq!(records.len(), 1);
+        assert!(
+            analytics
+                .to_csv()
+                .contains("2026-03-04,\"a,\"\"b\"\"\",1,1\n")
+        );
+    }
+
+    #[test]
+    fn test_tools_tracked_per_day_are_capped() {
+        let analytics = ToolUsageAnalytics::new(AnalyticsConfig {
+            max_tools_per_day: 2,
+            ..Default::default()
+        });
+        for tool in ["a", "b", "c", "a"] {
+            analytics.record_call_on(day(1), tool, "alice");
+        }
+        analytics.record_call_on(day(2), "c", "alice");
+
+        let summary: Vec<(NaiveDate, String, u64)> = analytics
+            .records()
+            .into_iter()
+            .map(|r| (r.date, r.tool, r.calls))
+            .collect();
+        assert_eq!(
+            summary,
+            [
+                (day(1), "a".to_string(), 2),
+                (day(1), "b".to_string(), 1),
+                (day(2), "c".to_string(), 1),
+            ]
+        );
+    }
+}
@@ -23,13 +23,17 @@
 //! }
 //! ```
 
+pub mod analytics;
 pub mod collector;
 pub mod config;
 pub mod metrics;
+pub mod statsd;
 
+pub use analytics::{AnalyticsConfig, HyperLogLog, ToolUsageAnalytics, ToolUsageRecord};
 pub use collector::{MetricsCollector, RequestContext};
 pub use config::MonitoringConfig;
 pub use metrics::{LoadAverage, ServerMetrics, SystemMetrics};
+pub use statsd::{StatsdConfig, StatsdExporter, StatsdFlavor};
 
 /// Default monitoring configuration
 pub fn default_config() -> MonitoringConfig {
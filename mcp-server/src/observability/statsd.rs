@@ -0,0 +1,276 @@
+//! StatsD / DogStatsD metrics exporter
+//!
+//! [`StatsdExporter`] pushes counters, gauges and timers over UDP to a StatsD
+//! server or a Datadog agent. Metrics are queued as they're recorded and sent
+//! by [`StatsdExporter::flush`], packed into as few datagrams as fit under
+//! `max_packet_size` so they aren't fragmented.
+
+use super::MetricsCollector;
+use serde::{Deserialize, Serialize};
+use std::sync::Mutex;
+use std::time::Duration;
+use tokio::net::UdpSocket;
+
+/// Line format spoken by the StatsD server
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum StatsdFlavor {
+    /// Plain StatsD, which has no tags; tags are dropped
+    Statsd,
+    /// DogStatsD, with tags appended as `|#key:value,...`
+    #[default]
+    Dogstatsd,
+}
+
+/// StatsD exporter configuration
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct StatsdConfig {
+    /// Address of the StatsD server or Datadog agent
+    pub endpoint: String,
+    /// Prefix prepended to every metric name, joined with `.`
+    pub prefix: String,
+    /// Line format to emit
+    pub flavor: StatsdFlavor,
+    /// Tags added to every metric, as `key:value` or bare `key`
+    pub tags: Vec<String>,
+    /// Largest UDP payload to send; 1432 fits a 1500 byte Ethernet MTU
+    pub max_packet_size: usize,
+}
+
+impl Default for StatsdConfig {
+    fn default() -> Self {
+        Self {
+            endpoint: "127.0.0.1:8125".to_string(),
+            prefix: "mcp".to_string(),
+            flavor: StatsdFlavor::default(),
+            tags: Vec::new(),
+            max_packet_size: 1432,
+        }
+    }
+}
+
+/// Pushes metrics to StatsD over UDP
+pub struct StatsdExporter {
+    config: StatsdConfig,
+    socket: UdpSocket,
+    pending: Mutex<Vec<String>>,
+    /// `requests_total` at the previous export, to send counter deltas
+    exported_requests: Mutex<u64>,
+}
+
+impl StatsdExporter {
+    /// Create an exporter sending to `config.endpoint`
+    pub async fn new(config: StatsdConfig) -> std::io::Result<Self> {
+        let socket = UdpSocket::bind("0.0.0.0:0").await?;
+        socket.connect(&config.endpoint).await?;
+        Ok(Self {
+            config,
+            socket,
+            pending: Mutex::new(Vec::new()),
+            exported_requests: Mutex::new(0),
+        })
+    }
+
+    pub fn config(&self) -> &StatsdConfig {
+        &self.config
+    }
+
+    /// Add `value` to a counter
+    pub fn count(&self, name: &str, value: i64, tags: &[&str]) {
+        self.record(name, &value.to_string(), "c", tags);
+    }
+
+    /// Set a gauge to `value`
+    pub fn gauge(&self, name: &str, value: f64, tags: &[&str]) {
+        self.record(name, &value.to_string(), "g", tags);
+    }
+
+    /// Record a duration in milliseconds
+    pub fn timing(&self, name: &str, duration: Duration, tags: &[&str]) {
+        let millis = duration.as_secs_f64() * 1000.0;
+        self.record(name, &millis.to_string(), "ms", tags);
+    }
+
+    fn record(&self, name: &str, value: &str, kind: &str, tags: &[&str]) {
+        let line = self.format_line(name, value, kind, tags);
+        self.pending.lock().unwrap().push(line);
+    }
+
+    fn format_line(&self, name: &str, value: &str, kind: &str, tags: &[&str]) -> String {
+        let name = sanitize(name, &[':', '|', '@', '#', '\n']);
+        let mut line = if self.config.prefix.is_empty() {
+            format!("{name}:{value}|{kind}")
+        } else {
+            format!("{}.{name}:{value}|{kind}", self.config.prefix)
+        };
+
+        if self.config.flavor == StatsdFlavor::Dogstatsd {
+            let tags: Vec<String> = self
+                .config
+                .tags
+                .iter()
+                .map(String::as_str)
+                .chain(tags.iter().copied())
+                .map(|tag| sanitize(tag, &['|', ',', '#', '\n']))
+                .collect();
+            if !tags.is_empty() {
+                line.push_str("|#");
+                line.push_str(&tags.join(","));
+            }
+        }
+        line
+    }
+
+    /// Send every queued metric, returning the number of packets sent
+    pub async fn flush(&self) -> std::io::Result<usize> {
+        let lines = std::mem::take(&mut *self.pending.lock().unwrap());
+        let packets = pack_lines(&lines, self.config.max_packet_size);
+        for packet in &packets {
+            self.socket.send(packet.as_bytes()).await?;
+        }
+        Ok(packets.len())
+    }
+
+    /// Record the collector's current server metrics and flush them
+    pub async fn export(&self, collector: &MetricsCollector) -> std::io::Result<usize> {
+        let metrics = collector.get_current_metrics().await;
+        let delta = {
+            let mut exported = self.exported_requests.lock().unwrap();
+            let delta = metrics.requests_total.saturating_sub(*exported);
+            *exported = metrics.requests_total;
+            delta
+        };
+
+        self.count("requests", delta as i64, &[]);
+        self.gauge("requests_per_second", metrics.requests_per_second, &[]);
+        self.gauge("error_rate", metrics.error_rate, &[]);
+        self.gauge("active_connections", metrics.active_connections as f64, &[]);
+        self.gauge("memory_usage_bytes", metrics.memory_usage_bytes as f64, &[]);
+        self.timing(
+            "response_time",
+            Duration::from_secs_f64(metrics.average_response_time_ms.max(0.0) / 1000.0),
+            &[],
+        );
+        self.flush().await
+    }
+}
+
+/// Replace characters that would break the line format with `_`
+fn sanitize(value: &str, reserved: &[char]) -> String {
+    value.replace(reserved, "_")
+}
+
+/// Join lines with newlines into packets of at most `max_size` bytes
+///
+/// A single line longer than `max_size` is sent in a packet of its own.
+fn pack_lines(lines: &[String], max_size: usize) -> Vec<String> {
+    let mut packets = Vec::new();
+    let mut packet = String::new();
+    for line in lines {
+        if !packet.is_empty() && packet.len() + 1 + line.len() > max_size {
+            packets.push(std::mem::take(&mut packet));
+        }
+        if !packet.is_empty() {
+            packet.push('\n');
+        }
+        packet.push_str(line);
+    }
+    if !packet.is_empty() {
+        packets.push(packet);
+    }
+    packets
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::observability::MonitoringConfig;
+
+    async fn exporter_with_receiver(config: StatsdConfig) -> (StatsdExporter, UdpSocket) {
+        let receiver = UdpSocket::bind("127.0.0.1:0").await.unwrap();
+        let exporter = StatsdExporter::new(StatsdConfig {
+            endpoint: receiver.local_addr().unwrap().to_string(),
+            ..config
+        })
+        .await
+        .unwrap();
+        (exporter, receiver)
+    }
+
+    async fn receive(receiver: &UdpSocket) -> String {
+        let mut buffer = vec![0u8; 65536];
+        let len = tokio::time::timeout(Duration::from_secs(5), receiver.recv(&mut buffer))
+            .await
+            .expect("packet should arrive")
+            .unwrap();
+        String::from_utf8(buffer[..len].to_vec()).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_dogstatsd_lines_carry_tags() {
+        let (exporter, receiver) = exporter_with_receiver(StatsdConfig {
+            tags: vec!["env:test".to_string()],
+            ..Default::default()
+        })
+        .await;
+
+        exporter.count("tool.calls", 3, &["tool:echo"]);
+        exporter.gauge("sessions", 2.5, &[]);
+        exporter.timing("latency", Duration::from_millis(12), &["tool:a|b"]);
+        assert_eq!(exporter.flush().await.unwrap(), 1);
+
+        let packet = receive(&receiver).await;
+        assert_eq!(
+            packet.lines().collect::<Vec<_>>(),
+            [
+                "mcp.tool.calls:3|c|#env:test,tool:echo",
+                "mcp.sessions:2.5|g|#env:test",
+                "mcp.latency:12|ms|#env:test,tool:a_b",
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_plain_statsd_drops_tags_and_batches_under_mtu() {
+        let (exporter, receiver) = exporter_with_receiver(StatsdConfig {
+            prefix: String::new(),
+            flavor: StatsdFlavor::Statsd,
+            tags: vec!["env:test".to_string()],
+            max_packet_size: 64,
+            ..Default::default()
+        })
+        .await;
+
+        for i in 0..10 {
+            exporter.count(&format!("counter_{i}"), i, &["ignored"]);
+        }
+        let packets = exporter.flush().await.unwrap();
+        assert!(packets > 1);
+
+        let mut lines = Vec::new();
+        for _ in 0..packets {
+            let packet = receive(&receiver).await;
+            assert!(packet.len() <= 64, "packet over limit: {packet:?}");
+            lines.extend(packet.lines().map(str::to_string));
+        }
+        let expected: Vec<String> = (0..10).map(|i| format!("counter_{i}:{i}|c")).collect();
+        assert_eq!(lines, expected);
+    }
+
+    #[tokio::test]
+    async fn test_export_sends_request_deltas() {
+        let (exporter, receiver) = exporter_with_receiver(StatsdConfig::default()).await;
+        let collector = MetricsCollector::new(MonitoringConfig::default());
+
+        exporter.export(&collector).await.unwrap();
+        let packet = receive(&receiver).await;
+        assert!(packet.lines().any(|line| line == "mcp.requests:0|c"));
+        assert!(
+            packet
+                .lines()
+                .any(|line| line.starts_with("mcp.response_time:") && line.ends_with("|ms"))
+        );
+        assert!(packet.lines().all(|line| line.starts_with("mcp.")));
+    }
+}
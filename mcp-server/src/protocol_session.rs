@@ -0,0 +1,368 @@
+//! Per-session protocol negotiation state
+//!
+//! Tracks the protocol version and client capabilities negotiated by
+//! `initialize` for each session, so a client can re-initialize mid-session
+//! (e.g. to upgrade from an older protocol version) without reconnecting.
+//! `initialize` handling is serialized per session, so two initializes
+//! arriving together (e.g. two clients sharing a stdio pipe) can't race.
+
+use pulseengine_mcp_protocol::{Error, ErrorCode, Implementation};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use tokio::sync::{Mutex, OwnedMutexGuard, RwLock};
+
+/// Session key used when the transport doesn't provide a session ID (e.g. stdio)
+pub const DEFAULT_SESSION_KEY: &str = "default";
+
+/// State negotiated for a single session
+#[derive(Debug, Clone)]
+pub struct ProtocolSession {
+    /// Negotiated protocol version
+    pub protocol_version: String,
+    /// Capabilities declared by the client
+    pub client_capabilities: serde_json::Value,
+    /// Client implementation info
+    pub client_info: Implementation,
+    /// Resource URIs subscribed under the current negotiation
+    pub subscriptions: HashSet<String>,
+    /// Number of times this session has been initialized
+    pub generation: u32,
+    /// Whether the client has sent `notifications/initialized`
+    pub client_initialized: bool,
+    in_flight: Arc<AtomicUsize>,
+}
+
+impl ProtocolSession {
+    /// Number of streaming requests (tool calls) currently executing
+    pub fn in_flight(&self) -> usize {
+        self.in_flight.load(Ordering::SeqCst)
+    }
+
+    /// Number of resources this session is subscribed to
+    pub fn subscription_count(&self) -> usize {
+        self.subscriptions.len()
+    }
+}
+
+/// Point-in-time statistics for a session
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ProtocolSessionStats {
+    pub protocol_version: String,
+    pub generation: u32,
+    pub in_flight: usize,
+    pub subscriptions: usize,
+    /// Configured per-session subscription cap (`None` when unbounded)
+    pub max_subscriptions: Option<usize>,
+}
+
+/// Outcome of (re-)initializing a session
+#[derive(Debug, Clone, Default)]
+pub struct Renegotiation {
+    /// Protocol version negotiated before this initialize, if any
+    pub previous_version: Option<String>,
+    /// Subscriptions dropped because the protocol version changed
+    pub invalidated_subscriptions: Vec<String>,
+}
+
+/// Guard counting an in-flight streaming request; decrements on drop
+pub struct InFlightGuard(Arc<AtomicUsize>);
+
+impl Drop for InFlightGuard {
+    fn drop(&mut self) {
+        self.0.fetch_sub(1, Ordering::SeqCst);
+    }
+}
+
+/// How a session handles an `initialize` that arrives while another is
+/// still being processed
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ConcurrentInitialize {
+    /// Fail the later request with an `InvalidRequest` error
+    #[default]
+    Reject,
+    /// Process the later request once the earlier one has finished, as a
+    /// re-initialization
+    Queue,
+}
+
+/// How requests arriving before the client's `notifications/initialized`
+/// are handled
+///
+/// `initialize`, `ping` and notifications are always accepted.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum InitializedStrictness {
+    /// Accept them silently
+    #[default]
+    Off,
+    /// Accept them but log a warning
+    Warn,
+    /// Fail them with an `InvalidRequest` error
+    Reject,
+}
+
+/// Held while a session's `initialize` is processed; releases on drop
+pub struct InitializeGuard {
+    guard: Option<OwnedMutexGuard<()>>,
+    lock: Arc<Mutex<()>>,
+    session_key: String,
+    initializing: Arc<std::sync::Mutex<HashMap<String, Arc<Mutex<()>>>>>,
+}
+
+impl Drop for InitializeGuard {
+    fn drop(&mut self) {
+        self.guard.take();
+        let mut initializing = self.initializing.lock().unwrap_or_else(|e| e.into_inner());
+        // Only the map and this guard still refer to the lock: nobody is queued
+        if Arc::strong_count(&self.lock) == 2 {
+            initializing.remove(&self.session_key);
+        }
+    }
+}
+
+/// Error for a subscription beyond the per-session cap
+fn subscription_limit_error(uri: &str, max: usize) -> Error {
+    Error::with_data(
+        ErrorCode::InvalidRequest,
+        format!(
+            "Cannot subscribe to {uri}: session already holds the maximum of {max} subscription(s)"
+        ),
+        serde_json::json!({ "uri": uri, "maxSubscriptions": max }),
+    )
+}
+
+/// Registry of negotiated sessions, shared across handler clones
+#[derive(Clone, Default)]
+pub struct ProtocolSessions {
+    sessions: Arc<RwLock<HashMap<String, ProtocolSession>>>,
+    /// Subscriptions of sessions that haven't sent `initialize`, counted
+    /// against the same cap and carried over once they do
+    uninitialized_subscriptions: Arc<RwLock<HashMap<String, HashSet<String>>>>,
+    max_subscriptions: Option<usize>,
+    initializing: Arc<std::sync::Mutex<HashMap<String, Arc<Mutex<()>>>>>,
+    concurrent_initialize: ConcurrentInitialize,
+}
+
+impl ProtocolSessions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Cap the number of resources a single session may subscribe to
+    pub fn with_max_subscriptions(mut self, max: usize) -> Self {
+        self.max_subscriptions = Some(max);
+        self
+    }
+
+    pub fn max_subscriptions(&self) -> Option<usize> {
+        self.max_subscriptions
+    }
+
+    /// Choose how an `initialize` racing another one for the same session is handled
+    pub fn with_concurrent_initialize(mut self, mode: ConcurrentInitialize) -> Self {
+        self.concurrent_initialize = mode;
+        self
+    }
+
+    pub fn concurrent_initialize(&self) -> ConcurrentInitialize {
+        self.concurrent_initialize
+    }
+
+    /// Claim a session's `initialize` slot for the duration of the returned guard
+    ///
+    /// While the slot is held, another claim for the same session is rejected
+    /// or waits, depending on [`ConcurrentInitialize`]. Other sessions are
+    /// unaffected.
+    pub async fn begin_initialize(&self, session_key: &str) -> Result<InitializeGuard, Error> {
+        let lock = self
+            .initializing
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .entry(session_key.to_string())
+            .or_default()
+            .clone();
+        let guard = match self.concurrent_initialize {
+            ConcurrentInitialize::Reject => lock.clone().try_lock_owned().map_err(|_| {
+                Error::invalid_request(
+                    "Another initialize request for this session is still being processed",
+                )
+            })?,
+            ConcurrentInitialize::Queue => lock.clone().lock_owned().await,
+        };
+        Ok(InitializeGuard {
+            guard: Some(guard),
+            lock,
+            session_key: session_key.to_string(),
+            initializing: self.initializing.clone(),
+        })
+    }
+
+    /// Get a snapshot of a session's negotiated state
+    pub async fn get(&self, session_key: &str) -> Option<ProtocolSession> {
+        self.sessions.read().await.get(session_key).cloned()
+    }
+
+    /// Record an `initialize` for a session
+    ///
+    /// Re-initializing with the same version keeps existing subscriptions.
+    /// Changing the version invalidates them, since they were established
+    /// under the old protocol semantics, and is rejected while streaming
+    /// requests are still in flight.
+    pub async fn initialize(
+        &self,
+        session_key: &str,
+        protocol_version: String,
+        client_capabilities: serde_json::Value,
+        client_info: Implementation,
+    ) -> Result<Renegotiation, Error> {
+        let mut sessions = self.sessions.write().await;
+
+        let Some(session) = sessions.get_mut(session_key) else {
+            let subscriptions = self
+                .uninitialized_subscriptions
+                .write()
+                .await
+                .remove(session_key)
+                .unwrap_or_default();
+            sessions.insert(
+                session_key.to_string(),
+                ProtocolSession {
+                    protocol_version,
+                    client_capabilities,
+                    client_info,
+                    subscriptions,
+                    generation: 1,
+                    client_initialized: false,
+                    in_flight: Arc::new(AtomicUsize::new(0)),
+                },
+            );
+            return Ok(Renegotiation::default());
+        };
+
+        let previous_version = session.protocol_version.clone();
+        let version_changed = previous_version != protocol_version;
+        let in_flight = session.in_flight();
+        if version_changed && in_flight > 0 {
+            return Err(Error::invalid_request(format!(
+                "Cannot re-initialize from {previous_version} to {protocol_version}: \
+                 {in_flight} request(s) still in flight"
+            )));
+        }
+
+        let invalidated_subscriptions = if version_changed {
+            session.subscriptions.drain().collect()
+        } else {
+            Vec::new()
+        };
+        session.protocol_version = protocol_version;
+        session.client_capabilities = client_capabilities;
+        session.client_info = client_info;
+        session.generation += 1;
+
+        Ok(Renegotiation {
+            previous_version: Some(previous_version),
+            invalidated_subscriptions,
+        })
+    }
+
+    /// Forget a session, e.g. once its connection closed
+    pub async fn remove(&self, session_key: &str) -> Option<ProtocolSession> {
+        self.uninitialized_subscriptions
+            .write()
+            .await
+            .remove(session_key);
+        self.sessions.write().await.remove(session_key)
+    }
+
+    /// Record the client's `notifications/initialized` for a session
+    ///
+    /// Returns `false` if the session hasn't sent `initialize` yet.
+    pub async fn mark_client_initialized(&self, session_key: &str) -> bool {
+        match self.sessions.write().await.get_mut(session_key) {
+            Some(session) => {
+                session.client_initialized = true;
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Get statistics for a session
+    pub async fn stats(&self, session_key: &str) -> Option<ProtocolSessionStats> {
+        let sessions = self.sessions.read().await;
+        let session = sessions.get(session_key)?;
+        Some(ProtocolSessionStats {
+            protocol_version: session.protocol_version.clone(),
+            generation: session.generation,
+            in_flight: session.in_flight(),
+            subscriptions: session.subscription_count(),
+            max_subscriptions: self.max_subscriptions,
+        })
+    }
+
+    /// Track a subscription against a session
+    ///
+    /// Returns whether the URI was newly added. Re-subscribing to a URI the
+    /// session already holds always succeeds; a new URI is rejected once the
+    /// session has reached the subscription cap. Sessions that haven't sent
+    /// `initialize` are capped the same way.
+    pub async fn add_subscription(&self, session_key: &str, uri: &str) -> Result<bool, Error> {
+        let mut sessions = self.sessions.write().await;
+        let mut uninitialized = self.uninitialized_subscriptions.write().await;
+        let subscriptions = match sessions.get_mut(session_key) {
+            Some(session) => &mut session.subscriptions,
+            None => uninitialized.entry(session_key.to_string()).or_default(),
+        };
+        if subscriptions.contains(uri) {
+            return Ok(false);
+        }
+        if let Some(max) = self.max_subscriptions
+            && subscriptions.len() >= max
+        {
+            return Err(subscription_limit_error(uri, max));
+        }
+        Ok(subscriptions.insert(uri.to_string()))
+    }
+
+    /// Remove a subscription from a session
+    pub async fn remove_subscription(&self, session_key: &str, uri: &str) {
+        if let Some(session) = self.sessions.write().await.get_mut(session_key) {
+            session.subscriptions.remove(uri);
+        }
+        let mut uninitialized = self.uninitialized_subscriptions.write().await;
+        if let Some(subscriptions) = uninitialized.get_mut(session_key) {
+            subscriptions.remove(uri);
+            if subscriptions.is_empty() {
+                uninitialized.remove(session_key);
+            }
+        }
+    }
+
+    /// Check whether any session still holds a subscription to a URI
+    pub async fn is_subscribed_elsewhere(&self, session_key: &str, uri: &str) -> bool {
+        let initialized = self
+            .sessions
+            .read()
+            .await
+            .iter()
+            .any(|(key, session)| key != session_key && session.subscriptions.contains(uri));
+        initialized
+            || self
+                .uninitialized_subscriptions
+                .read()
+                .await
+                .iter()
+                .any(|(key, subscriptions)| key != session_key && subscriptions.contains(uri))
+    }
+
+    /// Mark a streaming request as in flight for a session
+    pub async fn begin_request(&self, session_key: &str) -> Option<InFlightGuard> {
+        let sessions = self.sessions.read().await;
+        let counter = sessions.get(session_key)?.in_flight.clone();
+        counter.fetch_add(1, Ordering::SeqCst);
+        Some(InFlightGuard(counter))
+    }
+}
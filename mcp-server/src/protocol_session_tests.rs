@@ -0,0 +1,182 @@
+//! Tests for per-session protocol negotiation state
+
+use crate::protocol_session::*;
+use pulseengine_mcp_protocol::{ErrorCode, Implementation};
+use serde_json::json;
+
+fn client() -> Implementation {
+    Implementation::new("client", "1.0.0")
+}
+
+#[tokio::test]
+async fn test_first_initialize_creates_session() {
+    let sessions = ProtocolSessions::new();
+    let outcome = sessions
+        .initialize("s1", "2025-06-18".to_string(), json!({}), client())
+        .await
+        .unwrap();
+    assert!(outcome.previous_version.is_none());
+
+    let session = sessions.get("s1").await.unwrap();
+    assert_eq!(session.protocol_version, "2025-06-18");
+    assert_eq!(session.generation, 1);
+    assert!(sessions.get("s2").await.is_none());
+}
+
+#[tokio::test]
+async fn test_reinitialize_rejected_while_requests_in_flight() {
+    let sessions = ProtocolSessions::new();
+    sessions
+        .initialize("s1", "2025-06-18".to_string(), json!({}), client())
+        .await
+        .unwrap();
+
+    let guard = sessions.begin_request("s1").await.unwrap();
+    assert_eq!(sessions.get("s1").await.unwrap().in_flight(), 1);
+
+    let err = sessions
+        .initialize("s1", "2025-11-25".to_string(), json!({}), client())
+        .await
+        .unwrap_err();
+    assert_eq!(err.code, ErrorCode::InvalidRequest);
+    assert_eq!(
+        sessions.get("s1").await.unwrap().protocol_version,
+        "2025-06-18"
+    );
+
+    // Same-version refresh doesn't disrupt streams, so it is allowed
+    sessions
+        .initialize(
+            "s1",
+            "2025-06-18".to_string(),
+            json!({"roots": {}}),
+            client(),
+        )
+        .await
+        .unwrap();
+
+    drop(guard);
+    assert_eq!(sessions.get("s1").await.unwrap().in_flight(), 0);
+    let outcome = sessions
+        .initialize("s1", "2025-11-25".to_string(), json!({}), client())
+        .await
+        .unwrap();
+    assert_eq!(outcome.previous_version.as_deref(), Some("2025-06-18"));
+}
+
+#[tokio::test]
+async fn test_invalidated_subscriptions_are_reported() {
+    let sessions = ProtocolSessions::new();
+    for key in ["s1", "s2"] {
+        sessions
+            .initialize(key, "2025-06-18".to_string(), json!({}), client())
+            .await
+            .unwrap();
+        sessions
+            .add_subscription(key, "file://shared.txt")
+            .await
+            .unwrap();
+    }
+
+    let outcome = sessions
+        .initialize("s1", "2025-11-25".to_string(), json!({}), client())
+        .await
+        .unwrap();
+    assert_eq!(outcome.invalidated_subscriptions, vec!["file://shared.txt"]);
+    assert!(
+        sessions
+            .is_subscribed_elsewhere("s1", "file://shared.txt")
+            .await
+    );
+    assert!(sessions.begin_request("unknown").await.is_none());
+}
+
+#[tokio::test]
+async fn test_subscriptions_capped_per_session() {
+    let sessions = ProtocolSessions::new().with_max_subscriptions(2);
+    for key in ["s1", "s2"] {
+        sessions
+            .initialize(key, "2025-06-18".to_string(), json!({}), client())
+            .await
+            .unwrap();
+    }
+
+    assert!(sessions.add_subscription("s1", "file://a").await.unwrap());
+    assert!(sessions.add_subscription("s1", "file://b").await.unwrap());
+    let err = sessions
+        .add_subscription("s1", "file://c")
+        .await
+        .unwrap_err();
+    assert_eq!(err.code, ErrorCode::InvalidRequest);
+    assert_eq!(err.data.unwrap()["maxSubscriptions"], 2);
+
+    // Re-subscribing to a held URI doesn't count against the cap
+    assert!(!sessions.add_subscription("s1", "file://a").await.unwrap());
+    // The cap is per session
+    assert!(sessions.add_subscription("s2", "file://c").await.unwrap());
+
+    sessions.remove_subscription("s1", "file://b").await;
+    assert!(sessions.add_subscription("s1", "file://c").await.unwrap());
+
+    let stats = sessions.stats("s1").await.unwrap();
+    assert_eq!(stats.subscriptions, 2);
+    assert_eq!(stats.max_subscriptions, Some(2));
+    assert!(sessions.stats("unknown").await.is_none());
+}
+
+#[tokio::test]
+async fn test_subscriptions_capped_before_initialize() {
+    let sessions = ProtocolSessions::new().with_max_subscriptions(2);
+
+    assert!(sessions.add_subscription("s1", "file://a").await.unwrap());
+    assert!(sessions.add_subscription("s1", "file://b").await.unwrap());
+    assert!(sessions.add_subscription("s1", "file://c").await.is_err());
+    assert!(sessions.is_subscribed_elsewhere("s2", "file://a").await);
+
+    // The subscriptions carry over into the session once it initializes
+    sessions
+        .initialize("s1", "2025-06-18".to_string(), json!({}), client())
+        .await
+        .unwrap();
+    assert_eq!(sessions.stats("s1").await.unwrap().subscriptions, 2);
+    assert!(sessions.add_subscription("s1", "file://c").await.is_err());
+
+    sessions.remove("s1").await;
+    assert!(!sessions.is_subscribed_elsewhere("s2", "file://a").await);
+}
+
+#[tokio::test]
+async fn test_concurrent_initialize_rejected_per_session() {
+    let sessions = ProtocolSessions::new();
+    assert_eq!(
+        sessions.concurrent_initialize(),
+        ConcurrentInitialize::Reject
+    );
+
+    let guard = sessions.begin_initialize("s1").await.unwrap();
+    let error = sessions.begin_initialize("s1").await.err().unwrap();
+    assert_eq!(error.code, ErrorCode::InvalidRequest);
+    assert!(error.message.contains("still being processed"));
+
+    // Other sessions initialize independently
+    assert!(sessions.begin_initialize("s2").await.is_ok());
+
+    drop(guard);
+    assert!(sessions.begin_initialize("s1").await.is_ok());
+}
+
+#[tokio::test]
+async fn test_concurrent_initialize_queued() {
+    let sessions = ProtocolSessions::new().with_concurrent_initialize(ConcurrentInitialize::Queue);
+    let guard = sessions.begin_initialize("s1").await.unwrap();
+
+    let queued = tokio::spawn({
+        let sessions = sessions.clone();
+        async move { sessions.begin_initialize("s1").await.map(drop) }
+    });
+    tokio::time::sleep(std::time::Duration::from_millis(20)).await;
+    assert!(!queued.is_finished());
+
+    drop(guard);
+    queued.await.unwrap().unwrap();
+}
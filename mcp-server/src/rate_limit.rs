@@ -0,0 +1,105 @@
+//! Server-wide request rate limiting
+//!
+//! The authentication manager limits request rates per client, which does
+//! nothing when auth is disabled. [`GlobalRateLimiter`] caps the total request
+//! rate of the server with a token bucket, independently of auth, so
+//! unauthenticated and development servers are protected from floods too.
+
+use crate::context::RequestContext;
+use crate::middleware::Middleware;
+use async_trait::async_trait;
+use pulseengine_mcp_protocol::{Error, ErrorCode, Request};
+use serde::{Deserialize, Serialize};
+use std::sync::{Arc, Mutex};
+use std::time::Instant;
+use tracing::warn;
+
+/// Configuration for the server-wide request rate limit
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GlobalRateLimitConfig {
+    /// Sustained number of requests accepted per second
+    pub requests_per_second: f64,
+    /// Number of requests that may arrive at once on top of the sustained rate
+    pub burst: u32,
+}
+
+impl Default for GlobalRateLimitConfig {
+    fn default() -> Self {
+        Self {
+            requests_per_second: 100.0,
+            burst: 200,
+        }
+    }
+}
+
+struct Bucket {
+    tokens: f64,
+    refilled_at: Instant,
+}
+
+/// Token bucket rate limiter shared by every request the server handles
+#[derive(Clone)]
+pub struct GlobalRateLimiter {
+    config: GlobalRateLimitConfig,
+    bucket: Arc<Mutex<Bucket>>,
+}
+
+impl GlobalRateLimiter {
+    /// Create a limiter with a full bucket
+    pub fn new(config: GlobalRateLimitConfig) -> Self {
+        Self {
+            bucket: Arc::new(Mutex::new(Bucket {
+                tokens: f64::from(config.burst),
+                refilled_at: Instant::now(),
+            })),
+            config,
+        }
+    }
+
+    pub fn config(&self) -> &GlobalRateLimitConfig {
+        &self.config
+    }
+
+    /// Take a token for one request, returning a rate limit error when the
+    /// bucket is empty
+    pub fn try_acquire(&self) -> Result<(), Error> {
+        let mut bucket = self.bucket.lock().unwrap();
+        let now = Instant::now();
+        let elapsed = now.duration_since(bucket.refilled_at).as_secs_f64();
+        bucket.tokens = (bucket.tokens + elapsed * self.config.requests_per_second)
+            .min(f64::from(self.config.burst));
+        bucket.refilled_at = now;
+
+        if bucket.tokens >= 1.0 {
+            bucket.tokens -= 1.0;
+            return Ok(());
+        }
+
+        let retry_after_ms = if self.config.requests_per_second > 0.0 {
+            ((1.0 - bucket.tokens) / self.config.requests_per_second * 1000.0).ceil() as u64
+        } else {
+            u64::MAX
+        };
+        warn!(
+            requests_per_second = self.config.requests_per_second,
+            retry_after_ms, "Server rate limit exceeded"
+        );
+        Err(Error::with_data(
+            ErrorCode::RateLimitExceeded,
+            "Server rate limit exceeded",
+            serde_json::json!({ "retryAfterMs": retry_after_ms }),
+        ))
+    }
+}
+
+#[async_trait]
+impl Middleware for GlobalRateLimiter {
+    async fn on_request(
+        &self,
+        request: Request,
+        _context: &RequestContext,
+    ) -> std::result::Result<Request, Error> {
+        self.try_acquire()?;
+        Ok(request)
+    }
+}
@@ -0,0 +1,47 @@
+//! Tests for the server-wide request rate limit
+
+use crate::rate_limit::*;
+use pulseengine_mcp_protocol::ErrorCode;
+use std::time::Duration;
+
+fn limiter(requests_per_second: f64, burst: u32) -> GlobalRateLimiter {
+    GlobalRateLimiter::new(GlobalRateLimitConfig {
+        requests_per_second,
+        burst,
+    })
+}
+
+#[test]
+fn test_burst_then_rejection() {
+    let limiter = limiter(1.0, 3);
+
+    for _ in 0..3 {
+        assert!(limiter.try_acquire().is_ok());
+    }
+    let error = limiter.try_acquire().unwrap_err();
+    assert_eq!(error.code, ErrorCode::RateLimitExceeded);
+    let retry_after_ms = error.data.unwrap()["retryAfterMs"].as_u64().unwrap();
+    assert!(retry_after_ms > 0 && retry_after_ms <= 1000);
+}
+
+#[test]
+fn test_tokens_refill_at_sustained_rate() {
+    let limiter = limiter(50.0, 1);
+
+    assert!(limiter.try_acquire().is_ok());
+    assert!(limiter.try_acquire().is_err());
+
+    std::thread::sleep(Duration::from_millis(40));
+    assert!(limiter.try_acquire().is_ok());
+}
+
+#[test]
+fn test_clones_share_the_bucket() {
+    let limiter = limiter(0.0, 2);
+    let clone = limiter.clone();
+
+    assert!(limiter.try_acquire().is_ok());
+    assert!(clone.try_acquire().is_ok());
+    assert!(limiter.try_acquire().is_err());
+    assert!(clone.try_acquire().is_err());
+}
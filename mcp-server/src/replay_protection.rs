@@ -0,0 +1,161 @@
+//! Nonce-based request replay protection
+//!
+//! A captured request, even a signed one, stays valid if an attacker sends it
+//! again. With replay protection enabled, clients put a unique
+//! `params._meta.nonce` and an RFC 3339 `params._meta.timestamp` on every
+//! request. [`ReplayGuard`] rejects requests whose timestamp is outside the
+//! freshness window and nonces already seen within that window. A seen nonce
+//! is kept until its request's timestamp leaves the window, after which a
+//! replay fails the timestamp check anyway. The store is bounded; while it's
+//! full of live nonces, new requests are refused rather than forgetting a
+//! nonce that could still be replayed.
+
+use crate::context::RequestContext;
+use crate::middleware::Middleware;
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use pulseengine_mcp_protocol::{Error, Request};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeSet, HashSet};
+use std::sync::{Arc, Mutex};
+use tracing::warn;
+
+/// `_meta` key carrying the request nonce
+pub const NONCE_META_KEY: &str = "nonce";
+
+/// `_meta` key carrying the RFC 3339 time the request was issued
+pub const TIMESTAMP_META_KEY: &str = "timestamp";
+
+/// Configuration for request replay protection
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ReplayProtectionConfig {
+    /// Maximum age, and clock skew into the future, of a request timestamp
+    pub window_secs: u64,
+    /// Maximum number of live nonces remembered; requests are refused while
+    /// the store is full
+    pub max_nonces: usize,
+    /// Methods accepted without a nonce and timestamp
+    pub exempt_methods: Vec<String>,
+}
+
+impl Default for ReplayProtectionConfig {
+    fn default() -> Self {
+        Self {
+            window_secs: 300,
+            max_nonces: 100_000,
+            exempt_methods: vec!["initialize".to_string(), "ping".to_string()],
+        }
+    }
+}
+
+#[derive(Default)]
+struct SeenNonces {
+    /// Nonces by the time their request's timestamp leaves the window
+    expiries: BTreeSet<(DateTime<Utc>, String)>,
+    nonces: HashSet<String>,
+}
+
+/// Rejects replayed and stale requests
+#[derive(Clone)]
+pub struct ReplayGuard {
+    config: ReplayProtectionConfig,
+    seen: Arc<Mutex<SeenNonces>>,
+}
+
+impl ReplayGuard {
+    pub fn new(config: ReplayProtectionConfig) -> Self {
+        Self {
+            config,
+            seen: Arc::new(Mutex::new(SeenNonces::default())),
+        }
+    }
+
+    pub fn config(&self) -> &ReplayProtectionConfig {
+        &self.config
+    }
+
+    /// Check a request against the current time
+    pub fn check(&self, request: &Request) -> Result<(), Error> {
+        self.check_at(request, Utc::now())
+    }
+
+    /// Check a request as if received at `now`, recording its nonce if accepted
+    ///
+    /// # Errors
+    ///
+    /// Returns an unauthorized error if the nonce or timestamp is missing or
+    /// malformed, the timestamp is outside the window, or the nonce was
+    /// already used, and a server busy error if the nonce store is full
+    pub fn check_at(&self, request: &Request, now: DateTime<Utc>) -> Result<(), Error> {
+        if request.id.is_none()
+            || self
+                .config
+                .exempt_methods
+                .iter()
+                .any(|method| method == &request.method)
+        {
+            return Ok(());
+        }
+
+        let meta = request.params.get("_meta");
+        let nonce = meta
+            .and_then(|m| m.get(NONCE_META_KEY))
+            .and_then(|n| n.as_str())
+            .filter(|n| !n.is_empty())
+            .ok_or_else(|| Error::unauthorized("Request nonce missing"))?;
+        let timestamp = meta
+            .and_then(|m| m.get(TIMESTAMP_META_KEY))
+            .ok_or_else(|| Error::unauthorized("Request timestamp missing"))?
+            .as_str()
+            .and_then(|t| DateTime::parse_from_rfc3339(t).ok())
+            .ok_or_else(|| Error::unauthorized("Malformed request timestamp"))?
+            .with_timezone(&Utc);
+
+        let window = chrono::Duration::seconds(self.config.window_secs as i64);
+        if (now - timestamp).abs() > window {
+            warn!(method = %request.method, %timestamp, "Rejected stale request");
+            return Err(Error::unauthorized(format!(
+                "Request timestamp outside the allowed window of {}s",
+                self.config.window_secs
+            )));
+        }
+
+        let mut seen = self.seen.lock().unwrap();
+        while let Some((expires_at, _)) = seen.expiries.first() {
+            if *expires_at >= now {
+                break;
+            }
+            let (_, expired) = seen.expiries.pop_first().expect("first exists");
+            seen.nonces.remove(&expired);
+        }
+
+        if seen.nonces.contains(nonce) {
+            warn!(method = %request.method, "Rejected replayed request nonce");
+            return Err(Error::unauthorized("Request nonce already used"));
+        }
+        if seen.nonces.len() >= self.config.max_nonces {
+            warn!(method = %request.method, "Replay protection nonce store is full");
+            return Err(Error::server_busy(
+                "Too many recent requests to track; retry later",
+            ));
+        }
+
+        seen.nonces.insert(nonce.to_string());
+        seen.expiries
+            .insert((timestamp + window, nonce.to_string()));
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl Middleware for ReplayGuard {
+    async fn on_request(
+        &self,
+        request: Request,
+        _context: &RequestContext,
+    ) -> std::result::Result<Request, Error> {
+        self.check(&request)?;
+        Ok(request)
+    }
+}
@@ -0,0 +1,188 @@
+//! Tests for nonce-based request replay protection
+
+use crate::context::RequestContext;
+use crate::middleware::MiddlewareStack;
+use crate::replay_protection::*;
+use chrono::{DateTime, Duration, Utc};
+use pulseengine_mcp_protocol::{ErrorCode, NumberOrString, Request};
+use serde_json::json;
+
+fn request(method: &str, nonce: &str, timestamp: DateTime<Utc>) -> Request {
+    Request {
+        jsonrpc: "2.0".to_string(),
+        method: method.to_string(),
+        params: json!({
+            "name": "transfer",
+            "_meta": { "nonce": nonce, "timestamp": timestamp.to_rfc3339() }
+        }),
+        id: Some(NumberOrString::Number(1)),
+    }
+}
+
+fn guard(window_secs: u64, max_nonces: usize) -> ReplayGuard {
+    ReplayGuard::new(ReplayProtectionConfig {
+        window_secs,
+        max_nonces,
+        ..Default::default()
+    })
+}
+
+#[test]
+fn test_fresh_unique_requests_accepted_and_replay_rejected() {
+    let guard = guard(60, 100);
+    let now = Utc::now();
+
+    assert!(
+        guard
+            .check_at(&request("tools/call", "n-1", now), now)
+            .is_ok()
+    );
+    assert!(
+        guard
+            .check_at(&request("tools/call", "n-2", now), now)
+            .is_ok()
+    );
+
+    let replay = request("tools/call", "n-1", now);
+    let error = guard
+        .check_at(&replay, now + Duration::seconds(5))
+        .unwrap_err();
+    assert_eq!(error.code, ErrorCode::Unauthorized);
+    assert_eq!(error.message, "Request nonce already used");
+}
+
+#[test]
+fn test_stale_and_future_timestamps_rejected() {
+    let guard = guard(60, 100);
+    let now = Utc::now();
+
+    for timestamp in [now - Duration::seconds(61), now + Duration::seconds(61)] {
+        let error = guard
+            .check_at(&request("tools/call", "fresh-nonce", timestamp), now)
+            .unwrap_err();
+        assert_eq!(error.code, ErrorCode::Unauthorized);
+        assert!(error.message.contains("outside the allowed window of 60s"));
+    }
+    // A rejected request doesn't burn its nonce
+    assert!(
+        guard
+            .check_at(&request("tools/call", "fresh-nonce", now), now)
+            .is_ok()
+    );
+}
+
+#[test]
+fn test_missing_or_malformed_meta_rejected() {
+    let guard = guard(60, 100);
+    let mut missing = request("tools/call", "n", Utc::now());
+    missing.params = json!({ "name": "transfer" });
+    assert_eq!(
+        guard.check(&missing).unwrap_err().message,
+        "Request nonce missing"
+    );
+
+    let mut malformed = request("tools/call", "n", Utc::now());
+    malformed.params["_meta"]["timestamp"] = json!("yesterday");
+    assert_eq!(
+        guard.check(&malformed).unwrap_err().message,
+        "Malformed request timestamp"
+    );
+
+    // Exempt methods and notifications need no nonce
+    missing.method = "ping".to_string();
+    assert!(guard.check(&missing).is_ok());
+    missing.method = "tools/call".to_string();
+    missing.id = None;
+    assert!(guard.check(&missing).is_ok());
+}
+
+#[test]
+fn test_full_nonce_store_refuses_instead_of_evicting() {
+    let guard = guard(60, 2);
+    let now = Utc::now();
+    for nonce in ["a", "b"] {
+        assert!(
+            guard
+                .check_at(&request("tools/call", nonce, now), now)
+                .is_ok()
+        );
+    }
+    // Live nonces are never evicted, so a full store refuses new requests
+    let error = guard
+        .check_at(&request("tools/call", "c", now), now)
+        .unwrap_err();
+    assert_eq!(error.code, ErrorCode::ServerBusy);
+    assert!(
+        guard
+            .check_at(&request("tools/call", "a", now), now)
+            .is_err()
+    );
+
+    // Once their requests leave the window, the slots free up again
+    let later = now + Duration::seconds(61);
+    assert!(
+        guard
+            .check_at(&request("tools/call", "c", later), later)
+            .is_ok()
+    );
+}
+
+#[test]
+fn test_future_dated_nonce_kept_until_its_timestamp_expires() {
+    let guard = guard(60, 100);
+    let now = Utc::now();
+    let future = now + Duration::seconds(50);
+    assert!(
+        guard
+            .check_at(&request("tools/call", "early", future), now)
+            .is_ok()
+    );
+
+    // Received 100s later the timestamp is still fresh, so the nonce must be too
+    let later = now + Duration::seconds(100);
+    assert_eq!(
+        guard
+            .check_at(&request("tools/call", "early", future), later)
+            .unwrap_err()
+            .message,
+        "Request nonce already used"
+    );
+}
+
+#[test]
+fn test_expired_nonces_are_forgotten() {
+    let guard = guard(60, 100);
+    let issued = Utc::now();
+    assert!(
+        guard
+            .check_at(&request("tools/call", "n", issued), issued)
+            .is_ok()
+    );
+
+    // Past the window the nonce is forgotten, but the old timestamp still fails
+    let later = issued + Duration::seconds(120);
+    let error = guard
+        .check_at(&request("tools/call", "n", issued), later)
+        .unwrap_err();
+    assert!(error.message.contains("outside the allowed window"));
+    assert!(
+        guard
+            .check_at(&request("tools/call", "n", later), later)
+            .is_ok()
+    );
+}
+
+#[tokio::test]
+async fn test_middleware_stack_rejects_replay() {
+    let stack = MiddlewareStack::new().with_replay_protection(guard(60, 100));
+    let context = RequestContext::new();
+    let signed = request("tools/call", "once", Utc::now());
+
+    assert!(
+        stack
+            .process_request(signed.clone(), &context)
+            .await
+            .is_ok()
+    );
+    assert!(stack.process_request(signed, &context).await.is_err());
+}
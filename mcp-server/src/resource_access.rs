@@ -0,0 +1,86 @@
+//! Per-resource access control for `resources/read`
+//!
+//! A backend declares the permission a resource needs through
+//! [`McpBackend::resource_permission`](crate::McpBackend::resource_permission),
+//! which `#[mcp_resource(requires_permission = "...")]` generates. Before the
+//! backend reads anything, the handler checks that permission against the
+//! caller's [`AuthContext`] along with an optional allowlist of URI schemes.
+//! Callers are identified by [`with_auth_context`](crate::with_auth_context)
+//! in process, and over transports by the handler's
+//! [`with_caller_auth`](crate::GenericServerHandler::with_caller_auth); without
+//! either every caller is unauthenticated.
+
+use pulseengine_auth::AuthContext;
+use pulseengine_auth::permissions::PermissionChecker;
+use pulseengine_mcp_protocol::Error;
+
+/// Rules applied to every resource read
+#[derive(Debug, Clone, Default)]
+pub struct ResourceAccessPolicy {
+    /// URI schemes resources may be read from (`None` = any scheme)
+    allowed_schemes: Option<Vec<String>>,
+}
+
+impl ResourceAccessPolicy {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Only allow reads of URIs with one of these schemes (e.g. `"db"`, `"file"`)
+    pub fn with_allowed_schemes<I, S>(mut self, schemes: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.allowed_schemes = Some(
+            schemes
+                .into_iter()
+                .map(|scheme| scheme.into().trim_end_matches("://").to_ascii_lowercase())
+                .collect(),
+        );
+        self
+    }
+
+    pub fn allowed_schemes(&self) -> Option<&[String]> {
+        self.allowed_schemes.as_deref()
+    }
+
+    /// Check whether `caller` may read `uri`
+    ///
+    /// The scheme allowlist applies to everyone. A required permission is
+    /// denied to unauthenticated callers and to callers whose roles and
+    /// granted permissions don't include it.
+    pub fn authorize(
+        &self,
+        uri: &str,
+        required_permission: Option<&str>,
+        caller: Option<&AuthContext>,
+    ) -> Result<(), Error> {
+        if let Some(allowed) = &self.allowed_schemes {
+            let scheme = uri
+                .split_once(':')
+                .map(|(scheme, _)| scheme.to_ascii_lowercase())
+                .unwrap_or_default();
+            if !allowed.contains(&scheme) {
+                return Err(Error::forbidden(format!(
+                    "Resource scheme '{scheme}' is not allowed: {uri}"
+                )));
+            }
+        }
+
+        let Some(permission) = required_permission else {
+            return Ok(());
+        };
+        let granted = caller.is_some_and(|caller| {
+            caller.has_permission(permission)
+                || PermissionChecker::check(permission, &caller.permissions)
+        });
+        if granted {
+            Ok(())
+        } else {
+            Err(Error::forbidden(format!(
+                "Permission '{permission}' is required to read {uri}"
+            )))
+        }
+    }
+}
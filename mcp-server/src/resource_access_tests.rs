@@ -0,0 +1,129 @@
+//! Tests for per-resource access control
+
+use crate::resource_access::*;
+use pulseengine_auth::{AuthContext, Role};
+use pulseengine_mcp_protocol::error::ErrorCode;
+
+fn caller(roles: Vec<Role>, permissions: &[&str]) -> AuthContext {
+    AuthContext {
+        user_id: Some("user".to_string()),
+        roles,
+        api_key_id: None,
+        permissions: permissions.iter().map(|p| p.to_string()).collect(),
+    }
+}
+
+const CUSTOMERS: &str = "resource:db://customers/*";
+
+#[test]
+fn test_no_requirement_allows_anyone() {
+    let policy = ResourceAccessPolicy::new();
+    assert!(policy.authorize("db://customers/1", None, None).is_ok());
+}
+
+#[test]
+fn test_required_permission_denies_unauthenticated_caller() {
+    let policy = ResourceAccessPolicy::new();
+    let error = policy
+        .authorize("db://customers/1", Some(CUSTOMERS), None)
+        .unwrap_err();
+    assert_eq!(error.code, ErrorCode::Forbidden);
+    assert!(error.message.contains(CUSTOMERS));
+}
+
+#[test]
+fn test_required_permission_checks_roles_and_grants() {
+    let policy = ResourceAccessPolicy::new();
+    let uri = "db://customers/1";
+
+    let lacking = caller(vec![Role::Monitor], &["resource:db://orders/*"]);
+    assert!(
+        policy
+            .authorize(uri, Some(CUSTOMERS), Some(&lacking))
+            .is_err()
+    );
+
+    let by_role = caller(
+        vec![Role::Custom {
+            permissions: vec![CUSTOMERS.to_string()],
+        }],
+        &[],
+    );
+    assert!(
+        policy
+            .authorize(uri, Some(CUSTOMERS), Some(&by_role))
+            .is_ok()
+    );
+
+    let by_grant = caller(vec![], &[CUSTOMERS]);
+    assert!(
+        policy
+            .authorize(uri, Some(CUSTOMERS), Some(&by_grant))
+            .is_ok()
+    );
+
+    let admin = caller(vec![Role::Admin], &[]);
+    assert!(policy.authorize(uri, Some(CUSTOMERS), Some(&admin)).is_ok());
+}
+
+#[test]
+fn test_wildcard_grants_and_denials() {
+    let policy = ResourceAccessPolicy::new();
+    let uri = "db://customers/1";
+
+    let wildcard = caller(vec![], &["resource:*"]);
+    assert!(
+        policy
+            .authorize(uri, Some(CUSTOMERS), Some(&wildcard))
+            .is_ok()
+    );
+
+    let denied = caller(vec![], &["resource:*", "!resource:db:*"]);
+    assert!(
+        policy
+            .authorize(uri, Some(CUSTOMERS), Some(&denied))
+            .is_err()
+    );
+}
+
+#[test]
+fn test_scheme_allowlist_applies_to_everyone() {
+    let policy = ResourceAccessPolicy::new().with_allowed_schemes(["db", "file://"]);
+    assert_eq!(
+        policy.allowed_schemes(),
+        Some(&["db".to_string(), "file".to_string()][..])
+    );
+    let admin = caller(vec![Role::Admin], &[]);
+
+    assert!(policy.authorize("DB://customers/1", None, None).is_ok());
+    assert!(policy.authorize("file:///etc/hosts", None, None).is_ok());
+
+    let error = policy
+        .authorize("http://internal/metrics", None, Some(&admin))
+        .unwrap_err();
+    assert_eq!(error.code, ErrorCode::Forbidden);
+    assert!(error.message.contains("'http'"));
+    assert!(policy.authorize("no-scheme", None, Some(&admin)).is_err());
+}
+
+#[test]
+fn test_scheme_allowlist_combines_with_permission() {
+    let policy = ResourceAccessPolicy::new().with_allowed_schemes(["db"]);
+    let permitted = caller(vec![], &[CUSTOMERS]);
+
+    assert!(
+        policy
+            .authorize("db://customers/1", Some(CUSTOMERS), Some(&permitted))
+            .is_ok()
+    );
+    assert!(
+        policy
+            .authorize("db://customers/1", Some(CUSTOMERS), None)
+            .is_err()
+    );
+    assert!(
+        policy
+            .authorize("s3://customers/1", Some(CUSTOMERS), Some(&permitted))
+            .is_err()
+    );
+}
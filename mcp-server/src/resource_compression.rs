@@ -0,0 +1,87 @@
+//! Pre-compression of large resource payloads
+//!
+//! UI resources (HTML) and other large resource reads are sizable, especially
+//! over long-lived WebSocket connections. When enabled, `resources/read`
+//! results are gzip-compressed and marked with `_meta.contentEncoding` for
+//! clients that advertise support via `_meta.acceptEncoding` on the request.
+//! Clients that don't advertise support always receive uncompressed text.
+
+use pulseengine_mcp_protocol::encoding::{self, GZIP};
+use pulseengine_mcp_protocol::{ReadResourceResult, ResourceContents, uri_schemes};
+use serde::{Deserialize, Serialize};
+
+/// Configuration for resource payload compression
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ResourceCompressionConfig {
+    /// Only compress text contents at least this many bytes long
+    pub min_size_bytes: usize,
+    /// gzip compression level (0-9)
+    pub level: u32,
+    /// Restrict compression to `ui://` resources
+    pub ui_resources_only: bool,
+}
+
+impl Default for ResourceCompressionConfig {
+    fn default() -> Self {
+        Self {
+            min_size_bytes: 8 * 1024,
+            level: 6,
+            ui_resources_only: false,
+        }
+    }
+}
+
+impl ResourceCompressionConfig {
+    /// Configuration that only compresses `ui://` resources
+    pub fn ui_only() -> Self {
+        Self {
+            ui_resources_only: true,
+            ..Self::default()
+        }
+    }
+
+    /// Check whether a request's `_meta.acceptEncoding` allows compression
+    pub fn client_accepts(&self, request_params: &serde_json::Value) -> bool {
+        request_params
+            .get("_meta")
+            .and_then(|m| m.get("acceptEncoding"))
+            .is_some_and(|accept| encoding::accepts_encoding(accept, GZIP))
+    }
+
+    /// Check whether a single resource contents entry should be compressed
+    pub fn should_compress(&self, contents: &ResourceContents) -> bool {
+        if contents.is_encoded() {
+            return false;
+        }
+        if self.ui_resources_only && !contents.uri.starts_with(uri_schemes::UI) {
+            return false;
+        }
+        contents
+            .text
+            .as_ref()
+            .is_some_and(|text| text.len() >= self.min_size_bytes)
+    }
+
+    /// Compress all eligible contents of a read result
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if compression fails
+    pub fn compress(
+        &self,
+        result: ReadResourceResult,
+    ) -> pulseengine_mcp_protocol::Result<ReadResourceResult> {
+        let ReadResourceResult { contents, _meta } = result;
+        let contents = contents
+            .into_iter()
+            .map(|c| {
+                if self.should_compress(&c) {
+                    c.gzip(self.level)
+                } else {
+                    Ok(c)
+                }
+            })
+            .collect::<pulseengine_mcp_protocol::Result<Vec<_>>>()?;
+        Ok(ReadResourceResult { contents, _meta })
+    }
+}
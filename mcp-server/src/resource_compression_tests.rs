@@ -0,0 +1,208 @@
+//! Tests for resource payload compression
+
+use crate::backend::{BackendError, McpBackend};
+use crate::handler::GenericServerHandler;
+use crate::middleware::MiddlewareStack;
+use crate::resource_compression::ResourceCompressionConfig;
+use async_trait::async_trait;
+use pulseengine_auth::AuthenticationManager;
+use pulseengine_mcp_protocol::encoding::GZIP;
+use pulseengine_mcp_protocol::*;
+use serde_json::{Value, json};
+use std::sync::Arc;
+
+fn large_html() -> String {
+    let cards: String = (0..400)
+        .map(|i| format!("<div class=\"card\"><h2>Widget {i}</h2><p>Status: ok</p></div>"))
+        .collect();
+    format!("<!DOCTYPE html><html><head><title>Dashboard</title></head><body>{cards}</body></html>")
+}
+
+#[derive(Clone)]
+struct UiBackend;
+
+#[async_trait]
+impl McpBackend for UiBackend {
+    type Error = BackendError;
+    type Config = ();
+
+    async fn initialize(_: ()) -> std::result::Result<Self, Self::Error> {
+        Ok(UiBackend)
+    }
+
+    fn get_server_info(&self) -> ServerInfo {
+        ServerInfo {
+            protocol_version: ProtocolVersion::default(),
+            capabilities: ServerCapabilities::builder().enable_resources().build(),
+            server_info: Implementation::new("ui-backend", "1.0.0"),
+            instructions: None,
+        }
+    }
+
+    async fn health_check(&self) -> std::result::Result<(), Self::Error> {
+        Ok(())
+    }
+
+    async fn list_tools(
+        &self,
+        _: PaginatedRequestParam,
+    ) -> std::result::Result<ListToolsResult, Self::Error> {
+        Ok(ListToolsResult {
+            tools: vec![],
+            next_cursor: None,
+        })
+    }
+
+    async fn call_tool(
+        &self,
+        _: CallToolRequestParam,
+    ) -> std::result::Result<CallToolResult, Self::Error> {
+        Err(BackendError::not_supported("no tools"))
+    }
+
+    async fn list_resources(
+        &self,
+        _: PaginatedRequestParam,
+    ) -> std::result::Result<ListResourcesResult, Self::Error> {
+        Ok(ListResourcesResult {
+            resources: vec![],
+            next_cursor: None,
+        })
+    }
+
+    async fn read_resource(
+        &self,
+        request: ReadResourceRequestParam,
+    ) -> std::result::Result<ReadResourceResult, Self::Error> {
+        let contents = match request.uri.as_str() {
+            "ui://dashboard" => ResourceContents::html_ui(&request.uri, large_html()),
+            "file://big.txt" => ResourceContents::text(&request.uri, "x".repeat(64 * 1024)),
+            _ => ResourceContents::text(&request.uri, "small"),
+        };
+        Ok(ReadResourceResult {
+            contents: vec![contents],
+            _meta: None,
+        })
+    }
+
+    async fn list_prompts(
+        &self,
+        _: PaginatedRequestParam,
+    ) -> std::result::Result<ListPromptsResult, Self::Error> {
+        Ok(ListPromptsResult {
+            prompts: vec![],
+            next_cursor: None,
+        })
+    }
+
+    async fn get_prompt(
+        &self,
+        _: GetPromptRequestParam,
+    ) -> std::result::Result<GetPromptResult, Self::Error> {
+        Err(BackendError::not_supported("no prompts"))
+    }
+}
+
+fn handler(config: ResourceCompressionConfig) -> GenericServerHandler<UiBackend> {
+    GenericServerHandler::new(
+        Arc::new(UiBackend),
+        Arc::new(AuthenticationManager::new_disabled()),
+        MiddlewareStack::new(),
+    )
+    .with_resource_compression(config)
+}
+
+async fn read(handler: &GenericServerHandler<UiBackend>, params: Value) -> Value {
+    let response = handler
+        .handle_request(Request {
+            jsonrpc: "2.0".to_string(),
+            method: "resources/read".to_string(),
+            params,
+            id: Some(NumberOrString::Number(1)),
+        })
+        .await
+        .unwrap();
+    assert!(response.error.is_none());
+    response.result.unwrap()
+}
+
+#[tokio::test]
+async fn test_large_ui_resource_is_compressed_for_supporting_client() {
+    let handler = handler(ResourceCompressionConfig::default());
+    let html = large_html();
+
+    let result = read(
+        &handler,
+        json!({"uri": "ui://dashboard", "_meta": {"acceptEncoding": ["gzip"]}}),
+    )
+    .await;
+
+    // Wire payload is compressed and marked with the encoding hint
+    let wire = &result["contents"][0];
+    assert_eq!(wire["_meta"]["contentEncoding"], GZIP);
+    assert_eq!(wire["mimeType"], mime_types::HTML_MCP);
+    assert!(wire.get("text").is_none());
+    assert!(wire["blob"].as_str().unwrap().len() < html.len());
+
+    // Client-side decoding restores the original HTML exactly
+    let parsed: ReadResourceResult = serde_json::from_value(result).unwrap();
+    let decoded = parsed.contents[0].decode().unwrap();
+    assert_eq!(decoded.text.as_deref(), Some(html.as_str()));
+    assert_eq!(decoded.mime_type.as_deref(), Some(mime_types::HTML_MCP));
+}
+
+#[tokio::test]
+async fn test_non_supporting_client_receives_uncompressed() {
+    let handler = handler(ResourceCompressionConfig::default());
+
+    let result = read(&handler, json!({"uri": "ui://dashboard"})).await;
+    assert_eq!(result["contents"][0]["text"], large_html());
+    assert!(result["contents"][0].get("_meta").is_none());
+
+    let result = read(
+        &handler,
+        json!({"uri": "ui://dashboard", "_meta": {"acceptEncoding": "identity"}}),
+    )
+    .await;
+    assert_eq!(result["contents"][0]["text"], large_html());
+}
+
+#[tokio::test]
+async fn test_small_payloads_are_not_compressed() {
+    let handler = handler(ResourceCompressionConfig::default());
+
+    let result = read(
+        &handler,
+        json!({"uri": "file://small.txt", "_meta": {"acceptEncoding": "gzip"}}),
+    )
+    .await;
+    assert_eq!(result["contents"][0]["text"], "small");
+}
+
+#[tokio::test]
+async fn test_ui_only_skips_other_schemes() {
+    let handler = handler(ResourceCompressionConfig::ui_only());
+    let accept = json!({"acceptEncoding": "gzip"});
+
+    let result = read(&handler, json!({"uri": "file://big.txt", "_meta": accept})).await;
+    assert!(result["contents"][0]["text"].is_string());
+
+    let result = read(&handler, json!({"uri": "ui://dashboard", "_meta": accept})).await;
+    assert_eq!(result["contents"][0]["_meta"]["contentEncoding"], GZIP);
+}
+
+#[tokio::test]
+async fn test_compression_disabled_by_default() {
+    let handler = GenericServerHandler::new(
+        Arc::new(UiBackend),
+        Arc::new(AuthenticationManager::new_disabled()),
+        MiddlewareStack::new(),
+    );
+
+    let result = read(
+        &handler,
+        json!({"uri": "ui://dashboard", "_meta": {"acceptEncoding": "gzip"}}),
+    )
+    .await;
+    assert!(result["contents"][0]["text"].is_string());
+}
@@ -0,0 +1,238 @@
+//! Per-connection resource subscriptions and update fan-out
+//!
+//! [`ResourceSubscriptionManager`] records which sessions subscribed to which
+//! resource URIs via `resources/subscribe`. When a resource changes, the
+//! backend calls [`ResourceSubscriptionManager::notify_resource_changed`] and
+//! `notifications/resources/updated` is sent to the subscribed sessions only.
+//!
+//! ```rust,ignore
+//! let subscriptions = ResourceSubscriptionManager::new();
+//! let backend = MyBackend::new(subscriptions.clone());
+//! let config = ServerConfig {
+//!     resource_subscriptions: Some(subscriptions),
+//!     ..Default::default()
+//! };
+//!
+//! // Later, inside the backend
+//! self.subscriptions.notify_resource_changed("file:///config.json").await?;
+//! ```
+
+use crate::notification_retry::NotificationDelivery;
+use crate::protocol_session::DEFAULT_SESSION_KEY;
+use pulseengine_mcp_protocol::{Error, ResourceUpdatedNotification, UriFilter};
+use pulseengine_mcp_transport::{Transport, TransportError};
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::{Arc, RwLock as StdRwLock};
+use tokio::sync::RwLock;
+use tracing::{debug, warn};
+
+/// Method of the notification sent when a subscribed resource changes
+pub const RESOURCE_UPDATED_METHOD: &str = "notifications/resources/updated";
+
+/// Where update notifications are sent once a transport is running
+#[derive(Clone)]
+struct Outlet {
+    transport: Arc<dyn Transport>,
+    delivery: Option<NotificationDelivery>,
+}
+
+/// Subscribed URI -> session -> optional filter widening the subscription
+type SubscriptionTable = HashMap<String, HashMap<String, Option<UriFilter>>>;
+
+/// Registry of resource subscriptions per session, shared across clones
+///
+/// A subscription with a [`UriFilter`] covers every resource matching the
+/// filter rather than its own URI. Create one up front and hand clones to
+/// both the backend and [`ServerConfig`](crate::ServerConfig) so the backend
+/// can notify the sessions the server tracks.
+#[derive(Clone, Default)]
+pub struct ResourceSubscriptionManager {
+    subscriptions: Arc<RwLock<SubscriptionTable>>,
+    outlet: Arc<StdRwLock<Option<Outlet>>>,
+}
+
+impl fmt::Debug for ResourceSubscriptionManager {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ResourceSubscriptionManager")
+            .field("attached", &self.outlet.read().unwrap().is_some())
+            .finish_non_exhaustive()
+    }
+}
+
+impl ResourceSubscriptionManager {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Send update notifications through `transport`
+    pub(crate) fn attach(
+        &self,
+        transport: Arc<dyn Transport>,
+        delivery: Option<NotificationDelivery>,
+    ) {
+        *self.outlet.write().unwrap() = Some(Outlet {
+            transport,
+            delivery,
+        });
+    }
+
+    /// Record a session's subscription, returning whether it is new
+    ///
+    /// Subscribing again replaces the filter of the existing subscription.
+    pub async fn subscribe(&self, session_key: &str, uri: &str, filter: Option<UriFilter>) -> bool {
+        self.subscriptions
+            .write()
+            .await
+            .entry(uri.to_string())
+            .or_default()
+            .insert(session_key.to_string(), filter)
+            .is_none()
+    }
+
+    /// Drop a session's subscription, returning whether it existed
+    pub async fn unsubscribe(&self, session_key: &str, uri: &str) -> bool {
+        let mut subscriptions = self.subscriptions.write().await;
+        let Some(sessions) = subscriptions.get_mut(uri) else {
+            return false;
+        };
+        let removed = sessions.remove(session_key).is_some();
+        if sessions.is_empty() {
+            subscriptions.remove(uri);
+        }
+        removed
+    }
+
+    /// Drop every subscription of a session
+    ///
+    /// The server calls this when the transport reports the session's
+    /// connection closed.
+    ///
+    /// Returns the URIs the session was subscribed to.
+    pub async fn remove_session(&self, session_key: &str) -> Vec<String> {
+        let mut subscriptions = self.subscriptions.write().await;
+        let mut removed = Vec::new();
+        subscriptions.retain(|uri, sessions| {
+            if sessions.remove(session_key).is_some() {
+                removed.push(uri.clone());
+            }
+            !sessions.is_empty()
+        });
+        removed.sort();
+        removed
+    }
+
+    /// URIs with at least one subscribed session
+    pub async fn subscribed_uris(&self) -> Vec<String> {
+        let mut uris: Vec<String> = self.subscriptions.read().await.keys().cloned().collect();
+        uris.sort();
+        uris
+    }
+
+    /// Sessions whose subscriptions cover `uri`, sorted
+    pub async fn subscribers(&self, uri: &str) -> Vec<String> {
+        let subscriptions = self.subscriptions.read().await;
+        let mut sessions: Vec<String> = subscriptions
+            .iter()
+            .flat_map(|(subscribed, sessions)| {
+                sessions
+                    .iter()
+                    .filter(move |(_, filter)| match filter {
+                        Some(filter) => filter.matches(uri),
+                        None => subscribed == uri,
+                    })
+                    .map(|(session, _)| session.clone())
+            })
+            .collect();
+        sessions.sort();
+        sessions.dedup();
+        sessions
+    }
+
+    /// Whether any session's subscription covers `uri`
+    pub async fn is_subscribed(&self, uri: &str) -> bool {
+        !self.subscribers(uri).await.is_empty()
+    }
+
+    /// Notify the sessions subscribed to `uri` that it changed
+    ///
+    /// Clients re-read the resource on receipt. Returns the number of
+    /// sessions notified; see [`Self::notify`].
+    pub async fn notify_resource_changed(&self, uri: &str) -> Result<usize, Error> {
+        self.notify(ResourceUpdatedNotification::full(uri)).await
+    }
+
+    /// Send `update` to every session subscribed to its URI
+    ///
+    /// Returns the number of sessions notified, zero when nobody is
+    /// subscribed. A failed send doesn't stop delivery to the remaining
+    /// sessions; the first failure is returned once all were attempted.
+    /// Sessions the transport no longer knows are dropped along with their
+    /// subscriptions rather than counted as failures.
+    ///
+    /// # Errors
+    ///
+    /// Returns an internal error when there are subscribers but no running
+    /// transport, or when sending to any live session failed
+    pub async fn notify(&self, update: ResourceUpdatedNotification) -> Result<usize, Error> {
+        let sessions = self.subscribers(&update.uri).await;
+        if sessions.is_empty() {
+            return Ok(0);
+        }
+        let outlet = self
+            .outlet
+            .read()
+            .unwrap()
+            .clone()
+            .ok_or_else(|| Error::internal_error("No transport available for notifications"))?;
+        let params = serde_json::to_value(&update)?;
+
+        let mut sent = 0;
+        let mut first_error = None;
+        for session in &sessions {
+            let session_id = (session != DEFAULT_SESSION_KEY).then_some(session.as_str());
+            let result = match &outlet.delivery {
+                Some(delivery) => {
+                    delivery
+                        .send(
+                            outlet.transport.as_ref(),
+                            session_id,
+                            RESOURCE_UPDATED_METHOD,
+                            params.clone(),
+                        )
+                        .await
+                }
+                None => {
+                    outlet
+                        .transport
+                        .send_notification(session_id, RESOURCE_UPDATED_METHOD, params.clone())
+                        .await
+                }
+            };
+            match result {
+                Ok(()) => sent += 1,
+                Err(TransportError::SessionNotFound(_)) => {
+                    debug!(session = %session, "Dropping subscriptions of closed session");
+                    self.remove_session(session).await;
+                }
+                Err(e) => {
+                    warn!(uri = %update.uri, session = %session, error = %e, "Failed to send resource update");
+                    first_error.get_or_insert(e);
+                }
+            }
+        }
+
+        if let Some(e) = first_error {
+            return Err(Error::internal_error(format!(
+                "Failed to send resource update: {e}"
+            )));
+        }
+        debug!(
+            uri = %update.uri,
+            sessions = sent,
+            patched = update.patch.is_some(),
+            "Sent resource update"
+        );
+        Ok(sent)
+    }
+}
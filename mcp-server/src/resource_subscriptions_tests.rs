@@ -0,0 +1,226 @@
+//! Tests for per-session resource subscriptions
+
+use crate::resource_subscriptions::*;
+use async_trait::async_trait;
+use pulseengine_mcp_protocol::{ErrorCode, UriFilter};
+use pulseengine_mcp_transport::{RequestHandler, Transport, TransportError};
+use std::sync::{Arc, Mutex};
+
+/// Transport recording which session each notification was sent to
+#[derive(Default)]
+struct SessionRecorder {
+    sent: Mutex<Vec<(Option<String>, String)>>,
+    closed: Vec<&'static str>,
+    failing: Vec<&'static str>,
+}
+
+#[async_trait]
+impl Transport for SessionRecorder {
+    async fn start(&mut self, _handler: RequestHandler) -> Result<(), TransportError> {
+        Ok(())
+    }
+
+    async fn stop(&mut self) -> Result<(), TransportError> {
+        Ok(())
+    }
+
+    async fn health_check(&self) -> Result<(), TransportError> {
+        Ok(())
+    }
+
+    async fn send_notification(
+        &self,
+        session_id: Option<&str>,
+        method: &str,
+        params: serde_json::Value,
+    ) -> Result<(), TransportError> {
+        assert_eq!(method, RESOURCE_UPDATED_METHOD);
+        if let Some(session) = session_id
+            && self.closed.contains(&session)
+        {
+            return Err(TransportError::SessionNotFound(session.to_string()));
+        }
+        if let Some(session) = session_id
+            && self.failing.contains(&session)
+        {
+            return Err(TransportError::ChannelClosed);
+        }
+        self.sent.lock().unwrap().push((
+            session_id.map(str::to_string),
+            params["uri"].as_str().unwrap().to_string(),
+        ));
+        Ok(())
+    }
+}
+
+#[tokio::test]
+async fn test_subscriptions_are_tracked_per_session() {
+    let manager = ResourceSubscriptionManager::new();
+    assert!(manager.subscribe("a", "file:///one", None).await);
+    assert!(manager.subscribe("b", "file:///one", None).await);
+    assert!(!manager.subscribe("a", "file:///one", None).await);
+    assert!(manager.subscribe("b", "file:///two", None).await);
+
+    assert_eq!(manager.subscribers("file:///one").await, ["a", "b"]);
+    assert_eq!(
+        manager.subscribed_uris().await,
+        ["file:///one", "file:///two"]
+    );
+
+    // One session unsubscribing leaves the other's subscription in place
+    assert!(manager.unsubscribe("a", "file:///one").await);
+    assert!(!manager.unsubscribe("a", "file:///one").await);
+    assert!(manager.is_subscribed("file:///one").await);
+
+    assert_eq!(
+        manager.remove_session("b").await,
+        ["file:///one", "file:///two"]
+    );
+    assert!(manager.subscribed_uris().await.is_empty());
+}
+
+#[tokio::test]
+async fn test_filtered_subscription_covers_matching_uris() {
+    let manager = ResourceSubscriptionManager::new();
+    manager
+        .subscribe(
+            "a",
+            "file:///logs/",
+            Some(UriFilter::new("file:///logs/*.log")),
+        )
+        .await;
+    manager.subscribe("b", "file:///logs/app.log", None).await;
+
+    assert_eq!(
+        manager.subscribers("file:///logs/app.log").await,
+        ["a", "b"]
+    );
+    assert_eq!(manager.subscribers("file:///logs/worker.log").await, ["a"]);
+    assert!(manager.subscribers("file:///logs/app.txt").await.is_empty());
+}
+
+#[tokio::test]
+async fn test_changes_fan_out_to_subscribed_sessions_only() {
+    let manager = ResourceSubscriptionManager::new();
+    let transport = Arc::new(SessionRecorder::default());
+    manager.attach(transport.clone(), None);
+
+    manager.subscribe("a", "file:///one", None).await;
+    manager.subscribe("b", "file:///one", None).await;
+    manager.subscribe("c", "file:///two", None).await;
+
+    assert_eq!(
+        manager
+            .notify_resource_changed("file:///one")
+            .await
+            .unwrap(),
+        2
+    );
+    assert_eq!(
+        manager
+            .notify_resource_changed("file:///three")
+            .await
+            .unwrap(),
+        0
+    );
+    assert_eq!(
+        *transport.sent.lock().unwrap(),
+        [
+            (Some("a".to_string()), "file:///one".to_string()),
+            (Some("b".to_string()), "file:///one".to_string()),
+        ]
+    );
+}
+
+#[tokio::test]
+async fn test_default_session_is_notified_without_session_id() {
+    let manager = ResourceSubscriptionManager::new();
+    let transport = Arc::new(SessionRecorder::default());
+    manager.attach(transport.clone(), None);
+
+    manager
+        .subscribe(
+            crate::protocol_session::DEFAULT_SESSION_KEY,
+            "file:///one",
+            None,
+        )
+        .await;
+    manager
+        .notify_resource_changed("file:///one")
+        .await
+        .unwrap();
+    assert_eq!(
+        *transport.sent.lock().unwrap(),
+        [(None, "file:///one".to_string())]
+    );
+}
+
+#[tokio::test]
+async fn test_failed_session_does_not_block_others() {
+    let manager = ResourceSubscriptionManager::new();
+    let transport = Arc::new(SessionRecorder {
+        failing: vec!["a"],
+        ..Default::default()
+    });
+    manager.attach(transport.clone(), None);
+    manager.subscribe("a", "file:///one", None).await;
+    manager.subscribe("b", "file:///one", None).await;
+
+    let error = manager
+        .notify_resource_changed("file:///one")
+        .await
+        .unwrap_err();
+    assert_eq!(error.code, ErrorCode::InternalError);
+    assert_eq!(
+        *transport.sent.lock().unwrap(),
+        [(Some("b".to_string()), "file:///one".to_string())]
+    );
+    // A failing session stays subscribed
+    assert_eq!(manager.subscribers("file:///one").await, ["a", "b"]);
+}
+
+#[tokio::test]
+async fn test_closed_session_is_dropped() {
+    let manager = ResourceSubscriptionManager::new();
+    let transport = Arc::new(SessionRecorder {
+        closed: vec!["a"],
+        ..Default::default()
+    });
+    manager.attach(transport.clone(), None);
+    manager.subscribe("a", "file:///one", None).await;
+    manager.subscribe("a", "file:///two", None).await;
+    manager.subscribe("b", "file:///one", None).await;
+
+    assert_eq!(
+        manager
+            .notify_resource_changed("file:///one")
+            .await
+            .unwrap(),
+        1
+    );
+    assert_eq!(
+        *transport.sent.lock().unwrap(),
+        [(Some("b".to_string()), "file:///one".to_string())]
+    );
+    assert_eq!(manager.subscribers("file:///one").await, ["b"]);
+    assert_eq!(manager.subscribed_uris().await, ["file:///one"]);
+}
+
+#[tokio::test]
+async fn test_notify_without_transport() {
+    let manager = ResourceSubscriptionManager::new();
+    assert_eq!(
+        manager
+            .notify_resource_changed("file:///one")
+            .await
+            .unwrap(),
+        0
+    );
+
+    manager.subscribe("a", "file:///one", None).await;
+    let error = manager
+        .notify_resource_changed("file:///one")
+        .await
+        .unwrap_err();
+    assert!(error.message.contains("No transport"), "{}", error.message);
+}
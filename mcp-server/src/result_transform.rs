@@ -0,0 +1,75 @@
+//! Post-processing transforms for tool results
+//!
+//! A [`ResultTransform`] rewrites every `tools/call` result after the backend
+//! returns it, e.g. to append a disclaimer, convert units or attach
+//! provenance, without touching individual tools. Transforms run in
+//! ascending [`priority`](ResultTransform::priority) order; transforms with
+//! the same priority run in registration order.
+
+use pulseengine_mcp_protocol::CallToolResult;
+use std::fmt;
+use std::sync::Arc;
+
+/// Transform applied to every tool result
+pub trait ResultTransform: Send + Sync {
+    /// Name used in logs and debug output
+    fn name(&self) -> &str;
+
+    /// Ordering key; lower priorities run first
+    fn priority(&self) -> i32 {
+        0
+    }
+
+    /// Modify the content blocks, structured content or metadata of a result
+    fn transform(&self, tool_name: &str, result: &mut CallToolResult);
+}
+
+/// Ordered pipeline of result transforms
+#[derive(Clone, Default)]
+pub struct ResultTransformPipeline {
+    transforms: Vec<Arc<dyn ResultTransform>>,
+}
+
+impl ResultTransformPipeline {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Register a transform, keeping the pipeline ordered by priority
+    pub fn with_transform(mut self, transform: impl ResultTransform + 'static) -> Self {
+        self.push(Arc::new(transform));
+        self
+    }
+
+    /// Register a shared transform, keeping the pipeline ordered by priority
+    pub fn push(&mut self, transform: Arc<dyn ResultTransform>) {
+        // Insert after every transform with the same or lower priority so ties
+        // keep their registration order
+        let position = self
+            .transforms
+            .partition_point(|existing| existing.priority() <= transform.priority());
+        self.transforms.insert(position, transform);
+    }
+
+    /// Run every transform over a tool result, in order
+    pub fn apply(&self, tool_name: &str, result: &mut CallToolResult) {
+        for transform in &self.transforms {
+            transform.transform(tool_name, result);
+        }
+    }
+
+    /// Names of the registered transforms, in execution order
+    pub fn names(&self) -> Vec<&str> {
+        self.transforms.iter().map(|t| t.name()).collect()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.transforms.is_empty()
+    }
+}
+
+impl fmt::Debug for ResultTransformPipeline {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.names()).finish()
+    }
+}
@@ -0,0 +1,62 @@
+//! Tests for tool result post-processing transforms
+
+use crate::result_transform::*;
+use pulseengine_mcp_protocol::{CallToolResult, Content};
+
+/// Appends its label to the first text block
+struct Label {
+    label: &'static str,
+    priority: i32,
+}
+
+impl ResultTransform for Label {
+    fn name(&self) -> &str {
+        self.label
+    }
+
+    fn priority(&self) -> i32 {
+        self.priority
+    }
+
+    fn transform(&self, _tool_name: &str, result: &mut CallToolResult) {
+        if let Some(Content::Text { text, .. }) = result.content.first_mut() {
+            text.push_str(self.label);
+        }
+    }
+}
+
+fn label(label: &'static str, priority: i32) -> Label {
+    Label { label, priority }
+}
+
+fn first_text(result: &CallToolResult) -> &str {
+    match &result.content[0] {
+        Content::Text { text, .. } => text,
+        other => panic!("expected text content, got {other:?}"),
+    }
+}
+
+#[test]
+fn test_transforms_run_in_priority_then_registration_order() {
+    let pipeline = ResultTransformPipeline::new()
+        .with_transform(label("c", 10))
+        .with_transform(label("a", 0))
+        .with_transform(label("b", 0))
+        .with_transform(label("first", -5));
+    assert_eq!(pipeline.names(), ["first", "a", "b", "c"]);
+
+    let mut result = CallToolResult::text("");
+    pipeline.apply("tool", &mut result);
+    assert_eq!(first_text(&result), "firstabc");
+}
+
+#[test]
+fn test_empty_pipeline_leaves_result_untouched() {
+    let pipeline = ResultTransformPipeline::new();
+    assert!(pipeline.is_empty());
+
+    let mut result = CallToolResult::text("unchanged");
+    pipeline.apply("tool", &mut result);
+    assert_eq!(first_text(&result), "unchanged");
+    assert!(result._meta.is_none());
+}
@@ -1,16 +1,40 @@
 //! Generic MCP server implementation
 
-use crate::observability::{MetricsCollector, MonitoringConfig};
-use crate::{backend::McpBackend, handler::GenericServerHandler, middleware::MiddlewareStack};
+use crate::adaptive_timeout::AdaptiveTimeoutConfig;
+use crate::build_info::BuildInfo;
+use crate::capability_filter::CapabilityFilterConfig;
+use crate::client_policy::ClientPolicy;
+use crate::concurrency::ConcurrencyConfig;
+use crate::load_shedding::LoadShedder;
+use crate::memory_guard::{MemoryGuard, MemoryGuardConfig};
+use crate::notification_retry::NotificationRetryConfig;
+use crate::observability::{MetricsCollector, MonitoringConfig, ToolUsageAnalytics};
+use crate::protocol_session::{ConcurrentInitialize, InitializedStrictness};
+use crate::rate_limit::{GlobalRateLimitConfig, GlobalRateLimiter};
+use crate::replay_protection::{ReplayGuard, ReplayProtectionConfig};
+use crate::resource_access::ResourceAccessPolicy;
+use crate::resource_compression::ResourceCompressionConfig;
+use crate::resource_subscriptions::ResourceSubscriptionManager;
+use crate::result_transform::ResultTransformPipeline;
+use crate::{
+    backend::McpBackend,
+    handler::{DEFAULT_BATCH_CONCURRENCY, GenericServerHandler, HandlerError},
+    middleware::MiddlewareStack,
+};
 use async_trait::async_trait;
+use pulseengine_auth::middleware::{McpAuthConfig, McpAuthMiddleware};
 use pulseengine_auth::{AuthConfig, AuthenticationManager};
 use pulseengine_logging::{
-    AlertConfig, AlertManager, DashboardConfig, DashboardManager, PerformanceProfiler,
-    PersistenceConfig, ProfilingConfig, SanitizationConfig, StructuredLogger,
+    AdaptiveMetricsConfig, AlertConfig, AlertManager, DashboardConfig, DashboardManager,
+    PerformanceProfiler, PersistenceConfig, ProfilingConfig, SamplingConfig, SanitizationConfig,
+    StructuredLogger, get_metrics,
 };
+use pulseengine_mcp_protocol::signing::RequestSigner;
 use pulseengine_mcp_protocol::*;
 use pulseengine_mcp_security::{SecurityConfig, SecurityMiddleware};
-use pulseengine_mcp_transport::{RequestHandler, Transport, TransportConfig, TransportError};
+use pulseengine_mcp_transport::{
+    BatchConfig, RequestHandler, Transport, TransportConfig, TransportError, no_response,
+};
 
 use std::sync::Arc;
 use std::time::Duration;
@@ -119,6 +143,29 @@ pub enum ServerError {
     ShutdownTimeout,
 }
 
+/// When [`McpBackend::warmup`] runs relative to accepting traffic
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum WarmupMode {
+    /// `start` waits for warm-up before accepting traffic and fails if it
+    /// fails
+    #[default]
+    Blocking,
+    /// Traffic is accepted at once while warm-up runs in the background; the
+    /// server isn't ready until it completes
+    Background,
+}
+
+/// Progress of [`McpBackend::warmup`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum WarmupStatus {
+    /// Not started or still running
+    Pending,
+    /// Finished successfully
+    Complete,
+    /// Failed with the given error
+    Failed(String),
+}
+
 /// Server configuration
 #[derive(Debug, Clone)]
 pub struct ServerConfig {
@@ -157,6 +204,123 @@ pub struct ServerConfig {
 
     /// Shutdown timeout in seconds
     pub shutdown_timeout_secs: u64,
+
+    /// Pre-compression of large resource payloads (disabled when `None`)
+    pub resource_compression: Option<ResourceCompressionConfig>,
+
+    /// Fill omitted tool arguments from input schema defaults before dispatch
+    pub resolve_argument_defaults: bool,
+
+    /// HMAC signing of server-initiated requests (disabled when `None`)
+    pub request_signing: Option<RequestSigner>,
+
+    /// Reject methods other than `initialize` and `ping` until the session
+    /// has completed the `initialize` handshake
+    pub require_initialization: bool,
+
+    /// Handling of requests sent before the client's
+    /// `notifications/initialized` (accepted silently by default)
+    pub initialized_strictness: InitializedStrictness,
+
+    /// Server-wide request rate limit, enforced independently of auth
+    /// (unlimited when `None`)
+    pub rate_limit: Option<GlobalRateLimitConfig>,
+
+    /// Require a unique nonce and fresh timestamp in `_meta` on every
+    /// request, rejecting replays (disabled when `None`)
+    pub replay_protection: Option<ReplayProtectionConfig>,
+
+    /// Shared registry of resource subscriptions, for backends that notify
+    /// subscribers when resources change (a private registry when `None`)
+    pub resource_subscriptions: Option<ResourceSubscriptionManager>,
+
+    /// Fair limit on concurrently executing requests (unlimited when `None`)
+    pub concurrency: Option<ConcurrencyConfig>,
+
+    /// Requests of one JSON-RPC batch dispatched at once, both by
+    /// [`GenericServerHandler::handle_message`] and by the transport
+    pub batch_concurrency: usize,
+
+    /// Reject JSON-RPC batches in which two requests share an id, both in
+    /// [`GenericServerHandler::handle_message`] and in the transport
+    pub unique_batch_ids: bool,
+
+    /// Best-effort enforcement of backend-hinted tool memory budgets
+    /// (disabled when `None`)
+    pub memory_guard: Option<MemoryGuardConfig>,
+
+    /// Transforms applied to every tool result, in priority order
+    pub result_transforms: ResultTransformPipeline,
+
+    /// Allow/deny lists of client implementations (all clients allowed when
+    /// `None`)
+    pub client_policy: Option<ClientPolicy>,
+
+    /// Features hidden from clients lacking the matching capability (nothing
+    /// is hidden when `None`)
+    pub capability_filter: Option<CapabilityFilterConfig>,
+
+    /// Per-method sampling of request tracing spans (every request is traced
+    /// when `None`)
+    pub trace_sampling: Option<SamplingConfig>,
+
+    /// Answer requests whose handler panics with an internal error instead
+    /// of unwinding into the transport
+    pub catch_backend_panics: bool,
+
+    /// Aggregate tool usage analytics, kept by the caller for export
+    /// (disabled when `None`)
+    pub tool_analytics: Option<Arc<ToolUsageAnalytics>>,
+
+    /// Reject tool results that don't match the tool's declared output schema
+    /// (strict mode, for development and testing)
+    pub validate_tool_output: bool,
+
+    /// Size bounds on structured content checked by output validation
+    pub structured_content_limits: StructuredContentLimits,
+
+    /// Retry of failed resource update notifications (disabled when `None`)
+    pub notification_retry: Option<NotificationRetryConfig>,
+
+    /// Upper bound in milliseconds on tool call duration, also clamping
+    /// client-supplied `_meta.timeoutMs` (unbounded when `None`); a tool's
+    /// own `execution.timeoutMs` takes precedence
+    pub max_tool_timeout_ms: Option<u64>,
+
+    /// Timeout for every request, scaled by the size of its params
+    /// (disabled when `None`)
+    pub adaptive_timeout: Option<AdaptiveTimeoutConfig>,
+
+    /// Maximum number of resources a single session may subscribe to
+    /// (unbounded when `None`)
+    pub max_subscriptions_per_session: Option<usize>,
+
+    /// Handling of an `initialize` racing another one on the same session
+    pub concurrent_initialize: ConcurrentInitialize,
+
+    /// Rejection of low-priority requests while the backend is degraded
+    /// (disabled when `None`); keep a clone of its health signal to update it
+    pub load_shedding: Option<LoadShedder>,
+
+    /// URI schemes `resources/read` may access (any scheme when `None`)
+    pub allowed_resource_schemes: Option<Vec<String>>,
+
+    /// Build metadata reported in `initialize` and at `/version`
+    pub build_info: Option<BuildInfo>,
+
+    /// Authentication of transport callers from their connection's
+    /// credentials (callers stay anonymous when `None`)
+    pub caller_auth: Option<McpAuthConfig>,
+
+    /// Wire format for timestamps in protocol and audit types
+    pub timestamp_format: TimestampFormat,
+
+    /// Load-dependent reduction of latency tracking in the global request
+    /// metrics (full fidelity when `None`)
+    pub adaptive_metrics: Option<AdaptiveMetricsConfig>,
+
+    /// Whether `start` waits for the backend's warm-up
+    pub warmup: WarmupMode,
 }
 
 impl Default for ServerConfig {
@@ -179,6 +343,38 @@ impl Default for ServerConfig {
             profiling_config: ProfilingConfig::default(),
             graceful_shutdown: true,
             shutdown_timeout_secs: 30,
+            resource_compression: None,
+            resolve_argument_defaults: false,
+            request_signing: None,
+            require_initialization: false,
+            initialized_strictness: InitializedStrictness::default(),
+            rate_limit: None,
+            replay_protection: None,
+            resource_subscriptions: None,
+            concurrency: None,
+            batch_concurrency: DEFAULT_BATCH_CONCURRENCY,
+            unique_batch_ids: true,
+            memory_guard: None,
+            result_transforms: ResultTransformPipeline::new(),
+            client_policy: None,
+            capability_filter: None,
+            trace_sampling: None,
+            catch_backend_panics: true,
+            tool_analytics: None,
+            validate_tool_output: false,
+            structured_content_limits: StructuredContentLimits::default(),
+            notification_retry: None,
+            max_tool_timeout_ms: None,
+            adaptive_timeout: None,
+            max_subscriptions_per_session: None,
+            concurrent_initialize: ConcurrentInitialize::default(),
+            load_shedding: None,
+            allowed_resource_schemes: None,
+            build_info: None,
+            caller_auth: None,
+            timestamp_format: TimestampFormat::default(),
+            adaptive_metrics: None,
+            warmup: WarmupMode::default(),
         }
     }
 }
@@ -203,6 +399,7 @@ pub struct McpServer<B: McpBackend> {
     profiler: Option<Arc<PerformanceProfiler>>,
     config: ServerConfig,
     running: Arc<tokio::sync::RwLock<bool>>,
+    warmup_status: Arc<RwLock<WarmupStatus>>,
 }
 
 impl<B: McpBackend + 'static> McpServer<B> {
@@ -213,6 +410,17 @@ impl<B: McpBackend + 'static> McpServer<B> {
 
         info!("Initializing MCP server with backend");
 
+        // Leave a crate-level choice in place unless the config overrides it
+        if config.timestamp_format != TimestampFormat::default() {
+            pulseengine_mcp_protocol::timestamp::set_timestamp_format(config.timestamp_format);
+        }
+
+        if let Some(adaptive_metrics) = config.adaptive_metrics.clone()
+            && !get_metrics().enable_adaptive_fidelity(adaptive_metrics)
+        {
+            warn!("Adaptive metrics fidelity already configured, keeping the existing settings");
+        }
+
         // Initialize authentication only if enabled
         let auth_manager = if config.auth_config.enabled {
             Arc::new(
@@ -249,10 +457,17 @@ impl<B: McpBackend + 'static> McpServer<B> {
                     ))
                 })?;
         }
-        let middleware_stack = MiddlewareStack::new()
+        let mut middleware_stack = MiddlewareStack::new()
             .with_security(security_middleware)
             .with_monitoring(monitoring_metrics.clone())
             .with_auth(auth_manager.clone());
+        if let Some(rate_limit) = config.rate_limit.clone() {
+            middleware_stack = middleware_stack.with_rate_limit(GlobalRateLimiter::new(rate_limit));
+        }
+        if let Some(replay_protection) = config.replay_protection.clone() {
+            middleware_stack =
+                middleware_stack.with_replay_protection(ReplayGuard::new(replay_protection));
+        }
 
         // Create backend arc
         let backend = Arc::new(backend);
@@ -273,11 +488,75 @@ impl<B: McpBackend + 'static> McpServer<B> {
         };
 
         // Create handler (transport will be set after transport.start())
-        let handler = GenericServerHandler::new(
+        let mut handler = GenericServerHandler::new(
             backend.clone(),
             auth_manager.clone(),
             middleware_stack.clone(),
-        );
+        )
+        .with_argument_defaults(config.resolve_argument_defaults)
+        .with_initialization_required(config.require_initialization)
+        .with_initialized_strictness(config.initialized_strictness)
+        .with_panic_recovery(config.catch_backend_panics)
+        .with_error_data_sanitization(config.sanitization_config.clone())
+        .with_result_transforms(config.result_transforms.clone())
+        .with_output_validation(config.validate_tool_output)
+        .with_structured_content_limits(config.structured_content_limits)
+        .with_batch_concurrency(config.batch_concurrency)
+        .with_unique_batch_ids(config.unique_batch_ids)
+        .with_concurrent_initialize(config.concurrent_initialize);
+        if let Some(compression) = config.resource_compression.clone() {
+            handler = handler.with_resource_compression(compression);
+        }
+        if let Some(signer) = config.request_signing.clone() {
+            handler = handler.with_request_signing(signer);
+        }
+        if let Some(subscriptions) = config.resource_subscriptions.clone() {
+            handler = handler.with_resource_subscriptions(subscriptions);
+        }
+        if let Some(concurrency) = config.concurrency.clone() {
+            handler = handler.with_concurrency_limit(concurrency);
+        }
+        if let Some(policy) = config.client_policy.clone() {
+            handler = handler.with_client_policy(policy);
+        }
+        if let Some(filter) = config.capability_filter.clone() {
+            handler = handler.with_capability_filter(filter);
+        }
+        if let Some(sampling) = config.trace_sampling.clone() {
+            handler = handler.with_trace_sampling(sampling);
+        }
+        if let Some(analytics) = config.tool_analytics.clone() {
+            handler = handler.with_tool_analytics(analytics);
+        }
+        if let Some(retry) = config.notification_retry.clone() {
+            handler = handler.with_notification_retry(retry);
+        }
+        if let Some(timeout_ms) = config.max_tool_timeout_ms {
+            handler = handler.with_max_tool_timeout(Duration::from_millis(timeout_ms));
+        }
+        if let Some(adaptive_timeout) = config.adaptive_timeout.clone() {
+            handler = handler.with_adaptive_timeout(adaptive_timeout);
+        }
+        if let Some(max) = config.max_subscriptions_per_session {
+            handler = handler.with_max_subscriptions_per_session(max);
+        }
+        if let Some(memory_guard) = config.memory_guard.clone() {
+            handler = handler.with_memory_guard(MemoryGuard::new(memory_guard));
+        }
+        if let Some(shedder) = config.load_shedding.clone() {
+            handler = handler.with_load_shedding(shedder);
+        }
+        if let Some(schemes) = config.allowed_resource_schemes.clone() {
+            handler = handler
+                .with_resource_access(ResourceAccessPolicy::new().with_allowed_schemes(schemes));
+        }
+        if let Some(info) = config.build_info.clone() {
+            handler = handler.with_build_info(info);
+        }
+        if let Some(caller_auth) = config.caller_auth.clone() {
+            handler =
+                handler.with_caller_auth(McpAuthMiddleware::new(auth_manager.clone(), caller_auth));
+        }
 
         Ok(Self {
             backend,
@@ -293,9 +572,23 @@ impl<B: McpBackend + 'static> McpServer<B> {
             profiler,
             config,
             running: Arc::new(tokio::sync::RwLock::new(false)),
+            warmup_status: Arc::new(RwLock::new(WarmupStatus::Pending)),
         })
     }
 
+    /// The request handler, configured from this server's `ServerConfig`
+    pub fn handler(&self) -> &GenericServerHandler<B> {
+        &self.handler
+    }
+
+    /// Build metadata this server was configured with
+    pub fn build_info(&self) -> Option<&BuildInfo> {
+        self.config
+            .build_info
+            .as_ref()
+            .filter(|info| !info.is_empty())
+    }
+
     /// Start the server
     #[tracing::instrument(skip(self))]
     pub async fn start(&mut self) -> std::result::Result<(), ServerError> {
@@ -315,6 +608,25 @@ impl<B: McpBackend + 'static> McpServer<B> {
             .await
             .map_err(|e| ServerError::Backend(e.to_string()))?;
 
+        // Warm the backend up before the transport starts
+        *self.warmup_status.write().await = WarmupStatus::Pending;
+        match self.config.warmup {
+            WarmupMode::Blocking => {
+                run_warmup(self.backend.as_ref(), &self.warmup_status)
+                    .await
+                    .map_err(|e| ServerError::Backend(format!("Backend warm-up failed: {e}")))?;
+            }
+            WarmupMode::Background => {
+                let backend = self.backend.clone();
+                let warmup_status = self.warmup_status.clone();
+                tokio::spawn(async move {
+                    if let Err(e) = run_warmup(backend.as_ref(), &warmup_status).await {
+                        error!("Backend warm-up failed: {}", e);
+                    }
+                });
+            }
+        }
+
         // Start background services
         self.auth_manager
             .start_background_tasks()
@@ -357,12 +669,23 @@ impl<B: McpBackend + 'static> McpServer<B> {
         let handler = self.handler.clone();
         {
             let mut transport_guard = self.transport.write().await;
+            let closed_handler = self.handler.clone();
+            transport_guard.set_batch_config(BatchConfig {
+                max_concurrency: self.config.batch_concurrency,
+                unique_ids: self.config.unique_batch_ids,
+            });
+            transport_guard.set_disconnect_handler(Arc::new(move |closed| {
+                let handler = closed_handler.clone();
+                Box::pin(async move { handler.connection_closed(&closed).await })
+            }));
             transport_guard
                 .start(Box::new(move |request| {
                     let handler = handler.clone();
                     Box::pin(async move {
                         match handler.handle_request(request).await {
                             Ok(response) => response,
+                            // A request the client cancelled goes unanswered
+                            Err(HandlerError::Cancelled(id)) => no_response(Some(id)),
                             Err(error) => Response {
                                 jsonrpc: "2.0".to_string(),
                                 id: None,
@@ -404,11 +727,13 @@ impl<B: McpBackend + 'static> McpServer<B> {
 
         info!("Stopping MCP server");
 
-        // Stop transport (acquire write lock for mutable access)
+        // Drain in-flight requests, then stop the transport (acquire write
+        // lock for mutable access)
         {
+            let grace = Duration::from_secs(self.config.shutdown_timeout_secs);
             let mut transport_guard = self.transport.write().await;
             transport_guard
-                .stop()
+                .shutdown(grace)
                 .await
                 .map_err(|e| ServerError::Transport(e.to_string()))?;
         }
@@ -504,6 +829,16 @@ impl<B: McpBackend + 'static> McpServer<B> {
         *self.running.read().await
     }
 
+    /// Progress of the backend's warm-up
+    pub async fn warmup_status(&self) -> WarmupStatus {
+        self.warmup_status.read().await.clone()
+    }
+
+    /// Check if server is running and the backend has warmed up
+    pub async fn is_ready(&self) -> bool {
+        self.is_running().await && self.warmup_status().await == WarmupStatus::Complete
+    }
+
     /// Get alert manager
     pub fn get_alert_manager(&self) -> Arc<AlertManager> {
         self.alert_manager.clone()
@@ -547,6 +882,23 @@ impl<B: McpBackend + 'static> McpServer<B> {
 }
 
 /// Health status information
+/// Run the backend's warm-up, recording the outcome in `status`
+async fn run_warmup<B: McpBackend>(
+    backend: &B,
+    status: &RwLock<WarmupStatus>,
+) -> std::result::Result<(), String> {
+    let started = std::time::Instant::now();
+    let result = backend.warmup().await.map_err(|e| e.to_string());
+    *status.write().await = match &result {
+        Ok(()) => {
+            info!("Backend warmed up in {:?}", started.elapsed());
+            WarmupStatus::Complete
+        }
+        Err(e) => WarmupStatus::Failed(e.clone()),
+    };
+    result
+}
+
 #[derive(Debug, serde::Serialize, serde::Deserialize)]
 pub struct HealthStatus {
     pub status: String,
@@ -1,8 +1,9 @@
 //! Tests for MCP server implementation
 
 use crate::backend::{BackendError, McpBackend};
+use crate::health_endpoint::create_health_router;
 use crate::observability::MonitoringConfig;
-use crate::server::{HealthStatus, McpServer, ServerConfig, ServerError};
+use crate::server::{HealthStatus, McpServer, ServerConfig, ServerError, WarmupMode, WarmupStatus};
 use async_trait::async_trait;
 use pulseengine_auth::{AuthConfig, config::StorageConfig};
 use pulseengine_mcp_protocol::*;
@@ -10,7 +11,9 @@ use pulseengine_mcp_security::SecurityConfig;
 use pulseengine_mcp_transport::TransportConfig;
 use std::error::Error as StdError;
 use std::fmt;
+use std::sync::Arc;
 use std::time::Duration;
+use tokio::sync::Semaphore;
 use tokio::time::timeout;
 
 // Mock backend for server testing
@@ -19,6 +22,9 @@ struct MockServerBackend {
     should_fail_health: bool,
     should_fail_startup: bool,
     should_fail_shutdown: bool,
+    should_fail_warmup: bool,
+    /// Warm-up waits for a permit when set
+    warmup_gate: Option<Arc<Semaphore>>,
     server_name: String,
 }
 
@@ -57,6 +63,8 @@ impl McpBackend for MockServerBackend {
             should_fail_health,
             should_fail_startup,
             should_fail_shutdown,
+            should_fail_warmup: false,
+            warmup_gate: None,
             server_name,
         })
     }
@@ -86,6 +94,17 @@ impl McpBackend for MockServerBackend {
         }
     }
 
+    async fn warmup(&self) -> std::result::Result<(), Self::Error> {
+        if let Some(gate) = &self.warmup_gate {
+            let _permit = gate.acquire().await.unwrap();
+        }
+        if self.should_fail_warmup {
+            Err(MockServerError("Cache prefetch failed".to_string()))
+        } else {
+            Ok(())
+        }
+    }
+
     async fn on_shutdown(&self) -> std::result::Result<(), Self::Error> {
         if self.should_fail_shutdown {
             Err(MockServerError("Backend shutdown failed".to_string()))
@@ -112,6 +131,7 @@ impl McpBackend for MockServerBackend {
             content: vec![],
             is_error: Some(false),
             structured_content: None,
+            structured_content_blocks: None,
             _meta: None,
         })
     }
@@ -495,6 +515,7 @@ async fn test_server_with_different_transports() {
         transport_config: TransportConfig::Http {
             host: Some("127.0.0.1".to_string()),
             port: 0, // Use random port
+            max_in_flight: None,
         },
         auth_config: AuthConfig {
             storage: StorageConfig::Memory,
@@ -858,3 +879,133 @@ async fn test_server_with_auth_enabled() {
     let server = McpServer::new(backend, config).await;
     assert!(server.is_ok());
 }
+
+fn warmup_config(warmup: WarmupMode) -> ServerConfig {
+    ServerConfig {
+        transport_config: TransportConfig::Stdio,
+        graceful_shutdown: false,
+        auth_config: AuthConfig {
+            storage: StorageConfig::Memory,
+            enabled: false,
+            cache_size: 100,
+            session_timeout_secs: 3600,
+            max_failed_attempts: 5,
+            rate_limit_window_secs: 900,
+        },
+        warmup,
+        ..Default::default()
+    }
+}
+
+#[tokio::test]
+async fn test_blocking_warmup_completes_before_start_returns() {
+    let backend = MockServerBackend::initialize((false, false, false, "Warm Server".to_string()))
+        .await
+        .unwrap();
+    let mut server = McpServer::new(backend, warmup_config(WarmupMode::Blocking))
+        .await
+        .unwrap();
+    assert_eq!(server.warmup_status().await, WarmupStatus::Pending);
+    assert!(!server.is_ready().await);
+
+    server.start().await.unwrap();
+    assert_eq!(server.warmup_status().await, WarmupStatus::Complete);
+    assert!(server.is_ready().await);
+    server.stop().await.unwrap();
+}
+
+#[tokio::test]
+async fn test_background_warmup_gates_readiness() {
+    let mut backend =
+        MockServerBackend::initialize((false, false, false, "Warming Server".to_string()))
+            .await
+            .unwrap();
+    let gate = Arc::new(Semaphore::new(0));
+    backend.warmup_gate = Some(gate.clone());
+    let mut server = McpServer::new(backend, warmup_config(WarmupMode::Background))
+        .await
+        .unwrap();
+
+    // Traffic is accepted while warm-up is still running
+    server.start().await.unwrap();
+    assert!(server.is_running().await);
+    assert_eq!(server.warmup_status().await, WarmupStatus::Pending);
+    assert!(!server.is_ready().await);
+
+    let server = Arc::new(server);
+    let health = axum_test::TestServer::new(create_health_router(server.clone())).unwrap();
+    let response = health.get("/ready").await;
+    response.assert_status(axum::http::StatusCode::SERVICE_UNAVAILABLE);
+    assert_eq!(
+        response.json::<serde_json::Value>()["message"],
+        "Backend warm-up in progress"
+    );
+
+    gate.add_permits(1);
+    timeout(Duration::from_secs(5), async {
+        while !server.is_ready().await {
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+    })
+    .await
+    .unwrap();
+    health.get("/ready").await.assert_status_ok();
+
+    drop(health);
+    let mut server = Arc::try_unwrap(server).ok().unwrap();
+    server.stop().await.unwrap();
+}
+
+#[tokio::test]
+async fn test_warmup_failure_is_surfaced() {
+    let mut backend =
+        MockServerBackend::initialize((false, false, false, "Cold Server".to_string()))
+            .await
+            .unwrap();
+    backend.should_fail_warmup = true;
+
+    // Blocking warm-up fails start
+    let mut server = McpServer::new(backend.clone(), warmup_config(WarmupMode::Blocking))
+        .await
+        .unwrap();
+    match server.start().await {
+        Err(ServerError::Backend(message)) => {
+            assert!(message.contains("warm-up failed"), "{message}");
+            assert!(message.contains("Cache prefetch failed"), "{message}");
+        }
+        other => panic!("Expected a backend error, got {other:?}"),
+    }
+
+    // Background warm-up leaves the server unready with the error
+    let mut server = McpServer::new(backend, warmup_config(WarmupMode::Background))
+        .await
+        .unwrap();
+    server.start().await.unwrap();
+    let status = timeout(Duration::from_secs(5), async {
+        loop {
+            match server.warmup_status().await {
+                WarmupStatus::Pending => tokio::time::sleep(Duration::from_millis(10)).await,
+                status => break status,
+            }
+        }
+    })
+    .await
+    .unwrap();
+    assert!(matches!(status, WarmupStatus::Failed(ref e) if e.contains("Cache prefetch failed")));
+    assert!(!server.is_ready().await);
+
+    let server = Arc::new(server);
+    let health = axum_test::TestServer::new(create_health_router(server.clone())).unwrap();
+    let response = health.get("/ready").await;
+    response.assert_status(axum::http::StatusCode::SERVICE_UNAVAILABLE);
+    assert!(
+        response.json::<serde_json::Value>()["message"]
+            .as_str()
+            .unwrap()
+            .starts_with("Backend warm-up failed")
+    );
+
+    drop(health);
+    let mut server = Arc::try_unwrap(server).ok().unwrap();
+    server.stop().await.unwrap();
+}
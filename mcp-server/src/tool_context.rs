@@ -663,6 +663,7 @@ where
 // Transport Bridge (connects ToolContext to Transport)
 // ============================================================================
 
+use pulseengine_mcp_protocol::signing::RequestSigner;
 use pulseengine_mcp_transport::{
     NotificationSender as StreamingNotificationSender, StreamingNotification, Transport,
     TransportError,
@@ -677,6 +678,8 @@ pub struct TransportBridge {
     /// Captured streaming notification sender for this request
     /// This is captured at construction time to avoid task-local scope issues
     streaming_sender: Option<StreamingNotificationSender>,
+    /// Optional HMAC signer for outbound requests
+    signer: Option<Arc<RequestSigner>>,
 }
 
 impl TransportBridge {
@@ -690,8 +693,15 @@ impl TransportBridge {
             transport,
             session_id,
             streaming_sender,
+            signer: None,
         }
     }
+
+    /// Sign every outbound request with the given signer
+    pub fn with_signer(mut self, signer: Arc<RequestSigner>) -> Self {
+        self.signer = Some(signer);
+        self
+    }
 }
 
 #[async_trait]
@@ -768,12 +778,16 @@ impl RequestSender for TransportBridge {
     async fn send_request(
         &self,
         method: &str,
-        params: Value,
+        mut params: Value,
         timeout: Duration,
     ) -> Result<Value, ToolContextError> {
         // Generate a unique request ID
         let request_id = uuid::Uuid::new_v4().to_string();
 
+        if let Some(ref signer) = self.signer {
+            signer.sign(method, &mut params);
+        }
+
         eprintln!(
             "[DEBUG] TransportBridge::send_request: method={}, request_id={}, has_streaming_sender={}",
             method,
@@ -864,10 +878,33 @@ pub fn create_tool_context(
     progress_token: Option<String>,
     session_id: Option<String>,
 ) -> Arc<dyn ToolContext> {
-    let bridge = Arc::new(TransportBridge::new(
-        Arc::clone(&transport),
-        session_id.clone(),
-    ));
+    create_signed_tool_context(
+        transport,
+        request_id,
+        tool_name,
+        progress_token,
+        session_id,
+        None,
+    )
+}
+
+/// Create a ToolContext whose outbound requests are HMAC-signed
+///
+/// With `signer` set, requests such as `sampling/createMessage` carry a
+/// signature in `params._meta` that cooperating clients can verify.
+pub fn create_signed_tool_context(
+    transport: Arc<dyn Transport>,
+    request_id: impl Into<String>,
+    tool_name: impl Into<String>,
+    progress_token: Option<String>,
+    session_id: Option<String>,
+    signer: Option<Arc<RequestSigner>>,
+) -> Arc<dyn ToolContext> {
+    let mut bridge = TransportBridge::new(Arc::clone(&transport), session_id.clone());
+    if let Some(signer) = signer {
+        bridge = bridge.with_signer(signer);
+    }
+    let bridge = Arc::new(bridge);
 
     Arc::new(DefaultToolContext::new(
         request_id,
@@ -881,6 +881,7 @@ struct MockTransport {
     notification_result: std::sync::Mutex<MockTransportResult>,
     request_result: std::sync::Mutex<MockTransportResult>,
     request_response: std::sync::Mutex<Value>,
+    sent_requests: std::sync::Mutex<Vec<(String, Value)>>,
 }
 
 impl MockTransport {
@@ -890,6 +891,7 @@ impl MockTransport {
             notification_result: std::sync::Mutex::new(MockTransportResult::Ok),
             request_result: std::sync::Mutex::new(MockTransportResult::Ok),
             request_response: std::sync::Mutex::new(json!({})),
+            sent_requests: std::sync::Mutex::new(vec![]),
         }
     }
 
@@ -942,10 +944,14 @@ impl Transport for MockTransport {
     async fn send_request(
         &self,
         _session_id: Option<&str>,
-        _method: &str,
-        _params: Value,
+        method: &str,
+        params: Value,
         _timeout: Duration,
     ) -> Result<Value, TransportError> {
+        self.sent_requests
+            .lock()
+            .unwrap()
+            .push((method.to_string(), params));
         let result = self.request_result.lock().unwrap().clone();
         let response = self.request_response.lock().unwrap().clone();
         result.to_request_result(&response)
@@ -1404,3 +1410,57 @@ async fn test_create_tool_context_request_error() {
         .await;
     assert!(matches!(result, Err(ToolContextError::Timeout)));
 }
+
+#[tokio::test]
+async fn test_signed_tool_context_signs_outbound_requests() {
+    use crate::tool_context::create_signed_tool_context;
+    use pulseengine_mcp_protocol::signing::{RequestSigner, SIGNATURE_META_KEY};
+
+    let mock = Arc::new(MockTransport::new(true));
+    mock.set_request_response(json!({"action": "accept", "content": {"ok": true}}));
+    let signer = RequestSigner::new("shared-secret");
+
+    let ctx = create_signed_tool_context(
+        mock.clone() as Arc<dyn Transport>,
+        "req-signed",
+        "signed-tool",
+        None,
+        Some("session".to_string()),
+        Some(Arc::new(signer.clone())),
+    );
+    ctx.request_elicitation(ElicitationRequest::text("Confirm?"), Duration::from_secs(1))
+        .await
+        .unwrap();
+
+    let (method, params) = mock.sent_requests.lock().unwrap()[0].clone();
+    assert_eq!(method, "elicitation/create");
+    assert!(params["_meta"][SIGNATURE_META_KEY]["value"].is_string());
+    signer.verify(&method, &params).unwrap();
+
+    // A tampered request fails verification on the client side
+    let mut tampered = params.clone();
+    tampered["message"] = json!("Delete everything?");
+    assert!(signer.verify(&method, &tampered).is_err());
+}
+
+#[tokio::test]
+async fn test_unsigned_tool_context_sends_plain_requests() {
+    use crate::tool_context::create_tool_context;
+
+    let mock = Arc::new(MockTransport::new(true));
+    mock.set_request_response(json!({"action": "decline"}));
+
+    let ctx = create_tool_context(
+        mock.clone() as Arc<dyn Transport>,
+        "req-plain",
+        "plain-tool",
+        None,
+        Some("session".to_string()),
+    );
+    ctx.request_elicitation(ElicitationRequest::text("Confirm?"), Duration::from_secs(1))
+        .await
+        .unwrap();
+
+    let (_, params) = mock.sent_requests.lock().unwrap()[0].clone();
+    assert!(params.get("_meta").is_none());
+}
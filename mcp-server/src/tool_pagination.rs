@@ -0,0 +1,254 @@
+//! Paginated tool results
+//!
+//! A tool that can return a huge array, such as search results, hands it out
+//! a page at a time instead of as one giant result. The convention mirrors
+//! resource pagination:
+//!
+//! - Each page is returned as structured content `{"items": [...], "total": n}`,
+//!   with the same JSON as text content.
+//! - While items remain, the result carries the cursor of the next page in
+//!   `_meta.nextCursor`.
+//! - The client calls the tool again with that cursor as its `cursor`
+//!   argument to get the next page:
+//!
+//! ```json
+//! {"method": "tools/call", "params": {"name": "search", "arguments": {"cursor": "..."}}}
+//! ```
+//!
+//! [`ToolResultPaginator`] implements this for a backend: it produces the
+//! full result once, keeps the items not yet handed out for the calling
+//! session, and serves later pages from them. Cursors are signed and only
+//! valid for the session and tool they were issued to.
+
+use pulseengine_mcp_protocol::{CallToolResult, Cursor, Error, Meta};
+use serde_json::{Value, json};
+use std::collections::HashMap;
+use std::future::Future;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use uuid::Uuid;
+
+use crate::protocol_session::DEFAULT_SESSION_KEY;
+
+/// Tool argument carrying the cursor of the requested page
+pub const CURSOR_ARGUMENT: &str = "cursor";
+
+/// `_meta` entry carrying the cursor of the next page
+pub const NEXT_CURSOR_META_KEY: &str = "nextCursor";
+
+/// The `cursor` argument of a tool call, if any
+pub fn cursor_argument(arguments: Option<&Value>) -> Option<&str> {
+    arguments?.get(CURSOR_ARGUMENT)?.as_str()
+}
+
+/// The `_meta.nextCursor` of a tool result, if more pages remain
+pub fn next_cursor(result: &CallToolResult) -> Option<&str> {
+    result
+        ._meta
+        .as_ref()?
+        .extra
+        .get(NEXT_CURSOR_META_KEY)?
+        .as_str()
+}
+
+/// A tool result being handed out page by page
+struct PagedResult {
+    tool_name: String,
+    items: Arc<Vec<Value>>,
+    created_at: Instant,
+}
+
+/// Splits large tool results into pages and keeps their state per session
+pub struct ToolResultPaginator {
+    page_size: usize,
+    ttl: Duration,
+    max_results_per_session: usize,
+    /// Secret mixed into the key material of every cursor
+    secret: [u8; 16],
+    /// Results with pages left, by session and result id
+    results: Mutex<HashMap<(String, String), PagedResult>>,
+}
+
+impl ToolResultPaginator {
+    /// Paginator handing out `page_size` items per page
+    ///
+    /// Results are kept for 5 minutes, at most 16 per session.
+    pub fn new(page_size: usize) -> Self {
+        Self {
+            page_size: page_size.max(1),
+            ttl: Duration::from_secs(300),
+            max_results_per_session: 16,
+            secret: *Uuid::new_v4().as_bytes(),
+            results: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Forget a result this long after it was produced, failing its cursors
+    pub fn with_ttl(mut self, ttl: Duration) -> Self {
+        self.ttl = ttl;
+        self
+    }
+
+    /// Keep at most `max` results per session, dropping the oldest first
+    pub fn with_max_results_per_session(mut self, max: usize) -> Self {
+        self.max_results_per_session = max.max(1);
+        self
+    }
+
+    /// Serve a call to `tool_name`
+    ///
+    /// Without a `cursor` argument, `produce` computes the full list of items
+    /// and the first page is returned; with one, the page it points at is
+    /// returned from the stored items without calling `produce`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an `InvalidCursor` error if the cursor is malformed, was
+    /// issued to another session or tool, or its result has expired, and
+    /// whatever `produce` fails with.
+    pub async fn paginate<F, Fut>(
+        &self,
+        tool_name: &str,
+        arguments: Option<&Value>,
+        produce: F,
+    ) -> Result<CallToolResult, Error>
+    where
+        F: FnOnce() -> Fut,
+        Fut: Future<Output = Result<Vec<Value>, Error>>,
+    {
+        match cursor_argument(arguments) {
+            Some(cursor) => self.page(tool_name, cursor),
+            None => Ok(self.first_page(tool_name, produce().await?)),
+        }
+    }
+
+    /// First page of `items`, keeping the rest for the calling session
+    pub fn first_page(&self, tool_name: &str, items: Vec<Value>) -> CallToolResult {
+        if items.len() <= self.page_size {
+            return page_result(&items, items.len(), None);
+        }
+
+        let session = current_session_key();
+        let result_id = Uuid::new_v4().simple().to_string();
+        let items = Arc::new(items);
+        let next = self.encode_cursor(&session, &result_id, self.page_size);
+        let result = page_result(&items[..self.page_size], items.len(), Some(next));
+
+        let mut results = self.results.lock().unwrap_or_else(|e| e.into_inner());
+        self.evict(&mut results, &session);
+        results.insert(
+            (session, result_id),
+            PagedResult {
+                tool_name: tool_name.to_string(),
+                items,
+                created_at: Instant::now(),
+            },
+        );
+        result
+    }
+
+    /// The page of a `tool_name` result that `cursor` points at
+    ///
+    /// The stored items are released once the last page has been served.
+    pub fn page(&self, tool_name: &str, cursor: &str) -> Result<CallToolResult, Error> {
+        let session = current_session_key();
+        let (result_id, token) = cursor
+            .split_once('.')
+            .ok_or_else(|| Error::invalid_cursor("Malformed pagination cursor"))?;
+        let offset = Cursor::decode(token, &self.key_material(&session, result_id))?.offset;
+
+        let key = (session, result_id.to_string());
+        let mut results = self.results.lock().unwrap_or_else(|e| e.into_inner());
+        let items = match results.get(&key) {
+            Some(paged) if paged.created_at.elapsed() < self.ttl => {
+                if paged.tool_name != tool_name {
+                    return Err(Error::invalid_cursor(format!(
+                        "Pagination cursor was issued by tool '{}'",
+                        paged.tool_name
+                    )));
+                }
+                paged.items.clone()
+            }
+            _ => {
+                results.remove(&key);
+                return Err(Error::invalid_cursor(
+                    "Paginated result expired; call the tool again without a cursor",
+                ));
+            }
+        };
+        if offset >= items.len() {
+            return Err(Error::invalid_cursor(
+                "Pagination cursor offset out of range",
+            ));
+        }
+
+        let end = (offset + self.page_size).min(items.len());
+        let next = if end < items.len() {
+            Some(self.encode_cursor(&key.0, &key.1, end))
+        } else {
+            results.remove(&key);
+            None
+        };
+        Ok(page_result(&items[offset..end], items.len(), next))
+    }
+
+    /// Results currently held for the calling session
+    pub fn pending_results(&self) -> usize {
+        let session = current_session_key();
+        let results = self.results.lock().unwrap_or_else(|e| e.into_inner());
+        results.keys().filter(|(s, _)| *s == session).count()
+    }
+
+    /// Drop expired results everywhere and the oldest of `session` beyond
+    /// the per-session limit, making room for one more
+    fn evict(&self, results: &mut HashMap<(String, String), PagedResult>, session: &str) {
+        results.retain(|_, paged| paged.created_at.elapsed() < self.ttl);
+
+        let mut held: Vec<(Instant, String)> = results
+            .iter()
+            .filter(|((s, _), _)| s == session)
+            .map(|((_, id), paged)| (paged.created_at, id.clone()))
+            .collect();
+        if held.len() < self.max_results_per_session {
+            return;
+        }
+        held.sort();
+        let excess = held.len() + 1 - self.max_results_per_session;
+        for (_, id) in held.into_iter().take(excess) {
+            results.remove(&(session.to_string(), id));
+        }
+    }
+
+    fn encode_cursor(&self, session: &str, result_id: &str, offset: usize) -> String {
+        let token = Cursor::encode(offset, &self.key_material(session, result_id));
+        format!("{result_id}.{token}")
+    }
+
+    /// Key material binding cursors to this paginator, the session and the
+    /// result
+    fn key_material(&self, session: &str, result_id: &str) -> Vec<u8> {
+        let mut key = self.secret.to_vec();
+        for part in [session, result_id] {
+            key.extend_from_slice(&(part.len() as u64).to_be_bytes());
+            key.extend_from_slice(part.as_bytes());
+        }
+        key
+    }
+}
+
+fn current_session_key() -> String {
+    pulseengine_mcp_transport::try_current_session_id()
+        .unwrap_or_else(|| DEFAULT_SESSION_KEY.to_string())
+}
+
+fn page_result(items: &[Value], total: usize, next_cursor: Option<String>) -> CallToolResult {
+    let page = json!({ "items": items, "total": total });
+    let mut result = CallToolResult::text_with_structured(page.to_string(), page);
+    if let Some(cursor) = next_cursor {
+        result._meta = Some(Meta {
+            extra: HashMap::from([(NEXT_CURSOR_META_KEY.to_string(), Value::String(cursor))]),
+            ..Default::default()
+        });
+    }
+    result
+}
@@ -0,0 +1,143 @@
+//! Tests for paginated tool results
+
+use crate::tool_pagination::*;
+use pulseengine_mcp_protocol::{CallToolResult, ErrorCode};
+use pulseengine_mcp_transport::with_session;
+use serde_json::{Value, json};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::Duration;
+
+fn search_results(count: usize) -> Vec<Value> {
+    (0..count)
+        .map(|i| json!({"id": i, "greeting": format!("Hello #{i}")}))
+        .collect()
+}
+
+fn page_ids(result: &CallToolResult) -> Vec<u64> {
+    result.structured_content.as_ref().unwrap()["items"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|item| item["id"].as_u64().unwrap())
+        .collect()
+}
+
+#[tokio::test]
+async fn test_large_result_paginated_across_calls() {
+    let paginator = ToolResultPaginator::new(10);
+    let produced = AtomicUsize::new(0);
+    let call = |arguments: Option<Value>| {
+        let paginator = &paginator;
+        let produced = &produced;
+        async move {
+            paginator
+                .paginate("search_greetings", arguments.as_ref(), || async {
+                    produced.fetch_add(1, Ordering::SeqCst);
+                    Ok(search_results(25))
+                })
+                .await
+                .unwrap()
+        }
+    };
+
+    let first = call(Some(json!({"query": "hello"}))).await;
+    assert_eq!(page_ids(&first), (0..10).collect::<Vec<_>>());
+    assert_eq!(first.structured_content.as_ref().unwrap()["total"], 25);
+    let text: Value =
+        serde_json::from_str(&first.content[0].as_text_content().unwrap().text).unwrap();
+    assert_eq!(&text, first.structured_content.as_ref().unwrap());
+
+    // The cursor travels in `_meta.nextCursor` on the wire
+    let wire = serde_json::to_value(&first).unwrap();
+    let cursor = wire["_meta"]["nextCursor"].as_str().unwrap().to_string();
+    assert_eq!(next_cursor(&first), Some(cursor.as_str()));
+
+    let second = call(Some(json!({"cursor": cursor}))).await;
+    assert_eq!(page_ids(&second), (10..20).collect::<Vec<_>>());
+
+    let last = call(Some(json!({"cursor": next_cursor(&second).unwrap()}))).await;
+    assert_eq!(page_ids(&last), (20..25).collect::<Vec<_>>());
+    assert!(next_cursor(&last).is_none());
+    assert!(serde_json::to_value(&last).unwrap().get("_meta").is_none());
+
+    // The items were produced once and released after the last page
+    assert_eq!(produced.load(Ordering::SeqCst), 1);
+    assert_eq!(paginator.pending_results(), 0);
+}
+
+#[tokio::test]
+async fn test_small_result_fits_one_page() {
+    let paginator = ToolResultPaginator::new(10);
+    let result = paginator.first_page("search_greetings", search_results(10));
+    assert_eq!(page_ids(&result).len(), 10);
+    assert!(next_cursor(&result).is_none());
+    assert_eq!(paginator.pending_results(), 0);
+}
+
+#[tokio::test]
+async fn test_cursor_bound_to_session_and_tool() {
+    let paginator = ToolResultPaginator::new(5);
+    let first = with_session("session-a".to_string(), async {
+        paginator.first_page("search_greetings", search_results(12))
+    })
+    .await;
+    let cursor = next_cursor(&first).unwrap();
+
+    let error = with_session("session-b".to_string(), async {
+        paginator.page("search_greetings", cursor)
+    })
+    .await
+    .unwrap_err();
+    assert_eq!(error.code, ErrorCode::InvalidCursor);
+
+    let error = with_session("session-a".to_string(), async {
+        paginator.page("other_tool", cursor)
+    })
+    .await
+    .unwrap_err();
+    assert_eq!(error.code, ErrorCode::InvalidCursor);
+
+    let page = with_session("session-a".to_string(), async {
+        paginator.page("search_greetings", cursor)
+    })
+    .await
+    .unwrap();
+    assert_eq!(page_ids(&page), (5..10).collect::<Vec<_>>());
+
+    // Tampered cursors are rejected
+    let tampered = format!("{cursor}x");
+    let error = with_session("session-a".to_string(), async {
+        paginator.page("search_greetings", &tampered)
+    })
+    .await
+    .unwrap_err();
+    assert_eq!(error.code, ErrorCode::InvalidCursor);
+}
+
+#[tokio::test]
+async fn test_expired_and_evicted_results() {
+    let paginator = ToolResultPaginator::new(2).with_ttl(Duration::from_millis(20));
+    let first = paginator.first_page("search_greetings", search_results(5));
+    tokio::time::sleep(Duration::from_millis(40)).await;
+    let error = paginator
+        .page("search_greetings", next_cursor(&first).unwrap())
+        .unwrap_err();
+    assert_eq!(error.code, ErrorCode::InvalidCursor);
+    assert!(error.message.contains("expired"), "{}", error.message);
+
+    let paginator = ToolResultPaginator::new(2).with_max_results_per_session(2);
+    let oldest = paginator.first_page("search_greetings", search_results(5));
+    paginator.first_page("search_greetings", search_results(5));
+    let newest = paginator.first_page("search_greetings", search_results(5));
+    assert_eq!(paginator.pending_results(), 2);
+    assert!(
+        paginator
+            .page("search_greetings", next_cursor(&oldest).unwrap())
+            .is_err()
+    );
+    assert!(
+        paginator
+            .page("search_greetings", next_cursor(&newest).unwrap())
+            .is_ok()
+    );
+}
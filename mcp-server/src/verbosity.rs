@@ -0,0 +1,74 @@
+//! Lean list responses for clients that cache definitions
+//!
+//! A client polling `tools/list` or `resources/list` for changes usually
+//! already holds the descriptions and schemas from an earlier call. It can
+//! ask for a minimal listing with `_meta.verbosity`:
+//!
+//! ```json
+//! {"method": "tools/list", "params": {"_meta": {"verbosity": "minimal"}}}
+//! ```
+//!
+//! Minimal tool listings omit `description`, `inputSchema` and
+//! `outputSchema`; minimal resource listings omit `description`. Clients that
+//! don't ask get the full listing.
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+/// Fields dropped from each tool in a minimal listing
+const HEAVY_TOOL_FIELDS: &[&str] = &["description", "inputSchema", "outputSchema"];
+
+/// Fields dropped from each resource in a minimal listing
+const HEAVY_RESOURCE_FIELDS: &[&str] = &["description"];
+
+/// How much detail a client wants in list responses
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ListVerbosity {
+    /// Complete definitions
+    #[default]
+    Full,
+    /// Names and identifiers only, without descriptions or schemas
+    Minimal,
+}
+
+impl ListVerbosity {
+    /// Read the verbosity a request asks for from `_meta.verbosity`
+    ///
+    /// Missing or unrecognised values mean [`ListVerbosity::Full`].
+    pub fn from_params(params: &Value) -> Self {
+        params
+            .get("_meta")
+            .and_then(|meta| meta.get("verbosity"))
+            .and_then(|verbosity| serde_json::from_value(verbosity.clone()).ok())
+            .unwrap_or_default()
+    }
+
+    pub fn is_minimal(self) -> bool {
+        self == Self::Minimal
+    }
+
+    /// Strip heavy fields from a serialized `ListToolsResult`
+    pub fn apply_to_tools(self, result: &mut Value) {
+        self.strip(result, "tools", HEAVY_TOOL_FIELDS);
+    }
+
+    /// Strip heavy fields from a serialized `ListResourcesResult`
+    pub fn apply_to_resources(self, result: &mut Value) {
+        self.strip(result, "resources", HEAVY_RESOURCE_FIELDS);
+    }
+
+    fn strip(self, result: &mut Value, list: &str, fields: &[&str]) {
+        if !self.is_minimal() {
+            return;
+        }
+        let Some(items) = result.get_mut(list).and_then(Value::as_array_mut) else {
+            return;
+        };
+        for item in items.iter_mut().filter_map(Value::as_object_mut) {
+            for field in fields {
+                item.remove(*field);
+            }
+        }
+    }
+}
@@ -0,0 +1,72 @@
+//! Tests for lean list responses
+
+use crate::verbosity::*;
+use serde_json::json;
+
+fn tools_result() -> serde_json::Value {
+    json!({
+        "tools": [{
+            "name": "search",
+            "title": "Search",
+            "description": "Search the index",
+            "inputSchema": {"type": "object"},
+            "outputSchema": {"type": "object"}
+        }],
+        "nextCursor": "abc"
+    })
+}
+
+#[test]
+fn test_verbosity_from_params() {
+    assert_eq!(
+        ListVerbosity::from_params(&json!(null)),
+        ListVerbosity::Full
+    );
+    assert_eq!(ListVerbosity::from_params(&json!({})), ListVerbosity::Full);
+    assert_eq!(
+        ListVerbosity::from_params(&json!({"_meta": {"verbosity": "minimal"}})),
+        ListVerbosity::Minimal
+    );
+    assert_eq!(
+        ListVerbosity::from_params(&json!({"_meta": {"verbosity": "full"}})),
+        ListVerbosity::Full
+    );
+    assert_eq!(
+        ListVerbosity::from_params(&json!({"_meta": {"verbosity": "loud"}})),
+        ListVerbosity::Full
+    );
+}
+
+#[test]
+fn test_minimal_tools_omit_descriptions_and_schemas() {
+    let mut result = tools_result();
+    ListVerbosity::Minimal.apply_to_tools(&mut result);
+    assert_eq!(
+        result,
+        json!({"tools": [{"name": "search", "title": "Search"}], "nextCursor": "abc"})
+    );
+}
+
+#[test]
+fn test_full_tools_unchanged() {
+    let mut result = tools_result();
+    ListVerbosity::Full.apply_to_tools(&mut result);
+    assert_eq!(result, tools_result());
+}
+
+#[test]
+fn test_minimal_resources_omit_descriptions() {
+    let mut result = json!({
+        "resources": [{
+            "uri": "file:///notes.txt",
+            "name": "notes",
+            "description": "Meeting notes",
+            "mimeType": "text/plain"
+        }]
+    });
+    ListVerbosity::Minimal.apply_to_resources(&mut result);
+    assert_eq!(
+        result,
+        json!({"resources": [{"uri": "file:///notes.txt", "name": "notes", "mimeType": "text/plain"}]})
+    );
+}
@@ -1,8 +1,10 @@
 //! JSON-RPC batch message handling
 
 use crate::{RequestHandler, TransportError, validation::validate_batch};
+use futures_util::StreamExt;
 use pulseengine_mcp_protocol::{Request, Response};
 use serde_json::Value;
+use std::collections::HashSet;
 use tracing::debug;
 
 /// Represents a JSON-RPC message that can be either single or batch
@@ -12,6 +14,32 @@ pub enum JsonRpcMessage {
     Batch(Vec<Value>),
 }
 
+/// Requests of one JSON-RPC batch dispatched at once unless configured
+pub const DEFAULT_BATCH_CONCURRENCY: usize = 8;
+
+/// How transports process JSON-RPC batches
+#[derive(Debug, Clone)]
+pub struct BatchConfig {
+    /// Requests of one batch dispatched at once; responses keep the order of
+    /// the requests. Values below 1 are treated as 1.
+    pub max_concurrency: usize,
+    /// Reject batches in which two requests share an id
+    ///
+    /// Duplicate ids make it impossible for the client to tell which response
+    /// answers which request, so such a batch is answered with a single
+    /// invalid-request error without processing any entry.
+    pub unique_ids: bool,
+}
+
+impl Default for BatchConfig {
+    fn default() -> Self {
+        Self {
+            max_concurrency: DEFAULT_BATCH_CONCURRENCY,
+            unique_ids: true,
+        }
+    }
+}
+
 /// Represents a processed batch result
 #[derive(Debug)]
 pub struct BatchResult {
@@ -157,16 +185,38 @@ impl JsonRpcMessage {
     }
 }
 
-/// Process a batch of requests through a handler
+/// Process a batch of requests through a handler with the default
+/// [`BatchConfig`]
 pub async fn process_batch(
     message: JsonRpcMessage,
     handler: &RequestHandler,
+) -> Result<Option<JsonRpcMessage>, TransportError> {
+    process_batch_with(message, handler, &BatchConfig::default()).await
+}
+
+/// Process a batch of requests through a handler
+pub async fn process_batch_with(
+    message: JsonRpcMessage,
+    handler: &RequestHandler,
+    config: &BatchConfig,
 ) -> Result<Option<JsonRpcMessage>, TransportError> {
     debug!("Processing batch message");
 
     // Validate the message first
     message.validate()?;
 
+    if let JsonRpcMessage::Batch(values) = &message
+        && config.unique_ids
+        && let Some(id) = duplicate_id(values)
+    {
+        let error = pulseengine_mcp_protocol::Error::invalid_request(format!(
+            "Batch contains more than one request with id {id}"
+        ));
+        let response = serde_json::to_value(create_error_response(error, None))
+            .map_err(|e| TransportError::Protocol(format!("Failed to serialize response: {e}")))?;
+        return Ok(Some(JsonRpcMessage::Single(response)));
+    }
+
     // Extract requests and notifications
     let requests = message.extract_requests()?;
     let notifications = message.extract_notifications()?;
@@ -189,16 +239,23 @@ pub async fn process_batch(
         return Ok(None);
     }
 
-    // Process requests and collect responses
-    let mut responses = Vec::new();
+    // Process requests concurrently, collecting responses in request order
+    let responses: Vec<Response> = futures_util::stream::iter(requests)
+        .map(|request| {
+            debug!(
+                "Processing request: {} (ID: {:?})",
+                request.method, request.id
+            );
+            handler(request)
+        })
+        .buffered(config.max_concurrency.max(1))
+        .filter(|response| std::future::ready(!crate::is_no_response(response)))
+        .collect()
+        .await;
 
-    for request in requests {
-        debug!(
-            "Processing request: {} (ID: {:?})",
-            request.method, request.id
-        );
-        let response = handler(request).await;
-        responses.push(response);
+    // Every request went unanswered
+    if responses.is_empty() {
+        return Ok(None);
     }
 
     // Return appropriate response format
@@ -222,6 +279,17 @@ pub async fn process_batch(
     Ok(Some(response_message))
 }
 
+/// The first non-null `id` shared by two entries of a batch
+fn duplicate_id(entries: &[Value]) -> Option<&Value> {
+    let mut seen = HashSet::new();
+    entries
+        .iter()
+        .filter(|entry| entry.get("method").is_some())
+        .filter_map(|entry| entry.get("id"))
+        .filter(|id| !id.is_null())
+        .find(|id| !seen.insert(id.to_string()))
+}
+
 /// Create an error response for a malformed request
 pub fn create_error_response(
     error: pulseengine_mcp_protocol::Error,
@@ -324,6 +392,111 @@ mod tests {
         }
     }
 
+    #[tokio::test]
+    async fn test_unanswered_requests_are_dropped() {
+        let handler: RequestHandler = Box::new(|request: Request| {
+            Box::pin(async move {
+                if request.method == "cancelled" {
+                    crate::no_response(request.id)
+                } else {
+                    mock_handler(request).await
+                }
+            })
+        });
+
+        let single =
+            JsonRpcMessage::parse(r#"{"jsonrpc": "2.0", "method": "cancelled", "id": 1}"#).unwrap();
+        assert!(process_batch(single, &handler).await.unwrap().is_none());
+
+        let batch = JsonRpcMessage::parse(
+            r#"[
+                {"jsonrpc": "2.0", "method": "cancelled", "id": 1},
+                {"jsonrpc": "2.0", "method": "answered", "id": 2}
+            ]"#,
+        )
+        .unwrap();
+        match process_batch(batch, &handler).await.unwrap() {
+            Some(JsonRpcMessage::Batch(responses)) => {
+                assert_eq!(responses.len(), 1);
+                assert_eq!(responses[0]["id"], 2);
+            }
+            other => panic!("Expected batch response, got {other:?}"),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_duplicate_ids_rejected() {
+        let handler: RequestHandler = Box::new(mock_handler);
+        let batch = r#"[
+            {"jsonrpc": "2.0", "method": "request1", "id": 1},
+            {"jsonrpc": "2.0", "method": "request2", "id": 1}
+        ]"#;
+
+        match process_batch(JsonRpcMessage::parse(batch).unwrap(), &handler)
+            .await
+            .unwrap()
+        {
+            Some(JsonRpcMessage::Single(response)) => {
+                assert_eq!(response["error"]["code"], -32600);
+                assert!(response["id"].is_null());
+            }
+            other => panic!("Expected a single error response, got {other:?}"),
+        }
+
+        let lenient = BatchConfig {
+            unique_ids: false,
+            ..BatchConfig::default()
+        };
+        match process_batch_with(JsonRpcMessage::parse(batch).unwrap(), &handler, &lenient)
+            .await
+            .unwrap()
+        {
+            Some(JsonRpcMessage::Batch(responses)) => assert_eq!(responses.len(), 2),
+            other => panic!("Expected batch response, got {other:?}"),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_batch_concurrency_is_bounded() {
+        use std::sync::Arc;
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        let in_flight = Arc::new(AtomicUsize::new(0));
+        let peak = Arc::new(AtomicUsize::new(0));
+        let handler: RequestHandler = {
+            let (in_flight, peak) = (in_flight.clone(), peak.clone());
+            Box::new(move |request: Request| {
+                let (in_flight, peak) = (in_flight.clone(), peak.clone());
+                Box::pin(async move {
+                    let now = in_flight.fetch_add(1, Ordering::SeqCst) + 1;
+                    peak.fetch_max(now, Ordering::SeqCst);
+                    tokio::time::sleep(std::time::Duration::from_millis(20)).await;
+                    in_flight.fetch_sub(1, Ordering::SeqCst);
+                    mock_handler(request).await
+                })
+            })
+        };
+        let batch = (1..=6)
+            .map(|id| json!({"jsonrpc": "2.0", "method": "slow", "id": id}))
+            .collect::<Vec<_>>();
+        let config = BatchConfig {
+            max_concurrency: 2,
+            ..BatchConfig::default()
+        };
+
+        let result = process_batch_with(JsonRpcMessage::Batch(batch), &handler, &config)
+            .await
+            .unwrap();
+        match result {
+            Some(JsonRpcMessage::Batch(responses)) => {
+                let ids: Vec<_> = responses.iter().map(|r| r["id"].clone()).collect();
+                assert_eq!(ids, (1..=6).map(|id| json!(id)).collect::<Vec<_>>());
+            }
+            other => panic!("Expected batch response, got {other:?}"),
+        }
+        assert_eq!(peak.load(Ordering::SeqCst), 2);
+    }
+
     #[test]
     fn test_create_error_response() {
         let error = McpError::parse_error("Test error");
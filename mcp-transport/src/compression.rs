@@ -0,0 +1,277 @@
+//! HTTP body compression and `Accept-Encoding` negotiation
+//!
+//! Supports gzip, deflate (zlib framing, as HTTP defines it) and zstd. The
+//! HTTP transport uses [`CompressionConfig::negotiate`] to pick a response
+//! encoding and [`decompress`] to unpack request bodies sent with a
+//! `Content-Encoding`.
+//!
+//! Connection-oriented streams (SSE and WebSocket) negotiate once when the
+//! connection is set up and keep that encoding for every message after it;
+//! SSE streams go through a [`StreamCompressor`].
+
+use flate2::Compression;
+use flate2::read::{GzDecoder, ZlibDecoder};
+use flate2::write::{GzEncoder, ZlibEncoder};
+use serde::{Deserialize, Serialize};
+use std::io::{Read, Write};
+use thiserror::Error;
+
+/// Compression algorithms the HTTP transport can negotiate
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CompressionAlgorithm {
+    Gzip,
+    Deflate,
+    Zstd,
+}
+
+impl CompressionAlgorithm {
+    /// Token used for this algorithm in `Accept-Encoding`/`Content-Encoding`
+    pub fn encoding(&self) -> &'static str {
+        match self {
+            Self::Gzip => "gzip",
+            Self::Deflate => "deflate",
+            Self::Zstd => "zstd",
+        }
+    }
+
+    /// Parse a content-coding token, accepting the legacy `x-gzip` alias
+    pub fn from_encoding(token: &str) -> Option<Self> {
+        match token.trim().to_ascii_lowercase().as_str() {
+            "gzip" | "x-gzip" => Some(Self::Gzip),
+            "deflate" => Some(Self::Deflate),
+            "zstd" => Some(Self::Zstd),
+            _ => None,
+        }
+    }
+}
+
+/// Errors from compressing or decompressing a body
+#[derive(Debug, Error)]
+pub enum CompressionError {
+    #[error("Unsupported content encoding: {0}")]
+    Unsupported(String),
+
+    #[error("Decompressed body exceeds {0} bytes")]
+    TooLarge(usize),
+
+    #[error("Compression I/O error: {0}")]
+    Io(#[from] std::io::Error),
+}
+
+/// Response compression settings for the HTTP transport
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CompressionConfig {
+    /// Algorithms the server may use, in order of preference when the
+    /// client weighs several equally; empty disables compression
+    pub algorithms: Vec<CompressionAlgorithm>,
+    /// Responses smaller than this many bytes are sent uncompressed
+    pub min_size: usize,
+}
+
+impl Default for CompressionConfig {
+    fn default() -> Self {
+        Self {
+            algorithms: vec![
+                CompressionAlgorithm::Zstd,
+                CompressionAlgorithm::Gzip,
+                CompressionAlgorithm::Deflate,
+            ],
+            min_size: 1024,
+        }
+    }
+}
+
+impl CompressionConfig {
+    /// Never compress responses, and reject compressed requests
+    pub fn disabled() -> Self {
+        Self {
+            algorithms: Vec::new(),
+            min_size: 0,
+        }
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        !self.algorithms.is_empty()
+    }
+
+    pub fn allows(&self, algorithm: CompressionAlgorithm) -> bool {
+        self.algorithms.contains(&algorithm)
+    }
+
+    /// Pick the response encoding for an `Accept-Encoding` header value
+    ///
+    /// The allowed algorithm with the highest q-value wins, ties going to the
+    /// earlier entry in `algorithms`. A `*` entry covers algorithms the header
+    /// doesn't name, and `q=0` rules an algorithm out.
+    pub fn negotiate(&self, accept_encoding: &str) -> Option<CompressionAlgorithm> {
+        let mut wildcard = None;
+        let mut named = Vec::new();
+        for entry in accept_encoding.split(',') {
+            let mut parts = entry.split(';');
+            let token = parts.next().unwrap_or("").trim();
+            if token.is_empty() {
+                continue;
+            }
+            let quality = parts
+                .filter_map(|param| {
+                    let (key, value) = param.split_once('=')?;
+                    key.trim()
+                        .eq_ignore_ascii_case("q")
+                        .then(|| value.trim().parse::<f32>().ok())
+                        .flatten()
+                })
+                .next()
+                .unwrap_or(1.0);
+
+            if token == "*" {
+                wildcard = Some(quality);
+            } else if let Some(algorithm) = CompressionAlgorithm::from_encoding(token) {
+                named.push((algorithm, quality));
+            }
+        }
+
+        let mut best: Option<(CompressionAlgorithm, f32)> = None;
+        for &algorithm in &self.algorithms {
+            let quality = named
+                .iter()
+                .find(|(named, _)| *named == algorithm)
+                .map(|(_, quality)| *quality)
+                .or(wildcard)
+                .unwrap_or(0.0);
+            if quality > 0.0 && best.is_none_or(|(_, best_quality)| quality > best_quality) {
+                best = Some((algorithm, quality));
+            }
+        }
+        best.map(|(algorithm, _)| algorithm)
+    }
+}
+
+/// Compress `data` with `algorithm`
+pub fn compress(algorithm: CompressionAlgorithm, data: &[u8]) -> Result<Vec<u8>, CompressionError> {
+    let compressed = match algorithm {
+        CompressionAlgorithm::Gzip => {
+            let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+            encoder.write_all(data)?;
+            encoder.finish()?
+        }
+        CompressionAlgorithm::Deflate => {
+            let mut encoder = ZlibEncoder::new(Vec::new(), Compression::default());
+            encoder.write_all(data)?;
+            encoder.finish()?
+        }
+        CompressionAlgorithm::Zstd => zstd::stream::encode_all(data, 0)?,
+    };
+    Ok(compressed)
+}
+
+/// Compresses a stream chunk by chunk with a single encoder
+///
+/// Every chunk is flushed so the peer can decode it as soon as it arrives,
+/// while the encoder keeps its state across chunks, so later messages
+/// compress against earlier ones.
+pub struct StreamCompressor {
+    algorithm: CompressionAlgorithm,
+    encoder: StreamEncoder,
+}
+
+enum StreamEncoder {
+    Gzip(GzEncoder<Vec<u8>>),
+    Deflate(ZlibEncoder<Vec<u8>>),
+    Zstd(zstd::stream::write::Encoder<'static, Vec<u8>>),
+}
+
+impl StreamCompressor {
+    pub fn new(algorithm: CompressionAlgorithm) -> Result<Self, CompressionError> {
+        let encoder = match algorithm {
+            CompressionAlgorithm::Gzip => {
+                StreamEncoder::Gzip(GzEncoder::new(Vec::new(), Compression::default()))
+            }
+            CompressionAlgorithm::Deflate => {
+                StreamEncoder::Deflate(ZlibEncoder::new(Vec::new(), Compression::default()))
+            }
+            CompressionAlgorithm::Zstd => {
+                StreamEncoder::Zstd(zstd::stream::write::Encoder::new(Vec::new(), 0)?)
+            }
+        };
+        Ok(Self { algorithm, encoder })
+    }
+
+    pub fn algorithm(&self) -> CompressionAlgorithm {
+        self.algorithm
+    }
+
+    /// Compress `chunk` and flush it, returning the bytes to send
+    pub fn compress_chunk(&mut self, chunk: &[u8]) -> Result<Vec<u8>, CompressionError> {
+        let output = match &mut self.encoder {
+            StreamEncoder::Gzip(encoder) => {
+                encoder.write_all(chunk)?;
+                encoder.flush()?;
+                encoder.get_mut()
+            }
+            StreamEncoder::Deflate(encoder) => {
+                encoder.write_all(chunk)?;
+                encoder.flush()?;
+                encoder.get_mut()
+            }
+            StreamEncoder::Zstd(encoder) => {
+                encoder.write_all(chunk)?;
+                encoder.flush()?;
+                encoder.get_mut()
+            }
+        };
+        Ok(std::mem::take(output))
+    }
+
+    /// End the stream, returning the trailing bytes to send
+    pub fn finish(self) -> Result<Vec<u8>, CompressionError> {
+        let output = match self.encoder {
+            StreamEncoder::Gzip(encoder) => encoder.finish()?,
+            StreamEncoder::Deflate(encoder) => encoder.finish()?,
+            StreamEncoder::Zstd(encoder) => encoder.finish()?,
+        };
+        Ok(output)
+    }
+}
+
+/// Decompress a body sent with the given `Content-Encoding` value
+///
+/// Stacked codings (`gzip, zstd`) are undone last-applied first. Output is
+/// capped at `limit` bytes so a small compressed body can't expand without
+/// bound.
+pub fn decompress(
+    content_encoding: &str,
+    data: &[u8],
+    limit: usize,
+) -> Result<Vec<u8>, CompressionError> {
+    let mut body = data.to_vec();
+    for token in content_encoding.rsplit(',') {
+        let token = token.trim();
+        if token.is_empty() || token.eq_ignore_ascii_case("identity") {
+            continue;
+        }
+        let algorithm = CompressionAlgorithm::from_encoding(token)
+            .ok_or_else(|| CompressionError::Unsupported(token.to_string()))?;
+        body = match algorithm {
+            CompressionAlgorithm::Gzip => read_limited(GzDecoder::new(body.as_slice()), limit)?,
+            CompressionAlgorithm::Deflate => {
+                read_limited(ZlibDecoder::new(body.as_slice()), limit)?
+            }
+            CompressionAlgorithm::Zstd => {
+                read_limited(zstd::stream::read::Decoder::new(body.as_slice())?, limit)?
+            }
+        };
+    }
+    Ok(body)
+}
+
+fn read_limited(reader: impl Read, limit: usize) -> Result<Vec<u8>, CompressionError> {
+    let mut output = Vec::new();
+    reader
+        .take(limit.saturating_add(1) as u64)
+        .read_to_end(&mut output)?;
+    if output.len() > limit {
+        return Err(CompressionError::TooLarge(limit));
+    }
+    Ok(output)
+}
@@ -0,0 +1,157 @@
+//! Tests for HTTP body compression and encoding negotiation
+
+#[cfg(test)]
+mod tests {
+    use super::super::compression::*;
+
+    fn payload() -> Vec<u8> {
+        br#"{"jsonrpc":"2.0","id":1,"result":{"content":[{"type":"text","text":"hello"}]}}"#
+            .repeat(64)
+    }
+
+    #[test]
+    fn test_algorithm_encoding_tokens() {
+        for algorithm in [
+            CompressionAlgorithm::Gzip,
+            CompressionAlgorithm::Deflate,
+            CompressionAlgorithm::Zstd,
+        ] {
+            assert_eq!(
+                CompressionAlgorithm::from_encoding(algorithm.encoding()),
+                Some(algorithm)
+            );
+        }
+        assert_eq!(
+            CompressionAlgorithm::from_encoding(" X-GZIP "),
+            Some(CompressionAlgorithm::Gzip)
+        );
+        assert_eq!(CompressionAlgorithm::from_encoding("br"), None);
+    }
+
+    #[test]
+    fn test_compression_config_default() {
+        let config = CompressionConfig::default();
+        assert!(config.is_enabled());
+        assert_eq!(config.min_size, 1024);
+        assert_eq!(config.algorithms[0], CompressionAlgorithm::Zstd);
+        assert!(!CompressionConfig::disabled().is_enabled());
+    }
+
+    #[test]
+    fn test_negotiate_prefers_highest_quality() {
+        let config = CompressionConfig::default();
+        assert_eq!(
+            config.negotiate("gzip;q=0.9, deflate;q=0.5"),
+            Some(CompressionAlgorithm::Gzip)
+        );
+        assert_eq!(
+            config.negotiate("gzip;q=0.5, zstd;q=0.8"),
+            Some(CompressionAlgorithm::Zstd)
+        );
+        // Equal weights fall back to the configured preference order
+        assert_eq!(
+            config.negotiate("deflate, gzip, zstd"),
+            Some(CompressionAlgorithm::Zstd)
+        );
+    }
+
+    #[test]
+    fn test_negotiate_honours_exclusions_and_wildcard() {
+        let config = CompressionConfig::default();
+        assert_eq!(config.negotiate(""), None);
+        assert_eq!(config.negotiate("identity"), None);
+        assert_eq!(config.negotiate("br"), None);
+        assert_eq!(config.negotiate("gzip;q=0"), None);
+        assert_eq!(
+            config.negotiate("*, zstd;q=0"),
+            Some(CompressionAlgorithm::Gzip)
+        );
+        assert_eq!(config.negotiate("*;q=0"), None);
+    }
+
+    #[test]
+    fn test_negotiate_only_allowed_algorithms() {
+        let config = CompressionConfig {
+            algorithms: vec![CompressionAlgorithm::Deflate],
+            min_size: 0,
+        };
+        assert_eq!(config.negotiate("zstd, gzip"), None);
+        assert_eq!(
+            config.negotiate("zstd, deflate;q=0.1"),
+            Some(CompressionAlgorithm::Deflate)
+        );
+        assert_eq!(CompressionConfig::disabled().negotiate("gzip"), None);
+    }
+
+    #[test]
+    fn test_round_trip_each_algorithm() {
+        let data = payload();
+        for algorithm in [
+            CompressionAlgorithm::Gzip,
+            CompressionAlgorithm::Deflate,
+            CompressionAlgorithm::Zstd,
+        ] {
+            let compressed = compress(algorithm, &data).unwrap();
+            assert!(compressed.len() < data.len(), "{algorithm:?} didn't shrink");
+            let restored = decompress(algorithm.encoding(), &compressed, data.len()).unwrap();
+            assert_eq!(restored, data);
+        }
+    }
+
+    #[test]
+    fn test_decompress_stacked_and_identity() {
+        let data = payload();
+        let once = compress(CompressionAlgorithm::Gzip, &data).unwrap();
+        let twice = compress(CompressionAlgorithm::Zstd, &once).unwrap();
+        assert_eq!(decompress("gzip, zstd", &twice, data.len()).unwrap(), data);
+        assert_eq!(decompress("identity", &data, data.len()).unwrap(), data);
+    }
+
+    #[test]
+    fn test_decompress_errors() {
+        let data = payload();
+        let compressed = compress(CompressionAlgorithm::Gzip, &data).unwrap();
+
+        assert!(matches!(
+            decompress("gzip", &compressed, data.len() - 1),
+            Err(CompressionError::TooLarge(_))
+        ));
+        assert!(matches!(
+            decompress("br", &compressed, data.len()),
+            Err(CompressionError::Unsupported(token)) if token == "br"
+        ));
+        assert!(matches!(
+            decompress("gzip", b"not gzip", data.len()),
+            Err(CompressionError::Io(_))
+        ));
+    }
+
+    #[test]
+    fn test_stream_compressor_round_trip() {
+        let chunks = [
+            b"data: one\n\n".to_vec(),
+            payload(),
+            b"data: three\n\n".to_vec(),
+        ];
+        for algorithm in [
+            CompressionAlgorithm::Gzip,
+            CompressionAlgorithm::Deflate,
+            CompressionAlgorithm::Zstd,
+        ] {
+            let mut compressor = StreamCompressor::new(algorithm).unwrap();
+            assert_eq!(compressor.algorithm(), algorithm);
+            let mut stream = Vec::new();
+            for chunk in &chunks {
+                let compressed = compressor.compress_chunk(chunk).unwrap();
+                assert!(!compressed.is_empty());
+                stream.extend(compressed);
+            }
+            stream.extend(compressor.finish().unwrap());
+
+            assert_eq!(
+                decompress(algorithm.encoding(), &stream, 1 << 20).unwrap(),
+                chunks.concat()
+            );
+        }
+    }
+}
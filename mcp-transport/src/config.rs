@@ -1,6 +1,7 @@
 //! Transport configuration
 
 use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
 
 /// Transport configuration
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -9,13 +10,27 @@ pub enum TransportConfig {
     Stdio,
 
     /// HTTP transport with Server-Sent Events
-    Http { port: u16, host: Option<String> },
+    Http {
+        port: u16,
+        host: Option<String>,
+        /// Maximum requests handled concurrently (None = unlimited)
+        #[serde(default)]
+        max_in_flight: Option<usize>,
+    },
 
     /// Streamable HTTP transport (MCP Inspector compatible)
     StreamableHttp { port: u16, host: Option<String> },
 
     /// WebSocket transport
     WebSocket { port: u16, host: Option<String> },
+
+    /// Unix domain socket transport for co-located clients (Unix only)
+    UnixSocket {
+        path: PathBuf,
+        /// Permission bits applied to the socket file (e.g. `0o600`)
+        #[serde(default)]
+        mode: Option<u32>,
+    },
 }
 
 impl Default for TransportConfig {
@@ -32,7 +47,11 @@ impl TransportConfig {
 
     /// Create HTTP transport configuration
     pub fn http(port: u16) -> Self {
-        Self::Http { port, host: None }
+        Self::Http {
+            port,
+            host: None,
+            max_in_flight: None,
+        }
     }
 
     /// Create Streamable HTTP transport configuration (MCP Inspector compatible)
@@ -44,4 +63,12 @@ impl TransportConfig {
     pub fn websocket(port: u16) -> Self {
         Self::WebSocket { port, host: None }
     }
+
+    /// Create Unix domain socket transport configuration
+    pub fn unix_socket(path: impl Into<PathBuf>) -> Self {
+        Self::UnixSocket {
+            path: path.into(),
+            mode: None,
+        }
+    }
 }
@@ -11,6 +11,7 @@ mod tests {
         let http = TransportConfig::Http {
             port: 8080,
             host: None,
+            max_in_flight: None,
         };
         let streamable = TransportConfig::StreamableHttp {
             port: 8081,
@@ -39,6 +40,7 @@ mod tests {
             TransportConfig::Http {
                 host: Some("localhost".to_string()),
                 port: 8080,
+                max_in_flight: None,
             },
             TransportConfig::WebSocket {
                 host: Some("127.0.0.1".to_string()),
@@ -54,8 +56,12 @@ mod tests {
 
             match (&config, &recovered) {
                 (
-                    TransportConfig::Http { host: h1, port: p1 },
-                    TransportConfig::Http { host: h2, port: p2 },
+                    TransportConfig::Http {
+                        host: h1, port: p1, ..
+                    },
+                    TransportConfig::Http {
+                        host: h2, port: p2, ..
+                    },
                 ) => {
                     assert_eq!(h1, h2);
                     assert_eq!(p1, p2);
@@ -80,10 +86,11 @@ mod tests {
         let config = TransportConfig::Http {
             host: Some("0.0.0.0".to_string()),
             port: 3000,
+            max_in_flight: None,
         };
 
         match config {
-            TransportConfig::Http { host, port } => {
+            TransportConfig::Http { host, port, .. } => {
                 assert_eq!(host, Some("0.0.0.0".to_string()));
                 assert_eq!(port, 3000);
             }
@@ -126,10 +133,12 @@ mod tests {
             TransportConfig::Http {
                 host: Some("".to_string()), // Empty host
                 port: 0,                    // Port 0 (system assigned)
+                max_in_flight: None,
             },
             TransportConfig::Http {
                 host: Some("255.255.255.255".to_string()), // IPv4 broadcast
                 port: 65535,                               // Maximum port number
+                max_in_flight: None,
             },
             TransportConfig::WebSocket {
                 host: Some("::1".to_string()), // IPv6 localhost
@@ -178,6 +187,7 @@ mod tests {
             let config = TransportConfig::Http {
                 host: Some(host.to_string()),
                 port: 8080,
+                max_in_flight: None,
             };
 
             // Should handle all host variants
@@ -212,6 +222,7 @@ mod tests {
                 TransportConfig::Http {
                     host: Some("localhost".to_string()),
                     port,
+                    max_in_flight: None,
                 },
                 TransportConfig::WebSocket {
                     host: Some("localhost".to_string()),
@@ -244,6 +255,7 @@ mod tests {
         let config = TransportConfig::Http {
             host: Some("localhost".to_string()),
             port: 8080,
+            max_in_flight: None,
         };
 
         let json = serde_json::to_string_pretty(&config).unwrap();
@@ -263,6 +275,7 @@ mod tests {
                 TransportConfig::Http {
                     host: Some("example.com".to_string()),
                     port: 443,
+                    max_in_flight: None,
                 },
                 "Http",
             ),
@@ -288,6 +301,7 @@ mod tests {
         let original = TransportConfig::Http {
             host: Some("original.com".to_string()),
             port: 9999,
+            max_in_flight: None,
         };
 
         let cloned = original.clone();
@@ -295,8 +309,12 @@ mod tests {
         // Should be equal but not the same object
         match (&original, &cloned) {
             (
-                TransportConfig::Http { host: h1, port: p1 },
-                TransportConfig::Http { host: h2, port: p2 },
+                TransportConfig::Http {
+                    host: h1, port: p1, ..
+                },
+                TransportConfig::Http {
+                    host: h2, port: p2, ..
+                },
             ) => {
                 assert_eq!(h1, h2);
                 assert_eq!(p1, p2);
@@ -0,0 +1,122 @@
+//! Tracking of in-progress request handlers for graceful shutdown
+//!
+//! Transports wrap their [`RequestHandler`] with [`ActiveHandlers::wrap`] so
+//! every call is counted while it runs. On shutdown a transport first stops
+//! accepting new work, then waits with [`ActiveHandlers::drain`] for the
+//! counted handlers to finish before tearing down what remains.
+
+use crate::RequestHandler;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::Duration;
+use tokio::sync::Notify;
+
+#[derive(Debug, Default)]
+struct Inner {
+    active: AtomicUsize,
+    idle: Notify,
+}
+
+/// Count of request handlers currently running
+#[derive(Debug, Clone, Default)]
+pub struct ActiveHandlers {
+    inner: Arc<Inner>,
+}
+
+impl ActiveHandlers {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Count a handler as active until the returned guard is dropped
+    pub fn track(&self) -> ActiveHandlerGuard {
+        self.inner.active.fetch_add(1, Ordering::SeqCst);
+        ActiveHandlerGuard {
+            inner: self.inner.clone(),
+        }
+    }
+
+    /// Wrap a handler so each of its calls is tracked
+    pub fn wrap(&self, handler: RequestHandler) -> RequestHandler {
+        let active = self.clone();
+        Box::new(move |request| {
+            let guard = active.track();
+            let response = handler(request);
+            Box::pin(async move {
+                let _guard = guard;
+                response.await
+            })
+        })
+    }
+
+    /// Number of handlers currently running
+    pub fn count(&self) -> usize {
+        self.inner.active.load(Ordering::SeqCst)
+    }
+
+    /// Wait up to `grace` for every active handler to finish
+    ///
+    /// Returns `false` if handlers were still running when `grace` elapsed.
+    pub async fn drain(&self, grace: Duration) -> bool {
+        let deadline = tokio::time::Instant::now() + grace;
+        loop {
+            // Register for the wakeup before checking, so a handler finishing
+            // in between isn't missed
+            let idle = self.inner.idle.notified();
+            tokio::pin!(idle);
+            idle.as_mut().enable();
+
+            if self.count() == 0 {
+                return true;
+            }
+            if tokio::time::timeout_at(deadline, idle).await.is_err() {
+                return self.count() == 0;
+            }
+        }
+    }
+}
+
+/// Registration of one running handler
+#[derive(Debug)]
+pub struct ActiveHandlerGuard {
+    inner: Arc<Inner>,
+}
+
+impl Drop for ActiveHandlerGuard {
+    fn drop(&mut self) {
+        if self.inner.active.fetch_sub(1, Ordering::SeqCst) == 1 {
+            self.inner.idle.notify_waiters();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_drain_waits_for_active_handlers() {
+        let active = ActiveHandlers::new();
+        assert!(active.drain(Duration::ZERO).await);
+
+        let guard = active.track();
+        assert_eq!(active.count(), 1);
+        let release = tokio::spawn(async move {
+            tokio::time::sleep(Duration::from_millis(20)).await;
+            drop(guard);
+        });
+
+        assert!(active.drain(Duration::from_secs(5)).await);
+        assert_eq!(active.count(), 0);
+        release.await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_drain_gives_up_after_grace() {
+        let active = ActiveHandlers::new();
+        let _stuck = active.track();
+
+        assert!(!active.drain(Duration::from_millis(20)).await);
+        assert_eq!(active.count(), 1);
+    }
+}
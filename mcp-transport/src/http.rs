@@ -1,19 +1,29 @@
 //! HTTP transport with Server-Sent Events (SSE) support
 
 use crate::{
-    RequestHandler, Transport, TransportError,
-    batch::{JsonRpcMessage, process_batch},
+    ConnectionInfo, DisconnectHandler, RequestHandler, Transport, TransportError,
+    batch::{BatchConfig, JsonRpcMessage, process_batch_with},
+    compression::{
+        CompressionAlgorithm, CompressionConfig, CompressionError, StreamCompressor, compress,
+        decompress,
+    },
+    drain::ActiveHandlers,
+    notify_disconnect,
     validation::validate_message_string,
 };
 use async_trait::async_trait;
 use axum::response::sse::{Event, KeepAlive};
 use axum::{
     Router,
-    extract::{Query, State},
+    extract::{ConnectInfo, DefaultBodyLimit, Query, State},
     http::{
-        HeaderMap, StatusCode,
-        header::{AUTHORIZATION, ORIGIN},
+        HeaderMap, HeaderValue, Method, StatusCode,
+        header::{
+            ACCEPT_ENCODING, AUTHORIZATION, CONTENT_ENCODING, CONTENT_LENGTH, CONTENT_TYPE, HOST,
+            LOCATION, ORIGIN, RETRY_AFTER, TRANSFER_ENCODING, VARY,
+        },
     },
+    middleware::Next,
     response::{IntoResponse, Response as AxumResponse, Sse},
     routing::{get, post},
 };
@@ -21,8 +31,16 @@ use axum::{
 // mcp_protocol types are imported via batch module
 use serde::Deserialize;
 use serde_json;
-use std::{collections::HashMap, net::SocketAddr, sync::Arc, time::Duration};
-use tokio::sync::{Mutex, RwLock, broadcast};
+use std::{
+    collections::HashMap,
+    net::SocketAddr,
+    sync::{
+        Arc,
+        atomic::{AtomicUsize, Ordering},
+    },
+    time::Duration,
+};
+use tokio::sync::{Mutex, OwnedSemaphorePermit, RwLock, Semaphore, broadcast, oneshot};
 use tower::ServiceBuilder;
 use tower_http::cors::CorsLayer;
 use tracing::{debug, error, info, warn};
@@ -36,6 +54,9 @@ pub struct HttpConfig {
     /// Host to bind to (default: localhost)
     pub host: String,
     /// Maximum message size in bytes
+    ///
+    /// Also caps request bodies however they are framed: chunked bodies are
+    /// buffered up to this size and rejected with 413 beyond it.
     pub max_message_size: usize,
     /// Enable CORS
     pub enable_cors: bool,
@@ -49,6 +70,27 @@ pub struct HttpConfig {
     pub require_auth: bool,
     /// Valid bearer tokens (for testing)
     pub valid_tokens: Vec<String>,
+    /// Refuse plaintext requests to the MCP endpoints
+    ///
+    /// The transport doesn't terminate TLS itself, so a request only counts
+    /// as HTTPS when a trusted proxy says so via `X-Forwarded-Proto`.
+    pub require_https: bool,
+    /// Redirect plaintext `GET`/`HEAD` requests to HTTPS with a 301 instead
+    /// of rejecting them; other methods are always rejected with a 400
+    pub https_redirect: bool,
+    /// Peer IP addresses whose `X-Forwarded-Proto` header is trusted
+    pub trusted_proxies: Vec<String>,
+    /// Maximum requests handled concurrently (None = unlimited)
+    ///
+    /// Requests arriving while every slot is busy are rejected with a 503
+    /// rather than queued, so load beyond the limit can't pile up in memory.
+    pub max_in_flight: Option<usize>,
+    /// `Retry-After` seconds sent with 503 responses when saturated
+    pub retry_after_secs: u64,
+    /// Response compression and accepted request `Content-Encoding`s
+    pub compression: CompressionConfig,
+    /// How JSON-RPC batches are processed
+    pub batch: BatchConfig,
 }
 
 impl Default for HttpConfig {
@@ -63,6 +105,13 @@ impl Default for HttpConfig {
             session_timeout_secs: 300, // 5 minutes
             require_auth: false,
             valid_tokens: vec![],
+            require_https: false,
+            https_redirect: false,
+            trusted_proxies: vec![],
+            max_in_flight: None,
+            retry_after_secs: 1,
+            compression: CompressionConfig::default(),
+            batch: BatchConfig::default(),
         }
     }
 }
@@ -87,6 +136,53 @@ struct HttpState {
     handler: Arc<RequestHandler>,
     config: HttpConfig,
     sessions: Arc<RwLock<HashMap<String, SessionInfo>>>,
+    limiter: Arc<RequestLimiter>,
+}
+
+/// Bounds concurrent request handling and tracks the in-flight count
+#[derive(Debug, Default)]
+struct RequestLimiter {
+    permits: Option<Arc<Semaphore>>,
+    in_flight: AtomicUsize,
+}
+
+impl RequestLimiter {
+    fn new(max_in_flight: Option<usize>) -> Self {
+        Self {
+            permits: max_in_flight.map(|max| Arc::new(Semaphore::new(max))),
+            in_flight: AtomicUsize::new(0),
+        }
+    }
+
+    /// Claim a slot for one request, or `None` when every slot is busy
+    fn try_acquire(self: &Arc<Self>) -> Option<InFlightPermit> {
+        let permit = match &self.permits {
+            Some(permits) => Some(permits.clone().try_acquire_owned().ok()?),
+            None => None,
+        };
+        self.in_flight.fetch_add(1, Ordering::Relaxed);
+        Some(InFlightPermit {
+            limiter: self.clone(),
+            _permit: permit,
+        })
+    }
+
+    fn in_flight(&self) -> usize {
+        self.in_flight.load(Ordering::Relaxed)
+    }
+}
+
+/// Slot held while a request is handled; released on drop, including
+/// when the handler panics
+struct InFlightPermit {
+    limiter: Arc<RequestLimiter>,
+    _permit: Option<OwnedSemaphorePermit>,
+}
+
+impl Drop for InFlightPermit {
+    fn drop(&mut self) {
+        self.limiter.in_flight.fetch_sub(1, Ordering::Relaxed);
+    }
 }
 
 /// Query parameters for SSE endpoint
@@ -116,6 +212,13 @@ pub struct HttpTransport {
     config: HttpConfig,
     state: Option<HttpState>,
     server_handle: Option<tokio::task::JoinHandle<()>>,
+    limiter: Arc<RequestLimiter>,
+    /// Handlers still running, waited on by `shutdown`
+    active: ActiveHandlers,
+    /// Tells the server to stop accepting connections
+    shutdown_signal: Option<oneshot::Sender<()>>,
+    /// Told as sessions expire
+    on_disconnect: Option<DisconnectHandler>,
 }
 
 impl HttpTransport {
@@ -126,11 +229,7 @@ impl HttpTransport {
             ..Default::default()
         };
 
-        Self {
-            config,
-            state: None,
-            server_handle: None,
-        }
+        Self::with_config(config)
     }
 
     /// Get the configuration
@@ -148,6 +247,13 @@ impl HttpTransport {
         self.server_handle.is_some()
     }
 
+    /// Number of requests currently being handled
+    ///
+    /// Compare against `max_in_flight` to observe saturation.
+    pub fn in_flight_requests(&self) -> usize {
+        self.limiter.in_flight()
+    }
+
     /// Send a message to all connected SSE clients
     pub async fn broadcast_message(&self, message: &str) -> Result<(), TransportError> {
         if let Some(ref state) = self.state {
@@ -167,10 +273,15 @@ impl HttpTransport {
 
     /// Create a new HTTP transport with custom configuration
     pub fn with_config(config: HttpConfig) -> Self {
+        let limiter = Arc::new(RequestLimiter::new(config.max_in_flight));
         Self {
             config,
             state: None,
             server_handle: None,
+            limiter,
+            active: ActiveHandlers::new(),
+            shutdown_signal: None,
+            on_disconnect: None,
         }
     }
 
@@ -226,19 +337,35 @@ impl HttpTransport {
         }
     }
 
-    /// Clean up expired sessions
-    async fn cleanup_sessions(state: Arc<HttpState>) {
+    /// Clean up expired sessions, returning their ids
+    async fn cleanup_sessions(state: Arc<HttpState>) -> Vec<String> {
         let timeout = Duration::from_secs(state.config.session_timeout_secs);
         let now = std::time::Instant::now();
 
+        let mut expired_ids = Vec::new();
         let mut sessions = state.sessions.write().await;
         sessions.retain(|id, session| {
             let expired = now.duration_since(session.last_activity) > timeout;
             if expired {
                 debug!("Removing expired session: {}", id);
+                expired_ids.push(id.clone());
             }
             !expired
         });
+        expired_ids
+    }
+
+    /// Whether a request arrived over HTTPS, according to a trusted proxy
+    pub fn is_https_request(config: &HttpConfig, headers: &HeaderMap, peer: SocketAddr) -> bool {
+        let peer_ip = peer.ip().to_string();
+        if !config.trusted_proxies.contains(&peer_ip) {
+            return false;
+        }
+        headers
+            .get("X-Forwarded-Proto")
+            .and_then(|proto| proto.to_str().ok())
+            .and_then(|proto| proto.split(',').next())
+            .is_some_and(|proto| proto.trim().eq_ignore_ascii_case("https"))
     }
 
     /// Validate origin header
@@ -379,8 +506,27 @@ async fn handle_post(
         HttpTransport::update_session_activity(state.clone(), &session_id).await;
     }
 
-    // Process the message
-    match process_batch(message, &state.handler).await {
+    // Hold a slot until the handler finishes so load beyond the limit is shed
+    let Some(_permit) = state.limiter.try_acquire() else {
+        warn!(
+            "Rejecting request: {} requests already in flight",
+            state.limiter.in_flight()
+        );
+        return Ok(AxumResponse::builder()
+            .status(StatusCode::SERVICE_UNAVAILABLE)
+            .header(RETRY_AFTER, state.config.retry_after_secs)
+            .body("".to_string())
+            .unwrap());
+    };
+
+    // Process the message within its session, so per-session server state
+    // (negotiated version, client capabilities) isn't shared between clients
+    let processed = crate::with_session(
+        session_id.clone(),
+        process_batch_with(message, &state.handler, &state.config.batch),
+    )
+    .await;
+    match processed {
         Ok(Some(response_message)) => {
             let response_json = response_message
                 .to_string()
@@ -580,6 +726,15 @@ async fn handle_post(
     }
 }
 
+/// Resolves once `shutdown` asks the server to stop accepting connections
+///
+/// A dropped sender isn't a shutdown request; `stop` handles that case.
+async fn shutdown_requested(signal: oneshot::Receiver<()>) {
+    if signal.await.is_err() {
+        std::future::pending::<()>().await;
+    }
+}
+
 /// Handle SSE requests (server-to-client streaming)
 async fn handle_sse(
     uri: axum::http::Uri,
@@ -645,13 +800,32 @@ async fn handle_sse(
         let mut receiver = receiver;
         loop {
             tokio::select! {
-                Ok(data) = receiver.recv() => {
-                    event_counter += 1;
-                    yield Ok::<_, axum::Error>(Event::default()
-                        .id(event_counter.to_string())
-                        .event("message")
-                        .data(data));
-                }
+                received = receiver.recv() => match received {
+                    Ok(data) => {
+                        event_counter += 1;
+                        yield Ok::<_, axum::Error>(Event::default()
+                            .id(event_counter.to_string())
+                            .event("message")
+                            .data(data));
+                    }
+                    Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                        warn!("SSE stream for session {} skipped {} events", session_id, skipped);
+                    }
+                    Err(broadcast::error::RecvError::Closed) => {
+                        // The session was closed by `stop` or expired; tell the
+                        // client before ending the stream so it can reconnect
+                        info!("Closing SSE stream for session: {}", session_id);
+                        event_counter += 1;
+                        yield Ok::<_, axum::Error>(Event::default()
+                            .id(event_counter.to_string())
+                            .event("close")
+                            .data(serde_json::json!({
+                                "type": "close",
+                                "sessionId": session_id
+                            }).to_string()));
+                        break;
+                    }
+                },
                 _ = tokio::time::sleep(Duration::from_secs(30)) => {
                     // Send periodic ping to keep connection alive
                     event_counter += 1;
@@ -696,7 +870,235 @@ async fn handle_sse(
     Ok(response)
 }
 
+/// Run a request within the [`ConnectionInfo`] of its HTTP connection
+///
+/// An HTTP exchange is its own connection, identified by its MCP session
+/// where the client sent one and by the peer address otherwise.
+pub(crate) async fn scope_connection(
+    State(transport): State<&'static str>,
+    peer: Option<ConnectInfo<SocketAddr>>,
+    request: axum::extract::Request,
+    next: Next,
+) -> AxumResponse {
+    let headers = request.headers();
+    let session_id = headers
+        .get("mcp-session-id")
+        .and_then(|value| value.to_str().ok());
+    let peer = peer.map(|ConnectInfo(peer)| peer);
+    let connection_id = session_id
+        .map(str::to_string)
+        .or_else(|| peer.map(|peer| peer.to_string()))
+        .unwrap_or_default();
+    let mut connection = ConnectionInfo::new(connection_id, transport).with_headers(
+        headers
+            .iter()
+            .filter_map(|(name, value)| Some((name.as_str(), value.to_str().ok()?))),
+    );
+    connection.peer_addr = peer;
+    crate::with_connection(connection, next.run(request)).await
+}
+
 /// Handle health check requests
+/// Reject or redirect plaintext requests when HTTPS is required
+async fn enforce_https(
+    State(state): State<Arc<HttpState>>,
+    ConnectInfo(peer): ConnectInfo<SocketAddr>,
+    request: axum::extract::Request,
+    next: Next,
+) -> AxumResponse {
+    let config = &state.config;
+    if !config.require_https || HttpTransport::is_https_request(config, request.headers(), peer) {
+        return next.run(request).await;
+    }
+
+    // Redirecting a POST would make many clients retry it as a GET, so only
+    // safe methods are redirected
+    let host = request
+        .headers()
+        .get(HOST)
+        .and_then(|host| host.to_str().ok());
+    if config.https_redirect
+        && matches!(*request.method(), Method::GET | Method::HEAD)
+        && let Some(host) = host
+    {
+        let location = format!("https://{host}{}", request.uri());
+        warn!(
+            "Redirecting plaintext request from {} to {}",
+            peer, location
+        );
+        return (StatusCode::MOVED_PERMANENTLY, [(LOCATION, location)]).into_response();
+    }
+
+    warn!("Rejecting plaintext request from {}", peer);
+    (StatusCode::BAD_REQUEST, "HTTPS required").into_response()
+}
+
+/// Decompress request bodies and compress responses per `HttpConfig::compression`
+///
+/// SSE responses aren't buffered: the encoding negotiated when the stream is
+/// opened applies to every event sent on it.
+async fn compress_exchange(
+    State(state): State<Arc<HttpState>>,
+    request: axum::extract::Request,
+    next: Next,
+) -> AxumResponse {
+    let config = &state.config;
+    let (mut parts, body) = request.into_parts();
+    let accept_encoding = parts
+        .headers
+        .get(ACCEPT_ENCODING)
+        .and_then(|value| value.to_str().ok())
+        .map(str::to_owned);
+
+    let body = match parts.headers.remove(CONTENT_ENCODING) {
+        Some(encoding) => {
+            let Ok(encoding) = encoding.to_str() else {
+                return StatusCode::UNSUPPORTED_MEDIA_TYPE.into_response();
+            };
+            let Ok(compressed) = axum::body::to_bytes(body, config.max_message_size).await else {
+                return StatusCode::PAYLOAD_TOO_LARGE.into_response();
+            };
+            match decompress_request(config, encoding, &compressed) {
+                Ok(body) => {
+                    parts
+                        .headers
+                        .insert(CONTENT_LENGTH, HeaderValue::from(body.len()));
+                    axum::body::Body::from(body)
+                }
+                Err(e) => {
+                    warn!("Rejecting request body: {}", e);
+                    let status = match e {
+                        CompressionError::Unsupported(_) => StatusCode::UNSUPPORTED_MEDIA_TYPE,
+                        CompressionError::TooLarge(_) => StatusCode::PAYLOAD_TOO_LARGE,
+                        CompressionError::Io(_) => StatusCode::BAD_REQUEST,
+                    };
+                    return (status, e.to_string()).into_response();
+                }
+            }
+        }
+        None => body,
+    };
+
+    let response = next
+        .run(axum::extract::Request::from_parts(parts, body))
+        .await;
+
+    let is_event_stream = response
+        .headers()
+        .get(CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .is_some_and(|value| value.starts_with("text/event-stream"));
+    if !config.compression.is_enabled() || response.headers().contains_key(CONTENT_ENCODING) {
+        return response;
+    }
+
+    let (mut parts, body) = response.into_parts();
+    parts
+        .headers
+        .append(VARY, HeaderValue::from_static("accept-encoding"));
+    let Some(algorithm) = accept_encoding.and_then(|accept| config.compression.negotiate(&accept))
+    else {
+        return AxumResponse::from_parts(parts, body);
+    };
+    if is_event_stream {
+        return compress_event_stream(parts, body, algorithm);
+    }
+
+    let body = match axum::body::to_bytes(body, usize::MAX).await {
+        Ok(body) => body,
+        Err(e) => {
+            error!("Failed to buffer response for compression: {}", e);
+            return StatusCode::INTERNAL_SERVER_ERROR.into_response();
+        }
+    };
+    if body.is_empty() || body.len() < config.compression.min_size {
+        return AxumResponse::from_parts(parts, axum::body::Body::from(body));
+    }
+
+    match compress(algorithm, &body) {
+        Ok(compressed) => {
+            debug!(
+                "Compressed response with {}: {} -> {} bytes",
+                algorithm.encoding(),
+                body.len(),
+                compressed.len()
+            );
+            parts.headers.insert(
+                CONTENT_ENCODING,
+                HeaderValue::from_static(algorithm.encoding()),
+            );
+            parts
+                .headers
+                .insert(CONTENT_LENGTH, HeaderValue::from(compressed.len()));
+            parts.headers.remove(TRANSFER_ENCODING);
+            AxumResponse::from_parts(parts, axum::body::Body::from(compressed))
+        }
+        Err(e) => {
+            warn!("Sending response uncompressed: {}", e);
+            AxumResponse::from_parts(parts, axum::body::Body::from(body))
+        }
+    }
+}
+
+/// Compress an SSE stream with the encoding negotiated when it was opened
+///
+/// One encoder serves the whole stream and is flushed after every chunk, so
+/// events still reach the client as they are sent.
+fn compress_event_stream(
+    mut parts: axum::http::response::Parts,
+    body: axum::body::Body,
+    algorithm: CompressionAlgorithm,
+) -> AxumResponse {
+    use futures_util::StreamExt;
+
+    let compressor = match StreamCompressor::new(algorithm) {
+        Ok(compressor) => compressor,
+        Err(e) => {
+            warn!("Sending event stream uncompressed: {}", e);
+            return AxumResponse::from_parts(parts, body);
+        }
+    };
+    debug!("Compressing event stream with {}", algorithm.encoding());
+
+    let chunks = futures_util::stream::unfold(
+        (body.into_data_stream(), Some(compressor)),
+        |(mut chunks, mut compressor)| async move {
+            let active = compressor.as_mut()?;
+            let output = match chunks.next().await {
+                Some(Ok(chunk)) => active.compress_chunk(&chunk),
+                Some(Err(e)) => return Some((Err(e), (chunks, None))),
+                None => compressor.take()?.finish(),
+            };
+            Some((output.map_err(axum::Error::new), (chunks, compressor)))
+        },
+    );
+
+    parts.headers.insert(
+        CONTENT_ENCODING,
+        HeaderValue::from_static(algorithm.encoding()),
+    );
+    parts.headers.remove(CONTENT_LENGTH);
+    AxumResponse::from_parts(parts, axum::body::Body::from_stream(chunks))
+}
+
+/// Undo a request's `Content-Encoding`, accepting only allowed algorithms
+fn decompress_request(
+    config: &HttpConfig,
+    encoding: &str,
+    body: &[u8],
+) -> Result<Vec<u8>, CompressionError> {
+    for token in encoding.split(',').map(str::trim) {
+        if token.is_empty() || token.eq_ignore_ascii_case("identity") {
+            continue;
+        }
+        if !CompressionAlgorithm::from_encoding(token).is_some_and(|a| config.compression.allows(a))
+        {
+            return Err(CompressionError::Unsupported(token.to_string()));
+        }
+    }
+    decompress(encoding, body, config.max_message_size)
+}
+
 async fn handle_health() -> &'static str {
     "OK"
 }
@@ -710,9 +1112,10 @@ impl Transport for HttpTransport {
         );
 
         let state = Arc::new(HttpState {
-            handler: Arc::new(handler),
+            handler: Arc::new(self.active.wrap(handler)),
             config: self.config.clone(),
             sessions: Arc::new(RwLock::new(HashMap::new())),
+            limiter: self.limiter.clone(),
         });
 
         // Build CORS layer - be very permissive for MCP Inspector
@@ -725,17 +1128,36 @@ impl Transport for HttpTransport {
         let app = Router::new()
             .route("/messages", post(handle_post))
             .route("/sse", get(handle_sse))
+            .route_layer(axum::middleware::from_fn_with_state(
+                state.clone(),
+                enforce_https,
+            ))
+            // Health checks stay reachable by plaintext load balancer probes
             .route("/health", get(handle_health))
+            .layer(DefaultBodyLimit::max(self.config.max_message_size))
+            .layer(axum::middleware::from_fn_with_state(
+                state.clone(),
+                compress_exchange,
+            ))
+            .layer(axum::middleware::from_fn_with_state(
+                "http",
+                scope_connection,
+            ))
             .layer(ServiceBuilder::new().layer(cors))
             .with_state(state.clone());
 
         // Start session cleanup task
         let cleanup_state = state.clone();
+        let on_disconnect = self.on_disconnect.clone();
         tokio::spawn(async move {
             let mut interval = tokio::time::interval(Duration::from_secs(60));
             loop {
                 interval.tick().await;
-                HttpTransport::cleanup_sessions(cleanup_state.clone()).await;
+                // Requests of a session are handled under its id as both
+                // session and connection id
+                for id in HttpTransport::cleanup_sessions(cleanup_state.clone()).await {
+                    notify_disconnect(on_disconnect.as_ref(), id.clone(), Some(id)).await;
+                }
             }
         });
 
@@ -754,8 +1176,15 @@ impl Transport for HttpTransport {
         info!("  GET    http://{}/sse        - Server-Sent Events", addr);
         info!("  GET    http://{}/health     - Health check", addr);
 
+        let (shutdown_tx, shutdown_rx) = oneshot::channel();
         let server_handle = tokio::spawn(async move {
-            if let Err(e) = axum::serve(listener, app).await {
+            if let Err(e) = axum::serve(
+                listener,
+                app.into_make_service_with_connect_info::<SocketAddr>(),
+            )
+            .with_graceful_shutdown(shutdown_requested(shutdown_rx))
+            .await
+            {
                 error!("HTTP server error: {}", e);
             }
         });
@@ -764,23 +1193,57 @@ impl Transport for HttpTransport {
             handler: state.handler.clone(),
             config: state.config.clone(),
             sessions: state.sessions.clone(),
+            limiter: state.limiter.clone(),
         });
         self.server_handle = Some(server_handle);
+        self.shutdown_signal = Some(shutdown_tx);
 
         Ok(())
     }
 
+    fn set_batch_config(&mut self, config: BatchConfig) {
+        self.config.batch = config;
+    }
+
+    fn set_disconnect_handler(&mut self, handler: DisconnectHandler) {
+        self.on_disconnect = Some(handler);
+    }
+
     async fn stop(&mut self) -> Result<(), TransportError> {
         info!("Stopping HTTP transport");
 
+        if let Some(state) = &self.state {
+            // Dropping the session channels ends each SSE stream with a
+            // `close` event instead of cutting the connection
+            state.sessions.write().await.clear();
+        }
         if let Some(handle) = self.server_handle.take() {
             handle.abort();
         }
 
+        self.shutdown_signal = None;
         self.state = None;
         Ok(())
     }
 
+    async fn shutdown(&mut self, grace: Duration) -> Result<(), TransportError> {
+        info!(
+            "Shutting down HTTP transport, draining for up to {:?}",
+            grace
+        );
+
+        if let Some(signal) = self.shutdown_signal.take() {
+            let _ = signal.send(());
+        }
+        if !self.active.drain(grace).await {
+            warn!(
+                "Grace period elapsed with {} requests still running, closing",
+                self.active.count()
+            );
+        }
+        self.stop().await
+    }
+
     async fn health_check(&self) -> Result<(), TransportError> {
         if self.state.is_some() {
             Ok(())
@@ -859,6 +1322,7 @@ mod tests {
             handler: Arc::new(Box::new(mock_handler)),
             config,
             sessions: Arc::new(RwLock::new(HashMap::new())),
+            limiter: Arc::default(),
         })
     }
 
@@ -896,6 +1360,13 @@ mod tests {
             session_timeout_secs: 600,
             require_auth: true,
             valid_tokens: vec!["test-token".to_string()],
+            require_https: false,
+            https_redirect: false,
+            trusted_proxies: vec![],
+            max_in_flight: None,
+            retry_after_secs: 1,
+            compression: CompressionConfig::default(),
+            batch: BatchConfig::default(),
         };
 
         let transport = HttpTransport::with_config(config.clone());
@@ -1268,6 +1739,7 @@ mod tests {
             handler: Arc::new(Box::new(mock_handler)),
             config,
             sessions: Arc::new(RwLock::new(HashMap::new())),
+            limiter: Arc::default(),
         });
 
         // Create a session
@@ -1283,7 +1755,8 @@ mod tests {
         }
 
         // Run cleanup
-        HttpTransport::cleanup_sessions(state.clone()).await;
+        let expired = HttpTransport::cleanup_sessions(state.clone()).await;
+        assert_eq!(expired, vec![session_id]);
 
         // Session should be removed
         let sessions = state.sessions.read().await;
@@ -1332,6 +1805,10 @@ mod tests {
             config: HttpConfig::default(),
             state: Some((*state).clone()),
             server_handle: None,
+            limiter: Arc::default(),
+            active: ActiveHandlers::new(),
+            shutdown_signal: None,
+            on_disconnect: None,
         };
 
         // Create a session
@@ -1361,6 +1838,10 @@ mod tests {
             config: HttpConfig::default(),
             state: Some((*state).clone()),
             server_handle: None,
+            limiter: Arc::default(),
+            active: ActiveHandlers::new(),
+            shutdown_signal: None,
+            on_disconnect: None,
         };
 
         // Broadcast a message (should succeed even with no sessions)
@@ -1408,6 +1889,44 @@ mod tests {
         assert!(result.is_ok());
     }
 
+    #[tokio::test]
+    async fn test_handle_post_runs_handler_in_its_session() {
+        let state = Arc::new(HttpState {
+            handler: Arc::new(Box::new(|request: pulseengine_mcp_protocol::Request| {
+                Box::pin(async move {
+                    Response {
+                        jsonrpc: "2.0".to_string(),
+                        id: request.id,
+                        result: Some(json!({ "session": crate::try_current_session_id() })),
+                        error: None,
+                    }
+                })
+            })),
+            config: HttpConfig::default(),
+            sessions: Arc::new(RwLock::new(HashMap::new())),
+            limiter: Arc::default(),
+        });
+        let mut headers = create_test_headers();
+        headers.insert("Accept", "application/json".parse().unwrap());
+        let body = json!({ "jsonrpc": "2.0", "method": "ping", "id": 1 }).to_string();
+
+        for session in ["client-a", "client-b"] {
+            let query = PostQuery {
+                session_id: Some(session.to_string()),
+            };
+            let response = handle_post(
+                State(state.clone()),
+                Query(query),
+                headers.clone(),
+                body.clone(),
+            )
+            .await
+            .unwrap();
+            let response: serde_json::Value = serde_json::from_str(response.body()).unwrap();
+            assert_eq!(response["result"]["session"], session);
+        }
+    }
+
     #[tokio::test]
     async fn test_handle_post_invalid_json() {
         let state = create_test_state();
@@ -1446,6 +1965,7 @@ mod tests {
             handler: Arc::new(Box::new(mock_handler)),
             config,
             sessions: Arc::new(RwLock::new(HashMap::new())),
+            limiter: Arc::default(),
         });
 
         let query = PostQuery { session_id: None };
@@ -1476,6 +1996,7 @@ mod tests {
             handler: Arc::new(Box::new(mock_handler)),
             config,
             sessions: Arc::new(RwLock::new(HashMap::new())),
+            limiter: Arc::default(),
         });
 
         let query = PostQuery { session_id: None };
@@ -1506,6 +2027,7 @@ mod tests {
             handler: Arc::new(Box::new(mock_handler)),
             config,
             sessions: Arc::new(RwLock::new(HashMap::new())),
+            limiter: Arc::default(),
         });
 
         let query = PostQuery { session_id: None };
@@ -1585,6 +2107,7 @@ mod tests {
             handler: Arc::new(Box::new(mock_notification_handler)),
             config: HttpConfig::default(),
             sessions: Arc::new(RwLock::new(HashMap::new())),
+            limiter: Arc::default(),
         });
 
         let query = PostQuery { session_id: None };
@@ -1609,6 +2132,7 @@ mod tests {
             handler: Arc::new(Box::new(mock_error_handler)),
             config: HttpConfig::default(),
             sessions: Arc::new(RwLock::new(HashMap::new())),
+            limiter: Arc::default(),
         });
 
         let query = PostQuery { session_id: None };
@@ -1703,6 +2227,7 @@ mod tests {
             handler: Arc::new(Box::new(mock_handler)),
             config,
             sessions: Arc::new(RwLock::new(HashMap::new())),
+            limiter: Arc::default(),
         });
 
         let query = SseQuery {
@@ -1732,6 +2257,7 @@ mod tests {
             handler: Arc::new(Box::new(mock_handler)),
             config,
             sessions: Arc::new(RwLock::new(HashMap::new())),
+            limiter: Arc::default(),
         });
 
         let query = SseQuery {
@@ -1935,6 +2461,13 @@ mod tests {
             session_timeout_secs: 0,
             require_auth: false,
             valid_tokens: vec![],
+            require_https: false,
+            https_redirect: false,
+            trusted_proxies: vec![],
+            max_in_flight: None,
+            retry_after_secs: 1,
+            compression: CompressionConfig::default(),
+            batch: BatchConfig::default(),
         };
 
         let transport = HttpTransport::with_config(config);
@@ -1989,6 +2522,10 @@ mod tests {
             config: HttpConfig::default(),
             state: Some((*state).clone()),
             server_handle: None,
+            limiter: Arc::default(),
+            active: ActiveHandlers::new(),
+            shutdown_signal: None,
+            on_disconnect: None,
         };
 
         // Broadcasting should still work (might log warnings but not fail)
@@ -2016,4 +2553,434 @@ mod tests {
         // This should not crash the test
         // Channel is tested for capacity behavior
     }
+
+    // === Backpressure Tests ===
+
+    fn create_limited_state(max_in_flight: usize) -> Arc<HttpState> {
+        let config = HttpConfig {
+            max_in_flight: Some(max_in_flight),
+            retry_after_secs: 7,
+            ..Default::default()
+        };
+        Arc::new(HttpState {
+            handler: Arc::new(Box::new(mock_handler)),
+            limiter: Arc::new(RequestLimiter::new(config.max_in_flight)),
+            config,
+            sessions: Arc::new(RwLock::new(HashMap::new())),
+        })
+    }
+
+    fn ping_body() -> String {
+        json!({"jsonrpc": "2.0", "method": "ping", "params": {}, "id": 1}).to_string()
+    }
+
+    #[tokio::test]
+    async fn test_handle_post_rejects_when_saturated() {
+        let state = create_limited_state(1);
+        let busy = state.limiter.try_acquire().unwrap();
+        assert_eq!(state.limiter.in_flight(), 1);
+
+        let response = handle_post(
+            State(state.clone()),
+            Query(PostQuery { session_id: None }),
+            create_test_headers(),
+            ping_body(),
+        )
+        .await
+        .unwrap();
+        assert_eq!(response.status(), StatusCode::SERVICE_UNAVAILABLE);
+        assert_eq!(response.headers().get(RETRY_AFTER).unwrap(), "7");
+
+        drop(busy);
+        assert_eq!(state.limiter.in_flight(), 0);
+
+        let mut headers = create_test_headers();
+        headers.insert("accept", "application/json".parse().unwrap());
+        let response = handle_post(
+            State(state.clone()),
+            Query(PostQuery { session_id: None }),
+            headers,
+            ping_body(),
+        )
+        .await
+        .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(state.limiter.in_flight(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_handler_panic_releases_in_flight_slot() {
+        fn panicking_handler(
+            _request: pulseengine_mcp_protocol::Request,
+        ) -> std::pin::Pin<
+            Box<dyn std::future::Future<Output = pulseengine_mcp_protocol::Response> + Send>,
+        > {
+            Box::pin(async move { panic!("handler failed") })
+        }
+
+        let config = HttpConfig {
+            max_in_flight: Some(1),
+            ..Default::default()
+        };
+        let state = Arc::new(HttpState {
+            handler: Arc::new(Box::new(panicking_handler)),
+            limiter: Arc::new(RequestLimiter::new(config.max_in_flight)),
+            config,
+            sessions: Arc::new(RwLock::new(HashMap::new())),
+        });
+
+        let task = tokio::spawn(handle_post(
+            State(state.clone()),
+            Query(PostQuery { session_id: None }),
+            create_test_headers(),
+            ping_body(),
+        ));
+        assert!(task.await.unwrap_err().is_panic());
+
+        assert_eq!(state.limiter.in_flight(), 0);
+        assert!(state.limiter.try_acquire().is_some());
+    }
+
+    #[test]
+    fn test_in_flight_requests_starts_at_zero() {
+        let transport = HttpTransport::with_config(HttpConfig {
+            max_in_flight: Some(4),
+            ..Default::default()
+        });
+        assert_eq!(transport.in_flight_requests(), 0);
+        assert_eq!(transport.config().max_in_flight, Some(4));
+    }
+
+    #[tokio::test]
+    async fn test_shutdown_drains_in_flight_request() {
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+        fn slow_handler(
+            request: pulseengine_mcp_protocol::Request,
+        ) -> std::pin::Pin<
+            Box<dyn std::future::Future<Output = pulseengine_mcp_protocol::Response> + Send>,
+        > {
+            Box::pin(async move {
+                tokio::time::sleep(Duration::from_millis(200)).await;
+                Response {
+                    jsonrpc: "2.0".to_string(),
+                    id: request.id,
+                    result: Some(json!({"done": true})),
+                    error: None,
+                }
+            })
+        }
+
+        let port = std::net::TcpListener::bind("127.0.0.1:0")
+            .unwrap()
+            .local_addr()
+            .unwrap()
+            .port();
+        let mut transport = HttpTransport::with_config(HttpConfig {
+            port,
+            ..Default::default()
+        });
+        transport.start(Box::new(slow_handler)).await.unwrap();
+
+        let client = tokio::spawn(async move {
+            let mut stream = tokio::net::TcpStream::connect(("127.0.0.1", port))
+                .await
+                .unwrap();
+            let body = json!({"jsonrpc": "2.0", "id": 1, "method": "tools/call", "params": {}})
+                .to_string();
+            let request = format!(
+                "POST /messages HTTP/1.1\r\nHost: 127.0.0.1\r\nContent-Type: application/json\r\n\
+                 Accept: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+                body.len()
+            );
+            stream.write_all(request.as_bytes()).await.unwrap();
+            let mut response = String::new();
+            stream.read_to_string(&mut response).await.unwrap();
+            response
+        });
+
+        tokio::time::timeout(Duration::from_secs(5), async {
+            while transport.active.count() == 0 {
+                tokio::time::sleep(Duration::from_millis(5)).await;
+            }
+        })
+        .await
+        .expect("request reaches the handler");
+
+        transport.shutdown(Duration::from_secs(5)).await.unwrap();
+        assert!(!transport.is_running());
+
+        // The slow request finished instead of being cut off
+        let response = client.await.unwrap();
+        assert!(response.starts_with("HTTP/1.1 200"), "{response}");
+        assert!(response.contains(r#""done":true"#));
+
+        // New connections are refused
+        assert!(
+            tokio::net::TcpStream::connect(("127.0.0.1", port))
+                .await
+                .is_err()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_chunked_request_body_buffered_up_to_limit() {
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+        let port = std::net::TcpListener::bind("127.0.0.1:0")
+            .unwrap()
+            .local_addr()
+            .unwrap()
+            .port();
+        let mut transport = HttpTransport::with_config(HttpConfig {
+            port,
+            max_message_size: 4096,
+            ..Default::default()
+        });
+        transport.start(Box::new(mock_handler)).await.unwrap();
+
+        // Send `body` split into chunks of at most `chunk_size` bytes. The
+        // server may answer and close before reading an oversized body, so
+        // write errors are ignored and whatever response arrived is returned.
+        async fn post_chunked(port: u16, body: &str, chunk_size: usize) -> String {
+            let mut request = b"POST /messages HTTP/1.1\r\nHost: 127.0.0.1\r\n\
+                Content-Type: application/json\r\nAccept: application/json\r\n\
+                Transfer-Encoding: chunked\r\nConnection: close\r\n\r\n"
+                .to_vec();
+            for chunk in body.as_bytes().chunks(chunk_size) {
+                request.extend_from_slice(format!("{:x}\r\n", chunk.len()).as_bytes());
+                request.extend_from_slice(chunk);
+                request.extend_from_slice(b"\r\n");
+            }
+            request.extend_from_slice(b"0\r\n\r\n");
+
+            let mut stream = tokio::net::TcpStream::connect(("127.0.0.1", port))
+                .await
+                .unwrap();
+            let _ = stream.write_all(&request).await;
+            let mut response = Vec::new();
+            let _ = stream.read_to_end(&mut response).await;
+            String::from_utf8_lossy(&response).into_owned()
+        }
+
+        let request = json!({
+            "jsonrpc": "2.0",
+            "id": 7,
+            "method": "tools/call",
+            "params": {"name": "echo", "arguments": {"text": "a".repeat(1000)}}
+        })
+        .to_string();
+        let response = post_chunked(port, &request, 100).await;
+        assert!(response.starts_with("HTTP/1.1 200"), "{response}");
+        assert!(response.contains(r#""id":7"#), "{response}");
+
+        // Every chunk is small, but together they exceed the limit
+        let oversized = json!({
+            "jsonrpc": "2.0",
+            "id": 8,
+            "method": "tools/call",
+            "params": {"name": "echo", "arguments": {"text": "a".repeat(8192)}}
+        })
+        .to_string();
+        let response = post_chunked(port, &oversized, 512).await;
+        assert!(response.starts_with("HTTP/1.1 413"), "{response}");
+
+        transport.shutdown(Duration::from_secs(5)).await.unwrap();
+    }
+
+    fn compression_router(state: Arc<HttpState>) -> Router {
+        Router::new()
+            .route("/messages", post(handle_post))
+            .route("/large", get(|| async { "x".repeat(4096) }))
+            .layer(axum::middleware::from_fn_with_state(
+                state.clone(),
+                compress_exchange,
+            ))
+            .with_state(state)
+    }
+
+    async fn send(
+        mut router: Router,
+        request: axum::http::Request<axum::body::Body>,
+    ) -> (axum::http::response::Parts, Vec<u8>) {
+        use tower::Service;
+
+        let (parts, body) = router.call(request).await.unwrap().into_parts();
+        let body = axum::body::to_bytes(body, usize::MAX).await.unwrap();
+        (parts, body.to_vec())
+    }
+
+    fn get_with_encoding(
+        path: &str,
+        accept_encoding: &str,
+    ) -> axum::http::Request<axum::body::Body> {
+        axum::http::Request::get(path)
+            .header(ACCEPT_ENCODING, accept_encoding)
+            .body(axum::body::Body::empty())
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_compresses_negotiated_response() {
+        let router = compression_router(create_test_state());
+        let (parts, body) = send(router, get_with_encoding("/large", "gzip;q=0.5, zstd")).await;
+
+        assert_eq!(parts.headers[CONTENT_ENCODING], "zstd");
+        assert_eq!(parts.headers[VARY], "accept-encoding");
+        assert_eq!(
+            parts.headers[CONTENT_LENGTH],
+            body.len().to_string().as_str()
+        );
+        assert!(!parts.headers.contains_key(TRANSFER_ENCODING));
+        assert!(body.len() < 4096);
+        assert_eq!(
+            decompress("zstd", &body, 4096).unwrap(),
+            "x".repeat(4096).as_bytes()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_skips_small_and_unaccepted_responses() {
+        let state = Arc::new(HttpState {
+            handler: Arc::new(Box::new(mock_handler)),
+            config: HttpConfig {
+                compression: CompressionConfig {
+                    algorithms: vec![CompressionAlgorithm::Gzip],
+                    min_size: 8192,
+                },
+                ..Default::default()
+            },
+            sessions: Arc::new(RwLock::new(HashMap::new())),
+            limiter: Arc::default(),
+        });
+
+        // Below the threshold
+        let (parts, body) = send(
+            compression_router(state.clone()),
+            get_with_encoding("/large", "gzip"),
+        )
+        .await;
+        assert!(!parts.headers.contains_key(CONTENT_ENCODING));
+        assert_eq!(body.len(), 4096);
+
+        // Only a disallowed algorithm is acceptable to the client
+        let (parts, _) = send(
+            compression_router(state.clone()),
+            get_with_encoding("/large", "zstd"),
+        )
+        .await;
+        assert!(!parts.headers.contains_key(CONTENT_ENCODING));
+    }
+
+    #[tokio::test]
+    async fn test_event_stream_compressed_per_connection() {
+        use futures_util::StreamExt;
+        use std::io::Write;
+
+        let events = ["data: one\n\n", "data: two\n\n", "data: three\n\n"];
+        let mut router = Router::new()
+            .route(
+                "/sse",
+                get(move || async move {
+                    let chunks = futures_util::stream::iter(events)
+                        .map(|event| Ok::<_, std::convert::Infallible>(event.to_string()));
+                    (
+                        [(CONTENT_TYPE, "text/event-stream")],
+                        axum::body::Body::from_stream(chunks),
+                    )
+                }),
+            )
+            .layer(axum::middleware::from_fn_with_state(
+                create_test_state(),
+                compress_exchange,
+            ));
+
+        let response = {
+            use tower::Service;
+            router
+                .call(get_with_encoding("/sse", "gzip"))
+                .await
+                .unwrap()
+        };
+        // Negotiated once, when the stream is opened
+        assert_eq!(response.headers()[CONTENT_ENCODING], "gzip");
+        assert!(!response.headers().contains_key(CONTENT_LENGTH));
+
+        // Every event decodes as soon as its chunk arrives
+        let mut chunks = response.into_body().into_data_stream();
+        let mut decoder = flate2::write::GzDecoder::new(Vec::new());
+        let mut expected = String::new();
+        for event in events {
+            let chunk = chunks.next().await.unwrap().unwrap();
+            assert!(!chunk.starts_with(b"data:"));
+            decoder.write_all(&chunk).unwrap();
+            decoder.flush().unwrap();
+            expected.push_str(event);
+            assert_eq!(decoder.get_ref().as_slice(), expected.as_bytes());
+        }
+        // The trailer ends the stream
+        let trailer = chunks.next().await.unwrap().unwrap();
+        decoder.write_all(&trailer).unwrap();
+        assert_eq!(decoder.finish().unwrap(), expected.as_bytes());
+        assert!(chunks.next().await.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_decompresses_request_body() {
+        let body = compress(CompressionAlgorithm::Gzip, ping_body().as_bytes()).unwrap();
+        let request = axum::http::Request::post("/messages")
+            .header(CONTENT_TYPE, "application/json")
+            .header(CONTENT_ENCODING, "gzip")
+            .header("accept", "application/json")
+            .body(axum::body::Body::from(body))
+            .unwrap();
+
+        let (parts, body) = send(compression_router(create_test_state()), request).await;
+        assert_eq!(parts.status, StatusCode::OK);
+        let response: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(response["result"]["echo"], "ping");
+    }
+
+    #[tokio::test]
+    async fn test_rejects_unsupported_request_encoding() {
+        let state = Arc::new(HttpState {
+            handler: Arc::new(Box::new(mock_handler)),
+            config: HttpConfig {
+                compression: CompressionConfig {
+                    algorithms: vec![CompressionAlgorithm::Gzip],
+                    min_size: 0,
+                },
+                ..Default::default()
+            },
+            sessions: Arc::new(RwLock::new(HashMap::new())),
+            limiter: Arc::default(),
+        });
+
+        for (encoding, body) in [
+            ("br", ping_body().into_bytes()),
+            (
+                "zstd",
+                compress(CompressionAlgorithm::Zstd, ping_body().as_bytes()).unwrap(),
+            ),
+        ] {
+            let request = axum::http::Request::post("/messages")
+                .header(CONTENT_TYPE, "application/json")
+                .header(CONTENT_ENCODING, encoding)
+                .body(axum::body::Body::from(body))
+                .unwrap();
+            let (parts, _) = send(compression_router(state.clone()), request).await;
+            assert_eq!(
+                parts.status,
+                StatusCode::UNSUPPORTED_MEDIA_TYPE,
+                "{encoding}"
+            );
+        }
+
+        let request = axum::http::Request::post("/messages")
+            .header(CONTENT_ENCODING, "gzip")
+            .body(axum::body::Body::from("not gzip"))
+            .unwrap();
+        let (parts, _) = send(compression_router(state), request).await;
+        assert_eq!(parts.status, StatusCode::BAD_REQUEST);
+    }
 }
@@ -3,7 +3,7 @@
 #[cfg(test)]
 mod tests {
     use super::super::http::*;
-    use crate::{Transport, TransportError};
+    use crate::{BatchConfig, Transport, TransportError};
     use axum::http::HeaderMap;
     use axum::http::header::{AUTHORIZATION, ORIGIN};
     use pulseengine_mcp_protocol::{Request, Response};
@@ -67,6 +67,13 @@ mod tests {
             session_timeout_secs: 600,
             require_auth: true,
             valid_tokens: vec!["test-token".to_string()],
+            require_https: false,
+            https_redirect: false,
+            trusted_proxies: vec![],
+            max_in_flight: None,
+            retry_after_secs: 1,
+            compression: crate::CompressionConfig::default(),
+            batch: BatchConfig::default(),
         };
 
         assert_eq!(config.port, 8080);
@@ -102,6 +109,13 @@ mod tests {
             session_timeout_secs: 120,
             require_auth: false,
             valid_tokens: vec![],
+            require_https: false,
+            https_redirect: false,
+            trusted_proxies: vec![],
+            max_in_flight: None,
+            retry_after_secs: 1,
+            compression: crate::CompressionConfig::default(),
+            batch: BatchConfig::default(),
         };
 
         let transport = HttpTransport::with_config(config.clone());
@@ -340,6 +354,13 @@ mod tests {
                 session_timeout_secs: 300,
                 require_auth: false,
                 valid_tokens: vec![],
+                require_https: false,
+                https_redirect: false,
+                trusted_proxies: vec![],
+                max_in_flight: None,
+                retry_after_secs: 1,
+                compression: crate::CompressionConfig::default(),
+                batch: BatchConfig::default(),
             },
             HttpConfig {
                 port: 9000,
@@ -351,6 +372,13 @@ mod tests {
                 session_timeout_secs: 600,
                 require_auth: true,
                 valid_tokens: vec!["token".to_string()],
+                require_https: false,
+                https_redirect: false,
+                trusted_proxies: vec![],
+                max_in_flight: None,
+                retry_after_secs: 1,
+                compression: crate::CompressionConfig::default(),
+                batch: BatchConfig::default(),
             },
         ];
 
@@ -455,6 +483,13 @@ mod tests {
             session_timeout_secs: 300,
             require_auth: true,
             valid_tokens: vec!["token1".to_string(), "token2".to_string()],
+            require_https: false,
+            https_redirect: false,
+            trusted_proxies: vec![],
+            max_in_flight: None,
+            retry_after_secs: 1,
+            compression: crate::CompressionConfig::default(),
+            batch: BatchConfig::default(),
         };
 
         let cloned = config.clone();
@@ -496,6 +531,13 @@ mod tests {
             session_timeout_secs: 300,
             require_auth: false,
             valid_tokens: vec![],
+            require_https: false,
+            https_redirect: false,
+            trusted_proxies: vec![],
+            max_in_flight: None,
+            retry_after_secs: 1,
+            compression: crate::CompressionConfig::default(),
+            batch: BatchConfig::default(),
         };
 
         // Test that config can be used to create transport
@@ -521,6 +563,13 @@ mod tests {
             session_timeout_secs: 0, // Immediate timeout
             require_auth: true,
             valid_tokens: vec!["".to_string()], // Empty token
+            require_https: false,
+            https_redirect: false,
+            trusted_proxies: vec![],
+            max_in_flight: None,
+            retry_after_secs: 1,
+            compression: crate::CompressionConfig::default(),
+            batch: BatchConfig::default(),
         };
 
         assert_eq!(config.port, 65535);
@@ -610,4 +659,161 @@ mod tests {
             "invalid-host-name-that-does-not-exist"
         );
     }
+
+    #[test]
+    fn test_is_https_request_trusts_only_configured_proxies() {
+        let config = HttpConfig {
+            trusted_proxies: vec!["10.0.0.1".to_string()],
+            ..Default::default()
+        };
+        let proxy: std::net::SocketAddr = "10.0.0.1:443".parse().unwrap();
+        let client: std::net::SocketAddr = "203.0.113.7:50000".parse().unwrap();
+
+        let mut headers = HeaderMap::new();
+        assert!(!HttpTransport::is_https_request(&config, &headers, proxy));
+
+        headers.insert("X-Forwarded-Proto", "HTTPS, http".parse().unwrap());
+        assert!(HttpTransport::is_https_request(&config, &headers, proxy));
+        assert!(!HttpTransport::is_https_request(&config, &headers, client));
+
+        headers.insert("X-Forwarded-Proto", "http".parse().unwrap());
+        assert!(!HttpTransport::is_https_request(&config, &headers, proxy));
+    }
+
+    async fn raw_request(port: u16, request: &str) -> String {
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+        let mut stream = tokio::net::TcpStream::connect(("127.0.0.1", port))
+            .await
+            .unwrap();
+        stream.write_all(request.as_bytes()).await.unwrap();
+        let mut response = String::new();
+        stream.read_to_string(&mut response).await.unwrap();
+        response
+    }
+
+    #[tokio::test]
+    async fn test_require_https_rejects_and_redirects_plaintext() {
+        let port = 18215;
+        let mut transport = HttpTransport::with_config(HttpConfig {
+            port,
+            require_https: true,
+            https_redirect: true,
+            ..Default::default()
+        });
+        transport.start(Box::new(mock_handler)).await.unwrap();
+
+        let body = r#"{"jsonrpc":"2.0","id":1,"method":"ping"}"#;
+        let response = raw_request(
+            port,
+            &format!(
+                "POST /messages HTTP/1.1\r\nHost: example.com\r\nContent-Type: application/json\r\n\
+                 Content-Length: {}\r\nConnection: close\r\n\r\n{body}",
+                body.len()
+            ),
+        )
+        .await;
+        assert!(response.starts_with("HTTP/1.1 400"), "{response}");
+
+        let response = raw_request(
+            port,
+            "GET /sse?session_id=abc HTTP/1.1\r\nHost: example.com\r\nConnection: close\r\n\r\n",
+        )
+        .await;
+        assert!(response.starts_with("HTTP/1.1 301"), "{response}");
+        assert!(response.contains("location: https://example.com/sse?session_id=abc"));
+
+        // Forwarded headers from an untrusted peer are ignored
+        let response = raw_request(
+            port,
+            &format!(
+                "POST /messages HTTP/1.1\r\nHost: example.com\r\nX-Forwarded-Proto: https\r\n\
+                 Content-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+                body.len()
+            ),
+        )
+        .await;
+        assert!(response.starts_with("HTTP/1.1 400"), "{response}");
+
+        // Health checks are not subject to the HTTPS requirement
+        let response = raw_request(
+            port,
+            "GET /health HTTP/1.1\r\nHost: example.com\r\nConnection: close\r\n\r\n",
+        )
+        .await;
+        assert!(response.starts_with("HTTP/1.1 200"), "{response}");
+
+        transport.stop().await.ok();
+    }
+
+    #[tokio::test]
+    async fn test_require_https_accepts_forwarded_https_from_trusted_proxy() {
+        let port = 18216;
+        let mut transport = HttpTransport::with_config(HttpConfig {
+            port,
+            require_https: true,
+            trusted_proxies: vec!["127.0.0.1".to_string()],
+            ..Default::default()
+        });
+        transport.start(Box::new(mock_handler)).await.unwrap();
+
+        let body = r#"{"jsonrpc":"2.0","id":1,"method":"ping"}"#;
+        let response = raw_request(
+            port,
+            &format!(
+                "POST /messages HTTP/1.1\r\nHost: example.com\r\nX-Forwarded-Proto: https\r\n\
+                 Content-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+                body.len()
+            ),
+        )
+        .await;
+        // Accepted; the JSON-RPC response itself is delivered over SSE
+        assert!(response.starts_with("HTTP/1.1 204"), "{response}");
+
+        transport.stop().await.ok();
+    }
+
+    #[tokio::test]
+    async fn test_stop_sends_close_event_to_sse_streams() {
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+        let port = 18220;
+        let mut transport = HttpTransport::new(port);
+        transport.start(Box::new(mock_handler)).await.unwrap();
+
+        let mut stream = tokio::net::TcpStream::connect(("127.0.0.1", port))
+            .await
+            .unwrap();
+        stream
+            .write_all(
+                b"GET /sse?sessionId=closing HTTP/1.1\r\nHost: localhost\r\nConnection: close\r\n\r\n",
+            )
+            .await
+            .unwrap();
+
+        // The endpoint event means the stream is subscribed to its session
+        let mut received = Vec::new();
+        let mut buf = [0u8; 1024];
+        while !String::from_utf8_lossy(&received).contains("event: endpoint") {
+            let n = stream.read(&mut buf).await.unwrap();
+            assert!(n > 0, "SSE stream ended before the endpoint event");
+            received.extend_from_slice(&buf[..n]);
+        }
+
+        transport.stop().await.unwrap();
+
+        // The stream ends with a close event rather than being cut off
+        let mut rest = Vec::new();
+        tokio::time::timeout(
+            std::time::Duration::from_secs(5),
+            stream.read_to_end(&mut rest),
+        )
+        .await
+        .expect("SSE stream should end after stop")
+        .unwrap();
+        let rest = String::from_utf8_lossy(&rest);
+        assert!(rest.contains("event: close"), "{rest}");
+        assert!(rest.contains(r#""sessionId":"closing""#), "{rest}");
+        assert!(rest.ends_with("0\r\n\r\n"), "{rest}");
+    }
 }
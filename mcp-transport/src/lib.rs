@@ -1,7 +1,8 @@
 //! Transport layer implementations for MCP servers
 //!
 //! This crate provides multiple transport options for MCP servers:
-//! stdio (Claude Desktop), HTTP (web clients), and WebSocket (real-time).
+//! stdio (Claude Desktop), HTTP (web clients), WebSocket (real-time), and
+//! Unix domain sockets (co-located services).
 //!
 //! # Quick Start
 //!
@@ -30,16 +31,22 @@
 //! ```
 
 pub mod batch;
+pub mod compression;
 pub mod config;
+pub mod drain;
 pub mod http;
 pub mod stdio;
 pub mod streamable_http;
+#[cfg(unix)]
+pub mod unix;
 pub mod validation;
 pub mod websocket;
 
 #[cfg(test)]
 mod batch_tests;
 #[cfg(test)]
+mod compression_tests;
+#[cfg(test)]
 mod config_tests;
 #[cfg(test)]
 mod http_test;
@@ -51,6 +58,8 @@ mod lib_tests;
 mod stdio_tests;
 #[cfg(test)]
 mod streamable_http_tests;
+#[cfg(all(test, unix))]
+mod unix_tests;
 #[cfg(test)]
 mod validation_tests;
 #[cfg(test)]
@@ -59,12 +68,17 @@ mod websocket_tests;
 use async_trait::async_trait;
 use pulseengine_mcp_protocol::{Request, Response};
 use serde_json::Value;
+use std::collections::HashMap;
+use std::net::SocketAddr;
 use std::sync::Arc;
 use std::time::Duration;
 // std::error::Error not needed with thiserror
 use thiserror::Error as ThisError;
 
+pub use batch::BatchConfig;
+pub use compression::{CompressionAlgorithm, CompressionConfig};
 pub use config::TransportConfig;
+pub use drain::ActiveHandlers;
 
 #[derive(Debug, ThisError)]
 pub enum TransportError {
@@ -97,6 +111,25 @@ pub type RequestHandler = Box<
         + Sync,
 >;
 
+/// Response a [`RequestHandler`] returns for a request that must go
+/// unanswered, such as one the client cancelled
+///
+/// It carries neither a result nor an error, which no answer does, and
+/// transports drop it instead of sending it.
+pub fn no_response(id: Option<pulseengine_mcp_protocol::NumberOrString>) -> Response {
+    Response {
+        jsonrpc: "2.0".to_string(),
+        id,
+        result: None,
+        error: None,
+    }
+}
+
+/// Whether a handler's response is [`no_response`]
+pub fn is_no_response(response: &Response) -> bool {
+    response.result.is_none() && response.error.is_none()
+}
+
 /// Response handler for server-initiated requests
 ///
 /// When the server sends a request to the client, this handler is used to
@@ -107,6 +140,38 @@ pub type ResponseHandler = Arc<
         + Sync,
 >;
 
+/// A connection that closed, or a session its client ended
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConnectionClosed {
+    /// The [`ConnectionInfo::connection_id`] its requests were handled under
+    pub connection_id: String,
+    /// The session its requests were scoped to with [`with_session`], if any
+    pub session_id: Option<String>,
+}
+
+/// Handler told when a connection closes, so per-connection state kept by
+/// the server can be released
+pub type DisconnectHandler = Arc<
+    dyn Fn(ConnectionClosed) -> std::pin::Pin<Box<dyn std::future::Future<Output = ()> + Send>>
+        + Send
+        + Sync,
+>;
+
+/// Tell `handler`, if set, that a connection closed
+pub(crate) async fn notify_disconnect(
+    handler: Option<&DisconnectHandler>,
+    connection_id: String,
+    session_id: Option<String>,
+) {
+    if let Some(handler) = handler {
+        handler(ConnectionClosed {
+            connection_id,
+            session_id,
+        })
+        .await;
+    }
+}
+
 /// Transport layer trait
 #[async_trait]
 pub trait Transport: Send + Sync {
@@ -116,6 +181,16 @@ pub trait Transport: Send + Sync {
     /// Stop the transport
     async fn stop(&mut self) -> std::result::Result<(), TransportError>;
 
+    /// Stop accepting new requests, then wait up to `grace` for in-flight
+    /// handlers to finish before closing whatever remains
+    ///
+    /// # Default Implementation
+    /// Stops immediately via [`Transport::stop`]
+    async fn shutdown(&mut self, grace: Duration) -> std::result::Result<(), TransportError> {
+        let _ = grace;
+        self.stop().await
+    }
+
     /// Check if the transport is healthy
     async fn health_check(&self) -> std::result::Result<(), TransportError>;
 
@@ -177,6 +252,26 @@ pub trait Transport: Send + Sync {
         // Default: no-op for transports that don't support server requests
     }
 
+    /// Set how JSON-RPC batches are processed
+    ///
+    /// Call before [`Transport::start`].
+    ///
+    /// # Default Implementation
+    /// Does nothing - transports should override if they accept batches
+    fn set_batch_config(&mut self, _config: BatchConfig) {
+        // Default: no-op for transports that don't process batches
+    }
+
+    /// Set the handler told when a connection closes or its session ends
+    ///
+    /// Call before [`Transport::start`].
+    ///
+    /// # Default Implementation
+    /// Does nothing - transports should override if they track connections
+    fn set_disconnect_handler(&mut self, _handler: DisconnectHandler) {
+        // Default: no-op for transports without connection lifecycle events
+    }
+
     /// Check if this transport supports bidirectional communication
     ///
     /// Returns true if the transport can send notifications and requests to clients
@@ -234,6 +329,78 @@ tokio::task_local! {
     /// and tools can use it to send notifications that will be included in the
     /// SSE response stream.
     pub static NOTIFICATION_SENDER: NotificationSender;
+
+    /// Task-local storage for the connection a request arrived on
+    static CONNECTION: ConnectionInfo;
+}
+
+/// Transport-level details of the connection a request arrived on
+///
+/// Transports scope request handling with [`with_connection`] so the server
+/// can authenticate the caller from its credentials and address.
+#[derive(Debug, Clone, Default)]
+pub struct ConnectionInfo {
+    /// Identifies the connection, for state kept across its requests
+    pub connection_id: String,
+    /// Transport the request arrived on, e.g. `"stdio"`, `"http"`,
+    /// `"streamable_http"`, `"websocket"` or `"unix"`
+    pub transport: &'static str,
+    /// Socket address of the remote peer, for TCP transports
+    pub peer_addr: Option<SocketAddr>,
+    /// HTTP request or WebSocket handshake headers, with lowercase names
+    pub headers: HashMap<String, String>,
+}
+
+impl ConnectionInfo {
+    /// Create the details of connection `connection_id` on `transport`
+    pub fn new(connection_id: impl Into<String>, transport: &'static str) -> Self {
+        Self {
+            connection_id: connection_id.into(),
+            transport,
+            ..Default::default()
+        }
+    }
+
+    /// Set the socket address of the remote peer
+    pub fn with_peer_addr(mut self, peer_addr: SocketAddr) -> Self {
+        self.peer_addr = Some(peer_addr);
+        self
+    }
+
+    /// Record the headers of an HTTP request or WebSocket handshake
+    pub fn with_headers<'a>(
+        mut self,
+        headers: impl IntoIterator<Item = (&'a str, &'a str)>,
+    ) -> Self {
+        self.headers.extend(
+            headers
+                .into_iter()
+                .map(|(name, value)| (name.to_ascii_lowercase(), value.to_string())),
+        );
+        self
+    }
+
+    /// Value of a header, matching its name case-insensitively
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .get(&name.to_ascii_lowercase())
+            .map(String::as_str)
+    }
+}
+
+/// Get the connection of the request being handled
+///
+/// Returns `None` outside a [`with_connection`] scope
+pub fn try_current_connection() -> Option<ConnectionInfo> {
+    CONNECTION.try_with(|connection| connection.clone()).ok()
+}
+
+/// Execute an async block on behalf of a connection
+pub async fn with_connection<F, T>(connection: ConnectionInfo, f: F) -> T
+where
+    F: std::future::Future<Output = T>,
+{
+    CONNECTION.scope(connection, f).await
 }
 
 /// Get the current session ID
@@ -323,12 +490,31 @@ pub fn create_transport(
 ) -> std::result::Result<Box<dyn Transport>, TransportError> {
     match config {
         TransportConfig::Stdio => Ok(Box::new(stdio::StdioTransport::new())),
-        TransportConfig::Http { port, .. } => Ok(Box::new(http::HttpTransport::new(port))),
+        TransportConfig::Http {
+            port,
+            max_in_flight,
+            ..
+        } => Ok(Box::new(http::HttpTransport::with_config(
+            http::HttpConfig {
+                port,
+                max_in_flight,
+                ..Default::default()
+            },
+        ))),
         TransportConfig::StreamableHttp { port, .. } => Ok(Box::new(
             streamable_http::StreamableHttpTransport::new(port),
         )),
         TransportConfig::WebSocket { port, .. } => {
             Ok(Box::new(websocket::WebSocketTransport::new(port)))
         }
+        #[cfg(unix)]
+        TransportConfig::UnixSocket { path, mode } => Ok(Box::new(
+            unix::UnixSocketTransport::new(path).with_mode(mode),
+        )),
+        #[cfg(not(unix))]
+        TransportConfig::UnixSocket { path, .. } => Err(TransportError::Config(format!(
+            "Unix domain sockets are not supported on this platform: {}",
+            path.display()
+        ))),
     }
 }
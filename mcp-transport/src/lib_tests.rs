@@ -9,10 +9,11 @@ mod tests {
         let config = TransportConfig::Http {
             host: Some("127.0.0.1".to_string()),
             port: 8080,
+            max_in_flight: None,
         };
 
         match config {
-            TransportConfig::Http { host, port } => {
+            TransportConfig::Http { host, port, .. } => {
                 assert_eq!(host, Some("127.0.0.1".to_string()));
                 assert_eq!(port, 8080);
             }
@@ -115,14 +116,19 @@ mod tests {
         let original = TransportConfig::Http {
             host: Some("example.com".to_string()),
             port: 443,
+            max_in_flight: None,
         };
 
         let cloned = original.clone();
 
         match (&original, &cloned) {
             (
-                TransportConfig::Http { host: h1, port: p1 },
-                TransportConfig::Http { host: h2, port: p2 },
+                TransportConfig::Http {
+                    host: h1, port: p1, ..
+                },
+                TransportConfig::Http {
+                    host: h2, port: p2, ..
+                },
             ) => {
                 assert_eq!(h1, h2);
                 assert_eq!(p1, p2);
@@ -138,10 +144,12 @@ mod tests {
             TransportConfig::Http {
                 host: Some("".to_string()), // Empty host
                 port: 0,                    // Port 0
+                max_in_flight: None,
             },
             TransportConfig::Http {
                 host: Some("255.255.255.255".to_string()), // Max IPv4
                 port: 65535,                               // Max port
+                max_in_flight: None,
             },
             TransportConfig::WebSocket {
                 host: Some("::1".to_string()), // IPv6 localhost
@@ -210,10 +218,12 @@ mod tests {
             TransportConfig::Http {
                 host: None,
                 port: 8080,
+                max_in_flight: None,
             },
             TransportConfig::Http {
                 host: Some("localhost".to_string()),
                 port: 3000,
+                max_in_flight: None,
             },
             TransportConfig::WebSocket {
                 host: None,
@@ -313,6 +323,24 @@ mod tests {
         assert_eq!(result, Some(session_id.to_string()));
     }
 
+    #[tokio::test]
+    async fn test_with_connection_context() {
+        assert!(crate::try_current_connection().is_none());
+
+        let peer: std::net::SocketAddr = "10.0.0.7:4000".parse().unwrap();
+        let connection = crate::ConnectionInfo::new("conn-1", "http")
+            .with_peer_addr(peer)
+            .with_headers([("Authorization", "Bearer token")]);
+        let current = crate::with_connection(connection, async { crate::try_current_connection() })
+            .await
+            .unwrap();
+
+        assert_eq!(current.connection_id, "conn-1");
+        assert_eq!(current.transport, "http");
+        assert_eq!(current.peer_addr, Some(peer));
+        assert_eq!(current.header("AUTHORIZATION"), Some("Bearer token"));
+    }
+
     #[tokio::test]
     async fn test_with_streaming_context() {
         let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel();
@@ -401,6 +429,7 @@ mod tests {
         let config = TransportConfig::Http {
             host: Some("127.0.0.1".to_string()),
             port: 3000,
+            max_in_flight: None,
         };
         let transport = crate::create_transport(config);
 
@@ -1,16 +1,25 @@
 //! MCP-compliant Standard I/O transport implementation
 
 use crate::{
-    RequestHandler, Transport, TransportError,
-    batch::{JsonRpcMessage, create_error_response, process_batch},
-    validation::{extract_id_from_malformed, validate_message_string},
+    DisconnectHandler, RequestHandler, Transport, TransportError,
+    batch::{BatchConfig, JsonRpcMessage, create_error_response, process_batch_with},
+    drain::ActiveHandlers,
+    validation::{
+        InvalidUtf8Policy, decode_message_bytes, extract_id_from_malformed, validate_message_string,
+    },
 };
 use async_trait::async_trait;
 use pulseengine_mcp_protocol::Response;
 use std::sync::Arc;
-use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+use std::time::Duration;
+use tokio::io::{AsyncBufReadExt, AsyncRead, AsyncWrite, AsyncWriteExt, BufReader};
+use tokio::sync::Notify;
 use tracing::{debug, error, info, warn};
 
+/// Environment variable holding the API key of the stdio client, reported
+/// as its `X-API-Key` header
+pub const STDIO_API_KEY_ENV: &str = "MCP_API_KEY";
+
 /// Configuration for stdio transport
 #[derive(Debug, Clone)]
 pub struct StdioConfig {
@@ -18,6 +27,10 @@ pub struct StdioConfig {
     pub max_message_size: usize,
     /// Enable message validation
     pub validate_messages: bool,
+    /// How to handle lines that aren't valid UTF-8
+    pub invalid_utf8: InvalidUtf8Policy,
+    /// How JSON-RPC batches are processed
+    pub batch: BatchConfig,
 }
 
 impl Default for StdioConfig {
@@ -25,6 +38,8 @@ impl Default for StdioConfig {
         Self {
             max_message_size: 10 * 1024 * 1024, // 10MB
             validate_messages: true,
+            invalid_utf8: InvalidUtf8Policy::default(),
+            batch: BatchConfig::default(),
         }
     }
 }
@@ -37,10 +52,27 @@ impl Default for StdioConfig {
 /// - Messages must be valid UTF-8
 /// - Supports JSON-RPC batching
 /// - Proper error handling with ID preservation
-#[derive(Debug)]
+///
+/// When it stops, stdout is flushed and shut down so the peer reads every
+/// response followed by EOF.
 pub struct StdioTransport {
     running: Arc<std::sync::atomic::AtomicBool>,
+    /// Wakes the read loop when `stop` is called
+    stop_signal: Arc<Notify>,
     config: StdioConfig,
+    active: ActiveHandlers,
+    /// Told once stdin closes
+    on_disconnect: Option<DisconnectHandler>,
+}
+
+impl std::fmt::Debug for StdioTransport {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("StdioTransport")
+            .field("running", &self.running)
+            .field("config", &self.config)
+            .field("active", &self.active)
+            .finish_non_exhaustive()
+    }
 }
 
 impl StdioTransport {
@@ -48,7 +80,10 @@ impl StdioTransport {
     pub fn new() -> Self {
         Self {
             running: Arc::new(std::sync::atomic::AtomicBool::new(false)),
+            stop_signal: Arc::new(Notify::new()),
             config: StdioConfig::default(),
+            active: ActiveHandlers::new(),
+            on_disconnect: None,
         }
     }
 
@@ -56,7 +91,10 @@ impl StdioTransport {
     pub fn with_config(config: StdioConfig) -> Self {
         Self {
             running: Arc::new(std::sync::atomic::AtomicBool::new(false)),
+            stop_signal: Arc::new(Notify::new()),
             config,
+            active: ActiveHandlers::new(),
+            on_disconnect: None,
         }
     }
 
@@ -77,12 +115,99 @@ impl StdioTransport {
             .store(running, std::sync::atomic::Ordering::Relaxed);
     }
 
+    /// Stop reading and wake a read loop waiting for the next line
+    fn request_stop(&self) {
+        self.running
+            .store(false, std::sync::atomic::Ordering::Relaxed);
+        self.stop_signal.notify_waiters();
+    }
+
+    /// Serve newline-delimited messages until EOF or `stop`, then flush and
+    /// shut down the writer
+    async fn serve<R, W>(
+        &self,
+        reader: R,
+        mut stdout: W,
+        handler: &RequestHandler,
+    ) -> Result<(), TransportError>
+    where
+        R: AsyncRead + Unpin,
+        W: AsyncWrite + Unpin,
+    {
+        let mut reader = BufReader::new(reader);
+        let mut line = Vec::new();
+
+        let result = loop {
+            // Registered before checking the flag so a stop in between isn't missed
+            let stopped = self.stop_signal.notified();
+            tokio::pin!(stopped);
+            stopped.as_mut().enable();
+            if !self.running.load(std::sync::atomic::Ordering::Relaxed) {
+                break Ok(());
+            }
+
+            line.clear();
+            let read = tokio::select! {
+                biased;
+                _ = &mut stopped => break Ok(()),
+                read = reader.read_until(b'\n', &mut line) => read,
+            };
+
+            match read {
+                Ok(0) => {
+                    debug!("EOF reached, stopping stdio transport");
+                    break Ok(());
+                }
+                Ok(_) => {
+                    // Lines are read as bytes so invalid UTF-8 gets a targeted
+                    // error response instead of ending the transport
+                    let text = match decode_message_bytes(&line, self.config.invalid_utf8) {
+                        Ok(text) => text,
+                        Err(e) => {
+                            warn!("Rejecting message: {}", e);
+                            let request_id = e.request_id.clone();
+                            let error_response = create_error_response(e.into(), request_id);
+                            if let Err(e) = self.send_response(&mut stdout, &error_response).await {
+                                error!("Failed to send response: {}", e);
+                            }
+                            continue;
+                        }
+                    };
+
+                    // Remove trailing newline for processing
+                    let trimmed_line = text.trim_end_matches(['\n', '\r']);
+
+                    // Skip empty lines
+                    if trimmed_line.is_empty() {
+                        continue;
+                    }
+
+                    // Process the line
+                    if let Err(e) = self.process_line(trimmed_line, handler, &mut stdout).await {
+                        error!("Failed to process line: {}", e);
+                        // Continue processing other messages
+                    }
+                }
+                Err(e) => {
+                    error!("Failed to read from stdin: {}", e);
+                    break Err(TransportError::Connection(format!("Stdin read error: {e}")));
+                }
+            }
+        };
+
+        // Flush anything buffered and signal EOF to the peer
+        if let Err(e) = stdout.shutdown().await {
+            warn!("Failed to close stdout: {}", e);
+        }
+        result
+    }
+
     /// Process a single line from stdin
-    async fn process_line(
+    async fn process_line<W: AsyncWrite + Unpin>(
         &self,
         line: &str,
         handler: &RequestHandler,
-        stdout: &mut tokio::io::Stdout,
+        stdout: &mut W,
     ) -> Result<(), TransportError> {
         // Validate message according to MCP spec
         if self.config.validate_messages
@@ -138,7 +263,7 @@ impl StdioTransport {
         }
 
         // Process the message (handles both single and batch)
-        match process_batch(message, handler).await {
+        match process_batch_with(message, handler, &self.config.batch).await {
             Ok(Some(response_message)) => {
                 // Send response(s)
                 let response_json = response_message.to_string().map_err(|e| {
@@ -170,9 +295,9 @@ impl StdioTransport {
     }
 
     /// Send a response to stdout
-    async fn send_response(
+    async fn send_response<W: AsyncWrite + Unpin>(
         &self,
-        stdout: &mut tokio::io::Stdout,
+        stdout: &mut W,
         response: &Response,
     ) -> Result<(), TransportError> {
         let response_json = serde_json::to_string(response)
@@ -182,9 +307,9 @@ impl StdioTransport {
     }
 
     /// Send a line to stdout with proper newline handling
-    async fn send_line(
+    async fn send_line<W: AsyncWrite + Unpin>(
         &self,
-        stdout: &mut tokio::io::Stdout,
+        stdout: &mut W,
         line: &str,
     ) -> Result<(), TransportError> {
         // Validate outgoing message
@@ -232,50 +357,47 @@ impl Transport for StdioTransport {
 
         self.running
             .store(true, std::sync::atomic::Ordering::Relaxed);
+        let handler = self.active.wrap(handler);
+
+        // The process environment stands in for the credentials a network
+        // client would send as headers
+        let api_key = std::env::var(STDIO_API_KEY_ENV).ok();
+        let connection = crate::ConnectionInfo::new("stdio", "stdio")
+            .with_headers(api_key.as_deref().map(|key| ("x-api-key", key)));
+        let result = crate::with_connection(
+            connection,
+            self.serve(tokio::io::stdin(), tokio::io::stdout(), &handler),
+        )
+        .await;
+        crate::notify_disconnect(self.on_disconnect.as_ref(), "stdio".to_string(), None).await;
 
-        let stdin = tokio::io::stdin();
-        let mut stdout = tokio::io::stdout();
-        let mut reader = BufReader::new(stdin);
-        let mut line = String::new();
-
-        while self.running.load(std::sync::atomic::Ordering::Relaxed) {
-            line.clear();
-
-            match reader.read_line(&mut line).await {
-                Ok(0) => {
-                    debug!("EOF reached, stopping stdio transport");
-                    break;
-                }
-                Ok(_) => {
-                    // Remove trailing newline for processing
-                    let trimmed_line = line.trim_end_matches(['\n', '\r']);
-
-                    // Skip empty lines
-                    if trimmed_line.is_empty() {
-                        continue;
-                    }
+        info!("Stdio transport stopped");
+        result
+    }
 
-                    // Process the line
-                    if let Err(e) = self.process_line(trimmed_line, &handler, &mut stdout).await {
-                        error!("Failed to process line: {}", e);
-                        // Continue processing other messages
-                    }
-                }
-                Err(e) => {
-                    error!("Failed to read from stdin: {}", e);
-                    return Err(TransportError::Connection(format!("Stdin read error: {e}")));
-                }
-            }
-        }
+    fn set_batch_config(&mut self, config: BatchConfig) {
+        self.config.batch = config;
+    }
 
-        info!("Stdio transport stopped");
-        Ok(())
+    fn set_disconnect_handler(&mut self, handler: DisconnectHandler) {
+        self.on_disconnect = Some(handler);
     }
 
     async fn stop(&mut self) -> Result<(), TransportError> {
         info!("Stopping stdio transport");
-        self.running
-            .store(false, std::sync::atomic::Ordering::Relaxed);
+        self.request_stop();
+        Ok(())
+    }
+
+    async fn shutdown(&mut self, grace: Duration) -> Result<(), TransportError> {
+        // No further lines are read once stopped; wait for the one in progress
+        self.stop().await?;
+        if !self.active.drain(grace).await {
+            warn!(
+                "Stdio transport stopped with {} requests still running",
+                self.active.count()
+            );
+        }
         Ok(())
     }
 
@@ -325,6 +447,7 @@ mod tests {
         let config = StdioConfig {
             max_message_size: 1024,
             validate_messages: true,
+            ..Default::default()
         };
 
         let transport = StdioTransport::with_config(config.clone());
@@ -417,6 +540,7 @@ mod tests {
         let config = StdioConfig {
             max_message_size: 2048,
             validate_messages: false,
+            ..Default::default()
         };
         let transport = StdioTransport::with_config(config);
 
@@ -462,11 +586,65 @@ mod tests {
         assert!(!transport.is_running());
     }
 
+    #[tokio::test]
+    async fn test_stop_flushes_and_closes_output() {
+        use tokio::io::AsyncBufReadExt;
+
+        let transport = Arc::new(StdioTransport::new());
+        transport.set_running(true);
+        let (client, server) = tokio::io::duplex(4096);
+        let (server_read, server_write) = tokio::io::split(server);
+        let (client_read, mut client_write) = tokio::io::split(client);
+
+        let serving = tokio::spawn({
+            let transport = transport.clone();
+            async move {
+                let handler: RequestHandler = Box::new(mock_handler);
+                transport.serve(server_read, server_write, &handler).await
+            }
+        });
+
+        client_write
+            .write_all(b"{\"jsonrpc\":\"2.0\",\"id\":1,\"method\":\"ping\"}\n")
+            .await
+            .unwrap();
+        let mut lines = BufReader::new(client_read).lines();
+        let reply: serde_json::Value =
+            serde_json::from_str(&lines.next_line().await.unwrap().unwrap()).unwrap();
+        assert_eq!(reply["result"]["echo"], "ping");
+
+        // Stopping wakes the idle read loop; the peer then sees EOF
+        transport.request_stop();
+        let result = tokio::time::timeout(Duration::from_secs(5), serving)
+            .await
+            .expect("stop should end the read loop")
+            .unwrap();
+        assert!(result.is_ok());
+        assert!(lines.next_line().await.unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn test_input_eof_ends_serving_after_replies() {
+        let transport = StdioTransport::new();
+        transport.set_running(true);
+        let handler: RequestHandler = Box::new(mock_handler);
+        let mut output = Vec::new();
+
+        let input: &[u8] = b"{\"jsonrpc\":\"2.0\",\"id\":7,\"method\":\"tools/list\"}\n";
+        transport.serve(input, &mut output, &handler).await.unwrap();
+
+        let reply: serde_json::Value =
+            serde_json::from_str(String::from_utf8(output).unwrap().trim_end()).unwrap();
+        assert_eq!(reply["id"], 7);
+        assert_eq!(reply["result"]["echo"], "tools/list");
+    }
+
     #[test]
     fn test_stdio_config_clone() {
         let config1 = StdioConfig {
             max_message_size: 1024,
             validate_messages: true,
+            ..Default::default()
         };
 
         let config2 = config1.clone();
@@ -497,6 +675,7 @@ mod tests {
         let config = StdioConfig {
             max_message_size: 50, // Very small for testing
             validate_messages: true,
+            ..Default::default()
         };
         let _transport = StdioTransport::with_config(config);
 
@@ -52,6 +52,7 @@ mod tests {
         let config = StdioConfig {
             max_message_size: 1024,
             validate_messages: false,
+            ..Default::default()
         };
 
         assert_eq!(config.max_message_size, 1024);
@@ -64,6 +65,7 @@ mod tests {
         let config = StdioConfig {
             max_message_size: 0, // No limit
             validate_messages: true,
+            ..Default::default()
         };
 
         assert_eq!(config.max_message_size, 0);
@@ -73,6 +75,7 @@ mod tests {
         let config = StdioConfig {
             max_message_size: usize::MAX,
             validate_messages: false,
+            ..Default::default()
         };
 
         assert_eq!(config.max_message_size, usize::MAX);
@@ -93,6 +96,7 @@ mod tests {
         let config = StdioConfig {
             max_message_size: 2048,
             validate_messages: false,
+            ..Default::default()
         };
 
         let transport = StdioTransport::with_config(config.clone());
@@ -186,6 +190,7 @@ mod tests {
         let config = StdioConfig {
             max_message_size: 10 * 1024 * 1024,
             validate_messages: false, // Disabled validation
+            ..Default::default()
         };
         let _transport = StdioTransport::with_config(config);
         let mut output = Vec::new();
@@ -248,6 +253,7 @@ mod tests {
         let config = StdioConfig {
             max_message_size: 2048,
             validate_messages: false,
+            ..Default::default()
         };
 
         let cloned = config.clone();
@@ -317,6 +323,7 @@ mod tests {
         let transport3 = StdioTransport::with_config(StdioConfig {
             max_message_size: 1024,
             validate_messages: false,
+            ..Default::default()
         });
 
         // Each transport should be independent
@@ -374,6 +381,7 @@ mod tests {
         let config_min = StdioConfig {
             max_message_size: 0,
             validate_messages: false,
+            ..Default::default()
         };
         let transport_min = StdioTransport::with_config(config_min);
         assert_eq!(transport_min.config().max_message_size, 0);
@@ -383,6 +391,7 @@ mod tests {
         let config_max = StdioConfig {
             max_message_size: usize::MAX,
             validate_messages: true,
+            ..Default::default()
         };
         let transport_max = StdioTransport::with_config(config_max);
         assert_eq!(transport_max.config().max_message_size, usize::MAX);
@@ -407,6 +416,7 @@ mod tests {
             let config = StdioConfig {
                 max_message_size: size,
                 validate_messages: true,
+                ..Default::default()
             };
             let transport = StdioTransport::with_config(config);
 
@@ -424,6 +434,7 @@ mod tests {
             let config = StdioConfig {
                 max_message_size: 1024,
                 validate_messages: validate,
+                ..Default::default()
             };
             let transport = StdioTransport::with_config(config);
 
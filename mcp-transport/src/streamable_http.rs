@@ -11,11 +11,18 @@
 //! - **Bidirectional communication** - server can send notifications and requests to clients
 
 use crate::{
-    RequestHandler, StreamingNotification, Transport, TransportError, with_streaming_context,
+    DisconnectHandler, RequestHandler, StreamingNotification, Transport, TransportError,
+    batch::create_error_response,
+    drain::ActiveHandlers,
+    http::scope_connection,
+    is_no_response, notify_disconnect, try_current_connection,
+    validation::{InvalidUtf8Policy, decode_message_bytes},
+    with_connection, with_streaming_context,
 };
 use async_trait::async_trait;
 use axum::{
     Json, Router,
+    body::Bytes,
     extract::{Query, State},
     http::{HeaderMap, StatusCode},
     response::{IntoResponse, Response as AxumResponse, Sse, sse::Event as SseEvent},
@@ -55,9 +62,40 @@ pub struct StreamableHttpConfig {
     /// When true, server will attach event IDs and support Last-Event-ID header
     pub sse_resumable: bool,
     /// Channel capacity for SSE message broadcasting
+    ///
+    /// This is also how many events an SSE consumer may fall behind before
+    /// `slow_consumer_policy` engages.
     pub channel_capacity: usize,
+    /// What to do with an SSE consumer that falls behind by more than
+    /// `channel_capacity` events
+    pub slow_consumer_policy: SlowConsumerPolicy,
     /// Default timeout for server-initiated requests (sampling, elicitation)
     pub request_timeout: Duration,
+    /// Maximum lifetime of an SSE stream
+    ///
+    /// Once elapsed the server closes the stream with a `retry` hint so load
+    /// balancers can recycle the connection. With `sse_resumable`, events sent
+    /// before the client reconnects with `Last-Event-ID` are buffered and
+    /// delivered on the resumed stream. `None` keeps streams open indefinitely.
+    pub max_stream_lifetime: Option<Duration>,
+    /// How to handle request bodies that aren't valid UTF-8
+    pub invalid_utf8: InvalidUtf8Policy,
+    /// Never answer messages without an `id`
+    ///
+    /// Such messages are JSON-RPC notifications: they are still passed to the
+    /// handler, but whatever it returns is dropped and the POST is answered
+    /// with `202 Accepted`, even for methods that normally return a result.
+    /// Disabling this restores the old behaviour of returning the handler's
+    /// response for clients that rely on it.
+    pub strict_notifications: bool,
+    /// Longest `data:` line of an SSE event, in bytes
+    ///
+    /// Larger JSON payloads are wrapped onto several `data:` lines, which SSE
+    /// clients join with newlines before parsing, so proxies with line length
+    /// limits pass them through. Lines are only broken between JSON tokens; a
+    /// single string longer than the limit stays on one line. `None` sends
+    /// every event on one line.
+    pub max_sse_line_bytes: Option<usize>,
 }
 
 impl Default for StreamableHttpConfig {
@@ -71,7 +109,12 @@ impl Default for StreamableHttpConfig {
             sse_retry_ms: 3000, // 3 seconds default retry interval
             sse_resumable: true,
             channel_capacity: 100,
+            slow_consumer_policy: SlowConsumerPolicy::default(),
             request_timeout: Duration::from_secs(60),
+            max_stream_lifetime: None,
+            invalid_utf8: InvalidUtf8Policy::default(),
+            strict_notifications: true,
+            max_sse_line_bytes: Some(16 * 1024),
         }
     }
 }
@@ -98,6 +141,43 @@ impl StreamableHttpConfig {
     }
 }
 
+/// Policy for SSE consumers that can't keep up with their session's events
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum SlowConsumerPolicy {
+    /// Drop the oldest buffered events and keep streaming, counting the lag
+    #[default]
+    DropOldest,
+    /// Close the lagging stream; the client can reconnect and resume
+    Disconnect,
+}
+
+/// Counters for SSE consumers that fell behind
+#[derive(Debug, Default)]
+pub struct SlowConsumerMetrics {
+    lag_events: std::sync::atomic::AtomicU64,
+    dropped_events: std::sync::atomic::AtomicU64,
+    disconnected_consumers: std::sync::atomic::AtomicU64,
+}
+
+impl SlowConsumerMetrics {
+    /// Number of times a consumer was detected lagging
+    pub fn lag_events(&self) -> u64 {
+        self.lag_events.load(std::sync::atomic::Ordering::Relaxed)
+    }
+
+    /// Number of events dropped because consumers lagged
+    pub fn dropped_events(&self) -> u64 {
+        self.dropped_events
+            .load(std::sync::atomic::Ordering::Relaxed)
+    }
+
+    /// Number of consumers disconnected for lagging
+    pub fn disconnected_consumers(&self) -> u64 {
+        self.disconnected_consumers
+            .load(std::sync::atomic::Ordering::Relaxed)
+    }
+}
+
 /// Message that can be sent via SSE to clients
 #[derive(Debug, Clone)]
 pub enum SseMessage {
@@ -122,6 +202,9 @@ struct SessionInfo {
     event_counter: u64,
     /// Broadcast channel sender for this session's SSE messages
     message_sender: broadcast::Sender<SseMessage>,
+    /// Receiver of a stream closed at its max lifetime, kept so events sent
+    /// before the client resumes aren't lost
+    parked_receiver: Option<broadcast::Receiver<SseMessage>>,
 }
 
 /// Pending request awaiting response from client
@@ -177,6 +260,8 @@ struct AppState {
     sessions: Arc<SessionsMap>,
     pending_requests: Arc<PendingRequestsMap>,
     config: StreamableHttpConfig,
+    slow_consumers: Arc<SlowConsumerMetrics>,
+    on_disconnect: Option<DisconnectHandler>,
 }
 
 /// Handle for accessing transport state from outside the HTTP server
@@ -184,11 +269,17 @@ struct AppState {
 pub struct TransportHandle {
     sessions: Arc<SessionsMap>,
     pending_requests: Arc<PendingRequestsMap>,
+    slow_consumers: Arc<SlowConsumerMetrics>,
     #[allow(dead_code)]
     config: StreamableHttpConfig,
 }
 
 impl TransportHandle {
+    /// Metrics for SSE consumers that fell behind their session's events
+    pub fn slow_consumer_metrics(&self) -> &SlowConsumerMetrics {
+        &self.slow_consumers
+    }
+
     /// Send a notification to a specific session or all sessions
     pub async fn send_notification(
         &self,
@@ -415,6 +506,12 @@ pub struct StreamableHttpTransport {
     server_handle: Option<tokio::task::JoinHandle<()>>,
     /// Handle for sending messages to sessions
     transport_handle: Option<TransportHandle>,
+    /// Handlers still running, waited on by `shutdown`
+    active: ActiveHandlers,
+    /// Tells the server to stop accepting connections
+    shutdown_signal: Option<oneshot::Sender<()>>,
+    /// Told when a client ends its session
+    on_disconnect: Option<DisconnectHandler>,
 }
 
 impl StreamableHttpTransport {
@@ -426,6 +523,9 @@ impl StreamableHttpTransport {
             },
             server_handle: None,
             transport_handle: None,
+            active: ActiveHandlers::new(),
+            shutdown_signal: None,
+            on_disconnect: None,
         }
     }
 
@@ -435,6 +535,9 @@ impl StreamableHttpTransport {
             config,
             server_handle: None,
             transport_handle: None,
+            active: ActiveHandlers::new(),
+            shutdown_signal: None,
+            on_disconnect: None,
         }
     }
 
@@ -487,6 +590,7 @@ impl StreamableHttpTransport {
                 created_at: std::time::Instant::now(),
                 event_counter: 0,
                 message_sender: sender,
+                parked_receiver: None,
             };
             let mut sessions = state.sessions.write().await;
             sessions.insert(id.clone(), session);
@@ -502,6 +606,7 @@ impl StreamableHttpTransport {
             created_at: std::time::Instant::now(),
             event_counter: 0,
             message_sender: sender,
+            parked_receiver: None,
         };
 
         let mut sessions = state.sessions.write().await;
@@ -535,15 +640,27 @@ impl StreamableHttpTransport {
 async fn handle_messages(
     State(state): State<Arc<AppState>>,
     headers: HeaderMap,
-    body: String,
+    body: Bytes,
 ) -> AxumResponse {
-    debug!("Received POST /messages: {}", body);
-
     // MCP 2025-11-25: Validate Origin header - return 403 Forbidden for invalid origins
     if let Some(forbidden_response) = validate_origin(&headers, &state.config) {
         return forbidden_response.into_response();
     }
 
+    let body = match decode_message_bytes(&body, state.config.invalid_utf8) {
+        Ok(body) => body,
+        Err(e) => {
+            warn!("Rejecting message: {}", e);
+            let request_id = e.request_id.clone();
+            return (
+                StatusCode::BAD_REQUEST,
+                Json(create_error_response(e.into(), request_id)),
+            )
+                .into_response();
+        }
+    };
+    debug!("Received POST /messages: {}", body);
+
     // Get or create session
     let session_id = headers
         .get("Mcp-Session-Id")
@@ -579,6 +696,7 @@ async fn handle_messages(
             let handle = TransportHandle {
                 sessions: Arc::clone(&state.sessions),
                 pending_requests: Arc::clone(&state.pending_requests),
+                slow_consumers: Arc::clone(&state.slow_consumers),
                 config: state.config.clone(),
             };
 
@@ -628,6 +746,24 @@ async fn handle_messages(
             }
         };
 
+    // Build response headers
+    let mut response_headers = HeaderMap::new();
+    response_headers.insert("Mcp-Session-Id", session_id.parse().unwrap());
+
+    // Notifications are handled for their side effects only
+    if state.config.strict_notifications && mcp_request.id.is_none() {
+        let method = mcp_request.method.clone();
+        let (notification_tx, _notification_rx) =
+            tokio::sync::mpsc::unbounded_channel::<StreamingNotification>();
+        let handler = state.handler.clone();
+        let _dropped = with_streaming_context(session_id, notification_tx, async move {
+            (handler)(mcp_request).await
+        })
+        .await;
+        debug!("Handled notification {} without responding", method);
+        return (StatusCode::ACCEPTED, response_headers).into_response();
+    }
+
     // Check if client accepts SSE responses
     let accepts_sse = headers
         .get("Accept")
@@ -635,10 +771,6 @@ async fn handle_messages(
         .map(|s| s.contains("text/event-stream"))
         .unwrap_or(false);
 
-    // Build response headers
-    let mut response_headers = HeaderMap::new();
-    response_headers.insert("Mcp-Session-Id", session_id.parse().unwrap());
-
     // For bidirectional communication (sampling, elicitation), we need true streaming
     // where events are sent as they're produced, not collected after handler completes.
     // This is required because tools may block waiting for client responses.
@@ -684,7 +816,8 @@ async fn handle_messages(
             notifications.len()
         );
 
-        let stream = create_post_response_stream(notifications, response);
+        let stream =
+            create_post_response_stream(notifications, response, state.config.max_sse_line_bytes);
 
         response_headers.insert("Content-Type", "text/event-stream".parse().unwrap());
         response_headers.insert("Cache-Control", "no-cache".parse().unwrap());
@@ -694,9 +827,68 @@ async fn handle_messages(
     }
 
     // No notifications - return simple JSON response
+    if is_no_response(&response) {
+        return (StatusCode::ACCEPTED, response_headers).into_response();
+    }
     (StatusCode::OK, response_headers, Json(response)).into_response()
 }
 
+/// Break serialized JSON onto lines of at most `max_line_bytes` where possible
+///
+/// Breaks are placed only after `{`, `[`, `,` and `:` outside strings, where
+/// the newline an SSE client inserts between `data:` lines is insignificant
+/// whitespace, so the reassembled event parses to the same value.
+pub fn wrap_json_lines(json: &str, max_line_bytes: usize) -> String {
+    if json.len() <= max_line_bytes {
+        return json.to_string();
+    }
+
+    let mut wrapped = String::with_capacity(json.len() + json.len() / max_line_bytes.max(1));
+    let mut line = String::new();
+    let mut last_break = None;
+    let mut in_string = false;
+    let mut escaped = false;
+
+    for c in json.chars() {
+        line.push(c);
+        let breakable = if in_string {
+            match (escaped, c) {
+                (true, _) => escaped = false,
+                (false, '\\') => escaped = true,
+                (false, '"') => in_string = false,
+                _ => {}
+            }
+            false
+        } else {
+            in_string = c == '"';
+            matches!(c, '{' | '[' | ',' | ':')
+        };
+
+        if line.len() > max_line_bytes
+            && let Some(at) = last_break.take()
+        {
+            wrapped.push_str(&line[..at]);
+            wrapped.push('\n');
+            line.drain(..at);
+        }
+        if breakable {
+            last_break = Some(line.len());
+        }
+    }
+    wrapped.push_str(&line);
+    wrapped
+}
+
+/// SSE event carrying a JSON-RPC message, wrapped to the configured line length
+fn json_sse_event(message: &Value, max_line_bytes: Option<usize>) -> SseEvent {
+    let json = message.to_string();
+    let data = match max_line_bytes {
+        Some(max) => wrap_json_lines(&json, max),
+        None => json,
+    };
+    SseEvent::default().data(data)
+}
+
 /// Create a real-time SSE stream that sends events as they're produced
 ///
 /// This is essential for bidirectional communication (sampling, elicitation) where
@@ -708,6 +900,10 @@ fn create_realtime_sse_stream(
     session_id: String,
     mcp_request: pulseengine_mcp_protocol::Request,
 ) -> impl Stream<Item = Result<SseEvent, Infallible>> + Send {
+    let max_line_bytes = state.config.max_sse_line_bytes;
+    // The stream is polled after the request handler returns, outside its
+    // connection scope
+    let connection = try_current_connection().unwrap_or_default();
     async_stream::stream! {
         eprintln!("[DEBUG SSE RT] Starting real-time stream for session {}", session_id);
 
@@ -718,12 +914,12 @@ fn create_realtime_sse_stream(
         // Spawn handler in a separate task so we can stream events concurrently
         let handler = state.handler.clone();
         let session_id_for_context = session_id.clone();
-        let handler_task = tokio::spawn(async move {
+        let handler_task = tokio::spawn(with_connection(connection, async move {
             with_streaming_context(session_id_for_context, notification_tx, async move {
                 (handler)(mcp_request).await
             })
             .await
-        });
+        }));
 
         // Wrap the handler task in a fuse to allow awaiting multiple times safely
         let mut handler_task = std::pin::pin!(handler_task);
@@ -754,14 +950,14 @@ fn create_realtime_sse_stream(
                             })
                         };
                         eprintln!("[DEBUG SSE RT] Draining {}: {}", if is_request { "request" } else { "notification" }, notification.method);
-                        yield Ok(SseEvent::default().data(json_message.to_string()));
+                        yield Ok(json_sse_event(&json_message, max_line_bytes));
                     }
                     Err(_) => {
                         // No more notifications, send final response and exit
-                        if let Some(response) = handler_result.take() {
+                        if let Some(response) = handler_result.take().filter(|r| !is_no_response(r)) {
                             let json_response = serde_json::to_value(&response).unwrap_or(Value::Null);
                             eprintln!("[DEBUG SSE RT] Sending final response");
-                            yield Ok(SseEvent::default().data(json_response.to_string()));
+                            yield Ok(json_sse_event(&json_response, max_line_bytes));
                         }
                         break;
                     }
@@ -817,7 +1013,7 @@ fn create_realtime_sse_stream(
                             eprintln!("[DEBUG SSE RT] Sending {}: {}",
                                 if is_request { "request" } else { "notification" },
                                 notification.method);
-                            yield Ok(SseEvent::default().data(json_message.to_string()));
+                            yield Ok(json_sse_event(&json_message, max_line_bytes));
                         }
                         None => {
                             // Channel closed - handler should be done
@@ -839,6 +1035,7 @@ fn create_realtime_sse_stream(
 fn create_post_response_stream(
     notifications: Vec<StreamingNotification>,
     response: pulseengine_mcp_protocol::Response,
+    max_line_bytes: Option<usize>,
 ) -> impl Stream<Item = Result<SseEvent, Infallible>> + Send {
     async_stream::stream! {
         // First, send all notifications/requests as SSE events
@@ -864,29 +1061,36 @@ fn create_post_response_stream(
             eprintln!("[DEBUG SSE] Sending {}: {}",
                 if is_request { "request" } else { "notification" },
                 notification.method);
-            yield Ok(SseEvent::default().data(json_message.to_string()));
+            yield Ok(json_sse_event(&json_message, max_line_bytes));
         }
 
-        // Then send the final response
-        let json_response = serde_json::to_value(&response).unwrap_or(Value::Null);
-        eprintln!("[DEBUG SSE] Sending final response");
-        yield Ok(SseEvent::default().data(json_response.to_string()));
+        // Then send the final response, unless the request goes unanswered
+        if !is_no_response(&response) {
+            let json_response = serde_json::to_value(&response).unwrap_or(Value::Null);
+            eprintln!("[DEBUG SSE] Sending final response");
+            yield Ok(json_sse_event(&json_response, max_line_bytes));
+        }
     }
 }
 
 /// Create an SSE stream for a session
+///
+/// A `resuming` stream picks up the receiver parked by a stream that reached
+/// its max lifetime, so it delivers the events sent in between.
 fn create_sse_stream(
     state: Arc<AppState>,
     session_id: String,
     stream_id: String,
+    resuming: bool,
 ) -> impl Stream<Item = Result<SseEvent, Infallible>> + Send {
     async_stream::stream! {
         eprintln!("[DEBUG SSE] Stream started for session {}, stream {}", session_id, stream_id);
         // Get a receiver for this session's messages
         let mut receiver = {
-            let sessions = state.sessions.read().await;
-            if let Some(session) = sessions.get(&session_id) {
-                let rx = session.message_sender.subscribe();
+            let mut sessions = state.sessions.write().await;
+            if let Some(session) = sessions.get_mut(&session_id) {
+                let parked = resuming.then(|| session.parked_receiver.take()).flatten();
+                let rx = parked.unwrap_or_else(|| session.message_sender.subscribe());
                 let receiver_count = session.message_sender.receiver_count();
                 eprintln!("[DEBUG SSE] Subscribed to session {session_id}, receiver count now: {receiver_count}");
                 rx
@@ -925,47 +1129,119 @@ fn create_sse_stream(
         }
         yield Ok(event);
 
-        // Listen for messages and forward them
+        // Listen for messages and forward them until the stream's lifetime ends
         eprintln!("[DEBUG SSE] Entering message loop for session {}", session_id);
+        let deadline = state
+            .config
+            .max_stream_lifetime
+            .map(|lifetime| tokio::time::Instant::now() + lifetime);
         loop {
-            match receiver.recv().await {
-                Ok(message) => {
-                    eprintln!("[DEBUG SSE] Received message for session {session_id}: {message:?}");
-                    let json_message = match message {
-                        SseMessage::Notification { method, params } => {
-                            serde_json::json!({
-                                "jsonrpc": "2.0",
-                                "method": method,
-                                "params": params
-                            })
-                        }
-                        SseMessage::Request { id, method, params } => {
-                            serde_json::json!({
-                                "jsonrpc": "2.0",
-                                "id": id,
-                                "method": method,
-                                "params": params
-                            })
-                        }
-                    };
-
-                    let event_id = StreamableHttpTransport::next_event_id(&state, &session_id, &stream_id).await;
-                    let mut event = SseEvent::default().data(json_message.to_string());
-                    if let Some(id) = event_id {
-                        event = event.id(id.encode());
-                    }
-                    eprintln!("[DEBUG SSE] Yielding SSE event for session {session_id}");
-                    yield Ok(event);
+            let next = next_sse_message(
+                &mut receiver,
+                state.config.slow_consumer_policy,
+                &state.slow_consumers,
+                &session_id,
+            );
+            let next = match deadline {
+                Some(deadline) => tokio::time::timeout_at(deadline, next).await.ok(),
+                None => Some(next.await),
+            };
+            let Some(next) = next else {
+                debug!(
+                    "SSE stream {} for session {} reached its max lifetime",
+                    stream_id, session_id
+                );
+                if state.config.sse_resumable
+                    && let Some(session) = state.sessions.write().await.get_mut(&session_id)
+                {
+                    session.parked_receiver = Some(receiver);
+                }
+                let closing_event = serde_json::json!({
+                    "type": "connection",
+                    "status": "closing",
+                    "reason": "max_stream_lifetime",
+                    "sessionId": session_id,
+                    "resumable": state.config.sse_resumable
+                });
+                let event_id = StreamableHttpTransport::next_event_id(&state, &session_id, &stream_id).await;
+                let mut event = SseEvent::default()
+                    .retry(std::time::Duration::from_millis(state.config.sse_retry_ms))
+                    .data(closing_event.to_string());
+                if let Some(id) = event_id {
+                    event = event.id(id.encode());
                 }
-                Err(broadcast::error::RecvError::Lagged(n)) => {
-                    warn!("SSE stream lagged by {} messages", n);
-                    // Continue receiving
+                yield Ok(event);
+                break;
+            };
+            let Some(message) = next else {
+                break;
+            };
+            eprintln!("[DEBUG SSE] Received message for session {session_id}: {message:?}");
+            let json_message = match message {
+                SseMessage::Notification { method, params } => {
+                    serde_json::json!({
+                        "jsonrpc": "2.0",
+                        "method": method,
+                        "params": params
+                    })
+                }
+                SseMessage::Request { id, method, params } => {
+                    serde_json::json!({
+                        "jsonrpc": "2.0",
+                        "id": id,
+                        "method": method,
+                        "params": params
+                    })
                 }
-                Err(broadcast::error::RecvError::Closed) => {
-                    debug!("SSE channel closed for session {}", session_id);
-                    break;
+            };
+
+            let event_id = StreamableHttpTransport::next_event_id(&state, &session_id, &stream_id).await;
+            let mut event = json_sse_event(&json_message, state.config.max_sse_line_bytes);
+            if let Some(id) = event_id {
+                event = event.id(id.encode());
+            }
+            eprintln!("[DEBUG SSE] Yielding SSE event for session {session_id}");
+            yield Ok(event);
+        }
+    }
+}
+
+/// Receive the next message for an SSE stream, applying the slow consumer
+/// policy when the receiver has fallen behind
+///
+/// Returns `None` when the stream should end, either because the session's
+/// channel closed or because the lagging consumer is being disconnected.
+pub(crate) async fn next_sse_message(
+    receiver: &mut broadcast::Receiver<SseMessage>,
+    policy: SlowConsumerPolicy,
+    metrics: &SlowConsumerMetrics,
+    session_id: &str,
+) -> Option<SseMessage> {
+    use std::sync::atomic::Ordering;
+
+    loop {
+        match receiver.recv().await {
+            Ok(message) => return Some(message),
+            Err(broadcast::error::RecvError::Lagged(n)) => {
+                metrics.lag_events.fetch_add(1, Ordering::Relaxed);
+                metrics.dropped_events.fetch_add(n, Ordering::Relaxed);
+                warn!(
+                    session_id,
+                    dropped_events = n,
+                    policy = ?policy,
+                    "SSE consumer lagging behind session events"
+                );
+                if policy == SlowConsumerPolicy::Disconnect {
+                    metrics
+                        .disconnected_consumers
+                        .fetch_add(1, Ordering::Relaxed);
+                    return None;
                 }
             }
+            Err(broadcast::error::RecvError::Closed) => {
+                debug!("SSE channel closed for session {}", session_id);
+                return None;
+            }
         }
     }
 }
@@ -1007,8 +1283,12 @@ async fn handle_sse(
         );
     }
 
-    // Get or create session
-    let session_id = StreamableHttpTransport::ensure_session(&state, query.session_id).await;
+    // Get or create session, resuming the one named by Last-Event-ID if the
+    // client didn't name one
+    let resumed_session = last_event_id.as_ref().map(|id| id.session_id.clone());
+    let session_id =
+        StreamableHttpTransport::ensure_session(&state, query.session_id.or(resumed_session)).await;
+    let resuming = last_event_id.is_some_and(|id| id.session_id == session_id);
 
     // Generate a stream ID for this connection
     let stream_id = Uuid::new_v4().to_string();
@@ -1019,7 +1299,7 @@ async fn handle_sse(
     );
 
     // Create the SSE stream
-    let stream = create_sse_stream(Arc::clone(&state), session_id.clone(), stream_id);
+    let stream = create_sse_stream(Arc::clone(&state), session_id.clone(), stream_id, resuming);
 
     // Build response with headers
     let mut response_headers = HeaderMap::new();
@@ -1029,6 +1309,35 @@ async fn handle_sse(
     (response_headers, Sse::new(stream)).into_response()
 }
 
+/// Handle DELETE requests, which end the client's session
+async fn handle_delete_session(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+) -> AxumResponse {
+    if let Some(forbidden_response) = validate_origin(&headers, &state.config) {
+        return forbidden_response.into_response();
+    }
+    let Some(session_id) = headers
+        .get("Mcp-Session-Id")
+        .and_then(|v| v.to_str().ok())
+        .map(|s| s.to_string())
+    else {
+        return StatusCode::BAD_REQUEST.into_response();
+    };
+
+    if state.sessions.write().await.remove(&session_id).is_none() {
+        return StatusCode::NOT_FOUND.into_response();
+    }
+    info!("Client ended session: {}", session_id);
+    notify_disconnect(
+        state.on_disconnect.as_ref(),
+        session_id.clone(),
+        Some(session_id),
+    )
+    .await;
+    StatusCode::OK.into_response()
+}
+
 #[async_trait]
 impl Transport for StreamableHttpTransport {
     async fn start(&mut self, handler: RequestHandler) -> Result<(), TransportError> {
@@ -1040,30 +1349,43 @@ impl Transport for StreamableHttpTransport {
         let sessions: Arc<SessionsMap> = Arc::new(RwLock::new(HashMap::new()));
         let pending_requests: Arc<PendingRequestsMap> =
             Arc::new(std::sync::RwLock::new(HashMap::new()));
+        let slow_consumers = Arc::new(SlowConsumerMetrics::default());
 
         // Create transport handle for external access
         self.transport_handle = Some(TransportHandle {
             sessions: Arc::clone(&sessions),
             pending_requests: Arc::clone(&pending_requests),
+            slow_consumers: Arc::clone(&slow_consumers),
             config: self.config.clone(),
         });
 
         let state = Arc::new(AppState {
-            handler: Arc::new(handler),
+            handler: Arc::new(self.active.wrap(handler)),
             sessions,
             pending_requests,
             config: self.config.clone(),
+            slow_consumers,
+            on_disconnect: self.on_disconnect.clone(),
         });
 
         // Build router - using /mcp endpoint for MCP-UI compatibility
         let app = Router::new()
-            .route("/mcp", post(handle_messages).get(handle_sse))
+            .route(
+                "/mcp",
+                post(handle_messages)
+                    .get(handle_sse)
+                    .delete(handle_delete_session),
+            )
             .route("/messages", post(handle_messages)) // Legacy endpoint
             .route("/sse", get(handle_sse)) // Legacy endpoint
             .route(
                 "/",
                 get(|| async { "MCP Streamable HTTP Server (Bidirectional)" }),
             )
+            .layer(axum::middleware::from_fn_with_state(
+                "streamable_http",
+                scope_connection,
+            ))
             .layer(ServiceBuilder::new().layer(if self.config.enable_cors {
                 CorsLayer::permissive()
             } else {
@@ -1093,24 +1415,62 @@ impl Transport for StreamableHttpTransport {
         info!("  POST http://{}/messages - MCP messages (legacy)", addr);
         info!("  GET  http://{}/sse      - SSE stream (legacy)", addr);
 
+        let (shutdown_tx, shutdown_rx) = oneshot::channel::<()>();
         let server_handle = tokio::spawn(async move {
-            if let Err(e) = axum::serve(listener, app).await {
+            let shutdown_requested = async {
+                // A dropped sender isn't a shutdown request; `stop` handles that case
+                if shutdown_rx.await.is_err() {
+                    std::future::pending::<()>().await;
+                }
+            };
+            if let Err(e) = axum::serve(
+                listener,
+                app.into_make_service_with_connect_info::<SocketAddr>(),
+            )
+            .with_graceful_shutdown(shutdown_requested)
+            .await
+            {
                 tracing::error!("Server error: {}", e);
             }
         });
 
         self.server_handle = Some(server_handle);
+        self.shutdown_signal = Some(shutdown_tx);
         Ok(())
     }
 
+    fn set_disconnect_handler(&mut self, handler: DisconnectHandler) {
+        self.on_disconnect = Some(handler);
+    }
+
     async fn stop(&mut self) -> Result<(), TransportError> {
         if let Some(handle) = self.server_handle.take() {
             handle.abort();
         }
+        self.shutdown_signal = None;
         self.transport_handle = None;
         Ok(())
     }
 
+    async fn shutdown(&mut self, grace: Duration) -> Result<(), TransportError> {
+        info!(
+            "Shutting down Streamable HTTP transport, draining for up to {:?}",
+            grace
+        );
+        if let Some(signal) = self.shutdown_signal.take() {
+            let _ = signal.send(());
+        }
+        // Open SSE streams would hold a graceful shutdown forever, so only
+        // running handlers are waited on before the server is closed
+        if !self.active.drain(grace).await {
+            warn!(
+                "Grace period elapsed with {} requests still running, closing",
+                self.active.count()
+            );
+        }
+        self.stop().await
+    }
+
     async fn health_check(&self) -> Result<(), TransportError> {
         if self.server_handle.is_some() {
             Ok(())
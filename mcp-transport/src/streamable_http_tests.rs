@@ -5,7 +5,7 @@ mod tests {
     use super::super::streamable_http::*;
     use crate::{Transport, TransportError};
     use pulseengine_mcp_protocol::{Request, Response};
-    use serde_json::json;
+    use serde_json::{Value, json};
 
     // Mock handler for testing
     fn mock_handler(
@@ -1149,4 +1149,444 @@ mod tests {
         };
         assert_eq!(config.channel_capacity, 500);
     }
+
+    // Slow consumer tests
+
+    fn notification(n: u64) -> SseMessage {
+        SseMessage::Notification {
+            method: "notifications/progress".to_string(),
+            params: json!({ "n": n }),
+        }
+    }
+
+    /// A channel whose only consumer hasn't read anything while the session
+    /// produced `produced` events
+    fn slow_consumer(
+        capacity: usize,
+        produced: u64,
+    ) -> (
+        tokio::sync::broadcast::Sender<SseMessage>,
+        tokio::sync::broadcast::Receiver<SseMessage>,
+    ) {
+        let (sender, receiver) = tokio::sync::broadcast::channel(capacity);
+        for n in 0..produced {
+            sender.send(notification(n)).unwrap();
+        }
+        (sender, receiver)
+    }
+
+    #[test]
+    fn test_config_slow_consumer_policy_default() {
+        let config = StreamableHttpConfig::default();
+        assert_eq!(config.slow_consumer_policy, SlowConsumerPolicy::DropOldest);
+    }
+
+    #[tokio::test]
+    async fn test_slow_consumer_drop_oldest_keeps_streaming() {
+        let (_sender, mut receiver) = slow_consumer(4, 10);
+        let metrics = SlowConsumerMetrics::default();
+
+        let message = next_sse_message(
+            &mut receiver,
+            SlowConsumerPolicy::DropOldest,
+            &metrics,
+            "session",
+        )
+        .await;
+
+        // The 6 oldest events were dropped; streaming resumes at the oldest kept
+        match message {
+            Some(SseMessage::Notification { params, .. }) => assert_eq!(params["n"], 6),
+            other => panic!("expected a notification, got {other:?}"),
+        }
+        assert_eq!(metrics.lag_events(), 1);
+        assert_eq!(metrics.dropped_events(), 6);
+        assert_eq!(metrics.disconnected_consumers(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_slow_consumer_disconnected_by_policy() {
+        let (_sender, mut receiver) = slow_consumer(4, 10);
+        let metrics = SlowConsumerMetrics::default();
+
+        let message = next_sse_message(
+            &mut receiver,
+            SlowConsumerPolicy::Disconnect,
+            &metrics,
+            "session",
+        )
+        .await;
+
+        assert!(message.is_none());
+        assert_eq!(metrics.lag_events(), 1);
+        assert_eq!(metrics.disconnected_consumers(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_consumer_within_capacity_is_not_lagging() {
+        let (_sender, mut receiver) = slow_consumer(4, 4);
+        let metrics = SlowConsumerMetrics::default();
+
+        for _ in 0..4 {
+            let message = next_sse_message(
+                &mut receiver,
+                SlowConsumerPolicy::Disconnect,
+                &metrics,
+                "session",
+            )
+            .await;
+            assert!(message.is_some());
+        }
+        assert_eq!(metrics.lag_events(), 0);
+    }
+
+    // Stream lifetime tests
+
+    /// Open an SSE stream and read it until the server closes it, returning
+    /// the raw event stream text
+    async fn read_sse_until_closed(port: u16, last_event_id: Option<&str>) -> String {
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+        let mut stream = tokio::net::TcpStream::connect(("127.0.0.1", port))
+            .await
+            .unwrap();
+        let mut request = format!(
+            "GET /sse?sessionId=recycled HTTP/1.1\r\nHost: 127.0.0.1:{port}\r\n\
+             Accept: text/event-stream\r\nConnection: close\r\n"
+        );
+        if let Some(id) = last_event_id {
+            request.push_str(&format!("Last-Event-ID: {id}\r\n"));
+        }
+        request.push_str("\r\n");
+        stream.write_all(request.as_bytes()).await.unwrap();
+
+        let mut body = Vec::new();
+        tokio::time::timeout(
+            std::time::Duration::from_secs(5),
+            stream.read_to_end(&mut body),
+        )
+        .await
+        .expect("stream should be closed at its max lifetime")
+        .unwrap();
+        String::from_utf8_lossy(&body).into_owned()
+    }
+
+    #[test]
+    fn test_config_max_stream_lifetime_default() {
+        assert!(
+            StreamableHttpConfig::default()
+                .max_stream_lifetime
+                .is_none()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_stream_closed_at_max_lifetime_and_resumed() {
+        let port = 18213;
+        let config = StreamableHttpConfig {
+            port,
+            sse_retry_ms: 250,
+            max_stream_lifetime: Some(std::time::Duration::from_millis(200)),
+            ..Default::default()
+        };
+        let mut transport = StreamableHttpTransport::with_config(config);
+        transport.start(Box::new(mock_handler)).await.unwrap();
+        let handle = transport.handle().unwrap();
+
+        // The first stream is closed with a retry directive and a resumable
+        // event ID
+        let first = read_sse_until_closed(port, None).await;
+        assert!(first.contains("max_stream_lifetime"));
+        assert!(first.contains("retry: 250") || first.contains("retry:250"));
+        let last_event_id = first
+            .lines()
+            .filter_map(|line| line.strip_prefix("id:"))
+            .map(str::trim)
+            .next_back()
+            .unwrap()
+            .to_string();
+        assert!(last_event_id.starts_with("recycled:"));
+
+        // Sent while the client is reconnecting
+        handle
+            .send_notification(Some("recycled"), "notifications/progress", json!({"n": 1}))
+            .await
+            .unwrap();
+
+        let resumed = read_sse_until_closed(port, Some(&last_event_id)).await;
+        assert!(resumed.contains("notifications/progress"));
+        assert!(resumed.contains("max_stream_lifetime"));
+
+        transport.stop().await.ok();
+    }
+
+    #[tokio::test]
+    async fn test_invalid_utf8_body_rejected_with_field() {
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+        let port = 18214;
+        let mut transport = StreamableHttpTransport::new(port);
+        transport.start(Box::new(mock_handler)).await.unwrap();
+
+        let mut body = br#"{"jsonrpc":"2.0","id":"call-1","method":"tools/call","params":{"name":"echo","arguments":{"text":"x"#.to_vec();
+        body.extend_from_slice(&[0xFE, 0xFF]);
+        body.extend_from_slice(br#""}}}"#);
+
+        let mut stream = tokio::net::TcpStream::connect(("127.0.0.1", port))
+            .await
+            .unwrap();
+        let head = format!(
+            "POST /mcp HTTP/1.1\r\nHost: 127.0.0.1:{port}\r\nContent-Type: application/json\r\n\
+             Content-Length: {}\r\nConnection: close\r\n\r\n",
+            body.len()
+        );
+        stream.write_all(head.as_bytes()).await.unwrap();
+        stream.write_all(&body).await.unwrap();
+        let mut response = String::new();
+        stream.read_to_string(&mut response).await.unwrap();
+
+        assert!(response.starts_with("HTTP/1.1 400"));
+        let json: serde_json::Value =
+            serde_json::from_str(response.split("\r\n\r\n").nth(1).unwrap()).unwrap();
+        assert_eq!(json["id"], "call-1");
+        assert_eq!(json["error"]["code"], -32602);
+        assert_eq!(json["error"]["data"]["field"], "/params/arguments/text");
+
+        transport.stop().await.ok();
+    }
+
+    async fn post_mcp(port: u16, body: &str) -> String {
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+        let mut stream = tokio::net::TcpStream::connect(("127.0.0.1", port))
+            .await
+            .unwrap();
+        let request = format!(
+            "POST /mcp HTTP/1.1\r\nHost: 127.0.0.1:{port}\r\nContent-Type: application/json\r\n\
+             Accept: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+            body.len()
+        );
+        stream.write_all(request.as_bytes()).await.unwrap();
+        let mut response = String::new();
+        stream.read_to_string(&mut response).await.unwrap();
+        response
+    }
+
+    #[tokio::test]
+    async fn test_delete_ends_session_and_reports_disconnect() {
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+        let port = 18231;
+        let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel();
+        let mut transport = StreamableHttpTransport::new(port);
+        transport.set_disconnect_handler(std::sync::Arc::new(move |closed| {
+            let tx = tx.clone();
+            Box::pin(async move {
+                let _ = tx.send(closed);
+            })
+        }));
+        transport.start(Box::new(mock_handler)).await.unwrap();
+
+        let response = post_mcp(port, r#"{"jsonrpc":"2.0","id":1,"method":"ping"}"#).await;
+        let session_id = response
+            .lines()
+            .find_map(|line| line.strip_prefix("mcp-session-id: "))
+            .unwrap()
+            .to_string();
+
+        let delete = |session_id: String| async move {
+            let mut stream = tokio::net::TcpStream::connect(("127.0.0.1", port))
+                .await
+                .unwrap();
+            let request = format!(
+                "DELETE /mcp HTTP/1.1\r\nHost: 127.0.0.1:{port}\r\nMcp-Session-Id: {session_id}\r\n\
+                 Connection: close\r\n\r\n"
+            );
+            stream.write_all(request.as_bytes()).await.unwrap();
+            let mut response = String::new();
+            stream.read_to_string(&mut response).await.unwrap();
+            response
+        };
+
+        assert!(delete(session_id.clone()).await.starts_with("HTTP/1.1 200"));
+        let closed = rx.recv().await.unwrap();
+        assert_eq!(closed.connection_id, session_id);
+        assert_eq!(closed.session_id, Some(session_id.clone()));
+
+        // The session is gone
+        assert!(delete(session_id).await.starts_with("HTTP/1.1 404"));
+
+        transport.stop().await.ok();
+    }
+
+    #[tokio::test]
+    async fn test_tools_call_notification_gets_no_response() {
+        let port = 18217;
+        let mut transport = StreamableHttpTransport::new(port);
+        assert!(transport.config().strict_notifications);
+        transport.start(Box::new(mock_handler)).await.unwrap();
+
+        let response = post_mcp(
+            port,
+            r#"{"jsonrpc":"2.0","method":"tools/call","params":{"name":"echo","arguments":{}}}"#,
+        )
+        .await;
+
+        assert!(response.starts_with("HTTP/1.1 202"), "{response}");
+        let (_, body) = response.split_once("\r\n\r\n").unwrap();
+        assert!(body.is_empty(), "notification was answered: {body}");
+
+        // The same call with an id is answered as usual
+        let response = post_mcp(
+            port,
+            r#"{"jsonrpc":"2.0","id":7,"method":"tools/call","params":{"name":"echo","arguments":{}}}"#,
+        )
+        .await;
+        assert!(response.starts_with("HTTP/1.1 200"), "{response}");
+        assert!(response.contains(r#""id":7"#));
+
+        transport.stop().await.ok();
+    }
+
+    #[tokio::test]
+    async fn test_lenient_notifications_keep_handler_response() {
+        let port = 18218;
+        let mut transport = StreamableHttpTransport::with_config(StreamableHttpConfig {
+            port,
+            strict_notifications: false,
+            ..Default::default()
+        });
+        transport.start(Box::new(mock_handler)).await.unwrap();
+
+        let response = post_mcp(
+            port,
+            r#"{"jsonrpc":"2.0","method":"tools/call","params":{"name":"echo","arguments":{}}}"#,
+        )
+        .await;
+        assert!(response.starts_with("HTTP/1.1 200"), "{response}");
+        let (_, body) = response.split_once("\r\n\r\n").unwrap();
+        let body: Value = serde_json::from_str(body).unwrap();
+        assert_eq!(body["result"]["echo"], "tools/call");
+
+        transport.stop().await.ok();
+    }
+
+    #[tokio::test]
+    async fn test_notification_gets_no_response_over_sse() {
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+        let port = 18221;
+        let mut transport = StreamableHttpTransport::new(port);
+        transport.start(Box::new(mock_handler)).await.unwrap();
+
+        let body =
+            r#"{"jsonrpc":"2.0","method":"tools/call","params":{"name":"echo","arguments":{}}}"#;
+        let mut stream = tokio::net::TcpStream::connect(("127.0.0.1", port))
+            .await
+            .unwrap();
+        let request = format!(
+            "POST /mcp HTTP/1.1\r\nHost: 127.0.0.1:{port}\r\nContent-Type: application/json\r\n\
+             Accept: application/json, text/event-stream\r\nContent-Length: {}\r\n\
+             Connection: close\r\n\r\n{body}",
+            body.len()
+        );
+        stream.write_all(request.as_bytes()).await.unwrap();
+        let mut response = String::new();
+        stream.read_to_string(&mut response).await.unwrap();
+
+        assert!(response.starts_with("HTTP/1.1 202"), "{response}");
+        assert!(!response.contains("text/event-stream"), "{response}");
+        let (_, body) = response.split_once("\r\n\r\n").unwrap();
+        assert!(body.is_empty(), "notification was answered: {body}");
+
+        transport.stop().await.ok();
+    }
+
+    // Oversized SSE event tests
+
+    /// Join the `data:` lines of each SSE event the way an SSE client does
+    fn reassemble_sse_events(raw: &str) -> Vec<String> {
+        raw.split("\n\n")
+            .map(|event| {
+                event
+                    .lines()
+                    .filter_map(|line| line.strip_prefix("data:"))
+                    .map(|data| data.strip_prefix(' ').unwrap_or(data))
+                    .collect::<Vec<_>>()
+                    .join("\n")
+            })
+            .filter(|data| !data.is_empty())
+            .collect()
+    }
+
+    #[test]
+    fn test_wrap_json_lines_round_trips() {
+        let value = json!({
+            "items": (0..200).map(|i| json!({"id": i, "label": format!("item, \"{i}\": [x]")})).collect::<Vec<_>>(),
+            "blob": "b".repeat(300),
+        });
+        let json = value.to_string();
+        let wrapped = wrap_json_lines(&json, 64);
+
+        assert!(wrapped.lines().count() > 1);
+        for line in wrapped.lines() {
+            // Only the unbreakable 300-byte string may exceed the limit
+            assert!(
+                line.len() <= 64 || line.contains(&"b".repeat(300)),
+                "{line}"
+            );
+        }
+        let reparsed: serde_json::Value = serde_json::from_str(&wrapped).unwrap();
+        assert_eq!(reparsed, value);
+
+        assert_eq!(wrap_json_lines(r#"{"a":1}"#, 64), r#"{"a":1}"#);
+    }
+
+    #[tokio::test]
+    async fn test_oversized_notification_framed_across_data_lines() {
+        let port = 18219;
+        let config = StreamableHttpConfig {
+            port,
+            max_stream_lifetime: Some(std::time::Duration::from_millis(300)),
+            max_sse_line_bytes: Some(256),
+            ..Default::default()
+        };
+        assert_eq!(
+            StreamableHttpConfig::default().max_sse_line_bytes,
+            Some(16 * 1024)
+        );
+        let mut transport = StreamableHttpTransport::with_config(config);
+        transport.start(Box::new(mock_handler)).await.unwrap();
+        let handle = transport.handle().unwrap();
+
+        let params = json!({
+            "level": "info",
+            "data": (0..500).map(|i| format!("log line {i}")).collect::<Vec<_>>(),
+        });
+        let reader = tokio::spawn(read_sse_until_closed(port, None));
+        tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+        handle
+            .send_notification(Some("recycled"), "notifications/message", params.clone())
+            .await
+            .unwrap();
+        let raw = reader.await.unwrap();
+
+        let (_, body) = raw.split_once("\r\n\r\n").unwrap();
+        let data_lines: Vec<&str> = body.lines().filter(|l| l.starts_with("data:")).collect();
+        assert!(data_lines.len() > 10, "expected multi-line framing");
+        assert!(
+            data_lines
+                .iter()
+                .all(|line| line.len() <= 256 + "data: ".len())
+        );
+
+        let notification = reassemble_sse_events(body)
+            .into_iter()
+            .filter_map(|data| serde_json::from_str::<serde_json::Value>(&data).ok())
+            .find(|message| message["method"] == "notifications/message")
+            .expect("notification delivered");
+        assert_eq!(notification["params"], params);
+
+        transport.stop().await.ok();
+    }
 }
@@ -0,0 +1,340 @@
+//! Unix domain socket transport
+//!
+//! Serves MCP over a local socket file for co-located clients. Messages are
+//! framed exactly as on stdio: one JSON-RPC message (or batch) per line.
+//! Access is controlled by the socket file's mode.
+
+use crate::{
+    ConnectionInfo, DisconnectHandler, RequestHandler, Transport, TransportError,
+    batch::{BatchConfig, JsonRpcMessage, create_error_response, process_batch_with},
+    drain::ActiveHandlers,
+    notify_disconnect,
+    stdio::StdioConfig,
+    validation::{decode_message_bytes, extract_id_from_malformed, validate_message_string},
+    with_connection, with_session,
+};
+use async_trait::async_trait;
+use pulseengine_mcp_protocol::{Error as McpError, Response};
+use std::os::unix::fs::{FileTypeExt, PermissionsExt};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+use tokio::net::{UnixListener, UnixStream};
+use tokio::task::{JoinHandle, JoinSet};
+use tracing::{debug, error, info, warn};
+use uuid::Uuid;
+
+/// Unix domain socket transport for MCP protocol
+///
+/// Each accepted connection is served like a stdio session. Stopping the
+/// transport closes every connection and removes the socket file. Each
+/// connection is its own session, identified by a random id.
+pub struct UnixSocketTransport {
+    path: PathBuf,
+    mode: Option<u32>,
+    config: StdioConfig,
+    running: Arc<AtomicBool>,
+    server_handle: Option<JoinHandle<()>>,
+    active: ActiveHandlers,
+    /// Told as each client disconnects
+    on_disconnect: Option<DisconnectHandler>,
+}
+
+impl std::fmt::Debug for UnixSocketTransport {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("UnixSocketTransport")
+            .field("path", &self.path)
+            .field("mode", &self.mode)
+            .field("config", &self.config)
+            .field("running", &self.running)
+            .field("server_handle", &self.server_handle)
+            .field("active", &self.active)
+            .finish_non_exhaustive()
+    }
+}
+
+impl UnixSocketTransport {
+    /// Create a transport listening on `path`
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self {
+            path: path.into(),
+            mode: None,
+            config: StdioConfig::default(),
+            running: Arc::new(AtomicBool::new(false)),
+            server_handle: None,
+            active: ActiveHandlers::new(),
+            on_disconnect: None,
+        }
+    }
+
+    /// Set the socket file's permission bits (e.g. `0o600`) after binding
+    pub fn with_mode(mut self, mode: Option<u32>) -> Self {
+        self.mode = mode;
+        self
+    }
+
+    /// Use custom message size and validation settings
+    pub fn with_config(mut self, config: StdioConfig) -> Self {
+        self.config = config;
+        self
+    }
+
+    /// Path of the socket file
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    pub fn mode(&self) -> Option<u32> {
+        self.mode
+    }
+
+    pub fn config(&self) -> &StdioConfig {
+        &self.config
+    }
+
+    /// Check if the transport is accepting connections
+    pub fn is_running(&self) -> bool {
+        self.running.load(Ordering::Relaxed)
+    }
+
+    /// Remove the socket file, leaving anything that isn't a socket alone
+    fn remove_socket_file(&self) -> Result<(), TransportError> {
+        match std::fs::symlink_metadata(&self.path) {
+            Ok(metadata) if metadata.file_type().is_socket() => std::fs::remove_file(&self.path)
+                .map_err(|e| {
+                    TransportError::Connection(format!(
+                        "Failed to remove socket {}: {e}",
+                        self.path.display()
+                    ))
+                }),
+            Ok(_) => Err(TransportError::Config(format!(
+                "{} exists and is not a socket",
+                self.path.display()
+            ))),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(()),
+            Err(e) => Err(TransportError::Connection(format!(
+                "Failed to inspect {}: {e}",
+                self.path.display()
+            ))),
+        }
+    }
+}
+
+/// Serve one connection until the client disconnects or the transport stops
+async fn serve_connection(
+    stream: UnixStream,
+    handler: Arc<RequestHandler>,
+    config: StdioConfig,
+    running: Arc<AtomicBool>,
+) {
+    let (reader, mut writer) = stream.into_split();
+    let mut reader = BufReader::new(reader);
+    let mut line = Vec::new();
+
+    while running.load(Ordering::Relaxed) {
+        line.clear();
+        match reader.read_until(b'\n', &mut line).await {
+            Ok(0) => {
+                debug!("Unix socket client disconnected");
+                break;
+            }
+            Ok(_) => {
+                let reply = match decode_message_bytes(&line, config.invalid_utf8) {
+                    Ok(text) => {
+                        let trimmed = text.trim_end_matches(['\n', '\r']);
+                        if trimmed.is_empty() {
+                            continue;
+                        }
+                        process_line(trimmed, &handler, &config).await
+                    }
+                    Err(e) => {
+                        warn!("Rejecting message: {}", e);
+                        let request_id = e.request_id.clone();
+                        serialize_response(&create_error_response(e.into(), request_id))
+                    }
+                };
+                let Some(reply) = reply else {
+                    continue;
+                };
+                if config.validate_messages
+                    && let Err(e) = validate_message_string(&reply, Some(config.max_message_size))
+                {
+                    error!("Outgoing message validation failed: {}", e);
+                    continue;
+                }
+                if let Err(e) = writer.write_all(format!("{reply}\n").as_bytes()).await {
+                    error!("Failed to write to unix socket: {}", e);
+                    break;
+                }
+            }
+            Err(e) => {
+                error!("Failed to read from unix socket: {}", e);
+                break;
+            }
+        }
+    }
+}
+
+/// Handle one line, returning the line to send back if any
+async fn process_line(
+    line: &str,
+    handler: &RequestHandler,
+    config: &StdioConfig,
+) -> Option<String> {
+    if config.validate_messages
+        && let Err(e) = validate_message_string(line, Some(config.max_message_size))
+    {
+        warn!("Message validation failed: {}", e);
+        return serialize_response(&create_error_response(
+            McpError::invalid_request(format!("Message validation failed: {e}")),
+            extract_id_from_malformed(line),
+        ));
+    }
+
+    debug!("Processing message: {}", line);
+
+    let message = match JsonRpcMessage::parse(line) {
+        Ok(message) => message,
+        Err(e) => {
+            error!("Failed to parse JSON: {}", e);
+            return serialize_response(&create_error_response(
+                McpError::parse_error(format!("Invalid JSON: {e}")),
+                extract_id_from_malformed(line),
+            ));
+        }
+    };
+
+    if let Err(e) = message.validate() {
+        warn!("JSON-RPC validation failed: {}", e);
+        return serialize_response(&create_error_response(
+            McpError::invalid_request(format!("Invalid JSON-RPC: {e}")),
+            None,
+        ));
+    }
+
+    match process_batch_with(message, handler, &config.batch).await {
+        Ok(Some(response)) => match response.to_string() {
+            Ok(json) => Some(json),
+            Err(e) => {
+                error!("Failed to serialize response: {}", e);
+                None
+            }
+        },
+        Ok(None) => {
+            debug!("No response needed for message");
+            None
+        }
+        Err(e) => {
+            error!("Failed to process message: {}", e);
+            serialize_response(&create_error_response(
+                McpError::internal_error(format!("Processing failed: {e}")),
+                None,
+            ))
+        }
+    }
+}
+
+fn serialize_response(response: &Response) -> Option<String> {
+    serde_json::to_string(response)
+        .map_err(|e| error!("Failed to serialize response: {}", e))
+        .ok()
+}
+
+#[async_trait]
+impl Transport for UnixSocketTransport {
+    async fn start(&mut self, handler: RequestHandler) -> Result<(), TransportError> {
+        info!("Starting unix socket transport on {}", self.path.display());
+
+        // A socket left behind by an unclean exit would make bind fail
+        self.remove_socket_file()?;
+        let listener = UnixListener::bind(&self.path).map_err(|e| {
+            TransportError::Connection(format!("Failed to bind {}: {e}", self.path.display()))
+        })?;
+        if let Some(mode) = self.mode {
+            std::fs::set_permissions(&self.path, std::fs::Permissions::from_mode(mode)).map_err(
+                |e| {
+                    TransportError::Config(format!(
+                        "Failed to set mode {mode:o} on {}: {e}",
+                        self.path.display()
+                    ))
+                },
+            )?;
+        }
+
+        self.running.store(true, Ordering::Relaxed);
+        let handler = Arc::new(self.active.wrap(handler));
+        let config = self.config.clone();
+        let running = self.running.clone();
+        let on_disconnect = self.on_disconnect.clone();
+        self.server_handle = Some(tokio::spawn(async move {
+            // Dropping the set when this task is aborted closes every connection
+            let mut connections = JoinSet::new();
+            loop {
+                tokio::select! {
+                    accepted = listener.accept() => match accepted {
+                        Ok((stream, _)) => {
+                            debug!("Accepted unix socket connection");
+                            let id = Uuid::new_v4().to_string();
+                            let connection = ConnectionInfo::new(id.clone(), "unix");
+                            let served = serve_connection(
+                                stream,
+                                handler.clone(),
+                                config.clone(),
+                                running.clone(),
+                            );
+                            let on_disconnect = on_disconnect.clone();
+                            connections.spawn(async move {
+                                with_connection(connection, with_session(id.clone(), served)).await;
+                                notify_disconnect(on_disconnect.as_ref(), id.clone(), Some(id)).await;
+                            });
+                        }
+                        Err(e) => error!("Failed to accept unix socket connection: {}", e),
+                    },
+                    Some(_) = connections.join_next(), if !connections.is_empty() => {}
+                }
+            }
+        }));
+
+        Ok(())
+    }
+
+    fn set_batch_config(&mut self, config: BatchConfig) {
+        self.config.batch = config;
+    }
+
+    fn set_disconnect_handler(&mut self, handler: DisconnectHandler) {
+        self.on_disconnect = Some(handler);
+    }
+
+    async fn stop(&mut self) -> Result<(), TransportError> {
+        info!("Stopping unix socket transport");
+        self.running.store(false, Ordering::Relaxed);
+        if let Some(handle) = self.server_handle.take() {
+            handle.abort();
+        }
+        self.remove_socket_file()
+    }
+
+    async fn shutdown(&mut self, grace: Duration) -> Result<(), TransportError> {
+        // Connections stop reading new lines; let the ones in progress finish
+        self.running.store(false, Ordering::Relaxed);
+        if !self.active.drain(grace).await {
+            warn!(
+                "Unix socket transport stopped with {} requests still running",
+                self.active.count()
+            );
+        }
+        self.stop().await
+    }
+
+    async fn health_check(&self) -> Result<(), TransportError> {
+        match &self.server_handle {
+            Some(handle) if !handle.is_finished() => Ok(()),
+            _ => Err(TransportError::Connection(
+                "Transport not running".to_string(),
+            )),
+        }
+    }
+}
@@ -0,0 +1,161 @@
+//! Tests for the Unix domain socket transport
+
+#[cfg(test)]
+mod tests {
+    use super::super::unix::*;
+    use crate::{RequestHandler, Transport, TransportConfig, create_transport};
+    use pulseengine_mcp_protocol::{Request, Response};
+    use serde_json::{Value, json};
+    use std::os::unix::fs::{FileTypeExt, PermissionsExt};
+    use std::path::PathBuf;
+    use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+    use tokio::net::UnixStream;
+
+    fn socket_path() -> PathBuf {
+        std::env::temp_dir().join(format!("mcp-{}.sock", uuid::Uuid::new_v4().simple()))
+    }
+
+    fn echo_handler() -> RequestHandler {
+        Box::new(|request: Request| {
+            Box::pin(async move {
+                Response {
+                    jsonrpc: "2.0".to_string(),
+                    id: request.id,
+                    result: Some(json!({"echo": request.method})),
+                    error: None,
+                }
+            })
+        })
+    }
+
+    async fn exchange(stream: &mut BufReader<UnixStream>, line: &str) -> Value {
+        stream
+            .get_mut()
+            .write_all(format!("{line}\n").as_bytes())
+            .await
+            .unwrap();
+        let mut reply = String::new();
+        stream.read_line(&mut reply).await.unwrap();
+        assert!(reply.ends_with('\n'));
+        serde_json::from_str(&reply).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_newline_delimited_exchange() {
+        let path = socket_path();
+        let mut transport = UnixSocketTransport::new(&path);
+        transport.start(echo_handler()).await.unwrap();
+        assert!(transport.health_check().await.is_ok());
+
+        let mut stream = BufReader::new(UnixStream::connect(&path).await.unwrap());
+        let response = exchange(
+            &mut stream,
+            r#"{"jsonrpc":"2.0","id":1,"method":"tools/list"}"#,
+        )
+        .await;
+        assert_eq!(response["id"], 1);
+        assert_eq!(response["result"]["echo"], "tools/list");
+
+        // Batches come back as a single line; notifications get no entry
+        let response = exchange(
+            &mut stream,
+            r#"[{"jsonrpc":"2.0","id":2,"method":"a"},{"jsonrpc":"2.0","method":"notifications/b"}]"#,
+        )
+        .await;
+        assert_eq!(response.as_array().unwrap().len(), 1);
+        assert_eq!(response[0]["result"]["echo"], "a");
+
+        let response = exchange(&mut stream, r#"{"jsonrpc":"2.0","id":3,"method""#).await;
+        assert_eq!(response["error"]["code"], -32700);
+
+        transport.stop().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_disconnect_reported_with_connection_session() {
+        let path = socket_path();
+        let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel();
+        let mut transport = UnixSocketTransport::new(&path);
+        transport.set_disconnect_handler(std::sync::Arc::new(move |closed| {
+            let tx = tx.clone();
+            Box::pin(async move {
+                let _ = tx.send(closed);
+            })
+        }));
+        let session_handler: RequestHandler = Box::new(|request: Request| {
+            Box::pin(async move {
+                Response {
+                    jsonrpc: "2.0".to_string(),
+                    id: request.id,
+                    result: Some(json!({ "session": crate::try_current_session_id() })),
+                    error: None,
+                }
+            })
+        });
+        transport.start(session_handler).await.unwrap();
+
+        let mut stream = BufReader::new(UnixStream::connect(&path).await.unwrap());
+        let response = exchange(&mut stream, r#"{"jsonrpc":"2.0","id":1,"method":"ping"}"#).await;
+        let session = response["result"]["session"].as_str().unwrap().to_string();
+        drop(stream);
+
+        let closed = tokio::time::timeout(std::time::Duration::from_secs(5), rx.recv())
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(closed.connection_id, session);
+        assert_eq!(closed.session_id, Some(session));
+
+        transport.stop().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_stop_removes_socket_file_and_applies_mode() {
+        let path = socket_path();
+        let mut transport = UnixSocketTransport::new(&path).with_mode(Some(0o600));
+        transport.start(echo_handler()).await.unwrap();
+
+        let metadata = std::fs::metadata(&path).unwrap();
+        assert!(metadata.file_type().is_socket());
+        assert_eq!(metadata.permissions().mode() & 0o777, 0o600);
+
+        transport.stop().await.unwrap();
+        assert!(!path.exists());
+        assert!(transport.health_check().await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_replaces_stale_socket_but_not_other_files() {
+        let path = socket_path();
+        let stale = std::os::unix::net::UnixListener::bind(&path).unwrap();
+        drop(stale);
+        assert!(path.exists());
+
+        let mut transport = UnixSocketTransport::new(&path);
+        transport.start(echo_handler()).await.unwrap();
+        transport.stop().await.unwrap();
+
+        std::fs::write(&path, "not a socket").unwrap();
+        let mut transport = UnixSocketTransport::new(&path);
+        assert!(transport.start(echo_handler()).await.is_err());
+        assert!(path.exists());
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_create_transport_dispatches_unix_socket() {
+        let path = socket_path();
+        let mut transport = create_transport(TransportConfig::UnixSocket {
+            path: path.clone(),
+            mode: Some(0o660),
+        })
+        .unwrap();
+        transport.start(echo_handler()).await.unwrap();
+        assert_eq!(
+            std::fs::metadata(&path).unwrap().permissions().mode() & 0o777,
+            0o660
+        );
+        transport.stop().await.unwrap();
+        assert!(!path.exists());
+    }
+}
@@ -111,6 +111,118 @@ pub fn validate_jsonrpc_message(value: &Value) -> Result<MessageType, Validation
     }
 }
 
+/// How transports treat message bytes that aren't valid UTF-8
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum InvalidUtf8Policy {
+    /// Reject the message with an `invalid_params` error naming the field
+    /// that holds the invalid bytes
+    #[default]
+    Reject,
+    /// Replace invalid sequences with U+FFFD and process the message
+    Replace,
+}
+
+/// A message contained bytes that aren't valid UTF-8
+#[derive(Debug, Clone, PartialEq)]
+pub struct InvalidUtf8Error {
+    /// Byte offset of the first invalid sequence in the message
+    pub offset: usize,
+    /// JSON pointer of the string field containing that sequence, when it is
+    /// inside one
+    pub field: Option<String>,
+    /// ID of the request, when it could be recovered
+    pub request_id: Option<pulseengine_mcp_protocol::NumberOrString>,
+}
+
+impl std::fmt::Display for InvalidUtf8Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match &self.field {
+            Some(field) => write!(
+                f,
+                "Invalid UTF-8 in string field '{field}' at byte {}",
+                self.offset
+            ),
+            None => write!(f, "Message is not valid UTF-8 at byte {}", self.offset),
+        }
+    }
+}
+
+impl std::error::Error for InvalidUtf8Error {}
+
+impl From<InvalidUtf8Error> for pulseengine_mcp_protocol::Error {
+    fn from(err: InvalidUtf8Error) -> Self {
+        Self::with_data(
+            pulseengine_mcp_protocol::ErrorCode::InvalidParams,
+            err.to_string(),
+            serde_json::json!({ "field": err.field, "offset": err.offset }),
+        )
+    }
+}
+
+/// Decode raw message bytes as UTF-8 according to `policy`
+///
+/// Valid messages are borrowed as-is. When rejecting, the error locates the
+/// first invalid sequence so clients get a targeted diagnostic instead of a
+/// generic parse failure.
+pub fn decode_message_bytes(
+    bytes: &[u8],
+    policy: InvalidUtf8Policy,
+) -> Result<std::borrow::Cow<'_, str>, InvalidUtf8Error> {
+    let error = match std::str::from_utf8(bytes) {
+        Ok(text) => return Ok(std::borrow::Cow::Borrowed(text)),
+        Err(error) => error,
+    };
+    if policy == InvalidUtf8Policy::Replace {
+        return Ok(String::from_utf8_lossy(bytes));
+    }
+
+    // Stand in a marker for the invalid sequence, then find which field of
+    // the parsed message holds it
+    const MARKER: &str = "\u{E000}invalid-utf8\u{E000}";
+    let offset = error.valid_up_to();
+    let resume_at = offset + error.error_len().unwrap_or(bytes.len() - offset);
+    let mut text = String::from_utf8_lossy(&bytes[..offset]).into_owned();
+    text.push_str(MARKER);
+    text.push_str(&String::from_utf8_lossy(&bytes[resume_at..]));
+
+    let value = serde_json::from_str::<Value>(&text).ok();
+    Err(InvalidUtf8Error {
+        offset,
+        field: value
+            .as_ref()
+            .and_then(|value| find_marker(value, MARKER, String::new())),
+        request_id: value
+            .as_ref()
+            .and_then(|value| value.get("id"))
+            .and_then(|id| pulseengine_mcp_protocol::NumberOrString::from_json_value(id.clone())),
+    })
+}
+
+/// JSON pointer of the first string or key containing `marker`
+fn find_marker(value: &Value, marker: &str, path: String) -> Option<String> {
+    match value {
+        Value::String(text) if text.contains(marker) => Some(path),
+        Value::Object(map) => map.iter().find_map(|(key, value)| {
+            let child = format!(
+                "{path}/{}",
+                key.replace(marker, "\u{FFFD}")
+                    .replace('~', "~0")
+                    .replace('/', "~1")
+            );
+            if key.contains(marker) {
+                Some(child)
+            } else {
+                find_marker(value, marker, child)
+            }
+        }),
+        Value::Array(items) => items
+            .iter()
+            .enumerate()
+            .find_map(|(index, item)| find_marker(item, marker, format!("{path}/{index}"))),
+        _ => None,
+    }
+}
+
 /// Attempts to extract ID from a malformed JSON request for error responses
 pub fn extract_id_from_malformed(text: &str) -> Option<pulseengine_mcp_protocol::NumberOrString> {
     use pulseengine_mcp_protocol::NumberOrString;
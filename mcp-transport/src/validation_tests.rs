@@ -3,8 +3,8 @@
 #[cfg(test)]
 mod tests {
     use crate::validation::{
-        extract_id_from_malformed, validate_json_rpc_batch, validate_json_rpc_message,
-        validate_message_string,
+        InvalidUtf8Policy, decode_message_bytes, extract_id_from_malformed,
+        validate_json_rpc_batch, validate_json_rpc_message, validate_message_string,
     };
     use serde_json::json;
 
@@ -443,4 +443,63 @@ mod tests {
         let batch_error = validate_json_rpc_batch(empty_batch).unwrap_err();
         assert!(batch_error.to_string().contains("Empty batch not allowed"));
     }
+
+    /// A `tools/call` request whose `name` argument holds invalid UTF-8
+    fn tool_call_with_invalid_utf8() -> Vec<u8> {
+        let mut bytes = br#"{"jsonrpc":"2.0","id":7,"method":"tools/call","params":{"name":"lookup","arguments":{"query":"ok","name":"ab"#.to_vec();
+        bytes.extend_from_slice(&[0xC3, 0x28, 0xFF]);
+        bytes.extend_from_slice(br#"cd"}}}"#);
+        bytes
+    }
+
+    #[test]
+    fn test_invalid_utf8_rejected_with_field() {
+        let bytes = tool_call_with_invalid_utf8();
+
+        let error = decode_message_bytes(&bytes, InvalidUtf8Policy::Reject).unwrap_err();
+        assert_eq!(error.field.as_deref(), Some("/params/arguments/name"));
+        assert_eq!(error.offset, bytes.iter().position(|b| *b == 0xC3).unwrap());
+        assert_eq!(
+            error.request_id,
+            Some(pulseengine_mcp_protocol::NumberOrString::Number(7))
+        );
+
+        let error: pulseengine_mcp_protocol::Error = error.into();
+        assert_eq!(
+            error.code,
+            pulseengine_mcp_protocol::ErrorCode::InvalidParams
+        );
+        assert!(error.message.contains("'/params/arguments/name'"));
+        assert_eq!(error.data.unwrap()["field"], "/params/arguments/name");
+    }
+
+    #[test]
+    fn test_invalid_utf8_outside_strings_has_no_field() {
+        let mut bytes = br#"{"jsonrpc":"2.0","id":1,"method":"ping"}"#.to_vec();
+        bytes.push(0xFF);
+
+        let error = decode_message_bytes(&bytes, InvalidUtf8Policy::Reject).unwrap_err();
+        assert!(error.field.is_none());
+        assert!(error.to_string().contains("not valid UTF-8"));
+    }
+
+    #[test]
+    fn test_invalid_utf8_replaced_by_policy() {
+        let bytes = tool_call_with_invalid_utf8();
+
+        let text = decode_message_bytes(&bytes, InvalidUtf8Policy::Replace).unwrap();
+        let value: serde_json::Value = serde_json::from_str(&text).unwrap();
+        assert!(
+            value["params"]["arguments"]["name"]
+                .as_str()
+                .unwrap()
+                .contains('\u{FFFD}')
+        );
+
+        let valid = br#"{"jsonrpc":"2.0","id":1,"method":"ping"}"#;
+        assert!(matches!(
+            decode_message_bytes(valid, InvalidUtf8Policy::Reject).unwrap(),
+            std::borrow::Cow::Borrowed(_)
+        ));
+    }
 }
@@ -1,24 +1,472 @@
 //! WebSocket transport implementation (stub)
+//!
+//! Listening isn't implemented yet, but [`serve_connection`] already drives an
+//! accepted connection: it dispatches JSON-RPC text frames to the handler and,
+//! when configured, keeps the connection alive with ping frames and closes it
+//! once the peer stops answering or goes idle. Connections served with
+//! [`serve_connection_until`] are closed with a "going away" close frame when
+//! the transport is stopped.
+//!
+//! Compression is negotiated once, in the handshake: [`accept_connection`]
+//! picks an algorithm from the client's [`COMPRESSION_HEADER`] and names it in
+//! the handshake response. [`serve_negotiated_connection`] then sends every
+//! message as a binary frame compressed with it, and decompresses binary
+//! frames from the client.
+//!
+//! Embedders serving connections themselves should run them inside
+//! [`with_connection`](crate::with_connection) with the handshake headers and
+//! peer address, so the server can authenticate the caller, and inside
+//! [`with_session`](crate::with_session) so each connection negotiates its
+//! own protocol session.
 
-use crate::{RequestHandler, Transport, TransportError};
+use crate::{
+    RequestHandler, Transport, TransportError,
+    batch::{BatchConfig, JsonRpcMessage, create_error_response, process_batch_with},
+    compression::{CompressionAlgorithm, CompressionConfig, compress, decompress},
+};
 use async_trait::async_trait;
+use futures_util::stream::FuturesUnordered;
+use futures_util::{SinkExt, StreamExt};
+use std::time::Duration;
+use tokio::io::{AsyncRead, AsyncWrite};
+use tokio::sync::watch;
+use tokio::time::{Instant, MissedTickBehavior};
+use tokio_tungstenite::WebSocketStream;
+use tokio_tungstenite::tungstenite::Message;
+use tokio_tungstenite::tungstenite::handshake::server::{Request, Response};
+use tokio_tungstenite::tungstenite::http::HeaderValue;
+use tokio_tungstenite::tungstenite::protocol::CloseFrame;
+use tokio_tungstenite::tungstenite::protocol::frame::coding::CloseCode;
+use tracing::{debug, warn};
+
+/// How long to wait for the close handshake of a connection being dropped
+const CLOSE_TIMEOUT: Duration = Duration::from_secs(1);
+
+/// Largest decompressed message accepted, matching tungstenite's message limit
+const MAX_MESSAGE_SIZE: usize = 64 << 20;
+
+/// Handshake header negotiating the connection's compression
+///
+/// The client lists the algorithms it accepts in `Accept-Encoding` syntax;
+/// the handshake response names the one chosen for the connection.
+pub const COMPRESSION_HEADER: &str = "mcp-compression";
+
+/// Configuration for WebSocket transport
+#[derive(Debug, Clone)]
+pub struct WebSocketConfig {
+    /// Interval between keepalive pings; `None` disables pings
+    pub keepalive_interval: Option<Duration>,
+    /// Close connections that send no messages for this long; pongs don't count
+    pub idle_timeout: Option<Duration>,
+    /// Unanswered pings tolerated before the connection is closed
+    pub max_missed_pongs: u32,
+    /// Algorithms a connection may negotiate in its handshake; `min_size`
+    /// doesn't apply, every message is compressed once negotiated
+    pub compression: CompressionConfig,
+    /// How JSON-RPC batches are processed
+    pub batch: BatchConfig,
+    /// Messages of one connection handled at once; further messages wait
+    /// unread until one finishes. Values below 1 are treated as 1.
+    pub max_concurrent_requests: usize,
+}
+
+impl Default for WebSocketConfig {
+    fn default() -> Self {
+        Self {
+            keepalive_interval: None,
+            idle_timeout: None,
+            max_missed_pongs: 2,
+            compression: CompressionConfig::disabled(),
+            batch: BatchConfig::default(),
+            max_concurrent_requests: 32,
+        }
+    }
+}
 
 /// WebSocket transport for MCP protocol (stub)
 #[derive(Debug)]
 pub struct WebSocketTransport {
     #[allow(dead_code)]
     port: u16,
+    config: WebSocketConfig,
+    /// Flipped to `true` by `stop` to close served connections
+    shutdown: watch::Sender<bool>,
 }
 
 impl WebSocketTransport {
     pub fn new(port: u16) -> Self {
-        Self { port }
+        Self::with_config(port, WebSocketConfig::default())
+    }
+
+    pub fn with_config(port: u16, config: WebSocketConfig) -> Self {
+        Self {
+            port,
+            config,
+            shutdown: watch::channel(false).0,
+        }
     }
 
     /// Get the port this transport is configured for
     pub fn port(&self) -> u16 {
         self.port
     }
+
+    pub fn config(&self) -> &WebSocketConfig {
+        &self.config
+    }
+
+    /// Signal to pass to [`serve_connection_until`] so `stop` closes the
+    /// connection
+    pub fn shutdown_signal(&self) -> watch::Receiver<bool> {
+        self.shutdown.subscribe()
+    }
+}
+
+/// Run the server side of the WebSocket handshake on `socket`, negotiating
+/// the connection's compression
+pub async fn accept_connection<S>(
+    socket: S,
+    config: &WebSocketConfig,
+) -> Result<(WebSocketStream<S>, Option<CompressionAlgorithm>), TransportError>
+where
+    S: AsyncRead + AsyncWrite + Unpin,
+{
+    let mut negotiated = None;
+    let stream = tokio_tungstenite::accept_hdr_async(socket, |request: &Request, response| {
+        let (response, algorithm) = negotiate_compression(config, request, response);
+        negotiated = algorithm;
+        Ok(response)
+    })
+    .await
+    .map_err(|e| TransportError::Connection(format!("WebSocket handshake failed: {e}")))?;
+    Ok((stream, negotiated))
+}
+
+/// Pick the connection's compression from the handshake request and name it
+/// in the handshake response
+pub fn negotiate_compression(
+    config: &WebSocketConfig,
+    request: &Request,
+    mut response: Response,
+) -> (Response, Option<CompressionAlgorithm>) {
+    let algorithm = request
+        .headers()
+        .get(COMPRESSION_HEADER)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|accepted| config.compression.negotiate(accepted));
+    if let Some(algorithm) = algorithm {
+        debug!(
+            "WebSocket connection compressed with {}",
+            algorithm.encoding()
+        );
+        response.headers_mut().insert(
+            COMPRESSION_HEADER,
+            HeaderValue::from_static(algorithm.encoding()),
+        );
+    }
+    (response, algorithm)
+}
+
+/// Serve one accepted WebSocket connection until it closes
+///
+/// Returns `Ok(())` when the peer closes the connection, and
+/// `TransportError::Connection` when it is closed for missing pongs,
+/// exceeding the idle timeout or a socket error, so the caller can clean up
+/// the connection's session.
+pub async fn serve_connection<S>(
+    stream: WebSocketStream<S>,
+    handler: &RequestHandler,
+    config: &WebSocketConfig,
+) -> Result<(), TransportError>
+where
+    S: AsyncRead + AsyncWrite + Unpin,
+{
+    let (_never_stopped, shutdown) = watch::channel(false);
+    serve_negotiated_connection(stream, None, handler, config, shutdown).await
+}
+
+/// Serve one accepted WebSocket connection until it closes or `shutdown`
+/// turns `true`
+///
+/// On shutdown the connection gets a close frame with code 1001 (going
+/// away), the peer's acknowledgement is awaited for up to a second, and
+/// `Ok(())` is returned. Otherwise behaves like [`serve_connection`].
+pub async fn serve_connection_until<S>(
+    stream: WebSocketStream<S>,
+    handler: &RequestHandler,
+    config: &WebSocketConfig,
+    shutdown: watch::Receiver<bool>,
+) -> Result<(), TransportError>
+where
+    S: AsyncRead + AsyncWrite + Unpin,
+{
+    serve_negotiated_connection(stream, None, handler, config, shutdown).await
+}
+
+/// Serve a connection accepted with [`accept_connection`], applying the
+/// compression negotiated in its handshake to every message
+///
+/// With `compression` set, responses are sent as compressed binary frames
+/// and binary frames from the client are decompressed; text frames are still
+/// accepted. Otherwise behaves like [`serve_connection_until`].
+///
+/// Messages are handled concurrently, up to `max_concurrent_requests`, so a
+/// slow tool call doesn't hold up keepalive pings or a client's
+/// `notifications/cancelled` for it. Responses go out as they complete.
+pub async fn serve_negotiated_connection<S>(
+    mut stream: WebSocketStream<S>,
+    compression: Option<CompressionAlgorithm>,
+    handler: &RequestHandler,
+    config: &WebSocketConfig,
+    mut shutdown: watch::Receiver<bool>,
+) -> Result<(), TransportError>
+where
+    S: AsyncRead + AsyncWrite + Unpin,
+{
+    let mut keepalive = config.keepalive_interval.map(|period| {
+        let mut interval = tokio::time::interval_at(Instant::now() + period, period);
+        interval.set_missed_tick_behavior(MissedTickBehavior::Delay);
+        interval
+    });
+    let mut missed_pongs = 0;
+    let mut last_activity = Instant::now();
+    let max_in_flight = config.max_concurrent_requests.max(1);
+    let mut in_flight = FuturesUnordered::new();
+
+    loop {
+        // A connection waiting on its own requests isn't idle
+        let idle_deadline = config
+            .idle_timeout
+            .filter(|_| in_flight.is_empty())
+            .map(|timeout| last_activity + timeout);
+        let saturated = in_flight.len() >= max_in_flight;
+
+        tokio::select! {
+            _ = shutdown_requested(&mut shutdown) => {
+                debug!("Closing WebSocket connection for shutdown");
+                close_going_away(&mut stream).await;
+                return Ok(());
+            }
+            _ = async {
+                match keepalive.as_mut() {
+                    Some(interval) => interval.tick().await,
+                    None => std::future::pending().await,
+                }
+            } => {
+                // Pongs aren't read while every slot is busy, so they can't
+                // be counted as missed then
+                if saturated {
+                    missed_pongs = 0;
+                }
+                if missed_pongs >= config.max_missed_pongs {
+                    close_quietly(&mut stream).await;
+                    return Err(TransportError::Connection(format!(
+                        "WebSocket peer missed {missed_pongs} keepalive pongs"
+                    )));
+                }
+                missed_pongs += 1;
+                send_message(&mut stream, Message::Ping(Vec::new())).await?;
+            }
+            _ = async {
+                match idle_deadline {
+                    Some(deadline) => tokio::time::sleep_until(deadline).await,
+                    None => std::future::pending().await,
+                }
+            } => {
+                close_quietly(&mut stream).await;
+                return Err(TransportError::Connection(format!(
+                    "WebSocket connection idle for {:?}",
+                    config.idle_timeout.unwrap_or_default()
+                )));
+            }
+            Some(handled) = in_flight.next(), if !in_flight.is_empty() => {
+                last_activity = Instant::now();
+                if let Some(text) = handled? {
+                    send_text(&mut stream, compression, text).await?;
+                }
+            }
+            message = stream.next(), if !saturated => match message {
+                None | Some(Ok(Message::Close(_))) => {
+                    debug!("WebSocket peer closed the connection");
+                    return Ok(());
+                }
+                Some(Err(e)) => {
+                    return Err(TransportError::Connection(format!("WebSocket error: {e}")));
+                }
+                Some(Ok(Message::Pong(_))) => missed_pongs = 0,
+                // Pings are answered by tungstenite on the next read or write
+                Some(Ok(Message::Ping(_) | Message::Frame(_))) => {}
+                Some(Ok(Message::Text(text))) => {
+                    last_activity = Instant::now();
+                    in_flight.push(handle_text(text, handler, &config.batch));
+                }
+                Some(Ok(Message::Binary(data))) => {
+                    last_activity = Instant::now();
+                    match compression.map(|algorithm| decompress_text(algorithm, &data)) {
+                        Some(Ok(text)) => {
+                            in_flight.push(handle_text(text, handler, &config.batch));
+                        }
+                        Some(Err(error)) => {
+                            let response = create_error_response(error, None);
+                            send_json(&mut stream, compression, &response).await?;
+                        }
+                        None => {
+                            let response = create_error_response(
+                                pulseengine_mcp_protocol::Error::invalid_request(
+                                    "Binary WebSocket messages are not supported",
+                                ),
+                                None,
+                            );
+                            send_json(&mut stream, compression, &response).await?;
+                        }
+                    }
+                }
+            },
+        }
+    }
+}
+
+/// Unpack a binary frame compressed with the negotiated algorithm
+fn decompress_text(
+    algorithm: CompressionAlgorithm,
+    data: &[u8],
+) -> Result<String, pulseengine_mcp_protocol::Error> {
+    let bytes = decompress(algorithm.encoding(), data, MAX_MESSAGE_SIZE).map_err(|e| {
+        pulseengine_mcp_protocol::Error::parse_error(format!("Invalid compressed message: {e}"))
+    })?;
+    String::from_utf8(bytes).map_err(|e| {
+        pulseengine_mcp_protocol::Error::parse_error(format!("Invalid UTF-8 in message: {e}"))
+    })
+}
+
+/// Handle one message, returning the text of its response, if any
+async fn handle_text(
+    text: String,
+    handler: &RequestHandler,
+    batch: &BatchConfig,
+) -> Result<Option<String>, TransportError> {
+    let message = match JsonRpcMessage::parse(&text) {
+        Ok(message) => message,
+        Err(e) => {
+            let response = create_error_response(
+                pulseengine_mcp_protocol::Error::parse_error(format!("Invalid JSON: {e}")),
+                None,
+            );
+            return to_json(&response).map(Some);
+        }
+    };
+
+    if let Err(e) = message.validate() {
+        let response = create_error_response(
+            pulseengine_mcp_protocol::Error::invalid_request(format!("Invalid JSON-RPC: {e}")),
+            None,
+        );
+        return to_json(&response).map(Some);
+    }
+
+    match process_batch_with(message, handler, batch).await? {
+        Some(response) => response
+            .to_string()
+            .map(Some)
+            .map_err(|e| TransportError::Protocol(format!("Failed to serialize response: {e}"))),
+        None => Ok(None),
+    }
+}
+
+fn to_json<T: serde::Serialize>(value: &T) -> Result<String, TransportError> {
+    serde_json::to_string(value)
+        .map_err(|e| TransportError::Protocol(format!("Failed to serialize response: {e}")))
+}
+
+async fn send_json<S, T>(
+    stream: &mut WebSocketStream<S>,
+    compression: Option<CompressionAlgorithm>,
+    value: &T,
+) -> Result<(), TransportError>
+where
+    S: AsyncRead + AsyncWrite + Unpin,
+    T: serde::Serialize,
+{
+    send_text(stream, compression, to_json(value)?).await
+}
+
+/// Send a message as text, or as a compressed binary frame when the
+/// connection negotiated compression
+async fn send_text<S>(
+    stream: &mut WebSocketStream<S>,
+    compression: Option<CompressionAlgorithm>,
+    text: String,
+) -> Result<(), TransportError>
+where
+    S: AsyncRead + AsyncWrite + Unpin,
+{
+    let message = match compression {
+        Some(algorithm) => {
+            Message::Binary(compress(algorithm, text.as_bytes()).map_err(|e| {
+                TransportError::Protocol(format!("Failed to compress message: {e}"))
+            })?)
+        }
+        None => Message::Text(text),
+    };
+    send_message(stream, message).await
+}
+
+async fn send_message<S>(
+    stream: &mut WebSocketStream<S>,
+    message: Message,
+) -> Result<(), TransportError>
+where
+    S: AsyncRead + AsyncWrite + Unpin,
+{
+    stream
+        .send(message)
+        .await
+        .map_err(|e| TransportError::Connection(format!("WebSocket send failed: {e}")))
+}
+
+/// Send a close frame without waiting on a peer that may be gone
+async fn close_quietly<S>(stream: &mut WebSocketStream<S>)
+where
+    S: AsyncRead + AsyncWrite + Unpin,
+{
+    if tokio::time::timeout(CLOSE_TIMEOUT, stream.close(None))
+        .await
+        .is_err()
+    {
+        warn!("Timed out closing WebSocket connection");
+    }
+}
+
+/// Resolves once `signal` turns `true`; a dropped sender never resolves
+async fn shutdown_requested(signal: &mut watch::Receiver<bool>) {
+    if signal.wait_for(|stopping| *stopping).await.is_err() {
+        std::future::pending::<()>().await;
+    }
+}
+
+/// Run the close handshake with a "going away" frame, waiting briefly for
+/// the peer to answer with its own close frame
+async fn close_going_away<S>(stream: &mut WebSocketStream<S>)
+where
+    S: AsyncRead + AsyncWrite + Unpin,
+{
+    let frame = CloseFrame {
+        code: CloseCode::Away,
+        reason: "Server shutting down".into(),
+    };
+    let handshake = async {
+        stream.close(Some(frame)).await?;
+        // Messages still in flight are discarded until the close reply
+        while let Some(message) = stream.next().await {
+            message?;
+        }
+        Ok::<_, tokio_tungstenite::tungstenite::Error>(())
+    };
+    match tokio::time::timeout(CLOSE_TIMEOUT, handshake).await {
+        Ok(Ok(())) => {}
+        Ok(Err(e)) => debug!("WebSocket close handshake failed: {}", e),
+        Err(_) => warn!("Timed out waiting for WebSocket close acknowledgement"),
+    }
 }
 
 #[async_trait]
@@ -30,7 +478,12 @@ impl Transport for WebSocketTransport {
         ))
     }
 
+    fn set_batch_config(&mut self, config: BatchConfig) {
+        self.config.batch = config;
+    }
+
     async fn stop(&mut self) -> std::result::Result<(), TransportError> {
+        self.shutdown.send_replace(true);
         Ok(())
     }
 
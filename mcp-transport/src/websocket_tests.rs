@@ -4,8 +4,11 @@
 mod tests {
     use super::super::websocket::*;
     use crate::{Transport, TransportError};
+    use futures_util::{SinkExt, StreamExt};
     use pulseengine_mcp_protocol::{Request, Response};
     use serde_json::json;
+    use std::time::Duration;
+    use tokio_tungstenite::tungstenite::Message;
 
     // Mock handler for testing
     fn mock_handler(
@@ -222,4 +225,330 @@ mod tests {
         // Final health check
         assert!(transport.health_check().await.is_ok());
     }
+
+    async fn websocket_pair() -> (
+        tokio_tungstenite::WebSocketStream<tokio::io::DuplexStream>,
+        tokio_tungstenite::WebSocketStream<tokio::io::DuplexStream>,
+    ) {
+        use tokio_tungstenite::tungstenite::protocol::Role;
+
+        let (server, client) = tokio::io::duplex(64 * 1024);
+        let server =
+            tokio_tungstenite::WebSocketStream::from_raw_socket(server, Role::Server, None).await;
+        let client =
+            tokio_tungstenite::WebSocketStream::from_raw_socket(client, Role::Client, None).await;
+        (server, client)
+    }
+
+    fn keepalive_config() -> WebSocketConfig {
+        WebSocketConfig {
+            keepalive_interval: Some(Duration::from_millis(20)),
+            idle_timeout: None,
+            max_missed_pongs: 2,
+            ..WebSocketConfig::default()
+        }
+    }
+
+    #[test]
+    fn test_websocket_config_defaults_disable_keepalive() {
+        let transport = WebSocketTransport::new(8080);
+        assert!(transport.config().keepalive_interval.is_none());
+        assert!(transport.config().idle_timeout.is_none());
+
+        let transport = WebSocketTransport::with_config(8080, keepalive_config());
+        assert_eq!(
+            transport.config().keepalive_interval,
+            Some(Duration::from_millis(20))
+        );
+    }
+
+    #[tokio::test]
+    async fn test_keepalive_closes_connection_when_pongs_stop() {
+        let (server, _client) = websocket_pair().await;
+        let handler: crate::RequestHandler = Box::new(mock_handler);
+
+        // The client never reads, so it never answers the pings
+        let result = tokio::time::timeout(
+            Duration::from_secs(5),
+            serve_connection(server, &handler, &keepalive_config()),
+        )
+        .await
+        .expect("unresponsive connection should be closed");
+
+        match result {
+            Err(TransportError::Connection(msg)) => assert!(msg.contains("missed")),
+            other => panic!("Expected Connection error, got {other:?}"),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_keepalive_keeps_responsive_connection_open() {
+        let (server, mut client) = websocket_pair().await;
+        let server_task = tokio::spawn(async move {
+            let handler: crate::RequestHandler = Box::new(mock_handler);
+            serve_connection(server, &handler, &keepalive_config()).await
+        });
+
+        // Reading answers each ping; outlast several missed-pong windows
+        let deadline = tokio::time::Instant::now() + Duration::from_millis(200);
+        let mut pings = 0;
+        while let Ok(Some(message)) = tokio::time::timeout_at(deadline, client.next()).await {
+            assert!(matches!(message.unwrap(), Message::Ping(_)));
+            pings += 1;
+        }
+        assert!(pings >= 3);
+        assert!(!server_task.is_finished());
+
+        let request = json!({"jsonrpc": "2.0", "id": 1, "method": "tools/list", "params": {}});
+        client
+            .send(Message::Text(request.to_string()))
+            .await
+            .unwrap();
+        let reply = loop {
+            match client.next().await.unwrap().unwrap() {
+                Message::Text(text) => break text,
+                _ => continue,
+            }
+        };
+        let response: serde_json::Value = serde_json::from_str(&reply).unwrap();
+        assert_eq!(response["result"]["echo"], "tools/list");
+
+        client.close(None).await.unwrap();
+        let result = tokio::time::timeout(Duration::from_secs(5), server_task)
+            .await
+            .unwrap()
+            .unwrap();
+        assert!(result.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_idle_timeout_closes_connection_without_messages() {
+        let (server, mut client) = websocket_pair().await;
+        let config = WebSocketConfig {
+            idle_timeout: Some(Duration::from_millis(100)),
+            ..keepalive_config()
+        };
+        let server_task = tokio::spawn(async move {
+            let handler: crate::RequestHandler = Box::new(mock_handler);
+            serve_connection(server, &handler, &config).await
+        });
+
+        // Pongs keep the connection alive but don't count as activity
+        let client_task =
+            tokio::spawn(async move { while let Some(Ok(_)) = client.next().await {} });
+
+        let result = tokio::time::timeout(Duration::from_secs(5), server_task)
+            .await
+            .expect("idle connection should be closed")
+            .unwrap();
+        match result {
+            Err(TransportError::Connection(msg)) => assert!(msg.contains("idle")),
+            other => panic!("Expected Connection error, got {other:?}"),
+        }
+        client_task.abort();
+    }
+
+    /// Handler taking its time over `slow` requests
+    fn slow_handler(
+        request: Request,
+    ) -> std::pin::Pin<Box<dyn std::future::Future<Output = Response> + Send>> {
+        Box::pin(async move {
+            if request.method == "slow" {
+                tokio::time::sleep(Duration::from_millis(300)).await;
+            }
+            mock_handler(request).await
+        })
+    }
+
+    #[tokio::test]
+    async fn test_slow_request_does_not_block_the_connection() {
+        let (server, mut client) = websocket_pair().await;
+        let server_task = tokio::spawn(async move {
+            let handler: crate::RequestHandler = Box::new(slow_handler);
+            serve_connection(server, &handler, &keepalive_config()).await
+        });
+
+        for (id, method) in [(1, "slow"), (2, "tools/list")] {
+            let request = json!({"jsonrpc": "2.0", "id": id, "method": method, "params": {}});
+            client
+                .send(Message::Text(request.to_string()))
+                .await
+                .unwrap();
+        }
+
+        // Pings and the quick response arrive while the slow request runs
+        let mut pings = 0;
+        let mut replies = Vec::new();
+        while replies.len() < 2 {
+            match client.next().await.unwrap().unwrap() {
+                Message::Ping(_) => pings += 1,
+                Message::Text(text) => {
+                    let response: serde_json::Value = serde_json::from_str(&text).unwrap();
+                    replies.push(response["id"].as_i64().unwrap());
+                }
+                _ => {}
+            }
+        }
+        assert_eq!(replies, [2, 1]);
+        assert!(pings >= 3, "only {pings} pings during the slow request");
+
+        client.close(None).await.unwrap();
+        let result = tokio::time::timeout(Duration::from_secs(5), server_task)
+            .await
+            .unwrap()
+            .unwrap();
+        assert!(result.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_stop_closes_connection_with_going_away_frame() {
+        use tokio_tungstenite::tungstenite::protocol::frame::coding::CloseCode;
+
+        let (server, mut client) = websocket_pair().await;
+        let mut transport = WebSocketTransport::new(8080);
+        let shutdown = transport.shutdown_signal();
+        let server_task = tokio::spawn(async move {
+            let handler: crate::RequestHandler = Box::new(mock_handler);
+            serve_connection_until(server, &handler, &WebSocketConfig::default(), shutdown).await
+        });
+
+        transport.stop().await.unwrap();
+
+        let message = tokio::time::timeout(Duration::from_secs(5), client.next())
+            .await
+            .expect("stop should send a close frame")
+            .unwrap()
+            .unwrap();
+        match message {
+            Message::Close(Some(frame)) => {
+                assert_eq!(frame.code, CloseCode::Away);
+                assert!(!frame.reason.is_empty());
+            }
+            other => panic!("Expected close frame, got {other:?}"),
+        }
+
+        // Reading the close frame sent the reply, so the server exits cleanly
+        assert!(client.next().await.is_none());
+        let result = tokio::time::timeout(Duration::from_secs(5), server_task)
+            .await
+            .unwrap()
+            .unwrap();
+        assert!(result.is_ok());
+    }
+
+    /// Handshake over an in-memory pipe, the client offering `accepted`
+    async fn negotiated_pair(
+        config: WebSocketConfig,
+        accepted: Option<&str>,
+    ) -> (
+        tokio::task::JoinHandle<Result<(), TransportError>>,
+        tokio_tungstenite::WebSocketStream<tokio::io::DuplexStream>,
+        Option<String>,
+    ) {
+        use tokio_tungstenite::tungstenite::client::IntoClientRequest;
+
+        let (server, client) = tokio::io::duplex(64 * 1024);
+        let server_task = tokio::spawn(async move {
+            let (stream, compression) = accept_connection(server, &config).await?;
+            let handler: crate::RequestHandler = Box::new(mock_handler);
+            let (_never_stopped, shutdown) = tokio::sync::watch::channel(false);
+            serve_negotiated_connection(stream, compression, &handler, &config, shutdown).await
+        });
+
+        let mut request = "ws://localhost/mcp".into_client_request().unwrap();
+        if let Some(accepted) = accepted {
+            request
+                .headers_mut()
+                .insert(COMPRESSION_HEADER, accepted.parse().unwrap());
+        }
+        let (client, response) = tokio_tungstenite::client_async(request, client)
+            .await
+            .unwrap();
+        let negotiated = response
+            .headers()
+            .get(COMPRESSION_HEADER)
+            .map(|value| value.to_str().unwrap().to_string());
+        (server_task, client, negotiated)
+    }
+
+    fn compressing_config() -> WebSocketConfig {
+        WebSocketConfig {
+            compression: crate::CompressionConfig::default(),
+            ..WebSocketConfig::default()
+        }
+    }
+
+    fn list_request(id: u64) -> String {
+        json!({"jsonrpc": "2.0", "id": id, "method": "tools/list", "params": {}}).to_string()
+    }
+
+    #[tokio::test]
+    async fn test_compression_negotiated_once_for_connection() {
+        use crate::compression::{CompressionAlgorithm, compress, decompress};
+
+        let (server_task, mut client, negotiated) =
+            negotiated_pair(compressing_config(), Some("gzip;q=0.5, zstd")).await;
+        assert_eq!(negotiated.as_deref(), Some("zstd"));
+
+        // Plain and compressed requests alike get compressed replies
+        let requests = [
+            Message::Text(list_request(1)),
+            Message::Binary(
+                compress(CompressionAlgorithm::Zstd, list_request(2).as_bytes()).unwrap(),
+            ),
+            Message::Text(list_request(3)),
+        ];
+        for (id, request) in (1..).zip(requests) {
+            client.send(request).await.unwrap();
+            let reply = match client.next().await.unwrap().unwrap() {
+                Message::Binary(data) => decompress("zstd", &data, 4096).unwrap(),
+                other => panic!("Expected compressed reply, got {other:?}"),
+            };
+            let response: serde_json::Value = serde_json::from_slice(&reply).unwrap();
+            assert_eq!(response["id"], id);
+            assert_eq!(response["result"]["echo"], "tools/list");
+        }
+
+        // A frame that doesn't decompress gets a parse error, still compressed
+        client
+            .send(Message::Binary(b"not zstd".to_vec()))
+            .await
+            .unwrap();
+        let Message::Binary(data) = client.next().await.unwrap().unwrap() else {
+            panic!("Expected compressed error reply");
+        };
+        let response: serde_json::Value =
+            serde_json::from_slice(&decompress("zstd", &data, 4096).unwrap()).unwrap();
+        assert_eq!(response["error"]["code"], -32700);
+
+        client.close(None).await.unwrap();
+        assert!(server_task.await.unwrap().is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_connection_uncompressed_without_agreement() {
+        // The client didn't offer compression
+        let (server_task, mut client, negotiated) =
+            negotiated_pair(compressing_config(), None).await;
+        assert!(negotiated.is_none());
+        client.send(Message::Text(list_request(1))).await.unwrap();
+        assert!(matches!(
+            client.next().await.unwrap().unwrap(),
+            Message::Text(_)
+        ));
+        client.close(None).await.unwrap();
+        assert!(server_task.await.unwrap().is_ok());
+
+        // The server allows none of the offered algorithms
+        let (server_task, mut client, negotiated) =
+            negotiated_pair(WebSocketConfig::default(), Some("gzip, zstd")).await;
+        assert!(negotiated.is_none());
+        client.send(Message::Text(list_request(2))).await.unwrap();
+        assert!(matches!(
+            client.next().await.unwrap().unwrap(),
+            Message::Text(_)
+        ));
+        client.close(None).await.unwrap();
+        assert!(server_task.await.unwrap().is_ok());
+    }
 }
@@ -5,11 +5,14 @@
 
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
+use std::ops::Range;
 use std::path::PathBuf;
 use thiserror::Error;
 use tokio::fs;
-use tokio::io::AsyncWriteExt;
-use tracing::{debug, error, warn};
+use tracing::{debug, warn};
+
+use crate::audit_bundle::{AuditBundle, AuditBundleSigner};
+use crate::audit_sink::{AuditSinkDispatcher, FileAuditSink};
 
 /// Audit logging errors
 #[derive(Debug, Error)]
@@ -22,6 +25,15 @@ pub enum AuditError {
 
     #[error("Configuration error: {0}")]
     Configuration(String),
+
+    #[error("Bundle signing error: {0}")]
+    Signing(String),
+
+    #[error("Bundle verification failed: {0}")]
+    Verification(String),
+
+    #[error("Audit sink error: {0}")]
+    Sink(String),
 }
 
 /// Audit event types following security standards
@@ -80,7 +92,9 @@ pub struct AuditEvent {
     /// Unique event identifier
     pub id: String,
 
-    /// Event timestamp in UTC
+    /// Event timestamp in UTC, serialized in the configured
+    /// [`TimestampFormat`](pulseengine_mcp_protocol::TimestampFormat)
+    #[serde(with = "pulseengine_mcp_protocol::timestamp::serde_format")]
     pub timestamp: DateTime<Utc>,
 
     /// Event type
@@ -179,6 +193,16 @@ impl AuditEvent {
         self.request_id = Some(request_id);
         self
     }
+
+    /// Record a SHA-256 of the canonical form of `arguments` as
+    /// `arguments_sha256`, so identical calls can be correlated without
+    /// logging the arguments themselves
+    pub fn with_arguments_hash(self, arguments: &serde_json::Value) -> Self {
+        self.with_metadata(
+            "arguments_sha256".to_string(),
+            serde_json::Value::String(pulseengine_mcp_protocol::canonical_hash(arguments)),
+        )
+    }
 }
 
 /// Audit logger configuration
@@ -231,6 +255,11 @@ impl Default for AuditConfig {
 /// Audit logger implementation
 pub struct AuditLogger {
     config: AuditConfig,
+    file_sink: FileAuditSink,
+    /// Forwards events to external sinks
+    sink_dispatcher: Option<AuditSinkDispatcher>,
+    /// Signs exported audit bundles
+    bundle_signer: Option<AuditBundleSigner>,
 }
 
 impl AuditLogger {
@@ -257,7 +286,12 @@ impl AuditLogger {
             }
         }
 
-        Ok(Self { config })
+        Ok(Self {
+            file_sink: FileAuditSink::new(&config),
+            config,
+            sink_dispatcher: None,
+            bundle_signer: None,
+        })
     }
 
     /// Create a disabled audit logger (no-op)
@@ -266,7 +300,25 @@ impl AuditLogger {
             enabled: false,
             ..Default::default()
         };
-        Self { config }
+        Self {
+            file_sink: FileAuditSink::new(&config),
+            config,
+            sink_dispatcher: None,
+            bundle_signer: None,
+        }
+    }
+
+    /// Sign bundles produced by [`export_audit_bundle`](Self::export_audit_bundle)
+    /// with `signer`
+    pub fn with_bundle_signer(mut self, signer: AuditBundleSigner) -> Self {
+        self.bundle_signer = Some(signer);
+        self
+    }
+
+    /// Also forward logged events to the sinks of `dispatcher`
+    pub fn with_sink_dispatcher(mut self, dispatcher: AuditSinkDispatcher) -> Self {
+        self.sink_dispatcher = Some(dispatcher);
+        self
     }
 
     /// Log an audit event
@@ -296,7 +348,12 @@ impl AuditLogger {
         }
 
         // Log to file
-        self.write_to_file(&json_line).await?;
+        self.file_sink.write_line(&json_line).await?;
+
+        // Hand off to the external sinks without waiting for delivery
+        if let Some(dispatcher) = &self.sink_dispatcher {
+            dispatcher.submit(sanitized_event.clone());
+        }
 
         debug!(
             "Logged audit event: {} - {}",
@@ -352,87 +409,64 @@ impl AuditLogger {
         event
     }
 
-    /// Write log entry to file with rotation
-    async fn write_to_file(&self, line: &str) -> Result<(), AuditError> {
-        // Check if file rotation is needed
-        if self.config.log_file.exists() {
-            let metadata = fs::metadata(&self.config.log_file).await?;
-            if metadata.len() > self.config.max_file_size {
-                self.rotate_logs().await?;
+    /// Export the events logged within `time_range` as a signed, tamper-evident
+    /// bundle
+    ///
+    /// Rotated log files are included, oldest first. Lines that aren't audit
+    /// events are skipped with a warning.
+    pub async fn export_audit_bundle(
+        &self,
+        time_range: Range<DateTime<Utc>>,
+    ) -> Result<AuditBundle, AuditError> {
+        let signer = self.bundle_signer.as_ref().ok_or_else(|| {
+            AuditError::Configuration("No signer configured for audit bundles".to_string())
+        })?;
+
+        let mut files: Vec<PathBuf> = (1..=self.config.max_files)
+            .rev()
+            .map(|i| self.config.log_file.with_extension(format!("log.{i}")))
+            .collect();
+        files.push(self.config.log_file.clone());
+
+        let mut records = Vec::new();
+        for file in files {
+            if !file.exists() {
+                continue;
             }
-        }
-
-        // Append to log file
-        let mut file = fs::OpenOptions::new()
-            .create(true)
-            .append(true)
-            .open(&self.config.log_file)
-            .await?;
-
-        // Set secure permissions
-        #[cfg(unix)]
-        {
-            use std::os::unix::fs::PermissionsExt;
-            let mut perms = file.metadata().await?.permissions();
-            perms.set_mode(self.config.file_permissions);
-            file.set_permissions(perms).await?;
-        }
-
-        file.write_all(format!("{line}\n").as_bytes()).await?;
-        file.flush().await?;
-
-        Ok(())
-    }
-
-    /// Rotate log files when they get too large
-    async fn rotate_logs(&self) -> Result<(), AuditError> {
-        // Move existing files up one number
-        for i in (1..self.config.max_files).rev() {
-            let old_file = self.config.log_file.with_extension(format!("log.{i}"));
-            let new_file = self
-                .config
-                .log_file
-                .with_extension(format!("log.{}", i + 1));
-
-            if old_file.exists()
-                && let Err(e) = fs::rename(&old_file, &new_file).await
-            {
-                warn!(
-                    "Failed to rotate log file {} to {}: {}",
-                    old_file.display(),
-                    new_file.display(),
-                    e
-                );
+            let content = fs::read_to_string(&file).await?;
+            for line in content.lines().filter(|line| !line.trim().is_empty()) {
+                let record: serde_json::Value = match serde_json::from_str(line) {
+                    Ok(record) => record,
+                    Err(e) => {
+                        warn!(
+                            "Skipping unreadable audit line in {}: {}",
+                            file.display(),
+                            e
+                        );
+                        continue;
+                    }
+                };
+                match serde_json::from_value::<AuditEvent>(record.clone()) {
+                    Ok(event) if time_range.contains(&event.timestamp) => records.push(record),
+                    Ok(_) => {}
+                    Err(e) => {
+                        warn!(
+                            "Skipping unreadable audit line in {}: {}",
+                            file.display(),
+                            e
+                        );
+                    }
+                }
             }
         }
 
-        // Move current log to .1
-        let rotated_file = self.config.log_file.with_extension("log.1");
-        if let Err(e) = fs::rename(&self.config.log_file, &rotated_file).await {
-            error!("Failed to rotate current log file: {}", e);
-            return Err(AuditError::Io(e));
-        }
-
-        // Remove oldest log if we have too many
-        let oldest_file = self
-            .config
-            .log_file
-            .with_extension(format!("log.{}", self.config.max_files));
-        if oldest_file.exists()
-            && let Err(e) = fs::remove_file(&oldest_file).await
-        {
-            warn!(
-                "Failed to remove oldest log file {}: {}",
-                oldest_file.display(),
-                e
-            );
-        }
-
+        let bundle = AuditBundle::sign(time_range, records, signer)?;
         debug!(
-            "Rotated audit logs, moved current to {}",
-            rotated_file.display()
+            "Exported audit bundle with {} records, chain hash {}",
+            bundle.records.len(),
+            bundle.chain_hash
         );
-        Ok(())
+        Ok(bundle)
     }
 
     /// Get audit statistics
@@ -538,6 +572,36 @@ pub mod events {
         .with_client_ip(client_ip.to_string())
     }
 
+    pub fn permission_denied(
+        actor: Option<&str>,
+        action: &str,
+        missing_permission: &str,
+        roles: &[String],
+    ) -> AuditEvent {
+        let mut event = AuditEvent::new(
+            AuditEventType::PermissionDenied,
+            AuditSeverity::Warning,
+            "permissions".to_string(),
+            format!("Permission denied for {action}: missing {missing_permission}"),
+        )
+        .with_resource(action.to_string())
+        .with_metadata(
+            "action".to_string(),
+            serde_json::Value::String(action.to_string()),
+        )
+        .with_metadata(
+            "missing_permission".to_string(),
+            serde_json::Value::String(missing_permission.to_string()),
+        )
+        .with_metadata("roles".to_string(), serde_json::Value::from(roles.to_vec()));
+
+        if let Some(actor) = actor {
+            event = event.with_actor(actor.to_string());
+        }
+
+        event
+    }
+
     pub fn security_violation(description: &str, client_ip: Option<&str>) -> AuditEvent {
         let mut event = AuditEvent::new(
             AuditEventType::SecurityViolation,
@@ -559,6 +623,33 @@ mod tests {
     use super::*;
     use tempfile::tempdir;
 
+    #[test]
+    fn test_arguments_hash_ignores_key_order() {
+        let hash = |arguments: &str| {
+            let arguments: serde_json::Value = serde_json::from_str(arguments).unwrap();
+            AuditEvent::new(
+                AuditEventType::StorageAccessed,
+                AuditSeverity::Info,
+                "test".to_string(),
+                "Tool called".to_string(),
+            )
+            .with_arguments_hash(&arguments)
+            .metadata["arguments_sha256"]
+                .clone()
+        };
+
+        let first = hash(r#"{"path": "/tmp", "recursive": true, "depth": 2}"#);
+        assert_eq!(
+            first,
+            hash(r#"{"depth":2.0,"recursive":true,"path":"/tmp"}"#)
+        );
+        assert_ne!(
+            first,
+            hash(r#"{"path": "/tmp", "recursive": false, "depth": 2}"#)
+        );
+        assert_eq!(first.as_str().unwrap().len(), 64);
+    }
+
     #[tokio::test]
     async fn test_audit_event_creation() {
         let event = AuditEvent::new(
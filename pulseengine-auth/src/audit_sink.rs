@@ -0,0 +1,679 @@
+//! Pluggable destinations for audit events
+//!
+//! An [`AuditSink`] receives every event the [`AuditLogger`] records, e.g. to
+//! forward it to a SIEM. Sinks are driven by an [`AuditSinkDispatcher`]: the
+//! logger hands events to a bounded channel and a background task delivers
+//! them to each sink in batches, so a slow or unreachable sink never holds up
+//! request handling. When the channel is full, events are dropped for the
+//! sinks (the audit log file still records them) and counted.
+//!
+//! [`AuditLogger`]: crate::audit::AuditLogger
+
+use async_trait::async_trait;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Duration;
+use tokio::fs;
+use tokio::io::AsyncWriteExt;
+use tokio::sync::{mpsc, oneshot};
+use tracing::{debug, error, warn};
+
+use crate::audit::{AuditConfig, AuditError, AuditEvent};
+
+/// Destination for audit events
+#[async_trait]
+pub trait AuditSink: Send + Sync {
+    /// Name used when reporting delivery failures
+    fn name(&self) -> &str;
+
+    /// Deliver a single event
+    async fn emit(&self, event: &AuditEvent) -> Result<(), AuditError>;
+
+    /// Deliver a batch of events
+    ///
+    /// The default emits the events one by one, carrying on past failures
+    /// and returning the first error.
+    async fn emit_batch(&self, events: &[AuditEvent]) -> Result<(), AuditError> {
+        let mut result = Ok(());
+        for event in events {
+            if let Err(e) = self.emit(event).await
+                && result.is_ok()
+            {
+                result = Err(e);
+            }
+        }
+        result
+    }
+}
+
+/// Appends events as JSON lines to a file, rotating it when it grows too large
+///
+/// This is the sink behind the audit log file of [`AuditLogger`].
+///
+/// [`AuditLogger`]: crate::audit::AuditLogger
+#[derive(Debug, Clone)]
+pub struct FileAuditSink {
+    path: PathBuf,
+    max_file_size: u64,
+    max_files: u32,
+    file_permissions: u32,
+}
+
+impl FileAuditSink {
+    /// Sink writing to `config.log_file` with the configured rotation
+    pub fn new(config: &AuditConfig) -> Self {
+        Self {
+            path: config.log_file.clone(),
+            max_file_size: config.max_file_size,
+            max_files: config.max_files,
+            file_permissions: config.file_permissions,
+        }
+    }
+
+    /// Append `line` to the log file, rotating first if needed
+    pub async fn write_line(&self, line: &str) -> Result<(), AuditError> {
+        // Check if file rotation is needed
+        if self.path.exists() {
+            let metadata = fs::metadata(&self.path).await?;
+            if metadata.len() > self.max_file_size {
+                self.rotate_logs().await?;
+            }
+        }
+
+        // Append to log file
+        let mut file = fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)
+            .await?;
+
+        // Set secure permissions
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let mut perms = file.metadata().await?.permissions();
+            perms.set_mode(self.file_permissions);
+            file.set_permissions(perms).await?;
+        }
+
+        file.write_all(format!("{line}\n").as_bytes()).await?;
+        file.flush().await?;
+
+        Ok(())
+    }
+
+    /// Rotate log files when they get too large
+    async fn rotate_logs(&self) -> Result<(), AuditError> {
+        // Move existing files up one number
+        for i in (1..self.max_files).rev() {
+            let old_file = self.path.with_extension(format!("log.{i}"));
+            let new_file = self.path.with_extension(format!("log.{}", i + 1));
+
+            if old_file.exists()
+                && let Err(e) = fs::rename(&old_file, &new_file).await
+            {
+                warn!(
+                    "Failed to rotate log file {} to {}: {}",
+                    old_file.display(),
+                    new_file.display(),
+                    e
+                );
+            }
+        }
+
+        // Move current log to .1
+        let rotated_file = self.path.with_extension("log.1");
+        if let Err(e) = fs::rename(&self.path, &rotated_file).await {
+            error!("Failed to rotate current log file: {}", e);
+            return Err(AuditError::Io(e));
+        }
+
+        // Remove oldest log if we have too many
+        let oldest_file = self.path.with_extension(format!("log.{}", self.max_files));
+        if oldest_file.exists()
+            && let Err(e) = fs::remove_file(&oldest_file).await
+        {
+            warn!(
+                "Failed to remove oldest log file {}: {}",
+                oldest_file.display(),
+                e
+            );
+        }
+
+        debug!(
+            "Rotated audit logs, moved current to {}",
+            rotated_file.display()
+        );
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl AuditSink for FileAuditSink {
+    fn name(&self) -> &str {
+        "file"
+    }
+
+    async fn emit(&self, event: &AuditEvent) -> Result<(), AuditError> {
+        self.write_line(&serde_json::to_string(event)?).await
+    }
+}
+
+/// Prints events as JSON lines to stdout
+#[derive(Debug, Clone, Default)]
+pub struct StdoutAuditSink;
+
+#[async_trait]
+impl AuditSink for StdoutAuditSink {
+    fn name(&self) -> &str {
+        "stdout"
+    }
+
+    async fn emit(&self, event: &AuditEvent) -> Result<(), AuditError> {
+        println!("{}", serde_json::to_string(event)?);
+        Ok(())
+    }
+}
+
+/// Buffering and batching of events on their way to the sinks
+#[derive(Debug, Clone)]
+pub struct AuditSinkConfig {
+    /// Events that can wait for delivery before new ones are dropped
+    pub channel_capacity: usize,
+
+    /// Events delivered to the sinks in one batch at most
+    pub batch_size: usize,
+
+    /// How long a partial batch waits before it is delivered
+    pub flush_interval: Duration,
+}
+
+impl Default for AuditSinkConfig {
+    fn default() -> Self {
+        Self {
+            channel_capacity: 10_000,
+            batch_size: 100,
+            flush_interval: Duration::from_secs(1),
+        }
+    }
+}
+
+enum SinkCommand {
+    Event(Box<AuditEvent>),
+    Flush(oneshot::Sender<()>),
+}
+
+/// Delivers events to the registered sinks from a background task
+///
+/// Cloning shares the task. It delivers what is left and stops once every
+/// clone is dropped.
+#[derive(Clone)]
+pub struct AuditSinkDispatcher {
+    sender: mpsc::Sender<SinkCommand>,
+    dropped: Arc<AtomicU64>,
+}
+
+impl AuditSinkDispatcher {
+    /// Start delivering to `sinks`
+    ///
+    /// Must be called from within a Tokio runtime.
+    pub fn start(config: AuditSinkConfig, sinks: Vec<Arc<dyn AuditSink>>) -> Self {
+        let (sender, receiver) = mpsc::channel(config.channel_capacity.max(1));
+        tokio::spawn(run_dispatcher(config, sinks, receiver));
+        Self {
+            sender,
+            dropped: Arc::new(AtomicU64::new(0)),
+        }
+    }
+
+    /// Queue `event` for delivery without waiting
+    ///
+    /// Returns `false` if the event was dropped because the queue is full.
+    pub fn submit(&self, event: AuditEvent) -> bool {
+        match self.sender.try_send(SinkCommand::Event(Box::new(event))) {
+            Ok(()) => true,
+            Err(mpsc::error::TrySendError::Full(_)) => {
+                let dropped = self.dropped.fetch_add(1, Ordering::Relaxed) + 1;
+                warn!(
+                    "Audit sink queue full, dropped event ({} dropped so far)",
+                    dropped
+                );
+                false
+            }
+            Err(mpsc::error::TrySendError::Closed(_)) => {
+                self.dropped.fetch_add(1, Ordering::Relaxed);
+                false
+            }
+        }
+    }
+
+    /// Wait until every event queued so far has been handed to the sinks
+    pub async fn flush(&self) {
+        let (done, delivered) = oneshot::channel();
+        if self.sender.send(SinkCommand::Flush(done)).await.is_ok() {
+            let _ = delivered.await;
+        }
+    }
+
+    /// Events dropped because the queue was full
+    pub fn dropped_events(&self) -> u64 {
+        self.dropped.load(Ordering::Relaxed)
+    }
+}
+
+async fn run_dispatcher(
+    config: AuditSinkConfig,
+    sinks: Vec<Arc<dyn AuditSink>>,
+    mut receiver: mpsc::Receiver<SinkCommand>,
+) {
+    let batch_size = config.batch_size.max(1);
+    let mut batch = Vec::with_capacity(batch_size);
+    // Start ticking one interval from now; an immediate first tick could
+    // flush a partial batch ahead of the first full one
+    let start = tokio::time::Instant::now() + config.flush_interval;
+    let mut ticker = tokio::time::interval_at(start, config.flush_interval);
+    ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+
+    loop {
+        tokio::select! {
+            command = receiver.recv() => match command {
+                Some(SinkCommand::Event(event)) => {
+                    batch.push(*event);
+                    if batch.len() >= batch_size {
+                        deliver(&sinks, &mut batch).await;
+                    }
+                }
+                Some(SinkCommand::Flush(done)) => {
+                    deliver(&sinks, &mut batch).await;
+                    let _ = done.send(());
+                }
+                None => {
+                    deliver(&sinks, &mut batch).await;
+                    break;
+                }
+            },
+            _ = ticker.tick() => deliver(&sinks, &mut batch).await,
+        }
+    }
+    debug!("Audit sink dispatcher stopped");
+}
+
+async fn deliver(sinks: &[Arc<dyn AuditSink>], batch: &mut Vec<AuditEvent>) {
+    if batch.is_empty() {
+        return;
+    }
+    for sink in sinks {
+        if let Err(e) = sink.emit_batch(batch).await {
+            warn!(
+                "Audit sink {} failed to deliver {} events: {}",
+                sink.name(),
+                batch.len(),
+                e
+            );
+        }
+    }
+    batch.clear();
+}
+
+/// Posts newline-delimited JSON to a webhook
+#[async_trait]
+pub trait WebhookHttpClient: Send + Sync {
+    /// POST `body` to `url` with the extra `headers` and return the response
+    /// status
+    async fn post_ndjson(
+        &self,
+        url: &str,
+        headers: &[(String, String)],
+        body: String,
+    ) -> Result<u16, AuditError>;
+}
+
+/// [`WebhookHttpClient`] backed by `reqwest`
+#[cfg(feature = "audit-webhook")]
+pub struct ReqwestWebhookClient {
+    client: reqwest::Client,
+}
+
+#[cfg(feature = "audit-webhook")]
+impl ReqwestWebhookClient {
+    /// Create a client with a 10 second request timeout
+    pub fn new() -> Result<Self, AuditError> {
+        let client = reqwest::Client::builder()
+            .timeout(Duration::from_secs(10))
+            .build()
+            .map_err(|e| AuditError::Sink(e.to_string()))?;
+        Ok(Self { client })
+    }
+}
+
+#[cfg(feature = "audit-webhook")]
+#[async_trait]
+impl WebhookHttpClient for ReqwestWebhookClient {
+    async fn post_ndjson(
+        &self,
+        url: &str,
+        headers: &[(String, String)],
+        body: String,
+    ) -> Result<u16, AuditError> {
+        let mut request = self
+            .client
+            .post(url)
+            .header("Content-Type", "application/x-ndjson")
+            .body(body);
+        for (name, value) in headers {
+            request = request.header(name.as_str(), value.as_str());
+        }
+        let response = request
+            .send()
+            .await
+            .map_err(|e| AuditError::Sink(e.to_string()))?;
+        Ok(response.status().as_u16())
+    }
+}
+
+/// Forwards events to an HTTP endpoint, e.g. a Splunk HTTP Event Collector
+///
+/// Each batch is sent as one request with one JSON event per line. Network
+/// errors, 429 and 5xx responses are retried with exponential backoff; a
+/// batch that still can't be delivered, or is rejected with another status,
+/// is dead-lettered and counted in [`dead_letter_count`](Self::dead_letter_count).
+pub struct HttpWebhookSink {
+    url: String,
+    headers: Vec<(String, String)>,
+    client: Arc<dyn WebhookHttpClient>,
+    max_retries: u32,
+    retry_backoff: Duration,
+    dead_letters: AtomicU64,
+}
+
+impl HttpWebhookSink {
+    /// Sink posting to `url`
+    #[cfg(feature = "audit-webhook")]
+    pub fn new(url: impl Into<String>) -> Result<Self, AuditError> {
+        Ok(Self::with_client(
+            url,
+            Arc::new(ReqwestWebhookClient::new()?),
+        ))
+    }
+
+    /// Sink posting to `url` through a custom HTTP client
+    pub fn with_client(url: impl Into<String>, client: Arc<dyn WebhookHttpClient>) -> Self {
+        Self {
+            url: url.into(),
+            headers: Vec::new(),
+            client,
+            max_retries: 3,
+            retry_backoff: Duration::from_millis(500),
+            dead_letters: AtomicU64::new(0),
+        }
+    }
+
+    /// Send `name: value` with every request, e.g. an `Authorization` header
+    pub fn with_header(mut self, name: impl Into<String>, value: impl Into<String>) -> Self {
+        self.headers.push((name.into(), value.into()));
+        self
+    }
+
+    /// Retry a failed batch up to `max_retries` times, waiting `backoff`
+    /// before the first retry and doubling it for each one after
+    pub fn with_retries(mut self, max_retries: u32, backoff: Duration) -> Self {
+        self.max_retries = max_retries;
+        self.retry_backoff = backoff;
+        self
+    }
+
+    /// Events that couldn't be delivered and were given up on
+    pub fn dead_letter_count(&self) -> u64 {
+        self.dead_letters.load(Ordering::Relaxed)
+    }
+
+    fn dead_letter(&self, count: usize, reason: String) -> AuditError {
+        self.dead_letters.fetch_add(count as u64, Ordering::Relaxed);
+        AuditError::Sink(format!(
+            "Dead-lettered {count} events for {}: {reason}",
+            self.url
+        ))
+    }
+}
+
+#[async_trait]
+impl AuditSink for HttpWebhookSink {
+    fn name(&self) -> &str {
+        &self.url
+    }
+
+    async fn emit(&self, event: &AuditEvent) -> Result<(), AuditError> {
+        self.emit_batch(std::slice::from_ref(event)).await
+    }
+
+    async fn emit_batch(&self, events: &[AuditEvent]) -> Result<(), AuditError> {
+        if events.is_empty() {
+            return Ok(());
+        }
+        let mut body = String::new();
+        for event in events {
+            body.push_str(&serde_json::to_string(event)?);
+            body.push('\n');
+        }
+
+        let mut backoff = self.retry_backoff;
+        let mut attempt = 0;
+        loop {
+            let failure = match self
+                .client
+                .post_ndjson(&self.url, &self.headers, body.clone())
+                .await
+            {
+                Ok(status) if (200..300).contains(&status) => return Ok(()),
+                Ok(status) if status == 429 || status >= 500 => format!("HTTP {status}"),
+                Ok(status) => {
+                    return Err(self.dead_letter(events.len(), format!("HTTP {status}")));
+                }
+                Err(e) => e.to_string(),
+            };
+
+            if attempt >= self.max_retries {
+                return Err(self.dead_letter(events.len(), failure));
+            }
+            attempt += 1;
+            debug!(
+                "Audit webhook {} failed ({}), retry {} of {}",
+                self.url, failure, attempt, self.max_retries
+            );
+            tokio::time::sleep(backoff).await;
+            backoff *= 2;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::audit::{AuditLogger, events};
+    use std::sync::Mutex;
+    use tempfile::tempdir;
+
+    #[derive(Default)]
+    struct RecordingSink {
+        batches: Mutex<Vec<Vec<AuditEvent>>>,
+    }
+
+    #[async_trait]
+    impl AuditSink for RecordingSink {
+        fn name(&self) -> &str {
+            "recording"
+        }
+
+        async fn emit(&self, event: &AuditEvent) -> Result<(), AuditError> {
+            self.batches.lock().unwrap().push(vec![event.clone()]);
+            Ok(())
+        }
+
+        async fn emit_batch(&self, events: &[AuditEvent]) -> Result<(), AuditError> {
+            self.batches.lock().unwrap().push(events.to_vec());
+            Ok(())
+        }
+    }
+
+    /// Headers and body of a posted request
+    type PostedRequest = (Vec<(String, String)>, String);
+
+    /// Answers with the queued statuses, then 200
+    #[derive(Default)]
+    struct MockWebhookClient {
+        statuses: Mutex<Vec<u16>>,
+        requests: Mutex<Vec<PostedRequest>>,
+    }
+
+    #[async_trait]
+    impl WebhookHttpClient for MockWebhookClient {
+        async fn post_ndjson(
+            &self,
+            _url: &str,
+            headers: &[(String, String)],
+            body: String,
+        ) -> Result<u16, AuditError> {
+            self.requests.lock().unwrap().push((headers.to_vec(), body));
+            let mut statuses = self.statuses.lock().unwrap();
+            Ok(if statuses.is_empty() {
+                200
+            } else {
+                statuses.remove(0)
+            })
+        }
+    }
+
+    #[tokio::test]
+    async fn test_logger_delivers_batches_to_every_sink() {
+        let temp_dir = tempdir().unwrap();
+        let config = AuditConfig {
+            log_file: temp_dir.path().join("audit.jsonl"),
+            ..Default::default()
+        };
+        let first = Arc::new(RecordingSink::default());
+        let second = Arc::new(RecordingSink::default());
+        let dispatcher = AuditSinkDispatcher::start(
+            AuditSinkConfig {
+                batch_size: 2,
+                flush_interval: Duration::from_secs(60),
+                ..Default::default()
+            },
+            vec![first.clone(), second.clone()],
+        );
+        let logger = AuditLogger::new(config)
+            .await
+            .unwrap()
+            .with_sink_dispatcher(dispatcher.clone());
+
+        for ip in ["10.0.0.1", "10.0.0.2", "10.0.0.3"] {
+            logger
+                .log(events::auth_failure(ip, "bad key"))
+                .await
+                .unwrap();
+        }
+        dispatcher.flush().await;
+
+        for sink in [&first, &second] {
+            let batches = sink.batches.lock().unwrap();
+            let sizes: Vec<usize> = batches.iter().map(Vec::len).collect();
+            assert_eq!(sizes, vec![2, 1]);
+            assert_eq!(batches[1][0].client_ip.as_deref(), Some("10.0.0.3"));
+        }
+        // The log file still records every event
+        assert_eq!(logger.get_stats().await.unwrap().total_events, 3);
+        assert_eq!(dispatcher.dropped_events(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_full_queue_drops_events() {
+        struct StalledSink;
+
+        #[async_trait]
+        impl AuditSink for StalledSink {
+            fn name(&self) -> &str {
+                "stalled"
+            }
+
+            async fn emit(&self, _event: &AuditEvent) -> Result<(), AuditError> {
+                std::future::pending().await
+            }
+        }
+
+        let dispatcher = AuditSinkDispatcher::start(
+            AuditSinkConfig {
+                channel_capacity: 2,
+                batch_size: 1,
+                flush_interval: Duration::from_secs(60),
+            },
+            vec![Arc::new(StalledSink)],
+        );
+
+        // The first event stalls the task, the next two fill the queue
+        assert!(dispatcher.submit(events::auth_failure("10.0.0.1", "bad key")));
+        tokio::time::sleep(Duration::from_millis(20)).await;
+        assert!(dispatcher.submit(events::auth_failure("10.0.0.1", "bad key")));
+        assert!(dispatcher.submit(events::auth_failure("10.0.0.1", "bad key")));
+        assert!(!dispatcher.submit(events::auth_failure("10.0.0.1", "bad key")));
+        assert_eq!(dispatcher.dropped_events(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_webhook_posts_ndjson_and_retries() {
+        let client = Arc::new(MockWebhookClient::default());
+        client.statuses.lock().unwrap().extend([503, 429]);
+        let sink = HttpWebhookSink::with_client("https://siem.example.com/hec", client.clone())
+            .with_header("Authorization", "Splunk token")
+            .with_retries(2, Duration::ZERO);
+
+        let events = [
+            events::auth_failure("10.0.0.1", "bad key"),
+            events::key_used("key-1", "10.0.0.2"),
+        ];
+        sink.emit_batch(&events).await.unwrap();
+
+        let requests = client.requests.lock().unwrap();
+        assert_eq!(requests.len(), 3);
+        let (headers, body) = &requests[2];
+        assert_eq!(
+            headers,
+            &vec![("Authorization".to_string(), "Splunk token".to_string())]
+        );
+        let lines: Vec<AuditEvent> = body
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        assert_eq!(lines.len(), 2);
+        assert_eq!(lines[1].resource.as_deref(), Some("key-1"));
+        assert_eq!(sink.dead_letter_count(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_webhook_dead_letters_undeliverable_batches() {
+        let client = Arc::new(MockWebhookClient::default());
+        client.statuses.lock().unwrap().extend([500, 500, 400]);
+        let sink = HttpWebhookSink::with_client("https://siem.example.com/hec", client.clone())
+            .with_retries(1, Duration::ZERO);
+
+        let batch = [
+            events::auth_failure("10.0.0.1", "bad key"),
+            events::auth_failure("10.0.0.2", "bad key"),
+        ];
+        // Retries exhausted
+        assert!(matches!(
+            sink.emit_batch(&batch).await,
+            Err(AuditError::Sink(_))
+        ));
+        assert_eq!(client.requests.lock().unwrap().len(), 2);
+        assert_eq!(sink.dead_letter_count(), 2);
+
+        // Client errors aren't retried
+        assert!(
+            sink.emit(&events::key_used("key-1", "10.0.0.3"))
+                .await
+                .is_err()
+        );
+        assert_eq!(client.requests.lock().unwrap().len(), 3);
+        assert_eq!(sink.dead_letter_count(), 3);
+    }
+}
@@ -0,0 +1,109 @@
+//! Command-line tool for API key management
+//!
+//! Supports exporting keys to a file and importing them into another storage
+//! backend, e.g. to migrate between backends or seed a new environment.
+//! Exported files contain only salted secret hashes, never plain text keys.
+
+use anyhow::{Context, Result};
+use clap::{Parser, Subcommand, ValueEnum};
+use pulseengine_auth::{
+    ApiKey, AuthConfig, AuthenticationManager, KeyImportConflict, config::StorageConfig,
+};
+use std::path::PathBuf;
+
+#[derive(Parser)]
+#[command(name = "auth-manager")]
+#[command(about = "PulseEngine MCP API key management", long_about = None)]
+struct Cli {
+    /// Path to the encrypted key storage file (defaults to ~/.pulseengine/mcp-auth/keys.enc)
+    #[arg(long, global = true, env = "PULSEENGINE_AUTH_STORAGE")]
+    storage_path: Option<PathBuf>,
+
+    #[command(subcommand)]
+    command: Commands,
+}
+
+#[derive(Subcommand)]
+enum Commands {
+    /// Export all keys (without plain text secrets) to a JSON file
+    Export {
+        /// Output file
+        #[arg(long, short)]
+        output: PathBuf,
+    },
+
+    /// Import keys from a JSON file produced by `export`
+    Import {
+        /// Input file
+        #[arg(long, short)]
+        input: PathBuf,
+
+        /// What to do with keys whose ID already exists
+        #[arg(long, value_enum, default_value = "skip")]
+        on_conflict: ConflictArg,
+    },
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum ConflictArg {
+    Skip,
+    Overwrite,
+    Fail,
+}
+
+impl From<ConflictArg> for KeyImportConflict {
+    fn from(arg: ConflictArg) -> Self {
+        match arg {
+            ConflictArg::Skip => KeyImportConflict::Skip,
+            ConflictArg::Overwrite => KeyImportConflict::Overwrite,
+            ConflictArg::Fail => KeyImportConflict::Fail,
+        }
+    }
+}
+
+#[tokio::main]
+async fn main() -> Result<()> {
+    let cli = Cli::parse();
+
+    let mut config = AuthConfig::default();
+    if let Some(path) = cli.storage_path
+        && let StorageConfig::File { path: default, .. } = &mut config.storage
+    {
+        *default = path;
+    }
+    let manager = AuthenticationManager::new(config)
+        .await
+        .context("Failed to open key storage")?;
+
+    match cli.command {
+        Commands::Export { output } => {
+            let keys = manager.export_keys().await;
+            let json = serde_json::to_string_pretty(&keys)?;
+            tokio::fs::write(&output, json)
+                .await
+                .with_context(|| format!("Failed to write {}", output.display()))?;
+            println!("Exported {} keys to {}", keys.len(), output.display());
+        }
+        Commands::Import { input, on_conflict } => {
+            let json = tokio::fs::read_to_string(&input)
+                .await
+                .with_context(|| format!("Failed to read {}", input.display()))?;
+            let keys: Vec<ApiKey> = serde_json::from_str(&json)
+                .with_context(|| format!("Invalid key export file {}", input.display()))?;
+            let report = manager.import_keys(keys, on_conflict.into()).await?;
+
+            println!(
+                "Imported {} keys ({} overwritten, {} skipped, {} rejected)",
+                report.imported.len(),
+                report.overwritten.len(),
+                report.skipped.len(),
+                report.rejected.len()
+            );
+            for (id, reason) in &report.rejected {
+                eprintln!("  rejected {id}: {reason}");
+            }
+        }
+    }
+
+    Ok(())
+}
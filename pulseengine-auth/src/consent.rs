@@ -173,6 +173,10 @@ pub struct ConsentRecord {
     /// When consent expires (if applicable)
     pub expires_at: Option<DateTime<Utc>>,
 
+    /// How long a grant lasts in milliseconds; each grant restarts the clock
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ttl_millis: Option<u64>,
+
     /// Source of consent (web form, API, CLI, etc.)
     pub consent_source: String,
 
@@ -210,6 +214,7 @@ impl ConsentRecord {
             granted_at: None,
             withdrawn_at: None,
             expires_at: None,
+            ttl_millis: None,
             consent_source,
             source_ip: None,
             metadata: HashMap::new(),
@@ -235,6 +240,12 @@ impl ConsentRecord {
         self.updated_at = Utc::now();
     }
 
+    /// Mark a granted consent whose time limit has passed as expired
+    pub fn expire(&mut self) {
+        self.status = ConsentStatus::Expired;
+        self.updated_at = Utc::now();
+    }
+
     /// Deny consent
     pub fn deny(&mut self, source_ip: Option<String>) {
         self.status = ConsentStatus::Denied;
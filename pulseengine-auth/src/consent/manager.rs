@@ -8,7 +8,7 @@ use super::{
     LegalBasis,
 };
 use async_trait::async_trait;
-use chrono::Utc;
+use chrono::{DateTime, Utc};
 use serde_json;
 use std::collections::HashMap;
 use std::sync::Arc;
@@ -98,6 +98,13 @@ pub struct ConsentConfig {
     /// Default consent expiration in days (None = no expiration)
     pub default_expiration_days: Option<u32>,
 
+    /// Default time limit of a grant, taking precedence over
+    /// `default_expiration_days`
+    pub consent_ttl: Option<std::time::Duration>,
+
+    /// Time limits for specific consent types, overriding the defaults
+    pub consent_ttl_overrides: HashMap<ConsentType, std::time::Duration>,
+
     /// Require explicit consent for all operations
     pub require_explicit_consent: bool,
 
@@ -116,6 +123,8 @@ impl Default for ConsentConfig {
         Self {
             enabled: true,
             default_expiration_days: Some(365), // 1 year default
+            consent_ttl: None,
+            consent_ttl_overrides: HashMap::new(),
             require_explicit_consent: true,
             enable_audit_log: true,
             audit_log_path: None,
@@ -206,12 +215,13 @@ impl ConsentManager {
         }
 
         // Set expiration
-        if let Some(days) = request
+        let ttl = request
             .expires_in_days
-            .or(self.config.default_expiration_days)
-        {
-            let expires_at = Utc::now() + chrono::Duration::days(days as i64);
-            record.set_expiration(expires_at);
+            .map(|days| std::time::Duration::from_secs(u64::from(days) * 86_400))
+            .or_else(|| self.ttl_for(&request.consent_type));
+        if let Some(ttl) = ttl {
+            record.ttl_millis = Some(ttl.as_millis().try_into().unwrap_or(u64::MAX));
+            record.set_expiration(saturating_add(Utc::now(), ttl));
         }
 
         // Store consent record
@@ -273,8 +283,18 @@ impl ConsentManager {
 
         let previous_status = record.status.clone();
 
-        // Grant consent
+        // Grant consent, restarting its time limit
         record.grant(source_ip.clone());
+        if let Some(ttl) = record.ttl_millis {
+            record.set_expiration(saturating_add(
+                Utc::now(),
+                std::time::Duration::from_millis(ttl),
+            ));
+        }
+        let action = match previous_status {
+            ConsentStatus::Granted | ConsentStatus::Expired => "consent_renewed",
+            _ => "consent_granted",
+        };
 
         // Update storage
         let updated_data =
@@ -294,7 +314,7 @@ impl ConsentManager {
         // Create audit entry
         self.create_audit_entry(
             &record,
-            "consent_granted".to_string(),
+            action.to_string(),
             Some(previous_status),
             record.status.clone(),
             action_source,
@@ -390,38 +410,79 @@ impl ConsentManager {
         );
 
         // Try cache first
-        {
+        let cached = {
             let cache = self.consent_cache.read().await;
-            if let Some(record) = cache
+            cache
                 .values()
                 .find(|r| r.subject_id == subject_id && &r.consent_type == consent_type)
-            {
-                return Ok(record.is_valid());
-            }
-        }
+                .cloned()
+        };
 
-        // Load from storage
-        match self.storage.get(&consent_key).await {
-            Ok(consent_data) => {
-                let record: ConsentRecord = serde_json::from_str(&consent_data)
-                    .map_err(ConsentError::SerializationError)?;
+        let record = match cached {
+            Some(record) => record,
+            // Load from storage
+            None => match self.storage.get(&consent_key).await {
+                Ok(consent_data) => {
+                    let record: ConsentRecord = serde_json::from_str(&consent_data)
+                        .map_err(ConsentError::SerializationError)?;
+
+                    // Update cache
+                    {
+                        let mut cache = self.consent_cache.write().await;
+                        cache.insert(record.id.clone(), record.clone());
+                    }
 
-                // Update cache
-                {
-                    let mut cache = self.consent_cache.write().await;
-                    cache.insert(record.id.clone(), record.clone());
+                    record
                 }
-
-                Ok(record.is_valid())
-            }
-            Err(_) => {
-                if self.config.require_explicit_consent {
-                    Ok(false) // No consent found and explicit consent required
-                } else {
-                    Ok(true) // No consent found but explicit consent not required
+                Err(_) => {
+                    return if self.config.require_explicit_consent {
+                        Ok(false) // No consent found and explicit consent required
+                    } else {
+                        Ok(true) // No consent found but explicit consent not required
+                    };
                 }
+            },
+        };
+
+        if record.status == ConsentStatus::Granted && record.is_expired() {
+            self.expire_consent(&consent_key, record).await?;
+            return Ok(false);
+        }
+        Ok(record.is_valid())
+    }
+
+    /// Granted consents that expire within `within` from now, soonest first
+    ///
+    /// Applications can use this to ask subjects to renew their consent
+    /// before it lapses; granting it again restarts its time limit.
+    pub async fn consents_expiring_within(
+        &self,
+        within: std::time::Duration,
+    ) -> Result<Vec<ConsentRecord>, ConsentError> {
+        let now = Utc::now();
+        let horizon = saturating_add(now, within);
+
+        let all_keys = self
+            .storage
+            .list()
+            .await
+            .map_err(|e| ConsentError::StorageError(e.to_string()))?;
+
+        let mut expiring = Vec::new();
+        for key in all_keys {
+            if key.starts_with("consent:")
+                && let Ok(consent_data) = self.storage.get(&key).await
+                && let Ok(record) = serde_json::from_str::<ConsentRecord>(&consent_data)
+                && record.status == ConsentStatus::Granted
+                && let Some(expires_at) = record.expires_at
+                && now <= expires_at
+                && expires_at < horizon
+            {
+                expiring.push(record);
             }
         }
+        expiring.sort_by_key(|record| record.expires_at);
+        Ok(expiring)
     }
 
     /// Get consent summary for a subject
@@ -449,7 +510,14 @@ impl ConsentManager {
                 && let Ok(consent_data) = self.storage.get(&key).await
                 && let Ok(record) = serde_json::from_str::<ConsentRecord>(&consent_data)
             {
-                consents.insert(record.consent_type.clone(), record.status.clone());
+                // Granted consents past their time limit are reported as
+                // expired even before a check has recorded the transition
+                let status = if record.status == ConsentStatus::Granted && record.is_expired() {
+                    ConsentStatus::Expired
+                } else {
+                    record.status.clone()
+                };
+                consents.insert(record.consent_type.clone(), status);
 
                 if record.status == ConsentStatus::Pending {
                     pending_requests += 1;
@@ -528,6 +596,62 @@ impl ConsentManager {
             .collect()
     }
 
+    /// Record that a granted consent has passed its time limit
+    async fn expire_consent(
+        &self,
+        consent_key: &str,
+        mut record: ConsentRecord,
+    ) -> Result<(), ConsentError> {
+        record.expire();
+
+        let updated_data =
+            serde_json::to_string(&record).map_err(ConsentError::SerializationError)?;
+        self.storage
+            .set(consent_key, &updated_data)
+            .await
+            .map_err(|e| ConsentError::StorageError(e.to_string()))?;
+
+        {
+            let mut cache = self.consent_cache.write().await;
+            cache.insert(record.id.clone(), record.clone());
+        }
+
+        let mut details = HashMap::new();
+        if let Some(expires_at) = record.expires_at {
+            details.insert("expires_at".to_string(), expires_at.to_rfc3339());
+        }
+        self.create_audit_entry(
+            &record,
+            "consent_expired".to_string(),
+            Some(ConsentStatus::Granted),
+            ConsentStatus::Expired,
+            "consent_manager".to_string(),
+            None,
+            details,
+        )
+        .await?;
+
+        info!(
+            "Consent expired for subject {} with type {:?}",
+            record.subject_id, record.consent_type
+        );
+        Ok(())
+    }
+
+    /// Time limit of a new consent of `consent_type`
+    fn ttl_for(&self, consent_type: &ConsentType) -> Option<std::time::Duration> {
+        self.config
+            .consent_ttl_overrides
+            .get(consent_type)
+            .copied()
+            .or(self.config.consent_ttl)
+            .or_else(|| {
+                self.config
+                    .default_expiration_days
+                    .map(|days| std::time::Duration::from_secs(u64::from(days) * 86_400))
+            })
+    }
+
     /// Create an audit entry
     #[allow(clippy::too_many_arguments)]
     async fn create_audit_entry(
@@ -614,6 +738,14 @@ impl ConsentManager {
     }
 }
 
+/// `at` plus `duration`, saturating at the latest representable time
+fn saturating_add(at: DateTime<Utc>, duration: std::time::Duration) -> DateTime<Utc> {
+    chrono::Duration::from_std(duration)
+        .ok()
+        .and_then(|duration| at.checked_add_signed(duration))
+        .unwrap_or(DateTime::<Utc>::MAX_UTC)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -707,4 +839,126 @@ mod tests {
             .unwrap();
         assert!(!is_valid);
     }
+
+    fn request(consent_type: ConsentType) -> ConsentRequest {
+        ConsentRequest {
+            subject_id: "user123".to_string(),
+            consent_type,
+            legal_basis: LegalBasis::Consent,
+            purpose: "Time-limited processing".to_string(),
+            data_categories: vec![],
+            consent_source: "test".to_string(),
+            expires_in_days: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_consent_expires_after_ttl() {
+        let config = ConsentConfig {
+            consent_ttl_overrides: HashMap::from([(
+                ConsentType::Marketing,
+                std::time::Duration::from_millis(50),
+            )]),
+            ..Default::default()
+        };
+        let manager = ConsentManager::new(config, Arc::new(MemoryConsentStorage::new()));
+
+        manager
+            .request_consent(request(ConsentType::Marketing))
+            .await
+            .unwrap();
+        manager
+            .grant_consent("user123", &ConsentType::Marketing, None, "test".to_string())
+            .await
+            .unwrap();
+        assert!(
+            manager
+                .check_consent("user123", &ConsentType::Marketing)
+                .await
+                .unwrap()
+        );
+
+        tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+        assert!(
+            !manager
+                .check_consent("user123", &ConsentType::Marketing)
+                .await
+                .unwrap()
+        );
+
+        // The transition is audited once and persisted
+        let trail = manager.get_audit_trail("user123").await;
+        let expired: Vec<_> = trail
+            .iter()
+            .filter(|entry| entry.action == "consent_expired")
+            .collect();
+        assert_eq!(expired.len(), 1);
+        assert_eq!(expired[0].previous_status, Some(ConsentStatus::Granted));
+        assert_eq!(expired[0].new_status, ConsentStatus::Expired);
+        assert!(
+            !manager
+                .check_consent("user123", &ConsentType::Marketing)
+                .await
+                .unwrap()
+        );
+        assert_eq!(manager.get_audit_trail("user123").await.len(), trail.len());
+
+        let summary = manager.get_consent_summary("user123").await.unwrap();
+        assert_eq!(
+            summary.consents.get(&ConsentType::Marketing),
+            Some(&ConsentStatus::Expired)
+        );
+        assert!(!summary.is_valid);
+    }
+
+    #[tokio::test]
+    async fn test_consent_renewed_before_expiry() {
+        let config = ConsentConfig {
+            consent_ttl_overrides: HashMap::from([(
+                ConsentType::Marketing,
+                std::time::Duration::from_millis(600),
+            )]),
+            ..Default::default()
+        };
+        let manager = ConsentManager::new(config, Arc::new(MemoryConsentStorage::new()));
+
+        for consent_type in [ConsentType::Marketing, ConsentType::Analytics] {
+            manager
+                .request_consent(request(consent_type.clone()))
+                .await
+                .unwrap();
+            manager
+                .grant_consent("user123", &consent_type, None, "test".to_string())
+                .await
+                .unwrap();
+        }
+
+        // Only the short-lived consent is due for renewal
+        let expiring = manager
+            .consents_expiring_within(std::time::Duration::from_secs(60))
+            .await
+            .unwrap();
+        assert_eq!(expiring.len(), 1);
+        assert_eq!(expiring[0].consent_type, ConsentType::Marketing);
+
+        tokio::time::sleep(std::time::Duration::from_millis(400)).await;
+        let renewed = manager
+            .grant_consent("user123", &ConsentType::Marketing, None, "test".to_string())
+            .await
+            .unwrap();
+        assert!(renewed.expires_at > expiring[0].expires_at);
+
+        // Past the original expiry but within the renewed one
+        tokio::time::sleep(std::time::Duration::from_millis(400)).await;
+        assert!(
+            manager
+                .check_consent("user123", &ConsentType::Marketing)
+                .await
+                .unwrap()
+        );
+
+        let trail = manager.get_audit_trail("user123").await;
+        assert_eq!(trail.last().unwrap().action, "consent_renewed");
+        assert!(trail.iter().all(|entry| entry.action != "consent_expired"));
+    }
 }
@@ -130,7 +130,7 @@ pub fn generate_master_key_for_application(
 }
 
 /// Decode a base64-encoded master key
-fn decode_master_key(master_key_b64: &str) -> Result<[u8; 32], KeyDerivationError> {
+pub fn decode_master_key(master_key_b64: &str) -> Result<[u8; 32], KeyDerivationError> {
     let key_bytes = URL_SAFE_NO_PAD
         .decode(master_key_b64)
         .map_err(|e| KeyDerivationError::InvalidInput(format!("Invalid master key: {}", e)))?;
@@ -28,6 +28,12 @@ pub enum JwtError {
     #[error("Invalid token format")]
     InvalidFormat,
 
+    #[error("Token issuer is not accepted (accepted: {})", .0.join(", "))]
+    InvalidIssuer(Vec<String>),
+
+    #[error("Token audience is not accepted (accepted: {})", .0.join(", "))]
+    InvalidAudience(Vec<String>),
+
     #[error("Missing claims: {0}")]
     MissingClaims(String),
 
@@ -100,6 +106,14 @@ pub struct JwtConfig {
     /// Default audience
     pub audience: Vec<String>,
 
+    /// Further issuers whose tokens validate, e.g. other identity providers
+    /// in a federated setup; tokens from `issuer` are always accepted
+    pub accepted_issuers: Vec<String>,
+
+    /// Further audiences a token may be addressed to; a token is accepted
+    /// when any of its audiences is here or in `audience`
+    pub accepted_audiences: Vec<String>,
+
     /// Signing algorithm
     pub algorithm: Algorithm,
 
@@ -121,6 +135,8 @@ impl Default for JwtConfig {
         Self {
             issuer: "pulseengine-auth".to_string(),
             audience: vec!["mcp-server".to_string()],
+            accepted_issuers: Vec::new(),
+            accepted_audiences: Vec::new(),
             algorithm: Algorithm::HS256,
             signing_secret: b"default-secret-change-in-production".to_vec(),
             access_token_lifetime: Duration::hours(1),
@@ -136,6 +152,8 @@ pub struct JwtManager {
     encoding_key: EncodingKey,
     decoding_key: DecodingKey,
     validation: Validation,
+    accepted_issuers: Vec<String>,
+    accepted_audiences: Vec<String>,
     /// Blacklisted token JTIs
     blacklist: tokio::sync::RwLock<HashSet<String>>,
 }
@@ -169,9 +187,15 @@ impl JwtManager {
             _ => return Err(JwtError::Validation("Unsupported algorithm".to_string())),
         };
 
+        let accepted_issuers = merge_accepted(
+            std::slice::from_ref(&config.issuer),
+            &config.accepted_issuers,
+        );
+        let accepted_audiences = merge_accepted(&config.audience, &config.accepted_audiences);
+
         let mut validation = Validation::new(config.algorithm);
-        validation.set_audience(&config.audience);
-        validation.set_issuer(&[&config.issuer]);
+        validation.set_audience(&accepted_audiences);
+        validation.set_issuer(&accepted_issuers);
         validation.validate_exp = true;
         validation.validate_nbf = true;
 
@@ -180,6 +204,8 @@ impl JwtManager {
             encoding_key,
             decoding_key,
             validation,
+            accepted_issuers,
+            accepted_audiences,
             blacklist: tokio::sync::RwLock::new(HashSet::new()),
         })
     }
@@ -255,6 +281,12 @@ impl JwtManager {
             .map_err(|e| match e.kind() {
                 jsonwebtoken::errors::ErrorKind::ExpiredSignature => JwtError::Expired,
                 jsonwebtoken::errors::ErrorKind::InvalidToken => JwtError::InvalidFormat,
+                jsonwebtoken::errors::ErrorKind::InvalidIssuer => {
+                    JwtError::InvalidIssuer(self.accepted_issuers.clone())
+                }
+                jsonwebtoken::errors::ErrorKind::InvalidAudience => {
+                    JwtError::InvalidAudience(self.accepted_audiences.clone())
+                }
                 _ => JwtError::Validation(e.to_string()),
             })?;
 
@@ -282,11 +314,7 @@ impl JwtManager {
         }
 
         // Extract permissions from roles
-        let permissions: Vec<String> = claims
-            .roles
-            .iter()
-            .flat_map(|role| self.get_permissions_for_role(role))
-            .collect();
+        let permissions: Vec<String> = claims.roles.iter().flat_map(permissions_for_role).collect();
 
         Ok(AuthContext {
             user_id: Some(claims.sub),
@@ -357,34 +385,6 @@ impl JwtManager {
         initial_size
     }
 
-    /// Get permissions for a role (helper method)
-    fn get_permissions_for_role(&self, role: &Role) -> Vec<String> {
-        match role {
-            Role::Admin => vec![
-                "admin.*".to_string(),
-                "key.*".to_string(),
-                "user.*".to_string(),
-                "system.*".to_string(),
-            ],
-            Role::Operator => vec![
-                "device.*".to_string(),
-                "monitor.*".to_string(),
-                "key.create".to_string(),
-                "key.list".to_string(),
-            ],
-            Role::Monitor => vec![
-                "monitor.*".to_string(),
-                "health.check".to_string(),
-                "status.read".to_string(),
-            ],
-            Role::Device { allowed_devices } => allowed_devices
-                .iter()
-                .map(|device| format!("device.{}", device))
-                .collect(),
-            Role::Custom { permissions } => permissions.clone(),
-        }
-    }
-
     /// Get token info without validating signature (for debugging)
     pub fn decode_token_info(&self, token: &str) -> Result<TokenClaims, JwtError> {
         let mut validation = Validation::new(self.config.algorithm);
@@ -400,6 +400,45 @@ impl JwtManager {
     }
 }
 
+/// Permissions granted by a role in token-derived auth contexts
+pub(crate) fn permissions_for_role(role: &Role) -> Vec<String> {
+    match role {
+        Role::Admin => vec![
+            "admin.*".to_string(),
+            "key.*".to_string(),
+            "user.*".to_string(),
+            "system.*".to_string(),
+        ],
+        Role::Operator => vec![
+            "device.*".to_string(),
+            "monitor.*".to_string(),
+            "key.create".to_string(),
+            "key.list".to_string(),
+        ],
+        Role::Monitor => vec![
+            "monitor.*".to_string(),
+            "health.check".to_string(),
+            "status.read".to_string(),
+        ],
+        Role::Device { allowed_devices } => allowed_devices
+            .iter()
+            .map(|device| format!("device.{}", device))
+            .collect(),
+        Role::Custom { permissions } => permissions.clone(),
+    }
+}
+
+/// The configured values followed by any extra accepted ones, without duplicates
+fn merge_accepted(configured: &[String], extra: &[String]) -> Vec<String> {
+    let mut accepted = Vec::with_capacity(configured.len() + extra.len());
+    for value in configured.iter().chain(extra) {
+        if !accepted.contains(value) {
+            accepted.push(value.clone());
+        }
+    }
+    accepted
+}
+
 /// JWT token pair (access + refresh)
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TokenPair {
@@ -569,4 +608,75 @@ mod tests {
         assert_eq!(auth_context.api_key_id, Some("key123".to_string()));
         assert!(!auth_context.permissions.is_empty());
     }
+
+    async fn access_token(issuer: &str, audience: &str) -> String {
+        let issuing = JwtManager::new(JwtConfig {
+            issuer: issuer.to_string(),
+            audience: vec![audience.to_string()],
+            ..JwtConfig::default()
+        })
+        .unwrap();
+        issuing
+            .generate_access_token("user".to_string(), vec![], None, None, None, vec![])
+            .await
+            .unwrap()
+    }
+
+    fn federated_manager() -> JwtManager {
+        JwtManager::new(JwtConfig {
+            issuer: "https://idp-a.example".to_string(),
+            accepted_issuers: vec![
+                "https://idp-a.example".to_string(),
+                "https://idp-b.example".to_string(),
+            ],
+            accepted_audiences: vec!["mcp-staging".to_string()],
+            ..JwtConfig::default()
+        })
+        .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_jwt_accepts_any_listed_issuer() {
+        let jwt_manager = federated_manager();
+
+        let token = access_token("https://idp-b.example", "mcp-server").await;
+        let token_data = jwt_manager.validate_token(&token).await.unwrap();
+        assert_eq!(token_data.claims.iss, "https://idp-b.example");
+
+        // The manager's own tokens still validate
+        let own = jwt_manager
+            .generate_access_token("user".to_string(), vec![], None, None, None, vec![])
+            .await
+            .unwrap();
+        assert!(jwt_manager.validate_token(&own).await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_jwt_rejects_unlisted_issuer() {
+        let jwt_manager = federated_manager();
+
+        let token = access_token("https://idp-c.example", "mcp-server").await;
+        let error = jwt_manager.validate_token(&token).await.unwrap_err();
+        assert!(matches!(&error, JwtError::InvalidIssuer(accepted) if accepted.len() == 2));
+        assert_eq!(
+            error.to_string(),
+            "Token issuer is not accepted (accepted: https://idp-a.example, https://idp-b.example)"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_jwt_accepts_any_listed_audience() {
+        let jwt_manager = federated_manager();
+
+        let token = access_token("https://idp-a.example", "mcp-staging").await;
+        assert!(jwt_manager.validate_token(&token).await.is_ok());
+
+        let token = access_token("https://idp-a.example", "other-service").await;
+        let error = jwt_manager.validate_token(&token).await.unwrap_err();
+        assert!(matches!(
+            &error,
+            JwtError::InvalidAudience(accepted)
+                if accepted == &["mcp-server".to_string(), "mcp-staging".to_string()]
+        ));
+    }
 }
@@ -113,6 +113,9 @@
 //! - `monitoring` - Security monitoring, event logging, and health checks
 //! - `vault` - Enterprise vault integration (Infisical, HashiCorp Vault, etc.)
 //! - `consent` - GDPR/CCPA compliance and consent management
+//! - `jwks` - JWT validation against remote JWKS endpoints over HTTP
+//! - `oauth-client` - HTTP token requests for the OAuth authorization code flow client
+//! - `audit-webhook` - Forwarding audit events to HTTP webhooks
 //!
 //! Enable features in Cargo.toml:
 //! ```toml
@@ -121,10 +124,13 @@
 //! ```
 
 pub mod audit;
+pub mod audit_bundle;
+pub mod audit_sink;
 pub mod config;
 #[cfg(feature = "consent")]
 pub mod consent;
 pub mod crypto;
+pub mod jwks;
 pub mod jwt;
 pub mod manager;
 #[cfg(feature = "vault")]
@@ -154,17 +160,20 @@ pub use consent::{
 };
 pub use manager::{
     AuthenticationManager, RateLimitStats, RoleRateLimitConfig, RoleRateLimitStats,
-    ValidationConfig,
+    ToolRateLimitConfig, ToolRateLimitStats, ValidationConfig,
 };
 #[cfg(feature = "vault")]
-pub use manager_vault::{VaultAuthManagerError, VaultAuthenticationManager, VaultStatus};
+pub use manager_vault::{
+    SecretRotationHook, VaultAuthManagerError, VaultAuthenticationManager, VaultSecrets,
+    VaultStatus,
+};
 pub use middleware::{
     AuthExtractionError, AuthMiddlewareError, McpAuthConfig, McpAuthMiddleware, SessionMiddleware,
     SessionMiddlewareConfig, SessionMiddlewareError, SessionRequestContext,
 };
 pub use models::{
-    ApiCompletenessCheck, ApiKey, AuthContext, AuthResult, KeyCreationRequest, KeyUsageStats, Role,
-    SecureApiKey,
+    ApiCompletenessCheck, ApiKey, AuthContext, AuthResult, KeyCreationRequest, KeyImportConflict,
+    KeyImportReport, KeyRotation, KeyUsageStats, Role, RotatedSecret, SecureApiKey, parse_ip_range,
 };
 #[cfg(feature = "monitoring")]
 pub use monitoring::{
@@ -181,13 +190,14 @@ pub use security::{
     SecurityValidationError, SecurityViolation,
 };
 pub use session::{
-    MemorySessionStorage, Session, SessionConfig, SessionError, SessionManager, SessionStats,
-    SessionStorage,
+    CleanupSchedule, MemorySessionStorage, PersistentSessionStorage, Session, SessionConfig,
+    SessionError, SessionManager, SessionStats, SessionStorage,
 };
 pub use storage::{EnvironmentStorage, FileStorage, StorageBackend};
 pub use transport::{
     AuthExtractionResult, AuthExtractor, HttpAuthConfig, HttpAuthExtractor, StdioAuthConfig,
-    StdioAuthExtractor, TransportAuthContext, WebSocketAuthConfig, WebSocketAuthExtractor,
+    StdioAuthExtractor, TransportAuthContext, TransportAuthError, WebSocketAuthConfig,
+    WebSocketAuthExtractor,
 };
 #[cfg(feature = "vault")]
 pub use vault::{VaultClientInfo, VaultConfig, VaultError, VaultIntegration, VaultType};
@@ -11,7 +11,7 @@ use chrono::{DateTime, Utc};
 use std::collections::HashMap;
 use std::sync::Arc;
 use thiserror::Error;
-use tokio::sync::RwLock;
+use tokio::sync::{Mutex, RwLock};
 use tracing::{debug, error, info, warn};
 
 /// Simple request context for authentication
@@ -34,6 +34,12 @@ pub enum AuthError {
 
     #[error("Validation error: {0}")]
     Validation(String),
+
+    #[error("API key {0} has used up its maximum number of uses")]
+    KeyExhausted(String),
+
+    #[error("IP address {0} not allowed for this key")]
+    IpNotAllowed(String),
 }
 
 /// Authentication manager with comprehensive key management
@@ -50,12 +56,19 @@ pub struct AuthenticationManager {
     rate_limit_state: Arc<RwLock<HashMap<String, RateLimitState>>>,
     /// Per-role rate limiting state (role_key -> IP -> state)
     role_rate_limit_state: Arc<RwLock<HashMap<String, HashMap<String, RoleRateLimitStats>>>>,
+    /// Per-tool rate limiting state (tool name -> caller -> bucket)
+    tool_rate_limit_state: Arc<RwLock<HashMap<String, HashMap<String, ToolBucket>>>>,
     /// Audit logger for security events
     audit_logger: Arc<AuditLogger>,
     /// JWT manager for token-based authentication
     jwt_manager: Arc<JwtManager>,
+    /// Background task expiring rotated secrets, while running
+    background_task: Mutex<Option<tokio::task::JoinHandle<()>>>,
 }
 
+/// How often the background task drops secrets whose rotation overlap ended
+const ROTATION_CLEANUP_INTERVAL: std::time::Duration = std::time::Duration::from_secs(60);
+
 /// Rate limiting state for failed authentication attempts
 #[derive(Debug, Clone)]
 pub struct RateLimitState {
@@ -84,6 +97,38 @@ pub struct RoleRateLimitConfig {
     pub cooldown_duration_minutes: u64,
 }
 
+/// Per-tool rate limiting configuration
+///
+/// Each caller gets a token bucket per tool holding up to
+/// `max_requests_per_window` tokens, refilled at that many per window.
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct ToolRateLimitConfig {
+    /// Maximum requests per time window
+    pub max_requests_per_window: u32,
+    /// Time window duration in seconds
+    pub window_duration_seconds: u64,
+}
+
+/// Token bucket of one caller for one tool
+#[derive(Debug, Clone)]
+struct ToolBucket {
+    tokens: f64,
+    refilled_at: DateTime<Utc>,
+    blocked_requests: u64,
+    total_requests: u64,
+}
+
+impl ToolBucket {
+    /// Add the tokens earned since the last refill
+    fn refill(&mut self, config: &ToolRateLimitConfig, now: DateTime<Utc>) {
+        let capacity = f64::from(config.max_requests_per_window);
+        let per_second = capacity / config.window_duration_seconds.max(1) as f64;
+        let elapsed = (now - self.refilled_at).num_milliseconds().max(0) as f64 / 1000.0;
+        self.tokens = (self.tokens + elapsed * per_second).min(capacity);
+        self.refilled_at = now;
+    }
+}
+
 /// Validation configuration for rate limiting and security
 #[derive(Debug, Clone)]
 pub struct ValidationConfig {
@@ -95,12 +140,14 @@ pub struct ValidationConfig {
     pub block_duration_minutes: u64,
     /// Session timeout (minutes)
     pub session_timeout_minutes: u64,
-    /// Enable strict IP validation
+    /// Enforce per-key IP whitelists and denylists
     pub strict_ip_validation: bool,
     /// Enable role-based rate limiting
     pub enable_role_based_rate_limiting: bool,
     /// Per-role rate limiting configurations
     pub role_rate_limits: std::collections::HashMap<String, RoleRateLimitConfig>,
+    /// Per-tool rate limiting configurations, keyed by tool name
+    pub tool_rate_limits: std::collections::HashMap<String, ToolRateLimitConfig>,
 }
 
 impl Default for ValidationConfig {
@@ -166,6 +213,7 @@ impl Default for ValidationConfig {
             strict_ip_validation: true,
             enable_role_based_rate_limiting: true,
             role_rate_limits,
+            tool_rate_limits: std::collections::HashMap::new(),
         }
     }
 }
@@ -181,6 +229,9 @@ pub struct RateLimitStats {
     pub total_failed_attempts: u64,
     /// Role-based rate limiting statistics
     pub role_stats: std::collections::HashMap<String, RoleRateLimitStats>,
+    /// Per-tool rate limiting statistics
+    #[serde(default)]
+    pub tool_stats: std::collections::HashMap<String, ToolRateLimitStats>,
 }
 
 /// Per-role rate limiting statistics
@@ -200,6 +251,19 @@ pub struct RoleRateLimitStats {
     pub last_window_start: Option<DateTime<Utc>>,
 }
 
+/// Per-tool rate limiting statistics
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct ToolRateLimitStats {
+    /// Number of callers with a bucket for this tool
+    pub tracked_callers: usize,
+    /// Tokens currently taken from the buckets, summed over callers
+    pub consumed_tokens: f64,
+    /// Requests blocked due to rate limits
+    pub blocked_requests: u64,
+    /// Total requests processed
+    pub total_requests: u64,
+}
+
 impl AuthenticationManager {
     pub async fn new(config: AuthConfig) -> Result<Self, AuthError> {
         // Create storage backend
@@ -227,8 +291,10 @@ impl AuthenticationManager {
             api_keys_cache: Arc::new(RwLock::new(HashMap::new())),
             rate_limit_state: Arc::new(RwLock::new(HashMap::new())),
             role_rate_limit_state: Arc::new(RwLock::new(HashMap::new())),
+            tool_rate_limit_state: Arc::new(RwLock::new(HashMap::new())),
             audit_logger,
             jwt_manager,
+            background_task: Mutex::new(None),
             config,
         };
 
@@ -269,8 +335,10 @@ impl AuthenticationManager {
             api_keys_cache: Arc::new(RwLock::new(HashMap::new())),
             rate_limit_state: Arc::new(RwLock::new(HashMap::new())),
             role_rate_limit_state: Arc::new(RwLock::new(HashMap::new())),
+            tool_rate_limit_state: Arc::new(RwLock::new(HashMap::new())),
             audit_logger,
             jwt_manager,
+            background_task: Mutex::new(None),
             config,
         }
     }
@@ -304,8 +372,10 @@ impl AuthenticationManager {
             api_keys_cache: Arc::new(RwLock::new(HashMap::new())),
             rate_limit_state: Arc::new(RwLock::new(HashMap::new())),
             role_rate_limit_state: Arc::new(RwLock::new(HashMap::new())),
+            tool_rate_limit_state: Arc::new(RwLock::new(HashMap::new())),
             audit_logger,
             jwt_manager,
+            background_task: Mutex::new(None),
             config,
         };
 
@@ -333,7 +403,33 @@ impl AuthenticationManager {
         expires_at: Option<DateTime<Utc>>,
         ip_whitelist: Option<Vec<String>>,
     ) -> Result<ApiKey, AuthError> {
-        let key = ApiKey::new(name, role, expires_at, ip_whitelist.unwrap_or_default());
+        self.create_api_key_from_request(KeyCreationRequest {
+            name,
+            role,
+            expires_at,
+            ip_whitelist,
+            ip_denylist: None,
+            max_uses: None,
+        })
+        .await
+    }
+
+    /// Create a new API key from a creation request
+    ///
+    /// With `max_uses` set, the key stops validating after that many
+    /// successful validations, e.g. for a single-use agent credential.
+    pub async fn create_api_key_from_request(
+        &self,
+        request: KeyCreationRequest,
+    ) -> Result<ApiKey, AuthError> {
+        let ip_whitelist = request.ip_whitelist.unwrap_or_default();
+        let ip_denylist = request.ip_denylist.unwrap_or_default();
+        validate_ip_entries(&ip_whitelist)?;
+        validate_ip_entries(&ip_denylist)?;
+
+        let mut key = ApiKey::new(request.name, request.role, request.expires_at, ip_whitelist);
+        key.ip_denylist = ip_denylist;
+        key.max_uses = request.max_uses;
 
         // Save to storage
         self.storage
@@ -389,18 +485,22 @@ impl AuthenticationManager {
         let key = {
             let cache = self.api_keys_cache.read().await;
 
-            // Find key by verifying the provided secret against stored hashes
-            cache
-                .values()
-                .find(|key| {
-                    // Use secure verification if available, otherwise fallback to plain text
-                    key.verify_key(key_secret).unwrap_or_default()
-                })
-                .cloned()
+            // Find key by verifying the provided secret against stored hashes,
+            // falling back to secrets still inside a rotation overlap
+            cache.values().find_map(|key| {
+                // Use secure verification if available, otherwise fallback to plain text
+                if key.verify_key(key_secret).unwrap_or_default() {
+                    Some((key.clone(), false))
+                } else if key.verify_previous_secret(key_secret).unwrap_or_default() {
+                    Some((key.clone(), true))
+                } else {
+                    None
+                }
+            })
         };
 
-        let key = match key {
-            Some(key) => key,
+        let (key, used_previous_secret) = match key {
+            Some(found) => found,
             None => {
                 self.record_failed_attempt(client_ip).await;
 
@@ -413,14 +513,18 @@ impl AuthenticationManager {
         };
 
         // Validate the key
-        if let Err(reason) = self.validate_key_security(&key, client_ip) {
+        if let Err(error) = self.validate_key_security(&key, client_ip) {
             self.record_failed_attempt(client_ip).await;
 
             // Log authentication failure with reason
+            let reason = match &error {
+                AuthError::Failed(reason) => reason.clone(),
+                error => error.to_string(),
+            };
             let audit_event = events::auth_failure(client_ip, &reason);
             let _ = self.audit_logger.log(audit_event).await;
 
-            return Err(AuthError::Failed(reason));
+            return Err(error);
         }
 
         // Check role-based rate limiting
@@ -445,27 +549,57 @@ impl AuthenticationManager {
             )));
         }
 
-        // Clear any failed attempts for this IP
-        let mut updated_key = key.clone();
+        // Update key usage under the cache lock, so concurrent validations
+        // can't take a limited-use key past its maximum
+        {
+            let mut cache = self.api_keys_cache.write().await;
+            let mut updated_key = cache.get(&key.id).cloned().unwrap_or_else(|| key.clone());
 
-        self.clear_failed_attempts(client_ip).await;
+            if updated_key.is_exhausted() {
+                drop(cache);
+                self.record_failed_attempt(client_ip).await;
 
-        // Update key usage
-        updated_key.mark_used();
+                let audit_event = events::auth_failure(client_ip, "API key uses exhausted");
+                let _ = self.audit_logger.log(audit_event).await;
 
-        // Update in storage and cache
-        if let Err(e) = self.storage.save_key(&updated_key).await {
-            warn!("Failed to update key usage statistics: {}", e);
-        } else {
-            let mut cache = self.api_keys_cache.write().await;
-            cache.insert(updated_key.id.clone(), updated_key.clone());
+                return Err(AuthError::KeyExhausted(key.id));
+            }
+
+            updated_key.mark_used();
+            if used_previous_secret && let Some(previous) = updated_key.previous_secret.as_mut() {
+                previous.usage_count += 1;
+            }
+
+            // Update in storage and cache
+            if let Err(e) = self.storage.save_key(&updated_key).await {
+                if updated_key.max_uses.is_some() {
+                    // An unpersisted use could be replayed after a restart
+                    return Err(AuthError::Storage(format!(
+                        "Failed to record use of limited-use key: {}",
+                        e
+                    )));
+                }
+                warn!("Failed to update key usage statistics: {}", e);
+            } else {
+                cache.insert(updated_key.id.clone(), updated_key);
+            }
         }
 
+        // Clear any failed attempts for this IP
+        self.clear_failed_attempts(client_ip).await;
+
         // Log successful authentication and key usage
         let auth_event = events::auth_success(&key.id, client_ip);
         let _ = self.audit_logger.log(auth_event).await;
 
-        let key_usage_event = events::key_used(&key.id, client_ip);
+        let mut key_usage_event = events::key_used(&key.id, client_ip);
+        if used_previous_secret {
+            debug!("API key {} used its pre-rotation secret", key.id);
+            key_usage_event = key_usage_event.with_metadata(
+                "secret".to_string(),
+                serde_json::Value::String("previous".to_string()),
+            );
+        }
         let _ = self.audit_logger.log(key_usage_event).await;
 
         // Return valid auth context
@@ -538,6 +672,79 @@ impl AuthenticationManager {
         Ok(removed)
     }
 
+    /// Rotate an API key's secret without downtime
+    ///
+    /// A new secret is generated for the same key id. The old secret keeps
+    /// validating until `overlap` has passed, after which it is dropped by
+    /// [`cleanup_expired_rotations`](Self::cleanup_expired_rotations) (run
+    /// periodically by the background tasks) and rejected in any case.
+    pub async fn rotate_api_key(
+        &self,
+        key_id: &str,
+        overlap: chrono::Duration,
+    ) -> Result<KeyRotation, AuthError> {
+        let mut key = self
+            .get_key(key_id)
+            .await
+            .ok_or_else(|| AuthError::Failed("API key not found".to_string()))?;
+
+        let old_secret = key.key.clone();
+        let old_secret_valid_until = Utc::now() + overlap;
+        let new_secret = key.rotate_secret(old_secret_valid_until);
+        self.update_key(key).await?;
+
+        let audit_event = AuditEvent::new(
+            AuditEventType::KeyUpdated,
+            AuditSeverity::Info,
+            "key_management".to_string(),
+            format!(
+                "API key {} rotated, old secret valid until {}",
+                key_id,
+                old_secret_valid_until.format("%Y-%m-%d %H:%M:%S UTC")
+            ),
+        )
+        .with_resource(key_id.to_string());
+        let _ = self.audit_logger.log(audit_event).await;
+
+        info!("Rotated API key: {}", key_id);
+        Ok(KeyRotation {
+            key_id: key_id.to_string(),
+            new_secret,
+            old_secret,
+            old_secret_valid_until,
+        })
+    }
+
+    /// Switch to a new base64-encoded master key
+    ///
+    /// Stored keys are re-encrypted under it and reloaded, so they stay
+    /// readable once the new key is the only one configured. On failure the
+    /// previous master key stays in use.
+    pub async fn rotate_master_key(&self, master_key: &str) -> Result<(), AuthError> {
+        let master_key = crate::crypto::keys::decode_master_key(master_key)
+            .map_err(|e| AuthError::Config(e.to_string()))?;
+        self.storage
+            .rotate_master_key(&master_key)
+            .await
+            .map_err(|e| AuthError::Storage(e.to_string()))?;
+        self.refresh_cache().await?;
+
+        let audit_event = AuditEvent::new(
+            AuditEventType::ConfigurationChanged,
+            AuditSeverity::Info,
+            "key_management".to_string(),
+            "Master key rotated".to_string(),
+        );
+        let _ = self.audit_logger.log(audit_event).await;
+        info!("Rotated master key");
+        Ok(())
+    }
+
+    /// Drop previous secrets whose rotation overlap has ended
+    pub async fn cleanup_expired_rotations(&self) -> Result<u32, AuthError> {
+        expire_rotated_secrets(self.storage.as_ref(), &self.api_keys_cache).await
+    }
+
     /// Check if an IP is currently rate limited
     async fn check_rate_limit(&self, client_ip: &str) -> Option<DateTime<Utc>> {
         let rate_limits = self.rate_limit_state.read().await;
@@ -607,29 +814,25 @@ impl AuthenticationManager {
     }
 
     /// Validate an API key's security properties
-    fn validate_key_security(&self, key: &ApiKey, client_ip: &str) -> Result<(), String> {
+    fn validate_key_security(&self, key: &ApiKey, client_ip: &str) -> Result<(), AuthError> {
         // Check if key is active
         if !key.active {
-            return Err("API key is disabled".to_string());
+            return Err(AuthError::Failed("API key is disabled".to_string()));
         }
 
         // Check if key has expired
         if let Some(expires_at) = key.expires_at
             && Utc::now() > expires_at
         {
-            return Err("API key has expired".to_string());
+            return Err(AuthError::Failed("API key has expired".to_string()));
         }
 
-        // Check IP whitelist
-        if self.validation_config.strict_ip_validation && !key.ip_whitelist.is_empty() {
-            let is_ip_allowed = key.ip_whitelist.iter().any(|allowed_ip| {
-                // Simple IP matching (can be enhanced with CIDR support)
-                allowed_ip == client_ip || allowed_ip == "*"
-            });
-
-            if !is_ip_allowed {
-                return Err(format!("IP address {client_ip} not allowed for this key"));
-            }
+        // Check IP whitelist and denylist; "unknown" doesn't parse and only
+        // passes keys without a whitelist
+        if self.validation_config.strict_ip_validation
+            && !key.is_ip_allowed(client_ip.trim().parse().ok())
+        {
+            return Err(AuthError::IpNotAllowed(client_ip.to_string()));
         }
 
         Ok(())
@@ -667,6 +870,7 @@ impl AuthenticationManager {
     pub async fn get_rate_limit_stats(&self) -> RateLimitStats {
         let rate_limits = self.rate_limit_state.read().await;
         let role_states = self.role_rate_limit_state.read().await;
+        let tool_states = self.tool_rate_limit_state.read().await;
         let now = Utc::now();
 
         let mut stats = RateLimitStats {
@@ -674,6 +878,7 @@ impl AuthenticationManager {
             currently_blocked_ips: 0,
             total_failed_attempts: 0,
             role_stats: std::collections::HashMap::new(),
+            tool_stats: std::collections::HashMap::new(),
         };
 
         for state in rate_limits.values() {
@@ -718,6 +923,27 @@ impl AuthenticationManager {
             stats.role_stats.insert(role_key.clone(), role_statistics);
         }
 
+        for (tool_name, buckets) in tool_states.iter() {
+            let Some(config) = self.validation_config.tool_rate_limits.get(tool_name) else {
+                continue;
+            };
+            let mut tool_statistics = ToolRateLimitStats {
+                tracked_callers: buckets.len(),
+                consumed_tokens: 0.0,
+                blocked_requests: 0,
+                total_requests: 0,
+            };
+            for bucket in buckets.values() {
+                let mut bucket = bucket.clone();
+                bucket.refill(config, now);
+                tool_statistics.consumed_tokens +=
+                    f64::from(config.max_requests_per_window) - bucket.tokens;
+                tool_statistics.blocked_requests += bucket.blocked_requests;
+                tool_statistics.total_requests += bucket.total_requests;
+            }
+            stats.tool_stats.insert(tool_name.clone(), tool_statistics);
+        }
+
         stats
     }
 
@@ -884,6 +1110,70 @@ impl AuthenticationManager {
         Ok(false) // Not rate limited
     }
 
+    /// Check if a tool call should be rate limited
+    ///
+    /// Consults the tool's own limit in [`ValidationConfig::tool_rate_limits`],
+    /// returning `true` when it is exceeded. Limits apply per caller, identified
+    /// by API key id or else user id. Role limits are not consulted here: the
+    /// request was already counted against them in [`Self::validate_api_key`].
+    pub async fn check_tool_rate_limit(
+        &self,
+        auth: &AuthContext,
+        tool_name: &str,
+    ) -> Result<bool, AuthError> {
+        let caller = auth
+            .api_key_id
+            .as_deref()
+            .or(auth.user_id.as_deref())
+            .unwrap_or("anonymous");
+        Ok(self.take_tool_token(tool_name, caller).await)
+    }
+
+    /// Take a token from the caller's bucket for `tool_name`, returning `true`
+    /// when the bucket is empty
+    async fn take_tool_token(&self, tool_name: &str, caller: &str) -> bool {
+        let Some(config) = self.validation_config.tool_rate_limits.get(tool_name) else {
+            return false;
+        };
+
+        let now = Utc::now();
+        let mut tool_states = self.tool_rate_limit_state.write().await;
+        let bucket = tool_states
+            .entry(tool_name.to_string())
+            .or_default()
+            .entry(caller.to_string())
+            .or_insert_with(|| ToolBucket {
+                tokens: f64::from(config.max_requests_per_window),
+                refilled_at: now,
+                blocked_requests: 0,
+                total_requests: 0,
+            });
+        bucket.refill(config, now);
+        bucket.total_requests += 1;
+
+        if bucket.tokens >= 1.0 {
+            bucket.tokens -= 1.0;
+            return false;
+        }
+        bucket.blocked_requests += 1;
+
+        let audit_event = crate::audit::AuditEvent::new(
+            crate::audit::AuditEventType::AuthRateLimited,
+            crate::audit::AuditSeverity::Warning,
+            "tool_rate_limiter".to_string(),
+            format!(
+                "Caller {} rate limited for tool {} ({} requests per {} seconds)",
+                caller, tool_name, config.max_requests_per_window, config.window_duration_seconds
+            ),
+        );
+        let _ = self.audit_logger.log(audit_event).await;
+        warn!(
+            "Caller {} rate limited for tool {} ({} requests per {} seconds)",
+            caller, tool_name, config.max_requests_per_window, config.window_duration_seconds
+        );
+        true
+    }
+
     /// Get a consistent role key for rate limiting
     fn get_role_key(&self, role: &Role) -> String {
         match role {
@@ -964,6 +1254,15 @@ impl AuthenticationManager {
         }
     }
 
+    /// Clean up tool rate limit buckets that have refilled completely
+    pub async fn cleanup_tool_rate_limits(&self) {
+        prune_tool_buckets(
+            &self.tool_rate_limit_state,
+            &self.validation_config.tool_rate_limits,
+        )
+        .await;
+    }
+
     /// Refresh the in-memory cache from storage
     async fn refresh_cache(&self) -> Result<(), AuthError> {
         let keys = self
@@ -1031,6 +1330,7 @@ impl AuthenticationManager {
         key_id: &str,
         ip_whitelist: Vec<String>,
     ) -> Result<bool, AuthError> {
+        validate_ip_entries(&ip_whitelist)?;
         let mut key = match self.get_key(key_id).await {
             Some(key) => key,
             None => return Ok(false),
@@ -1043,6 +1343,25 @@ impl AuthenticationManager {
         Ok(true)
     }
 
+    /// Update key IP denylist
+    pub async fn update_key_ip_denylist(
+        &self,
+        key_id: &str,
+        ip_denylist: Vec<String>,
+    ) -> Result<bool, AuthError> {
+        validate_ip_entries(&ip_denylist)?;
+        let mut key = match self.get_key(key_id).await {
+            Some(key) => key,
+            None => return Ok(false),
+        };
+
+        key.ip_denylist = ip_denylist;
+        self.update_key(key).await?;
+
+        info!("Updated IP denylist for API key: {}", key_id);
+        Ok(true)
+    }
+
     /// Get keys by role
     pub async fn list_keys_by_role(&self, role: &Role) -> Vec<ApiKey> {
         let cache = self.api_keys_cache.read().await;
@@ -1120,6 +1439,13 @@ impl AuthenticationManager {
 
             stats.total_usage_count += key.usage_count;
 
+            if let Some(previous) = &key.previous_secret {
+                if !previous.is_expired() {
+                    stats.rotating_keys += 1;
+                }
+                stats.previous_secret_usage_count += previous.usage_count;
+            }
+
             // Track by role
             match &key.role {
                 Role::Admin => stats.admin_keys += 1,
@@ -1141,20 +1467,92 @@ impl AuthenticationManager {
         let mut results = Vec::new();
 
         for request in requests {
-            let result = self
-                .create_api_key(
-                    request.name,
-                    request.role,
-                    request.expires_at,
-                    request.ip_whitelist,
-                )
-                .await;
+            let result = self.create_api_key_from_request(request).await;
             results.push(result);
         }
 
         Ok(results)
     }
 
+    /// Export all API keys for backup or migration to another storage backend
+    ///
+    /// Plain text secrets are never exported: each key carries only its salted
+    /// hash, which is enough for the imported key to keep validating.
+    pub async fn export_keys(&self) -> Vec<ApiKey> {
+        let cache = self.api_keys_cache.read().await;
+        let mut keys: Vec<ApiKey> = cache.values().map(ApiKey::to_export).collect();
+        keys.sort_by(|a, b| a.id.cmp(&b.id));
+
+        info!("Exported {} API keys", keys.len());
+        keys
+    }
+
+    /// Import API keys previously produced by [`export_keys`](Self::export_keys)
+    ///
+    /// Keys without a secret hash and salt are rejected, since they could never
+    /// validate. With [`KeyImportConflict::Fail`] the import is aborted before
+    /// any key is written if one of the IDs already exists.
+    pub async fn import_keys(
+        &self,
+        keys: Vec<ApiKey>,
+        on_conflict: KeyImportConflict,
+    ) -> Result<KeyImportReport, AuthError> {
+        let mut report = KeyImportReport::default();
+
+        if on_conflict == KeyImportConflict::Fail {
+            let cache = self.api_keys_cache.read().await;
+            let conflicts: Vec<&str> = keys
+                .iter()
+                .filter(|key| cache.contains_key(&key.id))
+                .map(|key| key.id.as_str())
+                .collect();
+            if !conflicts.is_empty() {
+                return Err(AuthError::Validation(format!(
+                    "Import aborted, keys already exist: {}",
+                    conflicts.join(", ")
+                )));
+            }
+        }
+
+        for key in keys {
+            if key.secret_hash.is_none() || key.salt.is_none() {
+                report
+                    .rejected
+                    .push((key.id, "missing secret hash or salt".to_string()));
+                continue;
+            }
+
+            let exists = self.api_keys_cache.read().await.contains_key(&key.id);
+            if exists && on_conflict == KeyImportConflict::Skip {
+                debug!("Skipping import of existing key {}", key.id);
+                report.skipped.push(key.id);
+                continue;
+            }
+
+            // Store only the hash, even if the import file carried a secret
+            let key = key.to_export();
+            self.update_key(key.clone()).await?;
+
+            let audit_event = events::key_created(&key.id, "import", &key.role.to_string());
+            let _ = self.audit_logger.log(audit_event).await;
+
+            if exists {
+                report.overwritten.push(key.id);
+            } else {
+                report.imported.push(key.id);
+            }
+        }
+
+        info!(
+            "Imported {} API keys ({} overwritten, {} skipped, {} rejected)",
+            report.imported.len(),
+            report.overwritten.len(),
+            report.skipped.len(),
+            report.rejected.len()
+        );
+        Ok(report)
+    }
+
     /// Check if the authentication manager has all required methods for production use
     pub fn check_api_completeness(&self) -> ApiCompletenessCheck {
         ApiCompletenessCheck {
@@ -1174,11 +1572,35 @@ impl AuthenticationManager {
         }
     }
 
+    /// Start the task that expires rotated secrets once their overlap ends
+    /// and drops idle tool rate limit buckets
     pub async fn start_background_tasks(&self) -> Result<(), AuthError> {
+        let mut task = self.background_task.lock().await;
+        if task.is_some() {
+            return Ok(());
+        }
+
+        let storage = Arc::clone(&self.storage);
+        let cache = Arc::clone(&self.api_keys_cache);
+        let tool_states = Arc::clone(&self.tool_rate_limit_state);
+        let tool_limits = self.validation_config.tool_rate_limits.clone();
+        *task = Some(tokio::spawn(async move {
+            let mut interval = tokio::time::interval(ROTATION_CLEANUP_INTERVAL);
+            loop {
+                interval.tick().await;
+                if let Err(e) = expire_rotated_secrets(storage.as_ref(), &cache).await {
+                    error!("Failed to expire rotated API key secrets: {}", e);
+                }
+                prune_tool_buckets(&tool_states, &tool_limits).await;
+            }
+        }));
         Ok(())
     }
 
     pub async fn stop_background_tasks(&self) -> Result<(), AuthError> {
+        if let Some(task) = self.background_task.lock().await.take() {
+            task.abort();
+        }
         Ok(())
     }
 
@@ -1372,6 +1794,74 @@ impl AuthenticationManager {
     }
 }
 
+/// Drop every previous secret whose rotation overlap has ended, persisting
+/// the affected keys
+async fn expire_rotated_secrets(
+    storage: &dyn StorageBackend,
+    cache: &RwLock<HashMap<String, ApiKey>>,
+) -> Result<u32, AuthError> {
+    let mut cache = cache.write().await;
+    let mut expired = 0;
+
+    for key in cache.values_mut() {
+        if key.expire_previous_secret() {
+            storage
+                .save_key(key)
+                .await
+                .map_err(|e| AuthError::Storage(e.to_string()))?;
+            expired += 1;
+        }
+    }
+
+    if expired > 0 {
+        info!("Expired {} rotated API key secrets", expired);
+    }
+    Ok(expired)
+}
+
+/// Drop tool rate limit buckets that have refilled completely, and those of
+/// tools no longer limited
+async fn prune_tool_buckets(
+    tool_states: &RwLock<HashMap<String, HashMap<String, ToolBucket>>>,
+    tool_limits: &HashMap<String, ToolRateLimitConfig>,
+) {
+    let mut tool_states = tool_states.write().await;
+    let now = Utc::now();
+
+    let mut total_removed = 0;
+    for (tool_name, buckets) in tool_states.iter_mut() {
+        let Some(config) = tool_limits.get(tool_name) else {
+            total_removed += buckets.len();
+            buckets.clear();
+            continue;
+        };
+        let initial_count = buckets.len();
+        buckets.retain(|_caller, bucket| {
+            bucket.refill(config, now);
+            bucket.tokens < f64::from(config.max_requests_per_window)
+        });
+        total_removed += initial_count - buckets.len();
+    }
+    tool_states.retain(|_tool, buckets| !buckets.is_empty());
+
+    if total_removed > 0 {
+        debug!("Cleaned up {} idle tool rate limit buckets", total_removed);
+    }
+}
+
+/// Reject IP list entries that are neither an address nor a CIDR range
+fn validate_ip_entries(entries: &[String]) -> Result<(), AuthError> {
+    match entries
+        .iter()
+        .find(|entry| *entry != "*" && parse_ip_range(entry).is_none())
+    {
+        Some(entry) => Err(AuthError::Validation(format!(
+            "Invalid IP address or CIDR range: {entry}"
+        ))),
+        None => Ok(()),
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -1400,6 +1890,7 @@ mod tests {
             strict_ip_validation: false,
             enable_role_based_rate_limiting: false,
             role_rate_limits: HashMap::new(),
+            tool_rate_limits: HashMap::new(),
         }
     }
 
@@ -1426,4 +1917,454 @@ mod tests {
         assert!(key.id.starts_with("lmcp_"));
         assert_eq!(key.role, Role::Monitor);
     }
+
+    async fn create_file_manager(dir: &tempfile::TempDir) -> AuthenticationManager {
+        let config = AuthConfig {
+            storage: StorageConfig::File {
+                path: dir.path().join("keys.enc"),
+                file_permissions: 0o600,
+                dir_permissions: 0o700,
+                require_secure_filesystem: false,
+                enable_filesystem_monitoring: false,
+            },
+            ..create_test_config()
+        };
+        AuthenticationManager::new(config).await.unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_export_import_round_trip_across_backends() {
+        let source = AuthenticationManager::new(create_test_config())
+            .await
+            .unwrap();
+        let operator = source
+            .create_api_key("Operator".to_string(), Role::Operator, None, None)
+            .await
+            .unwrap();
+        source
+            .create_api_key(
+                "Monitor".to_string(),
+                Role::Monitor,
+                None,
+                Some(vec!["10.0.0.1".to_string()]),
+            )
+            .await
+            .unwrap();
+
+        let exported = source.export_keys().await;
+        assert_eq!(exported.len(), 2);
+        assert!(exported.iter().all(|k| k.key == "***redacted***"));
+        let json = serde_json::to_string(&exported).unwrap();
+        assert!(!json.contains(&operator.key));
+
+        // Round-trip through a file-backed manager via serialized JSON
+        let dir = tempfile::tempdir().unwrap();
+        let target = create_file_manager(&dir).await;
+        let keys: Vec<ApiKey> = serde_json::from_str(&json).unwrap();
+        let report = target
+            .import_keys(keys, KeyImportConflict::Skip)
+            .await
+            .unwrap();
+        assert_eq!(report.imported.len(), 2);
+        assert!(report.rejected.is_empty());
+
+        let reexported = target.export_keys().await;
+        assert_eq!(reexported.len(), exported.len());
+        for (a, b) in exported.iter().zip(&reexported) {
+            assert_eq!(a.id, b.id);
+            assert_eq!(a.name, b.name);
+            assert_eq!(a.role, b.role);
+            assert_eq!(a.secret_hash, b.secret_hash);
+            assert_eq!(a.ip_whitelist, b.ip_whitelist);
+            assert_eq!(a.active, b.active);
+        }
+
+        // The original secret still authenticates against the imported key
+        let context = target
+            .validate_api_key(&operator.key, Some("127.0.0.1"))
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(context.api_key_id, Some(operator.id));
+    }
+
+    #[tokio::test]
+    async fn test_import_conflict_handling() {
+        let source = AuthenticationManager::new(create_test_config())
+            .await
+            .unwrap();
+        let key = source
+            .create_api_key("Original".to_string(), Role::Monitor, None, None)
+            .await
+            .unwrap();
+
+        let mut renamed = source.export_keys().await;
+        renamed[0].name = "Renamed".to_string();
+
+        let err = source
+            .import_keys(renamed.clone(), KeyImportConflict::Fail)
+            .await
+            .unwrap_err();
+        assert!(matches!(err, AuthError::Validation(_)));
+
+        let report = source
+            .import_keys(renamed.clone(), KeyImportConflict::Skip)
+            .await
+            .unwrap();
+        assert_eq!(report.skipped, vec![key.id.clone()]);
+        assert_eq!(source.get_key(&key.id).await.unwrap().name, "Original");
+
+        let report = source
+            .import_keys(renamed, KeyImportConflict::Overwrite)
+            .await
+            .unwrap();
+        assert_eq!(report.overwritten, vec![key.id.clone()]);
+        assert_eq!(source.get_key(&key.id).await.unwrap().name, "Renamed");
+    }
+
+    #[tokio::test]
+    async fn test_import_rejects_keys_without_hash() {
+        let manager = AuthenticationManager::new(create_test_config())
+            .await
+            .unwrap();
+        let mut key = ApiKey::new("Legacy".to_string(), Role::Monitor, None, vec![]);
+        key.secret_hash = None;
+        key.salt = None;
+
+        let report = manager
+            .import_keys(vec![key.clone()], KeyImportConflict::Skip)
+            .await
+            .unwrap();
+        assert_eq!(report.rejected.len(), 1);
+        assert!(manager.get_key(&key.id).await.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_rotate_api_key_overlap() {
+        let manager = AuthenticationManager::new(create_test_config())
+            .await
+            .unwrap();
+        let key = manager
+            .create_api_key("Rotating".to_string(), Role::Operator, None, None)
+            .await
+            .unwrap();
+
+        let rotation = manager
+            .rotate_api_key(&key.id, chrono::Duration::hours(1))
+            .await
+            .unwrap();
+        assert_eq!(rotation.key_id, key.id);
+        assert_eq!(rotation.old_secret, key.key);
+        assert_ne!(rotation.new_secret, key.key);
+
+        // Both secrets authenticate as the same key during the overlap
+        for secret in [&rotation.new_secret, &rotation.old_secret] {
+            let context = manager
+                .validate_api_key(secret, Some("127.0.0.1"))
+                .await
+                .unwrap()
+                .unwrap();
+            assert_eq!(context.api_key_id, Some(key.id.clone()));
+        }
+
+        let stats = manager.get_key_usage_stats().await.unwrap();
+        assert_eq!(stats.rotating_keys, 1);
+        assert_eq!(stats.total_usage_count, 2);
+        assert_eq!(stats.previous_secret_usage_count, 1);
+        assert_eq!(manager.cleanup_expired_rotations().await.unwrap(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_rotate_api_key_old_secret_expires() {
+        let manager = AuthenticationManager::new(create_test_config())
+            .await
+            .unwrap();
+        let key = manager
+            .create_api_key("Rotating".to_string(), Role::Operator, None, None)
+            .await
+            .unwrap();
+
+        let rotation = manager
+            .rotate_api_key(&key.id, chrono::Duration::zero())
+            .await
+            .unwrap();
+        tokio::time::sleep(std::time::Duration::from_millis(5)).await;
+
+        assert!(
+            manager
+                .validate_api_key(&rotation.old_secret, Some("127.0.0.1"))
+                .await
+                .is_err()
+        );
+        assert!(
+            manager
+                .validate_api_key(&rotation.new_secret, Some("127.0.0.1"))
+                .await
+                .is_ok()
+        );
+
+        assert_eq!(manager.cleanup_expired_rotations().await.unwrap(), 1);
+        assert!(
+            manager
+                .get_key(&key.id)
+                .await
+                .unwrap()
+                .previous_secret
+                .is_none()
+        );
+        let stored = manager.storage.load_keys().await.unwrap();
+        assert!(stored[&key.id].previous_secret.is_none());
+
+        let missing = manager
+            .rotate_api_key("lmcp_missing", chrono::Duration::hours(1))
+            .await;
+        assert!(matches!(missing, Err(AuthError::Failed(_))));
+    }
+
+    fn limited_use_request(max_uses: u64) -> KeyCreationRequest {
+        KeyCreationRequest {
+            name: "Agent".to_string(),
+            role: Role::Operator,
+            expires_at: None,
+            ip_whitelist: None,
+            ip_denylist: None,
+            max_uses: Some(max_uses),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_limited_use_key_exhausts() {
+        let manager = AuthenticationManager::new(create_test_config())
+            .await
+            .unwrap();
+        let key = manager
+            .create_api_key_from_request(limited_use_request(3))
+            .await
+            .unwrap();
+        assert_eq!(key.max_uses, Some(3));
+
+        for _ in 0..3 {
+            assert!(
+                manager
+                    .validate_api_key(&key.key, Some("127.0.0.1"))
+                    .await
+                    .is_ok()
+            );
+        }
+
+        let err = manager
+            .validate_api_key(&key.key, Some("127.0.0.1"))
+            .await
+            .unwrap_err();
+        assert!(matches!(err, AuthError::KeyExhausted(ref id) if *id == key.id));
+        assert!(!manager.get_key(&key.id).await.unwrap().is_valid());
+    }
+
+    #[tokio::test]
+    async fn test_limited_use_count_survives_restart() {
+        let dir = tempfile::tempdir().unwrap();
+        let manager = create_file_manager(&dir).await;
+        let key = manager
+            .create_api_key_from_request(limited_use_request(2))
+            .await
+            .unwrap();
+        manager
+            .validate_api_key(&key.key, Some("127.0.0.1"))
+            .await
+            .unwrap();
+
+        // Reload from the key file as a restarted manager would
+        manager.api_keys_cache.write().await.clear();
+        manager.refresh_cache().await.unwrap();
+        assert_eq!(manager.get_key(&key.id).await.unwrap().usage_count, 1);
+
+        assert!(
+            manager
+                .validate_api_key(&key.key, Some("127.0.0.1"))
+                .await
+                .is_ok()
+        );
+        assert!(matches!(
+            manager.validate_api_key(&key.key, Some("127.0.0.1")).await,
+            Err(AuthError::KeyExhausted(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_ip_ranges_enforced_on_validation() {
+        let manager = AuthenticationManager::new(create_test_config())
+            .await
+            .unwrap();
+        let key = manager
+            .create_api_key_from_request(KeyCreationRequest {
+                name: "Office".to_string(),
+                role: Role::Operator,
+                expires_at: None,
+                ip_whitelist: Some(vec!["10.0.0.0/8".to_string(), "fd00::/8".to_string()]),
+                ip_denylist: Some(vec!["10.13.0.0/16".to_string()]),
+                max_uses: None,
+            })
+            .await
+            .unwrap();
+
+        for allowed in ["10.1.2.3", "fd00::42"] {
+            assert!(
+                manager
+                    .validate_api_key(&key.key, Some(allowed))
+                    .await
+                    .unwrap()
+                    .is_some()
+            );
+        }
+        for refused in ["192.168.0.1", "10.13.0.9", "2001:db8::1"] {
+            assert!(matches!(
+                manager.validate_api_key(&key.key, Some(refused)).await,
+                Err(AuthError::IpNotAllowed(ref ip)) if ip == refused
+            ));
+        }
+        // Without a client IP the whitelist can't be satisfied
+        assert!(matches!(
+            manager.validate_api_key(&key.key, None).await,
+            Err(AuthError::IpNotAllowed(_))
+        ));
+
+        // Denylist-only keys allow everything else
+        assert!(
+            manager
+                .update_key_ip_whitelist(&key.id, Vec::new())
+                .await
+                .unwrap()
+        );
+        assert!(
+            manager
+                .validate_api_key(&key.key, Some("172.16.0.1"))
+                .await
+                .is_ok()
+        );
+
+        assert!(matches!(
+            manager
+                .update_key_ip_denylist(&key.id, vec!["10.0.0.0/99".to_string()])
+                .await,
+            Err(AuthError::Validation(_))
+        ));
+        assert!(matches!(
+            manager
+                .create_api_key(
+                    "Bad".to_string(),
+                    Role::Monitor,
+                    None,
+                    Some(vec!["office".to_string()]),
+                )
+                .await,
+            Err(AuthError::Validation(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_tool_rate_limits_per_caller_and_tool() {
+        let mut validation_config = create_test_validation_config();
+        for (tool, max_requests) in [("search", 3), ("export", 1)] {
+            validation_config.tool_rate_limits.insert(
+                tool.to_string(),
+                ToolRateLimitConfig {
+                    max_requests_per_window: max_requests,
+                    window_duration_seconds: 3600,
+                },
+            );
+        }
+        let manager =
+            AuthenticationManager::new_with_validation(create_test_config(), validation_config)
+                .await
+                .unwrap();
+        let caller = |key_id: &str| AuthContext {
+            user_id: Some("user-1".to_string()),
+            roles: vec![Role::Operator],
+            api_key_id: Some(key_id.to_string()),
+            permissions: Vec::new(),
+        };
+        let key = caller("key-1");
+
+        assert!(!manager.check_tool_rate_limit(&key, "export").await.unwrap());
+        assert!(manager.check_tool_rate_limit(&key, "export").await.unwrap());
+        // The export limit doesn't affect the search bucket
+        for _ in 0..3 {
+            assert!(!manager.check_tool_rate_limit(&key, "search").await.unwrap());
+        }
+        assert!(manager.check_tool_rate_limit(&key, "search").await.unwrap());
+        // Tools without a limit aren't tracked
+        assert!(!manager.check_tool_rate_limit(&key, "status").await.unwrap());
+        // Another key has its own buckets
+        assert!(
+            !manager
+                .check_tool_rate_limit(&caller("key-2"), "export")
+                .await
+                .unwrap()
+        );
+
+        let stats = manager.get_rate_limit_stats().await;
+        let export = &stats.tool_stats["export"];
+        assert_eq!(export.tracked_callers, 2);
+        assert_eq!(export.total_requests, 3);
+        assert_eq!(export.blocked_requests, 1);
+        assert!((export.consumed_tokens - 2.0).abs() < 0.01);
+        let search = &stats.tool_stats["search"];
+        assert_eq!(search.tracked_callers, 1);
+        assert_eq!(search.blocked_requests, 1);
+        assert!((search.consumed_tokens - 3.0).abs() < 0.01);
+        assert!(!stats.tool_stats.contains_key("status"));
+    }
+
+    #[tokio::test]
+    async fn test_tool_rate_limit_leaves_role_limits_to_validation() {
+        let mut validation_config = create_test_validation_config();
+        validation_config.enable_role_based_rate_limiting = true;
+        validation_config.role_rate_limits.insert(
+            "operator".to_string(),
+            RoleRateLimitConfig {
+                max_requests_per_window: 1,
+                window_duration_minutes: 60,
+                burst_allowance: 0,
+                cooldown_duration_minutes: 60,
+            },
+        );
+        validation_config.tool_rate_limits.insert(
+            "export".to_string(),
+            ToolRateLimitConfig {
+                max_requests_per_window: 5,
+                window_duration_seconds: 3600,
+            },
+        );
+        let manager =
+            AuthenticationManager::new_with_validation(create_test_config(), validation_config)
+                .await
+                .unwrap();
+        let key = AuthContext {
+            user_id: None,
+            roles: vec![Role::Operator],
+            api_key_id: Some("key-1".to_string()),
+            permissions: Vec::new(),
+        };
+
+        // Only the tool's own limit applies, not the operator's budget of one
+        for _ in 0..3 {
+            assert!(!manager.check_tool_rate_limit(&key, "export").await.unwrap());
+        }
+
+        let stats = manager.get_rate_limit_stats().await;
+        assert!(stats.role_stats.is_empty());
+        let export = &stats.tool_stats["export"];
+        assert_eq!(export.total_requests, 3);
+        assert!((export.consumed_tokens - 3.0).abs() < 0.01);
+
+        // A bucket still short of tokens survives cleanup
+        manager.cleanup_tool_rate_limits().await;
+        assert!(
+            manager
+                .get_rate_limit_stats()
+                .await
+                .tool_stats
+                .contains_key("export")
+        );
+    }
 }
@@ -2,6 +2,12 @@
 //!
 //! This module provides an enhanced authentication manager that can fetch
 //! master keys and configuration from external vault systems like Infisical.
+//!
+//! When [`VaultConfig::rotate_schedule`] is set, the manager re-fetches its
+//! secrets in the background and swaps them in as a whole. A new master key
+//! is handed to the inner [`AuthenticationManager`], which re-encrypts its
+//! stored keys under it. A failed rotation keeps the last known good secrets
+//! and marks the [`VaultStatus`] degraded.
 
 use crate::{
     AuthConfig, AuthenticationManager, ValidationConfig,
@@ -9,14 +15,36 @@ use crate::{
     manager::AuthError,
     vault::{VaultConfig, VaultError, VaultIntegration},
 };
+use chrono::{DateTime, Utc};
 use std::collections::HashMap;
+use std::sync::{Arc, RwLock};
+use std::time::Duration;
+use tokio::sync::watch;
+use tokio::task::JoinHandle;
 use tracing::{debug, info, warn};
 
+/// Secrets fetched from the vault, replaced as a whole on each rotation
+#[derive(Debug, Clone)]
+pub struct VaultSecrets {
+    pub master_key: String,
+    pub config: HashMap<String, String>,
+    /// Number of successful rotations before these secrets were fetched
+    pub generation: u64,
+    pub fetched_at: DateTime<Utc>,
+}
+
+/// Callback run after each successful secret rotation
+pub type SecretRotationHook = Arc<dyn Fn(&VaultSecrets) + Send + Sync>;
+
 /// Vault-integrated authentication manager
 pub struct VaultAuthenticationManager {
-    auth_manager: AuthenticationManager,
-    vault_integration: Option<VaultIntegration>,
-    fallback_to_env: bool,
+    /// Shared with the rotation task, which hands it new master keys
+    auth_manager: Arc<AuthenticationManager>,
+    vault_integration: Option<Arc<VaultIntegration>>,
+    /// Current secrets and status, shared with the rotation task
+    rotation: Arc<SecretRotation>,
+    /// Background re-fetching of secrets, when scheduled
+    rotation_task: Option<JoinHandle<()>>,
 }
 
 impl VaultAuthenticationManager {
@@ -27,6 +55,12 @@ impl VaultAuthenticationManager {
         vault_config: Option<VaultConfig>,
         fallback_to_env: bool,
     ) -> Result<Self, VaultAuthManagerError> {
+        let rotate_schedule = vault_config.as_ref().and_then(|cfg| cfg.rotate_schedule);
+        if rotate_schedule.is_some_and(|interval| interval.is_zero()) {
+            return Err(VaultAuthManagerError::ConfigError(
+                "rotate_schedule must be greater than zero".to_string(),
+            ));
+        }
         let vault_integration = if let Some(vault_cfg) = vault_config {
             match VaultIntegration::new(vault_cfg).await {
                 Ok(integration) => {
@@ -34,7 +68,7 @@ impl VaultAuthenticationManager {
                         "Successfully connected to vault: {}",
                         integration.client_info().name
                     );
-                    Some(integration)
+                    Some(Arc::new(integration))
                 }
                 Err(e) => {
                     if fallback_to_env {
@@ -90,25 +124,57 @@ impl VaultAuthenticationManager {
         }
 
         // Try to get additional configuration from vault
+        let mut config = HashMap::new();
         if let Some(vault) = &vault_integration
             && let Ok(vault_config) = vault.get_api_config().await
         {
             Self::apply_vault_config(&mut auth_config, &vault_config);
+            config = vault_config;
         }
 
         // Use provided validation config or try to create from vault config
         let validation_config = validation_config.unwrap_or_default();
 
         // Create the authentication manager
-        let auth_manager =
+        let auth_manager = Arc::new(
             AuthenticationManager::new_with_validation(auth_config, validation_config)
                 .await
-                .map_err(VaultAuthManagerError::AuthError)?;
+                .map_err(VaultAuthManagerError::AuthError)?,
+        );
+
+        let status = VaultStatus {
+            enabled: vault_integration.is_some(),
+            connected: vault_integration.is_some(), // We assume it's connected if we have the integration
+            client_info: vault_integration.as_ref().map(|vault| vault.client_info()),
+            fallback_enabled: fallback_to_env,
+            degraded: false,
+            last_rotation_error: None,
+            last_rotated_at: None,
+        };
+        let secrets = VaultSecrets {
+            master_key,
+            config,
+            generation: 0,
+            fetched_at: Utc::now(),
+        };
+        let rotation = Arc::new(SecretRotation::new(secrets, status));
+        let rotation_task = match (&vault_integration, rotate_schedule) {
+            (Some(vault), Some(interval)) => {
+                info!("Rotating vault secrets every {:?}", interval);
+                Some(
+                    rotation
+                        .clone()
+                        .spawn(vault.clone(), auth_manager.clone(), interval),
+                )
+            }
+            _ => None,
+        };
 
         Ok(Self {
             auth_manager,
             vault_integration,
-            fallback_to_env,
+            rotation,
+            rotation_task,
         })
     }
 
@@ -175,7 +241,31 @@ impl VaultAuthenticationManager {
 
     /// Get vault integration if available
     pub fn vault_integration(&self) -> Option<&VaultIntegration> {
-        self.vault_integration.as_ref()
+        self.vault_integration.as_deref()
+    }
+
+    /// The secrets currently in use
+    ///
+    /// Hold on to the snapshot for the duration of an operation: a rotation
+    /// swaps in new secrets without changing snapshots already taken.
+    pub fn current_secrets(&self) -> Arc<VaultSecrets> {
+        self.rotation.current()
+    }
+
+    /// Run `hook` after every successful rotation, e.g. to refresh state
+    /// derived from the secrets
+    pub fn on_secret_rotation(&self, hook: impl Fn(&VaultSecrets) + Send + Sync + 'static) {
+        self.rotation
+            .hooks
+            .write()
+            .unwrap_or_else(|e| e.into_inner())
+            .push(Arc::new(hook));
+    }
+
+    /// Watch the vault status, which changes when a rotation succeeds or
+    /// fails
+    pub fn subscribe_status(&self) -> watch::Receiver<VaultStatus> {
+        self.rotation.status.subscribe()
     }
 
     /// Test vault connectivity
@@ -264,22 +354,125 @@ impl VaultAuthenticationManager {
 
     /// Get vault status information
     pub fn vault_status(&self) -> VaultStatus {
-        if let Some(vault) = &self.vault_integration {
-            VaultStatus {
-                enabled: true,
-                connected: true, // We assume it's connected if we have the integration
-                client_info: Some(vault.client_info()),
-                fallback_enabled: self.fallback_to_env,
+        self.rotation.status.borrow().clone()
+    }
+}
+
+impl Drop for VaultAuthenticationManager {
+    fn drop(&mut self) {
+        if let Some(task) = self.rotation_task.take() {
+            task.abort();
+        }
+    }
+}
+
+/// Secrets in use and the status of their rotation
+struct SecretRotation {
+    secrets: RwLock<Arc<VaultSecrets>>,
+    hooks: RwLock<Vec<SecretRotationHook>>,
+    status: watch::Sender<VaultStatus>,
+}
+
+impl SecretRotation {
+    fn new(secrets: VaultSecrets, status: VaultStatus) -> Self {
+        Self {
+            secrets: RwLock::new(Arc::new(secrets)),
+            hooks: RwLock::new(Vec::new()),
+            status: watch::Sender::new(status),
+        }
+    }
+
+    fn current(&self) -> Arc<VaultSecrets> {
+        self.secrets
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+            .clone()
+    }
+
+    /// Rotate every `interval` until the returned task is aborted
+    fn spawn(
+        self: Arc<Self>,
+        vault: Arc<VaultIntegration>,
+        auth_manager: Arc<AuthenticationManager>,
+        interval: Duration,
+    ) -> JoinHandle<()> {
+        tokio::spawn(async move {
+            let start = tokio::time::Instant::now() + interval;
+            let mut ticker = tokio::time::interval_at(start, interval);
+            ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+            loop {
+                ticker.tick().await;
+                self.rotate(&vault, &auth_manager).await;
             }
-        } else {
-            VaultStatus {
-                enabled: false,
-                connected: false,
-                client_info: None,
-                fallback_enabled: self.fallback_to_env,
+        })
+    }
+
+    /// Re-fetch the secrets and swap them in, keeping the current ones if
+    /// that fails
+    async fn rotate(&self, vault: &VaultIntegration, auth_manager: &AuthenticationManager) {
+        match self.fetch_and_apply(vault, auth_manager).await {
+            Ok((master_key, config)) => {
+                let secrets = {
+                    let mut current = self.secrets.write().unwrap_or_else(|e| e.into_inner());
+                    let next = Arc::new(VaultSecrets {
+                        master_key,
+                        config,
+                        generation: current.generation + 1,
+                        fetched_at: Utc::now(),
+                    });
+                    *current = next.clone();
+                    next
+                };
+                self.status.send_modify(|status| {
+                    status.degraded = false;
+                    status.last_rotation_error = None;
+                    status.last_rotated_at = Some(secrets.fetched_at);
+                });
+                info!("Rotated vault secrets (generation {})", secrets.generation);
+
+                let hooks = self.hooks.read().unwrap_or_else(|e| e.into_inner()).clone();
+                for hook in hooks {
+                    hook(&secrets);
+                }
+            }
+            Err(e) => {
+                warn!(
+                    "Vault secret rotation failed ({}), keeping the last known good secrets",
+                    e
+                );
+                self.status.send_modify(|status| {
+                    status.degraded = true;
+                    status.last_rotation_error = Some(e.to_string());
+                });
             }
         }
     }
+
+    /// Fetch the secrets and hand a changed master key to `auth_manager`
+    async fn fetch_and_apply(
+        &self,
+        vault: &VaultIntegration,
+        auth_manager: &AuthenticationManager,
+    ) -> Result<(String, HashMap<String, String>), VaultAuthManagerError> {
+        let (master_key, config) = fetch_secrets(vault)
+            .await
+            .map_err(VaultAuthManagerError::VaultError)?;
+        if master_key != self.current().master_key {
+            auth_manager
+                .rotate_master_key(&master_key)
+                .await
+                .map_err(VaultAuthManagerError::AuthError)?;
+        }
+        Ok((master_key, config))
+    }
+}
+
+/// Fetch the master key and configuration, bypassing the secret cache
+async fn fetch_secrets(
+    vault: &VaultIntegration,
+) -> Result<(String, HashMap<String, String>), VaultError> {
+    vault.clear_cache().await;
+    Ok((vault.get_master_key().await?, vault.get_api_config().await?))
 }
 
 // Implement Deref to allow direct access to AuthenticationManager methods
@@ -317,6 +510,10 @@ pub struct VaultStatus {
     pub connected: bool,
     pub client_info: Option<crate::vault::VaultClientInfo>,
     pub fallback_enabled: bool,
+    /// The last secret rotation failed and older secrets are still in use
+    pub degraded: bool,
+    pub last_rotation_error: Option<String>,
+    pub last_rotated_at: Option<DateTime<Utc>>,
 }
 
 impl std::fmt::Display for VaultStatus {
@@ -325,6 +522,13 @@ impl std::fmt::Display for VaultStatus {
         writeln!(f, "  Enabled: {}", self.enabled)?;
         writeln!(f, "  Connected: {}", self.connected)?;
         writeln!(f, "  Fallback Enabled: {}", self.fallback_enabled)?;
+        writeln!(f, "  Degraded: {}", self.degraded)?;
+        if let Some(error) = &self.last_rotation_error {
+            writeln!(f, "  Last Rotation Error: {}", error)?;
+        }
+        if let Some(rotated_at) = &self.last_rotated_at {
+            writeln!(f, "  Last Rotated: {}", rotated_at)?;
+        }
 
         if let Some(info) = &self.client_info {
             writeln!(f, "  Client: {} v{}", info.name, info.version)?;
@@ -350,6 +554,282 @@ impl VaultIntegration {
 mod tests {
     use super::*;
     use crate::config::StorageConfig;
+    use crate::vault::{SecretMetadata, VaultClient, VaultClientInfo, VaultType};
+    use std::sync::Mutex;
+    use std::sync::atomic::{AtomicBool, Ordering};
+
+    /// In-memory vault whose reads can be made to fail
+    #[derive(Clone, Default)]
+    struct MockVault {
+        secrets: Arc<Mutex<HashMap<String, String>>>,
+        unreachable: Arc<AtomicBool>,
+    }
+
+    impl MockVault {
+        fn with_master_key(key: &str) -> Self {
+            let vault = Self::default();
+            vault.set_master_key(key);
+            vault
+        }
+
+        fn set_master_key(&self, key: &str) {
+            self.secrets
+                .lock()
+                .unwrap()
+                .insert("PULSEENGINE_MCP_MASTER_KEY".to_string(), key.to_string());
+        }
+    }
+
+    #[async_trait::async_trait]
+    impl VaultClient for MockVault {
+        async fn authenticate(&self) -> Result<(), VaultError> {
+            Ok(())
+        }
+
+        async fn get_secret(&self, name: &str) -> Result<String, VaultError> {
+            if self.unreachable.load(Ordering::SeqCst) {
+                return Err(VaultError::NetworkError("vault unreachable".to_string()));
+            }
+            self.secrets
+                .lock()
+                .unwrap()
+                .get(name)
+                .cloned()
+                .ok_or_else(|| VaultError::SecretNotFound(name.to_string()))
+        }
+
+        async fn get_secret_with_metadata(
+            &self,
+            name: &str,
+        ) -> Result<(String, SecretMetadata), VaultError> {
+            Err(VaultError::SecretNotFound(name.to_string()))
+        }
+
+        async fn list_secrets(&self) -> Result<Vec<String>, VaultError> {
+            Ok(self.secrets.lock().unwrap().keys().cloned().collect())
+        }
+
+        async fn set_secret(&self, name: &str, value: &str) -> Result<(), VaultError> {
+            self.secrets
+                .lock()
+                .unwrap()
+                .insert(name.to_string(), value.to_string());
+            Ok(())
+        }
+
+        async fn delete_secret(&self, name: &str) -> Result<(), VaultError> {
+            self.secrets.lock().unwrap().remove(name);
+            Ok(())
+        }
+
+        async fn is_authenticated(&self) -> bool {
+            true
+        }
+
+        fn client_info(&self) -> VaultClientInfo {
+            VaultClientInfo {
+                name: "Mock Vault".to_string(),
+                version: "1.0.0".to_string(),
+                vault_type: VaultType::Custom("mock".to_string()),
+                read_only: false,
+            }
+        }
+    }
+
+    /// A valid base64-encoded master key
+    fn master_key(byte: u8) -> String {
+        use base64::Engine;
+        base64::engine::general_purpose::URL_SAFE_NO_PAD.encode([byte; 32])
+    }
+
+    async fn memory_manager() -> Arc<AuthenticationManager> {
+        Arc::new(
+            AuthenticationManager::new(AuthConfig::memory())
+                .await
+                .unwrap(),
+        )
+    }
+
+    async fn start_rotation(
+        mock: &MockVault,
+        auth_manager: Arc<AuthenticationManager>,
+        interval: Duration,
+    ) -> (Arc<SecretRotation>, JoinHandle<()>) {
+        let vault = Arc::new(VaultIntegration::with_client(
+            Box::new(mock.clone()),
+            Duration::from_secs(300),
+        ));
+        let (master_key, config) = fetch_secrets(&vault).await.unwrap();
+        let secrets = VaultSecrets {
+            master_key,
+            config,
+            generation: 0,
+            fetched_at: Utc::now(),
+        };
+        let status = VaultStatus {
+            enabled: true,
+            connected: true,
+            client_info: Some(vault.client_info()),
+            fallback_enabled: false,
+            degraded: false,
+            last_rotation_error: None,
+            last_rotated_at: None,
+        };
+        let rotation = Arc::new(SecretRotation::new(secrets, status));
+        let task = rotation.clone().spawn(vault, auth_manager, interval);
+        (rotation, task)
+    }
+
+    #[tokio::test]
+    async fn test_rotation_swaps_secrets_and_runs_hooks() {
+        let mock = MockVault::with_master_key(&master_key(1));
+        let (rotation, task) =
+            start_rotation(&mock, memory_manager().await, Duration::from_millis(20)).await;
+        let rotated = Arc::new(Mutex::new(Vec::new()));
+        let seen = rotated.clone();
+        rotation
+            .hooks
+            .write()
+            .unwrap()
+            .push(Arc::new(move |secrets| {
+                seen.lock().unwrap().push(secrets.master_key.clone())
+            }));
+
+        // A validation in flight keeps the snapshot it started with
+        let in_flight = rotation.current();
+        mock.set_master_key(&master_key(2));
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        task.abort();
+
+        assert_eq!(in_flight.master_key, master_key(1));
+        let current = rotation.current();
+        assert_eq!(current.master_key, master_key(2));
+        assert!(current.generation >= 1);
+        assert_eq!(rotated.lock().unwrap().first().unwrap(), &master_key(2));
+
+        let status = rotation.status.borrow().clone();
+        assert!(!status.degraded);
+        assert!(status.last_rotated_at.is_some());
+    }
+
+    #[tokio::test]
+    async fn test_failed_rotation_keeps_last_known_good_secrets() {
+        let mock = MockVault::with_master_key(&master_key(1));
+        let (rotation, task) =
+            start_rotation(&mock, memory_manager().await, Duration::from_millis(20)).await;
+        let mut status = rotation.status.subscribe();
+
+        mock.unreachable.store(true, Ordering::SeqCst);
+        mock.set_master_key(&master_key(2));
+        tokio::time::timeout(Duration::from_secs(1), status.changed())
+            .await
+            .unwrap()
+            .unwrap();
+        {
+            let degraded = status.borrow_and_update();
+            assert!(degraded.degraded);
+            assert!(
+                degraded
+                    .last_rotation_error
+                    .as_deref()
+                    .unwrap()
+                    .contains("vault unreachable")
+            );
+        }
+        let current = rotation.current();
+        assert_eq!(current.master_key, master_key(1));
+        assert_eq!(current.generation, 0);
+
+        // The next successful rotation clears the degradation
+        mock.unreachable.store(false, Ordering::SeqCst);
+        tokio::time::timeout(Duration::from_secs(1), async {
+            while status.borrow_and_update().degraded {
+                status.changed().await.unwrap();
+            }
+        })
+        .await
+        .unwrap();
+        task.abort();
+        assert_eq!(rotation.current().master_key, master_key(2));
+    }
+
+    #[tokio::test]
+    async fn test_rotation_rekeys_the_authentication_manager() {
+        use crate::crypto::encryption::{EncryptedData, decrypt_data, derive_encryption_key};
+        use crate::crypto::keys::decode_master_key;
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("keys.enc");
+        let config = AuthConfig {
+            storage: StorageConfig::File {
+                path: path.clone(),
+                file_permissions: 0o600,
+                dir_permissions: 0o700,
+                require_secure_filesystem: false,
+                enable_filesystem_monitoring: false,
+            },
+            ..AuthConfig::memory()
+        };
+        let auth_manager = Arc::new(AuthenticationManager::new(config).await.unwrap());
+        let key = auth_manager
+            .create_api_key("client".to_string(), crate::Role::Monitor, None, None)
+            .await
+            .unwrap();
+
+        let mock = MockVault::with_master_key(&master_key(1));
+        let (rotation, task) =
+            start_rotation(&mock, auth_manager.clone(), Duration::from_millis(20)).await;
+        let mut status = rotation.status.subscribe();
+        mock.set_master_key(&master_key(2));
+        tokio::time::timeout(Duration::from_secs(1), async {
+            while status.borrow_and_update().last_rotated_at.is_none() {
+                status.changed().await.unwrap();
+            }
+        })
+        .await
+        .unwrap();
+        task.abort();
+        assert!(!rotation.status.borrow().degraded);
+
+        // Stored keys are now encrypted under the rotated master key
+        let encrypted: EncryptedData =
+            serde_json::from_slice(&std::fs::read(&path).unwrap()).unwrap();
+        let storage_key = derive_encryption_key(
+            &decode_master_key(&master_key(2)).unwrap(),
+            "api-key-storage",
+        );
+        assert!(decrypt_data(&encrypted, &storage_key).is_ok());
+
+        // and keep validating, as do keys created after the rotation
+        let context = auth_manager
+            .validate_api_key(&key.key, Some("127.0.0.1"))
+            .await
+            .unwrap();
+        assert!(context.is_some());
+        auth_manager
+            .create_api_key("later".to_string(), crate::Role::Monitor, None, None)
+            .await
+            .unwrap();
+        let encrypted: EncryptedData =
+            serde_json::from_slice(&std::fs::read(&path).unwrap()).unwrap();
+        assert!(decrypt_data(&encrypted, &storage_key).is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_zero_rotate_schedule_is_rejected() {
+        let vault_config = VaultConfig {
+            rotate_schedule: Some(Duration::ZERO),
+            ..VaultConfig::default()
+        };
+        let result = VaultAuthenticationManager::new_with_vault(
+            AuthConfig::memory(),
+            None,
+            Some(vault_config),
+            true,
+        )
+        .await;
+        assert!(matches!(result, Err(VaultAuthManagerError::ConfigError(_))));
+    }
 
     #[test]
     fn test_vault_status_display() {
@@ -363,6 +843,9 @@ mod tests {
                 read_only: false,
             }),
             fallback_enabled: true,
+            degraded: false,
+            last_rotation_error: None,
+            last_rotated_at: None,
         };
 
         let output = status.to_string();
@@ -4,10 +4,20 @@
 //! for incoming requests, integrating with the AuthenticationManager and
 //! permission system.
 
-use crate::{AuthContext, AuthenticationManager, models::Role, security::RequestSecurityValidator};
+use crate::{
+    AuthContext, AuthenticationManager,
+    models::Role,
+    permissions::PermissionChecker,
+    security::RequestSecurityValidator,
+    transport::auth_extractors::{AuthUtils, TransportType},
+};
+use pulseengine_mcp_protocol::ErrorCode;
 use std::collections::HashMap;
+use std::net::IpAddr;
 use std::sync::Arc;
+use std::time::{Duration, Instant};
 use thiserror::Error;
+use tokio::sync::RwLock;
 use tracing::{debug, error, warn};
 
 /// Errors returned by the auth middleware
@@ -27,6 +37,10 @@ pub enum AuthMiddlewareError {
 }
 
 /// Errors that can occur during authentication extraction
+///
+/// Like [`TransportAuthError`](crate::transport::TransportAuthError), each
+/// variant has a machine-readable [`reason`](Self::reason) and its own
+/// [`ErrorCode`].
 #[derive(Debug, Error)]
 pub enum AuthExtractionError {
     #[error("No authentication provided")]
@@ -35,11 +49,58 @@ pub enum AuthExtractionError {
     #[error("Invalid authentication format: {0}")]
     InvalidFormat(String),
 
-    #[error("Authentication method not supported: {0}")]
-    UnsupportedMethod(String),
-
     #[error("Missing required header: {0}")]
     MissingHeader(String),
+
+    #[error("Malformed authentication header: {0}")]
+    MalformedHeader(String),
+
+    #[error("Unsupported authentication scheme: {0}")]
+    UnsupportedScheme(String),
+
+    #[error("Empty credential")]
+    EmptyCredential,
+
+    #[error("Authentication failed: {0}")]
+    AuthFailed(String),
+}
+
+impl AuthExtractionError {
+    /// Machine-readable reason, sent to clients as `data.reason`
+    pub fn reason(&self) -> &'static str {
+        match self {
+            Self::NoAuth => "no_auth",
+            Self::InvalidFormat(_) => "invalid_format",
+            Self::MissingHeader(_) => "missing_header",
+            Self::MalformedHeader(_) => "malformed_header",
+            Self::UnsupportedScheme(_) => "unsupported_scheme",
+            Self::EmptyCredential => "empty_credential",
+            Self::AuthFailed(_) => "auth_failed",
+        }
+    }
+
+    /// Protocol error code reported to clients
+    pub fn error_code(&self) -> ErrorCode {
+        match self {
+            Self::NoAuth => ErrorCode::AuthenticationRequired,
+            Self::InvalidFormat(_) => ErrorCode::InvalidCredentialFormat,
+            Self::MissingHeader(_) => ErrorCode::MissingAuthHeader,
+            Self::MalformedHeader(_) => ErrorCode::MalformedAuthHeader,
+            Self::UnsupportedScheme(_) => ErrorCode::UnsupportedAuthScheme,
+            Self::EmptyCredential => ErrorCode::EmptyCredential,
+            Self::AuthFailed(_) => ErrorCode::Unauthorized,
+        }
+    }
+}
+
+impl From<AuthExtractionError> for pulseengine_mcp_protocol::Error {
+    fn from(err: AuthExtractionError) -> Self {
+        let mut data = serde_json::json!({ "reason": err.reason() });
+        if let AuthExtractionError::UnsupportedScheme(scheme) = &err {
+            data["scheme"] = serde_json::Value::String(scheme.clone());
+        }
+        Self::with_data(err.error_code(), err.to_string(), data)
+    }
 }
 
 /// Configuration for MCP authentication middleware
@@ -63,8 +124,17 @@ pub struct McpAuthConfig {
     /// Enable audit logging for authentication events
     pub enable_audit_logging: bool,
 
-    /// Client IP header name for proxy environments
+    /// Client IP header name for proxy environments, honoured only for
+    /// requests whose peer is one of `trusted_proxies`
     pub client_ip_header: Option<String>,
+
+    /// Proxy addresses or CIDR ranges allowed to report the client IP
+    pub trusted_proxies: Vec<String>,
+
+    /// Reuse the auth context of WebSocket and stdio connections for this
+    /// long before re-validating their credentials (`None` validates every
+    /// request)
+    pub connection_auth_ttl: Option<Duration>,
 }
 
 impl Default for McpAuthConfig {
@@ -77,6 +147,8 @@ impl Default for McpAuthConfig {
             auth_header_name: "Authorization".to_string(),
             enable_audit_logging: true,
             client_ip_header: Some("X-Forwarded-For".to_string()),
+            trusted_proxies: Vec::new(),
+            connection_auth_ttl: None,
         }
     }
 }
@@ -141,6 +213,15 @@ impl McpRequestContext {
     }
 }
 
+/// Auth context cached for a long-lived connection
+struct ConnectionAuth {
+    auth_context: AuthContext,
+    auth_method: String,
+    /// Credentials used to re-validate once the cached context expires
+    headers: HashMap<String, String>,
+    validated_at: Instant,
+}
+
 /// MCP Authentication Middleware
 pub struct McpAuthMiddleware {
     /// Authentication manager for key validation
@@ -151,16 +232,22 @@ pub struct McpAuthMiddleware {
 
     /// Request security validator
     security_validator: Arc<RequestSecurityValidator>,
+
+    /// Auth contexts of connection-oriented transports, by connection ID
+    connection_auth: RwLock<HashMap<String, ConnectionAuth>>,
+
+    /// Per-method, tool, prompt and resource permissions
+    permission_checker: Option<Arc<PermissionChecker>>,
 }
 
 impl McpAuthMiddleware {
     /// Create a new MCP authentication middleware
     pub fn new(auth_manager: Arc<AuthenticationManager>, config: McpAuthConfig) -> Self {
-        Self {
+        Self::with_security_validator(
             auth_manager,
             config,
-            security_validator: Arc::new(RequestSecurityValidator::default()),
-        }
+            Arc::new(RequestSecurityValidator::default()),
+        )
     }
 
     /// Create with custom security validator
@@ -173,6 +260,8 @@ impl McpAuthMiddleware {
             auth_manager,
             config,
             security_validator,
+            connection_auth: RwLock::new(HashMap::new()),
+            permission_checker: None,
         }
     }
 
@@ -181,6 +270,30 @@ impl McpAuthMiddleware {
         Self::new(auth_manager, McpAuthConfig::default())
     }
 
+    /// Check authenticated callers against `checker` when
+    /// `enable_permission_checking` is set
+    ///
+    /// The middleware checks the method of every request; servers check the
+    /// tool, prompt or resource a request names against
+    /// [`permission_checker`](Self::permission_checker). Denials are audited
+    /// when the checker has an audit logger.
+    pub fn with_permission_checker(mut self, checker: PermissionChecker) -> Self {
+        self.permission_checker = Some(Arc::new(checker));
+        self
+    }
+
+    /// The permission checker in effect, if permission checking is enabled
+    pub fn permission_checker(&self) -> Option<&Arc<PermissionChecker>> {
+        self.permission_checker
+            .as_ref()
+            .filter(|_| self.config.enable_permission_checking)
+    }
+
+    /// Get the middleware configuration
+    pub fn config(&self) -> &McpAuthConfig {
+        &self.config
+    }
+
     /// Get access to the security validator for monitoring violations
     pub fn security_validator(&self) -> &RequestSecurityValidator {
         &self.security_validator
@@ -190,22 +303,28 @@ impl McpAuthMiddleware {
     ///
     /// Takes the method name, an optional request ID, and optional HTTP headers.
     /// Returns the auth context on success, or an `AuthMiddlewareError` on failure.
+    /// Without a peer address the client IP is unknown; use
+    /// [`authenticate_peer`](Self::authenticate_peer) for network transports.
     pub async fn authenticate(
         &self,
         method: &str,
         request_id: Option<String>,
         headers: Option<&HashMap<String, String>>,
     ) -> Result<McpRequestContext, AuthMiddlewareError> {
-        let id = request_id.unwrap_or_else(|| uuid::Uuid::new_v4().to_string());
-        let mut context = McpRequestContext::new(id);
+        self.authenticate_peer(None, method, request_id, headers)
+            .await
+    }
 
-        // Extract client IP if available
-        if let Some(headers) = headers
-            && let Some(ip_header) = &self.config.client_ip_header
-            && let Some(client_ip) = headers.get(ip_header)
-        {
-            context = context.with_client_ip(client_ip.clone());
-        }
+    /// Authenticate and authorize a request from the socket peer `peer_ip`
+    pub async fn authenticate_peer(
+        &self,
+        peer_ip: Option<IpAddr>,
+        method: &str,
+        request_id: Option<String>,
+        headers: Option<&HashMap<String, String>>,
+    ) -> Result<McpRequestContext, AuthMiddlewareError> {
+        let client_ip = self.client_ip(peer_ip, headers);
+        let mut context = self.new_context(request_id, client_ip.clone());
 
         // Check if authentication is required for this method
         if self.should_skip_auth(method) {
@@ -215,7 +334,8 @@ impl McpAuthMiddleware {
 
         // Extract authentication from headers
         let auth_result = if let Some(headers) = headers {
-            self.extract_authentication(headers).await
+            self.extract_authentication(headers, client_ip.as_deref())
+                .await
         } else {
             Err(AuthExtractionError::NoAuth)
         };
@@ -246,44 +366,193 @@ impl McpAuthMiddleware {
         }
     }
 
+    /// Authenticate a request arriving on a long-lived connection
+    ///
+    /// With `connection_auth_ttl` set, WebSocket and stdio connections are
+    /// validated once and later requests on the same connection, which may
+    /// omit credentials, reuse the cached context until it expires; the
+    /// stored credentials are then re-validated. HTTP is stateless and always
+    /// authenticates per request, as does every transport without a TTL.
+    pub async fn authenticate_connection(
+        &self,
+        connection_id: &str,
+        transport: &TransportType,
+        peer_ip: Option<IpAddr>,
+        method: &str,
+        request_id: Option<String>,
+        headers: Option<&HashMap<String, String>>,
+    ) -> Result<McpRequestContext, AuthMiddlewareError> {
+        let Some(ttl) = self.config.connection_auth_ttl else {
+            return self
+                .authenticate_peer(peer_ip, method, request_id, headers)
+                .await;
+        };
+        if !matches!(transport, TransportType::WebSocket | TransportType::Stdio)
+            || self.should_skip_auth(method)
+        {
+            return self
+                .authenticate_peer(peer_ip, method, request_id, headers)
+                .await;
+        }
+
+        let client_ip = self.client_ip(peer_ip, headers);
+        let mut context = self.new_context(request_id, client_ip.clone());
+        let cached = {
+            let connections = self.connection_auth.read().await;
+            connections.get(connection_id).map(|cached| {
+                (
+                    cached.validated_at.elapsed() < ttl,
+                    cached.auth_context.clone(),
+                    cached.auth_method.clone(),
+                    cached.headers.clone(),
+                )
+            })
+        };
+
+        let (auth_context, auth_method) = match cached {
+            Some((true, auth_context, auth_method, _)) => {
+                debug!(
+                    "Reusing cached auth context for connection {}",
+                    connection_id
+                );
+                (auth_context, auth_method)
+            }
+            cached => {
+                // Prefer fresh credentials, falling back to the ones the
+                // connection authenticated with
+                let credentials = headers
+                    .cloned()
+                    .filter(|headers| self.has_credentials(headers))
+                    .or_else(|| cached.map(|(_, _, _, headers)| headers));
+                let auth_result = match &credentials {
+                    Some(credentials) => {
+                        self.extract_authentication(credentials, client_ip.as_deref())
+                            .await
+                    }
+                    None => Err(AuthExtractionError::NoAuth),
+                };
+
+                match auth_result {
+                    Ok((auth_context, auth_method)) => {
+                        debug!("Authenticated connection {}", connection_id);
+                        self.connection_auth.write().await.insert(
+                            connection_id.to_string(),
+                            ConnectionAuth {
+                                auth_context: auth_context.clone(),
+                                auth_method: auth_method.clone(),
+                                headers: credentials.unwrap_or_default(),
+                                validated_at: Instant::now(),
+                            },
+                        );
+                        (auth_context, auth_method)
+                    }
+                    Err(e) => {
+                        self.end_connection(connection_id).await;
+                        if !self.config.require_auth {
+                            debug!("Connection authentication failed but not required: {}", e);
+                            return Ok(context);
+                        }
+                        warn!("Connection authentication failed: {}", e);
+                        return Err(AuthMiddlewareError::AuthRequired(e.to_string()));
+                    }
+                }
+            }
+        };
+
+        context = context.with_auth(auth_context, auth_method);
+        if let Err(e) = self.check_method_permissions(method, &context).await {
+            error!("Method permission check failed: {}", e);
+            return Err(AuthMiddlewareError::AccessDenied(e));
+        }
+        Ok(context)
+    }
+
+    /// Forget the cached auth context of a closed connection
+    pub async fn end_connection(&self, connection_id: &str) {
+        self.connection_auth.write().await.remove(connection_id);
+    }
+
+    /// Create a request context, recording the client IP if known
+    fn new_context(
+        &self,
+        request_id: Option<String>,
+        client_ip: Option<String>,
+    ) -> McpRequestContext {
+        let id = request_id.unwrap_or_else(|| uuid::Uuid::new_v4().to_string());
+        let context = McpRequestContext::new(id);
+        match client_ip {
+            Some(client_ip) => context.with_client_ip(client_ip),
+            None => context,
+        }
+    }
+
+    /// Whether the headers carry credentials `extract_authentication` reads
+    fn has_credentials(&self, headers: &HashMap<String, String>) -> bool {
+        headers.contains_key(&self.config.auth_header_name) || headers.contains_key("X-API-Key")
+    }
+
     /// Extract authentication from request headers
     async fn extract_authentication(
         &self,
         headers: &HashMap<String, String>,
+        client_ip: Option<&str>,
     ) -> Result<(AuthContext, String), AuthExtractionError> {
         // Try to extract from Authorization header
         if let Some(auth_header) = headers.get(&self.config.auth_header_name) {
-            return self.parse_auth_header(auth_header).await;
+            return self.parse_auth_header(auth_header, client_ip).await;
         }
 
         // Try to extract from X-API-Key header
         if let Some(api_key) = headers.get("X-API-Key") {
-            return self.validate_api_key(api_key, "X-API-Key").await;
+            return self.validate_api_key(api_key, "X-API-Key", client_ip).await;
         }
 
         Err(AuthExtractionError::NoAuth)
     }
 
+    /// Originating client IP of a request from `peer_ip`
+    ///
+    /// The client IP header is only read when the peer is a trusted proxy.
+    fn client_ip(
+        &self,
+        peer_ip: Option<IpAddr>,
+        headers: Option<&HashMap<String, String>>,
+    ) -> Option<String> {
+        let forwarded = headers
+            .zip(self.config.client_ip_header.as_ref())
+            .and_then(|(headers, ip_header)| headers.get(ip_header));
+        AuthUtils::resolve_client_ip(
+            peer_ip,
+            forwarded.map(String::as_str),
+            &self.config.trusted_proxies,
+        )
+        .map(|ip| ip.to_string())
+    }
+
     /// Parse the Authorization header
     async fn parse_auth_header(
         &self,
         auth_header: &str,
+        client_ip: Option<&str>,
     ) -> Result<(AuthContext, String), AuthExtractionError> {
         let parts: Vec<&str> = auth_header.splitn(2, ' ').collect();
-        if parts.len() != 2 {
-            return Err(AuthExtractionError::InvalidFormat(
+        if parts.len() != 2 || parts[0].is_empty() {
+            return Err(AuthExtractionError::MalformedHeader(
                 "Authorization header must be in format 'Type Token'".to_string(),
             ));
         }
 
         let auth_type = parts[0].to_lowercase();
         let token = parts[1];
-
-        match auth_type.as_str() {
-            "bearer" => self.validate_api_key(token, "Bearer").await,
-            "apikey" => self.validate_api_key(token, "ApiKey").await,
-            _ => Err(AuthExtractionError::UnsupportedMethod(auth_type)),
+        let method = match auth_type.as_str() {
+            "bearer" => "Bearer",
+            "apikey" => "ApiKey",
+            _ => return Err(AuthExtractionError::UnsupportedScheme(parts[0].to_string())),
+        };
+        if token.trim().is_empty() {
+            return Err(AuthExtractionError::EmptyCredential);
         }
+        self.validate_api_key(token, method, client_ip).await
     }
 
     /// Validate an API key
@@ -291,15 +560,19 @@ impl McpAuthMiddleware {
         &self,
         api_key: &str,
         method: &str,
+        client_ip: Option<&str>,
     ) -> Result<(AuthContext, String), AuthExtractionError> {
-        match self.auth_manager.validate_api_key(api_key, None).await {
+        if api_key.trim().is_empty() {
+            return Err(AuthExtractionError::EmptyCredential);
+        }
+        match self.auth_manager.validate_api_key(api_key, client_ip).await {
             Ok(Some(auth_context)) => Ok((auth_context, method.to_string())),
-            Ok(None) => Err(AuthExtractionError::InvalidFormat(
+            Ok(None) => Err(AuthExtractionError::AuthFailed(
                 "Invalid API key".to_string(),
             )),
             Err(e) => {
                 error!("API key validation failed: {}", e);
-                Err(AuthExtractionError::InvalidFormat(
+                Err(AuthExtractionError::AuthFailed(
                     "Authentication failed".to_string(),
                 ))
             }
@@ -307,11 +580,10 @@ impl McpAuthMiddleware {
     }
 
     /// Check if authentication should be skipped for a method
+    ///
+    /// Without `require_auth` other methods still authenticate when the
+    /// caller presents credentials, and proceed anonymously otherwise.
     fn should_skip_auth(&self, method: &str) -> bool {
-        if !self.config.require_auth {
-            return true;
-        }
-
         self.config.anonymous_methods.contains(&method.to_string())
     }
 
@@ -340,6 +612,15 @@ impl McpAuthMiddleware {
             }
         }
 
+        if let Some(checker) = self.permission_checker()
+            && let Some(auth_context) = &context.auth.auth_context
+        {
+            checker
+                .check_method(auth_context, method)
+                .await
+                .map_err(|e| e.to_string())?;
+        }
+
         Ok(())
     }
 }
@@ -377,14 +658,319 @@ mod tests {
         let middleware = McpAuthMiddleware::with_default_config(auth_manager);
 
         // Test invalid format
-        let result = middleware.parse_auth_header("invalid").await;
-        assert!(result.is_err());
+        let result = middleware.parse_auth_header("invalid", None).await;
+        assert!(matches!(
+            result,
+            Err(AuthExtractionError::MalformedHeader(_))
+        ));
 
-        // Test unsupported method
-        let result = middleware.parse_auth_header("Basic token123").await;
+        // Test unsupported scheme
+        let result = middleware.parse_auth_header("Basic token123", None).await;
         assert!(matches!(
             result,
-            Err(AuthExtractionError::UnsupportedMethod(_))
+            Err(AuthExtractionError::UnsupportedScheme(_))
         ));
+
+        let result = middleware.parse_auth_header("Bearer ", None).await;
+        assert!(matches!(result, Err(AuthExtractionError::EmptyCredential)));
+
+        let result = middleware
+            .parse_auth_header("Bearer not-a-real-key", None)
+            .await;
+        assert!(matches!(result, Err(AuthExtractionError::AuthFailed(_))));
+
+        // Every variant has its own code
+        let variants = [
+            AuthExtractionError::NoAuth,
+            AuthExtractionError::InvalidFormat(String::new()),
+            AuthExtractionError::MissingHeader(String::new()),
+            AuthExtractionError::MalformedHeader(String::new()),
+            AuthExtractionError::UnsupportedScheme(String::new()),
+            AuthExtractionError::EmptyCredential,
+            AuthExtractionError::AuthFailed(String::new()),
+        ];
+        let codes: std::collections::HashSet<_> = variants
+            .iter()
+            .map(AuthExtractionError::error_code)
+            .collect();
+        assert_eq!(codes.len(), variants.len());
+        let error: pulseengine_mcp_protocol::Error =
+            AuthExtractionError::UnsupportedScheme("Basic".to_string()).into();
+        assert_eq!(error.code, ErrorCode::UnsupportedAuthScheme);
+        assert_eq!(error.data.unwrap()["reason"], "unsupported_scheme");
+    }
+
+    #[tokio::test]
+    async fn test_permission_checker_denies_and_audits_methods() {
+        use crate::audit::{AuditConfig, AuditLogger};
+        use crate::permissions::PermissionConfig;
+
+        let temp_dir = tempfile::tempdir().unwrap();
+        let log_file = temp_dir.path().join("audit.jsonl");
+        let audit_logger = AuditLogger::new(AuditConfig {
+            enabled: true,
+            log_file: log_file.clone(),
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+        let auth_manager = Arc::new(
+            AuthenticationManager::new(AuthConfig::memory())
+                .await
+                .unwrap(),
+        );
+        let key = auth_manager
+            .create_api_key("ops".to_string(), Role::Operator, None, None)
+            .await
+            .unwrap();
+        let checker = PermissionChecker::new(PermissionConfig::production())
+            .with_audit_logger(Arc::new(audit_logger));
+        let middleware =
+            McpAuthMiddleware::with_default_config(auth_manager).with_permission_checker(checker);
+        let headers = HashMap::from([("Authorization".to_string(), format!("Bearer {}", key.key))]);
+
+        assert!(
+            middleware
+                .authenticate("tools/list", None, Some(&headers))
+                .await
+                .is_ok()
+        );
+        let denied = middleware
+            .authenticate("logging/setLevel", None, Some(&headers))
+            .await;
+        assert!(matches!(denied, Err(AuthMiddlewareError::AccessDenied(_))));
+
+        let content = tokio::fs::read_to_string(&log_file).await.unwrap();
+        let denials: Vec<serde_json::Value> = content
+            .lines()
+            .map(|line| serde_json::from_str::<serde_json::Value>(line).unwrap())
+            .filter(|record| record["event_type"] == "permission_denied")
+            .collect();
+        assert_eq!(denials.len(), 1);
+        assert_eq!(denials[0]["metadata"]["action"], "logging/setLevel");
+    }
+
+    async fn connection_middleware(ttl: Duration) -> (McpAuthMiddleware, String, String) {
+        let auth_manager = Arc::new(
+            AuthenticationManager::new(AuthConfig::memory())
+                .await
+                .unwrap(),
+        );
+        let key = auth_manager
+            .create_api_key("ws".to_string(), Role::Operator, None, None)
+            .await
+            .unwrap();
+        let config = McpAuthConfig {
+            connection_auth_ttl: Some(ttl),
+            ..Default::default()
+        };
+        (
+            McpAuthMiddleware::new(auth_manager, config),
+            key.id,
+            key.key,
+        )
+    }
+
+    #[tokio::test]
+    async fn test_client_ip_header_only_trusted_from_proxies() {
+        let auth_manager = Arc::new(
+            AuthenticationManager::new(AuthConfig::memory())
+                .await
+                .unwrap(),
+        );
+        let key = auth_manager
+            .create_api_key(
+                "office".to_string(),
+                Role::Operator,
+                None,
+                Some(vec!["10.0.0.0/8".to_string()]),
+            )
+            .await
+            .unwrap();
+        let middleware = McpAuthMiddleware::new(
+            auth_manager,
+            McpAuthConfig {
+                trusted_proxies: vec!["192.0.2.1".to_string()],
+                ..Default::default()
+            },
+        );
+        let headers = HashMap::from([
+            ("X-API-Key".to_string(), key.key),
+            ("X-Forwarded-For".to_string(), "10.1.2.3".to_string()),
+        ]);
+        let peer = |ip: &str| Some(ip.parse::<IpAddr>().unwrap());
+
+        // A client can't claim a whitelisted address itself
+        let spoofed = middleware
+            .authenticate_peer(peer("203.0.113.9"), "tools/list", None, Some(&headers))
+            .await;
+        assert!(matches!(spoofed, Err(AuthMiddlewareError::AuthRequired(_))));
+        let no_peer = middleware
+            .authenticate("tools/list", None, Some(&headers))
+            .await;
+        assert!(no_peer.is_err());
+
+        let proxied = middleware
+            .authenticate_peer(peer("192.0.2.1"), "tools/list", None, Some(&headers))
+            .await
+            .unwrap();
+        assert_eq!(proxied.auth.client_ip.as_deref(), Some("10.1.2.3"));
+    }
+
+    async fn usage_count(middleware: &McpAuthMiddleware, key_id: &str) -> u64 {
+        middleware
+            .auth_manager
+            .get_key(key_id)
+            .await
+            .unwrap()
+            .usage_count
+    }
+
+    #[tokio::test]
+    async fn test_websocket_connection_authenticates_once() {
+        let (middleware, key_id, secret) = connection_middleware(Duration::from_secs(60)).await;
+        let headers = HashMap::from([("Authorization".to_string(), format!("Bearer {secret}"))]);
+
+        let first = middleware
+            .authenticate_connection(
+                "conn-1",
+                &TransportType::WebSocket,
+                None,
+                "tools/list",
+                None,
+                Some(&headers),
+            )
+            .await
+            .unwrap();
+        assert!(!first.auth.is_anonymous);
+
+        // Later messages on the connection carry no credentials
+        for _ in 0..3 {
+            let context = middleware
+                .authenticate_connection(
+                    "conn-1",
+                    &TransportType::WebSocket,
+                    None,
+                    "tools/call",
+                    None,
+                    None,
+                )
+                .await
+                .unwrap();
+            let auth_context = context.auth.auth_context.unwrap();
+            assert_eq!(auth_context.api_key_id.as_deref(), Some(key_id.as_str()));
+        }
+        assert_eq!(usage_count(&middleware, &key_id).await, 1);
+
+        // Other connections and closed connections don't share the context
+        let other = middleware
+            .authenticate_connection(
+                "conn-2",
+                &TransportType::WebSocket,
+                None,
+                "tools/list",
+                None,
+                None,
+            )
+            .await;
+        assert!(matches!(other, Err(AuthMiddlewareError::AuthRequired(_))));
+        middleware.end_connection("conn-1").await;
+        let closed = middleware
+            .authenticate_connection(
+                "conn-1",
+                &TransportType::WebSocket,
+                None,
+                "tools/list",
+                None,
+                None,
+            )
+            .await;
+        assert!(matches!(closed, Err(AuthMiddlewareError::AuthRequired(_))));
+    }
+
+    #[tokio::test]
+    async fn test_connection_revalidates_after_expiry() {
+        let (middleware, key_id, secret) = connection_middleware(Duration::from_millis(50)).await;
+        let headers = HashMap::from([("X-API-Key".to_string(), secret)]);
+
+        middleware
+            .authenticate_connection(
+                "conn",
+                &TransportType::Stdio,
+                None,
+                "tools/list",
+                None,
+                Some(&headers),
+            )
+            .await
+            .unwrap();
+        middleware
+            .authenticate_connection(
+                "conn",
+                &TransportType::Stdio,
+                None,
+                "tools/list",
+                None,
+                None,
+            )
+            .await
+            .unwrap();
+        assert_eq!(usage_count(&middleware, &key_id).await, 1);
+
+        tokio::time::sleep(Duration::from_millis(80)).await;
+        let context = middleware
+            .authenticate_connection(
+                "conn",
+                &TransportType::Stdio,
+                None,
+                "tools/list",
+                None,
+                None,
+            )
+            .await
+            .unwrap();
+        assert!(!context.auth.is_anonymous);
+        assert_eq!(usage_count(&middleware, &key_id).await, 2);
+
+        // A revoked key fails re-validation once the cache expires
+        middleware.auth_manager.revoke_key(&key_id).await.unwrap();
+        tokio::time::sleep(Duration::from_millis(80)).await;
+        let revoked = middleware
+            .authenticate_connection(
+                "conn",
+                &TransportType::Stdio,
+                None,
+                "tools/list",
+                None,
+                None,
+            )
+            .await;
+        assert!(matches!(revoked, Err(AuthMiddlewareError::AuthRequired(_))));
+    }
+
+    #[tokio::test]
+    async fn test_http_authenticates_every_request() {
+        let (middleware, key_id, secret) = connection_middleware(Duration::from_secs(60)).await;
+        let headers = HashMap::from([("Authorization".to_string(), format!("Bearer {secret}"))]);
+
+        for _ in 0..2 {
+            middleware
+                .authenticate_connection(
+                    "conn",
+                    &TransportType::Http,
+                    None,
+                    "tools/list",
+                    None,
+                    Some(&headers),
+                )
+                .await
+                .unwrap();
+        }
+        assert_eq!(usage_count(&middleware, &key_id).await, 2);
+
+        let without_credentials = middleware
+            .authenticate_connection("conn", &TransportType::Http, None, "tools/list", None, None)
+            .await;
+        assert!(without_credentials.is_err());
     }
 }
@@ -11,8 +11,10 @@ use crate::{
     },
     security::RequestSecurityValidator,
     session::{Session, SessionError, SessionManager},
+    transport::auth_extractors::AuthUtils,
 };
 use std::collections::HashMap;
+use std::net::IpAddr;
 use std::sync::Arc;
 use thiserror::Error;
 use tracing::{debug, error, info, warn};
@@ -189,23 +191,35 @@ impl SessionMiddleware {
     /// Authenticate a request with session awareness.
     ///
     /// Takes the method name, an optional request ID, and optional HTTP headers.
-    /// Returns the session request context on success.
+    /// Returns the session request context on success. Without a peer
+    /// address the client IP is unknown; use
+    /// [`authenticate_peer`](Self::authenticate_peer) for network transports.
     pub async fn authenticate(
         &self,
         method: &str,
         request_id: Option<String>,
         headers: Option<&HashMap<String, String>>,
+    ) -> Result<SessionRequestContext, AuthMiddlewareError> {
+        self.authenticate_peer(None, method, request_id, headers)
+            .await
+    }
+
+    /// Authenticate a request from the socket peer `peer_ip` with session awareness
+    pub async fn authenticate_peer(
+        &self,
+        peer_ip: Option<IpAddr>,
+        method: &str,
+        request_id: Option<String>,
+        headers: Option<&HashMap<String, String>>,
     ) -> Result<SessionRequestContext, AuthMiddlewareError> {
         let id = request_id.unwrap_or_else(|| uuid::Uuid::new_v4().to_string());
 
         let mut base_context = McpRequestContext::new(id);
         let mut session_context = SessionRequestContext::new(base_context.clone());
 
-        // Extract client IP
-        if let Some(headers) = headers
-            && let Some(ip_header) = &self.config.auth_config.client_ip_header
-            && let Some(client_ip) = headers.get(ip_header)
-        {
+        // Extract client IP, believing the IP header only from trusted proxies
+        let client_ip = self.client_ip(peer_ip, headers);
+        if let Some(client_ip) = &client_ip {
             base_context = base_context.with_client_ip(client_ip.clone());
         }
 
@@ -217,7 +231,9 @@ impl SessionMiddleware {
         }
 
         // Try different authentication methods
-        let auth_result = self.authenticate_request(headers).await;
+        let auth_result = self
+            .authenticate_request(headers, client_ip.as_deref())
+            .await;
 
         match auth_result {
             Ok((auth_context, auth_method, session)) => {
@@ -232,7 +248,10 @@ impl SessionMiddleware {
                     session_context = session_context.with_session(session, false);
                 } else if self.config.auto_create_sessions && !session_context.jwt_authenticated {
                     // Auto-create session for API key authentication
-                    match self.create_auto_session(&auth_context, headers).await {
+                    match self
+                        .create_auto_session(&auth_context, headers, client_ip.clone())
+                        .await
+                    {
                         Ok(session) => {
                             session_context = session_context.with_session(session, true);
                             info!(
@@ -269,10 +288,29 @@ impl SessionMiddleware {
         }
     }
 
+    /// Originating client IP of a request from `peer_ip`
+    fn client_ip(
+        &self,
+        peer_ip: Option<IpAddr>,
+        headers: Option<&HashMap<String, String>>,
+    ) -> Option<String> {
+        let auth_config = &self.config.auth_config;
+        let forwarded = headers
+            .zip(auth_config.client_ip_header.as_ref())
+            .and_then(|(headers, ip_header)| headers.get(ip_header));
+        AuthUtils::resolve_client_ip(
+            peer_ip,
+            forwarded.map(String::as_str),
+            &auth_config.trusted_proxies,
+        )
+        .map(|ip| ip.to_string())
+    }
+
     /// Authenticate request using multiple methods
     async fn authenticate_request(
         &self,
         headers: Option<&HashMap<String, String>>,
+        client_ip: Option<&str>,
     ) -> Result<(AuthContext, String, Option<Session>), SessionMiddlewareError> {
         if let Some(headers) = headers {
             // Try JWT authentication first
@@ -290,7 +328,9 @@ impl SessionMiddleware {
             }
 
             // Fall back to traditional API key authentication
-            if let Ok((auth_context, method)) = self.try_api_key_authentication(headers).await {
+            if let Ok((auth_context, method)) =
+                self.try_api_key_authentication(headers, client_ip).await
+            {
                 return Ok((auth_context, method, None));
             }
         }
@@ -337,17 +377,18 @@ impl SessionMiddleware {
     async fn try_api_key_authentication(
         &self,
         headers: &HashMap<String, String>,
+        client_ip: Option<&str>,
     ) -> Result<(AuthContext, String), SessionMiddlewareError> {
         // Try Authorization header
         if let Some(auth_header) = headers.get(&self.config.auth_config.auth_header_name)
-            && let Ok((auth_context, method)) = self.parse_auth_header(auth_header).await
+            && let Ok((auth_context, method)) = self.parse_auth_header(auth_header, client_ip).await
         {
             return Ok((auth_context, method));
         }
 
         // Try X-API-Key header
         if let Some(api_key) = headers.get("X-API-Key")
-            && let Ok(auth_context) = self.validate_api_key(api_key).await
+            && let Ok(auth_context) = self.validate_api_key(api_key, client_ip).await
         {
             return Ok((auth_context, "X-API-Key".to_string()));
         }
@@ -361,11 +402,12 @@ impl SessionMiddleware {
     async fn parse_auth_header(
         &self,
         auth_header: &str,
+        client_ip: Option<&str>,
     ) -> Result<(AuthContext, String), SessionMiddlewareError> {
         let parts: Vec<&str> = auth_header.splitn(2, ' ').collect();
         if parts.len() != 2 {
             return Err(SessionMiddlewareError::AuthError(
-                AuthExtractionError::InvalidFormat(
+                AuthExtractionError::MalformedHeader(
                     "Invalid Authorization header format".to_string(),
                 ),
             ));
@@ -373,7 +415,7 @@ impl SessionMiddleware {
 
         match parts[0] {
             "Bearer" => {
-                let auth_context = self.validate_api_key(parts[1]).await?;
+                let auth_context = self.validate_api_key(parts[1], client_ip).await?;
                 Ok((auth_context, "Bearer".to_string()))
             }
             "Basic" => {
@@ -399,30 +441,34 @@ impl SessionMiddleware {
                     ));
                 }
 
-                let auth_context = self.validate_api_key(auth_parts[0]).await?;
+                let auth_context = self.validate_api_key(auth_parts[0], client_ip).await?;
                 Ok((auth_context, "Basic".to_string()))
             }
             _ => Err(SessionMiddlewareError::AuthError(
-                AuthExtractionError::UnsupportedMethod(parts[0].to_string()),
+                AuthExtractionError::UnsupportedScheme(parts[0].to_string()),
             )),
         }
     }
 
     /// Validate API key and return auth context
-    async fn validate_api_key(&self, api_key: &str) -> Result<AuthContext, SessionMiddlewareError> {
+    async fn validate_api_key(
+        &self,
+        api_key: &str,
+        client_ip: Option<&str>,
+    ) -> Result<AuthContext, SessionMiddlewareError> {
         let auth_result = self
             .auth_manager
-            .validate_api_key(api_key, None)
+            .validate_api_key(api_key, client_ip)
             .await
             .map_err(|e| {
-                SessionMiddlewareError::AuthError(AuthExtractionError::InvalidFormat(format!(
+                SessionMiddlewareError::AuthError(AuthExtractionError::AuthFailed(format!(
                     "API key validation failed: {}",
                     e
                 )))
             })?;
 
         auth_result.ok_or_else(|| {
-            SessionMiddlewareError::AuthError(AuthExtractionError::InvalidFormat(
+            SessionMiddlewareError::AuthError(AuthExtractionError::AuthFailed(
                 "Invalid API key".to_string(),
             ))
         })
@@ -433,17 +479,8 @@ impl SessionMiddleware {
         &self,
         auth_context: &AuthContext,
         headers: Option<&HashMap<String, String>>,
+        client_ip: Option<String>,
     ) -> Result<Session, SessionError> {
-        let client_ip = headers
-            .and_then(|h| {
-                self.config
-                    .auth_config
-                    .client_ip_header
-                    .as_ref()
-                    .and_then(|ip_header| h.get(ip_header))
-            })
-            .cloned();
-
         let user_agent = headers.and_then(|h| h.get("User-Agent")).cloned();
 
         let user_id = auth_context.api_key_id.clone().unwrap_or_else(|| {
@@ -1,9 +1,12 @@
 //! Authentication models
 
 use crate::crypto::hashing::Salt;
+use crate::permissions::PermissionChecker;
 use chrono::{DateTime, Utc};
+use ipnet::IpNet;
 use serde::{Deserialize, Serialize};
 use std::fmt;
+use std::net::IpAddr;
 
 /// API key for authentication with comprehensive metadata
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -21,19 +24,59 @@ pub struct ApiKey {
     /// Role-based permissions
     pub role: Role,
     /// Creation timestamp
+    #[serde(with = "pulseengine_mcp_protocol::timestamp::serde_format")]
     pub created_at: DateTime<Utc>,
     /// Optional expiration timestamp
+    #[serde(
+        with = "pulseengine_mcp_protocol::timestamp::serde_format::option",
+        default
+    )]
     pub expires_at: Option<DateTime<Utc>>,
     /// Last time this key was used
+    #[serde(
+        with = "pulseengine_mcp_protocol::timestamp::serde_format::option",
+        default
+    )]
     pub last_used: Option<DateTime<Utc>>,
-    /// IP address whitelist (empty = all IPs allowed)
+    /// IP addresses or CIDR ranges allowed to use the key (empty = all IPs allowed)
     #[serde(default)]
     pub ip_whitelist: Vec<String>,
+    /// IP addresses or CIDR ranges refused even when whitelisted
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub ip_denylist: Vec<String>,
     /// Is the key currently active
     pub active: bool,
     /// Usage count
     #[serde(default)]
     pub usage_count: u64,
+    /// Number of successful validations after which the key stops working
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_uses: Option<u64>,
+    /// Secret superseded by the last rotation, valid until its overlap ends
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub previous_secret: Option<RotatedSecret>,
+}
+
+/// A superseded key secret that keeps validating during a rotation overlap
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RotatedSecret {
+    /// Secure hash of the old secret token
+    pub secret_hash: String,
+    /// Salt used for hashing the old secret token
+    pub salt: Salt,
+    /// When the old secret stops being accepted
+    #[serde(with = "pulseengine_mcp_protocol::timestamp::serde_format")]
+    pub valid_until: DateTime<Utc>,
+    /// Number of authentications made with the old secret since the rotation
+    #[serde(default)]
+    pub usage_count: u64,
+}
+
+impl RotatedSecret {
+    /// Check if the overlap window has ended
+    pub fn is_expired(&self) -> bool {
+        Utc::now() > self.valid_until
+    }
 }
 
 impl ApiKey {
@@ -73,8 +116,11 @@ impl ApiKey {
             expires_at,
             last_used: None,
             ip_whitelist,
+            ip_denylist: Vec::new(),
             active: true,
             usage_count: 0,
+            max_uses: None,
+            previous_secret: None,
         }
     }
 
@@ -87,9 +133,37 @@ impl ApiKey {
         }
     }
 
+    /// Check if a limited-use key has used up all its validations
+    pub fn is_exhausted(&self) -> bool {
+        self.max_uses
+            .is_some_and(|max_uses| self.usage_count >= max_uses)
+    }
+
     /// Check if the key is valid for use
     pub fn is_valid(&self) -> bool {
-        self.active && !self.is_expired()
+        self.active && !self.is_expired() && !self.is_exhausted()
+    }
+
+    /// Check if `client_ip` may use this key
+    ///
+    /// Denylist entries take precedence over the whitelist, and a `*` in the
+    /// whitelist allows any address. An unknown client IP can't match the
+    /// denylist, but is refused when the key has a whitelist.
+    pub fn is_ip_allowed(&self, client_ip: Option<IpAddr>) -> bool {
+        let client_ip = client_ip.map(|ip| ip.to_canonical());
+        if let Some(ip) = client_ip
+            && self
+                .ip_denylist
+                .iter()
+                .any(|entry| ip_entry_matches(entry, ip))
+        {
+            return false;
+        }
+
+        self.ip_whitelist.is_empty()
+            || self.ip_whitelist.iter().any(|entry| {
+                entry == "*" || client_ip.is_some_and(|ip| ip_entry_matches(entry, ip))
+            })
     }
 
     /// Update last used timestamp
@@ -113,6 +187,86 @@ impl ApiKey {
         }
     }
 
+    /// Verify if the provided key matches the secret superseded by the last
+    /// rotation, as long as its overlap window is still open
+    pub fn verify_previous_secret(
+        &self,
+        provided_key: &str,
+    ) -> Result<bool, crate::crypto::hashing::HashingError> {
+        use crate::crypto::hashing::verify_api_key;
+
+        match &self.previous_secret {
+            Some(previous) if !previous.is_expired() => {
+                verify_api_key(provided_key, &previous.secret_hash, &previous.salt)
+            }
+            _ => Ok(false),
+        }
+    }
+
+    /// Replace the secret with a freshly generated one, keeping the current
+    /// secret valid until `valid_until`
+    ///
+    /// Returns the new plain text secret. A secret left over from an earlier
+    /// rotation is dropped.
+    pub fn rotate_secret(&mut self, valid_until: DateTime<Utc>) -> String {
+        use crate::crypto::hashing::{generate_salt, hash_api_key};
+        use crate::crypto::keys::generate_secure_key;
+
+        let (old_hash, old_salt) = match (self.secret_hash.take(), self.salt.take()) {
+            (Some(hash), Some(salt)) => (hash, salt),
+            _ => {
+                // Legacy plain text key: hash it so the overlap works the same way
+                let salt = generate_salt();
+                (hash_api_key(&self.key, &salt), salt)
+            }
+        };
+        self.previous_secret = Some(RotatedSecret {
+            secret_hash: old_hash,
+            salt: old_salt,
+            valid_until,
+            usage_count: 0,
+        });
+
+        let secret = generate_secure_key();
+        let salt = generate_salt();
+        self.secret_hash = Some(hash_api_key(&secret, &salt));
+        self.salt = Some(salt);
+        self.key = secret.clone();
+        secret
+    }
+
+    /// Drop the previous secret once its overlap window has ended
+    ///
+    /// Returns whether a secret was dropped.
+    pub fn expire_previous_secret(&mut self) -> bool {
+        if self
+            .previous_secret
+            .as_ref()
+            .is_some_and(RotatedSecret::is_expired)
+        {
+            self.previous_secret = None;
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Copy of this key that is safe to export: the plain text secret is
+    /// replaced by its salted hash, so the copy still validates the original
+    /// secret once imported but never exposes it
+    pub fn to_export(&self) -> ApiKey {
+        use crate::crypto::hashing::{generate_salt, hash_api_key};
+
+        let mut secure = self.to_secure_storage();
+        if secure.secret_hash.is_none() || secure.salt.is_none() {
+            // Legacy plain text key: hash it rather than exporting the secret
+            let salt = generate_salt();
+            secure.secret_hash = Some(hash_api_key(&self.key, &salt));
+            secure.salt = Some(salt);
+        }
+        secure.to_api_key()
+    }
+
     /// Convert to secure storage format (without plain text key)
     pub fn to_secure_storage(&self) -> SecureApiKey {
         SecureApiKey {
@@ -125,12 +279,31 @@ impl ApiKey {
             expires_at: self.expires_at,
             last_used: self.last_used,
             ip_whitelist: self.ip_whitelist.clone(),
+            ip_denylist: self.ip_denylist.clone(),
             active: self.active,
             usage_count: self.usage_count,
+            max_uses: self.max_uses,
+            previous_secret: self.previous_secret.clone(),
         }
     }
 }
 
+/// Parse an IP list entry, either a CIDR range or a single address
+///
+/// A single address is treated as a /32 (IPv4) or /128 (IPv6) range.
+pub fn parse_ip_range(entry: &str) -> Option<IpNet> {
+    let entry = entry.trim();
+    entry
+        .parse::<IpNet>()
+        .ok()
+        .or_else(|| entry.parse::<IpAddr>().ok().map(IpNet::from))
+}
+
+/// Check if `ip` falls within an IP list entry; unparseable entries never match
+fn ip_entry_matches(entry: &str, ip: IpAddr) -> bool {
+    parse_ip_range(entry).is_some_and(|range| range.contains(&ip))
+}
+
 /// Secure API key for storage (without plain text key)
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SecureApiKey {
@@ -145,19 +318,37 @@ pub struct SecureApiKey {
     /// Role-based permissions
     pub role: Role,
     /// Creation timestamp
+    #[serde(with = "pulseengine_mcp_protocol::timestamp::serde_format")]
     pub created_at: DateTime<Utc>,
     /// Optional expiration timestamp
+    #[serde(
+        with = "pulseengine_mcp_protocol::timestamp::serde_format::option",
+        default
+    )]
     pub expires_at: Option<DateTime<Utc>>,
     /// Last time this key was used
+    #[serde(
+        with = "pulseengine_mcp_protocol::timestamp::serde_format::option",
+        default
+    )]
     pub last_used: Option<DateTime<Utc>>,
-    /// IP address whitelist (empty = all IPs allowed)
+    /// IP addresses or CIDR ranges allowed to use the key (empty = all IPs allowed)
     #[serde(default)]
     pub ip_whitelist: Vec<String>,
+    /// IP addresses or CIDR ranges refused even when whitelisted
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub ip_denylist: Vec<String>,
     /// Is the key currently active
     pub active: bool,
     /// Usage count
     #[serde(default)]
     pub usage_count: u64,
+    /// Number of successful validations after which the key stops working
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_uses: Option<u64>,
+    /// Secret superseded by the last rotation, valid until its overlap ends
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub previous_secret: Option<RotatedSecret>,
 }
 
 impl SecureApiKey {
@@ -174,8 +365,11 @@ impl SecureApiKey {
             expires_at: self.expires_at,
             last_used: self.last_used,
             ip_whitelist: self.ip_whitelist.clone(),
+            ip_denylist: self.ip_denylist.clone(),
             active: self.active,
             usage_count: self.usage_count,
+            max_uses: self.max_uses,
+            previous_secret: self.previous_secret.clone(),
         }
     }
 
@@ -188,9 +382,15 @@ impl SecureApiKey {
         }
     }
 
+    /// Check if a limited-use key has used up all its validations
+    pub fn is_exhausted(&self) -> bool {
+        self.max_uses
+            .is_some_and(|max_uses| self.usage_count >= max_uses)
+    }
+
     /// Check if the key is valid for use
     pub fn is_valid(&self) -> bool {
-        self.active && !self.is_expired()
+        self.active && !self.is_expired() && !self.is_exhausted()
     }
 
     /// Verify if the provided key matches the stored hash
@@ -246,7 +446,7 @@ impl Role {
                     false
                 }
             }
-            Role::Custom { permissions } => permissions.contains(&permission.to_string()),
+            Role::Custom { permissions } => PermissionChecker::check(permission, permissions),
         }
     }
 
@@ -361,9 +561,45 @@ pub struct KeyCreationRequest {
     /// Role to assign to the key
     pub role: Role,
     /// Optional expiration date
+    #[serde(
+        with = "pulseengine_mcp_protocol::timestamp::serde_format::option",
+        default
+    )]
     pub expires_at: Option<DateTime<Utc>>,
     /// Optional IP whitelist
     pub ip_whitelist: Option<Vec<String>>,
+    /// Optional IP denylist, taking precedence over the whitelist
+    #[serde(default)]
+    pub ip_denylist: Option<Vec<String>>,
+    /// Optional number of successful validations before the key stops working
+    #[serde(default)]
+    pub max_uses: Option<u64>,
+}
+
+/// How `import_keys` handles keys whose ID already exists
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum KeyImportConflict {
+    /// Keep the existing key and skip the imported one
+    #[default]
+    Skip,
+    /// Replace the existing key with the imported one
+    Overwrite,
+    /// Abort the whole import without writing any key
+    Fail,
+}
+
+/// Outcome of a bulk key import
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct KeyImportReport {
+    /// IDs of newly imported keys
+    pub imported: Vec<String>,
+    /// IDs of existing keys replaced by imported ones
+    pub overwritten: Vec<String>,
+    /// IDs skipped because a key with the same ID already existed
+    pub skipped: Vec<String>,
+    /// IDs rejected as invalid, with the reason
+    pub rejected: Vec<(String, String)>,
 }
 
 /// API key usage statistics
@@ -389,6 +625,27 @@ pub struct KeyUsageStats {
     pub device_keys: u32,
     /// Custom role keys
     pub custom_keys: u32,
+    /// Keys whose previous secret is still inside its rotation overlap
+    #[serde(default)]
+    pub rotating_keys: u32,
+    /// Authentications made with a previous secret during rotation overlaps
+    #[serde(default)]
+    pub previous_secret_usage_count: u64,
+}
+
+/// Result of rotating an API key's secret
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct KeyRotation {
+    /// Identifier of the rotated key, unchanged by the rotation
+    pub key_id: String,
+    /// The newly generated secret
+    pub new_secret: String,
+    /// The secret being phased out (`***redacted***` if the key was loaded
+    /// from storage that only keeps hashes)
+    pub old_secret: String,
+    /// When the old secret stops being accepted
+    #[serde(with = "pulseengine_mcp_protocol::timestamp::serde_format")]
+    pub old_secret_valid_until: DateTime<Utc>,
 }
 
 /// API completeness check result
@@ -467,6 +724,48 @@ mod tests {
         assert!(matches!(device_key.role, Role::Device { .. }));
     }
 
+    #[test]
+    fn test_ip_allowed_cidr_ranges() {
+        let mut key = ApiKey::new(
+            "ranged".to_string(),
+            Role::Operator,
+            None,
+            vec![
+                "10.0.0.0/8".to_string(),
+                "192.168.1.7".to_string(),
+                "2001:db8::/32".to_string(),
+                "not-an-ip".to_string(),
+            ],
+        );
+        key.ip_denylist = vec!["10.1.0.0/16".to_string()];
+
+        let ip = |ip: &str| Some(ip.parse::<IpAddr>().unwrap());
+        assert!(key.is_ip_allowed(ip("10.2.3.4")));
+        assert!(key.is_ip_allowed(ip("192.168.1.7")));
+        assert!(key.is_ip_allowed(ip("2001:db8::1")));
+        assert!(key.is_ip_allowed(ip("::ffff:10.2.3.4")));
+        assert!(!key.is_ip_allowed(ip("192.168.1.8")));
+        assert!(!key.is_ip_allowed(ip("2001:db9::1")));
+        // The denylist wins over the whitelist
+        assert!(!key.is_ip_allowed(ip("10.1.2.3")));
+        // A whitelisted key needs a known client IP
+        assert!(!key.is_ip_allowed(None));
+
+        key.ip_whitelist.clear();
+        assert!(key.is_ip_allowed(ip("172.16.0.1")));
+        assert!(key.is_ip_allowed(None));
+        assert!(!key.is_ip_allowed(ip("10.1.255.255")));
+
+        key.ip_whitelist = vec!["*".to_string()];
+        assert!(key.is_ip_allowed(None));
+
+        assert_eq!(
+            parse_ip_range("192.168.1.7"),
+            Some("192.168.1.7/32".parse().unwrap())
+        );
+        assert!(parse_ip_range("10.0.0.0/33").is_none());
+    }
+
     #[test]
     fn test_api_key_id_format() {
         let admin_key = ApiKey::new("admin".to_string(), Role::Admin, None, vec![]);
@@ -822,6 +1121,8 @@ mod tests {
             role: Role::Operator,
             expires_at: Some(Utc::now() + Duration::days(30)),
             ip_whitelist: Some(vec!["192.168.1.1".to_string()]),
+            ip_denylist: None,
+            max_uses: Some(3),
         };
 
         let json = serde_json::to_string(&request).unwrap();
@@ -831,6 +1132,7 @@ mod tests {
         assert_eq!(deserialized.role, request.role);
         assert_eq!(deserialized.expires_at, request.expires_at);
         assert_eq!(deserialized.ip_whitelist, request.ip_whitelist);
+        assert_eq!(deserialized.max_uses, Some(3));
     }
 
     #[test]
@@ -911,4 +1213,21 @@ mod tests {
         assert_eq!(deserialized.active, key.active);
         assert_eq!(deserialized.usage_count, key.usage_count);
     }
+
+    #[test]
+    fn test_api_key_timestamps_read_in_any_format() {
+        let key = ApiKey::new("test".to_string(), Role::Admin, None, vec![]);
+        let mut json = serde_json::to_value(&key).unwrap();
+        json["created_at"] = serde_json::json!(1_735_732_800_123_i64);
+        json["expires_at"] = serde_json::json!(1_767_268_800_i64);
+        json["last_used"] = serde_json::json!("2025-06-01T08:30:00Z");
+
+        let key: ApiKey = serde_json::from_value(json).unwrap();
+        assert_eq!(key.created_at.timestamp_millis(), 1_735_732_800_123);
+        assert_eq!(key.expires_at.unwrap().timestamp(), 1_767_268_800);
+        assert_eq!(
+            key.last_used.unwrap().to_rfc3339(),
+            "2025-06-01T08:30:00+00:00"
+        );
+    }
 }
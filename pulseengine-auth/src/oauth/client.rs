@@ -0,0 +1,607 @@
+//! OAuth 2.1 Authorization Code Flow with PKCE (client side)
+//!
+//! The rest of this module implements the authorization server. This file is
+//! the other end: what a browser-based MCP client needs to obtain tokens from
+//! an authorization server.
+//!
+//! 1. [`AuthorizationCodeFlow::authorization_request`] generates a PKCE code
+//!    verifier and a `state` value and builds the authorization URL to send
+//!    the user to.
+//! 2. When the user is redirected back, [`AuthorizationCodeFlow::exchange_code`]
+//!    checks the returned `state` (CSRF protection) and exchanges the code and
+//!    the verifier for tokens at the token endpoint.
+//! 3. [`AuthorizationCodeFlow::ensure_fresh`] refreshes the tokens once the
+//!    access token is about to expire.
+
+use async_trait::async_trait;
+use base64::Engine;
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use subtle::ConstantTimeEq;
+use thiserror::Error;
+use tracing::debug;
+use url::Url;
+
+use crate::crypto::keys::generate_secure_key;
+use crate::jwks::auth_context_from_claims;
+use crate::models::AuthContext;
+use crate::oauth::models::OAuthError;
+use crate::oauth::pkce::{code_challenge, generate_code_verifier};
+
+/// Access tokens expiring within this window are refreshed ahead of time
+const EXPIRY_SKEW_SECS: i64 = 30;
+
+/// OAuth client errors
+#[derive(Debug, Error)]
+pub enum OAuthClientError {
+    #[error("Invalid OAuth configuration: {0}")]
+    Config(String),
+
+    #[error("OAuth state mismatch, the callback doesn't belong to this authorization")]
+    StateMismatch,
+
+    #[error("Token endpoint returned {error} (HTTP {status}){}", .description.as_deref().map(|d| format!(": {d}")).unwrap_or_default())]
+    TokenEndpoint {
+        status: u16,
+        /// RFC 6749 error code, e.g. `invalid_grant`
+        error: String,
+        description: Option<String>,
+    },
+
+    #[error("Token request failed: {0}")]
+    Http(String),
+
+    #[error("Invalid token response: {0}")]
+    InvalidResponse(String),
+
+    #[error("No refresh token available")]
+    NoRefreshToken,
+
+    #[error("Access token claims unreadable: {0}")]
+    InvalidToken(String),
+}
+
+/// Client registration and endpoints for the authorization code flow
+#[derive(Debug, Clone)]
+pub struct OAuthConfig {
+    /// Client identifier issued at registration
+    pub client_id: String,
+
+    /// Client secret for confidential clients; public clients rely on PKCE alone
+    pub client_secret: Option<String>,
+
+    /// Where the authorization server sends the user back to
+    pub redirect_uri: String,
+
+    /// Requested scopes
+    pub scopes: Vec<String>,
+
+    /// Authorization endpoint URL
+    pub authorization_endpoint: String,
+
+    /// Token endpoint URL
+    pub token_endpoint: String,
+
+    /// Resource indicator (RFC 8707), e.g. the MCP server URL
+    pub resource: Option<String>,
+}
+
+/// Posts form-encoded requests to the token endpoint
+#[async_trait]
+pub trait TokenHttpClient: Send + Sync {
+    /// POST `form` to `url` and return the response status and body
+    async fn post_form(
+        &self,
+        url: &str,
+        form: &[(&str, &str)],
+    ) -> Result<(u16, String), OAuthClientError>;
+}
+
+/// [`TokenHttpClient`] backed by `reqwest`
+#[cfg(feature = "oauth-client")]
+pub struct ReqwestTokenClient {
+    client: reqwest::Client,
+}
+
+#[cfg(feature = "oauth-client")]
+impl ReqwestTokenClient {
+    /// Create a client with a 30 second request timeout
+    pub fn new() -> Result<Self, OAuthClientError> {
+        let client = reqwest::Client::builder()
+            .timeout(std::time::Duration::from_secs(30))
+            .build()
+            .map_err(|e| OAuthClientError::Http(e.to_string()))?;
+        Ok(Self { client })
+    }
+}
+
+#[cfg(feature = "oauth-client")]
+#[async_trait]
+impl TokenHttpClient for ReqwestTokenClient {
+    async fn post_form(
+        &self,
+        url: &str,
+        form: &[(&str, &str)],
+    ) -> Result<(u16, String), OAuthClientError> {
+        let response = self
+            .client
+            .post(url)
+            .header("Accept", "application/json")
+            .form(form)
+            .send()
+            .await
+            .map_err(|e| OAuthClientError::Http(e.to_string()))?;
+        let status = response.status().as_u16();
+        let body = response
+            .text()
+            .await
+            .map_err(|e| OAuthClientError::Http(e.to_string()))?;
+        Ok((status, body))
+    }
+}
+
+/// An authorization in progress, to keep until the user is redirected back
+///
+/// `state` and `code_verifier` are secrets; keep them server-side or in the
+/// client's own storage, never in the redirect URL.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PendingAuthorization {
+    /// URL to send the user to
+    pub url: String,
+    /// CSRF token the callback must echo back
+    pub state: String,
+    /// PKCE code verifier proving this client started the authorization
+    pub code_verifier: String,
+}
+
+/// Tokens obtained from the token endpoint
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TokenSet {
+    pub access_token: String,
+    /// Token type, normally `Bearer`
+    pub token_type: String,
+    pub refresh_token: Option<String>,
+    /// When the access token expires, if the server said
+    pub expires_at: Option<DateTime<Utc>>,
+    /// Granted scopes
+    pub scopes: Vec<String>,
+}
+
+impl TokenSet {
+    /// Check if the access token has expired or is about to
+    pub fn is_expired(&self) -> bool {
+        self.expires_at.is_some_and(|expires_at| {
+            expires_at - Duration::seconds(EXPIRY_SKEW_SECS) <= Utc::now()
+        })
+    }
+
+    /// Auth context built from the access token's JWT claims
+    ///
+    /// The claims are read without verifying the signature: the token came
+    /// straight from the token endpoint and is meant for the resource server,
+    /// which validates it. Opaque (non-JWT) access tokens have no claims to
+    /// read.
+    pub fn auth_context(&self) -> Result<AuthContext, OAuthClientError> {
+        let payload = self
+            .access_token
+            .split('.')
+            .nth(1)
+            .ok_or_else(|| OAuthClientError::InvalidToken("not a JWT".to_string()))?;
+        let bytes = URL_SAFE_NO_PAD
+            .decode(payload)
+            .map_err(|e| OAuthClientError::InvalidToken(e.to_string()))?;
+        let claims: serde_json::Map<String, serde_json::Value> = serde_json::from_slice(&bytes)
+            .map_err(|e| OAuthClientError::InvalidToken(e.to_string()))?;
+        auth_context_from_claims(&claims, "roles")
+            .map_err(|e| OAuthClientError::InvalidToken(e.to_string()))
+    }
+}
+
+/// Successful token endpoint response (RFC 6749 Section 5.1)
+#[derive(Debug, Deserialize)]
+struct TokenEndpointResponse {
+    access_token: String,
+    token_type: String,
+    expires_in: Option<i64>,
+    refresh_token: Option<String>,
+    scope: Option<String>,
+}
+
+/// OAuth 2.1 authorization code flow with PKCE
+pub struct AuthorizationCodeFlow {
+    config: OAuthConfig,
+    http: Arc<dyn TokenHttpClient>,
+}
+
+#[cfg(feature = "oauth-client")]
+impl AuthorizationCodeFlow {
+    /// Create a flow talking to the token endpoint over HTTP
+    pub fn new(config: OAuthConfig) -> Result<Self, OAuthClientError> {
+        Self::with_http_client(config, Arc::new(ReqwestTokenClient::new()?))
+    }
+}
+
+impl AuthorizationCodeFlow {
+    /// Create a flow posting token requests through `http`
+    pub fn with_http_client(
+        config: OAuthConfig,
+        http: Arc<dyn TokenHttpClient>,
+    ) -> Result<Self, OAuthClientError> {
+        for (name, url) in [
+            ("authorization_endpoint", &config.authorization_endpoint),
+            ("token_endpoint", &config.token_endpoint),
+            ("redirect_uri", &config.redirect_uri),
+        ] {
+            Url::parse(url).map_err(|e| OAuthClientError::Config(format!("{name}: {e}")))?;
+        }
+        Ok(Self { config, http })
+    }
+
+    /// Start an authorization: generate the PKCE verifier and `state` and
+    /// build the authorization URL
+    pub fn authorization_request(&self) -> PendingAuthorization {
+        let code_verifier = generate_code_verifier();
+        let state = generate_secure_key();
+
+        let mut url = Url::parse(&self.config.authorization_endpoint)
+            .expect("endpoint validated in constructor");
+        {
+            let mut query = url.query_pairs_mut();
+            query
+                .append_pair("response_type", "code")
+                .append_pair("client_id", &self.config.client_id)
+                .append_pair("redirect_uri", &self.config.redirect_uri)
+                .append_pair("state", &state)
+                .append_pair("code_challenge", &code_challenge(&code_verifier))
+                .append_pair("code_challenge_method", "S256");
+            if !self.config.scopes.is_empty() {
+                query.append_pair("scope", &self.config.scopes.join(" "));
+            }
+            if let Some(resource) = &self.config.resource {
+                query.append_pair("resource", resource);
+            }
+        }
+
+        PendingAuthorization {
+            url: url.into(),
+            state,
+            code_verifier,
+        }
+    }
+
+    /// Exchange the authorization code from the callback for tokens
+    ///
+    /// `returned_state` is the `state` parameter of the callback; it must match
+    /// the pending authorization's, otherwise the callback may have been forged.
+    pub async fn exchange_code(
+        &self,
+        pending: &PendingAuthorization,
+        code: &str,
+        returned_state: &str,
+    ) -> Result<TokenSet, OAuthClientError> {
+        if !bool::from(pending.state.as_bytes().ct_eq(returned_state.as_bytes())) {
+            return Err(OAuthClientError::StateMismatch);
+        }
+
+        let mut form = vec![
+            ("grant_type", "authorization_code"),
+            ("code", code),
+            ("redirect_uri", self.config.redirect_uri.as_str()),
+            ("code_verifier", pending.code_verifier.as_str()),
+        ];
+        self.push_client_params(&mut form);
+        self.request_tokens(&form, None).await
+    }
+
+    /// Obtain new tokens with the refresh token
+    pub async fn refresh(&self, tokens: &TokenSet) -> Result<TokenSet, OAuthClientError> {
+        let refresh_token = tokens
+            .refresh_token
+            .as_deref()
+            .ok_or(OAuthClientError::NoRefreshToken)?;
+
+        let mut form = vec![
+            ("grant_type", "refresh_token"),
+            ("refresh_token", refresh_token),
+        ];
+        self.push_client_params(&mut form);
+        self.request_tokens(&form, Some(refresh_token)).await
+    }
+
+    /// Return `tokens`, refreshed first if the access token has expired
+    pub async fn ensure_fresh(&self, tokens: TokenSet) -> Result<TokenSet, OAuthClientError> {
+        if tokens.is_expired() {
+            debug!("Access token expired, refreshing");
+            self.refresh(&tokens).await
+        } else {
+            Ok(tokens)
+        }
+    }
+
+    fn push_client_params<'a>(&'a self, form: &mut Vec<(&'a str, &'a str)>) {
+        form.push(("client_id", &self.config.client_id));
+        if let Some(secret) = &self.config.client_secret {
+            form.push(("client_secret", secret));
+        }
+        if let Some(resource) = &self.config.resource {
+            form.push(("resource", resource));
+        }
+    }
+
+    /// Post a token request; `previous_refresh_token` is kept when the
+    /// server doesn't rotate it
+    async fn request_tokens(
+        &self,
+        form: &[(&str, &str)],
+        previous_refresh_token: Option<&str>,
+    ) -> Result<TokenSet, OAuthClientError> {
+        let (status, body) = self
+            .http
+            .post_form(&self.config.token_endpoint, form)
+            .await?;
+
+        if !(200..300).contains(&status) {
+            return Err(match serde_json::from_str::<OAuthError>(&body) {
+                Ok(error) => OAuthClientError::TokenEndpoint {
+                    status,
+                    error: error.error,
+                    description: error.error_description,
+                },
+                Err(_) => OAuthClientError::Http(format!("HTTP {status}: {body}")),
+            });
+        }
+
+        let response: TokenEndpointResponse = serde_json::from_str(&body)
+            .map_err(|e| OAuthClientError::InvalidResponse(e.to_string()))?;
+        Ok(TokenSet {
+            access_token: response.access_token,
+            token_type: response.token_type,
+            refresh_token: response
+                .refresh_token
+                .or_else(|| previous_refresh_token.map(str::to_string)),
+            expires_at: response
+                .expires_in
+                .map(|seconds| Utc::now() + Duration::seconds(seconds)),
+            scopes: response
+                .scope
+                .map(|scope| scope.split_whitespace().map(str::to_string).collect())
+                .unwrap_or_else(|| self.config.scopes.clone()),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::Role;
+    use crate::oauth::pkce::verify_pkce;
+    use jsonwebtoken::{EncodingKey, Header, encode};
+    use serde_json::json;
+    use std::collections::{HashMap, VecDeque};
+    use std::sync::Mutex;
+
+    /// Records token requests and answers them with canned responses
+    #[derive(Default)]
+    struct MockTokenClient {
+        requests: Mutex<Vec<HashMap<String, String>>>,
+        responses: Mutex<VecDeque<(u16, String)>>,
+    }
+
+    impl MockTokenClient {
+        fn respond(&self, status: u16, body: serde_json::Value) {
+            self.responses
+                .lock()
+                .unwrap()
+                .push_back((status, body.to_string()));
+        }
+
+        fn requests(&self) -> Vec<HashMap<String, String>> {
+            self.requests.lock().unwrap().clone()
+        }
+    }
+
+    #[async_trait]
+    impl TokenHttpClient for MockTokenClient {
+        async fn post_form(
+            &self,
+            url: &str,
+            form: &[(&str, &str)],
+        ) -> Result<(u16, String), OAuthClientError> {
+            assert_eq!(url, "https://auth.example.com/oauth/token");
+            self.requests.lock().unwrap().push(
+                form.iter()
+                    .map(|(k, v)| (k.to_string(), v.to_string()))
+                    .collect(),
+            );
+            Ok(self
+                .responses
+                .lock()
+                .unwrap()
+                .pop_front()
+                .expect("unexpected token request"))
+        }
+    }
+
+    fn config() -> OAuthConfig {
+        OAuthConfig {
+            client_id: "mcp-client".to_string(),
+            client_secret: None,
+            redirect_uri: "http://localhost:8765/callback".to_string(),
+            scopes: vec!["mcp:read".to_string(), "mcp:write".to_string()],
+            authorization_endpoint: "https://auth.example.com/oauth/authorize".to_string(),
+            token_endpoint: "https://auth.example.com/oauth/token".to_string(),
+            resource: Some("https://mcp.example.com".to_string()),
+        }
+    }
+
+    fn flow() -> (AuthorizationCodeFlow, Arc<MockTokenClient>) {
+        let http = Arc::new(MockTokenClient::default());
+        let flow = AuthorizationCodeFlow::with_http_client(config(), http.clone()).unwrap();
+        (flow, http)
+    }
+
+    fn access_token() -> String {
+        encode(
+            &Header::default(),
+            &json!({
+                "sub": "user-1",
+                "roles": ["operator"],
+                "scope": "mcp:read mcp:write",
+                "exp": Utc::now().timestamp() + 3600,
+            }),
+            &EncodingKey::from_secret(b"server-secret"),
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_authorization_url() {
+        let (flow, _) = flow();
+        let pending = flow.authorization_request();
+
+        let url = Url::parse(&pending.url).unwrap();
+        assert_eq!(url.path(), "/oauth/authorize");
+        let query: HashMap<_, _> = url.query_pairs().into_owned().collect();
+        assert_eq!(query["response_type"], "code");
+        assert_eq!(query["client_id"], "mcp-client");
+        assert_eq!(query["redirect_uri"], "http://localhost:8765/callback");
+        assert_eq!(query["scope"], "mcp:read mcp:write");
+        assert_eq!(query["resource"], "https://mcp.example.com");
+        assert_eq!(query["state"], pending.state);
+        assert_eq!(query["code_challenge_method"], "S256");
+        assert!(verify_pkce(
+            &pending.code_verifier,
+            &query["code_challenge"]
+        ));
+        assert!(!pending.url.contains(&pending.code_verifier));
+
+        let other = flow.authorization_request();
+        assert_ne!(other.state, pending.state);
+        assert_ne!(other.code_verifier, pending.code_verifier);
+    }
+
+    #[tokio::test]
+    async fn test_exchange_code() {
+        let (flow, http) = flow();
+        let pending = flow.authorization_request();
+        let token = access_token();
+        http.respond(
+            200,
+            json!({
+                "access_token": token,
+                "token_type": "Bearer",
+                "expires_in": 3600,
+                "refresh_token": "refresh-1",
+                "scope": "mcp:read",
+            }),
+        );
+
+        let tokens = flow
+            .exchange_code(&pending, "code-1", &pending.state)
+            .await
+            .unwrap();
+        assert_eq!(tokens.access_token, token);
+        assert_eq!(tokens.refresh_token.as_deref(), Some("refresh-1"));
+        assert_eq!(tokens.scopes, vec!["mcp:read"]);
+        assert!(!tokens.is_expired());
+
+        let request = &http.requests()[0];
+        assert_eq!(request["grant_type"], "authorization_code");
+        assert_eq!(request["code"], "code-1");
+        assert_eq!(request["code_verifier"], pending.code_verifier);
+        assert_eq!(request["redirect_uri"], "http://localhost:8765/callback");
+        assert_eq!(request["client_id"], "mcp-client");
+        assert!(!request.contains_key("client_secret"));
+
+        let context = tokens.auth_context().unwrap();
+        assert_eq!(context.user_id.as_deref(), Some("user-1"));
+        assert!(context.roles.contains(&Role::Operator));
+        assert!(context.permissions.contains(&"mcp:write".to_string()));
+    }
+
+    #[tokio::test]
+    async fn test_state_mismatch_rejected() {
+        let (flow, http) = flow();
+        let pending = flow.authorization_request();
+        let forged = flow.authorization_request();
+
+        assert!(matches!(
+            flow.exchange_code(&pending, "code-1", &forged.state).await,
+            Err(OAuthClientError::StateMismatch)
+        ));
+        assert!(http.requests().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_token_endpoint_error() {
+        let (flow, http) = flow();
+        let pending = flow.authorization_request();
+        http.respond(
+            400,
+            json!({
+                "error": "invalid_grant",
+                "error_description": "Authorization code expired",
+            }),
+        );
+
+        match flow.exchange_code(&pending, "code-1", &pending.state).await {
+            Err(OAuthClientError::TokenEndpoint {
+                status,
+                error,
+                description,
+            }) => {
+                assert_eq!(status, 400);
+                assert_eq!(error, "invalid_grant");
+                assert_eq!(description.as_deref(), Some("Authorization code expired"));
+            }
+            other => panic!("expected token endpoint error, got {other:?}"),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_expired_tokens_refreshed() {
+        let (flow, http) = flow();
+        let fresh = TokenSet {
+            access_token: "access-1".to_string(),
+            token_type: "Bearer".to_string(),
+            refresh_token: Some("refresh-1".to_string()),
+            expires_at: Some(Utc::now() + Duration::hours(1)),
+            scopes: vec!["mcp:read".to_string()],
+        };
+        let tokens = flow.ensure_fresh(fresh).await.unwrap();
+        assert_eq!(tokens.access_token, "access-1");
+        assert!(http.requests().is_empty());
+
+        // Expiring within the skew counts as expired
+        let expiring = TokenSet {
+            expires_at: Some(Utc::now() + Duration::seconds(10)),
+            ..tokens
+        };
+        // The server doesn't rotate the refresh token this time
+        http.respond(
+            200,
+            json!({"access_token": "access-2", "token_type": "Bearer", "expires_in": 3600}),
+        );
+        let tokens = flow.ensure_fresh(expiring).await.unwrap();
+        assert_eq!(tokens.access_token, "access-2");
+        assert_eq!(tokens.refresh_token.as_deref(), Some("refresh-1"));
+        assert_eq!(tokens.scopes, vec!["mcp:read", "mcp:write"]);
+
+        let request = &http.requests()[0];
+        assert_eq!(request["grant_type"], "refresh_token");
+        assert_eq!(request["refresh_token"], "refresh-1");
+        assert_eq!(request["resource"], "https://mcp.example.com");
+
+        let without_refresh = TokenSet {
+            refresh_token: None,
+            expires_at: Some(Utc::now() - Duration::seconds(1)),
+            ..tokens
+        };
+        assert!(matches!(
+            flow.ensure_fresh(without_refresh).await,
+            Err(OAuthClientError::NoRefreshToken)
+        ));
+    }
+}
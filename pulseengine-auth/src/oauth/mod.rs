@@ -16,10 +16,14 @@
 //! - SHOULD support Client ID Metadata Documents for registration
 //! - MAY support Dynamic Client Registration (for backwards compatibility)
 //!
+//! The [`client`] module implements the client side of the authorization code
+//! flow, for obtaining tokens from an authorization server.
+//!
 //! Reference: <https://github.com/shuttle-hq/shuttle-examples/tree/main/mcp/mcp-sse-oauth>
 
 pub mod authorize;
 pub mod bearer;
+pub mod client;
 pub mod client_metadata;
 pub mod metadata;
 pub mod models;
@@ -34,6 +38,12 @@ pub use bearer::{
     BearerError, BearerToken, BearerTokenConfig, WwwAuthenticate, unauthorized_response,
     validate_bearer_token,
 };
+#[cfg(feature = "oauth-client")]
+pub use client::ReqwestTokenClient;
+pub use client::{
+    AuthorizationCodeFlow, OAuthClientError, OAuthConfig, PendingAuthorization, TokenHttpClient,
+    TokenSet,
+};
 pub use client_metadata::{
     ClientIdMetadataDocument, ClientMetadataError, is_client_id_metadata_url,
     validate_client_id_url, validate_metadata_document, validate_redirect_uri,
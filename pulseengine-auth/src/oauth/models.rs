@@ -102,7 +102,7 @@ pub struct TokenResponse {
 }
 
 /// Error response (RFC 6749 Section 5.2)
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, Deserialize)]
 pub struct OAuthError {
     pub error: String,
     pub error_description: Option<String>,
@@ -18,16 +18,24 @@ use sha2::{Digest, Sha256};
 /// * `true` if verification succeeds
 /// * `false` if verification fails
 pub fn verify_pkce(code_verifier: &str, code_challenge: &str) -> bool {
-    // Compute SHA-256 hash of the code_verifier
-    let mut hasher = Sha256::new();
-    hasher.update(code_verifier.as_bytes());
-    let hash = hasher.finalize();
+    self::code_challenge(code_verifier) == code_challenge
+}
 
-    // Base64-URL encode the hash (without padding)
-    let computed_challenge = base64_url::encode(&hash);
+/// Generate a random PKCE code_verifier
+///
+/// 32 random bytes, Base64-URL encoded: 43 characters, the RFC 7636 minimum
+/// length with 256 bits of entropy.
+pub fn generate_code_verifier() -> String {
+    crate::crypto::keys::generate_secure_key()
+}
 
-    // Compare with provided code_challenge
-    computed_challenge == code_challenge
+/// Compute the S256 code_challenge for a code_verifier
+///
+/// BASE64URL(SHA256(ASCII(code_verifier)))
+pub fn code_challenge(code_verifier: &str) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(code_verifier.as_bytes());
+    base64_url::encode(&hasher.finalize())
 }
 
 /// Validate PKCE code_verifier format
@@ -83,6 +91,17 @@ mod tests {
         assert!(!verify_pkce(code_verifier, code_challenge));
     }
 
+    #[test]
+    fn test_generated_verifier_matches_challenge() {
+        let code_verifier = generate_code_verifier();
+        assert!(validate_code_verifier(&code_verifier));
+        assert_ne!(code_verifier, generate_code_verifier());
+
+        let challenge = code_challenge(&code_verifier);
+        assert!(validate_code_challenge(&challenge));
+        assert!(verify_pkce(&code_verifier, &challenge));
+    }
+
     #[test]
     fn test_code_verifier_validation() {
         // Valid: 43-128 chars, unreserved characters
@@ -3,11 +3,16 @@
 //! This module provides comprehensive permission management for tools,
 //! resources, and custom operations with role-based access control.
 
-use crate::{AuthContext, models::Role};
+use crate::{
+    AuthContext,
+    audit::{AuditLogger, events},
+    models::Role,
+};
 use serde::{Deserialize, Serialize};
 use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
 use thiserror::Error;
-use tracing::debug;
+use tracing::{debug, warn};
 
 /// Errors that can occur during permission checking
 #[derive(Debug, Error)]
@@ -320,12 +325,152 @@ impl PermissionConfig {
 /// Permission Checker
 pub struct PermissionChecker {
     config: PermissionConfig,
+    audit_logger: Option<Arc<AuditLogger>>,
 }
 
 impl PermissionChecker {
     /// Create a new permission checker
     pub fn new(config: PermissionConfig) -> Self {
-        Self { config }
+        Self {
+            config,
+            audit_logger: None,
+        }
+    }
+
+    /// Record every denied check as a `permission_denied` audit event
+    pub fn with_audit_logger(mut self, audit_logger: Arc<AuditLogger>) -> Self {
+        self.audit_logger = Some(audit_logger);
+        self
+    }
+
+    /// Check tool access, auditing the denial if it fails
+    pub async fn check_tool(
+        &self,
+        auth_context: &AuthContext,
+        tool_name: &str,
+    ) -> Result<(), PermissionError> {
+        if self.can_use_tool(auth_context, tool_name) {
+            return Ok(());
+        }
+        Err(self
+            .deny(auth_context, "tools/call", Permission::tool(tool_name))
+            .await)
+    }
+
+    /// Check resource access, auditing the denial if it fails
+    pub async fn check_resource(
+        &self,
+        auth_context: &AuthContext,
+        resource_uri: &str,
+    ) -> Result<(), PermissionError> {
+        if self.can_access_resource(auth_context, resource_uri) {
+            return Ok(());
+        }
+        Err(self
+            .deny(
+                auth_context,
+                "resources/read",
+                Permission::resource(resource_uri),
+            )
+            .await)
+    }
+
+    /// Check prompt access, auditing the denial if it fails
+    pub async fn check_prompt(
+        &self,
+        auth_context: &AuthContext,
+        prompt_name: &str,
+    ) -> Result<(), PermissionError> {
+        if self.can_use_prompt(auth_context, prompt_name) {
+            return Ok(());
+        }
+        Err(self
+            .deny(
+                auth_context,
+                "prompts/get",
+                Permission::UsePrompt(prompt_name.to_string()),
+            )
+            .await)
+    }
+
+    /// Check subscription access, auditing the denial if it fails
+    pub async fn check_subscribe(
+        &self,
+        auth_context: &AuthContext,
+        resource_uri: &str,
+    ) -> Result<(), PermissionError> {
+        if self.can_subscribe(auth_context, resource_uri) {
+            return Ok(());
+        }
+        Err(self
+            .deny(
+                auth_context,
+                "resources/subscribe",
+                Permission::Subscribe(resource_uri.to_string()),
+            )
+            .await)
+    }
+
+    /// Check method access, auditing the denial if it fails
+    pub async fn check_method(
+        &self,
+        auth_context: &AuthContext,
+        method: &str,
+    ) -> Result<(), PermissionError> {
+        if self.can_use_method(auth_context, method) {
+            return Ok(());
+        }
+        let permission = match method {
+            "completion/complete" => Permission::Complete,
+            "logging/setLevel" => Permission::SetLogLevel,
+            _ => Permission::Custom(method.to_string()),
+        };
+        Err(self.deny(auth_context, method, permission).await)
+    }
+
+    /// Audit a denied check and build the matching error
+    async fn deny(
+        &self,
+        auth_context: &AuthContext,
+        action: &str,
+        permission: Permission,
+    ) -> PermissionError {
+        let missing = permission.to_string();
+
+        if let Some(audit_logger) = &self.audit_logger {
+            let roles: Vec<String> = auth_context.roles.iter().map(|r| r.to_string()).collect();
+            let actor = auth_context
+                .user_id
+                .as_deref()
+                .or(auth_context.api_key_id.as_deref());
+            let event = events::permission_denied(actor, action, &missing, &roles);
+            if let Err(e) = audit_logger.log(event).await {
+                warn!("Failed to audit permission denial: {}", e);
+            }
+        }
+
+        PermissionError::AccessDenied(format!("{action} requires {missing}"))
+    }
+
+    /// Check `required` against the permission patterns granted to a caller
+    ///
+    /// Permissions are colon-delimited. A `*` within a segment matches any
+    /// characters of that segment, and a trailing `*` segment matches one or
+    /// more remaining segments, so `tools:*` covers `tools:call` and
+    /// `tools:read:weather` but not `tools` itself. Patterns prefixed with `!`
+    /// deny what they match, overriding any grant.
+    pub fn check(required: &str, granted: &[String]) -> bool {
+        let mut allowed = false;
+        for pattern in granted {
+            if let Some(denied) = pattern.strip_prefix('!') {
+                if permission_matches(denied, required) {
+                    return false;
+                }
+            } else if !allowed {
+                allowed = permission_matches(pattern, required);
+            }
+        }
+        allowed
     }
 
     /// Check if a user can use a specific tool
@@ -499,10 +644,14 @@ impl PermissionChecker {
                 // Will be checked per-tool in can_use_tool
                 true
             }
-            "resources/read" | "resources/list" => {
+            "resources/read" | "resources/list" | "resources/templates/list" => {
                 // Will be checked per-resource in can_access_resource
                 true
             }
+            "tools/list" | "prompts/list" | "prompts/get" => {
+                // Listing is open; prompts are checked per-prompt in can_use_prompt
+                true
+            }
             "resources/subscribe" | "resources/unsubscribe" => {
                 // Subscription requires at least operator role
                 auth_context
@@ -535,6 +684,10 @@ impl PermissionChecker {
                 // Always allowed
                 true
             }
+            _ if method.starts_with("notifications/") => {
+                // Protocol notifications, e.g. cancellations, are always allowed
+                true
+            }
             _ => {
                 // Unknown method - use default action
                 matches!(self.config.default_action, PermissionAction::Allow)
@@ -604,6 +757,47 @@ impl PermissionChecker {
     }
 }
 
+/// Match a permission against a colon-delimited pattern
+fn permission_matches(pattern: &str, permission: &str) -> bool {
+    let pattern: Vec<&str> = pattern.split(':').collect();
+    let segments: Vec<&str> = permission.split(':').collect();
+    match pattern.split_last() {
+        Some((&"*", parents)) => {
+            segments.len() > parents.len()
+                && parents
+                    .iter()
+                    .zip(&segments)
+                    .all(|(pattern, segment)| segment_matches(pattern, segment))
+        }
+        _ => {
+            pattern.len() == segments.len()
+                && pattern
+                    .iter()
+                    .zip(&segments)
+                    .all(|(pattern, segment)| segment_matches(pattern, segment))
+        }
+    }
+}
+
+/// Match one permission segment against a glob where `*` matches any characters
+fn segment_matches(pattern: &str, segment: &str) -> bool {
+    let mut parts = pattern.split('*');
+    let first = parts.next().unwrap_or_default();
+    let Some(last) = parts.next_back() else {
+        return pattern == segment;
+    };
+    let Some(mut rest) = segment.strip_prefix(first) else {
+        return false;
+    };
+    for part in parts {
+        match rest.find(part) {
+            Some(index) => rest = &rest[index + part.len()..],
+            None => return false,
+        }
+    }
+    rest.ends_with(last)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -617,6 +811,78 @@ mod tests {
         assert_eq!(perm, parsed);
     }
 
+    #[test]
+    fn test_check_wildcards_and_hierarchy() {
+        let granted =
+            |patterns: &[&str]| -> Vec<String> { patterns.iter().map(|p| p.to_string()).collect() };
+
+        // Exact grants still work
+        assert!(PermissionChecker::check(
+            "tools:call",
+            &granted(&["tools:call"])
+        ));
+        assert!(!PermissionChecker::check(
+            "tools:list",
+            &granted(&["tools:call"])
+        ));
+        assert!(!PermissionChecker::check("tools:call", &[]));
+
+        // A trailing wildcard covers every level below its parent
+        let tools = granted(&["tools:*"]);
+        assert!(PermissionChecker::check("tools:call", &tools));
+        assert!(PermissionChecker::check("tools:read:weather", &tools));
+        assert!(!PermissionChecker::check("tools", &tools));
+        assert!(!PermissionChecker::check("resources:read", &tools));
+        assert!(!PermissionChecker::check("toolsets:call", &tools));
+
+        let read = granted(&["tools:read:*"]);
+        assert!(PermissionChecker::check("tools:read:weather", &read));
+        assert!(PermissionChecker::check("tools:read:weather:hourly", &read));
+        assert!(!PermissionChecker::check("tools:read", &read));
+        assert!(!PermissionChecker::check("tools:write:weather", &read));
+
+        // Wildcards elsewhere match within a single segment
+        let globbed = granted(&["tools:*:weather", "resources:read:log_*"]);
+        assert!(PermissionChecker::check("tools:read:weather", &globbed));
+        assert!(!PermissionChecker::check(
+            "tools:read:weather:hourly",
+            &globbed
+        ));
+        assert!(PermissionChecker::check("resources:read:log_app", &globbed));
+        assert!(!PermissionChecker::check(
+            "resources:read:audit_log",
+            &globbed
+        ));
+
+        assert!(PermissionChecker::check(
+            "anything:at:all",
+            &granted(&["*"])
+        ));
+    }
+
+    #[test]
+    fn test_check_denials_override_grants() {
+        let granted: Vec<String> = ["tools:*", "!tools:admin:*", "!tools:delete"]
+            .iter()
+            .map(|p| p.to_string())
+            .collect();
+        assert!(PermissionChecker::check("tools:read:weather", &granted));
+        assert!(!PermissionChecker::check("tools:admin:reset", &granted));
+        assert!(!PermissionChecker::check("tools:delete", &granted));
+        assert!(PermissionChecker::check("tools:admin", &granted));
+
+        // Order doesn't matter, and a denial alone grants nothing
+        let granted: Vec<String> = ["!tools:*", "tools:read:weather"]
+            .iter()
+            .map(|p| p.to_string())
+            .collect();
+        assert!(!PermissionChecker::check("tools:read:weather", &granted));
+        assert!(!PermissionChecker::check(
+            "resources:read",
+            &["!tools:*".to_string()]
+        ));
+    }
+
     #[test]
     fn test_permission_rule_creation() {
         let rule = PermissionRule::allow(
@@ -700,4 +966,97 @@ mod tests {
         );
         assert_eq!(config.custom_rules.len(), 1);
     }
+
+    fn monitor_context() -> AuthContext {
+        AuthContext {
+            user_id: Some("alice".to_string()),
+            roles: vec![Role::Monitor],
+            api_key_id: Some("key_123".to_string()),
+            permissions: vec![],
+        }
+    }
+
+    async fn audited_checker(
+        config: PermissionConfig,
+        log_file: std::path::PathBuf,
+    ) -> PermissionChecker {
+        let audit_logger = crate::audit::AuditLogger::new(crate::audit::AuditConfig {
+            enabled: true,
+            log_file,
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+        PermissionChecker::new(config).with_audit_logger(Arc::new(audit_logger))
+    }
+
+    #[tokio::test]
+    async fn test_denied_tool_call_is_audited() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let log_file = temp_dir.path().join("audit.jsonl");
+        let config = PermissionConfig::production().allow_role_tool(Role::Admin, "delete_device");
+        let checker = audited_checker(config, log_file.clone()).await;
+
+        let err = checker
+            .check_tool(&monitor_context(), "delete_device")
+            .await
+            .unwrap_err();
+        assert!(matches!(err, PermissionError::AccessDenied(_)));
+
+        let content = tokio::fs::read_to_string(&log_file).await.unwrap();
+        let records: Vec<serde_json::Value> = content
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        assert_eq!(records.len(), 1);
+
+        let record = &records[0];
+        assert_eq!(record["event_type"], "permission_denied");
+        assert_eq!(record["severity"], "warning");
+        assert_eq!(record["actor"], "alice");
+        assert_eq!(record["resource"], "tools/call");
+        assert_eq!(record["metadata"]["action"], "tools/call");
+        assert_eq!(
+            record["metadata"]["missing_permission"],
+            "tool:delete_device"
+        );
+        assert_eq!(record["metadata"]["roles"], serde_json::json!(["monitor"]));
+    }
+
+    #[tokio::test]
+    async fn test_allowed_and_resource_checks_audit_only_denials() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let log_file = temp_dir.path().join("audit.jsonl");
+        let config = PermissionConfig::production()
+            .allow_role_tool(Role::Monitor, "get_status")
+            .deny_role_resource(Role::Monitor, "loxone://admin/*");
+        let checker = audited_checker(config, log_file.clone()).await;
+        let context = monitor_context();
+
+        checker.check_tool(&context, "get_status").await.unwrap();
+        assert!(!log_file.exists());
+
+        checker
+            .check_resource(&context, "loxone://admin/keys")
+            .await
+            .unwrap_err();
+        let content = tokio::fs::read_to_string(&log_file).await.unwrap();
+        let record: serde_json::Value = serde_json::from_str(content.trim()).unwrap();
+        assert_eq!(record["metadata"]["action"], "resources/read");
+        assert_eq!(
+            record["metadata"]["missing_permission"],
+            "resource:loxone://admin/keys"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_denial_without_audit_logger() {
+        let checker = PermissionChecker::new(PermissionConfig::restrictive());
+        let result = checker
+            .check_method(&monitor_context(), "logging/setLevel")
+            .await;
+        assert!(
+            matches!(result, Err(PermissionError::AccessDenied(msg)) if msg.contains("set_log_level"))
+        );
+    }
 }
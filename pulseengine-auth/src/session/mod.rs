@@ -3,11 +3,13 @@
 //! This module provides comprehensive session management for MCP authentication
 //! including JWT tokens, session storage, and lifecycle management.
 
+pub mod persistent_storage;
 pub mod session_manager;
 
+pub use persistent_storage::PersistentSessionStorage;
 pub use session_manager::{
-    MemorySessionStorage, Session, SessionConfig, SessionError, SessionManager, SessionStats,
-    SessionStorage,
+    CleanupSchedule, MemorySessionStorage, Session, SessionConfig, SessionError, SessionManager,
+    SessionStats, SessionStorage,
 };
 
 #[cfg(test)]
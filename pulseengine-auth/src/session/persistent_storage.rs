@@ -0,0 +1,342 @@
+//! Encrypted session storage that survives restarts
+//!
+//! [`PersistentSessionStorage`] keeps sessions in an embedded redb database.
+//! Each session is serialized and encrypted with AES-256-GCM under a key
+//! derived from the master key, the same scheme the file storage uses for API
+//! keys, so only session IDs are stored in plain text. Reopening the database
+//! with the same master key restores every session that hasn't expired.
+
+use super::session_manager::{Session, SessionError, SessionStorage};
+use crate::crypto::encryption::{EncryptedData, decrypt_data, derive_encryption_key, encrypt_data};
+use crate::crypto::keys::generate_master_key;
+use redb::{Database, ReadableTable, TableDefinition};
+use std::path::Path;
+use std::sync::Arc;
+use tracing::{debug, info, warn};
+
+/// Session ID to encrypted session
+const SESSIONS: TableDefinition<&str, &[u8]> = TableDefinition::new("sessions");
+
+/// Persistent session storage encrypted at rest
+///
+/// Lookups by user and expiry sweeps decrypt every stored session, which is
+/// cheap at the session counts a server holds.
+#[derive(Clone)]
+pub struct PersistentSessionStorage {
+    db: Arc<Database>,
+    encryption_key: [u8; 32],
+}
+
+impl PersistentSessionStorage {
+    /// Open or create the store at `path`
+    ///
+    /// The encryption key is derived from the master key in
+    /// `PULSEENGINE_MCP_MASTER_KEY`; without it a random key is generated and
+    /// the sessions can't be read after a restart.
+    pub fn open(path: impl AsRef<Path>) -> Result<Self, SessionError> {
+        let master_key = generate_master_key().map_err(storage_error)?;
+        Self::open_with_master_key(path, &master_key)
+    }
+
+    /// Open or create the store at `path`, encrypting with a key derived from
+    /// `master_key`
+    pub fn open_with_master_key(
+        path: impl AsRef<Path>,
+        master_key: &[u8],
+    ) -> Result<Self, SessionError> {
+        let path = path.as_ref();
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent).map_err(storage_error)?;
+        }
+        let db = Database::create(path).map_err(storage_error)?;
+
+        // Create the table up front so reads never see it missing
+        let txn = db.begin_write().map_err(storage_error)?;
+        txn.open_table(SESSIONS).map_err(storage_error)?;
+        txn.commit().map_err(storage_error)?;
+
+        debug!("Opened persistent session storage at {}", path.display());
+        Ok(Self {
+            db: Arc::new(db),
+            encryption_key: derive_encryption_key(master_key, "session-storage"),
+        })
+    }
+
+    /// Periodically remove expired sessions until the returned task is aborted
+    pub fn start_expiry_sweep(&self, interval: std::time::Duration) -> tokio::task::JoinHandle<()> {
+        let storage = self.clone();
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(interval);
+            ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+            loop {
+                ticker.tick().await;
+                if let Err(e) = storage.cleanup_expired().await {
+                    warn!("Session expiry sweep failed: {}", e);
+                }
+            }
+        })
+    }
+
+    fn encrypt(&self, session: &Session) -> Result<Vec<u8>, SessionError> {
+        let plaintext = serde_json::to_vec(session).map_err(storage_error)?;
+        let encrypted = encrypt_data(&plaintext, &self.encryption_key).map_err(storage_error)?;
+        serde_json::to_vec(&encrypted).map_err(storage_error)
+    }
+
+    fn decrypt(&self, bytes: &[u8]) -> Result<Session, SessionError> {
+        let encrypted: EncryptedData = serde_json::from_slice(bytes).map_err(storage_error)?;
+        let plaintext = decrypt_data(&encrypted, &self.encryption_key).map_err(storage_error)?;
+        serde_json::from_slice(&plaintext).map_err(storage_error)
+    }
+
+    /// Every stored session, in session ID order
+    fn load_all(&self) -> Result<Vec<Session>, SessionError> {
+        let txn = self.db.begin_read().map_err(storage_error)?;
+        let table = txn.open_table(SESSIONS).map_err(storage_error)?;
+        table
+            .iter()
+            .map_err(storage_error)?
+            .map(|entry| {
+                let (_, value) = entry.map_err(storage_error)?;
+                self.decrypt(value.value())
+            })
+            .collect()
+    }
+
+    /// Write `session`, failing if `must_exist` and it isn't stored yet
+    fn write(&self, session: &Session, must_exist: bool) -> Result<(), SessionError> {
+        let bytes = self.encrypt(session)?;
+        let txn = self.db.begin_write().map_err(storage_error)?;
+        {
+            let mut table = txn.open_table(SESSIONS).map_err(storage_error)?;
+            if must_exist
+                && table
+                    .get(session.session_id.as_str())
+                    .map_err(storage_error)?
+                    .is_none()
+            {
+                return Err(SessionError::SessionNotFound {
+                    session_id: session.session_id.clone(),
+                });
+            }
+            table
+                .insert(session.session_id.as_str(), bytes.as_slice())
+                .map_err(storage_error)?;
+        }
+        txn.commit().map_err(storage_error)
+    }
+
+    /// Run blocking database work off the async runtime
+    async fn blocking<T: Send + 'static>(
+        &self,
+        work: impl FnOnce(Self) -> Result<T, SessionError> + Send + 'static,
+    ) -> Result<T, SessionError> {
+        let storage = self.clone();
+        tokio::task::spawn_blocking(move || work(storage))
+            .await
+            .map_err(storage_error)?
+    }
+}
+
+#[async_trait::async_trait]
+impl SessionStorage for PersistentSessionStorage {
+    async fn store_session(&self, session: &Session) -> Result<(), SessionError> {
+        let session = session.clone();
+        self.blocking(move |storage| {
+            storage.write(&session, false)?;
+            debug!(
+                "Stored session {} for user {}",
+                session.session_id, session.user_id
+            );
+            Ok(())
+        })
+        .await
+    }
+
+    async fn get_session(&self, session_id: &str) -> Result<Option<Session>, SessionError> {
+        let session_id = session_id.to_string();
+        self.blocking(move |storage| {
+            let txn = storage.db.begin_read().map_err(storage_error)?;
+            let table = txn.open_table(SESSIONS).map_err(storage_error)?;
+            let value = table.get(session_id.as_str()).map_err(storage_error)?;
+            value
+                .map(|value| storage.decrypt(value.value()))
+                .transpose()
+        })
+        .await
+    }
+
+    async fn update_session(&self, session: &Session) -> Result<(), SessionError> {
+        let session = session.clone();
+        self.blocking(move |storage| {
+            storage.write(&session, true)?;
+            debug!("Updated session {}", session.session_id);
+            Ok(())
+        })
+        .await
+    }
+
+    async fn delete_session(&self, session_id: &str) -> Result<(), SessionError> {
+        let session_id = session_id.to_string();
+        self.blocking(move |storage| {
+            let txn = storage.db.begin_write().map_err(storage_error)?;
+            let removed = {
+                let mut table = txn.open_table(SESSIONS).map_err(storage_error)?;
+                table
+                    .remove(session_id.as_str())
+                    .map_err(storage_error)?
+                    .is_some()
+            };
+            txn.commit().map_err(storage_error)?;
+
+            if removed {
+                debug!("Deleted session {}", session_id);
+                Ok(())
+            } else {
+                Err(SessionError::SessionNotFound { session_id })
+            }
+        })
+        .await
+    }
+
+    async fn get_user_sessions(&self, user_id: &str) -> Result<Vec<Session>, SessionError> {
+        let user_id = user_id.to_string();
+        self.blocking(move |storage| {
+            let mut sessions = storage.load_all()?;
+            sessions.retain(|session| session.user_id == user_id);
+            Ok(sessions)
+        })
+        .await
+    }
+
+    async fn cleanup_expired(&self) -> Result<u64, SessionError> {
+        self.blocking(|storage| {
+            let now = chrono::Utc::now();
+            let expired: Vec<String> = storage
+                .load_all()?
+                .into_iter()
+                .filter(|session| session.expires_at < now)
+                .map(|session| session.session_id)
+                .collect();
+            if expired.is_empty() {
+                return Ok(0);
+            }
+
+            let txn = storage.db.begin_write().map_err(storage_error)?;
+            {
+                let mut table = txn.open_table(SESSIONS).map_err(storage_error)?;
+                for session_id in &expired {
+                    table.remove(session_id.as_str()).map_err(storage_error)?;
+                }
+            }
+            txn.commit().map_err(storage_error)?;
+
+            info!("Cleaned up {} expired sessions", expired.len());
+            Ok(expired.len() as u64)
+        })
+        .await
+    }
+
+    async fn get_session_count(&self, user_id: &str) -> Result<usize, SessionError> {
+        Ok(self.get_user_sessions(user_id).await?.len())
+    }
+}
+
+fn storage_error(e: impl std::fmt::Display) -> SessionError {
+    SessionError::StorageError(e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::AuthContext;
+    use crate::models::Role;
+    use tempfile::tempdir;
+
+    const MASTER_KEY: &[u8] = b"session-storage-test-master-key!";
+
+    fn session(user_id: &str, duration: chrono::Duration) -> Session {
+        let auth_context = AuthContext {
+            user_id: Some(user_id.to_string()),
+            roles: vec![Role::Operator],
+            api_key_id: Some("key-1".to_string()),
+            permissions: vec!["session:create".to_string()],
+        };
+        Session::new(user_id.to_string(), auth_context, duration)
+            .with_client_info(Some("10.0.0.1".to_string()), None)
+    }
+
+    #[tokio::test]
+    async fn test_sessions_survive_reopen() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("sessions.redb");
+        let stored = session("alice", chrono::Duration::hours(1));
+
+        {
+            let storage =
+                PersistentSessionStorage::open_with_master_key(&path, MASTER_KEY).unwrap();
+            storage.store_session(&stored).await.unwrap();
+        }
+
+        // Simulate a restart by reopening the database
+        let storage = PersistentSessionStorage::open_with_master_key(&path, MASTER_KEY).unwrap();
+        let restored = storage
+            .get_session(&stored.session_id)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(restored.user_id, "alice");
+        assert_eq!(restored.expires_at, stored.expires_at);
+        assert_eq!(restored.client_ip.as_deref(), Some("10.0.0.1"));
+        assert_eq!(storage.get_session_count("alice").await.unwrap(), 1);
+
+        // Session data is encrypted on disk
+        drop(storage);
+        let raw = std::fs::read(&path).unwrap();
+        assert!(!raw.windows(b"alice".len()).any(|w| w == b"alice"));
+
+        // A different master key can't read the sessions
+        let other = PersistentSessionStorage::open_with_master_key(&path, b"other").unwrap();
+        assert!(matches!(
+            other.get_session(&stored.session_id).await,
+            Err(SessionError::StorageError(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_expired_sessions_swept() {
+        let dir = tempdir().unwrap();
+        let storage =
+            PersistentSessionStorage::open_with_master_key(dir.path().join("s.redb"), MASTER_KEY)
+                .unwrap();
+        let live = session("bob", chrono::Duration::hours(1));
+        let expired = session("bob", chrono::Duration::seconds(-1));
+        storage.store_session(&live).await.unwrap();
+        storage.store_session(&expired).await.unwrap();
+
+        let sweep = storage.start_expiry_sweep(std::time::Duration::from_millis(10));
+        tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+        sweep.abort();
+
+        assert!(
+            storage
+                .get_session(&expired.session_id)
+                .await
+                .unwrap()
+                .is_none()
+        );
+        let remaining = storage.get_user_sessions("bob").await.unwrap();
+        assert_eq!(remaining.len(), 1);
+        assert_eq!(remaining[0].session_id, live.session_id);
+
+        assert!(matches!(
+            storage.update_session(&expired).await,
+            Err(SessionError::SessionNotFound { .. })
+        ));
+        storage.delete_session(&live.session_id).await.unwrap();
+        assert!(matches!(
+            storage.delete_session(&live.session_id).await,
+            Err(SessionError::SessionNotFound { .. })
+        ));
+    }
+}
@@ -313,6 +313,9 @@ pub struct SessionConfig {
     /// Cleanup interval for expired sessions
     pub cleanup_interval: chrono::Duration,
 
+    /// Jitter and backoff applied to `cleanup_interval` by the cleanup task
+    pub cleanup_schedule: CleanupSchedule,
+
     /// Enable session extension on access
     pub extend_on_access: bool,
 
@@ -331,12 +334,75 @@ impl Default for SessionConfig {
             enable_refresh: true,
             refresh_duration: chrono::Duration::days(30),
             cleanup_interval: chrono::Duration::hours(1),
+            cleanup_schedule: CleanupSchedule::default(),
             extend_on_access: true,
             extension_duration: chrono::Duration::hours(1),
         }
     }
 }
 
+/// Scheduling of the background session cleanup task
+///
+/// Jitter spreads cleanup runs so replicas started together don't all clean
+/// up at once. Backoff stretches the delay while runs find little to remove,
+/// up to `max_interval`, and snaps back to the base interval once a run
+/// removes more than `idle_threshold` sessions.
+#[derive(Debug, Clone)]
+pub struct CleanupSchedule {
+    /// Fraction of each delay that is randomised, e.g. `0.1` for ±10%
+    pub jitter: f64,
+
+    /// Factor the delay grows by after an idle run (`1.0` disables backoff)
+    pub backoff_factor: f64,
+
+    /// Longest un-jittered delay backoff can reach
+    pub max_interval: chrono::Duration,
+
+    /// Runs removing at most this many sessions count as idle
+    pub idle_threshold: u64,
+}
+
+impl Default for CleanupSchedule {
+    fn default() -> Self {
+        Self {
+            jitter: 0.1,
+            backoff_factor: 1.0,
+            max_interval: chrono::Duration::hours(4),
+            idle_threshold: 0,
+        }
+    }
+}
+
+impl CleanupSchedule {
+    /// Un-jittered delay after a run that removed `removed` sessions
+    pub fn next_interval(
+        &self,
+        base: std::time::Duration,
+        previous: std::time::Duration,
+        removed: u64,
+    ) -> std::time::Duration {
+        if removed > self.idle_threshold || self.backoff_factor <= 1.0 {
+            return base;
+        }
+        let max_interval = self.max_interval.to_std().unwrap_or(base).max(base);
+        previous
+            .mul_f64(self.backoff_factor)
+            .min(max_interval)
+            .max(base)
+    }
+
+    /// Randomise a delay uniformly within ±`jitter` of it
+    pub fn jittered(&self, interval: std::time::Duration) -> std::time::Duration {
+        use rand::Rng;
+
+        let jitter = self.jitter.clamp(0.0, 1.0);
+        if jitter == 0.0 {
+            return interval;
+        }
+        interval.mul_f64(rand::thread_rng().gen_range(1.0 - jitter..=1.0 + jitter))
+    }
+}
+
 /// Session manager for handling session lifecycle
 pub struct SessionManager {
     config: SessionConfig,
@@ -583,30 +649,38 @@ impl SessionManager {
     }
 
     /// Start background cleanup task
+    ///
+    /// Runs are spaced by `cleanup_interval`, adjusted by the configured
+    /// [`CleanupSchedule`].
     pub async fn start_cleanup_task(&self) -> tokio::task::JoinHandle<()> {
         let storage = Arc::clone(&self.storage);
-        let interval = self.config.cleanup_interval;
+        let schedule = self.config.cleanup_schedule.clone();
+        let base = self
+            .config
+            .cleanup_interval
+            .to_std()
+            .unwrap_or(std::time::Duration::from_secs(3600));
 
         tokio::spawn(async move {
-            let mut cleanup_interval = tokio::time::interval(
-                interval
-                    .to_std()
-                    .unwrap_or(std::time::Duration::from_secs(3600)),
-            );
+            let mut interval = base;
 
             loop {
-                cleanup_interval.tick().await;
+                tokio::time::sleep(schedule.jittered(interval)).await;
 
-                match storage.cleanup_expired().await {
+                let removed = match storage.cleanup_expired().await {
                     Ok(count) => {
                         if count > 0 {
                             debug!("Cleanup task removed {} expired sessions", count);
                         }
+                        count
                     }
                     Err(e) => {
                         error!("Session cleanup failed: {}", e);
+                        // Not an idle run: retry at the base interval
+                        u64::MAX
                     }
-                }
+                };
+                interval = schedule.next_interval(base, interval, removed);
             }
         })
     }
@@ -799,4 +873,94 @@ mod tests {
         assert!(cleanup_result.is_ok());
         assert!(cleanup_result.unwrap() > 0);
     }
+
+    #[test]
+    fn test_cleanup_jitter_within_bounds() {
+        let schedule = CleanupSchedule {
+            jitter: 0.25,
+            ..Default::default()
+        };
+        let interval = std::time::Duration::from_secs(100);
+
+        let delays: Vec<_> = (0..500).map(|_| schedule.jittered(interval)).collect();
+        assert!(delays.iter().all(|delay| {
+            *delay >= std::time::Duration::from_secs(75)
+                && *delay <= std::time::Duration::from_secs(125)
+        }));
+        assert!(delays.iter().any(|delay| *delay != delays[0]));
+
+        let no_jitter = CleanupSchedule {
+            jitter: 0.0,
+            ..Default::default()
+        };
+        assert_eq!(no_jitter.jittered(interval), interval);
+    }
+
+    #[test]
+    fn test_cleanup_backoff_while_idle() {
+        let schedule = CleanupSchedule {
+            backoff_factor: 2.0,
+            max_interval: chrono::Duration::seconds(35),
+            idle_threshold: 1,
+            ..Default::default()
+        };
+        let base = std::time::Duration::from_secs(10);
+
+        let mut interval = base;
+        let mut intervals = Vec::new();
+        for _ in 0..3 {
+            interval = schedule.next_interval(base, interval, 0);
+            intervals.push(interval.as_secs());
+        }
+        assert_eq!(intervals, vec![20, 35, 35]);
+
+        // A busy run snaps back to the base interval
+        assert_eq!(schedule.next_interval(base, interval, 5), base);
+    }
+
+    #[tokio::test]
+    async fn test_cleanup_task_removes_expired_sessions_within_bound() {
+        let config = SessionConfig {
+            cleanup_interval: chrono::Duration::milliseconds(20),
+            cleanup_schedule: CleanupSchedule {
+                jitter: 0.5,
+                backoff_factor: 2.0,
+                max_interval: chrono::Duration::milliseconds(80),
+                idle_threshold: 0,
+            },
+            ..Default::default()
+        };
+        let manager = SessionManager::new(config, Arc::new(MemorySessionStorage::new()));
+        let task = manager.start_cleanup_task().await;
+
+        // Let the task back off to its maximum interval while idle
+        tokio::time::sleep(std::time::Duration::from_millis(200)).await;
+
+        let (session, _) = manager
+            .create_session(
+                "test_user".to_string(),
+                create_test_auth_context(),
+                Some(chrono::Duration::milliseconds(1)),
+                None,
+                None,
+            )
+            .await
+            .unwrap();
+
+        // Expired sessions are removed within the max interval plus jitter
+        let removed = tokio::time::timeout(std::time::Duration::from_millis(500), async {
+            while manager
+                .storage
+                .get_session(&session.session_id)
+                .await
+                .unwrap()
+                .is_some()
+            {
+                tokio::time::sleep(std::time::Duration::from_millis(5)).await;
+            }
+        })
+        .await;
+        task.abort();
+        assert!(removed.is_ok());
+    }
 }
@@ -37,6 +37,13 @@ pub trait StorageBackend: Send + Sync {
     async fn save_key(&self, key: &ApiKey) -> Result<(), StorageError>;
     async fn delete_key(&self, key_id: &str) -> Result<(), StorageError>;
     async fn save_all_keys(&self, keys: &HashMap<String, ApiKey>) -> Result<(), StorageError>;
+
+    /// Re-encrypt stored keys under a new master key
+    ///
+    /// Backends that don't encrypt their contents have nothing to do.
+    async fn rotate_master_key(&self, _master_key: &[u8; 32]) -> Result<(), StorageError> {
+        Ok(())
+    }
 }
 
 /// Create a storage backend from configuration
@@ -75,7 +82,8 @@ pub async fn create_storage_backend(
 /// File-based storage backend with atomic operations and encryption
 pub struct FileStorage {
     path: PathBuf,
-    encryption_key: [u8; 32],
+    /// Derived from the master key; replaced when the master key rotates
+    encryption_key: std::sync::RwLock<[u8; 32]>,
     #[allow(dead_code)]
     file_permissions: u32,
     #[allow(dead_code)]
@@ -125,7 +133,7 @@ impl FileStorage {
 
         let storage = Self {
             path,
-            encryption_key,
+            encryption_key: std::sync::RwLock::new(encryption_key),
             file_permissions,
             dir_permissions,
             require_secure_filesystem,
@@ -286,7 +294,7 @@ impl FileStorage {
         use crate::crypto::encryption::encrypt_data;
 
         let content = serde_json::to_string_pretty(keys)?;
-        let encrypted_data = encrypt_data(content.as_bytes(), &self.encryption_key)?;
+        let encrypted_data = encrypt_data(content.as_bytes(), &self.encryption_key())?;
         let encrypted_content = serde_json::to_string_pretty(&encrypted_data)?;
 
         // Atomic write using temp file
@@ -519,7 +527,7 @@ impl StorageBackend for FileStorage {
         // Try to decrypt the content (new format)
         let decrypted_content = if let Ok(encrypted_data) = serde_json::from_slice(&content) {
             // Encrypted format
-            let decrypted_bytes = decrypt_data(&encrypted_data, &self.encryption_key)?;
+            let decrypted_bytes = decrypt_data(&encrypted_data, &self.encryption_key())?;
             String::from_utf8(decrypted_bytes)
                 .map_err(|e| StorageError::General(format!("Invalid UTF-8: {}", e)))?
         } else {
@@ -573,9 +581,43 @@ impl StorageBackend for FileStorage {
         let _lock = self.write_mutex.lock().await;
         self.save_all_keys_internal(keys).await
     }
+
+    async fn rotate_master_key(&self, master_key: &[u8; 32]) -> Result<(), StorageError> {
+        use crate::crypto::encryption::derive_encryption_key;
+
+        let _lock = self.write_mutex.lock().await;
+        let keys = self.load_keys().await?;
+        let previous = std::mem::replace(
+            &mut *self
+                .encryption_key
+                .write()
+                .unwrap_or_else(|e| e.into_inner()),
+            derive_encryption_key(master_key, "api-key-storage"),
+        );
+        if let Err(e) = self.save_all_keys_internal(&keys).await {
+            // The file still holds the old encryption
+            *self
+                .encryption_key
+                .write()
+                .unwrap_or_else(|e| e.into_inner()) = previous;
+            return Err(e);
+        }
+        info!(
+            "Re-encrypted {} stored keys under the new master key",
+            keys.len()
+        );
+        Ok(())
+    }
 }
 
 impl FileStorage {
+    fn encryption_key(&self) -> [u8; 32] {
+        *self
+            .encryption_key
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+    }
+
     async fn save_all_keys_internal(
         &self,
         keys: &HashMap<String, ApiKey>,
@@ -1163,6 +1205,26 @@ mod tests {
             assert!(!remaining_keys.contains_key(&key_to_delete));
         }
 
+        #[tokio::test]
+        async fn test_file_storage_persists_rotation() {
+            let (storage, _temp_dir) = create_test_file_storage().await;
+            let mut key = create_test_key("rotating-key", Role::Operator);
+            let old_secret = key.key.clone();
+            key.rotate_secret(Utc::now() + Duration::hours(1));
+
+            storage.save_key(&key).await.unwrap();
+
+            let loaded = storage.load_keys().await.unwrap();
+            let loaded_key = &loaded[&key.id];
+            let previous = loaded_key.previous_secret.as_ref().unwrap();
+            assert_eq!(
+                previous.valid_until,
+                key.previous_secret.as_ref().unwrap().valid_until
+            );
+            assert!(loaded_key.verify_previous_secret(&old_secret).unwrap());
+            assert!(loaded_key.verify_key(&key.key).unwrap());
+        }
+
         #[tokio::test]
         async fn test_file_storage_encryption() {
             let (storage, _temp_dir) = create_test_file_storage().await;
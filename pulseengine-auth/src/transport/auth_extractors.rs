@@ -3,17 +3,37 @@
 //! This module defines the common interface for extracting authentication
 //! from different transport types.
 
+use crate::manager::{AuthError, AuthenticationManager};
+use crate::models::{AuthContext, parse_ip_range};
 use async_trait::async_trait;
+use pulseengine_mcp_protocol::ErrorCode;
 use serde_json::Value;
 use std::collections::HashMap;
+use std::net::IpAddr;
 use thiserror::Error;
 
 /// Errors that can occur during transport authentication
+///
+/// Each variant has a machine-readable [`reason`](Self::reason) and maps to
+/// an [`ErrorCode`], so clients can tell a missing header from a malformed
+/// one or an unsupported scheme.
 #[derive(Debug, Error)]
 pub enum TransportAuthError {
     #[error("No authentication provided")]
     NoAuth,
 
+    #[error("Missing required header: {0}")]
+    MissingHeader(String),
+
+    #[error("Malformed authentication header: {0}")]
+    MalformedHeader(String),
+
+    #[error("Unsupported authentication scheme: {0}")]
+    UnsupportedScheme(String),
+
+    #[error("Empty credential")]
+    EmptyCredential,
+
     #[error("Invalid authentication format: {0}")]
     InvalidFormat(String),
 
@@ -27,6 +47,48 @@ pub enum TransportAuthError {
     AuthFailed(String),
 }
 
+impl TransportAuthError {
+    /// Machine-readable reason, sent to clients as `data.reason`
+    pub fn reason(&self) -> &'static str {
+        match self {
+            Self::NoAuth => "no_auth",
+            Self::MissingHeader(_) => "missing_header",
+            Self::MalformedHeader(_) => "malformed_header",
+            Self::UnsupportedScheme(_) => "unsupported_scheme",
+            Self::EmptyCredential => "empty_credential",
+            Self::InvalidFormat(_) => "invalid_format",
+            Self::UnsupportedTransport => "unsupported_transport",
+            Self::MissingData(_) => "missing_data",
+            Self::AuthFailed(_) => "auth_failed",
+        }
+    }
+
+    /// Protocol error code reported to clients
+    pub fn error_code(&self) -> ErrorCode {
+        match self {
+            Self::NoAuth => ErrorCode::AuthenticationRequired,
+            Self::MissingHeader(_) => ErrorCode::MissingAuthHeader,
+            Self::MalformedHeader(_) => ErrorCode::MalformedAuthHeader,
+            Self::UnsupportedScheme(_) => ErrorCode::UnsupportedAuthScheme,
+            Self::EmptyCredential => ErrorCode::EmptyCredential,
+            Self::InvalidFormat(_) => ErrorCode::InvalidCredentialFormat,
+            Self::UnsupportedTransport => ErrorCode::InternalError,
+            Self::MissingData(_) => ErrorCode::InvalidParams,
+            Self::AuthFailed(_) => ErrorCode::Unauthorized,
+        }
+    }
+}
+
+impl From<TransportAuthError> for pulseengine_mcp_protocol::Error {
+    fn from(err: TransportAuthError) -> Self {
+        let mut data = serde_json::json!({ "reason": err.reason() });
+        if let TransportAuthError::UnsupportedScheme(scheme) = &err {
+            data["scheme"] = Value::String(scheme.clone());
+        }
+        Self::with_data(err.error_code(), err.to_string(), data)
+    }
+}
+
 /// Result of authentication extraction
 pub type AuthExtractionResult = Result<Option<TransportAuthContext>, TransportAuthError>;
 
@@ -82,6 +144,18 @@ impl TransportAuthContext {
         self.metadata.insert(key, value);
         self
     }
+
+    /// Validate the credential as an API key from the client IP
+    ///
+    /// The client IP is checked against the key's IP whitelist and denylist.
+    pub async fn validate_api_key(
+        &self,
+        auth_manager: &AuthenticationManager,
+    ) -> Result<Option<AuthContext>, AuthError> {
+        auth_manager
+            .validate_api_key(&self.credential, self.client_ip.as_deref())
+            .await
+    }
 }
 
 /// Transport type enum
@@ -110,6 +184,9 @@ pub struct TransportRequest {
 
     /// Transport-specific metadata
     pub metadata: HashMap<String, Value>,
+
+    /// Address of the connected peer (for network transports)
+    pub peer_ip: Option<IpAddr>,
 }
 
 impl TransportRequest {
@@ -121,6 +198,7 @@ impl TransportRequest {
             body: None,
             raw_data: None,
             metadata: HashMap::new(),
+            peer_ip: None,
         }
     }
 
@@ -132,6 +210,7 @@ impl TransportRequest {
             body: None,
             raw_data: None,
             metadata: HashMap::new(),
+            peer_ip: None,
         }
     }
 
@@ -153,6 +232,12 @@ impl TransportRequest {
         self
     }
 
+    /// Set the address of the connected peer
+    pub fn with_peer_ip(mut self, peer_ip: IpAddr) -> Self {
+        self.peer_ip = Some(peer_ip);
+        self
+    }
+
     /// Get header value
     pub fn get_header(&self, key: &str) -> Option<&String> {
         self.headers.get(key)
@@ -201,22 +286,51 @@ pub struct AuthUtils;
 impl AuthUtils {
     /// Extract Bearer token from Authorization header
     pub fn extract_bearer_token(auth_header: &str) -> Result<String, TransportAuthError> {
-        if !auth_header.starts_with("Bearer ") {
-            return Err(TransportAuthError::InvalidFormat(
-                "Authorization header must start with 'Bearer '".to_string(),
-            ));
-        }
+        let Some(token) = auth_header.strip_prefix("Bearer ") else {
+            return Err(Self::unsupported_authorization(auth_header));
+        };
 
-        let token = &auth_header[7..]; // Skip "Bearer "
-        if token.is_empty() {
-            return Err(TransportAuthError::InvalidFormat(
-                "Bearer token cannot be empty".to_string(),
-            ));
+        if token.trim().is_empty() {
+            return Err(TransportAuthError::EmptyCredential);
         }
 
         Ok(token.to_string())
     }
 
+    /// Try credential sources in order of preference
+    ///
+    /// Returns the first credential found. A source rejecting its credential
+    /// doesn't stop the search; its error is only returned when no later
+    /// source succeeds either.
+    pub fn first_credential(sources: &[&dyn Fn() -> AuthExtractionResult]) -> AuthExtractionResult {
+        let mut rejection = None;
+        for source in sources {
+            match source() {
+                Ok(Some(context)) => return Ok(Some(context)),
+                Ok(None) => {}
+                Err(e) => {
+                    rejection.get_or_insert(e);
+                }
+            }
+        }
+        rejection.map_or(Ok(None), Err)
+    }
+
+    /// Error for an Authorization header that isn't in a supported scheme
+    ///
+    /// A header with a `Scheme credentials` shape names an unsupported
+    /// scheme; anything else is malformed.
+    pub fn unsupported_authorization(auth_header: &str) -> TransportAuthError {
+        match auth_header.split_once(' ') {
+            Some((scheme, _)) if !scheme.is_empty() => {
+                TransportAuthError::UnsupportedScheme(scheme.to_string())
+            }
+            _ => TransportAuthError::MalformedHeader(
+                "Authorization header must be in format 'Scheme credentials'".to_string(),
+            ),
+        }
+    }
+
     /// Extract API key from X-API-Key header
     pub fn extract_api_key_header(headers: &HashMap<String, String>) -> Option<String> {
         headers
@@ -240,6 +354,65 @@ impl AuthUtils {
             })
     }
 
+    /// Client IP of a request
+    ///
+    /// See [`resolve_client_ip`](Self::resolve_client_ip); forwarded hops are
+    /// read from `X-Forwarded-For`.
+    pub fn request_client_ip(
+        request: &TransportRequest,
+        trusted_proxies: &[String],
+    ) -> Option<String> {
+        let forwarded_for = request
+            .headers
+            .get("X-Forwarded-For")
+            .or_else(|| request.headers.get("x-forwarded-for"));
+        Self::resolve_client_ip(
+            request.peer_ip,
+            forwarded_for.map(String::as_str),
+            trusted_proxies,
+        )
+        .map(|ip| ip.to_string())
+    }
+
+    /// Resolve the originating client IP of a connection
+    ///
+    /// The socket peer address is authoritative. Only when the peer is one of
+    /// `trusted_proxies` (addresses or CIDR ranges) is the comma-separated
+    /// `forwarded_for` list consulted, and then the right-most hop that isn't
+    /// itself a trusted proxy is the client; hops left of it are
+    /// client-controlled. Without a peer address the client is unknown.
+    pub fn resolve_client_ip(
+        peer_ip: Option<IpAddr>,
+        forwarded_for: Option<&str>,
+        trusted_proxies: &[String],
+    ) -> Option<IpAddr> {
+        let peer_ip = peer_ip?.to_canonical();
+        let trusted: Vec<_> = trusted_proxies
+            .iter()
+            .filter_map(|entry| parse_ip_range(entry))
+            .collect();
+        let is_trusted = |ip: &IpAddr| trusted.iter().any(|range| range.contains(ip));
+
+        let mut client_ip = peer_ip;
+        if !is_trusted(&client_ip) {
+            return Some(client_ip);
+        }
+        for hop in forwarded_for
+            .into_iter()
+            .flat_map(|value| value.rsplit(','))
+        {
+            // An unparseable hop ends the chain at the last trusted proxy
+            let Ok(hop) = hop.trim().parse::<IpAddr>() else {
+                break;
+            };
+            client_ip = hop.to_canonical();
+            if !is_trusted(&client_ip) {
+                break;
+            }
+        }
+        Some(client_ip)
+    }
+
     /// Extract user agent from headers
     pub fn extract_user_agent(headers: &HashMap<String, String>) -> Option<String> {
         headers
@@ -251,9 +424,7 @@ impl AuthUtils {
     /// Validate API key format (basic checks)
     pub fn validate_api_key_format(api_key: &str) -> Result<(), TransportAuthError> {
         if api_key.is_empty() {
-            return Err(TransportAuthError::InvalidFormat(
-                "API key cannot be empty".to_string(),
-            ));
+            return Err(TransportAuthError::EmptyCredential);
         }
 
         if api_key.len() < 16 {
@@ -299,6 +470,61 @@ mod tests {
         assert!(AuthUtils::extract_bearer_token(empty_token).is_err());
     }
 
+    #[test]
+    fn test_errors_carry_reason_and_code() {
+        let cases = [
+            (
+                AuthUtils::extract_bearer_token("Basic abc123").unwrap_err(),
+                "unsupported_scheme",
+                ErrorCode::UnsupportedAuthScheme,
+            ),
+            (
+                AuthUtils::extract_bearer_token("Bearer ").unwrap_err(),
+                "empty_credential",
+                ErrorCode::EmptyCredential,
+            ),
+            (
+                AuthUtils::extract_bearer_token("abc123").unwrap_err(),
+                "malformed_header",
+                ErrorCode::MalformedAuthHeader,
+            ),
+            (
+                TransportAuthError::MissingHeader("Authorization".to_string()),
+                "missing_header",
+                ErrorCode::MissingAuthHeader,
+            ),
+        ];
+
+        for (err, reason, code) in cases {
+            assert_eq!(err.reason(), reason);
+            let error: pulseengine_mcp_protocol::Error = err.into();
+            assert_eq!(error.code, code);
+            assert_eq!(error.data.unwrap()["reason"], reason);
+        }
+
+        // Every variant has its own code
+        let variants = [
+            TransportAuthError::NoAuth,
+            TransportAuthError::MissingHeader(String::new()),
+            TransportAuthError::MalformedHeader(String::new()),
+            TransportAuthError::UnsupportedScheme(String::new()),
+            TransportAuthError::EmptyCredential,
+            TransportAuthError::InvalidFormat(String::new()),
+            TransportAuthError::UnsupportedTransport,
+            TransportAuthError::MissingData(String::new()),
+            TransportAuthError::AuthFailed(String::new()),
+        ];
+        let codes: std::collections::HashSet<_> = variants
+            .iter()
+            .map(TransportAuthError::error_code)
+            .collect();
+        assert_eq!(codes.len(), variants.len());
+
+        let error: pulseengine_mcp_protocol::Error =
+            TransportAuthError::UnsupportedScheme("Digest".to_string()).into();
+        assert_eq!(error.data.unwrap()["scheme"], "Digest");
+    }
+
     #[test]
     fn test_api_key_header_extraction() {
         let mut headers = HashMap::new();
@@ -333,6 +559,46 @@ mod tests {
         assert_eq!(ip2, "203.0.113.45");
     }
 
+    #[test]
+    fn test_client_ip_resolution_trusts_only_proxies() {
+        let proxies = vec!["10.0.0.0/8".to_string(), "192.0.2.1".to_string()];
+        let ip = |ip: &str| Some(ip.parse::<IpAddr>().unwrap());
+        let resolve = |peer: &str, forwarded: Option<&str>| {
+            AuthUtils::resolve_client_ip(ip(peer), forwarded, &proxies)
+        };
+
+        // Headers from an untrusted peer are ignored
+        assert_eq!(resolve("203.0.113.9", Some("10.1.1.1")), ip("203.0.113.9"));
+        assert_eq!(resolve("203.0.113.9", None), ip("203.0.113.9"));
+
+        // Behind trusted proxies, the right-most untrusted hop is the client
+        assert_eq!(
+            resolve("192.0.2.1", Some("1.1.1.1, 198.51.100.7, 10.0.0.5")),
+            ip("198.51.100.7")
+        );
+        assert_eq!(
+            resolve("::ffff:10.0.0.1", Some("198.51.100.7")),
+            ip("198.51.100.7")
+        );
+        assert_eq!(
+            resolve("10.0.0.1", Some("garbage, 10.0.0.2")),
+            ip("10.0.0.2")
+        );
+        assert_eq!(resolve("10.0.0.1", None), ip("10.0.0.1"));
+        assert_eq!(
+            AuthUtils::resolve_client_ip(None, Some("198.51.100.7"), &proxies),
+            None
+        );
+
+        let request = TransportRequest::new()
+            .with_header("X-Forwarded-For".to_string(), "10.9.9.9".to_string())
+            .with_peer_ip("203.0.113.9".parse().unwrap());
+        assert_eq!(
+            AuthUtils::request_client_ip(&request, &[]).as_deref(),
+            Some("203.0.113.9")
+        );
+    }
+
     #[test]
     fn test_api_key_format_validation() {
         // Valid key
@@ -28,7 +28,7 @@ pub struct HttpAuthConfig {
     /// Enable CORS preflight authentication
     pub enable_cors_auth: bool,
 
-    /// Trusted proxy IPs for X-Forwarded-For
+    /// Proxy addresses or CIDR ranges whose X-Forwarded-For is believed
     pub trusted_proxies: Vec<String>,
 }
 
@@ -134,33 +134,30 @@ impl HttpAuthExtractor {
             return self.extract_basic_auth(auth_header);
         }
 
-        Err(TransportAuthError::InvalidFormat(format!(
-            "Unsupported Authorization header format: {}",
-            auth_header
-        )))
+        Err(AuthUtils::unsupported_authorization(auth_header))
     }
 
     /// Extract Basic authentication
     fn extract_basic_auth(&self, auth_header: &str) -> AuthExtractionResult {
-        if !auth_header.starts_with("Basic ") {
-            return Err(TransportAuthError::InvalidFormat(
-                "Invalid Basic auth format".to_string(),
-            ));
+        let Some(encoded) = auth_header.strip_prefix("Basic ") else {
+            return Err(AuthUtils::unsupported_authorization(auth_header));
+        };
+        if encoded.trim().is_empty() {
+            return Err(TransportAuthError::EmptyCredential);
         }
 
-        let encoded = &auth_header[6..]; // Skip "Basic "
         use base64::{Engine as _, engine::general_purpose};
         let decoded = match general_purpose::STANDARD.decode(encoded) {
             Ok(bytes) => match String::from_utf8(bytes) {
                 Ok(string) => string,
                 Err(_) => {
-                    return Err(TransportAuthError::InvalidFormat(
+                    return Err(TransportAuthError::MalformedHeader(
                         "Invalid UTF-8 in Basic auth".to_string(),
                     ));
                 }
             },
             Err(_) => {
-                return Err(TransportAuthError::InvalidFormat(
+                return Err(TransportAuthError::MalformedHeader(
                     "Invalid Base64 in Basic auth".to_string(),
                 ));
             }
@@ -168,7 +165,7 @@ impl HttpAuthExtractor {
 
         let parts: Vec<&str> = decoded.splitn(2, ':').collect();
         if parts.len() != 2 {
-            return Err(TransportAuthError::InvalidFormat(
+            return Err(TransportAuthError::MalformedHeader(
                 "Basic auth must be username:password".to_string(),
             ));
         }
@@ -256,7 +253,8 @@ impl HttpAuthExtractor {
         request: &TransportRequest,
     ) -> TransportAuthContext {
         // Add client IP
-        if let Some(client_ip) = AuthUtils::extract_client_ip(&request.headers) {
+        if let Some(client_ip) = AuthUtils::request_client_ip(request, &self.config.trusted_proxies)
+        {
             context = context.with_client_ip(client_ip);
         }
 
@@ -364,9 +362,7 @@ impl AuthExtractor for HttpAuthExtractor {
     ) -> Result<(), TransportAuthError> {
         // Additional HTTP-specific validation can go here
         if context.credential.is_empty() {
-            return Err(TransportAuthError::InvalidFormat(
-                "Empty credential".to_string(),
-            ));
+            return Err(TransportAuthError::EmptyCredential);
         }
 
         Ok(())
@@ -484,8 +480,52 @@ mod tests {
     }
 
     #[test]
-    fn test_context_enrichment() {
+    fn test_authorization_errors_are_specific() {
         let extractor = HttpAuthExtractor::default();
+        let extract = |value: &str| {
+            let request =
+                TransportRequest::new().with_header("Authorization".to_string(), value.to_string());
+            tokio_test::block_on(extractor.extract_auth(&request)).unwrap_err()
+        };
+
+        assert!(matches!(
+            extract("Digest username=\"admin\""),
+            TransportAuthError::UnsupportedScheme(scheme) if scheme == "Digest"
+        ));
+        assert!(matches!(
+            extract("lmcp_test_1234567890abcdef"),
+            TransportAuthError::MalformedHeader(_)
+        ));
+        assert!(matches!(
+            extract("Bearer "),
+            TransportAuthError::EmptyCredential
+        ));
+        // Basic isn't enabled by default
+        assert!(matches!(
+            extract("Basic !!not-base64!!"),
+            TransportAuthError::UnsupportedScheme(scheme) if scheme == "Basic"
+        ));
+
+        let extractor = HttpAuthExtractor::new(HttpAuthConfig {
+            supported_methods: vec![HttpAuthMethod::Basic],
+            ..Default::default()
+        });
+        let request = TransportRequest::new().with_header(
+            "Authorization".to_string(),
+            "Basic !!not-base64!!".to_string(),
+        );
+        assert!(matches!(
+            tokio_test::block_on(extractor.extract_auth(&request)).unwrap_err(),
+            TransportAuthError::MalformedHeader(_)
+        ));
+    }
+
+    #[test]
+    fn test_context_enrichment() {
+        let extractor = HttpAuthExtractor::new(HttpAuthConfig {
+            trusted_proxies: vec!["10.0.0.1".to_string()],
+            ..Default::default()
+        });
         let mut headers = HashMap::new();
         headers.insert(
             "X-API-Key".to_string(),
@@ -495,13 +535,64 @@ mod tests {
         headers.insert("User-Agent".to_string(), "TestClient/1.0".to_string());
         headers.insert("Host".to_string(), "api.example.com".to_string());
 
-        let request = TransportRequest::from_headers(headers);
+        let request =
+            TransportRequest::from_headers(headers).with_peer_ip("10.0.0.1".parse().unwrap());
         let result = tokio_test::block_on(extractor.extract_auth(&request)).unwrap();
 
         assert!(result.is_some());
         let context = result.unwrap();
         assert_eq!(context.client_ip.unwrap(), "192.168.1.100");
+
+        // The same header from an untrusted peer is ignored
+        let request = request.with_peer_ip("203.0.113.9".parse().unwrap());
+        let context = tokio_test::block_on(extractor.extract_auth(&request))
+            .unwrap()
+            .unwrap();
+        assert_eq!(context.client_ip.unwrap(), "203.0.113.9");
         assert_eq!(context.user_agent.unwrap(), "TestClient/1.0");
         assert_eq!(context.metadata.get("host").unwrap(), "api.example.com");
     }
+
+    #[tokio::test]
+    async fn test_peer_ip_checked_against_key_ranges() {
+        use crate::{AuthConfig, AuthenticationManager, Role};
+
+        let manager = AuthenticationManager::new(AuthConfig::memory())
+            .await
+            .unwrap();
+        let key = manager
+            .create_api_key(
+                "office".to_string(),
+                Role::Operator,
+                None,
+                Some(vec!["10.0.0.0/8".to_string()]),
+            )
+            .await
+            .unwrap();
+
+        let extractor = HttpAuthExtractor::default();
+        let request = |peer_ip: &str| {
+            TransportRequest::new()
+                .with_header("X-API-Key".to_string(), key.key.clone())
+                .with_peer_ip(peer_ip.parse().unwrap())
+        };
+
+        let context = extractor
+            .extract_auth(&request("10.20.30.40"))
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(context.client_ip.as_deref(), Some("10.20.30.40"));
+        assert!(context.validate_api_key(&manager).await.unwrap().is_some());
+
+        let context = extractor
+            .extract_auth(&request("192.0.2.1"))
+            .await
+            .unwrap()
+            .unwrap();
+        assert!(matches!(
+            context.validate_api_key(&manager).await,
+            Err(crate::manager::AuthError::IpNotAllowed(_))
+        ));
+    }
 }
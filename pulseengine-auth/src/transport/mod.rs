@@ -8,7 +8,9 @@ pub mod http_auth;
 pub mod stdio_auth;
 pub mod websocket_auth;
 
-pub use auth_extractors::{AuthExtractionResult, AuthExtractor, TransportAuthContext};
+pub use auth_extractors::{
+    AuthExtractionResult, AuthExtractor, TransportAuthContext, TransportAuthError,
+};
 pub use http_auth::{HttpAuthConfig, HttpAuthExtractor};
 pub use stdio_auth::{StdioAuthConfig, StdioAuthExtractor};
 pub use websocket_auth::{WebSocketAuthConfig, WebSocketAuthExtractor};
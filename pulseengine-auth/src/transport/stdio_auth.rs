@@ -211,25 +211,15 @@ impl StdioAuthExtractor {
 #[async_trait]
 impl AuthExtractor for StdioAuthExtractor {
     async fn extract_auth(&self, request: &TransportRequest) -> AuthExtractionResult {
-        // Try different authentication sources in order of preference
-
-        // 1. Environment variables
-        if let Ok(Some(context)) = self.extract_env_auth() {
-            return Ok(Some(self.enrich_context(context, request)));
-        }
-
-        // 2. MCP initialize parameters
-        if let Ok(Some(context)) = self.extract_init_params(request) {
-            return Ok(Some(self.enrich_context(context, request)));
-        }
-
-        // 3. Process arguments (if allowed)
-        if let Ok(Some(context)) = self.extract_process_args() {
-            return Ok(Some(self.enrich_context(context, request)));
-        }
-
-        // 4. Default API key (if configured)
-        if let Ok(Some(context)) = self.extract_default_auth() {
+        // Try different authentication sources in order of preference:
+        // environment variables, MCP initialize parameters, process
+        // arguments (if allowed) and the default API key (if configured)
+        if let Some(context) = AuthUtils::first_credential(&[
+            &|| self.extract_env_auth(),
+            &|| self.extract_init_params(request),
+            &|| self.extract_process_args(),
+            &|| self.extract_default_auth(),
+        ])? {
             return Ok(Some(self.enrich_context(context, request)));
         }
 
@@ -256,9 +246,7 @@ impl AuthExtractor for StdioAuthExtractor {
     ) -> Result<(), TransportAuthError> {
         // Stdio-specific validation
         if context.credential.is_empty() {
-            return Err(TransportAuthError::InvalidFormat(
-                "Empty credential".to_string(),
-            ));
+            return Err(TransportAuthError::EmptyCredential);
         }
 
         // Additional validation for development environments
@@ -31,6 +31,9 @@ pub struct WebSocketAuthConfig {
 
     /// Connection timeout for authentication (seconds)
     pub auth_timeout_secs: u64,
+
+    /// Proxy addresses or CIDR ranges whose X-Forwarded-For is believed
+    pub trusted_proxies: Vec<String>,
 }
 
 impl Default for WebSocketAuthConfig {
@@ -46,6 +49,7 @@ impl Default for WebSocketAuthConfig {
             enable_per_message_auth: false,
             auth_subprotocol: Some("mcp-auth".to_string()),
             auth_timeout_secs: 30,
+            trusted_proxies: vec![],
         }
     }
 }
@@ -99,20 +103,15 @@ impl WebSocketAuthExtractor {
         if let Some(auth_header) = headers
             .get("Authorization")
             .or_else(|| headers.get("authorization"))
-            && auth_header.starts_with("Bearer ")
         {
-            match AuthUtils::extract_bearer_token(auth_header) {
-                Ok(token) => {
-                    AuthUtils::validate_api_key_format(&token)?;
-                    let context = TransportAuthContext::new(
-                        token,
-                        "HandshakeHeaders".to_string(),
-                        TransportType::WebSocket,
-                    );
-                    return Ok(Some(context));
-                }
-                Err(e) => return Err(e),
-            }
+            let token = AuthUtils::extract_bearer_token(auth_header)?;
+            AuthUtils::validate_api_key_format(&token)?;
+            let context = TransportAuthContext::new(
+                token,
+                "HandshakeHeaders".to_string(),
+                TransportType::WebSocket,
+            );
+            return Ok(Some(context));
         }
 
         // Try X-API-Key header
@@ -254,7 +253,8 @@ impl WebSocketAuthExtractor {
         request: &TransportRequest,
     ) -> TransportAuthContext {
         // Add client IP
-        if let Some(client_ip) = AuthUtils::extract_client_ip(&request.headers) {
+        if let Some(client_ip) = AuthUtils::request_client_ip(request, &self.config.trusted_proxies)
+        {
             context = context.with_client_ip(client_ip);
         }
 
@@ -310,26 +310,22 @@ impl WebSocketAuthExtractor {
 #[async_trait]
 impl AuthExtractor for WebSocketAuthExtractor {
     async fn extract_auth(&self, request: &TransportRequest) -> AuthExtractionResult {
-        // Try different authentication methods in order of preference
-
-        // 1. Handshake headers
-        if let Ok(Some(context)) = self.extract_handshake_headers(&request.headers) {
-            return Ok(Some(self.enrich_context(context, request)));
-        }
-
-        // 2. Query parameters
-        if let Ok(Some(context)) = self.extract_query_params(request) {
-            return Ok(Some(self.enrich_context(context, request)));
-        }
-
-        // 3. First message (if body is present)
-        if let Ok(Some(context)) = self.extract_first_message(request) {
+        // Try different authentication methods in order of preference:
+        // handshake headers, query parameters and the first message (if a
+        // body is present)
+        if let Some(context) = AuthUtils::first_credential(&[
+            &|| self.extract_handshake_headers(&request.headers),
+            &|| self.extract_query_params(request),
+            &|| self.extract_first_message(request),
+        ])? {
             return Ok(Some(self.enrich_context(context, request)));
         }
 
         // No authentication found
         if self.config.require_handshake_auth && !self.config.allow_post_connect_auth {
-            return Err(TransportAuthError::NoAuth);
+            return Err(TransportAuthError::MissingHeader(
+                "Authorization".to_string(),
+            ));
         }
 
         Ok(None)
@@ -352,9 +348,7 @@ impl AuthExtractor for WebSocketAuthExtractor {
     ) -> Result<(), TransportAuthError> {
         // WebSocket-specific validation
         if context.credential.is_empty() {
-            return Err(TransportAuthError::InvalidFormat(
-                "Empty credential".to_string(),
-            ));
+            return Err(TransportAuthError::EmptyCredential);
         }
 
         // Warn about insecure authentication methods
@@ -379,6 +373,7 @@ impl WebSocketAuthConfig {
             enable_per_message_auth: false,
             auth_subprotocol: Some("mcp-auth".to_string()),
             auth_timeout_secs: 10,
+            trusted_proxies: vec![],
         }
     }
 
@@ -395,6 +390,7 @@ impl WebSocketAuthConfig {
             enable_per_message_auth: false,
             auth_subprotocol: Some("mcp-auth".to_string()),
             auth_timeout_secs: 30,
+            trusted_proxies: vec![],
         }
     }
 
@@ -412,6 +408,7 @@ impl WebSocketAuthConfig {
             enable_per_message_auth: false,
             auth_subprotocol: Some("mcp-auth".to_string()),
             auth_timeout_secs: 60,
+            trusted_proxies: vec![],
         }
     }
 }
@@ -441,6 +438,46 @@ mod tests {
         assert_eq!(context.transport_type, TransportType::WebSocket);
     }
 
+    #[test]
+    fn test_handshake_errors_are_specific() {
+        let extractor = WebSocketAuthExtractor::new(WebSocketAuthConfig {
+            require_handshake_auth: true,
+            allow_post_connect_auth: false,
+            ..Default::default()
+        });
+
+        let request = TransportRequest::new().with_header(
+            "Authorization".to_string(),
+            "Basic dXNlcjpwYXNz".to_string(),
+        );
+        let err = tokio_test::block_on(extractor.extract_auth(&request)).unwrap_err();
+        assert_eq!(err.reason(), "unsupported_scheme");
+
+        let request = TransportRequest::new()
+            .with_header("Sec-WebSocket-Key".to_string(), "test-key".to_string());
+        let err = tokio_test::block_on(extractor.extract_auth(&request)).unwrap_err();
+        assert!(matches!(err, TransportAuthError::MissingHeader(_)));
+    }
+
+    #[test]
+    fn test_rejected_source_falls_through() {
+        let extractor = WebSocketAuthExtractor::default();
+        let request = TransportRequest::new()
+            .with_header(
+                "Authorization".to_string(),
+                "Basic dXNlcjpwYXNz".to_string(),
+            )
+            .with_query_param(
+                "api_key".to_string(),
+                "lmcp_test_1234567890abcdef".to_string(),
+            );
+
+        let context = tokio_test::block_on(extractor.extract_auth(&request))
+            .unwrap()
+            .unwrap();
+        assert_eq!(context.method, "QueryParams");
+    }
+
     #[test]
     fn test_query_parameter_extraction() {
         let extractor = WebSocketAuthExtractor::default();
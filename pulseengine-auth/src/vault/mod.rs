@@ -116,6 +116,8 @@ pub struct VaultConfig {
     pub timeout_seconds: u64,
     pub retry_attempts: u32,
     pub cache_ttl_seconds: u64,
+    /// Re-fetch secrets at this interval in the background (None = never)
+    pub rotate_schedule: Option<std::time::Duration>,
 }
 
 impl Default for VaultConfig {
@@ -128,6 +130,7 @@ impl Default for VaultConfig {
             timeout_seconds: 30,
             retry_attempts: 3,
             cache_ttl_seconds: 300, // 5 minutes
+            rotate_schedule: None,
         }
     }
 }
@@ -159,11 +162,16 @@ impl VaultIntegration {
         let cache_ttl = std::time::Duration::from_secs(config.cache_ttl_seconds);
         let client = create_vault_client(config).await?;
 
-        Ok(Self {
+        Ok(Self::with_client(client, cache_ttl))
+    }
+
+    /// Create a vault integration around an existing client
+    pub fn with_client(client: Box<dyn VaultClient>, cache_ttl: std::time::Duration) -> Self {
+        Self {
             client,
             secret_cache: tokio::sync::RwLock::new(HashMap::new()),
             cache_ttl,
-        })
+        }
     }
 
     /// Get a secret with caching
@@ -263,6 +271,7 @@ mod tests {
             Some("https://app.infisical.com".to_string())
         );
         assert_eq!(config.timeout_seconds, 30);
+        assert!(config.rotate_schedule.is_none());
     }
 
     #[test]
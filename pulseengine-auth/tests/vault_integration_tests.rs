@@ -37,6 +37,7 @@ mod vault_tests {
             timeout_seconds: 60,
             retry_attempts: 5,
             cache_ttl_seconds: 600,
+            rotate_schedule: None,
         };
 
         assert_eq!(
@@ -210,6 +211,7 @@ mod mock_tests {
             timeout_seconds: 30,
             retry_attempts: 3,
             cache_ttl_seconds: 300,
+            rotate_schedule: None,
         };
 
         // Test that we can create and use the config
@@ -229,6 +231,7 @@ mod mock_tests {
             timeout_seconds: 30,
             retry_attempts: 3,
             cache_ttl_seconds: 300,
+            rotate_schedule: None,
         };
 
         // This should be an async test, but we can test the config creation
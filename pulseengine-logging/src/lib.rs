@@ -54,8 +54,8 @@ pub use dashboard::{
     DataPoint, DataSource, GridPosition, LineStyle, Threshold,
 };
 pub use metrics::{
-    BusinessMetrics, ErrorMetrics, ErrorRecord, HealthMetrics, MetricsCollector, MetricsSnapshot,
-    RequestMetrics, get_metrics,
+    AdaptiveMetricsConfig, BusinessMetrics, ErrorMetrics, ErrorRecord, HealthMetrics,
+    MetricsCollector, MetricsFidelity, MetricsSnapshot, RequestMetrics, get_metrics,
 };
 pub use persistence::{MetricsPersistence, PersistedMetrics, PersistenceConfig, RotationInterval};
 pub use profiling::{
@@ -66,7 +66,7 @@ pub use profiling::{
 };
 pub use sanitization::{LogSanitizer, SanitizationConfig};
 pub use structured::{ErrorClass, StructuredContext, StructuredLogger};
-pub use telemetry::spans;
+pub use telemetry::{SamplingConfig, TraceSampler, spans};
 
 /// Result type for logging operations
 ///
@@ -9,7 +9,8 @@
 use crate::persistence::{MetricsPersistence, PersistenceConfig};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
-use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, OnceLock};
 use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
 use tokio::sync::RwLock;
 
@@ -32,6 +33,116 @@ pub struct MetricsCollector {
 
     /// Persistence layer for metrics
     persistence: Option<Arc<MetricsPersistence>>,
+
+    /// Load-dependent fidelity reduction, when enabled
+    adaptive: OnceLock<AdaptiveFidelity>,
+}
+
+/// How much detail the collector records per request
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MetricsFidelity {
+    /// Every latency recorded, percentiles over the full history
+    Full,
+    /// Latency sampled and kept in a shorter history to cut overhead under load
+    Reduced,
+}
+
+/// Adaptive fidelity configuration
+///
+/// Request counts stay exact at every fidelity; only latency tracking is
+/// degraded. Fidelity drops once the observed rate exceeds
+/// `high_load_requests_per_second` and returns to full once it falls below
+/// `recovery_requests_per_second`, so it doesn't flap around one threshold.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AdaptiveMetricsConfig {
+    /// Request rate above which fidelity is reduced
+    pub high_load_requests_per_second: f64,
+    /// Request rate below which full fidelity is restored
+    pub recovery_requests_per_second: f64,
+    /// Window over which the request rate is measured, in milliseconds
+    pub window_ms: u64,
+    /// Record one in this many latencies at reduced fidelity
+    pub latency_sample_rate: u64,
+    /// Latencies kept per tool at reduced fidelity (1000 at full)
+    pub reduced_history: usize,
+}
+
+impl Default for AdaptiveMetricsConfig {
+    fn default() -> Self {
+        Self {
+            high_load_requests_per_second: 1_000.0,
+            recovery_requests_per_second: 500.0,
+            window_ms: 1_000,
+            latency_sample_rate: 10,
+            reduced_history: 100,
+        }
+    }
+}
+
+struct LoadWindow {
+    started: Instant,
+    requests: u64,
+    fidelity: MetricsFidelity,
+}
+
+struct AdaptiveFidelity {
+    config: AdaptiveMetricsConfig,
+    window: Mutex<LoadWindow>,
+    completions: AtomicU64,
+}
+
+impl AdaptiveFidelity {
+    fn new(config: AdaptiveMetricsConfig) -> Self {
+        Self {
+            config,
+            window: Mutex::new(LoadWindow {
+                started: Instant::now(),
+                requests: 0,
+                fidelity: MetricsFidelity::Full,
+            }),
+            completions: AtomicU64::new(0),
+        }
+    }
+
+    fn fidelity(&self) -> MetricsFidelity {
+        self.window.lock().unwrap().fidelity
+    }
+
+    /// Count a request, re-evaluating fidelity when the window has elapsed
+    fn observe_request(&self) {
+        let mut window = self.window.lock().unwrap();
+        window.requests += 1;
+        let elapsed = window.started.elapsed();
+        if elapsed < Duration::from_millis(self.config.window_ms) {
+            return;
+        }
+
+        #[allow(clippy::cast_precision_loss)]
+        let rate = window.requests as f64 / elapsed.as_secs_f64();
+        let next = match window.fidelity {
+            MetricsFidelity::Full if rate > self.config.high_load_requests_per_second => {
+                MetricsFidelity::Reduced
+            }
+            MetricsFidelity::Reduced if rate < self.config.recovery_requests_per_second => {
+                MetricsFidelity::Full
+            }
+            current => current,
+        };
+        if next != window.fidelity {
+            tracing::info!(requests_per_second = rate, fidelity = ?next, "Metrics fidelity changed");
+            window.fidelity = next;
+        }
+        window.started = Instant::now();
+        window.requests = 0;
+    }
+
+    /// Whether this completion's latency should be recorded
+    fn sample_latency(&self) -> bool {
+        let n = self.completions.fetch_add(1, Ordering::Relaxed);
+        n % self.config.latency_sample_rate.max(1) == 0
+    }
 }
 
 /// Request performance metrics
@@ -295,6 +406,7 @@ impl MetricsCollector {
             error_metrics: Arc::new(RwLock::new(ErrorMetrics::default())),
             start_time: Instant::now(),
             persistence: None,
+            adaptive: OnceLock::new(),
         }
     }
 
@@ -308,9 +420,32 @@ impl MetricsCollector {
             error_metrics: Arc::new(RwLock::new(ErrorMetrics::default())),
             start_time: Instant::now(),
             persistence: Some(persistence),
+            adaptive: OnceLock::new(),
         })
     }
 
+    /// Reduce latency tracking fidelity while the request rate is high
+    #[must_use]
+    pub fn with_adaptive_fidelity(mut self, config: AdaptiveMetricsConfig) -> Self {
+        self.adaptive = OnceLock::from(AdaptiveFidelity::new(config));
+        self
+    }
+
+    /// Enable adaptive fidelity on a shared collector, such as [`get_metrics`]
+    ///
+    /// Returns `false`, leaving the existing configuration in effect, when
+    /// adaptive fidelity was already enabled.
+    pub fn enable_adaptive_fidelity(&self, config: AdaptiveMetricsConfig) -> bool {
+        self.adaptive.set(AdaptiveFidelity::new(config)).is_ok()
+    }
+
+    /// Current collection fidelity (always full unless adaptive mode is enabled)
+    pub fn fidelity(&self) -> MetricsFidelity {
+        self.adaptive
+            .get()
+            .map_or(MetricsFidelity::Full, AdaptiveFidelity::fidelity)
+    }
+
     /// Enable persistence for this metrics collector
     pub async fn enable_persistence(
         &self,
@@ -332,6 +467,9 @@ impl MetricsCollector {
 
     /// Record a request start
     pub async fn record_request_start(&self, tool_name: &str) {
+        if let Some(adaptive) = self.adaptive.get() {
+            adaptive.observe_request();
+        }
         let mut metrics = self.request_metrics.write().await;
         metrics.total_requests += 1;
         metrics.active_requests += 1;
@@ -356,6 +494,17 @@ impl MetricsCollector {
             metrics.failed_requests += 1;
         }
 
+        let history = match self.adaptive.get() {
+            Some(adaptive) if adaptive.fidelity() == MetricsFidelity::Reduced => {
+                if !adaptive.sample_latency() {
+                    metrics.last_updated = current_timestamp();
+                    return;
+                }
+                adaptive.config.reduced_history
+            }
+            _ => 1000,
+        };
+
         // Update response times
         metrics
             .response_times_by_tool
@@ -363,11 +512,11 @@ impl MetricsCollector {
             .or_insert_with(Vec::new)
             .push(duration_ms);
 
-        // Keep only last 1000 response times per tool for memory efficiency
+        // Keep only the most recent response times per tool for memory efficiency
         if let Some(times) = metrics.response_times_by_tool.get_mut(tool_name)
-            && times.len() > 1000
+            && times.len() > history
         {
-            times.drain(..times.len() - 1000);
+            times.drain(..times.len() - history);
         }
 
         // Recalculate averages and percentiles
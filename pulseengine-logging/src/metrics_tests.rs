@@ -479,4 +479,79 @@ mod tests {
         assert_eq!(record.request_id, "req_123");
         assert_eq!(record.duration_ms, 5000);
     }
+
+    #[tokio::test]
+    async fn test_adaptive_fidelity_degrades_under_load_and_recovers() {
+        let collector = MetricsCollector::new().with_adaptive_fidelity(AdaptiveMetricsConfig {
+            high_load_requests_per_second: 1_000.0,
+            recovery_requests_per_second: 500.0,
+            window_ms: 50,
+            latency_sample_rate: 10,
+            reduced_history: 20,
+        });
+        assert_eq!(collector.fidelity(), MetricsFidelity::Full);
+
+        // A burst of 500 requests, measured once the window elapses
+        for _ in 0..500 {
+            collector.record_request_start("burst").await;
+        }
+        sleep(Duration::from_millis(60)).await;
+        collector.record_request_start("burst").await;
+        assert_eq!(collector.fidelity(), MetricsFidelity::Reduced);
+
+        // Under reduced fidelity only every 10th latency is kept, in a short history
+        for _ in 0..400 {
+            collector
+                .record_request_end("burst", Duration::from_millis(5), true)
+                .await;
+        }
+        let snapshot = collector.get_metrics_snapshot().await;
+        assert_eq!(snapshot.request_metrics.total_requests, 501);
+        assert_eq!(snapshot.request_metrics.successful_requests, 400);
+        assert_eq!(
+            snapshot.request_metrics.response_times_by_tool["burst"].len(),
+            20
+        );
+
+        // A quiet window restores full fidelity
+        sleep(Duration::from_millis(60)).await;
+        collector.record_request_start("quiet").await;
+        assert_eq!(collector.fidelity(), MetricsFidelity::Full);
+        collector
+            .record_request_end("quiet", Duration::from_millis(5), true)
+            .await;
+        let snapshot = collector.get_metrics_snapshot().await;
+        assert_eq!(
+            snapshot.request_metrics.response_times_by_tool["quiet"].len(),
+            1
+        );
+    }
+
+    #[tokio::test]
+    async fn test_fidelity_is_full_without_adaptive_mode() {
+        let collector = MetricsCollector::new();
+        for _ in 0..2_000 {
+            collector.record_request_start("test").await;
+        }
+        assert_eq!(collector.fidelity(), MetricsFidelity::Full);
+    }
+
+    #[tokio::test]
+    async fn test_adaptive_fidelity_enabled_on_shared_collector() {
+        let collector = MetricsCollector::new();
+        assert!(collector.enable_adaptive_fidelity(AdaptiveMetricsConfig {
+            window_ms: 50,
+            high_load_requests_per_second: 1_000.0,
+            ..AdaptiveMetricsConfig::default()
+        }));
+        // The first configuration stays in effect
+        assert!(!collector.enable_adaptive_fidelity(AdaptiveMetricsConfig::default()));
+
+        for _ in 0..500 {
+            collector.record_request_start("burst").await;
+        }
+        sleep(Duration::from_millis(60)).await;
+        collector.record_request_start("burst").await;
+        assert_eq!(collector.fidelity(), MetricsFidelity::Reduced);
+    }
 }
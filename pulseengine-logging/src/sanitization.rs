@@ -68,6 +68,10 @@ pub struct SanitizationConfig {
 
     /// Replacement string for sensitive data
     pub replacement: String,
+
+    /// Keys in JSON-RPC error `data` passed to clients unmodified, e.g.
+    /// retry hints; every other key is sanitized and sensitive keys removed
+    pub preserved_error_data_keys: Vec<String>,
 }
 
 impl Default for SanitizationConfig {
@@ -77,6 +81,7 @@ impl Default for SanitizationConfig {
             preserve_ips: false,                  // Hide IPs in production
             preserve_uuids: true,                 // Keep UUIDs for Loxone debugging
             replacement: "[REDACTED]".to_string(),
+            preserved_error_data_keys: vec!["retry_after".to_string(), "retryAfter".to_string()],
         }
     }
 }
@@ -220,8 +225,41 @@ impl LogSanitizer {
         }
     }
 
+    /// Sanitize the `data` of an outgoing JSON-RPC error
+    ///
+    /// Keys listed in `preserved_error_data_keys` are kept verbatim so clients
+    /// still get hints like `retry_after`. Sensitive keys are removed entirely
+    /// rather than redacted, and all other values are sanitized.
+    pub fn sanitize_error_data(&self, data: &serde_json::Value) -> serde_json::Value {
+        if !self.config.enabled {
+            return data.clone();
+        }
+
+        match data {
+            serde_json::Value::Object(map) => {
+                let sanitized_map = map
+                    .iter()
+                    .filter_map(|(key, value)| {
+                        if self.config.preserved_error_data_keys.contains(key) {
+                            Some((key.clone(), value.clone()))
+                        } else if Self::is_sensitive_field(key) {
+                            None
+                        } else {
+                            Some((key.clone(), self.sanitize_error_data(value)))
+                        }
+                    })
+                    .collect();
+                serde_json::Value::Object(sanitized_map)
+            }
+            serde_json::Value::Array(arr) => {
+                serde_json::Value::Array(arr.iter().map(|v| self.sanitize_error_data(v)).collect())
+            }
+            other => self.sanitize_context(other),
+        }
+    }
+
     /// Check if a field name indicates sensitive data
-    fn is_sensitive_field(field_name: &str) -> bool {
+    pub fn is_sensitive_field(field_name: &str) -> bool {
         let lower_name = field_name.to_lowercase();
         // Check for exact matches first
         if matches!(
@@ -24,6 +24,7 @@ mod tests {
             preserve_ips: true,
             preserve_uuids: false,
             replacement: "***".to_string(),
+            preserved_error_data_keys: vec![],
         };
 
         let sanitizer = LogSanitizer::with_config(config.clone());
@@ -543,6 +544,50 @@ mod tests {
         assert_eq!(sanitized["object_password"], "[REDACTED]");
     }
 
+    #[test]
+    fn test_error_data_strips_sensitive_keys_and_keeps_retry_hint() {
+        let sanitizer = LogSanitizer::with_config(SanitizationConfig {
+            enabled: true,
+            ..Default::default()
+        });
+
+        let data = json!({
+            "retry_after": 30,
+            "db_password": "hunter2",
+            "detail": "connect failed with token=abc123",
+            "upstream": {"api_key": "sk-live", "status": 503}
+        });
+        let sanitized = sanitizer.sanitize_error_data(&data);
+
+        assert_eq!(sanitized["retry_after"], 30);
+        assert!(sanitized.get("db_password").is_none());
+        assert_eq!(sanitized["detail"], "connect failed with token=[REDACTED]");
+        assert!(sanitized["upstream"].get("api_key").is_none());
+        assert_eq!(sanitized["upstream"]["status"], 503);
+    }
+
+    #[test]
+    fn test_error_data_preserved_keys_are_configurable() {
+        let sanitizer = LogSanitizer::with_config(SanitizationConfig {
+            enabled: true,
+            preserved_error_data_keys: vec!["auth_scheme".to_string()],
+            ..Default::default()
+        });
+
+        let data = json!({"auth_scheme": "Bearer", "retry_after": 5});
+        let sanitized = sanitizer.sanitize_error_data(&data);
+
+        assert_eq!(sanitized["auth_scheme"], "Bearer");
+        assert_eq!(sanitized["retry_after"], 5);
+
+        let disabled = LogSanitizer::with_config(SanitizationConfig {
+            enabled: false,
+            ..Default::default()
+        });
+        let data = json!({"token": "abc"});
+        assert_eq!(disabled.sanitize_error_data(&data), data);
+    }
+
     #[test]
     fn test_thread_safety() {
         use std::sync::Arc;
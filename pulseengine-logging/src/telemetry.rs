@@ -3,16 +3,29 @@
 //! This module provides pre-configured tracing spans following semantic conventions
 //! for common MCP operations. These work with any tracing subscriber.
 
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+
 /// Span utilities for common MCP operations
 pub mod spans {
     use tracing::Span;
 
     /// Create a span for MCP request handling
+    ///
+    /// Method-specific attributes (`mcp.tool.name`, `mcp.resource.uri`,
+    /// `mcp.prompt.name`, `mcp.request.bytes`) and custom `mcp.attributes`
+    /// are declared empty so handlers can record them once known.
     pub fn mcp_request_span(method: &str, request_id: &str) -> Span {
         tracing::info_span!(
             "mcp_request",
             mcp.method = method,
             mcp.request_id = request_id,
+            mcp.tool.name = tracing::field::Empty,
+            mcp.resource.uri = tracing::field::Empty,
+            mcp.prompt.name = tracing::field::Empty,
+            mcp.request.bytes = tracing::field::Empty,
+            mcp.attributes = tracing::field::Empty,
             otel.kind = "server"
         )
     }
@@ -74,10 +87,127 @@ pub mod spans {
     }
 }
 
+/// Trace sampling rates for MCP requests
+///
+/// Rates are fractions between 0.0 (never traced) and 1.0 (always traced).
+/// Sampling is deterministic: a method sampled at 0.01 is traced on exactly
+/// one request in every hundred.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SamplingConfig {
+    /// Rate for methods without an override
+    pub default_rate: f64,
+    /// Per-method rates overriding `default_rate`, e.g. `"ping" => 0.01`
+    pub method_rates: HashMap<String, f64>,
+    /// Trace failed requests even when they weren't sampled
+    pub always_sample_errors: bool,
+}
+
+impl Default for SamplingConfig {
+    fn default() -> Self {
+        Self {
+            default_rate: 1.0,
+            method_rates: HashMap::new(),
+            always_sample_errors: true,
+        }
+    }
+}
+
+impl SamplingConfig {
+    /// Override the sampling rate of a single method
+    pub fn with_method_rate(mut self, method: impl Into<String>, rate: f64) -> Self {
+        self.method_rates.insert(method.into(), rate);
+        self
+    }
+
+    /// Sampling rate applied to `method`, clamped to 0.0..=1.0
+    pub fn rate_for(&self, method: &str) -> f64 {
+        self.method_rates
+            .get(method)
+            .copied()
+            .unwrap_or(self.default_rate)
+            .clamp(0.0, 1.0)
+    }
+}
+
+/// Makes per-request sampling decisions for a [`SamplingConfig`]
+#[derive(Debug)]
+pub struct TraceSampler {
+    config: SamplingConfig,
+    /// Requests seen per overridden method; other methods share `default_seen`
+    method_seen: HashMap<String, AtomicU64>,
+    default_seen: AtomicU64,
+}
+
+impl TraceSampler {
+    pub fn new(config: SamplingConfig) -> Self {
+        let method_seen = config
+            .method_rates
+            .keys()
+            .map(|method| (method.clone(), AtomicU64::new(0)))
+            .collect();
+        Self {
+            config,
+            method_seen,
+            default_seen: AtomicU64::new(0),
+        }
+    }
+
+    pub fn config(&self) -> &SamplingConfig {
+        &self.config
+    }
+
+    /// Whether the next request for `method` should be traced
+    pub fn should_sample(&self, method: &str) -> bool {
+        let rate = self.config.rate_for(method);
+        let seen = self.method_seen.get(method).unwrap_or(&self.default_seen);
+        let n = seen.fetch_add(1, Ordering::Relaxed) as f64;
+        // Sample whenever the running total of `rate` crosses an integer
+        ((n + 1.0) * rate).floor() > (n * rate).floor()
+    }
+
+    /// Whether a request that wasn't sampled must be traced after failing
+    pub fn should_sample_error(&self) -> bool {
+        self.config.always_sample_errors
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_sampling_rates_per_method() {
+        let sampler = TraceSampler::new(
+            SamplingConfig {
+                default_rate: 0.5,
+                ..Default::default()
+            }
+            .with_method_rate("ping", 0.01)
+            .with_method_rate("tools/call", 1.0)
+            .with_method_rate("resources/list", 0.0),
+        );
+
+        let traced = |method: &str| (0..1000).filter(|_| sampler.should_sample(method)).count();
+        assert_eq!(traced("ping"), 10);
+        assert_eq!(traced("tools/call"), 1000);
+        assert_eq!(traced("resources/list"), 0);
+        assert_eq!(traced("prompts/list"), 500);
+        assert!(sampler.should_sample_error());
+    }
+
+    #[test]
+    fn test_sampling_config_defaults_trace_everything() {
+        let config = SamplingConfig::default();
+        assert_eq!(config.rate_for("anything"), 1.0);
+        assert!(config.always_sample_errors);
+        assert_eq!(
+            config.with_method_rate("ping", 7.0).rate_for("ping"),
+            1.0,
+            "rates are clamped"
+        );
+    }
+
     #[test]
     fn test_span_utilities() {
         // Note: Without a subscriber, spans are disabled by default.